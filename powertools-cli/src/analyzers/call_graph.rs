@@ -0,0 +1,295 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::analyzers::{declaration_of, AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::location::byte_offset;
+use crate::core::{Location, SymbolKind};
+use crate::indexers::{qualified_symbol_name, symbol_name_and_kind, ScipQuery};
+
+/// A function or method in the call graph
+#[derive(Debug, Clone, Serialize)]
+pub struct CallGraphNode {
+    /// Full SCIP symbol id, used as the node id in DOT and JSON output
+    pub id: String,
+    /// Qualified name (`jobs::Job::start`)
+    pub name: String,
+    pub location: Location,
+}
+
+/// `caller` calls `callee` at least once (both are node ids)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CallEdge {
+    pub caller: String,
+    pub callee: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CallGraph {
+    pub nodes: Vec<CallGraphNode>,
+    pub edges: Vec<CallEdge>,
+}
+
+impl CallGraph {
+    /// Graphviz digraph with one cluster per file
+    pub fn to_dot(&self, project_root: &Path) -> String {
+        let mut by_file: BTreeMap<&PathBuf, Vec<&CallGraphNode>> = BTreeMap::new();
+        for node in &self.nodes {
+            by_file.entry(&node.location.file_path).or_default().push(node);
+        }
+
+        let mut dot = String::from("digraph calls {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (i, (file, nodes)) in by_file.into_iter().enumerate() {
+            let file = file.strip_prefix(project_root).unwrap_or(file);
+            let _ = writeln!(dot, "    subgraph cluster_{} {{", i);
+            let _ = writeln!(dot, "        label={};", dot_string(&file.display().to_string()));
+            for node in nodes {
+                let _ = writeln!(dot, "        {} [label={}];", dot_string(&node.id), dot_string(&node.name));
+            }
+            dot.push_str("    }\n");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(dot, "    {} -> {};", dot_string(&edge.caller), dot_string(&edge.callee));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A function definition and the lines its body covers
+struct FunctionInfo {
+    node: CallGraphNode,
+    /// 1-indexed line span of the whole declaration
+    lines: (usize, usize),
+    references: Vec<Location>,
+}
+
+/// Build caller -> callee edges from SCIP references
+///
+/// SCIP records where a function is referenced, not who calls it, so each reference
+/// is attributed to the innermost function definition whose span contains it.
+pub struct CallGraphBuilder {
+    analyzer: TreeSitterAnalyzer,
+    files: HashMap<PathBuf, Option<AnalyzedFile>>,
+}
+
+impl CallGraphBuilder {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+            files: HashMap::new(),
+        })
+    }
+
+    /// Call graph reachable from `root` (every function when None), following at most `depth` calls
+    pub fn build(&mut self, scip_query: &ScipQuery, root: Option<&str>, depth: Option<usize>) -> Result<CallGraph> {
+        let functions = self.functions(scip_query);
+        let calls = calls_between(&functions);
+
+        let roots: Vec<usize> = match root {
+            Some(root) => {
+                let suffix = format!("::{}", root);
+                let matches: Vec<usize> = (0..functions.len())
+                    .filter(|&i| {
+                        let name = &functions[i].node.name;
+                        name == root || name.ends_with(&suffix)
+                    })
+                    .collect();
+                if matches.is_empty() {
+                    bail!("No function or method named '{}' in the index", root);
+                }
+                matches
+            }
+            // Without a depth limit the whole graph is wanted; otherwise start at entry points
+            None if depth.is_none() => (0..functions.len()).collect(),
+            None => {
+                let called: HashSet<usize> = calls.values().flatten().copied().collect();
+                (0..functions.len()).filter(|i| !called.contains(i)).collect()
+            }
+        };
+
+        // Breadth-first so `depth` counts the shortest call chain; the visited set stops recursion
+        let mut visited: HashSet<usize> = roots.iter().copied().collect();
+        let mut queue: VecDeque<(usize, usize)> = roots.iter().map(|&i| (i, 0)).collect();
+        let mut edges = BTreeSet::new();
+        while let Some((caller, distance)) = queue.pop_front() {
+            if depth.is_some_and(|max| distance >= max) {
+                continue;
+            }
+            for &callee in calls.get(&caller).into_iter().flatten() {
+                edges.insert((caller, callee));
+                if visited.insert(callee) {
+                    queue.push_back((callee, distance + 1));
+                }
+            }
+        }
+
+        // A root with no calls still shows up; in the whole-project view only connected functions do
+        let mut included: BTreeSet<usize> = edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+        if root.is_some() {
+            included.extend(&roots);
+        }
+
+        let nodes = included.into_iter().map(|i| functions[i].node.clone()).collect();
+        let edges = edges
+            .into_iter()
+            .map(|(caller, callee)| CallEdge {
+                caller: functions[caller].node.id.clone(),
+                callee: functions[callee].node.id.clone(),
+            })
+            .collect();
+        Ok(CallGraph { nodes, edges })
+    }
+
+    /// Every function and method defined in the project, in file order
+    fn functions(&mut self, scip_query: &ScipQuery) -> Vec<FunctionInfo> {
+        scip_query
+            .symbol_usages()
+            .into_iter()
+            .filter(|usage| {
+                symbol_name_and_kind(&usage.symbol)
+                    .is_some_and(|(_, kind)| matches!(kind, SymbolKind::Function | SymbolKind::Method))
+            })
+            .map(|usage| {
+                // Without a parse the definition line is the best span we have
+                let lines = self
+                    .declaration_lines(&usage.definition)
+                    .unwrap_or((usage.definition.line, usage.definition.line));
+                FunctionInfo {
+                    node: CallGraphNode {
+                        name: qualified_symbol_name(&usage.symbol).unwrap_or_else(|| usage.symbol.clone()),
+                        id: usage.symbol,
+                        location: usage.definition,
+                    },
+                    lines,
+                    references: usage.references,
+                }
+            })
+            .collect()
+    }
+
+    fn declaration_lines(&mut self, definition: &Location) -> Option<(usize, usize)> {
+        let path = definition.file_path.clone();
+        let analyzer = &mut self.analyzer;
+        let file = self
+            .files
+            .entry(path.clone())
+            .or_insert_with(|| analyzer.analyze_file(&path).ok())
+            .as_ref()?;
+
+        let byte = byte_offset(&file.content, definition.line, definition.column)?;
+        let name_node = file.tree.root_node().descendant_for_byte_range(byte, byte)?;
+        let declaration = declaration_of(name_node)?;
+        Some((declaration.start_position().row + 1, declaration.end_position().row + 1))
+    }
+}
+
+/// Callees of each function, by index into `functions`
+fn calls_between(functions: &[FunctionInfo]) -> HashMap<usize, BTreeSet<usize>> {
+    let mut by_file: HashMap<&Path, Vec<usize>> = HashMap::new();
+    for (i, function) in functions.iter().enumerate() {
+        by_file.entry(function.node.location.file_path.as_path()).or_default().push(i);
+    }
+
+    let mut calls: HashMap<usize, BTreeSet<usize>> = HashMap::new();
+    for (callee, function) in functions.iter().enumerate() {
+        for reference in &function.references {
+            // Innermost enclosing definition: the one starting last (nested functions, closures in methods)
+            let caller = by_file
+                .get(reference.file_path.as_path())
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&i| {
+                    let (start, end) = functions[i].lines;
+                    start <= reference.line && reference.line <= end
+                })
+                .max_by_key(|&i| functions[i].lines.0);
+            if let Some(caller) = caller {
+                calls.entry(caller).or_default().insert(callee);
+            }
+        }
+    }
+    calls
+}
+
+fn dot_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexers::scip_fixture::ScipFixture;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = "fn main() {\n    run();\n}\n\nfn run() {\n    step(3);\n}\n\nfn step(n: u32) {\n    if n > 0 {\n        step(n - 1);\n    }\n    log();\n}\n\nfn log() {}\n";
+
+    fn fixture() -> (TempDir, ScipQuery) {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), SOURCE).unwrap();
+
+        let scip_query = ScipFixture::new()
+            .document("src/lib.rs")
+            .definition("jobs/main().", 0, 3, 4)
+            .reference("jobs/run().", 1, 4, 3)
+            .definition("jobs/run().", 4, 3, 3)
+            .reference("jobs/step().", 5, 4, 4)
+            .definition("jobs/step().", 8, 3, 4)
+            .reference("jobs/step().", 10, 8, 4)
+            .reference("jobs/log().", 12, 4, 3)
+            .definition("jobs/log().", 15, 3, 3)
+            .query(root);
+        (dir, scip_query)
+    }
+
+    fn edge_names(graph: &CallGraph) -> Vec<(String, String)> {
+        let name = |id: &str| graph.nodes.iter().find(|n| n.id == id).unwrap().name.clone();
+        graph.edges.iter().map(|e| (name(&e.caller), name(&e.callee))).collect()
+    }
+
+    fn edge(caller: &str, callee: &str) -> (String, String) {
+        (format!("jobs::{}", caller), format!("jobs::{}", callee))
+    }
+
+    #[test]
+    fn test_call_graph_handles_recursion_and_depth() {
+        let (_dir, scip_query) = fixture();
+        let mut builder = CallGraphBuilder::new().unwrap();
+
+        let graph = builder.build(&scip_query, None, None).unwrap();
+        assert_eq!(
+            edge_names(&graph),
+            vec![edge("main", "run"), edge("run", "step"), edge("step", "step"), edge("step", "log")]
+        );
+
+        // The recursive call is recorded once and doesn't loop
+        let graph = builder.build(&scip_query, Some("run"), None).unwrap();
+        assert_eq!(edge_names(&graph), vec![edge("run", "step"), edge("step", "step"), edge("step", "log")]);
+
+        let graph = builder.build(&scip_query, Some("run"), Some(1)).unwrap();
+        assert_eq!(edge_names(&graph), vec![edge("run", "step")]);
+
+        assert!(builder.build(&scip_query, Some("missing"), None).is_err());
+    }
+
+    #[test]
+    fn test_dot_output_clusters_by_file() {
+        let (dir, scip_query) = fixture();
+        let graph = CallGraphBuilder::new().unwrap().build(&scip_query, Some("main"), Some(1)).unwrap();
+        let dot = graph.to_dot(dir.path());
+
+        assert!(dot.starts_with("digraph calls {"));
+        assert!(dot.contains("subgraph cluster_0 {"));
+        assert!(dot.contains("label=\"src/lib.rs\";"));
+        assert!(dot.contains("[label=\"jobs::main\"];"));
+        assert!(dot.contains(
+            "\"rust-analyzer cargo demo 0.1.0 jobs/main().\" -> \"rust-analyzer cargo demo 0.1.0 jobs/run().\";"
+        ));
+    }
+}
@@ -0,0 +1,387 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+
+use crate::analyzers::{collect_source_files, AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::location::byte_offset;
+use crate::core::{Location, SymbolKind};
+use crate::indexers::{qualified_symbol_name, symbol_name_and_kind, ScipQuery, SymbolUsage};
+
+/// Node kinds for a call, whose `function` field is the callee expression
+const CALL_KINDS: &[&str] = &["call_expression", "call"];
+
+/// Node kinds whose `name` field names a function or method
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",
+    "function_declaration",
+    "function_definition",
+    "method_definition",
+    "generator_function_declaration",
+];
+
+/// How a call site invokes the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    /// `run()`, `jobs::run()`
+    Direct,
+    /// `job.run()`, `self.run()`
+    Method,
+    /// The file couldn't be parsed to tell
+    Unknown,
+}
+
+/// One place a function is called
+#[derive(Debug, Clone, Serialize)]
+pub struct CallSite {
+    /// Qualified name of the called function; overloads and same-named functions
+    /// are reported separately
+    pub callee: String,
+    /// Where the called function is defined (None from the tree-sitter fallback)
+    pub callee_location: Option<Location>,
+    pub location: Location,
+    /// Function or method containing the call (`Job::start`), when it can be determined
+    pub caller: Option<String>,
+    pub kind: CallKind,
+}
+
+/// Function to find callers of
+pub enum CallTarget {
+    /// Function name, optionally qualified (`start`, `Job::start`)
+    Name(String),
+    /// Position of the function's name, at its definition or any use
+    Location(Location),
+}
+
+/// Find call sites of a function from SCIP references, or by scanning call expressions
+pub struct CallerFinder {
+    analyzer: TreeSitterAnalyzer,
+    files: HashMap<PathBuf, Option<AnalyzedFile>>,
+}
+
+impl CallerFinder {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+            files: HashMap::new(),
+        })
+    }
+
+    /// Call sites of every indexed function matching the target
+    ///
+    /// SCIP references include non-call uses (imports, functions passed as values);
+    /// those are dropped when the referencing file parses.
+    pub fn find_in_index(&mut self, scip_query: &ScipQuery, target: &CallTarget) -> Result<Vec<CallSite>> {
+        let functions = scip_query.symbol_usages().into_iter().filter(|usage| {
+            symbol_name_and_kind(&usage.symbol)
+                .is_some_and(|(_, kind)| matches!(kind, SymbolKind::Function | SymbolKind::Method))
+        });
+
+        let candidates: Vec<SymbolUsage> = match target {
+            CallTarget::Name(name) => {
+                let suffix = format!("::{}", name);
+                functions
+                    .filter(|usage| {
+                        qualified_symbol_name(&usage.symbol)
+                            .is_some_and(|qualified| qualified == *name || qualified.ends_with(&suffix))
+                    })
+                    .collect()
+            }
+            CallTarget::Location(location) => {
+                let Some(definition) = scip_query.find_definition(&location.file_path, location.line, location.column)? else {
+                    return Ok(Vec::new());
+                };
+                functions
+                    .filter(|usage| {
+                        usage.definition.file_path == definition.file_path
+                            && usage.definition.line == definition.line
+                            && usage.definition.column == definition.column
+                    })
+                    .collect()
+            }
+        };
+
+        let mut sites = Vec::new();
+        for candidate in candidates {
+            let callee = qualified_symbol_name(&candidate.symbol).unwrap_or_else(|| candidate.symbol.clone());
+            for reference in &candidate.references {
+                let (kind, caller) = match self.classify(reference) {
+                    Some(Some(call)) => call,
+                    // Parsed, but not a call
+                    Some(None) => continue,
+                    None => (CallKind::Unknown, None),
+                };
+                sites.push(CallSite {
+                    callee: callee.clone(),
+                    callee_location: Some(candidate.definition.clone()),
+                    location: reference.clone(),
+                    caller,
+                    kind,
+                });
+            }
+        }
+        sort_sites(&mut sites);
+        Ok(sites)
+    }
+
+    /// Call expressions under `path` whose callee is named `name`
+    /// Only the last segment of a qualified name (`Job::start` -> `start`) is compared
+    pub fn find_by_name(&mut self, path: &Path, name: &str) -> Result<Vec<CallSite>> {
+        let name = name.rsplit(['.', ':']).next().unwrap_or(name);
+
+        let mut sites = Vec::new();
+        for file_path in collect_source_files(path)? {
+            let Ok(file) = self.analyzer.analyze_file(&file_path) else {
+                continue;
+            };
+            let mut stack = vec![file.tree.root_node()];
+            while let Some(node) = stack.pop() {
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+
+                if !CALL_KINDS.contains(&node.kind()) {
+                    continue;
+                }
+                let Some((name_node, kind)) = node.child_by_field_name("function").and_then(callee_name) else {
+                    continue;
+                };
+                if &file.content[name_node.byte_range()] != name {
+                    continue;
+                }
+                let start = name_node.start_position();
+                sites.push(CallSite {
+                    callee: name.to_string(),
+                    callee_location: None,
+                    location: Location {
+                        file_path: file_path.clone(),
+                        line: start.row + 1,
+                        column: start.column + 1,
+                        end_line: Some(name_node.end_position().row + 1),
+                        end_column: Some(name_node.end_position().column + 1),
+                    },
+                    caller: enclosing_function(name_node, &file.content),
+                    kind,
+                });
+            }
+        }
+        sort_sites(&mut sites);
+        Ok(sites)
+    }
+
+    /// Name of the identifier at a location, for resolving a position without an index
+    pub fn name_at(&mut self, location: &Location) -> Option<String> {
+        let file = self.file(&location.file_path)?;
+        let byte = byte_offset(&file.content, location.line, location.column)?;
+        let node = file.tree.root_node().descendant_for_byte_range(byte, byte)?;
+        node.kind()
+            .ends_with("identifier")
+            .then(|| file.content[node.byte_range()].to_string())
+    }
+
+    /// None when the file can't be parsed; otherwise the call kind and caller,
+    /// or Some(None) when the reference isn't the callee of a call
+    fn classify(&mut self, reference: &Location) -> Option<Option<(CallKind, Option<String>)>> {
+        let file = self.file(&reference.file_path)?;
+        let Some(byte) = byte_offset(&file.content, reference.line, reference.column) else {
+            return Some(None);
+        };
+        let Some(node) = file.tree.root_node().descendant_for_byte_range(byte, byte) else {
+            return Some(None);
+        };
+        Some(call_kind(node).map(|kind| (kind, enclosing_function(node, &file.content))))
+    }
+
+    fn file(&mut self, path: &Path) -> Option<&AnalyzedFile> {
+        let analyzer = &mut self.analyzer;
+        self.files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| analyzer.analyze_file(path).ok())
+            .as_ref()
+    }
+}
+
+/// The identifier naming the called function, and whether it's a method call
+/// (`run` -> Direct, `jobs::run` -> Direct, `job.run` -> Method)
+fn callee_name(function: Node) -> Option<(Node, CallKind)> {
+    match function.kind() {
+        "identifier" => Some((function, CallKind::Direct)),
+        "scoped_identifier" | "qualified_identifier" | "template_function" => {
+            callee_name(function.child_by_field_name("name")?)
+        }
+        // Turbofish: `parse::<u32>()`
+        "generic_function" => callee_name(function.child_by_field_name("function")?),
+        "field_expression" => Some((function.child_by_field_name("field")?, CallKind::Method)),
+        "member_expression" => Some((function.child_by_field_name("property")?, CallKind::Method)),
+        "attribute" => Some((function.child_by_field_name("attribute")?, CallKind::Method)),
+        _ => None,
+    }
+}
+
+/// How the call containing `name_node` invokes it, or None if it isn't a callee
+fn call_kind(name_node: Node) -> Option<CallKind> {
+    // The callee name sits at most a few levels below the call (`a::b::<T>()`)
+    let mut current = name_node.parent();
+    for _ in 0..4 {
+        let node = current?;
+        if CALL_KINDS.contains(&node.kind()) {
+            let (callee, kind) = callee_name(node.child_by_field_name("function")?)?;
+            return (callee.id() == name_node.id()).then_some(kind);
+        }
+        current = node.parent();
+    }
+    None
+}
+
+/// Name of the innermost function containing a node, qualified with its impl
+/// or class when it's a method (`Job::start`)
+fn enclosing_function(node: Node, content: &str) -> Option<String> {
+    let text = |n: Node| content[n.byte_range()].to_string();
+
+    let mut current = node.parent();
+    let mut function = None;
+    while let Some(candidate) = current {
+        current = candidate.parent();
+        if function.is_none() {
+            if FUNCTION_KINDS.contains(&candidate.kind()) {
+                function = function_name(candidate).map(text);
+            } else if candidate.kind() == "arrow_function" {
+                // `const run = () => {...}`
+                function = candidate
+                    .parent()
+                    .filter(|p| p.kind() == "variable_declarator")
+                    .and_then(|p| p.child_by_field_name("name"))
+                    .map(text);
+            }
+            continue;
+        }
+
+        let owner = match candidate.kind() {
+            "impl_item" => candidate.child_by_field_name("type"),
+            "class_declaration" | "class_definition" | "class_specifier" | "struct_specifier" => {
+                candidate.child_by_field_name("name")
+            }
+            _ => None,
+        };
+        if let Some(owner) = owner {
+            return function.map(|name| format!("{}::{}", text(owner), name));
+        }
+    }
+    function
+}
+
+/// The name node of a function declaration; C++ nests it in declarators
+fn function_name(declaration: Node) -> Option<Node> {
+    if let Some(name) = declaration.child_by_field_name("name") {
+        return Some(name);
+    }
+    let mut declarator = declaration.child_by_field_name("declarator")?;
+    while let Some(inner) = declarator.child_by_field_name("declarator") {
+        declarator = inner;
+    }
+    Some(declarator)
+}
+
+fn sort_sites(sites: &mut [CallSite]) {
+    sites.sort_by(|a, b| {
+        (&a.location.file_path, a.location.line, a.location.column, &a.callee)
+            .cmp(&(&b.location.file_path, b.location.line, b.location.column, &b.callee))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexers::scip_fixture::ScipFixture;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = "struct Job;\n\nimpl Job {\n    fn start(&self) {\n        run();\n    }\n}\n\nfn run() {}\n\nfn main() {\n    let job = Job;\n    job.start();\n    run();\n    let f = run;\n}\n";
+
+    fn fixture() -> (TempDir, ScipQuery) {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/main.rs"), SOURCE).unwrap();
+
+        let scip_query = ScipFixture::new()
+            .document("src/main.rs")
+            .definition("Job#start().", 3, 7, 5)
+            .reference("run().", 4, 8, 3)
+            .definition("run().", 8, 3, 3)
+            .definition("main().", 10, 3, 4)
+            .reference("Job#start().", 12, 8, 5)
+            .reference("run().", 13, 4, 3)
+            .reference("run().", 14, 12, 3)
+            .query(root);
+        (dir, scip_query)
+    }
+
+    fn summary(sites: &[CallSite]) -> Vec<(usize, Option<String>, CallKind)> {
+        sites.iter().map(|s| (s.location.line, s.caller.clone(), s.kind)).collect()
+    }
+
+    #[test]
+    fn test_index_callers_skip_non_call_references() {
+        let (_dir, scip_query) = fixture();
+        let mut finder = CallerFinder::new().unwrap();
+
+        let sites = finder.find_in_index(&scip_query, &CallTarget::Name("run".to_string())).unwrap();
+        assert_eq!(
+            summary(&sites),
+            vec![
+                (5, Some("Job::start".to_string()), CallKind::Direct),
+                (14, Some("main".to_string()), CallKind::Direct),
+            ]
+        );
+        assert_eq!(sites[0].callee, "run");
+        assert_eq!(sites[0].callee_location.as_ref().unwrap().line, 9);
+
+        let sites = finder.find_in_index(&scip_query, &CallTarget::Name("Job::start".to_string())).unwrap();
+        assert_eq!(summary(&sites), vec![(13, Some("main".to_string()), CallKind::Method)]);
+    }
+
+    #[test]
+    fn test_index_callers_by_location() {
+        let (dir, scip_query) = fixture();
+        let mut finder = CallerFinder::new().unwrap();
+
+        // A use of `start` resolves to its definition first
+        let target = CallTarget::Location(Location {
+            file_path: dir.path().join("src/main.rs"),
+            line: 13,
+            column: 10,
+            end_line: None,
+            end_column: None,
+        });
+        let sites = finder.find_in_index(&scip_query, &target).unwrap();
+        assert_eq!(summary(&sites), vec![(13, Some("main".to_string()), CallKind::Method)]);
+    }
+
+    #[test]
+    fn test_tree_sitter_fallback_matches_call_expressions() {
+        let (dir, _) = fixture();
+        fs::write(
+            dir.path().join("src/app.py"),
+            "class Worker:\n    def run(self):\n        pass\n\n    def go(self):\n        self.run()\n\nrun()\n",
+        )
+        .unwrap();
+
+        let mut finder = CallerFinder::new().unwrap();
+        let sites = finder.find_by_name(dir.path(), "run").unwrap();
+        let found: Vec<_> = sites
+            .iter()
+            .map(|s| (s.location.file_path.file_name().unwrap().to_string_lossy().to_string(), s.location.line, s.caller.clone(), s.kind))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("app.py".to_string(), 6, Some("Worker::go".to_string()), CallKind::Method),
+                ("app.py".to_string(), 8, None, CallKind::Direct),
+                ("main.rs".to_string(), 5, Some("Job::start".to_string()), CallKind::Direct),
+                ("main.rs".to_string(), 14, Some("main".to_string()), CallKind::Direct),
+            ]
+        );
+    }
+}
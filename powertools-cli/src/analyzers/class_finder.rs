@@ -42,6 +42,10 @@ impl ClassFinder {
                 (class_specifier name: (type_identifier) @name) @class
                 (struct_specifier name: (type_identifier) @name) @struct
             "#,
+            Language::Ruby => r#"
+                (class name: (_) @name) @class
+                (module name: (_) @name) @module
+            "#,
             _ => return Ok(Vec::new()),
         };
 
@@ -80,6 +84,11 @@ impl ClassFinder {
                     *capture_name == "struct"
                 }) {
                     SymbolKind::Struct
+                } else if m.captures.iter().any(|c| {
+                    let capture_name = &query.capture_names()[c.index as usize];
+                    *capture_name == "module"
+                }) {
+                    SymbolKind::Module
                 } else {
                     SymbolKind::Class
                 };
@@ -90,7 +99,8 @@ impl ClassFinder {
                         let capture_name = &query.capture_names()[c.index as usize];
                         *capture_name == "class" || *capture_name == "struct" ||
                         *capture_name == "enum" || *capture_name == "trait" ||
-                        *capture_name == "interface" || *capture_name == "type"
+                        *capture_name == "interface" || *capture_name == "type" ||
+                        *capture_name == "module"
                     })
                     .map(|c| c.node);
 
@@ -128,7 +138,7 @@ impl ClassFinder {
         // Check if this node has a parent that is also a class/struct
         let mut parent = node.parent();
         while let Some(p) = parent {
-            if matches!(p.kind(), "class_declaration" | "struct_item" | "class_definition") {
+            if matches!(p.kind(), "class_declaration" | "struct_item" | "class_definition" | "class") {
                 return true;
             }
             parent = p.parent();
@@ -149,4 +159,43 @@ impl ClassFinder {
         }
         None
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[test]
+    fn test_ruby_classes_and_modules() {
+        let mut finder = ClassFinder::new().unwrap();
+        let path = fixture("ruby/billing.rb");
+
+        let symbols = finder.find_in_file(&path, true).unwrap();
+        let found: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.location.line, s.container.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Billing", SymbolKind::Module, 1, None),
+                ("Invoice", SymbolKind::Class, 2, Some("Billing")),
+                ("LineItem", SymbolKind::Class, 17, Some("Billing")),
+                ("Reports", SymbolKind::Module, 24, Some("Billing")),
+            ]
+        );
+
+        // Modules are namespaces; only classes inside classes count as nested
+        let top_level: Vec<_> = finder
+            .find_in_file(&path, false)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(top_level, vec!["Billing", "Invoice", "Reports"]);
+    }
+}
@@ -0,0 +1,395 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use std::str::FromStr;
+use tree_sitter::Node;
+
+use crate::analyzers::{AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::sarif::{SarifLevel, SarifResult, SarifRule};
+use crate::core::Location;
+
+/// Named function declarations; each is scored on its own
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",                  // Rust
+    "function_declaration",           // TypeScript / JavaScript / Go / Swift
+    "generator_function_declaration", // TypeScript / JavaScript
+    "method_definition",              // TypeScript / JavaScript
+    "function_definition",            // Python / C++
+    "method_declaration",             // Java / Go
+];
+
+/// Anonymous functions; scored as part of the enclosing function, one nesting level deeper
+const LAMBDA_KINDS: &[&str] = &[
+    "closure_expression",
+    "arrow_function",
+    "function_expression",
+    "lambda",
+    "lambda_expression",
+];
+
+const IF_KINDS: &[&str] = &["if_expression", "if_statement"];
+
+const LOOP_KINDS: &[&str] = &[
+    "for_expression",
+    "while_expression",
+    "loop_expression",
+    "for_statement",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "for_range_loop",
+    "while_statement",
+    "do_statement",
+];
+
+const SWITCH_KINDS: &[&str] = &[
+    "match_expression",
+    "switch_statement",
+    "switch_expression",
+    "match_statement",
+];
+
+/// Individual branches of a switch/match (cyclomatic counts each one)
+const CASE_KINDS: &[&str] = &["match_arm", "switch_case", "case_clause", "case_statement", "switch_block_statement_group"];
+
+const CATCH_KINDS: &[&str] = &["catch_clause", "except_clause"];
+
+const TERNARY_KINDS: &[&str] = &["ternary_expression", "conditional_expression"];
+
+/// Which complexity metric to compute
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityMetric {
+    /// McCabe: 1 + the number of decision points
+    Cyclomatic,
+    /// SonarSource: control flow weighted by nesting depth
+    Cognitive,
+}
+
+impl FromStr for ComplexityMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "cyclomatic" => Ok(Self::Cyclomatic),
+            "cognitive" => Ok(Self::Cognitive),
+            _ => anyhow::bail!("Unknown complexity metric '{}' (expected cyclomatic or cognitive)", s),
+        }
+    }
+}
+
+impl ComplexityMetric {
+    pub fn sarif_rule(self) -> SarifRule {
+        match self {
+            Self::Cyclomatic => SarifRule {
+                id: "cyclomatic-complexity",
+                name: "CyclomaticComplexity",
+                description: "Function has too many decision points",
+            },
+            Self::Cognitive => SarifRule {
+                id: "cognitive-complexity",
+                name: "CognitiveComplexity",
+                description: "Function control flow is too deeply nested to follow easily",
+            },
+        }
+    }
+
+    /// Scores at or above which a function is a warning, and an error
+    fn thresholds(self) -> (usize, usize) {
+        match self {
+            Self::Cyclomatic => (10, 20),
+            Self::Cognitive => (15, 30),
+        }
+    }
+}
+
+/// Complexity score of one function
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionComplexity {
+    pub name: String,
+    pub location: Location,
+    pub metric: ComplexityMetric,
+    pub score: usize,
+}
+
+impl FunctionComplexity {
+    /// Functions below the metric's warning threshold aren't findings
+    pub fn to_sarif(&self) -> Option<SarifResult> {
+        let (warning, error) = self.metric.thresholds();
+        let level = match self.score {
+            score if score >= error => SarifLevel::Error,
+            score if score >= warning => SarifLevel::Warning,
+            _ => return None,
+        };
+        Some(SarifResult {
+            rule_id: self.metric.sarif_rule().id,
+            level,
+            message: format!("`{}` has complexity {} (threshold {})", self.name, self.score, warning),
+            location: self.location.clone(),
+            related: Vec::new(),
+        })
+    }
+}
+
+/// Score every function in a file
+pub struct ComplexityAnalyzer {
+    analyzer: TreeSitterAnalyzer,
+    metric: ComplexityMetric,
+}
+
+impl ComplexityAnalyzer {
+    pub fn new(metric: ComplexityMetric) -> Result<Self> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+            metric,
+        })
+    }
+
+    pub fn analyze_file(&mut self, file_path: &Path) -> Result<Vec<FunctionComplexity>> {
+        let file = self.analyzer.analyze_file(file_path)?;
+
+        let mut functions = Vec::new();
+        collect_functions(file.tree.root_node(), &mut functions);
+
+        Ok(functions
+            .into_iter()
+            .map(|function| {
+                let score = match self.metric {
+                    ComplexityMetric::Cyclomatic => 1 + cyclomatic(function, &file),
+                    ComplexityMetric::Cognitive => cognitive_children(function, 0, &file),
+                };
+                FunctionComplexity {
+                    name: function_name(function, &file),
+                    location: Location {
+                        file_path: file_path.to_path_buf(),
+                        line: function.start_position().row + 1,
+                        column: function.start_position().column + 1,
+                        end_line: Some(function.end_position().row + 1),
+                        end_column: Some(function.end_position().column + 1),
+                    },
+                    metric: self.metric,
+                    score,
+                }
+            })
+            .collect())
+    }
+}
+
+fn collect_functions<'t>(node: Node<'t>, found: &mut Vec<Node<'t>>) {
+    if FUNCTION_KINDS.contains(&node.kind()) {
+        found.push(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_functions(child, found);
+    }
+}
+
+fn function_name(function: Node, file: &AnalyzedFile) -> String {
+    if let Some(name) = function.child_by_field_name("name") {
+        return file.content[name.byte_range()].to_string();
+    }
+    // C++: `int Foo::bar(int x)` nests the name in declarators
+    let mut declarator = function.child_by_field_name("declarator");
+    while let Some(node) = declarator {
+        match node.child_by_field_name("declarator") {
+            Some(inner) => declarator = Some(inner),
+            None => return file.content[node.byte_range()].to_string(),
+        }
+    }
+    "<anonymous>".to_string()
+}
+
+/// `&&`, `||`, `and` or `or` when the node is a logical binary operation
+fn logical_operator<'a>(node: Node, file: &'a AnalyzedFile) -> Option<&'a str> {
+    if !matches!(node.kind(), "binary_expression" | "boolean_operator") {
+        return None;
+    }
+    let operator = &file.content[node.child_by_field_name("operator")?.byte_range()];
+    matches!(operator, "&&" | "||" | "and" | "or").then_some(operator)
+}
+
+/// Decision points below `node`, excluding nested named functions
+fn cyclomatic(node: Node, file: &AnalyzedFile) -> usize {
+    let mut count = 0;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let kind = child.kind();
+        if FUNCTION_KINDS.contains(&kind) {
+            continue;
+        }
+
+        let is_decision = IF_KINDS.contains(&kind)
+            || LOOP_KINDS.contains(&kind)
+            || CATCH_KINDS.contains(&kind)
+            || TERNARY_KINDS.contains(&kind)
+            || kind == "elif_clause"
+            || logical_operator(child, file).is_some()
+            // The last arm is the fallthrough, like an `else`
+            || (CASE_KINDS.contains(&kind) && child.next_named_sibling().is_some_and(|s| s.kind() == kind));
+        if is_decision {
+            count += 1;
+        }
+        count += cyclomatic(child, file);
+    }
+    count
+}
+
+/// Cognitive complexity of the children of `node`, at nesting depth `nesting`
+fn cognitive_children(node: Node, nesting: usize, file: &AnalyzedFile) -> usize {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.children(&mut cursor).collect();
+    children.into_iter().map(|child| cognitive(child, nesting, file)).sum()
+}
+
+fn cognitive(node: Node, nesting: usize, file: &AnalyzedFile) -> usize {
+    let kind = node.kind();
+
+    if FUNCTION_KINDS.contains(&kind) {
+        return 0;
+    }
+    if LAMBDA_KINDS.contains(&kind) {
+        return cognitive_children(node, nesting + 1, file);
+    }
+
+    if IF_KINDS.contains(&kind) {
+        // `else if` continues the chain: +1 without a nesting penalty
+        let is_else_if = node.parent().is_some_and(|p| p.kind() == "else_clause");
+        let mut score = if is_else_if { 1 } else { 1 + nesting };
+        let body_nesting = nesting + 1;
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            score += match child.kind() {
+                "else_clause" => {
+                    let mut inner = child.walk();
+                    let chained_if = child.named_children(&mut inner).find(|n| IF_KINDS.contains(&n.kind()));
+                    match chained_if {
+                        Some(chained) => cognitive(chained, nesting, file),
+                        None => 1 + cognitive_children(child, body_nesting, file),
+                    }
+                }
+                "elif_clause" => 1 + cognitive_children(child, body_nesting, file),
+                _ => cognitive(child, body_nesting, file),
+            };
+        }
+        return score;
+    }
+
+    if LOOP_KINDS.contains(&kind)
+        || SWITCH_KINDS.contains(&kind)
+        || CATCH_KINDS.contains(&kind)
+        || TERNARY_KINDS.contains(&kind)
+    {
+        return 1 + nesting + cognitive_children(node, nesting + 1, file);
+    }
+
+    if let Some(operator) = logical_operator(node, file) {
+        // Each run of the same operator counts once: `a && b && c` is +1, `a && b || c` is +2
+        let continues_run = node.parent().and_then(|p| logical_operator(p, file)) == Some(operator);
+        let score = usize::from(!continues_run);
+        return score + cognitive_children(node, nesting, file);
+    }
+
+    // Jumps to a label break the linear flow
+    if matches!(kind, "break_expression" | "continue_expression" | "break_statement" | "continue_statement") {
+        let mut cursor = node.walk();
+        let has_label = node
+            .named_children(&mut cursor)
+            .any(|child| matches!(child.kind(), "label" | "statement_identifier"));
+        return usize::from(has_label);
+    }
+    if kind == "goto_statement" {
+        return 1;
+    }
+
+    cognitive_children(node, nesting, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn scores(source: &str, metric: ComplexityMetric) -> Vec<(String, usize)> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, source).unwrap();
+        ComplexityAnalyzer::new(metric)
+            .unwrap()
+            .analyze_file(&path)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.name, f.score))
+            .collect()
+    }
+
+    const FLAT_AND_NESTED: &str = r#"
+fn flat(a: bool, b: bool, c: bool) {
+    if a { println!("a"); }
+    if b { println!("b"); }
+    if c { println!("c"); }
+}
+
+fn nested(a: bool, items: &[u32]) {
+    if a {
+        for item in items {
+            if *item > 3 {
+                println!("{}", item);
+            }
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_cyclomatic_ignores_nesting() {
+        let scores = scores(FLAT_AND_NESTED, ComplexityMetric::Cyclomatic);
+        assert_eq!(scores, vec![("flat".to_string(), 4), ("nested".to_string(), 4)]);
+    }
+
+    #[test]
+    fn test_cognitive_penalizes_nesting() {
+        let scores = scores(FLAT_AND_NESTED, ComplexityMetric::Cognitive);
+        // flat: three top-level ifs; nested: if (1) + for (1+1) + if (1+2)
+        assert_eq!(scores, vec![("flat".to_string(), 3), ("nested".to_string(), 6)]);
+    }
+
+    #[test]
+    fn test_cognitive_else_if_and_boolean_sequences() {
+        let source = r#"
+fn classify(a: bool, b: bool, c: bool) -> u8 {
+    if a && b || c {
+        1
+    } else if a {
+        2
+    } else {
+        3
+    }
+}
+"#;
+        // if (+1), `&& ... ||` (+2), else if (+1), else (+1)
+        assert_eq!(scores(source, ComplexityMetric::Cognitive), vec![("classify".to_string(), 5)]);
+        // two ifs and two logical operators
+        assert_eq!(scores(source, ComplexityMetric::Cyclomatic), vec![("classify".to_string(), 5)]);
+    }
+
+    #[test]
+    fn test_sarif_levels_follow_thresholds() {
+        let function = |score| FunctionComplexity {
+            name: "handle".to_string(),
+            location: Location {
+                file_path: "src/lib.rs".into(),
+                line: 1,
+                column: 1,
+                end_line: None,
+                end_column: None,
+            },
+            metric: ComplexityMetric::Cyclomatic,
+            score,
+        };
+        assert!(function(9).to_sarif().is_none());
+        assert_eq!(function(10).to_sarif().unwrap().level, SarifLevel::Warning);
+        assert_eq!(function(25).to_sarif().unwrap().level, SarifLevel::Error);
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tree_sitter::Node;
+
+use crate::analyzers::{AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::location::byte_offset;
+use crate::core::sarif::{SarifLevel, SarifResult, SarifRule};
+use crate::core::{Language, Location, SymbolKind};
+use crate::indexers::{symbol_name_and_kind, ScipQuery, SymbolUsage};
+
+/// Shown alongside results: SCIP only sees static references
+pub const DEAD_CODE_CAVEAT: &str = "Candidates only: symbols used via reflection, dynamic dispatch, macros, \
+     FFI or string lookups have no static references and may be reported here.";
+
+/// A defined symbol with no references outside its own definition
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub location: Location,
+
+    /// Part of the public/exported API (only reported with `include_public`)
+    pub is_public: bool,
+}
+
+pub const DEAD_CODE_RULE: SarifRule = SarifRule {
+    id: "dead-code",
+    name: "DeadCode",
+    description: "Function, method or type with no references outside its own definition",
+};
+
+impl DeadSymbol {
+    /// Public items are only notes, since other packages may use them
+    pub fn to_sarif(&self) -> SarifResult {
+        SarifResult {
+            rule_id: DEAD_CODE_RULE.id,
+            level: if self.is_public { SarifLevel::Note } else { SarifLevel::Warning },
+            message: format!("{:?} `{}` is never referenced", self.kind, self.name),
+            location: self.location.clone(),
+            related: Vec::new(),
+        }
+    }
+}
+
+/// What tree-sitter says about a definition
+struct DefinitionInfo {
+    kind: SymbolKind,
+    is_public: bool,
+    /// Entry points and dispatch targets that are used without a static reference
+    is_exempt: bool,
+    /// 1-indexed line span of the whole declaration
+    lines: (usize, usize),
+}
+
+/// Find functions, methods and types that nothing references
+pub struct DeadCodeFinder {
+    analyzer: TreeSitterAnalyzer,
+    include_public: bool,
+    files: HashMap<PathBuf, Option<AnalyzedFile>>,
+}
+
+impl DeadCodeFinder {
+    pub fn new(include_public: bool) -> Result<Self> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+            include_public,
+            files: HashMap::new(),
+        })
+    }
+
+    pub fn find(&mut self, scip_query: &ScipQuery) -> Result<Vec<DeadSymbol>> {
+        let mut dead = Vec::new();
+
+        for usage in scip_query.symbol_usages() {
+            // Only functions, methods and types; fields, modules and macros are left out
+            let Some((name, descriptor_kind)) = symbol_name_and_kind(&usage.symbol)
+                .filter(|(_, kind)| matches!(kind, SymbolKind::Function | SymbolKind::Method | SymbolKind::Class))
+            else {
+                continue;
+            };
+            let Some(info) = self.definition_info(&usage, descriptor_kind) else {
+                continue;
+            };
+            if info.is_exempt || (info.is_public && !self.include_public) {
+                continue;
+            }
+
+            // Recursive calls and other uses inside the definition don't keep it alive
+            let (start, end) = info.lines;
+            let used_elsewhere = usage.references.iter().any(|reference| {
+                reference.file_path != usage.definition.file_path
+                    || reference.line < start
+                    || reference.line > end
+            });
+            if !used_elsewhere {
+                dead.push(DeadSymbol {
+                    name,
+                    kind: info.kind,
+                    location: usage.definition,
+                    is_public: info.is_public,
+                });
+            }
+        }
+
+        Ok(dead)
+    }
+
+    fn definition_info(&mut self, usage: &SymbolUsage, descriptor_kind: SymbolKind) -> Option<DefinitionInfo> {
+        let path = usage.definition.file_path.clone();
+        let analyzer = &mut self.analyzer;
+        let file = self
+            .files
+            .entry(path.clone())
+            .or_insert_with(|| analyzer.analyze_file(&path).ok())
+            .as_ref()?;
+
+        let byte = byte_offset(&file.content, usage.definition.line, usage.definition.column)?;
+        let name_node = file.tree.root_node().descendant_for_byte_range(byte, byte)?;
+        let declaration = declaration_of(name_node)?;
+        let name = &file.content[name_node.byte_range()];
+
+        Some(DefinitionInfo {
+            kind: node_symbol_kind(declaration).unwrap_or(descriptor_kind),
+            is_public: is_public(declaration, name, file),
+            is_exempt: is_exempt(declaration, name, file),
+            lines: (declaration.start_position().row + 1, declaration.end_position().row + 1),
+        })
+    }
+}
+
+/// The declaration a name node belongs to (`fn foo` -> the function_item)
+pub fn declaration_of(name_node: Node) -> Option<Node> {
+    let mut current = name_node.parent();
+    while let Some(node) = current {
+        // C++ declarators nest (`function_definition > function_declarator > identifier`)
+        if node.kind().ends_with("declarator") || node.kind().ends_with("identifier") {
+            current = node.parent();
+            continue;
+        }
+        let names_it = node.child_by_field_name("name").map(|n| n.id()) == Some(name_node.id())
+            || node
+                .child_by_field_name("declarator")
+                .is_some_and(|d| d.byte_range().contains(&name_node.start_byte()));
+        return names_it.then_some(node);
+    }
+    None
+}
+
+fn node_symbol_kind(declaration: Node) -> Option<SymbolKind> {
+    let kind = match declaration.kind() {
+        "struct_item" | "struct_specifier" => SymbolKind::Struct,
+        "enum_item" | "enum_declaration" | "enum_specifier" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Trait,
+        "interface_declaration" => SymbolKind::Interface,
+        "class_declaration" | "abstract_class_declaration" | "class_definition" | "class_specifier" => {
+            SymbolKind::Class
+        }
+        "method_definition" => SymbolKind::Method,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// Whether the declaration is part of the public/exported API
+fn is_public(declaration: Node, name: &str, file: &AnalyzedFile) -> bool {
+    match file.language {
+        // `pub(crate)` and friends aren't public API
+        Language::Rust => {
+            let mut cursor = declaration.walk();
+            let is_pub = declaration
+                .children(&mut cursor)
+                .any(|child| child.kind() == "visibility_modifier" && &file.content[child.byte_range()] == "pub");
+            is_pub
+        }
+        Language::TypeScript | Language::JavaScript => {
+            let mut cursor = declaration.walk();
+            let is_private = name.starts_with('#')
+                || declaration.children(&mut cursor).any(|child| {
+                    child.kind() == "accessibility_modifier" && &file.content[child.byte_range()] != "public"
+                });
+            !is_private && has_ancestor(declaration, &["export_statement"])
+        }
+        // Python has no visibility: anything not `_private` in a public class is importable
+        Language::Python => {
+            let mut current = Some(declaration);
+            while let Some(node) = current {
+                if node.kind() == "class_definition" || node.id() == declaration.id() {
+                    let node_name = node
+                        .child_by_field_name("name")
+                        .map(|n| &file.content[n.byte_range()])
+                        .unwrap_or(name);
+                    if node_name.starts_with('_') && !node_name.starts_with("__") {
+                        return false;
+                    }
+                }
+                current = node.parent();
+            }
+            !name.starts_with("__") || name.ends_with("__")
+        }
+        // Anything declared in a header can be used by other translation units
+        Language::C | Language::Cpp => file
+            .path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| matches!(ext, "h" | "hpp" | "hxx" | "hh")),
+        _ => true,
+    }
+}
+
+/// Entry points, tests and dispatch targets that run without a static reference
+fn is_exempt(declaration: Node, name: &str, file: &AnalyzedFile) -> bool {
+    if name == "main" {
+        return true;
+    }
+
+    match file.language {
+        Language::Rust => {
+            // Trait methods are called through the trait
+            if let Some(parent) = declaration.parent().and_then(|list| list.parent()) {
+                let in_trait_impl = parent.kind() == "impl_item" && parent.child_by_field_name("trait").is_some();
+                if in_trait_impl || parent.kind() == "trait_item" {
+                    return true;
+                }
+            }
+            preceding_attributes(declaration, file)
+                .iter()
+                .any(|attr| attr.contains("test") || attr.contains("no_mangle") || attr.contains("bench"))
+        }
+        Language::Python => {
+            // Dunder methods, pytest tests and decorated (registered) functions
+            (name.starts_with("__") && name.ends_with("__"))
+                || name.starts_with("test_")
+                || declaration.parent().is_some_and(|p| p.kind() == "decorated_definition")
+        }
+        Language::TypeScript | Language::JavaScript => name == "constructor",
+        _ => false,
+    }
+}
+
+/// Text of the `#[...]` attributes directly above a Rust item
+fn preceding_attributes<'a>(declaration: Node, file: &'a AnalyzedFile) -> Vec<&'a str> {
+    let mut attributes = Vec::new();
+    let mut sibling = declaration.prev_sibling();
+    while let Some(node) = sibling {
+        match node.kind() {
+            "attribute_item" => attributes.push(&file.content[node.byte_range()]),
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = node.prev_sibling();
+    }
+    attributes
+}
+
+fn has_ancestor(node: Node, kinds: &[&str]) -> bool {
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        if kinds.contains(&ancestor.kind()) {
+            return true;
+        }
+        current = ancestor.parent();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexers::scip_fixture::ScipFixture;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_finds_unreferenced_private_items() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "fn helper() {}\n\nfn unused() {\n    unused();\n}\n\npub fn api() {\n    helper();\n}\n\nimpl std::fmt::Display for Job {\n    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n}\n",
+        )
+        .unwrap();
+
+        let scip_query = ScipFixture::new()
+            .document("src/lib.rs")
+            .definition("helper().", 0, 3, 6)
+            .definition("unused().", 2, 3, 6)
+            .reference("unused().", 3, 4, 6)
+            .definition("api().", 6, 7, 3)
+            .reference("helper().", 7, 4, 6)
+            .definition("Job#fmt().", 11, 7, 3)
+            .query(root);
+
+        // `unused` only calls itself; `api` is public; `fmt` implements a trait
+        let dead = DeadCodeFinder::new(false).unwrap().find(&scip_query).unwrap();
+        let names: Vec<&str> = dead.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["unused"]);
+
+        let dead = DeadCodeFinder::new(true).unwrap().find(&scip_query).unwrap();
+        let names: Vec<&str> = dead.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["unused", "api"]);
+    }
+}
@@ -0,0 +1,426 @@
+use anyhow::Result;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::analyzers::{collect_source_files, AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::sarif::{SarifLevel, SarifResult, SarifRule};
+use crate::core::Location;
+
+/// Base of the rolling hash over token windows
+const HASH_BASE: u64 = 1_000_003;
+
+/// Windows sharing a hash beyond this many are boilerplate (imports, braces); comparing
+/// them all is quadratic and the results aren't useful refactoring targets
+const MAX_BUCKET_SIZE: usize = 32;
+
+/// Thresholds for what counts as a duplicate
+#[derive(Debug, Clone)]
+pub struct DuplicateOptions {
+    /// Smallest duplicated span to report, in lines
+    pub min_lines: usize,
+
+    /// Smallest duplicated span to report, in normalized tokens (also the hash window size)
+    pub min_tokens: usize,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            min_lines: 6,
+            min_tokens: 50,
+        }
+    }
+}
+
+/// One copy of a duplicated block
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateSpan {
+    pub file_path: PathBuf,
+    /// First line (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed, inclusive)
+    pub end_line: usize,
+}
+
+/// Blocks with the same normalized token sequence
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    /// Share of tokens that match exactly, identifiers and literals included (1.0 = verbatim copy)
+    pub similarity: f64,
+
+    /// Lines in the first copy
+    pub lines: usize,
+
+    /// Normalized tokens in each copy
+    pub tokens: usize,
+
+    pub instances: Vec<DuplicateSpan>,
+}
+
+pub const DUPLICATE_RULE: SarifRule = SarifRule {
+    id: "duplicate-code",
+    name: "DuplicateCode",
+    description: "Block of code repeated elsewhere, possibly with renamed identifiers or changed literals",
+};
+
+impl DuplicateSpan {
+    fn location(&self) -> Location {
+        Location {
+            file_path: self.file_path.clone(),
+            line: self.start_line,
+            column: 1,
+            end_line: Some(self.end_line),
+            end_column: None,
+        }
+    }
+}
+
+impl DuplicateGroup {
+    /// Reported at the first copy, with the other copies as related locations
+    pub fn to_sarif(&self) -> Option<SarifResult> {
+        let (first, others) = self.instances.split_first()?;
+        Some(SarifResult {
+            rule_id: DUPLICATE_RULE.id,
+            level: SarifLevel::Warning,
+            message: format!(
+                "{} lines duplicated in {} places (~{:.0}% identical)",
+                self.lines,
+                self.instances.len(),
+                self.similarity * 100.0
+            ),
+            location: first.location(),
+            related: others.iter().map(DuplicateSpan::location).collect(),
+        })
+    }
+}
+
+/// A source token: `norm` ignores identifier names and literal values, `raw` doesn't
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    norm: u64,
+    raw: u64,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// Token range `start..end` of file `file`
+type Span = (usize, usize, usize);
+
+/// Find copy-pasted code by hashing windows of normalized tokens
+pub struct DuplicateFinder {
+    options: DuplicateOptions,
+}
+
+impl DuplicateFinder {
+    pub fn new(options: DuplicateOptions) -> Self {
+        Self { options }
+    }
+
+    /// Find duplicate groups among the source files under `path`
+    pub fn find(&self, path: &Path) -> Result<Vec<DuplicateGroup>> {
+        let window = self.options.min_tokens.max(1);
+
+        let files: Vec<(PathBuf, Vec<Token>)> = collect_source_files(path)?
+            .par_iter()
+            .filter_map(|file| {
+                let analyzed = TreeSitterAnalyzer::new().ok()?.analyze_file(file).ok()?;
+                Some((file.clone(), tokenize(&analyzed)))
+            })
+            .filter(|(_, tokens)| tokens.len() >= window)
+            .collect();
+
+        // Identical windows, keyed by rolling hash
+        let mut buckets: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+        for (file_index, (_, tokens)) in files.iter().enumerate() {
+            for (start, hash) in window_hashes(tokens, window) {
+                buckets.entry(hash).or_default().push((file_index, start));
+            }
+        }
+
+        // Pair every window with the first copy, grouped by diagonal so consecutive
+        // matching windows can be merged into one longer match
+        let mut diagonals: HashMap<(usize, usize, isize), Vec<usize>> = HashMap::new();
+        for positions in buckets.values() {
+            if positions.len() < 2 || positions.len() > MAX_BUCKET_SIZE {
+                continue;
+            }
+            let (first_file, first_start) = positions[0];
+            let first = norms(&files[first_file].1[first_start..first_start + window]);
+
+            for &(file, start) in &positions[1..] {
+                let offset = start as isize - first_start as isize;
+                // Overlapping windows in one file are repetition, not a copy
+                if file == first_file && offset.unsigned_abs() < window {
+                    continue;
+                }
+                // Guard against hash collisions
+                if norms(&files[file].1[start..start + window]).ne(first.clone()) {
+                    continue;
+                }
+                diagonals.entry((first_file, file, offset)).or_default().push(first_start);
+            }
+        }
+
+        let mut copies: HashMap<Span, Vec<Span>> = HashMap::new();
+        for ((first_file, file, offset), mut starts) in diagonals {
+            starts.sort_unstable();
+            starts.dedup();
+            for (run_start, run_end) in consecutive_runs(&starts) {
+                let original = (first_file, run_start, run_end + window);
+                let copy = (file, (run_start as isize + offset) as usize, (run_end as isize + offset) as usize + window);
+                copies.entry(original).or_default().push(copy);
+            }
+        }
+
+        let mut groups: Vec<DuplicateGroup> = copies
+            .into_iter()
+            .filter_map(|(original, mut others)| {
+                others.sort_unstable();
+                others.dedup();
+                self.build_group(&files, original, &others)
+            })
+            .collect();
+
+        groups.sort_by(|a, b| {
+            b.lines
+                .cmp(&a.lines)
+                .then_with(|| a.instances[0].file_path.cmp(&b.instances[0].file_path))
+                .then_with(|| a.instances[0].start_line.cmp(&b.instances[0].start_line))
+        });
+        Ok(groups)
+    }
+
+    fn build_group(&self, files: &[(PathBuf, Vec<Token>)], original: Span, others: &[Span]) -> Option<DuplicateGroup> {
+        let span = |(file, start, end): Span| {
+            let tokens = &files[file].1[start..end];
+            Some(DuplicateSpan {
+                file_path: files[file].0.clone(),
+                start_line: tokens.first()?.start_line,
+                end_line: tokens.last()?.end_line,
+            })
+        };
+
+        let first = span(original)?;
+        let lines = first.end_line - first.start_line + 1;
+        if lines < self.options.min_lines {
+            return None;
+        }
+
+        let (file, start, end) = original;
+        let original_tokens = &files[file].1[start..end];
+        let total_similarity: f64 = others
+            .iter()
+            .map(|&(file, start, end)| raw_similarity(original_tokens, &files[file].1[start..end]))
+            .sum();
+
+        let mut instances = vec![first];
+        instances.extend(others.iter().filter_map(|&other| span(other)));
+
+        Some(DuplicateGroup {
+            similarity: (total_similarity / others.len() as f64 * 100.0).round() / 100.0,
+            lines,
+            tokens: end - start,
+            instances,
+        })
+    }
+}
+
+/// Leaf tokens of a file, skipping comments; identifiers and literals are normalized
+fn tokenize(file: &AnalyzedFile) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut cursor = file.tree.walk();
+
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        let is_literal = is_string_kind(kind);
+
+        if kind.contains("comment") {
+            // Skip the comment and its children
+        } else if node.child_count() == 0 || is_literal {
+            let text = &file.content[node.byte_range()];
+            let norm = if kind.ends_with("identifier") {
+                "$id"
+            } else if is_literal {
+                "$str"
+            } else if is_number_kind(kind) {
+                "$num"
+            } else {
+                kind
+            };
+            tokens.push(Token {
+                norm: hash(norm),
+                raw: hash(text),
+                start_line: node.start_position().row + 1,
+                end_line: node.end_position().row + 1,
+            });
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+
+        // Move to the next sibling, climbing up as needed
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return tokens;
+            }
+        }
+    }
+}
+
+fn is_string_kind(kind: &str) -> bool {
+    kind.ends_with("string") || kind.ends_with("string_literal") || kind == "char_literal"
+}
+
+fn is_number_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "integer_literal" | "float_literal" | "number" | "integer" | "float" | "number_literal"
+    )
+}
+
+fn hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn norms(tokens: &[Token]) -> impl Iterator<Item = u64> + Clone + '_ {
+    tokens.iter().map(|token| token.norm)
+}
+
+/// Rabin-Karp hashes of every `window`-token window, as (start index, hash)
+fn window_hashes(tokens: &[Token], window: usize) -> Vec<(usize, u64)> {
+    if tokens.len() < window {
+        return Vec::new();
+    }
+
+    let leading_power = (1..window).fold(1u64, |power, _| power.wrapping_mul(HASH_BASE));
+    let mut hash = tokens[..window]
+        .iter()
+        .fold(0u64, |hash, token| hash.wrapping_mul(HASH_BASE).wrapping_add(token.norm));
+
+    let mut hashes = Vec::with_capacity(tokens.len() - window + 1);
+    hashes.push((0, hash));
+    for start in 1..=tokens.len() - window {
+        hash = hash
+            .wrapping_sub(tokens[start - 1].norm.wrapping_mul(leading_power))
+            .wrapping_mul(HASH_BASE)
+            .wrapping_add(tokens[start + window - 1].norm);
+        hashes.push((start, hash));
+    }
+    hashes
+}
+
+/// Runs of consecutive values in a sorted list, as (first, last)
+fn consecutive_runs(sorted: &[usize]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &value in sorted {
+        match runs.last_mut() {
+            Some((_, last)) if *last + 1 == value => *last = value,
+            _ => runs.push((value, value)),
+        }
+    }
+    runs
+}
+
+/// Share of tokens that are identical, names and literal values included
+fn raw_similarity(a: &[Token], b: &[Token]) -> f64 {
+    if a.is_empty() {
+        return 1.0;
+    }
+    let same = a.iter().zip(b).filter(|(x, y)| x.raw == y.raw).count();
+    same as f64 / a.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const ORIGINAL: &str = r#"
+fn summarize(orders: &[Order]) -> Summary {
+    let mut total = 0;
+    let mut count = 0;
+    for order in orders {
+        if order.status == "paid" {
+            total += order.amount * 2;
+            count += 1;
+        }
+    }
+    Summary { total, count }
+}
+"#;
+
+    // Same code with the names changed
+    const COPY: &str = r#"
+fn tally(invoices: &[Invoice]) -> Report {
+    let mut sum = 0;
+    let mut n = 0;
+    for invoice in invoices {
+        if invoice.status == "paid" {
+            sum += invoice.amount * 2;
+            n += 1;
+        }
+    }
+    Report { total: sum, count: n }
+}
+"#;
+
+    const UNRELATED: &str = r#"
+fn parse(input: &str) -> Option<u32> {
+    input.trim().parse().ok()
+}
+"#;
+
+    #[test]
+    fn test_finds_renamed_copy() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("orders.rs"), format!("{}{}", ORIGINAL, UNRELATED)).unwrap();
+        fs::write(dir.path().join("invoices.rs"), COPY).unwrap();
+
+        let groups = DuplicateFinder::new(DuplicateOptions { min_lines: 5, min_tokens: 30 })
+            .find(dir.path())
+            .unwrap();
+
+        assert_eq!(groups.len(), 1, "{:?}", groups);
+        let group = &groups[0];
+        assert_eq!(group.instances.len(), 2);
+        assert!(group.lines >= 9);
+        assert!(group.similarity > 0.3 && group.similarity < 1.0);
+
+        let files: Vec<_> = group.instances.iter().map(|i| i.file_path.file_name().unwrap()).collect();
+        assert!(files.contains(&"orders.rs".as_ref()) && files.contains(&"invoices.rs".as_ref()));
+        // The unrelated function isn't part of the duplicate
+        let orders = group.instances.iter().find(|i| i.file_path.ends_with("orders.rs")).unwrap();
+        assert!(orders.end_line <= 12);
+    }
+
+    #[test]
+    fn test_min_lines_threshold() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.rs"), ORIGINAL).unwrap();
+        fs::write(dir.path().join("b.rs"), COPY).unwrap();
+
+        let groups = DuplicateFinder::new(DuplicateOptions { min_lines: 50, min_tokens: 30 })
+            .find(dir.path())
+            .unwrap();
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_window_hashes_roll() {
+        let tokens: Vec<Token> = [1, 2, 3, 1, 2, 3]
+            .iter()
+            .map(|&norm| Token { norm, raw: norm, start_line: 1, end_line: 1 })
+            .collect();
+        let hashes = window_hashes(&tokens, 3);
+        assert_eq!(hashes.len(), 4);
+        assert_eq!(hashes[0].1, hashes[3].1);
+        assert_ne!(hashes[0].1, hashes[1].1);
+    }
+}
@@ -55,4 +55,36 @@ impl FunctionFinder {
 
         format!("{}({}){}", func.name, params, return_type)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+    }
+
+    #[test]
+    fn test_ruby_methods() {
+        let mut finder = FunctionFinder::new().unwrap();
+        let path = fixture("ruby/billing.rb");
+
+        let all = finder.find_in_file(&path, true).unwrap();
+        let names: Vec<_> = all.iter().map(|s| (s.name.as_str(), s.location.line)).collect();
+        assert_eq!(
+            names,
+            vec![("overdue", 3), ("total", 7), ("subtotal", 13), ("amount", 18), ("summary", 25)]
+        );
+        assert_eq!(all[1].signature.as_deref(), Some("total(tax_rate = 0.0, currency: \"USD\")"));
+
+        // Methods after a bare `private` are left out unless private ones are requested
+        let public: Vec<_> = finder
+            .find_in_file(&path, false)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(public, vec!["overdue", "total", "amount", "summary"]);
+    }
+}
@@ -0,0 +1,289 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::analyzers::{collect_source_files, AnalyzedFile, TreeSitterAnalyzer};
+use crate::core::Location;
+use crate::indexers::{qualified_symbol_name, ScipQuery};
+
+/// A type implementing a trait or interface, or extending a (usually abstract) class
+#[derive(Debug, Clone, Serialize)]
+pub struct Implementation {
+    /// Implementing type as written (`Job`, `Vec<T>`, `T`)
+    pub type_name: String,
+    /// Trait, interface or base class being implemented
+    pub implements: String,
+    pub location: Location,
+    /// Type parameters and where clause of a generic Rust impl (`T: Display`, `where T: Clone`)
+    pub constraints: Option<String>,
+    /// Rust impl for every type meeting the constraints (`impl<T: Display> Show for T`)
+    pub is_blanket: bool,
+}
+
+/// Find implementations from SCIP relationships, or by scanning impl blocks and class headers
+pub struct ImplementationFinder {
+    analyzer: TreeSitterAnalyzer,
+}
+
+impl ImplementationFinder {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            analyzer: TreeSitterAnalyzer::new()?,
+        })
+    }
+
+    /// Implementations recorded as `is_implementation` relationships in the index
+    ///
+    /// scip-typescript records these for classes and interfaces; rust-analyzer doesn't
+    /// record trait impls, so Rust projects need [`find_by_name`](Self::find_by_name).
+    pub fn find_in_index(&self, scip_query: &ScipQuery, name: &str) -> Result<Vec<Implementation>> {
+        let suffix = format!("::{}", name);
+        let definitions: HashMap<String, Location> = scip_query
+            .symbol_usages()
+            .into_iter()
+            .map(|usage| (usage.symbol, usage.definition))
+            .collect();
+
+        let mut implementations = Vec::new();
+        for (implementor, implemented) in scip_query.implementation_relationships() {
+            let Some(implemented) = qualified_symbol_name(&implemented) else {
+                continue;
+            };
+            if implemented != name && !implemented.ends_with(&suffix) {
+                continue;
+            }
+            let Some(location) = definitions.get(&implementor) else {
+                continue;
+            };
+            implementations.push(Implementation {
+                type_name: qualified_symbol_name(&implementor).unwrap_or(implementor),
+                implements: implemented,
+                location: location.clone(),
+                constraints: None,
+                is_blanket: false,
+            });
+        }
+        sort_implementations(&mut implementations);
+        Ok(implementations)
+    }
+
+    /// `impl Name for X` blocks, and classes that implement or extend `Name`, under `path`
+    /// Only the last segment of a qualified name (`fmt::Display` -> `Display`) is compared
+    pub fn find_by_name(&mut self, path: &Path, name: &str) -> Result<Vec<Implementation>> {
+        let name = name.rsplit(['.', ':']).next().unwrap_or(name);
+
+        let mut implementations = Vec::new();
+        for file_path in collect_source_files(path)? {
+            let Ok(file) = self.analyzer.analyze_file(&file_path) else {
+                continue;
+            };
+            let mut stack = vec![file.tree.root_node()];
+            while let Some(node) = stack.pop() {
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+
+                let found = match node.kind() {
+                    "impl_item" => rust_impl(node, &file, name),
+                    "class_declaration" | "abstract_class_declaration" | "class" | "class_definition"
+                    | "class_specifier" | "struct_specifier" => class_implementation(node, &file, name),
+                    _ => None,
+                };
+                implementations.extend(found);
+            }
+        }
+        sort_implementations(&mut implementations);
+        Ok(implementations)
+    }
+}
+
+/// `impl<T: Bound> Name for Type where ...`, if it implements `name`
+fn rust_impl(node: Node, file: &AnalyzedFile, name: &str) -> Option<Implementation> {
+    let text = |n: Node| file.content[n.byte_range()].to_string();
+
+    let trait_node = node.child_by_field_name("trait")?;
+    if type_name_node(trait_node).map(text).as_deref() != Some(name) {
+        return None;
+    }
+    let type_node = node.child_by_field_name("type")?;
+
+    let type_parameters: Vec<String> = node
+        .child_by_field_name("type_parameters")
+        .map(|params| {
+            let mut cursor = params.walk();
+            params
+                .named_children(&mut cursor)
+                .filter_map(|param| match param.kind() {
+                    "type_identifier" => Some(text(param)),
+                    "constrained_type_parameter" => param.child_by_field_name("left").map(text),
+                    "type_parameter" => param.child_by_field_name("name").map(text),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // `for T` and `for &T` cover every type; `for Vec<T>` is only generic
+    let implementing = text(type_node);
+    let bare = implementing.trim_start_matches('&').trim_start_matches("mut ").trim();
+    let is_blanket = type_parameters.iter().any(|param| param == bare);
+
+    let mut constraints = Vec::new();
+    if let Some(params) = node.child_by_field_name("type_parameters") {
+        let params = text(params);
+        constraints.push(params.trim_start_matches('<').trim_end_matches('>').trim().to_string());
+    }
+    let mut cursor = node.walk();
+    if let Some(where_clause) = node.children(&mut cursor).find(|child| child.kind() == "where_clause") {
+        constraints.push(text(where_clause));
+    }
+    constraints.retain(|c| !c.is_empty());
+
+    let start = type_node.start_position();
+    Some(Implementation {
+        type_name: implementing,
+        implements: text(trait_node),
+        location: Location {
+            file_path: file.path.clone(),
+            line: start.row + 1,
+            column: start.column + 1,
+            end_line: Some(node.end_position().row + 1),
+            end_column: Some(node.end_position().column + 1),
+        },
+        constraints: (!constraints.is_empty()).then(|| constraints.join(" ")),
+        is_blanket,
+    })
+}
+
+/// A class whose `implements`/`extends` list, Python bases or C++ base classes include `name`
+fn class_implementation(node: Node, file: &AnalyzedFile, name: &str) -> Option<Implementation> {
+    let text = |n: Node| file.content[n.byte_range()].to_string();
+
+    let base = base_types(node)
+        .into_iter()
+        .find(|&base| type_name_node(base).map(text).as_deref() == Some(name))?;
+    let class_name = node.child_by_field_name("name")?;
+
+    let start = class_name.start_position();
+    Some(Implementation {
+        type_name: text(class_name),
+        implements: text(base),
+        location: Location {
+            file_path: file.path.clone(),
+            line: start.row + 1,
+            column: start.column + 1,
+            end_line: Some(node.end_position().row + 1),
+            end_column: Some(node.end_position().column + 1),
+        },
+        constraints: None,
+        is_blanket: false,
+    })
+}
+
+/// Types listed as bases of a class declaration
+fn base_types(class: Node) -> Vec<Node> {
+    let mut bases = Vec::new();
+    let mut cursor = class.walk();
+    for child in class.named_children(&mut cursor) {
+        match child.kind() {
+            // TypeScript nests extends/implements clauses; JavaScript has the expression directly
+            "class_heritage" => {
+                let mut inner = child.walk();
+                for clause in child.named_children(&mut inner) {
+                    match clause.kind() {
+                        "extends_clause" | "implements_clause" => {
+                            let mut types = clause.walk();
+                            bases.extend(clause.named_children(&mut types));
+                        }
+                        _ => bases.push(clause),
+                    }
+                }
+            }
+            // Python `class Job(Base, metaclass=ABCMeta)` and C++ `class Job : public Base`
+            "argument_list" | "base_class_clause" => {
+                let mut inner = child.walk();
+                bases.extend(child.named_children(&mut inner));
+            }
+            _ => {}
+        }
+    }
+    bases
+}
+
+/// The node naming a type, without its path or type arguments
+/// (`fmt::Display` -> `Display`, `Repository<User>` -> `Repository`)
+fn type_name_node(node: Node) -> Option<Node> {
+    match node.kind() {
+        "type_identifier" | "identifier" => Some(node),
+        "scoped_type_identifier" | "nested_type_identifier" | "qualified_identifier" | "scoped_identifier"
+        | "template_type" => type_name_node(node.child_by_field_name("name")?),
+        "generic_type" => type_name_node(
+            node.child_by_field_name("name")
+                .or_else(|| node.child_by_field_name("type"))?,
+        ),
+        "member_expression" => Some(node.child_by_field_name("property")?),
+        "attribute" => Some(node.child_by_field_name("attribute")?),
+        _ => None,
+    }
+}
+
+fn sort_implementations(implementations: &mut [Implementation]) {
+    implementations.sort_by(|a, b| {
+        (&a.location.file_path, a.location.line, a.location.column)
+            .cmp(&(&b.location.file_path, b.location.line, b.location.column))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn find(files: &[(&str, &str)], name: &str) -> Vec<Implementation> {
+        let dir = TempDir::new().unwrap();
+        for (path, content) in files {
+            fs::write(dir.path().join(path), content).unwrap();
+        }
+        ImplementationFinder::new().unwrap().find_by_name(dir.path(), name).unwrap()
+    }
+
+    #[test]
+    fn test_rust_impls_note_generic_constraints() {
+        let source = "trait Show {}\n\nstruct Job;\nimpl Show for Job {}\n\nimpl<T: Show> Show for Vec<T> {}\n\nimpl<T> Show for T where T: std::fmt::Display {}\n\nimpl std::fmt::Display for Job {}\n";
+        let found = find(&[("lib.rs", source)], "Show");
+
+        let summary: Vec<_> = found
+            .iter()
+            .map(|i| (i.type_name.as_str(), i.location.line, i.constraints.as_deref(), i.is_blanket))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Job", 4, None, false),
+                ("Vec<T>", 6, Some("T: Show"), false),
+                ("T", 8, Some("T where T: std::fmt::Display"), true),
+            ]
+        );
+
+        // Qualified trait paths match on their last segment
+        let found = find(&[("lib.rs", source)], "fmt::Display");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].implements, "std::fmt::Display");
+    }
+
+    #[test]
+    fn test_classes_implementing_or_extending() {
+        let ts = "interface Store {}\nabstract class Base {}\nclass Memory implements Store {}\nclass Disk extends Base implements Store, Closeable {}\nclass Other {}\n";
+        let py = "class Store(ABC):\n    pass\n\nclass Memory(Store):\n    pass\n";
+
+        let found = find(&[("app.ts", ts), ("store.py", py)], "Store");
+        let names: Vec<_> = found.iter().map(|i| (i.type_name.as_str(), i.location.line)).collect();
+        assert_eq!(names, vec![("Memory", 3), ("Disk", 4), ("Memory", 4)]);
+
+        let found = find(&[("app.ts", ts)], "Base");
+        let names: Vec<_> = found.iter().map(|i| i.type_name.as_str()).collect();
+        assert_eq!(names, vec!["Disk"]);
+    }
+}
@@ -1,9 +1,50 @@
+use anyhow::Result;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
+
+use crate::core::Language;
+
 pub mod tree_sitter_analyzer;
+pub mod tree_cache;
 pub mod pattern_matcher;
 pub mod function_finder;
 pub mod class_finder;
+pub mod complexity;
+pub mod dead_code;
+pub mod duplicates;
+pub mod call_graph;
+pub mod callers;
+pub mod implementations;
 
 pub use tree_sitter_analyzer::*;
 pub use pattern_matcher::*;
 pub use function_finder::*;
-pub use class_finder::*;
\ No newline at end of file
+pub use class_finder::*;
+pub use complexity::*;
+pub use dead_code::*;
+pub use duplicates::*;
+pub use call_graph::*;
+pub use callers::*;
+pub use implementations::*;
+
+/// Source files under `path` that tree-sitter can parse
+pub(crate) fn collect_source_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(path).standard_filters(true).build() {
+        let entry = entry?;
+        let file = entry.path();
+        let supported = file
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| Language::from_extension(ext).tree_sitter_language().is_some());
+        if file.is_file() && supported {
+            files.push(file.to_path_buf());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
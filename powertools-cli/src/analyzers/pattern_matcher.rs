@@ -1,54 +1,137 @@
 use anyhow::Result;
 use ignore::WalkBuilder;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use rayon::prelude::*;
 use crate::core::{SearchResult, Language};
 use crate::analyzers::TreeSitterAnalyzer;
 
+/// Longest a single file may spend being parsed and queried before its search is cut short
+pub const MAX_FILE_SEARCH_TIME: Duration = Duration::from_secs(5);
+
+/// Matches from a search, and whether any time limit cut it short
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// The overall timeout expired; files not reached yet weren't searched
+    pub timed_out: bool,
+    /// Files whose own time limit expired; only their earlier matches are included
+    pub truncated_files: Vec<PathBuf>,
+}
+
+impl SearchOutcome {
+    fn record(&mut self, file: &Path, cut: Option<Cut>) {
+        match cut {
+            Some(Cut::Timeout) => self.timed_out = true,
+            Some(Cut::FileLimit) => self.truncated_files.push(file.to_path_buf()),
+            None => {}
+        }
+    }
+}
+
+/// Which time limit stopped a file's search early
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cut {
+    Timeout,
+    FileLimit,
+}
+
+/// Search one file until the earlier of the overall deadline and its own time limit
+fn search_one(
+    analyzer: &mut TreeSitterAnalyzer,
+    file: &Path,
+    patterns: &[String],
+    max_results: usize,
+    deadline: Option<Instant>,
+    file_timeout: Duration,
+) -> Result<(Vec<SearchResult>, Option<Cut>)> {
+    let file_deadline = Instant::now() + file_timeout;
+    let until = deadline.map_or(file_deadline, |deadline| deadline.min(file_deadline));
+    let (results, cut_short) = analyzer.search_patterns_until(file, patterns, max_results, Some(until))?;
+
+    let cut = cut_short.then(|| {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Cut::Timeout
+        } else {
+            Cut::FileLimit
+        }
+    });
+    Ok((results, cut))
+}
+
 pub struct PatternMatcher {
     analyzer: TreeSitterAnalyzer,
+    timeout: Option<Duration>,
+    file_timeout: Duration,
 }
 
 impl PatternMatcher {
     pub fn new() -> Result<Self> {
         Ok(Self {
             analyzer: TreeSitterAnalyzer::new()?,
+            timeout: None,
+            file_timeout: MAX_FILE_SEARCH_TIME,
         })
     }
 
+    /// Stop searching once `timeout` has passed, keeping the matches found so far
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Cap each file's search at `timeout` instead of [`MAX_FILE_SEARCH_TIME`]
+    #[cfg(test)]
+    pub fn with_file_timeout(mut self, timeout: Duration) -> Self {
+        self.file_timeout = timeout;
+        self
+    }
+
     pub fn search_directory(
         &mut self,
         dir: &Path,
-        pattern: &str,
+        patterns: &[String],
         extensions: Vec<String>,
         max_results: usize,
-    ) -> Result<Vec<SearchResult>> {
+    ) -> Result<SearchOutcome> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let file_timeout = self.file_timeout;
         let files = self.collect_files(dir, extensions)?;
 
         // Process files in parallel for better performance
-        let pattern = pattern.to_string();
-        let results: Vec<Vec<SearchResult>> = files
+        let searched: Vec<(&PathBuf, Vec<SearchResult>, Option<Cut>)> = files
             .par_iter()
             .filter_map(|file| {
                 let mut local_analyzer = TreeSitterAnalyzer::new().ok()?;
-                local_analyzer.search_pattern(file, &pattern, max_results).ok()
+                let (results, cut) =
+                    search_one(&mut local_analyzer, file, patterns, max_results, deadline, file_timeout).ok()?;
+                Some((file, results, cut))
             })
             .collect();
 
-        // Flatten and limit results
-        let mut all_results: Vec<SearchResult> = results.into_iter().flatten().collect();
-        all_results.truncate(max_results);
+        let mut outcome = SearchOutcome::default();
+        for (file, results, cut) in searched {
+            outcome.results.extend(results);
+            outcome.record(file, cut);
+        }
+        outcome.results.truncate(max_results);
 
-        Ok(all_results)
+        Ok(outcome)
     }
 
     pub fn search_file(
         &mut self,
         file: &Path,
-        pattern: &str,
+        patterns: &[String],
         max_results: usize,
-    ) -> Result<Vec<SearchResult>> {
-        self.analyzer.search_pattern(file, pattern, max_results)
+    ) -> Result<SearchOutcome> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let (results, cut) = search_one(&mut self.analyzer, file, patterns, max_results, deadline, self.file_timeout)?;
+
+        let mut outcome = SearchOutcome { results, ..Default::default() };
+        outcome.record(file, cut);
+        Ok(outcome)
     }
 
     fn collect_files(&self, dir: &Path, extensions: Vec<String>) -> Result<Vec<PathBuf>> {
@@ -81,10 +164,62 @@ impl PatternMatcher {
             }
         }
 
+        files.sort();
         Ok(files)
     }
 }
 
+/// Split a query file into its top-level patterns
+///
+/// A pattern runs from an opening `(`, `[` or `"` at the top level through any trailing
+/// captures and quantifiers (`(call) @c`, `(comment)+`). `;` comments are dropped.
+pub fn split_query_patterns(source: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                // Comment to end of line
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+                continue;
+            }
+            '"' => {
+                if depth == 0 && !current.trim().is_empty() {
+                    patterns.push(std::mem::take(&mut current).trim().to_string());
+                }
+                current.push(c);
+                while let Some(next) = chars.next() {
+                    current.push(next);
+                    match next {
+                        '\\' => current.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+            '(' | '[' => {
+                if depth == 0 && !current.trim().is_empty() {
+                    patterns.push(std::mem::take(&mut current).trim().to_string());
+                }
+                depth += 1;
+            }
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        patterns.push(current.trim().to_string());
+    }
+    patterns
+}
+
 /// Common query patterns for different languages
 #[allow(dead_code)]
 pub struct QueryPatterns;
@@ -134,6 +269,10 @@ impl QueryPatterns {
                 (method_declaration)
             ] @func",
             Language::Java => "(method_declaration) @func",
+            Language::Ruby => "[
+                (method)
+                (singleton_method)
+            ] @func",
             _ => "",
         }
     }
@@ -156,6 +295,10 @@ impl QueryPatterns {
                 (class_declaration)
                 (interface_declaration)
             ] @type",
+            Language::Ruby => "[
+                (class)
+                (module)
+            ] @type",
             _ => "",
         }
     }
@@ -177,4 +320,99 @@ impl QueryPatterns {
             _ => "",
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_split_query_patterns() {
+        let source = "; functions\n(function_item name: (identifier) @name) @func\n\n[(struct_item) (enum_item)] @type ; types\n(line_comment)+ @docs\n";
+        assert_eq!(
+            split_query_patterns(source),
+            vec![
+                "(function_item name: (identifier) @name) @func",
+                "[(struct_item) (enum_item)] @type",
+                "(line_comment)+ @docs",
+            ]
+        );
+
+        // Parens and semicolons inside strings don't split or truncate a pattern
+        let source = r#"((string_literal) @s (#eq? @s "\"(;\""))"#;
+        assert_eq!(split_query_patterns(source), vec![source]);
+    }
+
+    #[test]
+    fn test_overlapping_patterns_are_attributed() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("lib.rs");
+        fs::write(&file, "fn run() {}\n\nstruct Job;\n").unwrap();
+
+        // Both patterns match the `run` function item; the second also matches `Job`
+        let patterns = vec![
+            "(function_item) @func".to_string(),
+            "[(function_item) (struct_item)] @item".to_string(),
+        ];
+        let mut matcher = PatternMatcher::new().unwrap();
+        let results = matcher.search_file(&file, &patterns, 50).unwrap().results;
+
+        let mut found: Vec<_> = results
+            .iter()
+            .map(|r| (r.pattern_index, r.node_type.as_str(), r.location.line))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                (Some(0), "function_item", 1),
+                (Some(1), "function_item", 1),
+                (Some(1), "struct_item", 3),
+            ]
+        );
+
+        // A single pattern leaves results untagged
+        let results = matcher.search_file(&file, &patterns[..1], 50).unwrap().results;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].pattern_index, None);
+
+        // The per-pattern limit applies separately to each pattern
+        let results = matcher.search_directory(dir.path(), &patterns, Vec::new(), 50).unwrap().results;
+        assert_eq!(results.len(), 3);
+        let results = matcher.search_file(&file, &patterns, 1).unwrap().results;
+        let mut indexes: Vec<_> = results.iter().map(|r| r.pattern_index).collect();
+        indexes.sort();
+        assert_eq!(indexes, vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_timeout_returns_partial_matches() {
+        let dir = TempDir::new().unwrap();
+        let functions: String = (0..20_000).map(|i| format!("fn f{}() {{ let x = {}; }}\n", i, i)).collect();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            fs::write(dir.path().join(name), &functions).unwrap();
+        }
+        let patterns = vec!["(function_item) @func".to_string()];
+
+        let full = PatternMatcher::new()
+            .unwrap()
+            .search_directory(dir.path(), &patterns, Vec::new(), usize::MAX)
+            .unwrap();
+        assert!(!full.timed_out);
+        assert!(full.truncated_files.is_empty());
+        assert_eq!(full.results.len(), 60_000);
+
+        let mut matcher = PatternMatcher::new().unwrap().with_timeout(Some(Duration::from_millis(1)));
+        let partial = matcher.search_directory(dir.path(), &patterns, Vec::new(), usize::MAX).unwrap();
+        assert!(partial.timed_out);
+        assert!(partial.results.len() < full.results.len());
+
+        // A per-file cap truncates the slow file without flagging the whole search
+        let mut matcher = PatternMatcher::new().unwrap().with_file_timeout(Duration::from_millis(1));
+        let capped = matcher.search_file(&dir.path().join("a.rs"), &patterns, usize::MAX).unwrap();
+        assert!(!capped.timed_out);
+        assert_eq!(capped.truncated_files, vec![dir.path().join("a.rs")]);
+        assert!(capped.results.len() < 20_000);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tree_sitter::{InputEdit, Parser, Point, Tree};
+
+use crate::core::Language;
+
+/// Cache shared by every analyzer in the process, once [`enable_shared`] was called
+static SHARED: OnceLock<TreeCache> = OnceLock::new();
+
+/// Keep parse trees for the rest of the process, so analyzers re-parse changed files
+/// incrementally
+///
+/// Meant for the long-lived MCP server, where the same files are analyzed again after
+/// small edits. Each cached file's source is kept in memory.
+pub fn enable_shared() {
+    SHARED.get_or_init(TreeCache::new);
+}
+
+/// The process-wide cache, if [`enable_shared`] was called
+pub fn shared() -> Option<&'static TreeCache> {
+    SHARED.get()
+}
+
+/// The last parse of each file, so a changed file can be re-parsed incrementally
+#[derive(Default)]
+pub struct TreeCache {
+    entries: Mutex<HashMap<PathBuf, CachedTree>>,
+    /// Parses that started from a cached tree instead of from scratch
+    reused: AtomicUsize,
+}
+
+#[derive(Clone)]
+struct CachedTree {
+    language: Language,
+    source: Arc<str>,
+    tree: Tree,
+}
+
+impl TreeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `source`, reusing the file's previous tree when there is one
+    ///
+    /// The parser must already be set to `language`. The previous tree is edited to
+    /// match the single changed region between the old and new source and passed to
+    /// the parser; without a previous tree for the same language, this is a full parse.
+    /// The lock isn't held while parsing, so analyzers on other threads aren't blocked.
+    pub fn parse(&self, parser: &mut Parser, path: &Path, language: Language, source: &str) -> Option<Tree> {
+        let key = cache_key(path);
+        let previous = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|cached| cached.language == language)
+            .cloned();
+
+        let tree = match previous {
+            Some(cached) if &*cached.source == source => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                return Some(cached.tree);
+            }
+            Some(mut cached) => {
+                let edit = input_edit(&cached.source, source);
+                cached.tree.edit(&edit);
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                parser.parse(source, Some(&cached.tree))?
+            }
+            None => parser.parse(source, None)?,
+        };
+
+        self.entries.lock().unwrap().insert(
+            key,
+            CachedTree {
+                language,
+                source: Arc::from(source),
+                tree: tree.clone(),
+            },
+        );
+        Some(tree)
+    }
+
+    /// Forget a file, e.g. after it was deleted
+    pub fn invalidate(&self, path: &Path) {
+        self.entries.lock().unwrap().remove(&cache_key(path));
+    }
+
+    /// How many parses started from a cached tree
+    #[cfg(test)]
+    pub fn reused(&self) -> usize {
+        self.reused.load(Ordering::Relaxed)
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+/// Absolute form of `path`, so `./src/a.rs` and the watcher's `/project/src/a.rs` match
+///
+/// Doesn't touch the filesystem, so it works for deleted files too.
+fn cache_key(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The edit turning `old` into `new`: everything between their common prefix and suffix
+fn input_edit(old: &str, new: &str) -> InputEdit {
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+
+    let old_end = old.len() - suffix;
+    let new_end = new.len() - suffix;
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old, prefix),
+        old_end_position: point_at(old, old_end),
+        new_end_position: point_at(new, new_end),
+    }
+}
+
+/// Row and byte column of a byte offset
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text.as_bytes()[..byte];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let line_start = before.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Point::new(row, byte - line_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust_parser() -> Parser {
+        let mut parser = Parser::new();
+        parser.set_language(&Language::Rust.tree_sitter_language().unwrap()).unwrap();
+        parser
+    }
+
+    #[test]
+    fn test_input_edit_covers_changed_region() {
+        let edit = input_edit("fn a() {}\nfn b() {}\n", "fn a() {}\nfn bc() {}\n");
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (14, 14, 15));
+        assert_eq!(edit.start_position, Point::new(1, 4));
+        assert_eq!(edit.new_end_position, Point::new(1, 5));
+
+        // Repeated characters are attributed to the prefix, not counted twice
+        let edit = input_edit("aaa", "aaaa");
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (3, 3, 4));
+
+        // Never split a multi-byte character
+        let edit = input_edit("let s = \"é\";", "let s = \"è\";");
+        assert_eq!((edit.start_byte, edit.old_end_byte, edit.new_end_byte), (9, 11, 11));
+    }
+
+    #[test]
+    fn test_incremental_parse_reuses_cached_tree() {
+        let mut parser = rust_parser();
+        let cache = TreeCache::new();
+        let path = Path::new("src/lib.rs");
+
+        let versions = [
+            "fn first() {}\n\nfn second(x: u32) -> u32 { x }\n",
+            "fn first() {}\n\nfn second(x: u32, y: u32) -> u32 { x + y }\n",
+            "struct Job;\n\nfn first() {}\n\nfn second(x: u32, y: u32) -> u32 { x + y }\n",
+            "struct Job;\n\nfn second(x: u32, y: u32) -> u32 { x + y }\n",
+        ];
+        for (parsed, source) in versions.into_iter().enumerate() {
+            let incremental = cache.parse(&mut parser, path, Language::Rust, source).unwrap();
+            let full = rust_parser().parse(source, None).unwrap();
+            assert_eq!(incremental.root_node().to_sexp(), full.root_node().to_sexp());
+            // Only the first version was parsed from scratch
+            assert_eq!(cache.reused(), parsed);
+        }
+
+        // Unchanged source returns the cached tree itself
+        let last = versions[versions.len() - 1];
+        let cached = cache.parse(&mut parser, path, Language::Rust, last).unwrap();
+        let again = cache.parse(&mut parser, &std::path::absolute(path).unwrap(), Language::Rust, last).unwrap();
+        assert_eq!(cached.root_node().id(), again.root_node().id());
+        assert_eq!(cache.len(), 1);
+
+        // After invalidation the next parse starts from scratch
+        cache.invalidate(path);
+        assert_eq!(cache.len(), 0);
+        let reused = cache.reused();
+        cache.parse(&mut parser, path, Language::Rust, last).unwrap();
+        assert_eq!(cache.reused(), reused);
+    }
+}
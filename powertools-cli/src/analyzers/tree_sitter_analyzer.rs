@@ -1,28 +1,55 @@
 use anyhow::{anyhow, Result};
-use std::fs;
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::ops::Deref;
 use std::path::Path;
+use std::time::Instant;
 use tree_sitter::{Parser, Query, QueryCursor, Node};
+use crate::analyzers::tree_cache::{self, TreeCache};
 use crate::core::{Language, Location, SearchResult};
 
+/// Files at least this large are memory-mapped rather than read onto the heap
+pub const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
 pub struct TreeSitterAnalyzer {
     parser: Parser,
+    mmap_threshold: u64,
+    /// Previous trees to re-parse from; the process-wide cache when it's enabled
+    tree_cache: Option<&'static TreeCache>,
 }
 
 impl TreeSitterAnalyzer {
     pub fn new() -> Result<Self> {
         let parser = Parser::new();
-        Ok(Self { parser })
+        Ok(Self { parser, mmap_threshold: MMAP_THRESHOLD, tree_cache: tree_cache::shared() })
+    }
+
+    /// Memory-map files of at least `bytes` instead of [`MMAP_THRESHOLD`]
+    #[cfg(test)]
+    pub fn with_mmap_threshold(mut self, bytes: u64) -> Self {
+        self.mmap_threshold = bytes;
+        self
+    }
+
+    /// Re-parse from the trees in `cache` instead of the process-wide cache
+    #[cfg(test)]
+    pub fn with_tree_cache(mut self, cache: &'static TreeCache) -> Self {
+        self.tree_cache = Some(cache);
+        self
     }
 
     pub fn analyze_file(&mut self, file_path: &Path) -> Result<AnalyzedFile> {
-        let content = fs::read_to_string(file_path)?;
         let language = self.detect_language(file_path)?;
 
         let tree_sitter_lang = language.tree_sitter_language()
             .ok_or_else(|| anyhow!("Unsupported language: {:?}", language))?;
 
+        let content = SourceText::load(file_path, self.mmap_threshold)?;
         self.parser.set_language(&tree_sitter_lang)?;
-        let tree = self.parser.parse(&content, None)
+        let tree = match self.tree_cache {
+            Some(cache) => cache.parse(&mut self.parser, file_path, language, &content),
+            None => self.parser.parse(content.as_bytes(), None),
+        }
             .ok_or_else(|| anyhow!("Failed to parse file"))?;
 
         Ok(AnalyzedFile {
@@ -39,15 +66,95 @@ impl TreeSitterAnalyzer {
         pattern: &str,
         max_results: usize,
     ) -> Result<Vec<SearchResult>> {
-        let analyzed = self.analyze_file(file_path)?;
-        let query = Query::new(&analyzed.language.tree_sitter_language().unwrap(), pattern)
+        self.search_patterns(file_path, &[pattern.to_string()], max_results)
+    }
+
+    /// Run several queries over one parse of the file
+    ///
+    /// The patterns are compiled into a single query, so the tree is walked once.
+    /// With more than one pattern, each result's `pattern_index` says which one matched;
+    /// a node matched by two patterns is reported once per pattern.
+    pub fn search_patterns(
+        &mut self,
+        file_path: &Path,
+        patterns: &[String],
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>> {
+        self.search_patterns_until(file_path, patterns, max_results, None)
+            .map(|(results, _)| results)
+    }
+
+    /// [`search_patterns`](Self::search_patterns), giving up at `deadline`
+    ///
+    /// Returns the matches found before the deadline, and whether it cut the search
+    /// short. Parsing is bounded by the parser's timeout; the query is checked
+    /// between matches.
+    pub fn search_patterns_until(
+        &mut self,
+        file_path: &Path,
+        patterns: &[String],
+        max_results: usize,
+        deadline: Option<Instant>,
+    ) -> Result<(Vec<SearchResult>, bool)> {
+        if patterns.is_empty() {
+            return Err(anyhow!("No query pattern given"));
+        }
+        let expired = || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok((Vec::new(), true));
+            }
+            self.parser.set_timeout_micros(remaining.as_micros().max(1) as u64);
+        }
+        let analyzed = self.analyze_file(file_path);
+        self.parser.set_timeout_micros(0);
+        let analyzed = match analyzed {
+            Err(_) if expired() => {
+                // The next parse would otherwise try to resume this one
+                self.parser.reset();
+                return Ok((Vec::new(), true));
+            }
+            analyzed => analyzed?,
+        };
+
+        let mut source = String::new();
+        let mut starts = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            starts.push(source.len());
+            source.push_str(pattern);
+            source.push('\n');
+        }
+        let query = Query::new(&analyzed.language.tree_sitter_language().unwrap(), &source)
             .map_err(|e| anyhow!("Invalid query pattern: {}", e))?;
+        let tag = |pattern_index: usize| {
+            let start = query.start_byte_for_pattern(pattern_index);
+            (patterns.len() > 1).then(|| starts.partition_point(|&s| s <= start) - 1)
+        };
 
+        let lines: Vec<&str> = analyzed.content.lines().collect();
         let mut query_cursor = QueryCursor::new();
         let matches = query_cursor.matches(&query, analyzed.tree.root_node(), analyzed.content.as_bytes());
 
         let mut results = Vec::new();
-        for m in matches.take(max_results) {
+        let mut timed_out = false;
+        let mut per_pattern = vec![0; patterns.len()];
+        for m in matches {
+            if per_pattern.iter().all(|&count| count >= max_results) {
+                break;
+            }
+            if expired() {
+                timed_out = true;
+                break;
+            }
+            let pattern_index = tag(m.pattern_index);
+            let count = &mut per_pattern[pattern_index.unwrap_or(0)];
+            if *count >= max_results {
+                continue;
+            }
+            *count += 1;
+
             for capture in m.captures {
                 let node = capture.node;
                 let start = node.start_position();
@@ -56,7 +163,6 @@ impl TreeSitterAnalyzer {
                 let matched_text = &analyzed.content[node.byte_range()];
 
                 // Get context lines
-                let lines: Vec<&str> = analyzed.content.lines().collect();
                 let context_before = if start.row > 0 {
                     lines.get(start.row - 1).map(|s| s.to_string())
                 } else {
@@ -77,11 +183,12 @@ impl TreeSitterAnalyzer {
                     context_after,
                     language: analyzed.language,
                     node_type: node.kind().to_string(),
+                    pattern_index,
                 });
             }
         }
 
-        Ok(results)
+        Ok((results, timed_out))
     }
 
     pub fn find_functions(&mut self, file_path: &Path) -> Result<Vec<FunctionInfo>> {
@@ -105,6 +212,10 @@ impl TreeSitterAnalyzer {
             Language::Cpp | Language::C => r#"
                 (function_definition) @func
             "#,
+            Language::Ruby => r#"
+                (method name: (_) @name) @func
+                (singleton_method name: (_) @name) @func
+            "#,
             _ => return Ok(Vec::new()),
         };
 
@@ -177,6 +288,7 @@ impl TreeSitterAnalyzer {
                     true
                 }
             }
+            Language::Ruby => ruby_method_visibility(node, analyzed) == "public",
             _ => true, // Default to public for other languages
         }
     }
@@ -232,10 +344,83 @@ impl TreeSitterAnalyzer {
     }
 }
 
+/// Visibility of a Ruby method: `private def x`, or the last bare
+/// `private`/`protected`/`public` before it in the same class body.
+/// `def self.x` ignores those, as Ruby does
+fn ruby_method_visibility<'a>(node: &Node, analyzed: &'a AnalyzedFile) -> &'a str {
+    const MODIFIERS: &[&str] = &["private", "protected", "public"];
+    let text = |n: Node| &analyzed.content[n.byte_range()];
+
+    // `private def helper` parses as a call whose argument is the method
+    if let Some(call) = node.parent().and_then(|p| p.parent()).filter(|c| c.kind() == "call") {
+        if let Some(method) = call.child_by_field_name("method") {
+            if MODIFIERS.contains(&text(method)) {
+                return text(method);
+            }
+        }
+    }
+
+    if node.kind() == "singleton_method" {
+        return "public";
+    }
+    let mut sibling = node.prev_named_sibling();
+    while let Some(candidate) = sibling {
+        if candidate.kind() == "identifier" && MODIFIERS.contains(&text(candidate)) {
+            return text(candidate);
+        }
+        sibling = candidate.prev_named_sibling();
+    }
+    "public"
+}
+
+/// A file's text, read onto the heap or memory-mapped when large
+///
+/// Derefs to `str`, so byte ranges from the tree index it directly. Invalid UTF-8
+/// is replaced with U+FFFD (in a heap copy) before parsing, so offsets stay consistent.
+pub enum SourceText {
+    Owned(String),
+    Mapped(Mmap),
+}
+
+impl SourceText {
+    pub fn load(path: &Path, mmap_threshold: u64) -> Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+
+        if len > 0 && len >= mmap_threshold {
+            // SAFETY: the map is read-only. Like other tools that map source files, we
+            // accept that a file rewritten while it's being analyzed yields garbage results.
+            let map = unsafe { Mmap::map(&file)? };
+            return Ok(match std::str::from_utf8(&map) {
+                Ok(_) => Self::Mapped(map),
+                Err(_) => Self::Owned(String::from_utf8_lossy(&map).into_owned()),
+            });
+        }
+
+        let bytes = fs::read(path)?;
+        Ok(Self::Owned(match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        }))
+    }
+}
+
+impl Deref for SourceText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Owned(text) => text,
+            // SAFETY: `load` only keeps a map whose bytes passed UTF-8 validation
+            Self::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+}
+
 pub struct AnalyzedFile {
     #[allow(dead_code)]
     pub path: std::path::PathBuf,
-    pub content: String,
+    pub content: SourceText,
     pub tree: tree_sitter::Tree,
     pub language: Language,
 }
@@ -247,4 +432,80 @@ pub struct FunctionInfo {
     pub is_public: bool,
     pub parameters: Vec<String>,
     pub return_type: Option<String>,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Functions and search results, in a form that can be compared
+    fn analyze(analyzer: &mut TreeSitterAnalyzer, path: &Path) -> (Vec<(String, usize, usize, Vec<String>)>, String) {
+        let functions = analyzer
+            .find_functions(path)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.name, f.location.line, f.location.column, f.parameters))
+            .collect();
+        let results = analyzer.search_pattern(path, "(string_literal) @s", usize::MAX).unwrap();
+        (functions, serde_json::to_string(&results).unwrap())
+    }
+
+    #[test]
+    fn test_mapped_large_file_matches_read_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("generated.rs");
+        let mut source = String::new();
+        for i in 0..20_000 {
+            source.push_str(&format!("pub fn handler_{i}(id: u32, name: &str) -> String {{\n    format!(\"héllo {{}} → {i}\", name)\n}}\n\n"));
+        }
+        fs::write(&path, &source).unwrap();
+        assert!(fs::metadata(&path).unwrap().len() > MMAP_THRESHOLD);
+
+        let mut read = TreeSitterAnalyzer::new().unwrap().with_mmap_threshold(u64::MAX);
+        let mut mapped = TreeSitterAnalyzer::new().unwrap();
+        assert!(matches!(read.analyze_file(&path).unwrap().content, SourceText::Owned(_)));
+        assert!(matches!(mapped.analyze_file(&path).unwrap().content, SourceText::Mapped(_)));
+
+        let (functions, results) = analyze(&mut mapped, &path);
+        assert_eq!(functions.len(), 20_000);
+        assert_eq!(functions[19_999], ("handler_19999".to_string(), 79_997, 1, vec!["id: u32".to_string(), "name: &str".to_string()]));
+        assert_eq!((functions, results), analyze(&mut read, &path));
+    }
+
+    #[test]
+    fn test_tree_cache_follows_edits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        let cache: &'static TreeCache = Box::leak(Box::default());
+        let mut analyzer = TreeSitterAnalyzer::new().unwrap().with_tree_cache(cache);
+
+        fs::write(&path, "fn first() {}\nfn second() {}\n").unwrap();
+        assert_eq!(analyze(&mut analyzer, &path).0.len(), 2);
+        // Only the first of the two parses in `analyze` started from scratch
+        assert_eq!(cache.reused(), 1);
+
+        fs::write(&path, "fn first() {}\nfn renamed(x: u8) {}\nfn third() {}\n").unwrap();
+        let (functions, _) = analyze(&mut TreeSitterAnalyzer::new().unwrap().with_tree_cache(cache), &path);
+        assert_eq!(cache.reused(), 3);
+        let names: Vec<_> = functions.iter().map(|(name, line, ..)| (name.as_str(), *line)).collect();
+        assert_eq!(names, vec![("first", 1), ("renamed", 2), ("third", 3)]);
+        assert_eq!(functions, analyze(&mut TreeSitterAnalyzer::new().unwrap(), &path).0);
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_replaced_on_both_paths() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("latin1.rs");
+        fs::write(&path, b"// caf\xe9\nfn first() {}\nfn second() {}\n").unwrap();
+
+        let mut read = TreeSitterAnalyzer::new().unwrap().with_mmap_threshold(u64::MAX);
+        let mut mapped = TreeSitterAnalyzer::new().unwrap().with_mmap_threshold(0);
+        assert!(read.analyze_file(&path).unwrap().content.starts_with("// caf\u{FFFD}"));
+
+        let (functions, results) = analyze(&mut mapped, &path);
+        let names: Vec<_> = functions.iter().map(|(name, line, ..)| (name.as_str(), *line)).collect();
+        assert_eq!(names, vec![("first", 2), ("second", 3)]);
+        assert_eq!((functions, results), analyze(&mut read, &path));
+    }
+}
@@ -1,24 +1,30 @@
 use anyhow::Result;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::PathBuf;
 
-use crate::refactor::{BatchReplacer, generate_preview};
+use crate::refactor::{BatchReplacer, ReplaceScope, generate_preview};
 
 pub async fn run(
     pattern: String,
     replacement: String,
     file_pattern: Option<String>,
     path: Option<PathBuf>,
+    within: Option<String>,
     preview: bool,
+    yes: bool,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     let search_path = path.unwrap_or_else(|| PathBuf::from("."));
 
-    let replacer = BatchReplacer::new(
+    let mut replacer = BatchReplacer::new(
         &pattern,
         replacement.clone(),
         file_pattern.clone(),
         search_path.clone(),
     )?;
+    if let Some(within) = within {
+        replacer = replacer.with_scope(within.parse::<ReplaceScope>()?);
+    }
 
     if preview {
         // Preview mode - show what would change
@@ -38,6 +44,32 @@ pub async fn run(
             }
         }
     } else {
+        if !yes {
+            let diffs = replacer.preview()?;
+            if diffs.is_empty() {
+                println!("No matches found.");
+                return Ok(());
+            }
+
+            let files = diffs.len();
+            let replacements: usize = diffs.iter().map(|diff| diff.num_changes).sum();
+            if !io::stdin().is_terminal() {
+                anyhow::bail!(
+                    "Refusing to make {} replacements in {} files without confirmation: stdin is not a terminal. \
+                     Pass --yes to apply, or --preview to review the changes.",
+                    replacements,
+                    files
+                );
+            }
+
+            // The prompt goes to stderr so `--format json` output stays parseable
+            let prompt = format!("Apply {} replacements in {} files? [y/N] ", replacements, files);
+            if !confirm(&prompt, &mut io::stdin().lock(), &mut io::stderr())? {
+                eprintln!("Aborted; no files were changed.");
+                return Ok(());
+            }
+        }
+
         // Apply mode - make the changes
         let result = replacer.apply()?;
 
@@ -70,3 +102,35 @@ pub async fn run(
 
     Ok(())
 }
+
+/// Ask a yes/no question; anything but `y` or `yes` (including EOF) means no
+pub(crate) fn confirm(prompt: &str, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool> {
+    write!(output, "{}", prompt)?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_defaults_to_no() {
+        let ask = |answer: &str| {
+            let mut output = Vec::new();
+            let confirmed = confirm("Apply? [y/N] ", &mut answer.as_bytes(), &mut output).unwrap();
+            assert_eq!(output, b"Apply? [y/N] ");
+            confirmed
+        };
+
+        assert!(ask("y\n"));
+        assert!(ask("YES\n"));
+        assert!(!ask("\n"));
+        assert!(!ask("n\n"));
+        assert!(!ask("yep\n"));
+        assert!(!ask(""));
+    }
+}
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use crate::analyzers::{CallGraph, CallGraphBuilder};
+use crate::indexers::ScipQuery;
+
+/// Build the call graph and return it (for MCP/API use)
+pub fn build_call_graph(project_root: PathBuf, root: Option<&str>, depth: Option<usize>) -> Result<CallGraph> {
+    let scip_query = ScipQuery::from_project(project_root)
+        .context("Failed to load SCIP index. Run 'powertools index' first.")?;
+    CallGraphBuilder::new()?.build(&scip_query, root, depth)
+}
+
+pub async fn run(
+    root: Option<String>,
+    project_root: Option<PathBuf>,
+    depth: Option<usize>,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let graph = build_call_graph(project_root.clone(), root.as_deref(), depth)?;
+
+    // DOT is the text form; pipe it to `dot -Tsvg` to render
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&graph)?);
+        }
+        _ => {
+            print!("{}", graph.to_dot(&project_root));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::analyzers::{CallKind, CallSite, CallTarget, CallerFinder};
+use crate::core::{location::parse_location, pagination::Page, Location};
+use crate::indexers::ScipQuery;
+
+/// Find callers and return them (for MCP/API use)
+///
+/// Uses SCIP references when the project is indexed and the function is found there;
+/// otherwise scans the project for call expressions with a matching callee name.
+pub fn find_callers(function: String, project_root: PathBuf) -> Result<Vec<CallSite>> {
+    let target = if function.contains(':') && function.split(':').count() >= 3 {
+        CallTarget::Location(parse_location(&function)?)
+    } else {
+        CallTarget::Name(function)
+    };
+
+    let mut finder = CallerFinder::new()?;
+    if let Ok(scip_query) = ScipQuery::from_project(project_root.clone()) {
+        let sites = finder.find_in_index(&scip_query, &target)?;
+        if !sites.is_empty() {
+            return Ok(sites);
+        }
+    }
+
+    let name = match target {
+        CallTarget::Name(name) => name,
+        CallTarget::Location(location) => {
+            let location = if location.file_path.is_relative() && !location.file_path.exists() {
+                Location { file_path: project_root.join(&location.file_path), ..location }
+            } else {
+                location
+            };
+            finder.name_at(&location).ok_or_else(|| {
+                anyhow!(
+                    "No function name at {}:{}:{}",
+                    location.file_path.display(),
+                    location.line,
+                    location.column
+                )
+            })?
+        }
+    };
+    finder.find_by_name(&project_root, &name)
+}
+
+pub async fn run(
+    function: String,
+    project_root: PathBuf,
+    limit: usize,
+    offset: usize,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let sites = find_callers(function.clone(), project_root.clone())?;
+    let page = Page::new(sites, limit, offset);
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&page.to_json("callers"))?);
+        }
+        _ if page.count == 0 => {
+            println!("No callers found for: {}", function);
+        }
+        _ => {
+            // One group per candidate, so overloads and same-named functions stay apart
+            let mut by_callee: BTreeMap<(&str, Option<String>), Vec<&CallSite>> = BTreeMap::new();
+            for site in &page.items {
+                let definition = site.callee_location.as_ref().map(|location| {
+                    let file = location.file_path.strip_prefix(&project_root).unwrap_or(&location.file_path);
+                    format!("{}:{}", file.display(), location.line)
+                });
+                by_callee.entry((site.callee.as_str(), definition)).or_default().push(site);
+            }
+
+            println!("Found {} call sites:", page.count);
+            for ((callee, definition), sites) in by_callee {
+                match definition {
+                    Some(definition) => println!("\n{} ({})", callee, definition),
+                    None => println!("\n{}", callee),
+                }
+                for site in sites {
+                    let file = site.location.file_path.strip_prefix(&project_root).unwrap_or(&site.location.file_path);
+                    let kind = match site.kind {
+                        CallKind::Direct => "",
+                        CallKind::Method => " [method]",
+                        CallKind::Unknown => " [?]",
+                    };
+                    println!(
+                        "  {}:{}:{}  in {}{}",
+                        file.display(),
+                        site.location.line,
+                        site.location.column,
+                        site.caller.as_deref().unwrap_or("<top level>"),
+                        kind
+                    );
+                }
+            }
+            if let Some(footer) = page.footer() {
+                println!("\n{}", footer);
+            }
+        }
+    }
+
+    Ok(())
+}
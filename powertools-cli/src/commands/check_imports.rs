@@ -0,0 +1,53 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::refactor::{format_file_changes, CheckImportsOptions, ImportChecker};
+
+pub async fn run(path: PathBuf, fix: bool, project_root: Option<PathBuf>, format: &crate::OutputFormat) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let checker = ImportChecker::new(project_root);
+
+    let result = checker.check(CheckImportsOptions { path, fix })?;
+
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        _ => {
+            if result.issues.is_empty() {
+                println!("✓ No duplicate or conflicting imports in {} files", result.files_checked);
+            } else {
+                println!("⚠️  {} import issues in {} files checked:", result.issues.len(), result.files_checked);
+                for issue in &result.issues {
+                    println!(
+                        "  {}:{} {}: {}{}",
+                        issue.file_path.display(),
+                        issue.lines[0],
+                        issue.kind.label(),
+                        issue.message(),
+                        if issue.fixable && !fix { " [fixable]" } else { "" }
+                    );
+                }
+            }
+
+            if let Some(transaction_result) = &result.transaction_result {
+                println!(
+                    "\n✓ Merged {} duplicate imports in {} files",
+                    result.imports_merged, result.files_fixed
+                );
+                print!("{}", format_file_changes(&transaction_result.files));
+            } else if !fix && result.issues.iter().any(|issue| issue.fixable) {
+                println!("\n💡 Run with --fix to merge the fixable duplicates");
+            }
+
+            if !result.warnings.is_empty() {
+                println!("\n⚠️  Skipped (could not parse):");
+                for warning in &result.warnings {
+                    println!("  {}", warning);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -2,13 +2,14 @@ use anyhow::Result;
 use std::path::PathBuf;
 use ignore::WalkBuilder;
 use rayon::prelude::*;
-use crate::core::{output::OutputWriter, Language, Symbol};
+use crate::core::{output::OutputWriter, ordering::{sort_symbols, SortOrder}, pagination::Page, Language, Symbol};
 use crate::analyzers::ClassFinder;
 
 /// Find classes and return them (for MCP/API use)
 pub async fn find_classes(
     path: Option<PathBuf>,
     include_nested: bool,
+    sort: SortOrder,
 ) -> Result<Vec<Symbol>> {
     let search_path = path.unwrap_or_else(|| PathBuf::from("."));
     let mut all_classes = Vec::new();
@@ -36,22 +37,36 @@ pub async fn find_classes(
         }
     }
 
+    sort_symbols(&mut all_classes, sort);
     Ok(all_classes)
 }
 
 pub async fn run(
     path: Option<PathBuf>,
     include_nested: bool,
+    sort: SortOrder,
+    limit: usize,
+    offset: usize,
     format: &crate::OutputFormat,
 ) -> Result<()> {
-    let all_classes = find_classes(path, include_nested).await?;
+    let all_classes = find_classes(path, include_nested, sort).await?;
     let output = OutputWriter::new(format);
+    let page = Page::new(all_classes, limit, offset);
 
-    if all_classes.is_empty() {
-        println!("No classes/structs found");
-    } else {
-        println!("Found {} classes/structs:", all_classes.len());
-        output.write_symbols(&all_classes)?;
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&page.to_json("classes"))?);
+        }
+        _ if page.count == 0 => {
+            println!("No classes/structs found");
+        }
+        _ => {
+            println!("Found {} classes/structs:", page.count);
+            output.write_symbols(&page.items)?;
+            if let Some(footer) = page.footer() {
+                println!("\n{}", footer);
+            }
+        }
     }
 
     Ok(())
@@ -76,5 +91,7 @@ fn collect_source_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
         }
     }
 
+    // Walk order varies by platform; keep "none" reproducible too
+    files.sort();
     Ok(files)
 }
\ No newline at end of file
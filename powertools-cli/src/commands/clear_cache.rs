@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use super::batch_replace::confirm;
+
+/// A file or directory removed by `clear-cache`
+#[derive(Debug, Clone, Serialize)]
+pub struct Removed {
+    pub path: PathBuf,
+    pub bytes: u64,
+}
+
+/// Per-user cache directory for powertools (`~/.cache/powertools` on Linux)
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("powertools"))
+}
+
+/// Existing paths `clear-cache` would remove
+///
+/// With `index`, that includes the project's SCIP indexes and their `.scip.meta`
+/// files. The include/exclude filter saved by `index` is kept: it's configuration.
+pub fn targets(cache_dir: Option<&Path>, project_root: &Path, index: bool) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = cache_dir.filter(|dir| dir.exists()).map(Path::to_path_buf).into_iter().collect();
+
+    if index && project_root.is_dir() {
+        let mut index_files = Vec::new();
+        for entry in std::fs::read_dir(project_root).context("Failed to read project directory")? {
+            let path = entry?.path();
+            let is_index = path.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+                name.starts_with("index.") && (name.ends_with(".scip") || name.ends_with(".scip.meta"))
+            });
+            if is_index && path.is_file() {
+                index_files.push(path);
+            }
+        }
+        index_files.sort();
+        paths.extend(index_files);
+    }
+
+    Ok(paths)
+}
+
+/// Remove the given files and directories, recording their sizes first
+pub fn remove(paths: &[PathBuf]) -> Result<Vec<Removed>> {
+    let mut removed = Vec::new();
+    for path in paths {
+        let bytes = size_on_disk(path);
+        if path.is_dir() {
+            std::fs::remove_dir_all(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        } else {
+            std::fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        removed.push(Removed { path: path.clone(), bytes });
+    }
+    Ok(removed)
+}
+
+fn size_on_disk(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub async fn run(project_root: PathBuf, index: bool, yes: bool, format: &crate::OutputFormat) -> Result<()> {
+    let paths = targets(cache_dir().as_deref(), &project_root, index)?;
+    if paths.is_empty() {
+        match format {
+            crate::OutputFormat::Json => println!("{}", serde_json::json!({ "removed": [], "total_bytes": 0 })),
+            _ => println!("Nothing to clear."),
+        }
+        return Ok(());
+    }
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            anyhow::bail!(
+                "Refusing to remove {} paths without confirmation: stdin is not a terminal. Pass --yes to clear.",
+                paths.len()
+            );
+        }
+
+        // The prompt goes to stderr so `--format json` output stays parseable
+        eprintln!("This will remove:");
+        for path in &paths {
+            eprintln!("  {} ({})", path.display(), format_size(size_on_disk(path)));
+        }
+        if !confirm("Continue? [y/N] ", &mut io::stdin().lock(), &mut io::stderr())? {
+            eprintln!("Aborted; nothing was removed.");
+            return Ok(());
+        }
+    }
+
+    let removed = remove(&paths)?;
+    let total: u64 = removed.iter().map(|r| r.bytes).sum();
+
+    match format {
+        crate::OutputFormat::Json => {
+            let result = serde_json::json!({ "removed": removed, "total_bytes": total });
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        _ => {
+            println!("Removed:");
+            for entry in &removed {
+                println!("  {} ({})", entry.path.display(), format_size(entry.bytes));
+            }
+            println!("Freed {} in {} paths", format_size(total), removed.len());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_clears_cache_and_indexes() {
+        let temp = TempDir::new().unwrap();
+        let cache = temp.path().join("cache/powertools");
+        let project = temp.path().join("project");
+        fs::create_dir_all(cache.join("lsp")).unwrap();
+        fs::create_dir_all(&project).unwrap();
+        fs::write(cache.join("lsp/state.json"), "{}").unwrap();
+        fs::write(project.join("index.rust.scip"), vec![0u8; 300]).unwrap();
+        fs::write(project.join("index.rust.scip.meta"), "{}").unwrap();
+        fs::write(project.join("index.filter.json"), "{}").unwrap();
+        fs::write(project.join("index.ts"), "export {};").unwrap();
+
+        // Without --index, indexes are left alone
+        let paths = targets(Some(&cache), &project, false).unwrap();
+        assert_eq!(paths, vec![cache.clone()]);
+
+        let paths = targets(Some(&cache), &project, true).unwrap();
+        assert_eq!(
+            paths,
+            vec![cache.clone(), project.join("index.rust.scip"), project.join("index.rust.scip.meta")]
+        );
+
+        let removed = remove(&paths).unwrap();
+        assert_eq!(removed.iter().map(|r| r.bytes).collect::<Vec<_>>(), vec![2, 300, 2]);
+        assert!(!cache.exists());
+        assert!(!project.join("index.rust.scip").exists());
+        assert!(!project.join("index.rust.scip.meta").exists());
+        assert!(project.join("index.filter.json").exists());
+        assert!(project.join("index.ts").exists());
+
+        // A second run finds nothing left
+        assert!(targets(Some(&cache), &project, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+use std::io::Write;
+
+/// Write the completion script for `shell` to `out`
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut command = crate::Cli::command();
+    let name = command.get_name().to_string();
+    generate(shell, &mut command, name, out);
+}
+
+pub async fn run(shell: Shell) -> Result<()> {
+    write_completions(shell, &mut std::io::stdout());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generates_completions_for_each_shell() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut out = Vec::new();
+            write_completions(shell, &mut out);
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("powertools"), "{:?} script doesn't mention the binary", shell);
+            assert!(script.contains("call-graph"), "{:?} script is missing subcommands", shell);
+        }
+    }
+}
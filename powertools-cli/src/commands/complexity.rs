@@ -0,0 +1,105 @@
+use anyhow::Result;
+use ignore::WalkBuilder;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+use crate::analyzers::{ComplexityAnalyzer, ComplexityMetric, FunctionComplexity};
+use crate::core::sarif::sarif_log;
+use crate::core::Language;
+
+/// Score every function and return them, most complex first (for MCP/API use)
+pub async fn analyze_complexity(
+    path: Option<PathBuf>,
+    metric: ComplexityMetric,
+) -> Result<Vec<FunctionComplexity>> {
+    let search_path = path.unwrap_or_else(|| PathBuf::from("."));
+    let files = if search_path.is_file() {
+        vec![search_path]
+    } else {
+        collect_source_files(&search_path)?
+    };
+
+    // Process files in parallel
+    let results: Vec<Vec<FunctionComplexity>> = files
+        .par_iter()
+        .filter_map(|file| {
+            let mut analyzer = ComplexityAnalyzer::new(metric).ok()?;
+            analyzer.analyze_file(file).ok()
+        })
+        .collect();
+
+    let mut functions: Vec<FunctionComplexity> = results.into_iter().flatten().collect();
+    functions.sort_by(|a, b| b.score.cmp(&a.score));
+    Ok(functions)
+}
+
+pub async fn run(
+    path: Option<PathBuf>,
+    sort: bool,
+    metric: String,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let metric: ComplexityMetric = metric.parse()?;
+    let mut functions = analyze_complexity(path, metric).await?;
+    if !sort {
+        functions.sort_by(|a, b| {
+            (&a.location.file_path, a.location.line).cmp(&(&b.location.file_path, b.location.line))
+        });
+    }
+
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&functions)?);
+        }
+        crate::OutputFormat::Sarif => {
+            let results: Vec<_> = functions.iter().filter_map(FunctionComplexity::to_sarif).collect();
+            let log = sarif_log(&[metric.sarif_rule()], &results, Path::new("."));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+        _ => {
+            if functions.is_empty() {
+                println!("No functions found");
+                return Ok(());
+            }
+
+            let metric_name = match metric {
+                ComplexityMetric::Cyclomatic => "Cyclomatic",
+                ComplexityMetric::Cognitive => "Cognitive",
+            };
+            println!("{} complexity of {} functions:", metric_name, functions.len());
+            for function in &functions {
+                println!(
+                    "  {:>4}  {} at {}:{}",
+                    function.score,
+                    function.name,
+                    function.location.file_path.display(),
+                    function.location.line
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_source_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(true)
+        .build();
+
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_file() {
+            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                if Language::from_extension(ext).tree_sitter_language().is_some() {
+                    files.push(path.to_path_buf());
+                }
+            }
+        }
+    }
+
+    Ok(files)
+}
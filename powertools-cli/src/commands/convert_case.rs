@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use crate::indexers::ScipQuery;
+use crate::refactor::{format_file_changes, CaseConverter, ConvertCaseOptions, DeclarationKind, TransactionMode};
+
+pub async fn run(
+    path: PathBuf,
+    kind: String,
+    from: String,
+    to: String,
+    project_root: Option<PathBuf>,
+    preview: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let kind: DeclarationKind = kind.parse()?;
+
+    // Locals are resolved with tree-sitter; functions and types need the index for cross-file references
+    let scip_query = if kind == DeclarationKind::Local {
+        None
+    } else {
+        Some(
+            ScipQuery::from_project(project_root)
+                .context("Failed to load SCIP index. Run 'powertools index' first.")?,
+        )
+    };
+    let converter = CaseConverter::new(scip_query.as_ref());
+
+    let options = ConvertCaseOptions {
+        path,
+        kind,
+        from: from.parse()?,
+        to: to.parse()?,
+        mode: if preview {
+            TransactionMode::DryRun
+        } else {
+            TransactionMode::Execute
+        },
+    };
+
+    if preview {
+        // Preview mode - show what would change
+        let summary = converter.preview(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+            _ => {
+                println!("{}", summary.format_summary());
+                println!("\n💡 Run without --preview to apply changes");
+            }
+        }
+    } else {
+        // Apply mode - make the changes
+        let result = converter.convert(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            _ => {
+                println!("✓ Case conversion complete!");
+                println!("  Declarations renamed: {}", result.renamed.len());
+                println!("  Files modified: {}", result.transaction_result.files_modified.len());
+                for rename in &result.renamed {
+                    println!(
+                        "  {}:{} {} → {} ({} references)",
+                        rename.file_path.display(),
+                        rename.line,
+                        rename.old_name,
+                        rename.new_name,
+                        rename.references
+                    );
+                }
+
+                if !result.transaction_result.files.is_empty() {
+                    println!("\n🧾 Changed:");
+                    print!("{}", format_file_changes(&result.transaction_result.files));
+                }
+
+                if !result.skipped.is_empty() {
+                    println!("\n⏭️  Skipped:");
+                    for skipped in &result.skipped {
+                        println!("  {}", skipped.format());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::refactor::{format_file_changes, ConvertRequireOptions, RequireConverter, TransactionMode};
+
+pub async fn run(
+    path: PathBuf,
+    project_root: Option<PathBuf>,
+    preview: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let converter = RequireConverter::new(project_root);
+
+    let options = ConvertRequireOptions {
+        path,
+        mode: if preview {
+            TransactionMode::DryRun
+        } else {
+            TransactionMode::Execute
+        },
+    };
+
+    if preview {
+        // Preview mode - show what would change
+        let summary = converter.preview(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+            crate::OutputFormat::Markdown => {
+                print!("{}", summary.format_markdown("Convert require preview"));
+            }
+            _ => {
+                println!("{}", summary.format_summary());
+                println!("\n💡 Run without --preview to apply changes");
+            }
+        }
+    } else {
+        // Apply mode - make the changes
+        let result = converter.convert(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            _ => {
+                println!("✓ Requires converted!");
+                println!("  Files updated: {}", result.files_updated);
+                println!("  Requires converted to imports: {}", result.requires_converted);
+
+                if !result.transaction_result.files.is_empty() {
+                    println!("\n🧾 Changed:");
+                    print!("{}", format_file_changes(&result.transaction_result.files));
+                }
+
+                if !result.warnings.is_empty() {
+                    println!("\n⚠️  Follow-up needed:");
+                    for warning in &result.warnings {
+                        println!("  {}", warning);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
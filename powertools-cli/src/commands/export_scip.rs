@@ -0,0 +1,43 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::indexers::scip_export::{merge_indexes, write_index};
+use crate::indexers::ScipQuery;
+
+pub async fn run(project_root: Option<PathBuf>, output: Option<PathBuf>, format: &crate::OutputFormat) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let output = output.unwrap_or_else(|| project_root.join("index.scip"));
+
+    let query = ScipQuery::from_project(project_root.clone())?;
+    let merged = merge_indexes(query.indexes(), &project_root);
+    write_index(&merged.index, &output)?;
+
+    match format {
+        crate::OutputFormat::Json => {
+            let summary = serde_json::json!({
+                "output": output,
+                "indexes_merged": query.indexes().len(),
+                "documents": merged.index.documents.len(),
+                "external_symbols": merged.index.external_symbols.len(),
+                "duplicate_documents": merged.duplicate_documents,
+            });
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        _ => {
+            println!(
+                "✓ Wrote {} documents from {} indexes to {}",
+                merged.index.documents.len(),
+                query.indexes().len(),
+                output.display()
+            );
+            if !merged.duplicate_documents.is_empty() {
+                println!("⚠️  Indexed by more than one language, kept the first copy:");
+                for path in &merged.duplicate_documents {
+                    println!("  {}", path);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
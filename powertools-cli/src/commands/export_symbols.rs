@@ -0,0 +1,45 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+
+use crate::core::{Language, SymbolKind};
+use crate::indexers::symbol_export::{export_symbols, write_ndjson, ExportFilter};
+use crate::indexers::ScipQuery;
+
+pub async fn run(
+    project_root: Option<PathBuf>,
+    output: Option<PathBuf>,
+    language: Option<String>,
+    kind: Option<String>,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let filter = ExportFilter {
+        language: language
+            .map(|l| parse_name::<Language>(&l).context("Unknown language (e.g. rust, typescript, python)"))
+            .transpose()?,
+        kind: kind
+            .map(|k| parse_name::<SymbolKind>(&k).context("Unknown kind (e.g. function, method, class, field)"))
+            .transpose()?,
+    };
+
+    let query = ScipQuery::from_project(project_root.clone())?;
+    let symbols = export_symbols(&query, &project_root, &filter);
+
+    match output {
+        Some(path) => {
+            let file = File::create(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            write_ndjson(&symbols, &mut BufWriter::new(file))?;
+            eprintln!("✓ Exported {} symbols to {}", symbols.len(), path.display());
+        }
+        None => write_ndjson(&symbols, &mut io::stdout().lock())?,
+    }
+
+    Ok(())
+}
+
+/// Parse a lowercase/snake_case name the same way it appears in the exported JSON
+fn parse_name<T: serde::de::DeserializeOwned>(name: &str) -> Result<T> {
+    Ok(serde_json::from_value(serde_json::Value::String(name.to_lowercase()))?)
+}
@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::analyzers::{DeadCodeFinder, DeadSymbol, DEAD_CODE_CAVEAT, DEAD_CODE_RULE};
+use crate::core::sarif::sarif_log;
+use crate::indexers::ScipQuery;
+
+/// Find dead code and return it (for MCP/API use)
+pub fn find_dead_code(project_root: PathBuf, include_public: bool) -> Result<Vec<DeadSymbol>> {
+    let scip_query = ScipQuery::from_project(project_root)
+        .context("Failed to load SCIP index. Run 'powertools index' first.")?;
+    DeadCodeFinder::new(include_public)?.find(&scip_query)
+}
+
+pub async fn run(
+    project_root: Option<PathBuf>,
+    include_public: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let dead = find_dead_code(project_root.clone(), include_public)?;
+
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&dead)?);
+        }
+        crate::OutputFormat::Sarif => {
+            let results: Vec<_> = dead.iter().map(DeadSymbol::to_sarif).collect();
+            let log = sarif_log(&[DEAD_CODE_RULE], &results, &project_root);
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+        _ => {
+            if dead.is_empty() {
+                println!("No dead code candidates found");
+                return Ok(());
+            }
+
+            let mut by_file: BTreeMap<&PathBuf, Vec<&DeadSymbol>> = BTreeMap::new();
+            for symbol in &dead {
+                by_file.entry(&symbol.location.file_path).or_default().push(symbol);
+            }
+
+            println!("Found {} dead code candidates in {} files:", dead.len(), by_file.len());
+            for (file, symbols) in by_file {
+                let file = file.strip_prefix(&project_root).unwrap_or(file);
+                println!("\n{}", file.display());
+                for symbol in symbols {
+                    println!(
+                        "  {}:{}  {:?} {}{}",
+                        symbol.location.line,
+                        symbol.location.column,
+                        symbol.kind,
+                        symbol.name,
+                        if symbol.is_public { " (public)" } else { "" }
+                    );
+                }
+            }
+            println!("\n⚠️  {}", DEAD_CODE_CAVEAT);
+        }
+    }
+
+    Ok(())
+}
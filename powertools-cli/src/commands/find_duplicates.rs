@@ -0,0 +1,59 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::analyzers::{DuplicateFinder, DuplicateGroup, DuplicateOptions, DUPLICATE_RULE};
+use crate::core::sarif::sarif_log;
+
+/// Find duplicate code and return it (for MCP/API use)
+pub fn find_duplicates(path: Option<PathBuf>, options: DuplicateOptions) -> Result<Vec<DuplicateGroup>> {
+    let search_path = path.unwrap_or_else(|| PathBuf::from("."));
+    DuplicateFinder::new(options).find(&search_path)
+}
+
+pub async fn run(
+    path: Option<PathBuf>,
+    min_lines: usize,
+    min_tokens: usize,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let groups = find_duplicates(path, DuplicateOptions { min_lines, min_tokens })?;
+
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&groups)?);
+        }
+        crate::OutputFormat::Sarif => {
+            let results: Vec<_> = groups.iter().filter_map(DuplicateGroup::to_sarif).collect();
+            let log = sarif_log(&[DUPLICATE_RULE], &results, Path::new("."));
+            println!("{}", serde_json::to_string_pretty(&log)?);
+        }
+        _ => {
+            if groups.is_empty() {
+                println!("No duplicate code found");
+                return Ok(());
+            }
+
+            println!("Found {} duplicate groups:", groups.len());
+            for (i, group) in groups.iter().enumerate() {
+                println!(
+                    "\n#{} ~{:.0}% identical, {} lines, {} tokens, {} copies",
+                    i + 1,
+                    group.similarity * 100.0,
+                    group.lines,
+                    group.tokens,
+                    group.instances.len()
+                );
+                for instance in &group.instances {
+                    println!(
+                        "  {}:{}-{}",
+                        instance.file_path.display(),
+                        instance.start_line,
+                        instance.end_line
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -2,13 +2,14 @@ use anyhow::Result;
 use std::path::PathBuf;
 use ignore::WalkBuilder;
 use rayon::prelude::*;
-use crate::core::{output::OutputWriter, Language, Symbol};
+use crate::core::{output::OutputWriter, ordering::{sort_symbols, SortOrder}, pagination::Page, Language, Symbol};
 use crate::analyzers::FunctionFinder;
 
 /// Find functions and return them (for MCP/API use)
 pub async fn find_functions(
     path: Option<PathBuf>,
     include_private: bool,
+    sort: SortOrder,
 ) -> Result<Vec<Symbol>> {
     let search_path = path.unwrap_or_else(|| PathBuf::from("."));
     let mut all_functions = Vec::new();
@@ -36,22 +37,36 @@ pub async fn find_functions(
         }
     }
 
+    sort_symbols(&mut all_functions, sort);
     Ok(all_functions)
 }
 
 pub async fn run(
     path: Option<PathBuf>,
     include_private: bool,
+    sort: SortOrder,
+    limit: usize,
+    offset: usize,
     format: &crate::OutputFormat,
 ) -> Result<()> {
-    let all_functions = find_functions(path, include_private).await?;
+    let all_functions = find_functions(path, include_private, sort).await?;
     let output = OutputWriter::new(format);
+    let page = Page::new(all_functions, limit, offset);
 
-    if all_functions.is_empty() {
-        println!("No functions found");
-    } else {
-        println!("Found {} functions:", all_functions.len());
-        output.write_symbols(&all_functions)?;
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&page.to_json("functions"))?);
+        }
+        _ if page.count == 0 => {
+            println!("No functions found");
+        }
+        _ => {
+            println!("Found {} functions:", page.count);
+            output.write_symbols(&page.items)?;
+            if let Some(footer) = page.footer() {
+                println!("\n{}", footer);
+            }
+        }
     }
 
     Ok(())
@@ -76,5 +91,49 @@ fn collect_source_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
         }
     }
 
+    // Walk order varies by platform; keep "none" reproducible too
+    files.sort();
     Ok(files)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_functions_sorted_across_files() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/zeta.rs"), "pub fn build() {}\n\npub fn alpha() {}\n").unwrap();
+        fs::write(dir.path().join("src/alpha.rs"), "pub fn zoom() {}\npub fn apply() {}\n").unwrap();
+        fs::write(dir.path().join("main.py"), "def main():\n    pass\n").unwrap();
+
+        let names = |functions: Vec<Symbol>| -> Vec<String> {
+            functions
+                .iter()
+                .map(|f| {
+                    let file = f.location.file_path.strip_prefix(dir.path()).unwrap();
+                    format!("{}:{}:{}", file.display(), f.location.line, f.name)
+                })
+                .collect()
+        };
+
+        let by_location = find_functions(Some(dir.path().to_path_buf()), true, SortOrder::Location).await.unwrap();
+        assert_eq!(
+            names(by_location),
+            vec!["main.py:1:main", "src/alpha.rs:1:zoom", "src/alpha.rs:2:apply", "src/zeta.rs:1:build", "src/zeta.rs:3:alpha"]
+        );
+
+        let by_name = find_functions(Some(dir.path().to_path_buf()), true, SortOrder::Name).await.unwrap();
+        assert_eq!(
+            names(by_name),
+            vec!["src/zeta.rs:3:alpha", "src/alpha.rs:2:apply", "src/zeta.rs:1:build", "main.py:1:main", "src/alpha.rs:1:zoom"]
+        );
+
+        // Repeated runs give the same order, even without sorting matches
+        let first = names(find_functions(Some(dir.path().to_path_buf()), true, SortOrder::None).await.unwrap());
+        let second = names(find_functions(Some(dir.path().to_path_buf()), true, SortOrder::None).await.unwrap());
+        assert_eq!(first, second);
+    }
+}
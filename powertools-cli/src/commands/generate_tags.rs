@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use crate::core::SymbolKind;
+use crate::indexers::symbol_export::{export_symbols, ExportFilter};
+use crate::indexers::tags::{tag_lines, write_tags};
+use crate::indexers::ScipQuery;
+
+pub async fn run(
+    project_root: Option<PathBuf>,
+    output: Option<PathBuf>,
+    kinds: Vec<String>,
+    absolute: bool,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let output = output.unwrap_or_else(|| project_root.join("tags"));
+    let kinds = kinds
+        .iter()
+        .map(|k| {
+            serde_json::from_value::<SymbolKind>(serde_json::Value::String(k.to_lowercase()))
+                .with_context(|| format!("Unknown kind '{}' (e.g. function, method, class, field)", k))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let query = ScipQuery::from_project(project_root.clone())?;
+    let symbols: Vec<_> = export_symbols(&query, &project_root, &ExportFilter::default())
+        .into_iter()
+        .filter(|symbol| kinds.is_empty() || kinds.contains(&symbol.kind))
+        .collect();
+    let lines = tag_lines(&symbols, &project_root, absolute);
+
+    let file = File::create(&output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    write_tags(&lines, &mut BufWriter::new(file))?;
+    eprintln!("✓ Wrote {} tags to {}", lines.len(), output.display());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::analyzers::{Implementation, ImplementationFinder};
+use crate::indexers::ScipQuery;
+
+/// Find implementations and return them (for MCP/API use)
+///
+/// Uses SCIP implementation relationships when the project is indexed and has any for
+/// `name`; otherwise scans impl blocks and class declarations.
+pub fn find_implementations(name: String, project_root: PathBuf) -> Result<Vec<Implementation>> {
+    let mut finder = ImplementationFinder::new()?;
+    if let Ok(scip_query) = ScipQuery::from_project(project_root.clone()) {
+        let implementations = finder.find_in_index(&scip_query, &name)?;
+        if !implementations.is_empty() {
+            return Ok(implementations);
+        }
+    }
+    finder.find_by_name(&project_root, &name)
+}
+
+pub async fn run(
+    name: String,
+    project_root: PathBuf,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let implementations = find_implementations(name.clone(), project_root.clone())?;
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&implementations)?);
+        }
+        _ if implementations.is_empty() => {
+            println!("No implementations found for: {}", name);
+        }
+        _ => {
+            println!("Found {} implementations of {}:", implementations.len(), name);
+            for implementation in &implementations {
+                let location = &implementation.location;
+                let file = location.file_path.strip_prefix(&project_root).unwrap_or(&location.file_path);
+                let mut line = format!(
+                    "  {}  {}:{}:{}",
+                    implementation.type_name,
+                    file.display(),
+                    location.line,
+                    location.column
+                );
+                if implementation.is_blanket {
+                    line.push_str("  [blanket]");
+                }
+                if let Some(constraints) = &implementation.constraints {
+                    line.push_str(&format!("  ({})", constraints));
+                }
+                println!("{}", line);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -2,17 +2,52 @@ use anyhow::Result;
 use std::path::PathBuf;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::time::Instant;
-use crate::indexers::ScipIndexer;
+use crate::indexers::{IndexFilter, MissingIndexer, ScipIndexer};
+
+/// Some languages failed to index; `missing` lists those whose indexer isn't installed
+#[derive(Debug)]
+pub struct IndexFailed {
+    pub languages: Vec<String>,
+    pub missing: Vec<MissingIndexer>,
+}
+
+impl std::fmt::Display for IndexFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Indexing failed for: {}", self.languages.join(", "))?;
+        for missing in &self.missing {
+            write!(f, "\n  {}", missing)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IndexFailed {}
 
 pub async fn run(
     path: Option<PathBuf>,
     _force: bool,
     languages: Vec<String>,
     auto_install: bool,
+    jobs: usize,
+    include: Vec<String>,
+    exclude: Vec<String>,
     _format: &crate::OutputFormat,
 ) -> Result<()> {
     let index_path = path.unwrap_or_else(|| PathBuf::from("."));
 
+    // New globs replace the saved ones; without any, the last run's filters apply again
+    let filter = if include.is_empty() && exclude.is_empty() {
+        let saved = IndexFilter::load(&index_path)?;
+        if !saved.is_empty() {
+            println!("Using include/exclude filters from {}", crate::indexers::index_filter::FILTER_FILE);
+        }
+        saved
+    } else {
+        let filter = IndexFilter::new(&include, &exclude)?;
+        filter.save(&index_path)?;
+        filter
+    };
+
     println!("Building SCIP indexes for: {}", index_path.display());
 
     let spinner = ProgressBar::new_spinner();
@@ -29,25 +64,50 @@ pub async fn run(
     // Create SCIP indexer and generate indexes for all detected languages
     let mut indexer = ScipIndexer::new(index_path.clone());
     indexer.set_auto_install(auto_install);
+    indexer.set_jobs(jobs);
+    indexer.set_filter(filter);
 
-    match indexer.generate_indexes(languages) {
-        Ok(output_paths) => {
-            spinner.finish_with_message("Indexing complete!");
-            let elapsed = start.elapsed();
-            println!("✓ Indexes built in {:?}", elapsed);
-            for path in output_paths {
-                println!("✓ Index saved to: {}", path.display());
-            }
-            Ok(())
-        }
+    let indexes = match indexer.generate_indexes(languages) {
+        Ok(indexes) => indexes,
         Err(e) => {
             spinner.finish_with_message("Indexing failed!");
             eprintln!("Error: {}", e);
-            eprintln!("\nMake sure the appropriate indexer is installed:");
-            eprintln!("  TypeScript/JavaScript: npm install -g @sourcegraph/scip-typescript");
-            eprintln!("  Python: npm install -g @sourcegraph/scip-python");
-            eprintln!("  Rust: rustup component add rust-analyzer");
-            Err(e)
+            return Err(e);
+        }
+    };
+
+    let failed: Vec<_> = indexes.iter().filter(|index| index.result.is_err()).collect();
+    if failed.is_empty() {
+        spinner.finish_with_message("Indexing complete!");
+    } else {
+        spinner.finish_with_message("Indexing finished with errors");
+    }
+    println!("✓ Indexes built in {:?}", start.elapsed());
+
+    let mut missing = Vec::new();
+    for index in &indexes {
+        match &index.result {
+            Ok(path) => println!("✓ {:?} index saved to: {}", index.project_type, path.display()),
+            Err(e) => match e.downcast_ref::<MissingIndexer>() {
+                Some(tool) => missing.push(tool.clone()),
+                None => eprintln!("✗ {:?} indexing failed: {:#}", index.project_type, e),
+            },
         }
     }
-}
\ No newline at end of file
+
+    if failed.is_empty() {
+        return Ok(());
+    }
+
+    if !missing.is_empty() {
+        eprintln!("\n⚠️  Missing indexers (re-run with --auto-install to install them):");
+        for tool in &missing {
+            eprintln!("  ✗ {:?} needs {}: {}", tool.project_type, tool.tool, tool.install);
+        }
+    }
+    Err(IndexFailed {
+        languages: failed.iter().map(|index| format!("{:?}", index.project_type)).collect(),
+        missing,
+    }
+    .into())
+}
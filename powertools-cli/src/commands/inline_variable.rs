@@ -4,18 +4,50 @@ use std::path::PathBuf;
 use crate::core::Language;
 use crate::indexers::{ScipQuery, SwiftLsp};
 use crate::indexers::lsp_query::apply_workspace_edit;
-use crate::refactor::{InlineOptions, TransactionMode, VariableInliner};
+use crate::refactor::{char_column_from_visual, format_file_changes, InlineOptions, TransactionMode, VariableInliner};
 
+/// Parse a `LINE:COL` position, both 1-indexed
+pub fn parse_position(position: &str) -> std::result::Result<(usize, usize), String> {
+    let (line, column) = position
+        .split_once(':')
+        .ok_or_else(|| format!("expected LINE:COL, got '{}'", position))?;
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("'{}' is not a 1-indexed line or column", n))
+    };
+    Ok((parse(line)?, parse(column)?))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     file_path: PathBuf,
     line: usize,
     column: usize,
+    tab_width: Option<usize>,
+    at_usage: Option<(usize, usize)>,
     project_root: Option<PathBuf>,
     preview: bool,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
 
+    // Editor columns count tabs and wide characters by their width; the inliner counts characters
+    let (column, at_usage) = match tab_width {
+        Some(tab_width) => {
+            let content = std::fs::read_to_string(&file_path)
+                .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+            let at_usage = match at_usage {
+                Some((line, column)) => Some((line, char_column_from_visual(&content, line, column, tab_width)?)),
+                None => None,
+            };
+            (char_column_from_visual(&content, line, column, tab_width)?, at_usage)
+        }
+        None => (column, at_usage),
+    };
+
     // Detect language from file extension
     let language = file_path
         .extension()
@@ -26,12 +58,15 @@ pub async fn run(
     // Route to LSP or SCIP based on language
     match language {
         Language::Swift => {
+            if at_usage.is_some() {
+                anyhow::bail!("--at-usage is not supported for Swift files");
+            }
             // Use LSP-based inline via code actions for Swift
             run_lsp_inline(file_path, line, column, project_root, preview, format).await
         }
         _ => {
             // Use SCIP-based inline for other languages
-            run_scip_inline(file_path, line, column, project_root, preview, format).await
+            run_scip_inline(file_path, line, column, at_usage, project_root, preview, format).await
         }
     }
 }
@@ -186,6 +221,7 @@ async fn run_scip_inline(
     file_path: PathBuf,
     line: usize,
     column: usize,
+    at_usage: Option<(usize, usize)>,
     project_root: PathBuf,
     preview: bool,
     format: &crate::OutputFormat,
@@ -206,6 +242,7 @@ async fn run_scip_inline(
         } else {
             TransactionMode::Execute
         },
+        only_at: at_usage,
     };
 
     if preview {
@@ -216,6 +253,9 @@ async fn run_scip_inline(
             crate::OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&summary)?);
             }
+            crate::OutputFormat::Markdown => {
+                print!("{}", summary.format_markdown("Inline variable preview"));
+            }
             _ => {
                 println!("{}", summary.format_summary());
                 println!("\n💡 Run without --preview to apply changes");
@@ -236,11 +276,9 @@ async fn run_scip_inline(
                 println!("  Usages replaced: {}", result.usages_replaced);
                 println!("  Files modified: {}", result.files_modified);
 
-                if !result.transaction_result.files_modified.is_empty() {
-                    println!("\nModified files:");
-                    for file in &result.transaction_result.files_modified {
-                        println!("  • {}", file.display());
-                    }
+                if !result.transaction_result.files.is_empty() {
+                    println!("\n🧾 Changed:");
+                    print!("{}", format_file_changes(&result.transaction_result.files));
                 }
 
                 if !result.transaction_result.errors.is_empty() {
@@ -255,3 +293,16 @@ async fn run_scip_inline(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_position() {
+        assert_eq!(parse_position("12:5"), Ok((12, 5)));
+        assert!(parse_position("12").is_err());
+        assert!(parse_position("0:5").is_err());
+        assert!(parse_position("12:x").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::refactor::{format_file_changes, ImportMerger, MergeImportsOptions, TransactionMode};
+
+pub async fn run(
+    file: PathBuf,
+    project_root: Option<PathBuf>,
+    preview: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let merger = ImportMerger::new(project_root);
+
+    let options = MergeImportsOptions {
+        file,
+        mode: if preview {
+            TransactionMode::DryRun
+        } else {
+            TransactionMode::Execute
+        },
+    };
+
+    if preview {
+        // Preview mode - show what would change
+        let summary = merger.preview(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+            crate::OutputFormat::Markdown => {
+                print!("{}", summary.format_markdown("Merge imports preview"));
+            }
+            _ => {
+                println!("{}", summary.format_summary());
+                println!("\n💡 Run without --preview to apply changes");
+            }
+        }
+    } else {
+        // Apply mode - make the changes
+        let result = merger.merge(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            _ if result.modules_merged == 0 => {
+                println!("✓ No imports to merge in {}", result.file_path.display());
+            }
+            _ => {
+                println!("✓ Imports merged!");
+                println!("  Modules: {}", result.modules_merged);
+                println!("  Statements removed: {}", result.statements_removed);
+
+                if !result.transaction_result.files.is_empty() {
+                    println!("\n🧾 Changed:");
+                    print!("{}", format_file_changes(&result.transaction_result.files));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
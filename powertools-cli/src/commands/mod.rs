@@ -2,10 +2,28 @@ pub mod index;
 pub mod search_ast;
 pub mod definition;
 pub mod references;
+pub mod r#type;
 pub mod functions;
 pub mod classes;
 pub mod stats;
 pub mod watch;
 pub mod batch_replace;
 pub mod rename_symbol;
-pub mod inline_variable;
\ No newline at end of file
+pub mod inline_variable;
+pub mod rename_file;
+pub mod convert_case;
+pub mod check_imports;
+pub mod convert_imports;
+pub mod convert_require;
+pub mod merge_imports;
+pub mod find_dead_code;
+pub mod find_duplicates;
+pub mod complexity;
+pub mod export_symbols;
+pub mod export_scip;
+pub mod generate_tags;
+pub mod call_graph;
+pub mod callers;
+pub mod implementations;
+pub mod completions;
+pub mod clear_cache;pub mod undo;
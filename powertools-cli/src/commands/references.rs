@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::path::PathBuf;
-use crate::core::{output::OutputWriter, Reference, Language, location::parse_location};
+use crate::core::{output::OutputWriter, pagination::Page, Reference, Language, location::parse_location};
 use crate::indexers::{ScipQuery, SwiftLsp, UnifiedQuery};
 
 /// Find references and return them (for MCP/API use)
@@ -45,19 +45,32 @@ pub async fn run(
     symbol: String,
     include_declarations: bool,
     project_root: PathBuf,
+    limit: usize,
+    offset: usize,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     let output = OutputWriter::new(format);
 
-    println!("Finding references for: {}", symbol);
-
     let references = find_references(symbol.clone(), project_root, include_declarations).await?;
+    let page = Page::new(references, limit, offset);
 
-    if references.is_empty() {
-        output.write_error(&format!("No references found for symbol: {}", symbol))?;
-    } else {
-        println!("Found {} references", references.len());
-        output.write_references(&references)?;
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&page.to_json("references"))?);
+        }
+        _ => {
+            println!("Finding references for: {}", symbol);
+
+            if page.count == 0 {
+                output.write_error(&format!("No references found for symbol: {}", symbol))?;
+            } else {
+                println!("Found {} references", page.count);
+                output.write_references(&page.items)?;
+                if let Some(footer) = page.footer() {
+                    println!("\n{}", footer);
+                }
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::refactor::{format_file_changes, FileMover, MoveFileOptions, TransactionMode};
+
+pub async fn run(
+    old_path: PathBuf,
+    new_path: PathBuf,
+    project_root: Option<PathBuf>,
+    preview: bool,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
+    let mover = FileMover::new(project_root);
+
+    let options = MoveFileOptions {
+        old_path,
+        new_path,
+        mode: if preview {
+            TransactionMode::DryRun
+        } else {
+            TransactionMode::Execute
+        },
+    };
+
+    if preview {
+        // Preview mode - show what would change
+        let summary = mover.preview(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&summary)?);
+            }
+            _ => {
+                println!("{}", summary.format_summary());
+                println!("\n💡 Run without --preview to apply changes");
+            }
+        }
+    } else {
+        // Apply mode - make the changes
+        let result = mover.move_file(options)?;
+
+        match format {
+            crate::OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            }
+            _ => {
+                println!("✓ File moved successfully!");
+                println!("  {} → {}", result.old_path.display(), result.new_path.display());
+                println!("  Importers updated: {}", result.importers_updated);
+                println!("  Import paths rewritten: {}", result.imports_updated);
+                println!("\n🧾 Changed:");
+                print!("{}", format_file_changes(&result.transaction_result.files));
+
+                if !result.warnings.is_empty() {
+                    println!("\n⚠️  Follow-up needed:");
+                    for warning in &result.warnings {
+                        println!("  {}", warning);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
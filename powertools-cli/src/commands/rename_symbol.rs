@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use crate::core::Language;
 use crate::indexers::{ScipQuery, SwiftLsp};
 use crate::indexers::lsp_query::apply_workspace_edit;
-use crate::refactor::{RenameOptions, SymbolRenamer, TransactionMode};
+use crate::refactor::{format_file_changes, RenameOptions, SymbolRenamer, TransactionMode};
 
 pub async fn run(
     file_path: PathBuf,
@@ -14,6 +14,7 @@ pub async fn run(
     project_root: Option<PathBuf>,
     preview: bool,
     update_imports: bool,
+    force: bool,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     let project_root = project_root.unwrap_or_else(|| PathBuf::from("."));
@@ -41,6 +42,7 @@ pub async fn run(
                 project_root,
                 preview,
                 update_imports,
+                force,
                 format,
             )
             .await
@@ -182,6 +184,7 @@ async fn run_scip_rename(
     project_root: PathBuf,
     preview: bool,
     update_imports: bool,
+    force: bool,
     format: &crate::OutputFormat,
 ) -> Result<()> {
     // Load the SCIP index
@@ -202,6 +205,7 @@ async fn run_scip_rename(
         } else {
             TransactionMode::Execute
         },
+        force,
     };
 
     if preview {
@@ -212,9 +216,16 @@ async fn run_scip_rename(
             crate::OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&summary)?);
             }
+            crate::OutputFormat::Markdown => {
+                print!("{}", summary.format_markdown(&format!("Rename preview: `{}`", new_name)));
+            }
             _ => {
                 println!("{}", summary.format_summary());
-                println!("\n💡 Run without --preview to apply changes");
+                if summary.conflicts.is_empty() {
+                    println!("\n💡 Run without --preview to apply changes");
+                } else {
+                    println!("\n💡 Resolve the conflicts or run with --force to apply changes anyway");
+                }
             }
         }
     } else {
@@ -235,11 +246,9 @@ async fn run_scip_rename(
                     println!("  Imports updated: {}", result.imports_updated);
                 }
 
-                if !result.transaction_result.files_modified.is_empty() {
-                    println!("\nModified files:");
-                    for file in &result.transaction_result.files_modified {
-                        println!("  • {}", file.display());
-                    }
+                if !result.transaction_result.files.is_empty() {
+                    println!("\n🧾 Changed:");
+                    print!("{}", format_file_changes(&result.transaction_result.files));
                 }
 
                 if !result.transaction_result.errors.is_empty() {
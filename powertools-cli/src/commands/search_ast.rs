@@ -1,35 +1,63 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use std::path::PathBuf;
-use crate::core::{output::OutputWriter, SearchResult};
-use crate::analyzers::PatternMatcher;
+use std::time::Duration;
+use crate::core::{output::OutputWriter, ordering::{sort_search_results, SortOrder}};
+use crate::analyzers::{split_query_patterns, PatternMatcher, SearchOutcome};
 use indicatif::{ProgressBar, ProgressStyle};
 
+/// Gather the patterns to search for from inline patterns and an optional query file
+///
+/// Each top-level pattern in the query file counts as its own pattern.
+pub fn collect_patterns(patterns: Vec<String>, query_file: Option<&PathBuf>) -> Result<Vec<String>> {
+    let mut all: Vec<String> = patterns.into_iter().filter(|p| !p.trim().is_empty()).collect();
+    if let Some(query_file) = query_file {
+        let source = std::fs::read_to_string(query_file)
+            .with_context(|| format!("Failed to read query file: {}", query_file.display()))?;
+        all.extend(split_query_patterns(&source));
+    }
+    if all.is_empty() {
+        return Err(anyhow!("No query pattern given. Pass a pattern, --pattern, or --query-file"));
+    }
+    Ok(all)
+}
+
 /// Search for patterns and return results (for MCP/API use)
+///
+/// All patterns are evaluated in one parse per file; with several patterns,
+/// results carry the index of the pattern that matched. With `timeout_ms`, the
+/// search stops once it expires and returns the matches found so far.
 pub async fn search_patterns(
-    pattern: String,
+    patterns: Vec<String>,
     path: Option<PathBuf>,
     extensions: Vec<String>,
     max_results: usize,
-) -> Result<Vec<SearchResult>> {
+    sort: SortOrder,
+    timeout_ms: Option<u64>,
+) -> Result<SearchOutcome> {
     let search_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let mut matcher = PatternMatcher::new()?;
+    let mut matcher = PatternMatcher::new()?.with_timeout(timeout_ms.map(Duration::from_millis));
 
-    let results = if search_path.is_file() {
-        matcher.search_file(&search_path, &pattern, max_results)?
+    let mut outcome = if search_path.is_file() {
+        matcher.search_file(&search_path, &patterns, max_results)?
     } else {
-        matcher.search_directory(&search_path, &pattern, extensions, max_results)?
+        matcher.search_directory(&search_path, &patterns, extensions, max_results)?
     };
 
-    Ok(results)
+    sort_search_results(&mut outcome.results, sort);
+    Ok(outcome)
 }
 
 pub async fn run(
-    pattern: String,
+    patterns: Vec<String>,
+    query_file: Option<PathBuf>,
     path: Option<PathBuf>,
     extensions: Vec<String>,
     max_results: usize,
+    sort: SortOrder,
+    timeout_ms: Option<u64>,
     format: &crate::OutputFormat,
 ) -> Result<()> {
+    let patterns = collect_patterns(patterns, query_file.as_ref())?;
     let search_path = path.clone().unwrap_or_else(|| PathBuf::from("."));
     let output = OutputWriter::new(format);
 
@@ -49,13 +77,38 @@ pub async fn run(
         spinner.set_message(format!("Searching in directory: {}", search_path.display()));
     }
 
-    let results = search_patterns(pattern.clone(), path, extensions, max_results).await?;
+    let outcome = search_patterns(patterns.clone(), path, extensions, max_results, sort, timeout_ms).await?;
 
     spinner.finish_and_clear();
 
+    // JSON carries the partial-result flags alongside the matches
+    if matches!(format, crate::OutputFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
+
+    // On stderr, so SARIF output stays parseable
+    if outcome.timed_out {
+        eprintln!(
+            "Warning: search timed out after {}ms; results are partial",
+            timeout_ms.unwrap_or_default()
+        );
+    }
+    for file in &outcome.truncated_files {
+        eprintln!("Warning: stopped searching {} after its time limit; matches may be missing", file.display());
+    }
+
+    let results = outcome.results;
+
     if results.is_empty() {
-        println!("No matches found for pattern: {}", pattern);
+        println!("No matches found for pattern: {}", patterns.join(" | "));
     } else {
+        if patterns.len() > 1 && !matches!(format, crate::OutputFormat::Sarif) {
+            println!("Patterns:");
+            for (index, pattern) in patterns.iter().enumerate() {
+                println!("  {}: {}", index, pattern);
+            }
+        }
         println!("Found {} matches:", results.len());
         output.write_search_results(&results)?;
     }
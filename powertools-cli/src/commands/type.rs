@@ -0,0 +1,114 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use crate::core::{location::{byte_offset, parse_location}, Language, Location, TypeInfo};
+use crate::indexers::{SwiftLsp, UnifiedQuery};
+use crate::refactor::parser_for_file;
+
+/// Longest node text reported by the tree-sitter fallback
+const MAX_NODE_TEXT: usize = 200;
+
+/// Get type information for a location and return it (for MCP/API use)
+///
+/// Uses LSP hover for Swift and the SCIP index for everything else. When neither
+/// can answer, the syntax node kind and text at the position are returned instead.
+pub async fn get_type(location: String, project_root: PathBuf) -> Result<TypeInfo> {
+    let loc = parse_location(&location)?;
+
+    let language = loc.file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(Language::from_extension)
+        .unwrap_or(Language::Unknown);
+
+    let query = match language {
+        Language::Swift => SwiftLsp::create_query(project_root.clone()),
+        _ => UnifiedQuery::scip_only(project_root.clone()),
+    };
+    if let Ok(mut query) = query {
+        if let Ok(Some(info)) = query.hover(&loc.file_path, loc.line, loc.column) {
+            return Ok(info);
+        }
+    }
+
+    syntax_type_info(&loc, &project_root)
+}
+
+/// Tree-sitter node kind and text at a location, for when no backend knows the type
+fn syntax_type_info(loc: &Location, project_root: &Path) -> Result<TypeInfo> {
+    let file_path = if loc.file_path.is_relative() && !loc.file_path.exists() {
+        project_root.join(&loc.file_path)
+    } else {
+        loc.file_path.clone()
+    };
+
+    let content = std::fs::read_to_string(&file_path)
+        .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+    let mut parser = parser_for_file(&file_path)?
+        .ok_or_else(|| anyhow!("No type information available for {}", file_path.display()))?;
+    let tree = parser
+        .parse(&content, None)
+        .ok_or_else(|| anyhow!("Failed to parse {}", file_path.display()))?;
+
+    let byte = byte_offset(&content, loc.line, loc.column)
+        .ok_or_else(|| anyhow!("Position {}:{} is outside {}", loc.line, loc.column, file_path.display()))?;
+    let node = tree
+        .root_node()
+        .named_descendant_for_byte_range(byte, byte)
+        .ok_or_else(|| anyhow!("No syntax node at {}:{}", loc.line, loc.column))?;
+
+    let mut text = content[node.byte_range()].to_string();
+    if text.chars().count() > MAX_NODE_TEXT {
+        text = text.chars().take(MAX_NODE_TEXT).collect::<String>() + "…";
+    }
+
+    Ok(TypeInfo {
+        type_name: Some(node.kind().to_string()),
+        documentation: Some(text),
+        location: Location {
+            file_path,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            end_line: Some(node.end_position().row + 1),
+            end_column: Some(node.end_position().column + 1),
+        },
+    })
+}
+
+pub async fn run(
+    location: String,
+    project_root: PathBuf,
+    format: &crate::OutputFormat,
+) -> Result<()> {
+    let info = get_type(location, project_root).await?;
+
+    match format {
+        crate::OutputFormat::Json | crate::OutputFormat::Sarif => {
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        }
+        crate::OutputFormat::Markdown => {
+            if let Some(type_name) = &info.type_name {
+                println!("```\n{}\n```", type_name);
+            }
+            if let Some(documentation) = &info.documentation {
+                println!("\n{}", documentation);
+            }
+        }
+        crate::OutputFormat::Text => {
+            println!(
+                "{}:{}:{}",
+                info.location.file_path.display(),
+                info.location.line,
+                info.location.column
+            );
+            match &info.type_name {
+                Some(type_name) => println!("Type: {}", type_name),
+                None => println!("Type: unknown"),
+            }
+            if let Some(documentation) = &info.documentation {
+                println!("\n{}", documentation);
+            }
+        }
+    }
+
+    Ok(())
+}
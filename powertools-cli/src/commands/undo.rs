@@ -0,0 +1,23 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::refactor::undo_latest;
+
+pub async fn run(project_root: PathBuf, format: &crate::OutputFormat) -> Result<()> {
+    let result = undo_latest(&project_root)?;
+
+    match format {
+        crate::OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        }
+        _ => {
+            println!("✓ Undid: {}", result.description);
+            println!("  Files restored: {}", result.files_restored.len());
+            for file in &result.files_restored {
+                println!("  • {}", file.display());
+            }
+        }
+    }
+
+    Ok(())
+}
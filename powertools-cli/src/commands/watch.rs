@@ -1,31 +1,73 @@
 use anyhow::Result;
 use std::path::PathBuf;
 use std::time::Duration;
-use tracing::info;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
 
 use crate::watcher::FileWatcher;
 
 pub async fn run(
-    path: Option<PathBuf>,
+    paths: Vec<PathBuf>,
     debounce_secs: u64,
     auto_install: bool,
+    ignore_patterns: Vec<String>,
+    respect_gitignore: bool,
 ) -> Result<()> {
-    let watch_path = path.unwrap_or_else(|| PathBuf::from("."));
-    let canonical_path = watch_path.canonicalize()?;
+    let watch_paths = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths
+    };
+    let canonical_paths = watch_paths
+        .iter()
+        .map(|p| p.canonicalize())
+        .collect::<std::io::Result<Vec<_>>>()?;
 
-    println!("🔭 Starting file watcher for: {}", canonical_path.display());
+    for path in &canonical_paths {
+        println!("🔭 Starting file watcher for: {}", path.display());
+    }
     println!("   Debounce delay: {}s", debounce_secs);
+    if !ignore_patterns.is_empty() {
+        println!("   Ignoring: {}", ignore_patterns.join(", "));
+    }
+    if !respect_gitignore {
+        println!("   .gitignore: disabled");
+    }
     println!("   Press Ctrl+C to stop\n");
 
-    let mut watcher = FileWatcher::new(canonical_path.clone())?;
+    let mut watcher = FileWatcher::with_roots(canonical_paths)?;
+    watcher.set_ignore_patterns(ignore_patterns);
+    watcher.set_respect_gitignore(respect_gitignore);
     watcher
         .start(Duration::from_secs(debounce_secs), auto_install)
         .await?;
 
     info!("File watcher started, monitoring for changes...");
 
-    // Wait for Ctrl+C
-    tokio::signal::ctrl_c().await?;
+    // Report re-index results until Ctrl+C
+    let mut events = watcher.subscribe();
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            event = events.recv() => match event {
+                Ok(event) => match &event.result {
+                    Ok(()) => println!(
+                        "✓ Re-indexed {:?} in {:.1}s",
+                        event.language,
+                        event.duration.as_secs_f64()
+                    ),
+                    Err(e) => println!("✗ Failed to re-index {:?}: {}", event.language, e),
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Missed {} re-index events", skipped);
+                }
+                Err(RecvError::Closed) => {
+                    tokio::signal::ctrl_c().await?;
+                    break;
+                }
+            },
+        }
+    }
 
     println!("\n\nStopping file watcher...");
     watcher.stop();
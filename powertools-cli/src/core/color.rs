@@ -0,0 +1,41 @@
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by the global `--no-color` flag
+static NO_COLOR_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Turn colors off (or back on) for the rest of the process, as `--no-color` does
+pub fn set_no_color(disabled: bool) {
+    NO_COLOR_FLAG.store(disabled, Ordering::Relaxed);
+}
+
+/// Whether text output may contain color escapes
+///
+/// Only when stdout is a terminal, `--no-color` wasn't given, and `NO_COLOR` isn't
+/// set (https://no-color.org).
+pub fn should_color() -> bool {
+    color_allowed(
+        NO_COLOR_FLAG.load(Ordering::Relaxed),
+        std::env::var_os("NO_COLOR"),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+fn color_allowed(no_color_flag: bool, no_color_env: Option<OsString>, is_terminal: bool) -> bool {
+    is_terminal && !no_color_flag && no_color_env.is_none_or(|value| value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_color_flag_env_and_pipes_disable_color() {
+        assert!(color_allowed(false, None, true));
+        assert!(color_allowed(false, Some(OsString::new()), true));
+        assert!(!color_allowed(false, Some(OsString::from("1")), true));
+        assert!(!color_allowed(true, None, true));
+        assert!(!color_allowed(false, None, false));
+    }
+}
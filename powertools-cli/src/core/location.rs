@@ -46,6 +46,19 @@ pub fn parse_location(location_str: &str) -> Result<Location> {
     }
 }
 
+/// Byte offset of a 1-indexed line and (character) column, as SCIP and the CLI count them
+pub fn byte_offset(content: &str, line: usize, column: usize) -> Option<usize> {
+    let mut line_start = 0;
+    for (index, text) in content.split('\n').enumerate() {
+        if index + 1 == line {
+            let (col_byte, _) = text.char_indices().nth(column.saturating_sub(1))?;
+            return Some(line_start + col_byte);
+        }
+        line_start += text.len() + 1;
+    }
+    None
+}
+
 /// Format a location for display
 #[allow(dead_code)]
 pub fn format_location(location: &Location) -> String {
@@ -61,6 +74,14 @@ pub fn format_location(location: &Location) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_byte_offset() {
+        let content = "ab\nçd ef\n";
+        assert_eq!(byte_offset(content, 1, 1), Some(0));
+        assert_eq!(byte_offset(content, 2, 2), Some(5));
+        assert_eq!(byte_offset(content, 3, 1), None);
+    }
+
     #[test]
     fn test_parse_location_with_column() {
         let result = parse_location("src/main.rs:10:5").unwrap();
@@ -1,6 +1,11 @@
 pub mod types;
+pub mod color;
 pub mod output;
 pub mod location;
 pub mod error;
+pub mod pagination;
+pub mod sarif;
+pub mod table;
+pub mod ordering;
 
 pub use types::*;
\ No newline at end of file
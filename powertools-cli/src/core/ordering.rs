@@ -0,0 +1,95 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+use crate::core::{Location, SearchResult, Symbol};
+
+/// Order of results from the analysis commands
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// As found: files in path order, matches in the order the analyzer reports them
+    None,
+    /// By name (matched text for AST searches), then location
+    Name,
+    /// By file path, then line, then column
+    #[default]
+    Location,
+}
+
+fn by_location(a: &Location, b: &Location) -> Ordering {
+    (&a.file_path, a.line, a.column).cmp(&(&b.file_path, b.line, b.column))
+}
+
+pub fn sort_symbols(symbols: &mut [Symbol], order: SortOrder) {
+    match order {
+        SortOrder::None => {}
+        SortOrder::Name => symbols.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| by_location(&a.location, &b.location))),
+        SortOrder::Location => symbols.sort_by(|a, b| by_location(&a.location, &b.location)),
+    }
+}
+
+/// Sort search results; the sort is stable, so captures of one match stay in pattern order
+pub fn sort_search_results(results: &mut [SearchResult], order: SortOrder) {
+    match order {
+        SortOrder::None => {}
+        SortOrder::Name => results.sort_by(|a, b| {
+            a.matched_text
+                .cmp(&b.matched_text)
+                .then_with(|| by_location(&a.location, &b.location))
+        }),
+        SortOrder::Location => results.sort_by(|a, b| by_location(&a.location, &b.location)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::SymbolKind;
+    use std::path::PathBuf;
+
+    fn symbol(name: &str, file: &str, line: usize, column: usize) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            location: Location {
+                file_path: PathBuf::from(file),
+                line,
+                column,
+                end_line: None,
+                end_column: None,
+            },
+            container: None,
+            signature: None,
+            documentation: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_symbols() {
+        let unsorted = vec![
+            symbol("zeta", "src/b.rs", 3, 1),
+            symbol("alpha", "src/b.rs", 1, 5),
+            symbol("alpha", "src/a.rs", 10, 1),
+            symbol("beta", "src/b.rs", 1, 1),
+        ];
+        let order = |order: SortOrder| {
+            let mut symbols = unsorted.clone();
+            sort_symbols(&mut symbols, order);
+            symbols
+                .into_iter()
+                .map(|s| format!("{}@{}:{}:{}", s.name, s.location.file_path.display(), s.location.line, s.location.column))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            order(SortOrder::Location),
+            vec!["alpha@src/a.rs:10:1", "beta@src/b.rs:1:1", "alpha@src/b.rs:1:5", "zeta@src/b.rs:3:1"]
+        );
+        assert_eq!(
+            order(SortOrder::Name),
+            vec!["alpha@src/a.rs:10:1", "alpha@src/b.rs:1:5", "beta@src/b.rs:1:1", "zeta@src/b.rs:3:1"]
+        );
+        assert_eq!(order(SortOrder::None)[0], "zeta@src/b.rs:3:1");
+    }
+}
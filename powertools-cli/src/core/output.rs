@@ -1,3 +1,4 @@
+use crate::core::table::{Cell, Style, TextTable};
 use crate::core::types::*;
 use anyhow::Result;
 use serde::Serialize;
@@ -9,6 +10,12 @@ pub enum OutputFormat {
     Markdown,
 }
 
+// Maximum column widths for text tables; longer values are cut with an ellipsis
+const SYMBOL_WIDTH: usize = 40;
+const KIND_WIDTH: usize = 14;
+const LOCATION_WIDTH: usize = 60;
+const CONTEXT_WIDTH: usize = 80;
+
 pub struct OutputWriter {
     format: OutputFormat,
 }
@@ -19,6 +26,8 @@ impl OutputWriter {
             crate::OutputFormat::Text => OutputFormat::Text,
             crate::OutputFormat::Json => OutputFormat::Json,
             crate::OutputFormat::Markdown => OutputFormat::Markdown,
+            // Only analysis findings have a SARIF form; listings fall back to JSON
+            crate::OutputFormat::Sarif => OutputFormat::Json,
         };
         Self { format }
     }
@@ -29,15 +38,16 @@ impl OutputWriter {
                 println!("{}", serde_json::to_string_pretty(symbols)?);
             }
             OutputFormat::Text => {
+                let mut table = TextTable::new(vec![SYMBOL_WIDTH, KIND_WIDTH, LOCATION_WIDTH]);
                 for symbol in symbols {
-                    println!(
-                        "{:?} {} at {}:{}:{}",
-                        symbol.kind,
-                        symbol.name,
-                        symbol.location.file_path.display(),
-                        symbol.location.line,
-                        symbol.location.column
-                    );
+                    table.add_row(vec![
+                        Cell::text(symbol.name.as_str(), Style::Bold),
+                        Cell::text(format!("{:?}", symbol.kind), Style::Kind),
+                        Cell::location(&symbol.location.file_path, symbol.location.line, symbol.location.column),
+                    ]);
+                }
+                for (line, symbol) in table.render().into_iter().zip(symbols) {
+                    println!("{}", line);
                     if let Some(doc) = &symbol.documentation {
                         println!("  {}", doc);
                     }
@@ -71,17 +81,25 @@ impl OutputWriter {
                 println!("{}", serde_json::to_string_pretty(references)?);
             }
             OutputFormat::Text => {
+                let mut table = TextTable::new(vec![KIND_WIDTH, LOCATION_WIDTH, CONTEXT_WIDTH]);
                 for reference in references {
-                    println!(
-                        "{:?} at {}:{}:{}",
-                        reference.kind,
-                        reference.location.file_path.display(),
-                        reference.location.line,
-                        reference.location.column
-                    );
+                    let mut row = vec![
+                        Cell::text(format!("{:?}", reference.kind), Style::Kind),
+                        Cell::location(
+                            &reference.location.file_path,
+                            reference.location.line,
+                            reference.location.column,
+                        ),
+                    ];
                     if let Some(context) = &reference.context {
-                        println!("  {}", context);
+                        // One line per row: collapse multi-line context
+                        let context = context.split_whitespace().collect::<Vec<_>>().join(" ");
+                        row.push(Cell::text(context, Style::Dim));
                     }
+                    table.add_row(row);
+                }
+                for line in table.render() {
+                    println!("{}", line);
                 }
             }
             OutputFormat::Markdown => {
@@ -114,12 +132,17 @@ impl OutputWriter {
             }
             OutputFormat::Text => {
                 for result in results {
+                    let pattern = result
+                        .pattern_index
+                        .map(|index| format!(" (pattern {})", index))
+                        .unwrap_or_default();
                     println!(
-                        "{}:{}:{} [{}] {}",
+                        "{}:{}:{} [{}]{} {}",
                         result.location.file_path.display(),
                         result.location.line,
                         result.location.column,
                         result.node_type,
+                        pattern,
                         result.matched_text
                     );
                 }
@@ -136,6 +159,9 @@ impl OutputWriter {
                         result.location.line
                     );
                     println!("\n**Node Type:** `{}`", result.node_type);
+                    if let Some(index) = result.pattern_index {
+                        println!("\n**Pattern:** {}", index);
+                    }
                     println!("\n```{:?}", result.language);
                     if let Some(before) = &result.context_before {
                         println!("{}", before);
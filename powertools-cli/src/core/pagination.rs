@@ -0,0 +1,87 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Page size used by the CLI and MCP tools when none is given
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+
+/// One page of a result list, plus the totals needed to ask for the next one
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Number of results before paginating
+    pub count: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    /// Skip `offset` results and keep at most `limit`
+    pub fn new(results: Vec<T>, limit: usize, offset: usize) -> Self {
+        let count = results.len();
+        let items: Vec<T> = results.into_iter().skip(offset).take(limit).collect();
+        let has_more = offset + items.len() < count;
+        Self { items, count, limit, offset, has_more }
+    }
+
+    /// Footer for text output, or None when every result is shown
+    /// ("showing 1–100 of 532 (use --offset 100 for more)")
+    pub fn footer(&self) -> Option<String> {
+        if self.offset == 0 && self.items.len() == self.count {
+            return None;
+        }
+        if self.items.is_empty() {
+            return Some(format!("no results at offset {} (only {})", self.offset, self.count));
+        }
+
+        let last = self.offset + self.items.len();
+        let mut footer = format!("showing {}–{} of {}", self.offset + 1, last, self.count);
+        if self.has_more {
+            footer.push_str(&format!(" (use --offset {} for more)", last));
+        }
+        Some(footer)
+    }
+}
+
+impl<T: Serialize> Page<T> {
+    /// The `count`/`limit`/`offset`/`has_more` envelope, with the items under `key`
+    pub fn to_json(&self, key: &str) -> Value {
+        let mut value = json!({
+            "count": self.count,
+            "limit": self.limit,
+            "offset": self.offset,
+            "has_more": self.has_more,
+        });
+        value[key] = json!(self.items);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_envelope_and_footer() {
+        let page = Page::new((1..=532).collect::<Vec<u32>>(), 100, 0);
+        assert_eq!(page.items.len(), 100);
+        assert!(page.has_more);
+        assert_eq!(page.footer().as_deref(), Some("showing 1–100 of 532 (use --offset 100 for more)"));
+
+        let json = page.to_json("functions");
+        assert_eq!(json["count"], 532);
+        assert_eq!(json["limit"], 100);
+        assert_eq!(json["offset"], 0);
+        assert_eq!(json["has_more"], true);
+        assert_eq!(json["functions"][0], 1);
+
+        let last = Page::new((1..=532).collect::<Vec<u32>>(), 100, 500);
+        assert!(!last.has_more);
+        assert_eq!(last.footer().as_deref(), Some("showing 501–532 of 532"));
+
+        let past_end = Page::new(vec![1, 2, 3], 100, 10);
+        assert_eq!(past_end.footer().as_deref(), Some("no results at offset 10 (only 3)"));
+
+        assert_eq!(Page::new(vec![1, 2, 3], 100, 0).footer(), None);
+    }
+}
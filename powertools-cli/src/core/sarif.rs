@@ -0,0 +1,204 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Component, Path};
+
+use crate::core::Location;
+
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// SARIF `level` of a result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Note,
+    Warning,
+    Error,
+}
+
+/// A check that results refer to by id
+#[derive(Debug, Clone)]
+pub struct SarifRule {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// One finding; `related` holds further locations (e.g. the other copies of duplicated code)
+#[derive(Debug, Clone)]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub level: SarifLevel,
+    pub message: String,
+    pub location: Location,
+    pub related: Vec<Location>,
+}
+
+/// A SARIF 2.1.0 log with a single run; paths under `base` become relative URIs
+pub fn sarif_log(rules: &[SarifRule], results: &[SarifResult], base: &Path) -> Value {
+    let rules: Vec<Value> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let related: Vec<Value> = result
+                .related
+                .iter()
+                .enumerate()
+                .map(|(i, location)| {
+                    let mut value = physical_location(location, base);
+                    value["id"] = json!(i + 1);
+                    value
+                })
+                .collect();
+            let mut value = json!({
+                "ruleId": result.rule_id,
+                "level": result.level,
+                "message": { "text": result.message },
+                "locations": [physical_location(&result.location, base)],
+            });
+            if !related.is_empty() {
+                value["relatedLocations"] = json!(related);
+            }
+            value
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "powertools",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/zachswift615/agent-power-tools",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn physical_location(location: &Location, base: &Path) -> Value {
+    let mut region = json!({
+        "startLine": location.line.max(1),
+        "startColumn": location.column.max(1),
+    });
+    if let Some(end_line) = location.end_line {
+        region["endLine"] = json!(end_line.max(location.line.max(1)));
+    }
+    if let Some(end_column) = location.end_column {
+        region["endColumn"] = json!(end_column.max(1));
+    }
+
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": artifact_uri(&location.file_path, base) },
+            "region": region,
+        }
+    })
+}
+
+/// Forward-slash URI relative to `base` when possible, so results line up with the repository checkout
+fn artifact_uri(path: &Path, base: &Path) -> String {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    if relative.is_absolute() {
+        return format!("file://{}", relative.display().to_string().replace('\\', "/"));
+    }
+    relative
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn location(path: &str, line: usize) -> Location {
+        Location {
+            file_path: PathBuf::from(path),
+            line,
+            column: 5,
+            end_line: Some(line + 2),
+            end_column: None,
+        }
+    }
+
+    /// Checks the properties the SARIF 2.1.0 schema requires for the objects we emit
+    fn assert_valid_sarif(log: &Value) {
+        assert_eq!(log["version"], "2.1.0");
+        assert!(log["$schema"].as_str().unwrap().ends_with("sarif-2.1.0.json"));
+
+        for run in log["runs"].as_array().unwrap() {
+            let driver = &run["tool"]["driver"];
+            assert!(driver["name"].is_string());
+
+            let rule_ids: Vec<&str> = driver["rules"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|rule| rule["id"].as_str().unwrap())
+                .collect();
+
+            for result in run["results"].as_array().unwrap() {
+                assert!(result["message"]["text"].is_string());
+                assert!(rule_ids.contains(&result["ruleId"].as_str().unwrap()));
+                assert!(["none", "note", "warning", "error"].contains(&result["level"].as_str().unwrap()));
+
+                let locations = result["locations"].as_array().unwrap().iter();
+                let related = result["relatedLocations"].as_array().into_iter().flatten();
+                for location in locations.chain(related) {
+                    let physical = &location["physicalLocation"];
+                    assert!(physical["artifactLocation"]["uri"].is_string());
+                    let region = &physical["region"];
+                    assert!(region["startLine"].as_u64().unwrap() >= 1);
+                    assert!(region["startColumn"].as_u64().unwrap() >= 1);
+                    if let Some(end_line) = region["endLine"].as_u64() {
+                        assert!(end_line >= region["startLine"].as_u64().unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sarif_log_matches_schema() {
+        let rules = [SarifRule {
+            id: "duplicate-code",
+            name: "DuplicateCode",
+            description: "Duplicated block",
+        }];
+        let results = [SarifResult {
+            rule_id: "duplicate-code",
+            level: SarifLevel::Warning,
+            message: "Block duplicated 2 times".to_string(),
+            location: location("/repo/src/a.rs", 3),
+            related: vec![location("./src/b.rs", 10)],
+        }];
+
+        let log = sarif_log(&rules, &results, Path::new("/repo"));
+        assert_valid_sarif(&log);
+
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/a.rs");
+        assert_eq!(result["relatedLocations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/b.rs");
+        assert_eq!(result["relatedLocations"][0]["id"], 1);
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["endLine"], 5);
+    }
+}
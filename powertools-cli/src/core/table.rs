@@ -0,0 +1,201 @@
+use std::path::Path;
+
+use crate::core::color::should_color;
+
+const RESET: &str = "\x1b[0m";
+const ELLIPSIS: char = '…';
+
+/// Color of a cell when output goes to a terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Bold,
+    /// Symbol and reference kinds
+    Kind,
+    Dim,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "\x1b[1m",
+            Style::Kind => "\x1b[35m",
+            Style::Dim => "\x1b[2m",
+        }
+    }
+}
+
+const PATH_COLOR: &str = "\x1b[36m";
+const LINE_COLOR: &str = "\x1b[33m";
+
+/// One cell of a text table
+#[derive(Debug, Clone)]
+pub enum Cell {
+    Text(String, Style),
+    /// `path:line:column`; long paths are shortened from the front so the file name stays visible
+    Location { path: String, line: usize, column: usize },
+}
+
+impl Cell {
+    pub fn text(text: impl Into<String>, style: Style) -> Self {
+        Cell::Text(text.into(), style)
+    }
+
+    pub fn location(path: &Path, line: usize, column: usize) -> Self {
+        Cell::Location {
+            path: path.display().to_string(),
+            line,
+            column,
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            Cell::Text(text, _) => text.chars().count(),
+            Cell::Location { path, line, column } => {
+                path.chars().count() + format!(":{}:{}", line, column).len()
+            }
+        }
+    }
+
+    /// The cell cut down to `max_width` characters, then colored
+    fn render(&self, max_width: usize, color: bool) -> (String, usize) {
+        match self {
+            Cell::Text(text, style) => {
+                let text = truncate_end(text, max_width);
+                let width = text.chars().count();
+                if color {
+                    (format!("{}{}{}", style.code(), text, RESET), width)
+                } else {
+                    (text, width)
+                }
+            }
+            Cell::Location { path, line, column } => {
+                let suffix = format!(":{}:{}", line, column);
+                let path = truncate_start(path, max_width.saturating_sub(suffix.len()).max(1));
+                let width = path.chars().count() + suffix.len();
+                if color {
+                    let position = format!("{}{}:{}{}", LINE_COLOR, line, column, RESET);
+                    (format!("{}{}{}:{}", PATH_COLOR, path, RESET, position), width)
+                } else {
+                    (format!("{}{}", path, suffix), width)
+                }
+            }
+        }
+    }
+}
+
+/// Rows of cells printed as aligned columns, colored when `color` is set
+pub struct TextTable {
+    max_widths: Vec<usize>,
+    rows: Vec<Vec<Cell>>,
+    color: bool,
+}
+
+impl TextTable {
+    /// A table whose columns are never wider than `max_widths`
+    pub fn new(max_widths: Vec<usize>) -> Self {
+        Self {
+            max_widths,
+            rows: Vec::new(),
+            color: should_color(),
+        }
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn add_row(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    /// One line per row; the last column isn't padded
+    pub fn render(&self) -> Vec<String> {
+        let widths: Vec<usize> = self
+            .max_widths
+            .iter()
+            .enumerate()
+            .map(|(i, &max)| {
+                let widest = self.rows.iter().filter_map(|row| row.get(i)).map(Cell::width).max();
+                widest.unwrap_or(0).min(max)
+            })
+            .collect();
+
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut line = String::new();
+                for (i, cell) in row.iter().enumerate() {
+                    let width = widths.get(i).copied().unwrap_or(usize::MAX);
+                    let (text, text_width) = cell.render(width, self.color);
+                    line.push_str(&text);
+                    if i + 1 < row.len() {
+                        line.push_str(&" ".repeat(width.saturating_sub(text_width) + 2));
+                    }
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+fn truncate_end(text: &str, max_width: usize) -> String {
+    if text.chars().count() <= max_width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_width.saturating_sub(1)).collect();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+fn truncate_start(text: &str, max_width: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = ELLIPSIS.to_string();
+    truncated.extend(text.chars().skip(count - max_width.saturating_sub(1)));
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(color: bool) -> TextTable {
+        let mut table = TextTable::new(vec![10, 8, 24]).with_color(color);
+        table.add_row(vec![
+            Cell::text("run", Style::Bold),
+            Cell::text("Function", Style::Kind),
+            Cell::location(Path::new("src/main.rs"), 3, 1),
+        ]);
+        table.add_row(vec![
+            Cell::text("a_very_long_symbol_name", Style::Bold),
+            Cell::text("Method", Style::Kind),
+            Cell::location(Path::new("src/deeply/nested/module/handlers.rs"), 120, 5),
+        ]);
+        table
+    }
+
+    #[test]
+    fn test_columns_are_aligned_and_truncated() {
+        let lines = table(false).render();
+        assert_eq!(
+            lines,
+            vec![
+                "run         Function  src/main.rs:3:1",
+                "a_very_lo…  Method    …odule/handlers.rs:120:5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_color_codes_only_when_enabled() {
+        let colored = table(true).render();
+        assert!(colored[0].contains("\x1b[36msrc/main.rs\x1b[0m:\x1b[33m3:1\x1b[0m"));
+        assert!(colored[0].starts_with("\x1b[1mrun\x1b[0m         \x1b[35mFunction"));
+
+        assert!(!table(false).render().iter().any(|line| line.contains('\x1b')));
+    }
+}
@@ -11,7 +11,7 @@ pub struct Symbol {
     pub documentation: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SymbolKind {
     File,
@@ -42,6 +42,7 @@ pub enum SymbolKind {
     TypeParameter,
     Trait,
     Implementation,
+    Macro,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +73,15 @@ pub enum ReferenceKind {
     TypeReference,
 }
 
+/// Type and documentation of the symbol at a position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeInfo {
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    pub documentation: Option<String>,
+    pub location: Location,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub location: Location,
@@ -80,6 +90,9 @@ pub struct SearchResult {
     pub context_after: Option<String>,
     pub language: Language,
     pub node_type: String,
+    /// Which of several searched patterns matched (0-based); absent for single-pattern searches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern_index: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, Eq, PartialEq)]
@@ -94,6 +107,7 @@ pub enum Language {
     Java,
     Cpp,
     C,
+    Ruby,
     Unknown,
 }
 
@@ -111,6 +125,7 @@ impl Language {
             "java" => Language::Java,
             "cpp" | "cxx" | "cc" => Language::Cpp,
             "c" | "h" => Language::C,
+            "rb" | "rake" | "gemspec" => Language::Ruby,
             _ => Language::Unknown,
         }
     }
@@ -125,6 +140,7 @@ impl Language {
             Language::Go => Some(tree_sitter_go::LANGUAGE.into()),
             Language::Java => Some(tree_sitter_java::LANGUAGE.into()),
             Language::Cpp | Language::C => Some(tree_sitter_cpp::LANGUAGE.into()),
+            Language::Ruby => Some(tree_sitter_ruby::LANGUAGE.into()),
             Language::Unknown => None,
         }
     }
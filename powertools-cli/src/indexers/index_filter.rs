@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use scip::types::Index;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File in the project root that remembers the filters `index` was run with
+pub const FILTER_FILE: &str = "index.filter.json";
+
+/// Include/exclude globs limiting which files end up in the SCIP indexes
+///
+/// Globs are matched against paths relative to the project root, and against each of
+/// their parent directories, so `generated` and `generated/**` both skip a directory.
+/// Exclude wins: a file matching both lists is left out. Without include globs,
+/// everything not excluded is kept.
+#[derive(Debug, Clone, Default)]
+pub struct IndexFilter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFilter {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+impl IndexFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let compile = |globs: &[String]| {
+            globs
+                .iter()
+                .map(|g| glob::Pattern::new(g).with_context(|| format!("Invalid glob pattern: {}", g)))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether a path relative to the project root belongs in the index
+    pub fn allows(&self, relative: &Path) -> bool {
+        let matches = |patterns: &[glob::Pattern]| {
+            relative
+                .ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| patterns.iter().any(|pattern| pattern.matches_path(p)))
+        };
+        if matches(&self.exclude) {
+            return false;
+        }
+        self.include.is_empty() || matches(&self.include)
+    }
+
+    /// The filter saved by the last `index` run, or an empty one
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join(FILTER_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let saved: SavedFilter = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Self::new(&saved.include, &saved.exclude)
+    }
+
+    /// Remember this filter for later re-indexing; an empty filter removes the file
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = project_root.join(FILTER_FILE);
+        if self.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path).context("Failed to remove index filter file")?;
+            }
+            return Ok(());
+        }
+
+        let as_strings = |patterns: &[glob::Pattern]| -> Vec<String> {
+            patterns.iter().map(|p| p.as_str().to_string()).collect()
+        };
+        let saved = SavedFilter {
+            include: as_strings(&self.include),
+            exclude: as_strings(&self.exclude),
+        };
+        let json = serde_json::to_string_pretty(&saved).context("Failed to serialize index filter")?;
+        std::fs::write(&path, json).context("Failed to write index filter file")?;
+        Ok(())
+    }
+
+    /// Drop documents this filter doesn't allow from an index file
+    ///
+    /// Language indexers can't be limited to a file set, so their output is filtered
+    /// afterwards. Returns the number of documents removed.
+    pub fn apply(&self, index_path: &Path) -> Result<usize> {
+        use protobuf::Message;
+
+        if self.is_empty() || !index_path.exists() {
+            return Ok(0);
+        }
+
+        let bytes = std::fs::read(index_path).context("Failed to read SCIP index file")?;
+        let mut index = Index::parse_from_bytes(&bytes).context("Failed to parse SCIP index")?;
+
+        let before = index.documents.len();
+        index.documents.retain(|doc| self.allows(Path::new(&doc.relative_path)));
+        let removed = before - index.documents.len();
+
+        if removed > 0 {
+            let bytes = index.write_to_bytes().context("Failed to serialize SCIP index")?;
+            std::fs::write(index_path, bytes).context("Failed to write SCIP index file")?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protobuf::Message;
+    use scip::types::Document;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> IndexFilter {
+        let strings = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+        IndexFilter::new(&strings(include), &strings(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let filter = filter(&["src/**"], &["generated", "**/*.gen.ts"]);
+
+        assert!(filter.allows(Path::new("src/app.ts")));
+        assert!(!filter.allows(Path::new("lib/app.ts")));
+        assert!(!filter.allows(Path::new("generated/api.ts")));
+        assert!(!filter.allows(Path::new("src/api.gen.ts")));
+
+        // A directory name excludes everything below it, even where an include glob matches
+        let filter = self::filter(&["generated/**"], &["generated"]);
+        assert!(!filter.allows(Path::new("generated/api.ts")));
+
+        assert!(IndexFilter::default().allows(Path::new("anything.rs")));
+    }
+
+    #[test]
+    fn test_generated_dir_is_dropped_from_index() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let index_path = temp.path().join("index.typescript.scip");
+
+        let mut index = Index::new();
+        for path in ["src/app.ts", "generated/api.ts", "generated/models/user.ts"] {
+            let mut doc = Document::new();
+            doc.relative_path = path.to_string();
+            index.documents.push(doc);
+        }
+        std::fs::write(&index_path, index.write_to_bytes().unwrap()).unwrap();
+
+        let filter = filter(&[], &["generated/**"]);
+        assert_eq!(filter.apply(&index_path).unwrap(), 2);
+
+        let index = Index::parse_from_bytes(&std::fs::read(&index_path).unwrap()).unwrap();
+        let paths: Vec<_> = index.documents.iter().map(|d| d.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["src/app.ts"]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let temp = tempfile::TempDir::new().unwrap();
+
+        let saved = filter(&["src/**"], &["generated/**"]);
+        saved.save(temp.path()).unwrap();
+        let loaded = IndexFilter::load(temp.path()).unwrap();
+        assert!(!loaded.allows(Path::new("generated/api.ts")));
+        assert!(!loaded.allows(Path::new("lib/app.ts")));
+        assert!(loaded.allows(Path::new("src/app.ts")));
+
+        // Saving an empty filter forgets the old one
+        IndexFilter::default().save(temp.path()).unwrap();
+        assert!(!temp.path().join(FILTER_FILE).exists());
+        assert!(IndexFilter::load(temp.path()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+/// Lock file, relative to the project root, held while any indexer writes index files
+pub const LOCK_FILE: &str = ".powertools/index.lock";
+
+/// Another process or thread is already indexing this project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexInProgress {
+    pub lock_path: PathBuf,
+}
+
+impl std::fmt::Display for IndexInProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "An index run is already in progress (lock held on {})", self.lock_path.display())
+    }
+}
+
+impl std::error::Error for IndexInProgress {}
+
+/// Advisory per-project lock on [`LOCK_FILE`], released when dropped
+///
+/// The OS drops the lock with the file handle, so a crashed indexer never leaves
+/// the project locked.
+#[derive(Debug)]
+pub struct IndexLock {
+    _file: File,
+}
+
+impl IndexLock {
+    /// Wait until no one else holds the project's lock, then take it
+    pub fn acquire(project_root: &Path) -> Result<Self> {
+        let (file, lock_path) = open(project_root)?;
+        file.lock()
+            .with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+        Ok(Self { _file: file })
+    }
+
+    /// Take the project's lock, or fail with [`IndexInProgress`] if it's held
+    pub fn try_acquire(project_root: &Path) -> Result<Self> {
+        let (file, lock_path) = open(project_root)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Self { _file: file }),
+            Err(TryLockError::WouldBlock) => Err(IndexInProgress { lock_path }.into()),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock {}", lock_path.display()))
+            }
+        }
+    }
+
+    /// Whether an indexer currently holds the project's lock
+    pub fn is_held(project_root: &Path) -> bool {
+        match Self::try_acquire(project_root) {
+            Ok(_) => false,
+            Err(e) => e.is::<IndexInProgress>(),
+        }
+    }
+}
+
+fn open(project_root: &Path) -> Result<(File, PathBuf)> {
+    let lock_path = project_root.join(LOCK_FILE);
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to open {}", lock_path.display()))?;
+    Ok((file, lock_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_concurrent_runs_serialize() {
+        let dir = TempDir::new().unwrap();
+        let root = Arc::new(dir.path().to_path_buf());
+        let spans = Arc::new(Mutex::new(Vec::new()));
+        let barrier = Arc::new(Barrier::new(2));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let (root, spans, barrier) = (root.clone(), spans.clone(), barrier.clone());
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let _lock = IndexLock::acquire(&root).unwrap();
+                    let start = Instant::now();
+                    std::thread::sleep(Duration::from_millis(100));
+                    spans.lock().unwrap().push((start, Instant::now()));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut spans = spans.lock().unwrap().clone();
+        spans.sort();
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].1 <= spans[1].0, "index runs overlapped: {:?}", spans);
+    }
+
+    #[test]
+    fn test_try_acquire_reports_run_in_progress() {
+        let dir = TempDir::new().unwrap();
+        assert!(!IndexLock::is_held(dir.path()));
+
+        let lock = IndexLock::acquire(dir.path()).unwrap();
+        let err = IndexLock::try_acquire(dir.path()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<IndexInProgress>(),
+            Some(&IndexInProgress { lock_path: dir.path().join(LOCK_FILE) })
+        );
+        assert!(IndexLock::is_held(dir.path()));
+
+        drop(lock);
+        assert!(IndexLock::try_acquire(dir.path()).is_ok());
+    }
+}
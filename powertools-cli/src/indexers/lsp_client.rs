@@ -6,6 +6,28 @@ use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a request waits for its response unless configured otherwise
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shutdown during drop shouldn't hold the caller up for a full request timeout
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Process-wide default request timeout in milliseconds (see `set_default_request_timeout`)
+static DEFAULT_TIMEOUT_MS: AtomicU64 = AtomicU64::new(DEFAULT_REQUEST_TIMEOUT.as_millis() as u64);
+
+/// Set the request timeout used by LSP clients started after this call
+pub fn set_default_request_timeout(timeout: Duration) {
+    DEFAULT_TIMEOUT_MS.store(timeout.as_millis() as u64, Ordering::SeqCst);
+}
+
+/// The request timeout new LSP clients start with
+pub fn default_request_timeout() -> Duration {
+    Duration::from_millis(DEFAULT_TIMEOUT_MS.load(Ordering::SeqCst))
+}
 
 /// Errors that can occur when communicating with an LSP server
 #[allow(dead_code)]
@@ -17,8 +39,8 @@ pub enum LspError {
     #[error("LSP server initialization failed: {0}")]
     InitializationFailed(String),
 
-    #[error("Request timeout after {0}ms")]
-    Timeout(u64),
+    #[error("{method} request timed out after {timeout_ms}ms")]
+    Timeout { method: String, timeout_ms: u64 },
 
     #[error("Server returned error: code={code}, message={message}")]
     ServerError { code: i32, message: String },
@@ -80,8 +102,11 @@ struct JsonRpcNotification {
 pub struct LspClient {
     process: Child,
     stdin: BufWriter<ChildStdin>,
-    stdout: BufReader<ChildStdout>,
+    /// Messages read off the server's stdout by a background thread, so a
+    /// server that never replies can't block us past the request timeout
+    messages: Receiver<String>,
     request_id: AtomicU64,
+    request_timeout: Duration,
     pub server_capabilities: Option<ServerCapabilities>,
     root_uri: Uri,
 }
@@ -125,11 +150,14 @@ impl LspClient {
         let root_url = Uri::from_str(root_uri)
             .map_err(|e| anyhow::anyhow!("Invalid root URI '{}': {}", root_uri, e))?;
 
+        let messages = spawn_reader(stdout);
+
         let mut client = Self {
             process,
             stdin,
-            stdout,
+            messages,
             request_id: AtomicU64::new(1),
+            request_timeout: default_request_timeout(),
             server_capabilities: None,
             root_uri: root_url.clone(),
         };
@@ -157,6 +185,10 @@ impl LspClient {
                     references: Some(ReferenceClientCapabilities {
                         dynamic_registration: Some(false),
                     }),
+                    hover: Some(HoverClientCapabilities {
+                        dynamic_registration: Some(false),
+                        content_format: Some(vec![MarkupKind::Markdown, MarkupKind::PlainText]),
+                    }),
                     rename: Some(RenameClientCapabilities {
                         dynamic_registration: Some(false),
                         prepare_support: Some(false),
@@ -197,6 +229,17 @@ impl LspClient {
         Ok(())
     }
 
+    /// Set how long each request waits for a response before failing with
+    /// `LspError::Timeout`. The server keeps running after a timeout.
+    pub fn set_request_timeout(&mut self, timeout: Duration) {
+        self.request_timeout = timeout;
+    }
+
+    /// Current per-request timeout
+    pub fn request_timeout(&self) -> Duration {
+        self.request_timeout
+    }
+
     /// Send a request to the LSP server and wait for response
     ///
     /// # Arguments
@@ -206,6 +249,11 @@ impl LspClient {
     /// # Returns
     /// The result field from the JSON-RPC response
     fn send_request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.send_request_with_timeout(method, params, self.request_timeout)
+    }
+
+    /// Send a request and wait at most `timeout` for its response
+    fn send_request_with_timeout(&mut self, method: &str, params: Value, timeout: Duration) -> Result<Value> {
         let id = self.next_id();
 
         let request = JsonRpcRequest {
@@ -223,7 +271,7 @@ impl LspClient {
         self.stdin.flush()?;
 
         // Read response
-        self.read_response(id)
+        self.read_response(method, id, timeout)
     }
 
     /// Send a notification (no response expected)
@@ -242,80 +290,46 @@ impl LspClient {
         Ok(())
     }
 
-    /// Read a JSON-RPC response from the server
-    fn read_response(&mut self, expected_id: u64) -> Result<Value> {
-        use std::time::{Duration, Instant};
-
-        let start = Instant::now();
-        let timeout = Duration::from_secs(30); // 30 second timeout
-        let mut attempts = 0;
-        const MAX_ATTEMPTS: usize = 100; // Prevent infinite loop
+    /// Wait for the response to request `expected_id`
+    ///
+    /// Responses to earlier requests that timed out arrive late with a stale ID
+    /// and are discarded, as are notifications and server-to-client requests.
+    fn read_response(&mut self, method: &str, expected_id: u64, timeout: Duration) -> Result<Value> {
+        let deadline = Instant::now() + timeout;
 
         loop {
-            // Check timeout and max attempts
-            if start.elapsed() > timeout {
-                return Err(LspError::Timeout(30000).into());
-            }
-            if attempts >= MAX_ATTEMPTS {
-                return Err(LspError::ProtocolViolation(
-                    format!("Max attempts ({}) exceeded waiting for response ID {}", MAX_ATTEMPTS, expected_id)
-                ).into());
-            }
-            attempts += 1;
-
-            // Read headers
-            let mut headers = Vec::new();
-            let mut line = String::new();
-
-            loop {
-                line.clear();
-                self.stdout.read_line(&mut line)?;
-
-                if line == "\r\n" {
-                    break; // End of headers
-                }
-
-                if !line.trim().is_empty() {
-                    headers.push(line.trim().to_string());
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let content = match self.messages.recv_timeout(remaining) {
+                Ok(content) => content,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(LspError::Timeout {
+                        method: method.to_string(),
+                        timeout_ms: timeout.as_millis() as u64,
+                    }
+                    .into());
                 }
+                Err(RecvTimeoutError::Disconnected) => return Err(LspError::ServerNotRunning.into()),
+            };
+
+            let Ok(message) = serde_json::from_str::<Value>(&content) else {
+                return Err(LspError::JsonParseError(content).into());
+            };
+            if message.get("method").is_some() || message["id"].as_u64() != Some(expected_id) {
+                continue;
             }
 
-            // Parse Content-Length header
-            let content_length = headers
-                .iter()
-                .find(|h| h.starts_with("Content-Length:"))
-                .and_then(|h| h.split(':').nth(1))
-                .and_then(|v| v.trim().parse::<usize>().ok())
-                .ok_or_else(|| {
-                    LspError::ProtocolViolation("Missing or invalid Content-Length".to_string())
-                })?;
-
-            // Read content
-            let mut buffer = vec![0u8; content_length];
-            self.stdout.read_exact(&mut buffer)?;
-
-            let content = String::from_utf8(buffer)
-                .map_err(|e| LspError::InvalidResponse(format!("Invalid UTF-8: {}", e)))?;
-
-            // Try to parse as response
-            if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(&content) {
-                if response.id == expected_id {
-                    if let Some(error) = response.error {
-                        return Err(LspError::ServerError {
-                            code: error.code,
-                            message: error.message,
-                        }
-                        .into());
-                    }
-
-                    return response
-                        .result
-                        .ok_or_else(|| LspError::InvalidResponse("No result field".to_string()).into());
+            let response: JsonRpcResponse = serde_json::from_value(message)
+                .map_err(|e| LspError::InvalidResponse(e.to_string()))?;
+            if let Some(error) = response.error {
+                return Err(LspError::ServerError {
+                    code: error.code,
+                    message: error.message,
                 }
+                .into());
             }
 
-            // Might be a notification or other message, ignore and keep reading
-            // (In production, we'd handle notifications properly)
+            // `"result": null` deserializes to None; callers check for null themselves
+            return Ok(response.result.unwrap_or(Value::Null));
         }
     }
 
@@ -434,6 +448,44 @@ impl LspClient {
         Ok(locations)
     }
 
+    /// Get hover information (type signature and docs) for a position
+    ///
+    /// # Arguments
+    /// * `uri` - Document URI
+    /// * `line` - Line number (0-indexed)
+    /// * `character` - Character offset (0-indexed)
+    ///
+    /// # Returns
+    /// Hover contents, or None if the server has nothing for this position
+    pub fn hover(
+        &mut self,
+        uri: &Uri,
+        line: u32,
+        character: u32,
+    ) -> Result<Option<Hover>> {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position: Position {
+                    line,
+                    character,
+                },
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let response = self.send_request("textDocument/hover", serde_json::to_value(params)?)?;
+
+        if response.is_null() {
+            return Ok(None);
+        }
+
+        let hover = serde_json::from_value::<Hover>(response)
+            .context("Failed to parse hover response")?;
+
+        Ok(Some(hover))
+    }
+
     /// Prepare to rename a symbol - validates that rename is possible
     ///
     /// This should be called before `rename()` to validate that the symbol
@@ -620,13 +672,10 @@ impl LspClient {
 impl Drop for LspClient {
     fn drop(&mut self) {
         // Try graceful shutdown with timeout
-        let _ = self.send_request("shutdown", Value::Null);
+        let _ = self.send_request_with_timeout("shutdown", Value::Null, SHUTDOWN_TIMEOUT);
         let _ = self.send_notification("exit", Value::Null);
 
         // Give process 1 second to exit cleanly
-        use std::time::Duration;
-        use std::thread;
-
         // Try to wait for up to 1 second
         for _ in 0..10 {
             match self.process.try_wait() {
@@ -641,6 +690,58 @@ impl Drop for LspClient {
     }
 }
 
+/// Read Content-Length framed messages from the server on a background thread.
+/// The thread ends when the server closes stdout, which disconnects the channel.
+fn spawn_reader(stdout: BufReader<ChildStdout>) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdout = stdout;
+        while let Ok(Some(content)) = read_message(&mut stdout) {
+            if sender.send(content).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Read one framed message, or `None` at end of stream
+fn read_message(reader: &mut impl BufRead) -> Result<Option<String>> {
+    let mut headers = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        if line == "\r\n" {
+            break; // End of headers
+        }
+
+        if !line.trim().is_empty() {
+            headers.push(line.trim().to_string());
+        }
+    }
+
+    // Parse Content-Length header
+    let content_length = headers
+        .iter()
+        .find(|h| h.starts_with("Content-Length:"))
+        .and_then(|h| h.split(':').nth(1))
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .ok_or_else(|| LspError::ProtocolViolation("Missing or invalid Content-Length".to_string()))?;
+
+    // Read content
+    let mut buffer = vec![0u8; content_length];
+    reader.read_exact(&mut buffer)?;
+
+    let content = String::from_utf8(buffer)
+        .map_err(|e| LspError::InvalidResponse(format!("Invalid UTF-8: {}", e)))?;
+    Ok(Some(content))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,8 +3,12 @@ use lsp_types::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use crate::core::{Location, Reference, ReferenceKind};
-use crate::indexers::lsp_client::LspClient;
+use std::time::Duration;
+use crate::core::{Location, Reference, ReferenceKind, TypeInfo};
+use crate::indexers::lsp_client::{LspClient, LspError};
+
+/// Consecutive timeouts after which a server is considered unhealthy for the session
+pub const MAX_CONSECUTIVE_TIMEOUTS: u32 = 2;
 
 /// LSP-based query implementation for languages without SCIP indexers
 ///
@@ -14,6 +18,9 @@ use crate::indexers::lsp_client::LspClient;
 pub struct LspQuery {
     client: LspClient,
     project_root: PathBuf,
+    command: String,
+    consecutive_timeouts: u32,
+    unhealthy: bool,
 }
 
 impl LspQuery {
@@ -34,9 +41,54 @@ impl LspQuery {
         Ok(Self {
             client,
             project_root,
+            command: command.to_string(),
+            consecutive_timeouts: 0,
+            unhealthy: false,
         })
     }
 
+    /// Set how long definition/reference queries wait for the server
+    pub fn set_request_timeout(&mut self, timeout: Duration) {
+        self.client.set_request_timeout(timeout);
+    }
+
+    /// False once the server has timed out `MAX_CONSECUTIVE_TIMEOUTS` times in a row.
+    /// An unhealthy server stays that way for the session and queries fail fast.
+    pub fn is_healthy(&self) -> bool {
+        !self.unhealthy
+    }
+
+    /// Project root the server was started for
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
+    /// Fail fast instead of waiting on a server that has stopped answering
+    fn ensure_healthy(&self) -> Result<()> {
+        if self.unhealthy {
+            return Err(anyhow::anyhow!(
+                "LSP server '{}' stopped responding ({} consecutive timeouts)",
+                self.command,
+                MAX_CONSECUTIVE_TIMEOUTS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Count timeouts from a server request; any answer resets the count
+    fn track<T>(&mut self, result: Result<T>) -> Result<T> {
+        match &result {
+            Err(e) if matches!(e.downcast_ref::<LspError>(), Some(LspError::Timeout { .. })) => {
+                self.consecutive_timeouts += 1;
+                if self.consecutive_timeouts >= MAX_CONSECUTIVE_TIMEOUTS {
+                    self.unhealthy = true;
+                }
+            }
+            _ => self.consecutive_timeouts = 0,
+        }
+        result
+    }
+
     /// Find the definition of a symbol at a given location
     ///
     /// This matches the ScipQuery interface for compatibility.
@@ -49,6 +101,8 @@ impl LspQuery {
     /// # Returns
     /// Location of the definition, or None if not found
     pub fn find_definition(&mut self, file_path: &Path, line: usize, column: usize) -> Result<Option<Location>> {
+        self.ensure_healthy()?;
+
         // Ensure file_path is absolute
         let abs_path = if file_path.is_absolute() {
             file_path.to_path_buf()
@@ -86,7 +140,8 @@ impl LspQuery {
         let lsp_char = (column.saturating_sub(1)) as u32;
 
         // Query for definition
-        let locations = self.client.goto_definition(&uri, lsp_line, lsp_char)?;
+        let result = self.client.goto_definition(&uri, lsp_line, lsp_char);
+        let locations = self.track(result)?;
 
         // Convert LSP Location to our Location type
         if let Some(lsp_location) = locations.first() {
@@ -117,6 +172,8 @@ impl LspQuery {
         column: usize,
         include_declarations: bool,
     ) -> Result<Vec<Reference>> {
+        self.ensure_healthy()?;
+
         // Ensure file_path is absolute
         let abs_path = if file_path.is_absolute() {
             file_path.to_path_buf()
@@ -154,7 +211,8 @@ impl LspQuery {
         let lsp_char = (column.saturating_sub(1)) as u32;
 
         // Query for references
-        let locations = self.client.find_references(&uri, lsp_line, lsp_char, include_declarations)?;
+        let result = self.client.find_references(&uri, lsp_line, lsp_char, include_declarations);
+        let locations = self.track(result)?;
 
         // Convert LSP Locations to our Reference type
         locations.iter()
@@ -169,6 +227,80 @@ impl LspQuery {
             .collect()
     }
 
+    /// Get the type and documentation of the symbol at a position (`textDocument/hover`)
+    ///
+    /// # Arguments
+    /// * `file_path` - File containing the symbol
+    /// * `line` - Line number (1-indexed)
+    /// * `column` - Column number (1-indexed)
+    ///
+    /// # Returns
+    /// Type information, or None if the server has no hover for this position
+    pub fn hover(&mut self, file_path: &Path, line: usize, column: usize) -> Result<Option<TypeInfo>> {
+        self.ensure_healthy()?;
+
+        let abs_path = if file_path.is_absolute() {
+            file_path.to_path_buf()
+        } else {
+            self.project_root.join(file_path)
+        };
+
+        let content = std::fs::read_to_string(&abs_path)
+            .with_context(|| format!("Failed to read file: {}", abs_path.display()))?;
+
+        let uri_str = format!("file://{}", abs_path.display());
+        let uri = Uri::from_str(&uri_str)
+            .map_err(|e| anyhow::anyhow!("Invalid URI '{}': {}", uri_str, e))?;
+
+        let language_id = abs_path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| match ext {
+                "swift" => Some("swift"),
+                "rs" => Some("rust"),
+                "ts" | "tsx" => Some("typescript"),
+                "js" | "jsx" => Some("javascript"),
+                "py" => Some("python"),
+                _ => None,
+            })
+            .unwrap_or("plaintext");
+
+        self.client.did_open(&uri, language_id, content)?;
+
+        let lsp_line = (line.saturating_sub(1)) as u32;
+        let lsp_char = (column.saturating_sub(1)) as u32;
+
+        let result = self.client.hover(&uri, lsp_line, lsp_char);
+        let Some(hover) = self.track(result)? else {
+            return Ok(None);
+        };
+
+        let markdown = match hover.contents {
+            HoverContents::Scalar(marked) => marked_string_to_markdown(marked),
+            HoverContents::Array(marked) => marked
+                .into_iter()
+                .map(marked_string_to_markdown)
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            HoverContents::Markup(markup) => markup.value,
+        };
+        let (type_name, documentation) = split_hover_markdown(&markdown);
+        if type_name.is_none() && documentation.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(TypeInfo {
+            type_name,
+            documentation,
+            location: Location {
+                file_path: abs_path,
+                line,
+                column,
+                end_line: hover.range.map(|r| r.end.line as usize + 1),
+                end_column: hover.range.map(|r| r.end.character as usize + 1),
+            },
+        }))
+    }
+
     /// Prepare to rename a symbol - validates that rename is possible
     ///
     /// # Arguments
@@ -538,6 +670,34 @@ fn apply_text_edits(file_path: &Path, edits: &[TextEdit]) -> Result<()> {
     Ok(())
 }
 
+/// Render a legacy `MarkedString` as markdown, fencing language strings
+fn marked_string_to_markdown(marked: MarkedString) -> String {
+    match marked {
+        MarkedString::String(text) => text,
+        MarkedString::LanguageString(code) => format!("```{}\n{}\n```", code.language, code.value),
+    }
+}
+
+/// Split hover markdown into the signature (the leading code block) and the documentation after it
+/// Hover text without a leading code block is all documentation
+pub fn split_hover_markdown(markdown: &str) -> (Option<String>, Option<String>) {
+    let text = markdown.trim();
+    let non_empty = |s: &str| {
+        let s = s.trim().trim_start_matches("---").trim();
+        (!s.is_empty()).then(|| s.to_string())
+    };
+
+    let Some(fenced) = text.strip_prefix("```") else {
+        return (None, non_empty(text));
+    };
+    // Skip the language tag on the opening fence
+    let body = fenced.split_once('\n').map(|(_, body)| body).unwrap_or("");
+    match body.find("```") {
+        Some(end) => (non_empty(&body[..end]), non_empty(&body[end + 3..])),
+        None => (non_empty(body), None),
+    }
+}
+
 /// Convert LSP URI to filesystem path
 fn uri_to_path(uri: &Uri) -> Result<PathBuf> {
     let uri_str = uri.as_str();
@@ -558,4 +718,67 @@ mod tests {
         // This would test starting and querying an LSP server
         // Requires an actual LSP server binary in PATH
     }
+
+    #[test]
+    fn test_split_hover_markdown() {
+        let (type_name, docs) = split_hover_markdown("```rust\nfn start(&mut self) -> Result<()>\n```\n\n---\n\nStart the job");
+        assert_eq!(type_name.as_deref(), Some("fn start(&mut self) -> Result<()>"));
+        assert_eq!(docs.as_deref(), Some("Start the job"));
+
+        let (type_name, docs) = split_hover_markdown("```swift\nlet count: Int\n```");
+        assert_eq!(type_name.as_deref(), Some("let count: Int"));
+        assert_eq!(docs, None);
+
+        let (type_name, docs) = split_hover_markdown("Just some docs");
+        assert_eq!(type_name, None);
+        assert_eq!(docs.as_deref(), Some("Just some docs"));
+
+        assert_eq!(split_hover_markdown("  \n"), (None, None));
+    }
+
+    /// Answers `initialize`, then reads requests forever without replying
+    #[cfg(unix)]
+    const SILENT_SERVER: &str = r#"body='{"jsonrpc":"2.0","id":1,"result":{"capabilities":{}}}'
+printf 'Content-Length: %d\r\n\r\n%s' "${#body}" "$body"
+exec cat > /dev/null"#;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_timeouts_mark_server_unhealthy() {
+        let project_root = std::env::temp_dir().join(format!("lsp_timeout_{}", std::process::id()));
+        fs::create_dir_all(&project_root).unwrap();
+        let file = project_root.join("main.swift");
+        fs::write(&file, "let x = 1\n").unwrap();
+
+        let mut query = LspQuery::start("sh", vec!["-c".to_string(), SILENT_SERVER.to_string()], project_root.clone())
+            .unwrap();
+        query.set_request_timeout(Duration::from_millis(200));
+
+        // Each query times out with a structured error instead of hanging
+        let started = std::time::Instant::now();
+        let err = query.find_definition(&file, 1, 5).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        match err.downcast_ref::<LspError>() {
+            Some(LspError::Timeout { method, timeout_ms }) => {
+                assert_eq!(method, "textDocument/definition");
+                assert_eq!(*timeout_ms, 200);
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert!(query.is_healthy());
+
+        // The server process is kept, so the next query reaches it (and times out again)
+        let err = query.find_references_at_position(&file, 1, 5, true).unwrap_err();
+        assert!(matches!(err.downcast_ref::<LspError>(), Some(LspError::Timeout { .. })));
+        assert!(!query.is_healthy());
+
+        // Once unhealthy, queries fail without waiting on the server
+        let started = std::time::Instant::now();
+        let err = query.find_definition(&file, 1, 5).unwrap_err();
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert!(err.to_string().contains("stopped responding"));
+
+        drop(query);
+        let _ = fs::remove_dir_all(&project_root);
+    }
 }
@@ -1,12 +1,21 @@
+pub mod index_filter;
+pub mod index_lock;
 pub mod scip_indexer;
+pub mod scip_export;
 pub mod scip_query_simple;
+pub mod symbol_export;
+pub mod tags;
 pub mod lsp_client;
 pub mod lsp_query;
 pub mod unified_query;
 pub mod swift_lsp;
+#[cfg(test)]
+pub mod scip_fixture;
 
-pub use scip_indexer::ScipIndexer;
-pub use scip_query_simple::ScipQuery;
+pub use index_filter::IndexFilter;
+pub use index_lock::{IndexInProgress, IndexLock};
+pub use scip_indexer::{MissingIndexer, ScipIndexer};
+pub use scip_query_simple::{qualified_symbol_name, symbol_name_and_kind, ScipQuery, SymbolUsage};
 pub use lsp_query::LspQuery;
 pub use unified_query::UnifiedQuery;
-pub use swift_lsp::SwiftLsp;
\ No newline at end of file
+pub use swift_lsp::{set_swift_lsp_config, swift_lsp_config, SwiftLsp, SwiftLspConfig};
\ No newline at end of file
@@ -0,0 +1,146 @@
+use anyhow::{Context, Result};
+use protobuf::{Message, MessageField};
+use scip::types::{Index, Metadata, ToolInfo};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Per-language indexes combined into one SCIP index for the whole project
+#[derive(Debug)]
+pub struct MergedIndex {
+    pub index: Index,
+    /// Documents indexed by more than one language (e.g. a `.js` file in a TypeScript
+    /// project); only the first copy is kept
+    pub duplicate_documents: Vec<String>,
+}
+
+/// Merge per-language SCIP indexes into a single index rooted at `project_root`
+///
+/// Document paths are made relative to `project_root` when an indexer ran in a
+/// subdirectory, and the metadata describes powertools as the producing tool.
+pub fn merge_indexes(indexes: &[Index], project_root: &Path) -> MergedIndex {
+    let root = project_root.canonicalize().unwrap_or_else(|_| project_root.to_path_buf());
+
+    let mut metadata = Metadata::new();
+    metadata.project_root = format!("file://{}", root.display());
+    let mut tool_info = ToolInfo::new();
+    tool_info.name = "powertools".to_string();
+    tool_info.version = env!("CARGO_PKG_VERSION").to_string();
+    metadata.tool_info = MessageField::some(tool_info);
+    if let Some(first) = indexes.iter().find_map(|index| index.metadata.as_ref()) {
+        metadata.version = first.version;
+        metadata.text_document_encoding = first.text_document_encoding;
+    }
+
+    let mut merged = Index::new();
+    merged.metadata = MessageField::some(metadata);
+
+    let mut seen_documents = HashSet::new();
+    let mut seen_symbols = HashSet::new();
+    let mut duplicate_documents = Vec::new();
+
+    for index in indexes {
+        let prefix = index
+            .metadata
+            .as_ref()
+            .and_then(|metadata| subdirectory(&metadata.project_root, &root));
+
+        for document in &index.documents {
+            let mut document = document.clone();
+            if let Some(prefix) = &prefix {
+                document.relative_path = to_slash(&prefix.join(&document.relative_path));
+            }
+
+            if seen_documents.insert(document.relative_path.clone()) {
+                merged.documents.push(document);
+            } else {
+                duplicate_documents.push(document.relative_path);
+            }
+        }
+
+        for symbol in &index.external_symbols {
+            if seen_symbols.insert(symbol.symbol.clone()) {
+                merged.external_symbols.push(symbol.clone());
+            }
+        }
+    }
+
+    MergedIndex { index: merged, duplicate_documents }
+}
+
+/// Serialize an index as a SCIP protobuf file
+pub fn write_index(index: &Index, path: &Path) -> Result<()> {
+    let bytes = index.write_to_bytes().context("Failed to serialize SCIP index")?;
+    std::fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Where an index's own root sits below the export root, if it isn't the root itself
+fn subdirectory(project_root_uri: &str, root: &Path) -> Option<PathBuf> {
+    let index_root = Path::new(project_root_uri.strip_prefix("file://")?);
+    let index_root = index_root.canonicalize().unwrap_or_else(|_| index_root.to_path_buf());
+    let prefix = index_root.strip_prefix(root).ok()?;
+    (!prefix.as_os_str().is_empty()).then(|| prefix.to_path_buf())
+}
+
+/// SCIP paths always use forward slashes
+fn to_slash(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scip::types::{Document, SymbolInformation};
+    use tempfile::TempDir;
+
+    fn index(project_root: &Path, paths: &[&str], external: &[&str]) -> Index {
+        let mut metadata = Metadata::new();
+        metadata.project_root = format!("file://{}", project_root.display());
+        let mut index = Index::new();
+        index.metadata = MessageField::some(metadata);
+        for path in paths {
+            let mut document = Document::new();
+            document.relative_path = path.to_string();
+            index.documents.push(document);
+        }
+        for symbol in external {
+            let mut info = SymbolInformation::new();
+            info.symbol = symbol.to_string();
+            index.external_symbols.push(info);
+        }
+        index
+    }
+
+    #[test]
+    fn test_merged_index_parses_with_scip() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("web")).unwrap();
+
+        let rust = index(&root, &["src/main.rs", "src/lib.rs"], &["rust-analyzer cargo std 1.0 io/"]);
+        let typescript = index(
+            &root.join("web"),
+            &["src/app.ts", "src/util.js"],
+            &["scip-typescript npm react 18.0.0 `index.d.ts`/", "rust-analyzer cargo std 1.0 io/"],
+        );
+        let javascript = index(&root.join("web"), &["src/util.js"], &[]);
+
+        let merged = merge_indexes(&[rust, typescript, javascript], &root);
+        assert_eq!(merged.duplicate_documents, vec!["web/src/util.js"]);
+
+        let path = root.join("index.scip");
+        write_index(&merged.index, &path).unwrap();
+        let parsed = Index::parse_from_bytes(&std::fs::read(&path).unwrap()).unwrap();
+
+        let paths: Vec<&str> = parsed.documents.iter().map(|doc| doc.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["src/main.rs", "src/lib.rs", "web/src/app.ts", "web/src/util.js"]);
+        assert_eq!(parsed.external_symbols.len(), 2);
+
+        let metadata = parsed.metadata.as_ref().unwrap();
+        assert_eq!(metadata.project_root, format!("file://{}", root.display()));
+        assert_eq!(metadata.tool_info.name, "powertools");
+        assert_eq!(metadata.tool_info.version, env!("CARGO_PKG_VERSION"));
+    }
+}
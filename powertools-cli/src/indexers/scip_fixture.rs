@@ -0,0 +1,74 @@
+//! Small hand-written SCIP indexes for tests
+
+use scip::types::{Document, Index, Occurrence, SymbolInformation};
+use std::path::Path;
+
+use super::ScipQuery;
+
+/// Package prefix of every fixture symbol, as rust-analyzer would write it
+pub const SYMBOL_PREFIX: &str = "rust-analyzer cargo demo 0.1.0 ";
+
+/// Builds an index one document and occurrence at a time
+///
+/// Positions are SCIP's: 0-indexed lines and columns.
+#[derive(Default)]
+pub struct ScipFixture {
+    documents: Vec<Document>,
+}
+
+impl ScipFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a document; occurrences and symbols that follow are added to it
+    pub fn document(mut self, relative_path: &str) -> Self {
+        let mut document = Document::new();
+        document.relative_path = relative_path.to_string();
+        self.documents.push(document);
+        self
+    }
+
+    /// The definition of `symbol` (without [`SYMBOL_PREFIX`]), `len` columns long
+    pub fn definition(self, symbol: &str, line: i32, column: i32, len: i32) -> Self {
+        self.occurrence(symbol, line, column, len, 1)
+    }
+
+    /// A reference to `symbol` (without [`SYMBOL_PREFIX`]), `len` columns long
+    pub fn reference(self, symbol: &str, line: i32, column: i32, len: i32) -> Self {
+        self.occurrence(symbol, line, column, len, 0)
+    }
+
+    /// Documentation for `symbol` (without [`SYMBOL_PREFIX`])
+    pub fn documentation(mut self, symbol: &str, documentation: &[&str]) -> Self {
+        let mut info = SymbolInformation::new();
+        info.symbol = format!("{}{}", SYMBOL_PREFIX, symbol);
+        info.documentation = documentation.iter().map(|d| d.to_string()).collect();
+        self.current().symbols.push(info);
+        self
+    }
+
+    pub fn build(self) -> Index {
+        let mut index = Index::new();
+        index.documents = self.documents;
+        index
+    }
+
+    /// The index, queried as if it was made for the project at `root`
+    pub fn query(self, root: &Path) -> ScipQuery {
+        ScipQuery::new(self.build(), root.to_path_buf())
+    }
+
+    fn occurrence(mut self, symbol: &str, line: i32, column: i32, len: i32, roles: i32) -> Self {
+        let mut occurrence = Occurrence::new();
+        occurrence.range = vec![line, column, column + len];
+        occurrence.symbol = format!("{}{}", SYMBOL_PREFIX, symbol);
+        occurrence.symbol_roles = roles;
+        self.current().occurrences.push(occurrence);
+        self
+    }
+
+    fn current(&mut self) -> &mut Document {
+        self.documents.last_mut().expect("call document() before adding occurrences")
+    }
+}
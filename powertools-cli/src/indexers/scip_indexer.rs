@@ -1,21 +1,75 @@
 use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::process::Command;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::sync::Mutex;
 use scip::types::Index;
+use serde::Serialize;
+use crate::indexers::index_lock::{IndexInProgress, IndexLock};
+use crate::indexers::IndexFilter;
 
 /// SCIP indexer that delegates to language-specific indexers
 pub struct ScipIndexer {
     project_root: PathBuf,
     auto_install: bool,
+    jobs: usize,
+    filter: IndexFilter,
+    /// Wait for another run's index lock instead of failing with [`IndexInProgress`]
+    wait_for_lock: bool,
+    /// Held while asking whether to install an indexer, so parallel prompts don't interleave
+    prompt_lock: Mutex<()>,
+}
+
+/// A language's SCIP indexer isn't installed and won't be installed automatically
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingIndexer {
+    pub project_type: ProjectType,
+    pub tool: &'static str,
+    /// Command that installs the tool
+    pub install: &'static str,
+}
+
+impl std::fmt::Display for MissingIndexer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is required to index {:?} but is not installed. Install it with `{}`, or re-run with --auto-install",
+            self.tool, self.project_type, self.install
+        )
+    }
+}
+
+impl std::error::Error for MissingIndexer {}
+
+/// The external indexer a language needs, and how to check for and install it
+struct IndexerTool {
+    name: &'static str,
+    command: &'static str,
+    args: &'static [&'static str],
+    install: &'static str,
+}
+
+/// Outcome of indexing one language
+#[derive(Debug)]
+pub struct LanguageIndex {
+    pub project_type: ProjectType,
+    pub result: Result<PathBuf>,
 }
 
 impl ScipIndexer {
     #[allow(dead_code)]
     pub fn new(project_root: PathBuf) -> Self {
+        // Re-indexing (e.g. from the watcher) keeps the filters `index` was last run with
+        let filter = IndexFilter::load(&project_root).unwrap_or_default();
         Self {
             project_root,
             auto_install: false,
+            jobs: 0,
+            filter,
+            wait_for_lock: true,
+            prompt_lock: Mutex::new(()),
         }
     }
 
@@ -23,18 +77,34 @@ impl ScipIndexer {
         self.auto_install = auto_install;
     }
 
+    /// Limit which files' documents are kept in the generated indexes
+    pub fn set_filter(&mut self, filter: IndexFilter) {
+        self.filter = filter;
+    }
+
+    /// Fail with [`IndexInProgress`] when another run holds the index lock, instead of waiting
+    pub fn set_wait_for_lock(&mut self, wait: bool) {
+        self.wait_for_lock = wait;
+    }
+
+    /// Run at most `jobs` language indexers at once (0 = one per detected language, up to the CPU count)
+    pub fn set_jobs(&mut self, jobs: usize) {
+        self.jobs = jobs;
+    }
+
     /// Generate SCIP indexes for all detected languages in the project
-    pub fn generate_indexes(&self, filter_languages: Vec<String>) -> Result<Vec<PathBuf>> {
+    ///
+    /// Languages are indexed in parallel. A failing indexer doesn't stop the others;
+    /// its error is returned in that language's [`LanguageIndex`].
+    pub fn generate_indexes(&self, filter_languages: Vec<String>) -> Result<Vec<LanguageIndex>> {
         let detected_types = self.detect_project_types();
 
         if detected_types.is_empty() {
             return Err(anyhow!(
-                "Could not detect project type. Supported: TypeScript, JavaScript, Python, Rust, C++"
+                "Could not detect project type. Supported: TypeScript, JavaScript, Python, Rust, C++, Ruby"
             ));
         }
 
-        let mut index_paths = Vec::new();
-
         // Filter by requested languages if specified
         let types_to_index: Vec<ProjectType> = if filter_languages.is_empty() {
             detected_types
@@ -64,26 +134,128 @@ impl ScipIndexer {
                 .join(", ")
         );
 
-        for project_type in types_to_index {
-            let path = match project_type {
-                ProjectType::TypeScript => self.index_typescript(),
-                ProjectType::JavaScript => self.index_javascript(),
-                ProjectType::Python => self.index_python(),
-                ProjectType::Rust => self.index_rust(),
-                ProjectType::CPP => self.index_cpp(),
-            }?;
-            index_paths.push(path);
+        // Nobody can answer an install prompt (e.g. under MCP), so fail missing indexers up front
+        // instead of letting them surface later as spawn failures
+        let missing = if self.auto_install || io::stdin().is_terminal() {
+            Vec::new()
+        } else {
+            self.missing_indexers(&types_to_index, |tool| self.check_indexer_installed(tool.command, tool.args))
+        };
+
+        let _lock = self.lock()?;
+        self.run_all(types_to_index, |project_type| {
+            match missing.iter().find(|m| m.project_type == project_type) {
+                Some(missing) => Err(missing.clone().into()),
+                None => self.index_one(project_type),
+            }
+        })
+    }
+
+    /// Languages whose indexer `is_installed` says is absent
+    fn missing_indexers<F>(&self, types: &[ProjectType], is_installed: F) -> Vec<MissingIndexer>
+    where
+        F: Fn(&IndexerTool) -> bool,
+    {
+        types
+            .iter()
+            .filter(|project_type| !(**project_type == ProjectType::Ruby && self.ruby_bundled()))
+            .map(|project_type| (*project_type, project_type.indexer()))
+            .filter(|(_, tool)| !is_installed(tool))
+            .map(|(project_type, tool)| MissingIndexer {
+                project_type,
+                tool: tool.name,
+                install: tool.install,
+            })
+            .collect()
+    }
+
+    /// Error for a missing indexer the user chose not to install
+    fn missing(&self, project_type: ProjectType) -> anyhow::Error {
+        let tool = project_type.indexer();
+        MissingIndexer {
+            project_type,
+            tool: tool.name,
+            install: tool.install,
         }
+        .into()
+    }
+
+    /// Run `index` for each language on a bounded thread pool, in the order given
+    ///
+    /// Languages that would write an index file another language already claimed are
+    /// reported as errors instead of racing on the same path.
+    fn run_all<F>(&self, types: Vec<ProjectType>, index: F) -> Result<Vec<LanguageIndex>>
+    where
+        F: Fn(ProjectType) -> Result<PathBuf> + Sync,
+    {
+        let mut claimed = HashSet::new();
+        let jobs: Vec<(ProjectType, bool)> = types
+            .into_iter()
+            .map(|project_type| (project_type, claimed.insert(self.get_index_path(&project_type))))
+            .collect();
+
+        let threads = match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()).min(jobs.len()),
+            n => n,
+        };
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.max(1))
+            .build()
+            .context("Failed to start indexing threads")?;
+
+        Ok(pool.install(|| {
+            jobs.into_par_iter()
+                .map(|(project_type, unique)| LanguageIndex {
+                    project_type,
+                    result: if unique {
+                        index(project_type)
+                    } else {
+                        Err(anyhow!(
+                            "{:?} writes {}, which another language is already indexing",
+                            project_type,
+                            self.get_index_path(&project_type).display()
+                        ))
+                    },
+                })
+                .collect()
+        }))
+    }
+
+    /// Take the project's index lock so concurrent runs don't write the same files
+    fn lock(&self) -> Result<IndexLock> {
+        match IndexLock::try_acquire(&self.project_root) {
+            Err(e) if self.wait_for_lock && e.is::<IndexInProgress>() => {
+                println!("Waiting for another index run to finish...");
+                IndexLock::acquire(&self.project_root)
+            }
+            result => result,
+        }
+    }
+
+    fn index_one(&self, project_type: ProjectType) -> Result<PathBuf> {
+        let index_path = match project_type {
+            ProjectType::TypeScript => self.index_typescript(),
+            ProjectType::JavaScript => self.index_javascript(),
+            ProjectType::Python => self.index_python(),
+            ProjectType::Rust => self.index_rust(),
+            ProjectType::CPP => self.index_cpp(),
+            ProjectType::Ruby => self.index_ruby(),
+        }?;
 
-        Ok(index_paths)
+        let removed = self.filter.apply(&index_path)?;
+        if removed > 0 {
+            println!("Filtered {} excluded file(s) out of the {:?} index", removed, project_type);
+        }
+        Ok(index_path)
     }
 
     /// Legacy method for backward compatibility - indexes first detected language
     #[allow(dead_code)]
     pub fn generate_index(&self) -> Result<PathBuf> {
-        let paths = self.generate_indexes(Vec::new())?;
-        paths.into_iter().next()
-            .ok_or_else(|| anyhow!("No indexes generated"))
+        let indexes = self.generate_indexes(Vec::new())?;
+        indexes.into_iter().next()
+            .ok_or_else(|| anyhow!("No indexes generated"))?
+            .result
     }
 
     /// Re-index a specific language
@@ -91,13 +263,52 @@ impl ScipIndexer {
         let project_type = ProjectType::from_language(language)
             .ok_or_else(|| anyhow!("Unsupported language: {:?}", language))?;
 
-        match project_type {
-            ProjectType::TypeScript => self.index_typescript(),
-            ProjectType::JavaScript => self.index_javascript(),
-            ProjectType::Python => self.index_python(),
-            ProjectType::Rust => self.index_rust(),
-            ProjectType::CPP => self.index_cpp(),
+        let _lock = self.lock()?;
+        self.index_one(project_type)
+    }
+
+    /// Drop documents for deleted or moved-away files from a language's index
+    ///
+    /// Returns the number of documents removed. A missing index is not an error.
+    pub fn remove_documents(&self, language: crate::core::Language, removed: &[PathBuf]) -> Result<usize> {
+        use protobuf::Message;
+
+        let project_type = ProjectType::from_language(language)
+            .ok_or_else(|| anyhow!("Unsupported language: {:?}", language))?;
+        let index_path = self.get_index_path(&project_type);
+
+        let _lock = self.lock()?;
+        if !index_path.exists() {
+            return Ok(0);
+        }
+
+        let relative_paths: Vec<String> = removed
+            .iter()
+            .map(|p| {
+                p.strip_prefix(&self.project_root)
+                    .unwrap_or(p)
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+
+        let bytes = std::fs::read(&index_path)
+            .context("Failed to read SCIP index file")?;
+        let mut index = Index::parse_from_bytes(&bytes)
+            .context("Failed to parse SCIP index")?;
+
+        let before = index.documents.len();
+        index.documents.retain(|doc| !relative_paths.contains(&doc.relative_path));
+        let removed_count = before - index.documents.len();
+
+        if removed_count > 0 {
+            let bytes = index.write_to_bytes()
+                .context("Failed to serialize SCIP index")?;
+            std::fs::write(&index_path, bytes)
+                .context("Failed to write SCIP index file")?;
         }
+
+        Ok(removed_count)
     }
 
     /// Read existing SCIP index from disk (legacy method - prefer ScipQuery::from_project)
@@ -159,6 +370,11 @@ impl ScipIndexer {
             types.push(ProjectType::CPP);
         }
 
+        // Check for Ruby
+        if self.project_root.join("Gemfile").exists() || self.has_gemspec() {
+            types.push(ProjectType::Ruby);
+        }
+
         types
     }
 
@@ -178,26 +394,33 @@ impl ScipIndexer {
         false
     }
 
+    fn has_gemspec(&self) -> bool {
+        std::fs::read_dir(&self.project_root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .any(|entry| entry.path().extension().is_some_and(|ext| ext == "gemspec"))
+            })
+            .unwrap_or(false)
+    }
+
     fn index_typescript(&self) -> Result<PathBuf> {
-        println!("Indexing TypeScript project...");
+        self.index_with_scip_typescript(ProjectType::TypeScript)
+    }
+
+    fn index_javascript(&self) -> Result<PathBuf> {
+        // JavaScript uses the same indexer as TypeScript
+        self.index_with_scip_typescript(ProjectType::JavaScript)
+    }
+
+    fn index_with_scip_typescript(&self, project_type: ProjectType) -> Result<PathBuf> {
+        println!("Indexing {:?} project...", project_type);
 
         // Check if scip-typescript is available
         if !self.check_indexer_installed("npx", &["@sourcegraph/scip-typescript", "--help"]) {
             println!("\n⚠️  scip-typescript is not installed.");
 
-            let should_install = if self.auto_install {
-                println!("Auto-installing scip-typescript...");
-                true
-            } else {
-                println!("Would you like to install it? (y/N)");
-                println!("Command: npm install -g @sourcegraph/scip-typescript");
-                print!("> ");
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-                input.trim().to_lowercase() == "y"
-            };
+            let should_install = self.confirm_install("scip-typescript", "Command: npm install -g @sourcegraph/scip-typescript")?;
 
             if should_install {
                 println!("Installing scip-typescript...");
@@ -211,13 +434,15 @@ impl ScipIndexer {
                 }
                 println!("✓ scip-typescript installed successfully!");
             } else {
-                return Err(anyhow!("scip-typescript is required for TypeScript indexing"));
+                return Err(self.missing(project_type));
             }
         }
 
-        // Run scip-typescript indexer
+        // Run scip-typescript indexer, writing straight to the language's own index file
+        let target_path = self.get_index_path(&project_type);
         let status = Command::new("npx")
-            .args(&["@sourcegraph/scip-typescript", "index"])
+            .args(&["@sourcegraph/scip-typescript", "index", "--output"])
+            .arg(&target_path)
             .current_dir(&self.project_root)
             .status()
             .context("Failed to run scip-typescript")?;
@@ -226,23 +451,9 @@ impl ScipIndexer {
             return Err(anyhow!("scip-typescript indexing failed"));
         }
 
-        // Rename the generated index.scip to language-specific name
-        let default_path = self.project_root.join("index.scip");
-        let target_path = self.get_index_path(&ProjectType::TypeScript);
-
-        if default_path.exists() && default_path != target_path {
-            std::fs::rename(&default_path, &target_path)
-                .context("Failed to rename index file")?;
-        }
-
         Ok(target_path)
     }
 
-    fn index_javascript(&self) -> Result<PathBuf> {
-        // JavaScript uses the same indexer as TypeScript
-        self.index_typescript()
-    }
-
     fn index_python(&self) -> Result<PathBuf> {
         println!("Indexing Python project...");
 
@@ -250,19 +461,7 @@ impl ScipIndexer {
         if !self.check_indexer_installed("npx", &["@sourcegraph/scip-python", "--help"]) {
             println!("\n⚠️  scip-python is not installed.");
 
-            let should_install = if self.auto_install {
-                println!("Auto-installing scip-python...");
-                true
-            } else {
-                println!("Would you like to install it? (y/N)");
-                println!("Command: npm install -g @sourcegraph/scip-python");
-                print!("> ");
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-                input.trim().to_lowercase() == "y"
-            };
+            let should_install = self.confirm_install("scip-python", "Command: npm install -g @sourcegraph/scip-python")?;
 
             if should_install {
                 println!("Installing scip-python...");
@@ -276,13 +475,15 @@ impl ScipIndexer {
                 }
                 println!("✓ scip-python installed successfully!");
             } else {
-                return Err(anyhow!("scip-python is required for Python indexing"));
+                return Err(self.missing(ProjectType::Python));
             }
         }
 
         // Run scip-python indexer
+        let target_path = self.get_index_path(&ProjectType::Python);
         let status = Command::new("npx")
-            .args(&["@sourcegraph/scip-python", "index", "."])
+            .args(&["@sourcegraph/scip-python", "index", ".", "--output"])
+            .arg(&target_path)
             .current_dir(&self.project_root)
             .status()
             .context("Failed to run scip-python")?;
@@ -291,15 +492,6 @@ impl ScipIndexer {
             return Err(anyhow!("scip-python indexing failed"));
         }
 
-        // Rename the generated index.scip to language-specific name
-        let default_path = self.project_root.join("index.scip");
-        let target_path = self.get_index_path(&ProjectType::Python);
-
-        if default_path.exists() && default_path != target_path {
-            std::fs::rename(&default_path, &target_path)
-                .context("Failed to rename index file")?;
-        }
-
         Ok(target_path)
     }
 
@@ -310,19 +502,7 @@ impl ScipIndexer {
         if !self.check_indexer_installed("rust-analyzer", &["--version"]) {
             println!("\n⚠️  rust-analyzer is not installed.");
 
-            let should_install = if self.auto_install {
-                println!("Auto-installing rust-analyzer...");
-                true
-            } else {
-                println!("Would you like to install it? (y/N)");
-                println!("Command: rustup component add rust-analyzer");
-                print!("> ");
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-                input.trim().to_lowercase() == "y"
-            };
+            let should_install = self.confirm_install("rust-analyzer", "Command: rustup component add rust-analyzer")?;
 
             if should_install {
                 println!("Installing rust-analyzer...");
@@ -336,13 +516,15 @@ impl ScipIndexer {
                 }
                 println!("✓ rust-analyzer installed successfully!");
             } else {
-                return Err(anyhow!("rust-analyzer is required for Rust indexing"));
+                return Err(self.missing(ProjectType::Rust));
             }
         }
 
         // Run rust-analyzer SCIP indexer
+        let target_path = self.get_index_path(&ProjectType::Rust);
         let status = Command::new("rust-analyzer")
-            .args(&["scip", "."])
+            .args(&["scip", ".", "--output"])
+            .arg(&target_path)
             .current_dir(&self.project_root)
             .status()
             .context("Failed to run rust-analyzer scip")?;
@@ -351,15 +533,6 @@ impl ScipIndexer {
             return Err(anyhow!("rust-analyzer SCIP indexing failed"));
         }
 
-        // Rename the generated index.scip to language-specific name
-        let default_path = self.project_root.join("index.scip");
-        let target_path = self.get_index_path(&ProjectType::Rust);
-
-        if default_path.exists() && default_path != target_path {
-            std::fs::rename(&default_path, &target_path)
-                .context("Failed to rename index file")?;
-        }
-
         Ok(target_path)
     }
 
@@ -383,24 +556,12 @@ impl ScipIndexer {
         if !self.check_indexer_installed("scip-clang", &["--version"]) {
             println!("\n⚠️  scip-clang is not installed.");
 
-            let should_install = if self.auto_install {
-                println!("Auto-installing scip-clang...");
-                true
-            } else {
-                println!("Would you like to install it? (y/N)");
-                println!("scip-clang will be downloaded from GitHub releases");
-                print!("> ");
-                io::stdout().flush()?;
-
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
-                input.trim().to_lowercase() == "y"
-            };
+            let should_install = self.confirm_install("scip-clang", "scip-clang will be downloaded from GitHub releases")?;
 
             if should_install {
                 self.install_scip_clang()?;
             } else {
-                return Err(anyhow!("scip-clang is required for C++ indexing"));
+                return Err(self.missing(ProjectType::CPP));
             }
         }
 
@@ -422,10 +583,12 @@ impl ScipIndexer {
             }
         };
 
+        let target_path = self.get_index_path(&ProjectType::CPP);
         let status = Command::new(&scip_clang_cmd)
             .args(&[
                 "--compdb-path=compile_commands.json",
             ])
+            .arg(format!("--index-output-path={}", target_path.display()))
             .current_dir(&self.project_root)
             .status()
             .context("Failed to run scip-clang")?;
@@ -434,18 +597,64 @@ impl ScipIndexer {
             return Err(anyhow!("scip-clang indexing failed"));
         }
 
-        // Rename the generated index.scip to language-specific name
-        let default_path = self.project_root.join("index.scip");
-        let target_path = self.get_index_path(&ProjectType::CPP);
+        Ok(target_path)
+    }
+
+    fn index_ruby(&self) -> Result<PathBuf> {
+        println!("Indexing Ruby project...");
 
-        if default_path.exists() && default_path != target_path {
-            std::fs::rename(&default_path, &target_path)
-                .context("Failed to rename index file")?;
+        let bundled = self.ruby_bundled();
+
+        // Check if scip-ruby is available
+        if !bundled && !self.check_indexer_installed("scip-ruby", &["--version"]) {
+            println!("\n⚠️  scip-ruby is not installed.");
+
+            let should_install = self.confirm_install("scip-ruby", "Command: gem install scip-ruby")?;
+
+            if should_install {
+                println!("Installing scip-ruby...");
+                let status = Command::new("gem")
+                    .args(&["install", "scip-ruby"])
+                    .status()
+                    .context("Failed to install scip-ruby. Is RubyGems installed?")?;
+
+                if !status.success() {
+                    return Err(anyhow!("Installation failed"));
+                }
+                println!("✓ scip-ruby installed successfully!");
+            } else {
+                return Err(self.missing(ProjectType::Ruby));
+            }
+        }
+
+        // Run scip-ruby indexer, through Bundler when the project pins it
+        let mut command = if bundled {
+            let mut command = Command::new("bundle");
+            command.args(&["exec", "scip-ruby"]);
+            command
+        } else {
+            Command::new("scip-ruby")
+        };
+        let target_path = self.get_index_path(&ProjectType::Ruby);
+        let status = command
+            .args(&[".", "--index-file"])
+            .arg(&target_path)
+            .current_dir(&self.project_root)
+            .status()
+            .context("Failed to run scip-ruby")?;
+
+        if !status.success() {
+            return Err(anyhow!("scip-ruby indexing failed"));
         }
 
         Ok(target_path)
     }
 
+    /// A Gemfile that pins scip-ruby provides it through `bundle exec`
+    fn ruby_bundled(&self) -> bool {
+        std::fs::read_to_string(self.project_root.join("Gemfile.lock")).is_ok_and(|lock| lock.contains("scip-ruby"))
+    }
+
     fn install_scip_clang(&self) -> Result<()> {
         println!("Installing scip-clang...");
 
@@ -526,9 +735,27 @@ impl ScipIndexer {
         Ok(())
     }
 
+    /// Whether to install a missing indexer: always with auto-install, otherwise ask
+    fn confirm_install(&self, tool: &str, hint: &str) -> Result<bool> {
+        if self.auto_install {
+            println!("Auto-installing {}...", tool);
+            return Ok(true);
+        }
+
+        let _prompt = self.prompt_lock.lock().unwrap_or_else(|e| e.into_inner());
+        println!("Would you like to install {}? (y/N)", tool);
+        println!("{}", hint);
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        Ok(input.trim().to_lowercase() == "y")
+    }
+
     fn check_indexer_installed(&self, command: &str, args: &[&str]) -> bool {
-        // First try the command directly (checks PATH)
-        if Command::new(command).args(args).output().is_ok() {
+        // First try the command directly (checks PATH); npx runs but fails for a missing package
+        if Command::new(command).args(args).output().is_ok_and(|output| output.status.success()) {
             return true;
         }
 
@@ -552,6 +779,7 @@ impl ScipIndexer {
             ProjectType::Python => "index.python.scip",
             ProjectType::Rust => "index.rust.scip",
             ProjectType::CPP => "index.cpp.scip",
+            ProjectType::Ruby => "index.ruby.scip",
         };
         self.project_root.join(filename)
     }
@@ -564,19 +792,21 @@ impl ScipIndexer {
             self.project_root.join("index.python.scip"),
             self.project_root.join("index.rust.scip"),
             self.project_root.join("index.cpp.scip"),
+            self.project_root.join("index.ruby.scip"),
             // Legacy path for backward compatibility
             self.project_root.join("index.scip"),
         ]
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum ProjectType {
     TypeScript,
     JavaScript,
     Python,
     Rust,
     CPP,
+    Ruby,
 }
 
 impl ProjectType {
@@ -589,9 +819,46 @@ impl ProjectType {
             crate::core::Language::Rust => Some(ProjectType::Rust),
             crate::core::Language::Cpp => Some(ProjectType::CPP),
             crate::core::Language::C => Some(ProjectType::CPP),
+            crate::core::Language::Ruby => Some(ProjectType::Ruby),
             _ => None,
         }
     }
+
+    fn indexer(&self) -> IndexerTool {
+        match self {
+            ProjectType::TypeScript | ProjectType::JavaScript => IndexerTool {
+                name: "scip-typescript",
+                command: "npx",
+                args: &["@sourcegraph/scip-typescript", "--help"],
+                install: "npm install -g @sourcegraph/scip-typescript",
+            },
+            ProjectType::Python => IndexerTool {
+                name: "scip-python",
+                command: "npx",
+                args: &["@sourcegraph/scip-python", "--help"],
+                install: "npm install -g @sourcegraph/scip-python",
+            },
+            ProjectType::Rust => IndexerTool {
+                name: "rust-analyzer",
+                command: "rust-analyzer",
+                args: &["--version"],
+                install: "rustup component add rust-analyzer",
+            },
+            // Downloaded from GitHub releases for the current platform
+            ProjectType::CPP => IndexerTool {
+                name: "scip-clang",
+                command: "scip-clang",
+                args: &["--version"],
+                install: "powertools index --languages cpp --auto-install",
+            },
+            ProjectType::Ruby => IndexerTool {
+                name: "scip-ruby",
+                command: "scip-ruby",
+                args: &["--version"],
+                install: "gem install scip-ruby",
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -602,4 +869,127 @@ mod tests {
     fn test_detect_project_type() {
         // Test would require setting up temporary project files
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_remove_documents() {
+        use protobuf::Message;
+        use scip::types::Document;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+
+        let mut index = Index::new();
+        for path in ["src/keep.rs", "src/old.rs"] {
+            let mut doc = Document::new();
+            doc.relative_path = path.to_string();
+            index.documents.push(doc);
+        }
+        std::fs::write(root.join("index.rust.scip"), index.write_to_bytes().unwrap()).unwrap();
+
+        let indexer = ScipIndexer::new(root.clone());
+        let removed = indexer
+            .remove_documents(crate::core::Language::Rust, &[root.join("src/old.rs")])
+            .unwrap();
+        assert_eq!(removed, 1);
+
+        let bytes = std::fs::read(root.join("index.rust.scip")).unwrap();
+        let index = Index::parse_from_bytes(&bytes).unwrap();
+        let paths: Vec<_> = index.documents.iter().map(|d| d.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["src/keep.rs"]);
+
+        // No index for this language yet
+        assert_eq!(
+            indexer.remove_documents(crate::core::Language::Python, &[root.join("a.py")]).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_failed_language_does_not_stop_others() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut indexer = ScipIndexer::new(temp.path().to_path_buf());
+        indexer.set_jobs(2);
+
+        let runs = AtomicUsize::new(0);
+        let results = indexer
+            .run_all(
+                vec![ProjectType::TypeScript, ProjectType::Python, ProjectType::Rust, ProjectType::Rust],
+                |project_type| {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    match project_type {
+                        ProjectType::Python => Err(anyhow!("scip-python crashed")),
+                        other => Ok(indexer.get_index_path(&other)),
+                    }
+                },
+            )
+            .unwrap();
+
+        let summary: Vec<_> = results
+            .iter()
+            .map(|r| (r.project_type, r.result.as_ref().map_err(|e| e.to_string())))
+            .collect();
+        assert_eq!(summary.len(), 4);
+        assert_eq!(summary[0], (ProjectType::TypeScript, Ok(&temp.path().join("index.typescript.scip"))));
+        assert_eq!(summary[1], (ProjectType::Python, Err("scip-python crashed".to_string())));
+        assert_eq!(summary[2], (ProjectType::Rust, Ok(&temp.path().join("index.rust.scip"))));
+
+        // The second Rust job would write the same file, so it never runs
+        assert!(summary[3].1.as_ref().unwrap_err().contains("already indexing"));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_missing_indexer_names_install_command() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let indexer = ScipIndexer::new(temp.path().to_path_buf());
+
+        assert!(!indexer.check_indexer_installed("powertools-no-such-indexer", &["--version"]));
+
+        // Pretend only rust-analyzer is missing
+        let missing = indexer.missing_indexers(&[ProjectType::TypeScript, ProjectType::Rust], |tool| {
+            tool.name != "rust-analyzer"
+        });
+        assert_eq!(
+            missing,
+            vec![MissingIndexer {
+                project_type: ProjectType::Rust,
+                tool: "rust-analyzer",
+                install: "rustup component add rust-analyzer",
+            }]
+        );
+
+        let error: anyhow::Error = missing[0].clone().into();
+        assert!(error.to_string().contains("`rustup component add rust-analyzer`"));
+        assert_eq!(error.downcast_ref::<MissingIndexer>(), Some(&missing[0]));
+
+        // A project that pins scip-ruby through Bundler doesn't need it installed
+        std::fs::write(temp.path().join("Gemfile.lock"), "    scip-ruby (0.3.0)\n").unwrap();
+        assert!(indexer.missing_indexers(&[ProjectType::Ruby], |_| false).is_empty());
+    }
+
+    #[test]
+    fn test_index_in_progress_blocks_writes() {
+        use protobuf::Message;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        std::fs::write(root.join("index.rust.scip"), Index::new().write_to_bytes().unwrap()).unwrap();
+
+        let mut indexer = ScipIndexer::new(root.clone());
+        indexer.set_wait_for_lock(false);
+
+        let lock = IndexLock::acquire(&root).unwrap();
+        let err = indexer
+            .remove_documents(crate::core::Language::Rust, &[root.join("src/old.rs")])
+            .unwrap_err();
+        assert!(err.is::<IndexInProgress>());
+
+        drop(lock);
+        assert_eq!(
+            indexer.remove_documents(crate::core::Language::Rust, &[root.join("src/old.rs")]).unwrap(),
+            0
+        );
+    }
+}
@@ -1,7 +1,121 @@
 use anyhow::{anyhow, Result};
 use scip::types::Index;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use crate::core::{Location, Reference, ReferenceKind};
+use crate::core::{Location, Reference, ReferenceKind, SymbolKind, TypeInfo};
+use crate::indexers::lsp_query::split_hover_markdown;
+
+/// A symbol defined in the indexed project, with every other place it occurs
+#[derive(Debug, Clone)]
+pub struct SymbolUsage {
+    /// Full SCIP symbol string
+    pub symbol: String,
+    pub definition: Location,
+    pub references: Vec<Location>,
+
+    /// Markdown documentation blocks from the index (often the signature first)
+    pub documentation: Vec<String>,
+}
+
+/// Name and kind of a SCIP symbol, from its last descriptor
+/// (`rust-analyzer cargo demo 0.1.0 jobs/Job#start().` -> `start`, Method)
+/// Returns None for local symbols and meta descriptors
+pub fn symbol_name_and_kind(symbol: &str) -> Option<(String, SymbolKind)> {
+    if symbol.starts_with("local ") {
+        return None;
+    }
+    // "<scheme> <manager> <package> <version> <descriptors>"
+    let descriptors = parse_descriptors(symbol.splitn(5, ' ').nth(4)?)?;
+    let (name, suffix) = descriptors.last()?.clone();
+    let in_type = descriptors.len() > 1 && descriptors[descriptors.len() - 2].1 == '#';
+
+    let kind = match suffix {
+        '/' => SymbolKind::Module,
+        '#' => SymbolKind::Class,
+        '(' if in_type => SymbolKind::Method,
+        '(' => SymbolKind::Function,
+        '.' if in_type => SymbolKind::Field,
+        '.' => SymbolKind::Variable,
+        '[' => SymbolKind::TypeParameter,
+        ')' => SymbolKind::Variable,
+        '!' => SymbolKind::Macro,
+        _ => return None,
+    };
+    Some((name, kind))
+}
+
+/// Descriptor path of a SCIP symbol without type parameters or parameters
+/// (`rust-analyzer cargo demo 0.1.0 jobs/Job#start().` -> `jobs::Job::start`)
+/// Returns None for local symbols
+pub fn qualified_symbol_name(symbol: &str) -> Option<String> {
+    if symbol.starts_with("local ") {
+        return None;
+    }
+    let descriptors = parse_descriptors(symbol.splitn(5, ' ').nth(4)?)?;
+    let names: Vec<String> = descriptors
+        .into_iter()
+        .filter(|(_, suffix)| !matches!(suffix, '[' | ')'))
+        .map(|(name, _)| name)
+        .collect();
+    (!names.is_empty()).then(|| names.join("::"))
+}
+
+/// Split SCIP descriptors into (name, suffix) pairs; methods use `(` as their suffix,
+/// type parameters `[` and parameters `)`
+fn parse_descriptors(mut rest: &str) -> Option<Vec<(String, char)>> {
+    let mut descriptors = Vec::new();
+
+    while !rest.is_empty() {
+        if let Some(inner) = rest.strip_prefix('[') {
+            let end = inner.find(']')?;
+            descriptors.push((inner[..end].to_string(), '['));
+            rest = &inner[end + 1..];
+            continue;
+        }
+        if let Some(inner) = rest.strip_prefix('(') {
+            let end = inner.find(')')?;
+            descriptors.push((inner[..end].to_string(), ')'));
+            rest = &inner[end + 1..];
+            continue;
+        }
+
+        let (name, after) = if let Some(escaped) = rest.strip_prefix('`') {
+            // Backticks are escaped by doubling them
+            let mut name = String::new();
+            let mut chars = escaped.char_indices().peekable();
+            let mut end = None;
+            while let Some((i, c)) = chars.next() {
+                if c == '`' {
+                    if chars.peek().map(|&(_, next)| next) == Some('`') {
+                        chars.next();
+                        name.push('`');
+                        continue;
+                    }
+                    end = Some(i + 1);
+                    break;
+                }
+                name.push(c);
+            }
+            (name, &escaped[end?..])
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '+' | '-' | '$')))
+                .unwrap_or(rest.len());
+            (rest[..end].to_string(), &rest[end..])
+        };
+
+        let suffix = after.chars().next()?;
+        rest = &after[suffix.len_utf8()..];
+        if suffix == '(' {
+            // Method disambiguator: `name(+1).`
+            let end = rest.find(')')?;
+            rest = rest[end + 1..].strip_prefix('.')?;
+        }
+        descriptors.push((name, suffix));
+    }
+
+    Some(descriptors)
+}
 
 /// SCIP query implementation supporting multiple language indexes
 pub struct ScipQuery {
@@ -31,9 +145,15 @@ impl ScipQuery {
             "index.python.scip",
             "index.rust.scip",
             "index.cpp.scip",
+            "index.ruby.scip",
             "index.scip", // Legacy fallback
         ] {
             let path = project_root.join(filename);
+            // `export-scip` writes a merged index.scip; reading it alongside the
+            // per-language indexes would count every symbol twice
+            if *filename == "index.scip" && !indexes.is_empty() {
+                continue;
+            }
             if path.exists() {
                 match std::fs::read(&path) {
                     Ok(bytes) => {
@@ -65,6 +185,11 @@ impl ScipQuery {
         Ok(Self { indexes, project_root })
     }
 
+    /// The loaded indexes, one per language
+    pub fn indexes(&self) -> &[Index] {
+        &self.indexes
+    }
+
     /// Find the definition of a symbol at a given location
     pub fn find_definition(&self, file_path: &Path, line: usize, column: usize) -> Result<Option<Location>> {
         // Make file_path relative to project_root
@@ -148,6 +273,203 @@ impl ScipQuery {
         Ok(None)
     }
 
+    /// Find every occurrence of the exact symbol at a location
+    /// Unlike `find_references`, this never matches other symbols whose names merely contain the same text
+    pub fn find_symbol_occurrences(&self, file_path: &Path, line: usize, column: usize) -> Result<Vec<Reference>> {
+        let relative_path = if file_path.is_absolute() {
+            file_path.strip_prefix(&self.project_root)
+                .unwrap_or(file_path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            file_path.to_string_lossy().to_string()
+        };
+
+        const DEFINITION_ROLE: i32 = 1;
+        let target_line = line.saturating_sub(1) as i32;
+        let target_col = column.saturating_sub(1) as i32;
+
+        let symbol = self.indexes.iter()
+            .flat_map(|index| index.documents.iter())
+            .filter(|doc| doc.relative_path == relative_path)
+            .flat_map(|doc| doc.occurrences.iter())
+            .find(|occ| {
+                occ.range.len() >= 3
+                    && occ.range[0] == target_line
+                    && target_col >= occ.range[1]
+                    && target_col < occ.range[2]
+            })
+            .map(|occ| occ.symbol.clone());
+
+        let Some(symbol) = symbol else {
+            return Ok(Vec::new());
+        };
+        // Local symbols ("local 3") are only unique within their document
+        let is_local = symbol.starts_with("local ");
+
+        let mut references = Vec::new();
+        for index in &self.indexes {
+            for document in &index.documents {
+                if is_local && document.relative_path != relative_path {
+                    continue;
+                }
+                for occurrence in &document.occurrences {
+                    if occurrence.symbol != symbol || occurrence.range.len() < 3 {
+                        continue;
+                    }
+                    let is_definition = occurrence.symbol_roles & DEFINITION_ROLE != 0;
+                    references.push(Reference {
+                        location: Location {
+                            file_path: self.project_root.join(&document.relative_path),
+                            line: (occurrence.range[0] as usize) + 1,
+                            column: (occurrence.range[1] as usize) + 1,
+                            end_line: Some((occurrence.range.get(3).unwrap_or(&occurrence.range[0]) + 1) as usize),
+                            end_column: Some((*occurrence.range.get(4).unwrap_or(&occurrence.range[2]) as usize) + 1),
+                        },
+                        kind: if is_definition { ReferenceKind::Definition } else { ReferenceKind::Reference },
+                        context: None,
+                    });
+                }
+            }
+        }
+
+        Ok(references)
+    }
+
+    /// Signature and documentation recorded in the index for the symbol at a location
+    /// Returns None when no occurrence covers the position or the symbol has no documentation
+    pub fn hover(&self, file_path: &Path, line: usize, column: usize) -> Result<Option<TypeInfo>> {
+        let relative_path = if file_path.is_absolute() {
+            file_path.strip_prefix(&self.project_root)
+                .unwrap_or(file_path)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            file_path.to_string_lossy().to_string()
+        };
+
+        let target_line = line.saturating_sub(1) as i32;
+        let target_col = column.saturating_sub(1) as i32;
+
+        let occurrence = self.indexes.iter()
+            .flat_map(|index| index.documents.iter())
+            .filter(|doc| doc.relative_path == relative_path)
+            .flat_map(|doc| doc.occurrences.iter())
+            .find(|occ| {
+                occ.range.len() >= 3
+                    && occ.range[0] == target_line
+                    && target_col >= occ.range[1]
+                    && target_col < occ.range[2]
+            });
+        let Some(occurrence) = occurrence else {
+            return Ok(None);
+        };
+
+        // Local symbols are only documented in their own document
+        let is_local = occurrence.symbol.starts_with("local ");
+        let mut documentation = None;
+        for index in &self.indexes {
+            let mut symbols = index.documents.iter()
+                .filter(|doc| !is_local || doc.relative_path == relative_path)
+                .flat_map(|doc| doc.symbols.iter())
+                .chain(index.external_symbols.iter());
+            if let Some(info) = symbols
+                .find(|info| info.symbol == occurrence.symbol && !info.documentation.is_empty())
+            {
+                documentation = Some(info.documentation.join("\n\n"));
+                break;
+            }
+        }
+        let Some(documentation) = documentation else {
+            return Ok(None);
+        };
+
+        let (type_name, documentation) = split_hover_markdown(&documentation);
+        Ok(Some(TypeInfo {
+            type_name,
+            documentation,
+            location: Location {
+                file_path: self.project_root.join(&relative_path),
+                line: (occurrence.range[0] as usize) + 1,
+                column: (occurrence.range[1] as usize) + 1,
+                end_line: Some((occurrence.range.get(3).unwrap_or(&occurrence.range[0]) + 1) as usize),
+                end_column: Some((*occurrence.range.get(4).unwrap_or(&occurrence.range[2]) as usize) + 1),
+            },
+        }))
+    }
+
+    /// (implementing symbol, implemented symbol) pairs from `is_implementation` relationships,
+    /// e.g. a class and the interface it implements
+    pub fn implementation_relationships(&self) -> Vec<(String, String)> {
+        let mut pairs: Vec<(String, String)> = self.indexes.iter()
+            .flat_map(|index| index.documents.iter())
+            .flat_map(|doc| doc.symbols.iter())
+            .flat_map(|info| {
+                info.relationships.iter()
+                    .filter(|relationship| relationship.is_implementation)
+                    .map(move |relationship| (info.symbol.clone(), relationship.symbol.clone()))
+            })
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    /// Every non-local symbol defined in the project, with its non-definition occurrences
+    /// Symbols only referenced (defined in dependencies) are left out
+    pub fn symbol_usages(&self) -> Vec<SymbolUsage> {
+        const DEFINITION_ROLE: i32 = 1;
+
+        let mut definitions: HashMap<&str, Location> = HashMap::new();
+        let mut references: HashMap<&str, Vec<Location>> = HashMap::new();
+        let mut documentation: HashMap<&str, &[String]> = HashMap::new();
+
+        for index in &self.indexes {
+            for document in &index.documents {
+                for info in &document.symbols {
+                    if !info.documentation.is_empty() {
+                        documentation.entry(info.symbol.as_str()).or_insert(&info.documentation);
+                    }
+                }
+                for occurrence in &document.occurrences {
+                    if occurrence.symbol.is_empty()
+                        || occurrence.symbol.starts_with("local ")
+                        || occurrence.range.len() < 3
+                    {
+                        continue;
+                    }
+                    let location = Location {
+                        file_path: self.project_root.join(&document.relative_path),
+                        line: (occurrence.range[0] as usize) + 1,
+                        column: (occurrence.range[1] as usize) + 1,
+                        end_line: Some((occurrence.range.get(3).unwrap_or(&occurrence.range[0]) + 1) as usize),
+                        end_column: Some((*occurrence.range.get(4).unwrap_or(&occurrence.range[2]) as usize) + 1),
+                    };
+                    if occurrence.symbol_roles & DEFINITION_ROLE != 0 {
+                        definitions.entry(occurrence.symbol.as_str()).or_insert(location);
+                    } else {
+                        references.entry(occurrence.symbol.as_str()).or_default().push(location);
+                    }
+                }
+            }
+        }
+
+        let mut usages: Vec<SymbolUsage> = definitions
+            .into_iter()
+            .map(|(symbol, definition)| SymbolUsage {
+                symbol: symbol.to_string(),
+                definition,
+                references: references.remove(symbol).unwrap_or_default(),
+                documentation: documentation.get(symbol).map(|docs| docs.to_vec()).unwrap_or_default(),
+            })
+            .collect();
+        usages.sort_by(|a, b| {
+            (&a.definition.file_path, a.definition.line, a.definition.column)
+                .cmp(&(&b.definition.file_path, b.definition.line, b.definition.column))
+        });
+        usages
+    }
+
     /// Find all references to a symbol
     pub fn find_references(&self, symbol_name: &str, include_declarations: bool) -> Result<Vec<Reference>> {
         let mut references = Vec::new();
@@ -212,3 +534,47 @@ impl ScipQuery {
         Ok(references)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(symbol: &str) -> Option<(String, String)> {
+        symbol_name_and_kind(symbol).map(|(name, kind)| (name, format!("{:?}", kind)))
+    }
+
+    #[test]
+    fn test_symbol_name_and_kind() {
+        let kind = |descriptors: &str| kind_of(&format!("rust-analyzer cargo demo 0.1.0 {}", descriptors));
+
+        assert_eq!(kind("jobs/run_job()."), Some(("run_job".into(), "Function".into())));
+        assert_eq!(kind("jobs/Job#start(+1)."), Some(("start".into(), "Method".into())));
+        assert_eq!(kind("jobs/Job#id."), Some(("id".into(), "Field".into())));
+        assert_eq!(kind("jobs/Job#"), Some(("Job".into(), "Class".into())));
+        assert_eq!(kind("jobs/"), Some(("jobs".into(), "Module".into())));
+        assert_eq!(kind("jobs/Job#[T]"), Some(("T".into(), "TypeParameter".into())));
+        assert_eq!(kind("jobs/log!"), Some(("log".into(), "Macro".into())));
+        assert_eq!(
+            kind_of("scip-typescript npm demo 1.0.0 src/`app.ts`/Server#"),
+            Some(("Server".into(), "Class".into()))
+        );
+        assert_eq!(kind_of("local 12"), None);
+    }
+
+    #[test]
+    fn test_qualified_symbol_name() {
+        assert_eq!(
+            qualified_symbol_name("rust-analyzer cargo demo 0.1.0 jobs/Job#start(+1)."),
+            Some("jobs::Job::start".to_string())
+        );
+        assert_eq!(
+            qualified_symbol_name("rust-analyzer cargo demo 0.1.0 jobs/Job#[T]"),
+            Some("jobs::Job".to_string())
+        );
+        assert_eq!(
+            qualified_symbol_name("scip-typescript npm demo 1.0.0 src/`app.ts`/Server#listen()."),
+            Some("src::app.ts::Server::listen".to_string())
+        );
+        assert_eq!(qualified_symbol_name("local 3"), None);
+    }
+}
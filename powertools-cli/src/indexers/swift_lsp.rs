@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
 use crate::indexers::{UnifiedQuery, LspQuery};
 
 /// Swift language support via sourcekit-lsp
@@ -10,7 +11,7 @@ use crate::indexers::{UnifiedQuery, LspQuery};
 /// Swift-specific conveniences.
 ///
 /// ## Requirements
-/// - sourcekit-lsp must be installed and available in PATH
+/// - sourcekit-lsp must be installed; see [`SwiftLsp::resolve`] for where it's looked for
 /// - Typically comes with Xcode or Swift toolchain
 /// - macOS: Install Xcode Command Line Tools
 /// - Linux: Install Swift from https://swift.org/download/
@@ -21,54 +22,123 @@ use crate::indexers::{UnifiedQuery, LspQuery};
 /// - Supports Swift 5.x and later
 pub struct SwiftLsp;
 
+/// Where to find sourcekit-lsp and how to start it
+///
+/// Read from the environment (`SOURCEKIT_LSP_PATH`, `SOURCEKIT_TOOLCHAIN_PATH`,
+/// `SOURCEKIT_SDK_PATH`); CLI flags override it via [`set_swift_lsp_config`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwiftLspConfig {
+    /// Explicit sourcekit-lsp binary
+    pub path: Option<PathBuf>,
+    /// Toolchain directory (`.../XcodeDefault.xctoolchain`); its `usr/bin/sourcekit-lsp` is used
+    pub toolchain: Option<PathBuf>,
+    /// SDK to build against, passed to the compiler as `-sdk`
+    pub sdk: Option<PathBuf>,
+}
+
+impl SwiftLspConfig {
+    /// Configuration from `SOURCEKIT_*` environment variables
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        Self {
+            path: var("SOURCEKIT_LSP_PATH"),
+            toolchain: var("SOURCEKIT_TOOLCHAIN_PATH"),
+            sdk: var("SOURCEKIT_SDK_PATH"),
+        }
+    }
+
+    /// Arguments sourcekit-lsp is started with
+    pub fn server_args(&self) -> Vec<String> {
+        match &self.sdk {
+            Some(sdk) => vec![
+                "-Xswiftc".to_string(),
+                "-sdk".to_string(),
+                "-Xswiftc".to_string(),
+                sdk.to_string_lossy().to_string(),
+            ],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Process-wide configuration used by every `SwiftLsp` entry point
+static CONFIG: Mutex<Option<SwiftLspConfig>> = Mutex::new(None);
+
+/// Set the sourcekit-lsp configuration for queries and refactors started after this call
+pub fn set_swift_lsp_config(config: SwiftLspConfig) {
+    *CONFIG.lock().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+/// The configuration set with [`set_swift_lsp_config`], or the environment's
+pub fn swift_lsp_config() -> SwiftLspConfig {
+    CONFIG
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(SwiftLspConfig::from_env)
+}
+
 impl SwiftLsp {
     /// Check if sourcekit-lsp is available on the system
     ///
     /// # Returns
-    /// - Ok(path) if sourcekit-lsp is found in PATH
-    /// - Err if sourcekit-lsp is not available
+    /// - Ok(path) of the binary from the current [`SwiftLspConfig`]
+    /// - Err explaining how to install or point to sourcekit-lsp
     pub fn check_available() -> Result<PathBuf> {
-        // Try to find sourcekit-lsp in common locations
-        let possible_paths = vec![
-            "sourcekit-lsp",  // In PATH
-            "/usr/bin/sourcekit-lsp",
-            "/usr/local/bin/sourcekit-lsp",
-            "/Applications/Xcode.app/Contents/Developer/Toolchains/XcodeDefault.xctoolchain/usr/bin/sourcekit-lsp",
-        ];
-
-        for path_str in &possible_paths {
-            let result = Command::new("which")
-                .arg(path_str)
-                .output();
-
-            if let Ok(output) = result {
-                if output.status.success() {
-                    let path = String::from_utf8_lossy(&output.stdout)
-                        .trim()
-                        .to_string();
-                    if !path.is_empty() {
-                        return Ok(PathBuf::from(path));
-                    }
-                }
+        Self::resolve(&swift_lsp_config())
+    }
+
+    /// Find sourcekit-lsp: the configured path, then the configured toolchain,
+    /// then `xcrun --find` on macOS, then `PATH`
+    pub fn resolve(config: &SwiftLspConfig) -> Result<PathBuf> {
+        if let Some(path) = &config.path {
+            if path.is_file() {
+                return Ok(path.clone());
             }
+            return Err(anyhow::anyhow!(
+                "sourcekit-lsp not found at {} (from --sourcekit-lsp or SOURCEKIT_LSP_PATH)",
+                path.display()
+            ));
+        }
 
-            // Also try direct execution test
-            let result = Command::new(path_str)
-                .arg("--version")
-                .output();
+        if let Some(toolchain) = &config.toolchain {
+            let path = toolchain.join("usr/bin/sourcekit-lsp");
+            if path.is_file() {
+                return Ok(path);
+            }
+            return Err(anyhow::anyhow!(
+                "Toolchain {} has no usr/bin/sourcekit-lsp (from --swift-toolchain or SOURCEKIT_TOOLCHAIN_PATH)",
+                toolchain.display()
+            ));
+        }
 
-            if let Ok(output) = result {
-                if output.status.success() {
-                    return Ok(PathBuf::from(*path_str));
-                }
+        if cfg!(target_os = "macos") {
+            if let Some(path) = Self::xcrun_find() {
+                return Ok(path);
             }
         }
 
+        if let Some(path) = find_in_path("sourcekit-lsp") {
+            return Ok(path);
+        }
+
         Err(anyhow::anyhow!(
-            "sourcekit-lsp not found. Please install Xcode Command Line Tools (macOS) or Swift toolchain."
+            "sourcekit-lsp not found. Install the Xcode Command Line Tools (macOS: `xcode-select --install`) \
+             or a Swift toolchain from https://swift.org/download/, or point to it with \
+             --sourcekit-lsp <path> / SOURCEKIT_LSP_PATH, or --swift-toolchain <dir> / SOURCEKIT_TOOLCHAIN_PATH."
         ))
     }
 
+    /// `xcrun --find sourcekit-lsp`, which honors the selected Xcode and `TOOLCHAINS`
+    fn xcrun_find() -> Option<PathBuf> {
+        let output = Command::new("xcrun").args(["--find", "sourcekit-lsp"]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        path.is_file().then_some(path)
+    }
+
     /// Create a UnifiedQuery instance for Swift using sourcekit-lsp
     ///
     /// # Arguments
@@ -87,10 +157,8 @@ impl SwiftLsp {
         let lsp_path = Self::check_available()
             .context("sourcekit-lsp is required for Swift navigation")?;
 
-        // Create LSP query with sourcekit-lsp
-        // sourcekit-lsp doesn't need special arguments, just the root URI
         let lsp_command = lsp_path.to_string_lossy().to_string();
-        let lsp_args = vec![]; // sourcekit-lsp is started without args
+        let lsp_args = swift_lsp_config().server_args();
 
         UnifiedQuery::lsp_only(&lsp_command, lsp_args, project_root)
             .context("Failed to start sourcekit-lsp server")
@@ -108,9 +176,8 @@ impl SwiftLsp {
         let lsp_path = Self::check_available()
             .context("sourcekit-lsp is required for Swift navigation")?;
 
-        // Create LSP query with sourcekit-lsp
         let lsp_command = lsp_path.to_string_lossy().to_string();
-        let lsp_args = vec![]; // sourcekit-lsp is started without args
+        let lsp_args = swift_lsp_config().server_args();
 
         LspQuery::start(&lsp_command, lsp_args, project_root)
             .context("Failed to start sourcekit-lsp server")
@@ -140,10 +207,59 @@ impl SwiftLsp {
     }
 }
 
+/// First `name` in a `PATH` directory
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_prefers_configured_path_and_toolchain() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let toolchain = dir.path().join("Custom.xctoolchain");
+        std::fs::create_dir_all(toolchain.join("usr/bin")).unwrap();
+        let binary = toolchain.join("usr/bin/sourcekit-lsp");
+        std::fs::write(&binary, "").unwrap();
+
+        let config = SwiftLspConfig { toolchain: Some(toolchain.clone()), ..Default::default() };
+        assert_eq!(SwiftLsp::resolve(&config).unwrap(), binary);
+
+        let config = SwiftLspConfig { path: Some(binary.clone()), ..Default::default() };
+        assert_eq!(SwiftLsp::resolve(&config).unwrap(), binary);
+
+        // A configured location that doesn't exist is an error, not a silent fallback
+        let config = SwiftLspConfig { path: Some(dir.path().join("missing")), ..Default::default() };
+        let err = SwiftLsp::resolve(&config).unwrap_err().to_string();
+        assert!(err.contains("SOURCEKIT_LSP_PATH"), "{}", err);
+        let config = SwiftLspConfig { toolchain: Some(dir.path().to_path_buf()), ..Default::default() };
+        assert!(SwiftLsp::resolve(&config).is_err());
+    }
+
+    #[test]
+    fn test_sdk_becomes_compiler_args() {
+        assert!(SwiftLspConfig::default().server_args().is_empty());
+        let config = SwiftLspConfig { sdk: Some(PathBuf::from("/sdks/MacOSX.sdk")), ..Default::default() };
+        assert_eq!(config.server_args(), vec!["-Xswiftc", "-sdk", "-Xswiftc", "/sdks/MacOSX.sdk"]);
+    }
+
     #[test]
     #[ignore] // Only runs if sourcekit-lsp is installed
     fn test_sourcekit_lsp_available() {
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::core::{Language, SymbolKind};
+use crate::indexers::{symbol_name_and_kind, ScipQuery};
+
+/// One line of `export-symbols` output
+///
+/// Schema (all fields always present):
+/// - `symbol`: full SCIP symbol id, stable across exports
+/// - `name`: symbol name from the last descriptor
+/// - `kind`: snake_case `SymbolKind` (`function`, `method`, `class`, `field`, `module`, ...)
+/// - `language`: lowercase `Language` of the defining file
+/// - `file_path`: defining file, relative to the project root
+/// - `line`, `column`: 1-indexed position of the definition
+/// - `documentation`: markdown blocks from the index, possibly empty
+/// - `references`: number of non-definition occurrences across the project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedSymbol {
+    pub symbol: String,
+    pub name: String,
+    pub kind: SymbolKind,
+    pub language: Language,
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub documentation: Vec<String>,
+    pub references: usize,
+}
+
+/// Restricts which symbols are exported
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    pub language: Option<Language>,
+    pub kind: Option<SymbolKind>,
+}
+
+/// Every project-defined symbol in the index that passes the filter, in file order
+pub fn export_symbols(query: &ScipQuery, project_root: &Path, filter: &ExportFilter) -> Vec<ExportedSymbol> {
+    query
+        .symbol_usages()
+        .into_iter()
+        .filter_map(|usage| {
+            let (name, kind) = symbol_name_and_kind(&usage.symbol)?;
+            let file_path = usage.definition.file_path;
+            let language = file_path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(Language::from_extension)
+                .unwrap_or(Language::Unknown);
+
+            if filter.language.is_some_and(|wanted| wanted != language)
+                || filter.kind.is_some_and(|wanted| wanted != kind)
+            {
+                return None;
+            }
+
+            Some(ExportedSymbol {
+                name,
+                kind,
+                language,
+                file_path: file_path.strip_prefix(project_root).unwrap_or(&file_path).to_path_buf(),
+                line: usage.definition.line,
+                column: usage.definition.column,
+                documentation: usage.documentation,
+                references: usage.references.len(),
+                symbol: usage.symbol,
+            })
+        })
+        .collect()
+}
+
+/// Write symbols as newline-delimited JSON, one object per line
+pub fn write_ndjson(symbols: &[ExportedSymbol], out: &mut impl Write) -> Result<()> {
+    for symbol in symbols {
+        serde_json::to_writer(&mut *out, symbol)?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexers::scip_fixture::ScipFixture;
+
+    #[test]
+    fn test_export_round_trips_through_json() {
+        let root = PathBuf::from("/project");
+        let query = ScipFixture::new()
+            .document("src/jobs.rs")
+            .definition("jobs/run_job().", 0, 3, 7)
+            .reference("jobs/run_job().", 5, 3, 7)
+            .reference("jobs/run_job().", 9, 3, 7)
+            .definition("jobs/Job#", 2, 3, 7)
+            .documentation("jobs/run_job().", &["```rust\nfn run_job()\n```", "Runs a \"job\"."])
+            .query(&root);
+        let symbols = export_symbols(&query, &root, &ExportFilter::default());
+        assert_eq!(symbols.len(), 2);
+
+        let mut out = Vec::new();
+        write_ndjson(&symbols, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let parsed: Vec<ExportedSymbol> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(parsed, symbols);
+
+        let run_job = parsed.iter().find(|s| s.name == "run_job").unwrap();
+        assert_eq!(run_job.kind, SymbolKind::Function);
+        assert_eq!(run_job.language, Language::Rust);
+        assert_eq!(run_job.file_path, PathBuf::from("src/jobs.rs"));
+        assert_eq!((run_job.line, run_job.column), (1, 4));
+        assert_eq!(run_job.references, 2);
+        assert_eq!(run_job.documentation.len(), 2);
+
+        let types_only = ExportFilter { language: Some(Language::Rust), kind: Some(SymbolKind::Class) };
+        let types = export_symbols(&query, &root, &types_only);
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].name, "Job");
+
+        let python_only = ExportFilter { language: Some(Language::Python), kind: None };
+        assert!(export_symbols(&query, &root, &python_only).is_empty());
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+use crate::indexers::symbol_export::ExportedSymbol;
+
+/// Header lines of an exuberant-ctags file; they sort ahead of every tag
+const HEADER: &[&str] = &[
+    "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/",
+    "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/",
+    "!_TAG_PROGRAM_NAME\tpowertools\t//",
+];
+
+/// Build sorted exuberant-ctags lines (`{name}\t{file}\t{pattern};"\t{kind}`) for symbols
+///
+/// The pattern matches the whole definition line. When the file can't be read the line
+/// number is used as the address instead. Paths are relative to `project_root` unless
+/// `absolute` is set.
+pub fn tag_lines(symbols: &[ExportedSymbol], project_root: &Path, absolute: bool) -> Vec<String> {
+    let mut sources: HashMap<&Path, Option<String>> = HashMap::new();
+
+    let mut lines: Vec<String> = symbols
+        .iter()
+        .map(|symbol| {
+            let source = sources
+                .entry(symbol.file_path.as_path())
+                .or_insert_with(|| std::fs::read_to_string(project_root.join(&symbol.file_path)).ok());
+            let address = source
+                .as_deref()
+                .and_then(|content| content.lines().nth(symbol.line.saturating_sub(1)))
+                .map(search_pattern)
+                .unwrap_or_else(|| symbol.line.to_string());
+
+            let file = if absolute {
+                let path = project_root.join(&symbol.file_path);
+                path.canonicalize().unwrap_or(path)
+            } else {
+                symbol.file_path.clone()
+            };
+            let kind = serde_json::to_value(symbol.kind)
+                .ok()
+                .and_then(|value| value.as_str().map(String::from))
+                .unwrap_or_default();
+
+            format!("{}\t{}\t{};\"\t{}", symbol.name, file.display(), address, kind)
+        })
+        .collect();
+
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+/// Write a complete tags file: header, then the tag lines
+pub fn write_tags(lines: &[String], out: &mut impl Write) -> Result<()> {
+    for line in HEADER.iter().copied().chain(lines.iter().map(String::as_str)) {
+        out.write_all(line.as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// `/^line$/`, escaping the characters special inside a ctags search pattern
+fn search_pattern(line: &str) -> String {
+    let escaped = line.replace('\\', "\\\\").replace('/', "\\/");
+    format!("/^{}$/", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Language, SymbolKind};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn symbol(name: &str, kind: SymbolKind, file: &str, line: usize) -> ExportedSymbol {
+        ExportedSymbol {
+            symbol: format!("rust-analyzer cargo demo 0.1.0 {}", name),
+            name: name.to_string(),
+            kind,
+            language: Language::Rust,
+            file_path: PathBuf::from(file),
+            line,
+            column: 1,
+            documentation: Vec::new(),
+            references: 0,
+        }
+    }
+
+    #[test]
+    fn test_tags_are_sorted_with_search_patterns() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/jobs.rs"),
+            "pub struct Job;\n\n/// Runs a job\npub fn run_job(path: &str) -> bool { path == \"a/b\" }\n",
+        )
+        .unwrap();
+
+        let symbols = vec![
+            symbol("run_job", SymbolKind::Function, "src/jobs.rs", 4),
+            symbol("Job", SymbolKind::Class, "src/jobs.rs", 1),
+            symbol("gone", SymbolKind::Function, "src/missing.rs", 7),
+        ];
+
+        let lines = tag_lines(&symbols, dir.path(), false);
+        assert_eq!(
+            lines,
+            vec![
+                "Job\tsrc/jobs.rs\t/^pub struct Job;$/;\"\tclass".to_string(),
+                "gone\tsrc/missing.rs\t7;\"\tfunction".to_string(),
+                "run_job\tsrc/jobs.rs\t/^pub fn run_job(path: &str) -> bool { path == \"a\\/b\" }$/;\"\tfunction".to_string(),
+            ]
+        );
+
+        let absolute = tag_lines(&symbols[1..2], dir.path(), true);
+        let root = dir.path().canonicalize().unwrap();
+        assert!(absolute[0].starts_with(&format!("Job\t{}\t", root.join("src/jobs.rs").display())));
+
+        let mut out = Vec::new();
+        write_tags(&lines, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+        assert_eq!(text.lines().count(), HEADER.len() + 3);
+    }
+}
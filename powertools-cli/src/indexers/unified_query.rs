@@ -1,6 +1,6 @@
 use anyhow::Result;
 use std::path::{Path, PathBuf};
-use crate::core::{Location, Reference};
+use crate::core::{Location, Reference, TypeInfo};
 use crate::indexers::{ScipQuery, LspQuery};
 
 /// Unified query interface that abstracts over SCIP and LSP backends
@@ -12,6 +12,7 @@ use crate::indexers::{ScipQuery, LspQuery};
 /// - **SCIP preferred**: Use SCIP for languages with good indexers (TypeScript, Python, Rust, C++)
 /// - **LSP fallback**: Use LSP for languages without SCIP support (Swift, others)
 /// - **Performance trade-off**: SCIP is 20-500x faster but LSP is more widely supported
+/// - **Degradation**: an LSP server that keeps timing out is dropped for SCIP when indexes exist
 ///
 /// ## Usage
 /// ```ignore
@@ -84,10 +85,32 @@ impl UnifiedQuery {
     /// # Returns
     /// Location of the definition, or None if not found
     pub fn find_definition(&mut self, file_path: &Path, line: usize, column: usize) -> Result<Option<Location>> {
-        match self {
+        let result = match self {
             UnifiedQuery::Scip(scip) => scip.find_definition(file_path, line, column),
             UnifiedQuery::Lsp(lsp) => lsp.find_definition(file_path, line, column),
+        };
+        if result.is_err() && self.fall_back_to_scip() {
+            return self.find_definition(file_path, line, column);
+        }
+        result
+    }
+
+    /// Get the type and documentation of the symbol at a given location
+    ///
+    /// LSP answers with `textDocument/hover`; SCIP with the signature and
+    /// documentation recorded in the index.
+    ///
+    /// # Returns
+    /// Type information, or None if the backend has nothing for this position
+    pub fn hover(&mut self, file_path: &Path, line: usize, column: usize) -> Result<Option<TypeInfo>> {
+        let result = match self {
+            UnifiedQuery::Scip(scip) => scip.hover(file_path, line, column),
+            UnifiedQuery::Lsp(lsp) => lsp.hover(file_path, line, column),
+        };
+        if result.is_err() && self.fall_back_to_scip() {
+            return self.hover(file_path, line, column);
         }
+        result
     }
 
     /// Find all references to a symbol (SCIP only)
@@ -152,8 +175,32 @@ impl UnifiedQuery {
                 }
             }
             UnifiedQuery::Lsp(lsp) => {
-                lsp.find_references_at_position(file_path, line, column, include_declarations)
+                let result = lsp.find_references_at_position(file_path, line, column, include_declarations);
+                if result.is_err() && self.fall_back_to_scip() {
+                    return self.find_references_at_position(file_path, line, column, include_declarations);
+                }
+                result
+            }
+        }
+    }
+
+    /// Swap an unhealthy LSP backend for SCIP, if the project has indexes
+    ///
+    /// # Returns
+    /// true if the backend was switched
+    fn fall_back_to_scip(&mut self) -> bool {
+        let UnifiedQuery::Lsp(lsp) = self else {
+            return false;
+        };
+        if lsp.is_healthy() {
+            return false;
+        }
+        match ScipQuery::from_project(lsp.project_root().to_path_buf()) {
+            Ok(scip) => {
+                *self = UnifiedQuery::Scip(scip);
+                true
             }
+            Err(_) => false,
         }
     }
 
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
 use std::path::PathBuf;
 
 mod analyzers;
@@ -27,14 +28,34 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
-    /// Output format (json, text, markdown)
-    #[arg(short = 'f', long, global = true, default_value = "text")]
+    /// Disable colored output (also honors the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Output format (json, text, markdown, sarif); `-F` because subcommands use `-f`
+    #[arg(short = 'F', long, global = true, default_value = "text")]
     format: OutputFormat,
 
-    /// Path to the project root (defaults to current directory)
-    #[arg(short = 'p', long, global = true)]
+    /// Path to the project root (defaults to current directory); `-P` because subcommands use `-p`
+    #[arg(short = 'P', long, global = true)]
     project: Option<PathBuf>,
 
+    /// How long LSP requests (definition, references) wait for the language server, in milliseconds
+    #[arg(long, global = true, value_name = "MS")]
+    lsp_timeout_ms: Option<u64>,
+
+    /// sourcekit-lsp binary for Swift (default: SOURCEKIT_LSP_PATH, xcrun on macOS, then PATH)
+    #[arg(long, global = true, value_name = "PATH")]
+    sourcekit_lsp: Option<PathBuf>,
+
+    /// Swift toolchain whose usr/bin/sourcekit-lsp is used (default: SOURCEKIT_TOOLCHAIN_PATH)
+    #[arg(long, global = true, value_name = "DIR")]
+    swift_toolchain: Option<PathBuf>,
+
+    /// SDK sourcekit-lsp builds against (default: SOURCEKIT_SDK_PATH)
+    #[arg(long, global = true, value_name = "DIR")]
+    swift_sdk: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -44,6 +65,8 @@ enum OutputFormat {
     Text,
     Json,
     Markdown,
+    /// SARIF 2.1.0, for code scanning (complexity, find-dead-code, find-duplicates)
+    Sarif,
 }
 
 #[derive(Subcommand)]
@@ -64,12 +87,33 @@ enum Commands {
         /// Automatically install missing indexers without prompting
         #[arg(long)]
         auto_install: bool,
+
+        /// Languages to index at once (default: all detected, up to the CPU count)
+        #[arg(short, long, default_value = "0")]
+        jobs: usize,
+
+        /// Only keep files matching this glob (relative to the project); repeatable
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Leave out files matching this glob, e.g. "generated/**"; repeatable, wins over --include
+        #[arg(long)]
+        exclude: Vec<String>,
     },
 
     /// Search for patterns in the AST using tree-sitter queries
     SearchAst {
         /// Tree-sitter query pattern
-        pattern: String,
+        #[arg(required_unless_present_any = ["patterns", "query_file"])]
+        pattern: Option<String>,
+
+        /// Additional query pattern; repeat to search for several in one pass
+        #[arg(long = "pattern", value_name = "PATTERN")]
+        patterns: Vec<String>,
+
+        /// File of tree-sitter queries; each top-level pattern is searched separately
+        #[arg(long, value_name = "FILE")]
+        query_file: Option<PathBuf>,
 
         /// File or directory to search in
         #[arg(short = 'p', long)]
@@ -82,6 +126,14 @@ enum Commands {
         /// Maximum results to return
         #[arg(short = 'm', long, default_value = "50")]
         max_results: usize,
+
+        /// Result order: none, name, or location (file, line, column)
+        #[arg(long, value_enum, default_value = "location")]
+        sort: crate::core::ordering::SortOrder,
+
+        /// Stop after this many milliseconds and print the matches found so far
+        #[arg(long, value_name = "MS")]
+        timeout_ms: Option<u64>,
     },
 
     /// Go to definition of a symbol
@@ -98,21 +150,37 @@ enum Commands {
         /// Include declarations
         #[arg(short, long)]
         include_declarations: bool,
+
+        /// Maximum number of results to show
+        #[arg(long, default_value_t = crate::core::pagination::DEFAULT_PAGE_LIMIT)]
+        limit: usize,
+
+        /// Number of results to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
     },
 
-    /// Find implementations of an interface or trait
+    /// Find types implementing a trait or interface, or extending a class
     Implementations {
-        /// Interface or trait name
+        /// Trait, interface or base class name (e.g. Display, fmt::Display, Repository)
         name: String,
     },
 
-    /// Find all callers of a function
+    /// Find all callers of a function (uses the index when present, else scans call expressions)
     Callers {
-        /// Function name or file:line:column
+        /// Function name (optionally qualified, e.g. Job::start) or file:line:column
         function: String,
+
+        /// Maximum number of results to show
+        #[arg(long, default_value_t = crate::core::pagination::DEFAULT_PAGE_LIMIT)]
+        limit: usize,
+
+        /// Number of results to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
     },
 
-    /// Get type information for an expression
+    /// Show the type and documentation of the symbol at a position
     Type {
         /// File path and position (file:line:column)
         location: String,
@@ -150,6 +218,10 @@ enum Commands {
         /// Sort by complexity
         #[arg(short, long)]
         sort: bool,
+
+        /// Metric to compute: cyclomatic or cognitive
+        #[arg(short, long, default_value = "cyclomatic")]
+        metric: String,
     },
 
     /// Analyze impact of changes to a symbol
@@ -170,6 +242,113 @@ enum Commands {
         /// Include private functions
         #[arg(long)]
         include_private: bool,
+
+        /// Result order: none, name, or location (file, line, column)
+        #[arg(long, value_enum, default_value = "location")]
+        sort: crate::core::ordering::SortOrder,
+
+        /// Maximum number of results to show
+        #[arg(long, default_value_t = crate::core::pagination::DEFAULT_PAGE_LIMIT)]
+        limit: usize,
+
+        /// Number of results to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
+    },
+
+    /// Report functions, methods and types with no references (requires index)
+    FindDeadCode {
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Also report public/exported items, which may be used by other crates or packages
+        #[arg(long)]
+        include_public: bool,
+    },
+
+    /// Find copy-pasted code blocks (identifier names and whitespace are ignored)
+    FindDuplicates {
+        /// File or directory to analyze
+        path: Option<PathBuf>,
+
+        /// Smallest duplicate to report, in lines
+        #[arg(long, default_value = "6")]
+        min_lines: usize,
+
+        /// Smallest duplicate to report, in tokens
+        #[arg(long, default_value = "50")]
+        min_tokens: usize,
+    },
+
+    /// Merge the per-language indexes into one standard SCIP file for other SCIP tools (requires index)
+    ExportScip {
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Where to write the index (defaults to index.scip in the project root)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Write a sorted exuberant-ctags `tags` file for Vim/Emacs (requires index)
+    GenerateTags {
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Where to write the tags (defaults to `tags` in the project root)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only include these kinds, comma-separated (e.g. function,class)
+        #[arg(long, value_delimiter = ',')]
+        kinds: Vec<String>,
+
+        /// Write absolute file paths instead of paths relative to the project root
+        #[arg(long)]
+        absolute: bool,
+    },
+
+    /// Export indexed symbols as newline-delimited JSON (requires index)
+    ExportSymbols {
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Only export symbols defined in this language (e.g. rust, typescript)
+        #[arg(long)]
+        language: Option<String>,
+
+        /// Only export symbols of this kind (e.g. function, method, class, field)
+        #[arg(long)]
+        kind: Option<String>,
+    },
+
+    /// Emit the function call graph as Graphviz DOT, or JSON with `-f json` (requires index)
+    CallGraph {
+        /// Function or method to start from (name or qualified name); defaults to the whole project
+        root: Option<String>,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Maximum number of calls to follow from the root
+        #[arg(short, long)]
+        depth: Option<usize>,
+    },
+
+    /// Print a shell completion script (source it from your shell config)
+    #[command(hide = true)]
+    Completions {
+        /// Shell to generate completions for (bash, zsh, fish, powershell)
+        shell: Shell,
     },
 
     /// List all classes/structs in a file or project
@@ -180,6 +359,18 @@ enum Commands {
         /// Include nested classes
         #[arg(long)]
         include_nested: bool,
+
+        /// Result order: none, name, or location (file, line, column)
+        #[arg(long, value_enum, default_value = "location")]
+        sort: crate::core::ordering::SortOrder,
+
+        /// Maximum number of results to show
+        #[arg(long, default_value_t = crate::core::pagination::DEFAULT_PAGE_LIMIT)]
+        limit: usize,
+
+        /// Number of results to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
     },
 
     /// Get project statistics
@@ -194,8 +385,8 @@ enum Commands {
 
     /// Watch for file changes and automatically re-index
     Watch {
-        /// Path to watch (defaults to current directory)
-        path: Option<PathBuf>,
+        /// Paths to watch (defaults to current directory)
+        paths: Vec<PathBuf>,
 
         /// Debounce delay in seconds
         #[arg(short, long, default_value = "2")]
@@ -204,6 +395,14 @@ enum Commands {
         /// Automatically install missing indexers
         #[arg(long)]
         auto_install: bool,
+
+        /// Extra glob patterns to ignore (e.g., "generated/**"); repeatable
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Don't honor .gitignore files in the watched roots
+        #[arg(long)]
+        no_gitignore: bool,
     },
 
     /// Batch replace text across multiple files using regex
@@ -211,7 +410,7 @@ enum Commands {
         /// Regex pattern to search for
         pattern: String,
 
-        /// Replacement text (supports capture groups like $1, $2)
+        /// Replacement text (supports capture groups like $1, ${name}; $$ for a literal $)
         replacement: String,
 
         /// File glob pattern (e.g., "*.rs", "**/*.ts")
@@ -222,9 +421,17 @@ enum Commands {
         #[arg(short, long)]
         path: Option<PathBuf>,
 
+        /// Only replace inside a scope: function:NAME, class:NAME or FILE:START-END
+        #[arg(long)]
+        within: Option<String>,
+
         /// Preview changes without applying
         #[arg(long)]
         preview: bool,
+
+        /// Apply without asking for confirmation (required when stdin isn't a terminal)
+        #[arg(long)]
+        yes: bool,
     },
 
     /// Rename a symbol across the codebase
@@ -252,6 +459,122 @@ enum Commands {
         /// Update imports/exports
         #[arg(long, default_value = "true")]
         update_imports: bool,
+
+        /// Rename even if the new name conflicts with existing symbols
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Move or rename a file and update every import that references it
+    RenameFile {
+        /// Current file path (relative to the project root)
+        old_path: PathBuf,
+
+        /// New file path (relative to the project root)
+        new_path: PathBuf,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Convert declarations from one naming convention to another (e.g. snake_case to camelCase)
+    ConvertCase {
+        /// File or directory to convert
+        path: PathBuf,
+
+        /// Declarations to convert: local, function or type
+        #[arg(long)]
+        kind: String,
+
+        /// Current convention: snake, camel or pascal
+        #[arg(long)]
+        from: String,
+
+        /// Target convention: snake, camel or pascal
+        #[arg(long)]
+        to: String,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Convert import paths between relative and absolute (path alias) style (TypeScript, Python)
+    ConvertImports {
+        /// File or directory to convert (relative to the project root)
+        path: PathBuf,
+
+        /// Target style: relative or absolute
+        #[arg(long)]
+        to: String,
+
+        /// TypeScript path alias prefix, e.g. "@/" (defaults to tsconfig.json's `paths`)
+        #[arg(long)]
+        alias: Option<String>,
+
+        /// Directory the alias or absolute Python module paths start from (defaults to the project root)
+        #[arg(long)]
+        base: Option<PathBuf>,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Convert top-level CommonJS require calls to ES imports (TypeScript, JavaScript)
+    ConvertRequire {
+        /// File or directory to convert (relative to the project root)
+        path: PathBuf,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Merge imports that share a source into one statement (TypeScript, JavaScript)
+    MergeImports {
+        /// File whose imports are merged
+        file: PathBuf,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+
+        /// Preview changes without applying
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Report duplicate and conflicting imports, and optionally merge the duplicates
+    CheckImports {
+        /// File or directory to check (relative to the project root)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Merge duplicate imports of the same module into one statement
+        #[arg(long)]
+        fix: bool,
+
+        /// Project root (defaults to current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
     },
 
     /// Inline a variable by replacing all usages with its initializer
@@ -260,8 +583,14 @@ enum Commands {
         file: PathBuf,
         /// Line number (1-indexed)
         line: usize,
-        /// Column number (1-indexed)
+        /// Column number (1-indexed, in characters; see --tab-width for editor columns)
         column: usize,
+        /// Treat the column as an editor column, expanding tabs to this width
+        #[arg(long)]
+        tab_width: Option<usize>,
+        /// Inline only the usage at this position, keeping the declaration and other usages
+        #[arg(long, value_name = "LINE:COL", value_parser = commands::inline_variable::parse_position)]
+        at_usage: Option<(usize, usize)>,
         /// Project root (defaults to current directory)
         #[arg(short, long)]
         project: Option<PathBuf>,
@@ -270,31 +599,54 @@ enum Commands {
         preview: bool,
     },
 
-    /// Clear the index cache
+    /// Clear the powertools cache, and optionally the project's SCIP indexes
     ClearCache {
-        /// Confirmation flag
+        /// Also remove the SCIP indexes (index.*.scip) in the project root
+        #[arg(long)]
+        index: bool,
+
+        /// Don't ask for confirmation
         #[arg(long)]
         yes: bool,
     },
+
+    /// Revert the most recent rename, inline, or file move, if its files are unchanged since
+    Undo,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if let Some(ms) = cli.lsp_timeout_ms {
+        indexers::lsp_client::set_default_request_timeout(std::time::Duration::from_millis(ms));
+    }
+
+    // Flags override the SOURCEKIT_* environment, field by field
+    let env_config = indexers::SwiftLspConfig::from_env();
+    indexers::set_swift_lsp_config(indexers::SwiftLspConfig {
+        path: cli.sourcekit_lsp.clone().or(env_config.path),
+        toolchain: cli.swift_toolchain.clone().or(env_config.toolchain),
+        sdk: cli.swift_sdk.clone().or(env_config.sdk),
+    });
+
     // Check if running as MCP server
     if cli.mcp_server {
         return mcp::run_mcp_server().await;
     }
 
+    crate::core::color::set_no_color(cli.no_color);
+
     // Initialize logging
     if cli.verbose {
         tracing_subscriber::fmt()
             .with_env_filter("powertools=debug")
+            .with_ansi(crate::core::color::should_color())
             .init();
     } else {
         tracing_subscriber::fmt()
             .with_env_filter("powertools=info")
+            .with_ansi(crate::core::color::should_color())
             .init();
     }
 
@@ -306,20 +658,27 @@ async fn main() -> Result<()> {
 
     // Execute command
     match command {
-        Commands::Index { path, force, languages, auto_install } => {
-            commands::index::run(path, force, languages, auto_install, &cli.format).await?
+        Commands::Index { path, force, languages, auto_install, jobs, include, exclude } => {
+            commands::index::run(path, force, languages, auto_install, jobs, include, exclude, &cli.format).await?
         }
         Commands::SearchAst {
             pattern,
+            patterns,
+            query_file,
             path,
             extensions,
             max_results,
+            sort,
+            timeout_ms,
         } => {
             commands::search_ast::run(
-                pattern,
+                pattern.into_iter().chain(patterns).collect(),
+                query_file,
                 path,
                 extensions,
                 max_results,
+                sort,
+                timeout_ms,
                 &cli.format,
             )
             .await?
@@ -330,33 +689,88 @@ async fn main() -> Result<()> {
         Commands::References {
             symbol,
             include_declarations,
+            limit,
+            offset,
         } => {
-            commands::references::run(symbol, include_declarations, project_root.clone(), &cli.format).await?
+            commands::references::run(symbol, include_declarations, project_root.clone(), limit, offset, &cli.format).await?
+        }
+        Commands::Implementations { name } => {
+            commands::implementations::run(name, project_root.clone(), &cli.format).await?
+        }
+        Commands::Callers { function, limit, offset } => {
+            commands::callers::run(function, project_root.clone(), limit, offset, &cli.format).await?
+        }
+        Commands::Type { location } => {
+            commands::r#type::run(location, project_root.clone(), &cli.format).await?
+        }
+        Commands::Functions { path, include_private, sort, limit, offset } => {
+            commands::functions::run(path, include_private, sort, limit, offset, &cli.format).await?
+        }
+        Commands::Complexity { path, sort, metric } => {
+            commands::complexity::run(path, sort, metric, &cli.format).await?
+        }
+        Commands::FindDeadCode { project, include_public } => {
+            commands::find_dead_code::run(project, include_public, &cli.format).await?
         }
-        Commands::Functions { path, include_private } => {
-            commands::functions::run(path, include_private, &cli.format).await?
+        Commands::FindDuplicates { path, min_lines, min_tokens } => {
+            commands::find_duplicates::run(path, min_lines, min_tokens, &cli.format).await?
         }
-        Commands::Classes { path, include_nested } => {
-            commands::classes::run(path, include_nested, &cli.format).await?
+        Commands::ExportScip { project, output } => {
+            commands::export_scip::run(project, output, &cli.format).await?
+        }
+        Commands::GenerateTags { project, output, kinds, absolute } => {
+            commands::generate_tags::run(project, output, kinds, absolute).await?
+        }
+        Commands::ExportSymbols { project, output, language, kind } => {
+            commands::export_symbols::run(project, output, language, kind).await?
+        }
+        Commands::CallGraph { root, project, depth } => {
+            commands::call_graph::run(root, project, depth, &cli.format).await?
+        }
+        Commands::Completions { shell } => {
+            commands::completions::run(shell).await?
+        }
+        Commands::Classes { path, include_nested, sort, limit, offset } => {
+            commands::classes::run(path, include_nested, sort, limit, offset, &cli.format).await?
         }
         Commands::Stats { path, detailed } => {
             commands::stats::run(path, detailed, &cli.format).await?
         }
-        Commands::Watch { path, debounce, auto_install } => {
-            commands::watch::run(path, debounce, auto_install).await?
+        Commands::Watch { paths, debounce, auto_install, ignore, no_gitignore } => {
+            commands::watch::run(paths, debounce, auto_install, ignore, !no_gitignore).await?
+        }
+        Commands::BatchReplace { pattern, replacement, files, path, within, preview, yes } => {
+            commands::batch_replace::run(pattern, replacement, files, path, within, preview, yes, &cli.format).await?
+        }
+        Commands::RenameSymbol { file, line, column, new_name, project, preview, update_imports, force } => {
+            commands::rename_symbol::run(file, line, column, new_name, project, preview, update_imports, force, &cli.format).await?
+        }
+        Commands::RenameFile { old_path, new_path, project, preview } => {
+            commands::rename_file::run(old_path, new_path, project, preview, &cli.format).await?
+        }
+        Commands::ConvertCase { path, kind, from, to, project, preview } => {
+            commands::convert_case::run(path, kind, from, to, project, preview, &cli.format).await?
+        }
+        Commands::ConvertImports { path, to, alias, base, project, preview } => {
+            commands::convert_imports::run(path, to, alias, base, project, preview, &cli.format).await?
+        }
+        Commands::ConvertRequire { path, project, preview } => {
+            commands::convert_require::run(path, project, preview, &cli.format).await?
+        }
+        Commands::MergeImports { file, project, preview } => {
+            commands::merge_imports::run(file, project, preview, &cli.format).await?
         }
-        Commands::BatchReplace { pattern, replacement, files, path, preview } => {
-            commands::batch_replace::run(pattern, replacement, files, path, preview, &cli.format).await?
+        Commands::CheckImports { path, fix, project } => {
+            commands::check_imports::run(path, fix, project, &cli.format).await?
         }
-        Commands::RenameSymbol { file, line, column, new_name, project, preview, update_imports } => {
-            commands::rename_symbol::run(file, line, column, new_name, project, preview, update_imports, &cli.format).await?
+        Commands::InlineVariable { file, line, column, tab_width, at_usage, project, preview } => {
+            commands::inline_variable::run(file, line, column, tab_width, at_usage, project, preview, &cli.format).await?
         }
-        Commands::InlineVariable { file, line, column, project, preview } => {
-            commands::inline_variable::run(file, line, column, project, preview, &cli.format).await?
+        Commands::ClearCache { index, yes } => {
+            commands::clear_cache::run(project_root.clone(), index, yes, &cli.format).await?
         }
-        _ => {
-            eprintln!("Command not yet implemented");
-            std::process::exit(1);
+        Commands::Undo => {
+            commands::undo::run(project_root.clone(), &cli.format).await?
         }
     }
 
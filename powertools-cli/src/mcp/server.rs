@@ -4,6 +4,7 @@ use std::time::Duration;
 use tracing::info;
 
 use super::tools::PowertoolsService;
+use crate::analyzers::tree_cache;
 
 /// Run the MCP server using stdio transport
 pub async fn run_mcp_server() -> Result<()> {
@@ -12,6 +13,9 @@ pub async fn run_mcp_server() -> Result<()> {
     // Get current directory
     let current_dir = std::env::current_dir()?;
 
+    // Files are analyzed again and again over the session; re-parse them incrementally
+    tree_cache::enable_shared();
+
     // Create the service with watcher
     let service = PowertoolsService::new(current_dir.clone())?;
 
@@ -12,8 +12,11 @@ use tokio::sync::Mutex;
 use anyhow::Result;
 
 use crate::commands;
+use crate::core::pagination::{Page, DEFAULT_PAGE_LIMIT};
+use crate::core::ordering::SortOrder;
+use crate::indexers::IndexLock;
 use crate::OutputFormat;
-use crate::watcher::FileWatcher;
+use crate::watcher::{FileWatcher, WatcherStatus};
 
 /// Powertools MCP Service
 #[derive(Clone)]
@@ -65,6 +68,11 @@ impl PowertoolsService {
         let watcher_guard = self.watcher.lock().await;
         watcher_guard.as_ref().map_or(false, |w| w.is_running())
     }
+
+    pub async fn watcher_status(&self) -> Option<WatcherStatus> {
+        let watcher_guard = self.watcher.lock().await;
+        watcher_guard.as_ref().map(|w| w.status())
+    }
 }
 
 // Tool parameter types
@@ -81,6 +89,15 @@ pub struct IndexProjectParams {
     /// Automatically install missing indexers without prompting
     #[serde(default = "default_true")]
     pub auto_install: bool,
+
+    /// Only index files matching these globs, relative to the project (e.g., ["src/**"])
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Skip files matching these globs (e.g., ["generated/**"]); exclude wins over include.
+    /// Saved filters are reused by later runs and the file watcher
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -93,6 +110,44 @@ pub struct GotoDefinitionParams {
     pub project_root: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindCallersParams {
+    /// Function name (optionally qualified, e.g. 'Job::start') or file:line:column location
+    pub function: String,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+
+    /// Maximum number of results to return (default: 100)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Number of results to skip (default: 0)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindImplementationsParams {
+    /// Trait, interface or base class name (e.g., 'Display', 'fmt::Display', 'Repository')
+    pub name: String,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetTypeParams {
+    /// Location in format 'file:line:column' (e.g., 'src/utils.ts:42:10')
+    pub location: String,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct FindReferencesParams {
     /// Symbol name or file:line:column location
@@ -118,7 +173,13 @@ pub struct FindReferencesParams {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SearchAstParams {
     /// Tree-sitter query pattern
-    pub pattern: String,
+    #[serde(default)]
+    pub pattern: Option<String>,
+
+    /// Several tree-sitter query patterns, evaluated in one parse per file.
+    /// Each result's pattern_index is the position of the pattern that matched
+    #[serde(default)]
+    pub patterns: Vec<String>,
 
     /// Path to search in
     #[serde(default)]
@@ -132,6 +193,15 @@ pub struct SearchAstParams {
     #[serde(default = "default_max_results")]
     pub max_results: usize,
 
+    /// Result order before pagination: "none", "name", or "location" (default: file, line, column)
+    #[serde(default)]
+    pub sort: SortOrder,
+
+    /// Stop searching after this many milliseconds and return the matches found so far,
+    /// with timed_out set (default: no limit)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
     /// Maximum number of results to return (default: 100)
     #[serde(default = "default_limit")]
     pub limit: usize,
@@ -151,6 +221,10 @@ pub struct ListFunctionsParams {
     #[serde(default)]
     pub include_private: bool,
 
+    /// Result order before pagination: "none", "name", or "location" (default: file, line, column)
+    #[serde(default)]
+    pub sort: SortOrder,
+
     /// Maximum number of results to return (default: 100)
     #[serde(default = "default_limit")]
     pub limit: usize,
@@ -160,6 +234,86 @@ pub struct ListFunctionsParams {
     pub offset: usize,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindDeadCodeParams {
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+
+    /// Also report public/exported items (default: false, since other packages may use them)
+    #[serde(default)]
+    pub include_public: bool,
+
+    /// Maximum number of results to return (default: 100)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Number of results to skip (default: 0)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindDuplicatesParams {
+    /// Path to analyze
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Smallest duplicate to report, in lines (default: 6)
+    #[serde(default = "default_min_lines")]
+    pub min_lines: usize,
+
+    /// Smallest duplicate to report, in tokens (default: 50)
+    #[serde(default = "default_min_tokens")]
+    pub min_tokens: usize,
+
+    /// Maximum number of results to return (default: 100)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Number of results to skip (default: 0)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ComplexityParams {
+    /// Path to analyze
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Metric to compute: "cyclomatic" (default) or "cognitive"
+    #[serde(default = "default_complexity_metric")]
+    pub metric: String,
+
+    /// Maximum number of results to return (default: 100)
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+
+    /// Number of results to skip (default: 0)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CallGraphParams {
+    /// Function or method to start from, by name or qualified name (defaults to the whole project)
+    #[serde(default)]
+    pub root: Option<String>,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+
+    /// Maximum number of calls to follow from the root (default: unlimited)
+    #[serde(default)]
+    pub depth: Option<usize>,
+
+    /// Output format: "json" (nodes and edges) or "dot" (Graphviz source). Default: json
+    #[serde(default = "default_graph_format")]
+    pub format: String,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListClassesParams {
     /// Path to analyze
@@ -170,6 +324,10 @@ pub struct ListClassesParams {
     #[serde(default)]
     pub include_nested: bool,
 
+    /// Result order before pagination: "none", "name", or "location" (default: file, line, column)
+    #[serde(default)]
+    pub sort: SortOrder,
+
     /// Maximum number of results to return (default: 100)
     #[serde(default = "default_limit")]
     pub limit: usize,
@@ -196,7 +354,7 @@ pub struct BatchReplaceParams {
     /// Regex pattern to search for
     pub pattern: String,
 
-    /// Replacement text (supports capture groups like $1, $2)
+    /// Replacement text (supports capture groups like $1, ${name}; $$ for a literal $)
     pub replacement: String,
 
     /// File glob pattern (e.g., "*.rs", "**/*.ts")
@@ -207,6 +365,10 @@ pub struct BatchReplaceParams {
     #[serde(default)]
     pub path: Option<String>,
 
+    /// Only replace inside a scope: "function:NAME", "class:NAME" or "FILE:START-END" (line range)
+    #[serde(default)]
+    pub within: Option<String>,
+
     /// Preview changes without applying (default: true for safety)
     #[serde(default = "default_true")]
     pub preview: bool,
@@ -237,6 +399,10 @@ pub struct RenameSymbolParams {
     /// Update imports/exports (default: true)
     #[serde(default = "default_true")]
     pub update_imports: bool,
+
+    /// Apply even if the new name conflicts with existing symbols (default: false)
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -247,9 +413,34 @@ pub struct InlineVariableParams {
     /// Line number (1-indexed)
     pub line: usize,
 
-    /// Column number (1-indexed)
+    /// Column number (1-indexed, in characters; a tab or wide character counts as one)
     pub column: usize,
 
+    /// Treat the column as an editor column, expanding tabs to this width
+    #[serde(default)]
+    pub tab_width: Option<usize>,
+
+    /// Inline only the usage at this "line:column", keeping the declaration and other usages
+    #[serde(default)]
+    pub at_usage: Option<String>,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+
+    /// Preview changes without applying (default: true for safety)
+    #[serde(default = "default_true")]
+    pub preview: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenameFileParams {
+    /// Current file path (relative to the project root)
+    pub old_path: String,
+
+    /// New file path (relative to the project root)
+    pub new_path: String,
+
     /// Project root directory (defaults to current directory)
     #[serde(default)]
     pub project_root: Option<String>,
@@ -259,6 +450,60 @@ pub struct InlineVariableParams {
     pub preview: bool,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConvertCaseParams {
+    /// File or directory whose declarations should be converted
+    pub path: String,
+
+    /// Declarations to convert: "local", "function" or "type"
+    pub kind: String,
+
+    /// Convention the names currently follow: "snake", "camel" or "pascal"
+    pub from: String,
+
+    /// Convention to convert to: "snake", "camel" or "pascal"
+    pub to: String,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+
+    /// Preview changes without applying (default: true for safety)
+    #[serde(default = "default_true")]
+    pub preview: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckImportsParams {
+    /// File or directory to check (defaults to the whole project)
+    #[serde(default)]
+    pub path: Option<String>,
+
+    /// Merge duplicate imports of the same module (default: false, report only)
+    #[serde(default)]
+    pub fix: bool,
+
+    /// Project root directory (defaults to current directory)
+    #[serde(default)]
+    pub project_root: Option<String>,
+}
+
+fn default_min_lines() -> usize {
+    6
+}
+
+fn default_min_tokens() -> usize {
+    50
+}
+
+fn default_complexity_metric() -> String {
+    "cyclomatic".to_string()
+}
+
+fn default_graph_format() -> String {
+    "json".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -268,7 +513,7 @@ fn default_max_results() -> usize {
 }
 
 fn default_limit() -> usize {
-    100
+    DEFAULT_PAGE_LIMIT
 }
 
 // Tool implementations
@@ -283,11 +528,27 @@ impl PowertoolsService {
         let path_buf = params.path.map(PathBuf::from);
         let format = OutputFormat::Json;
 
+        // Report an overlapping run instead of blocking the tool call until it finishes
+        let root = path_buf.clone().unwrap_or_else(|| PathBuf::from("."));
+        if IndexLock::is_held(&root) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                serde_json::json!({
+                    "success": false,
+                    "status": "index_in_progress",
+                    "message": "Another index run is in progress for this project; try again when it finishes"
+                })
+                .to_string(),
+            )]));
+        }
+
         match commands::index::run(
             path_buf,
             false,
             params.languages,
             params.auto_install,
+            0,
+            params.include,
+            params.exclude,
             &format,
         )
         .await
@@ -299,9 +560,22 @@ impl PowertoolsService {
                 })
                 .to_string(),
             )])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(
-                format!("Failed to index project: {}", e),
-            )])),
+            Err(e) => match e.downcast_ref::<commands::index::IndexFailed>() {
+                // Name each missing indexer and its install command so the caller can act on it
+                Some(failed) if !failed.missing.is_empty() => Ok(CallToolResult::error(vec![Content::text(
+                    serde_json::json!({
+                        "success": false,
+                        "error": format!("Failed to index project: {}", failed),
+                        "failed_languages": failed.languages,
+                        "missing_indexers": failed.missing,
+                        "hint": "Install the missing indexers, or call index_project with auto_install=true"
+                    })
+                    .to_string(),
+                )])),
+                _ => Ok(CallToolResult::error(vec![Content::text(
+                    format!("Failed to index project: {}", e),
+                )])),
+            },
         }
     }
 
@@ -332,6 +606,28 @@ impl PowertoolsService {
         }
     }
 
+    /// Get the type and documentation of the symbol at a position
+    #[tool(description = "Get the type signature and documentation of the symbol at a position (e.g., 'src/file.ts:10:5'). Falls back to the syntax node kind and text when no index or language server has type information.")]
+    async fn get_type(
+        &self,
+        Parameters(params): Parameters<GetTypeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = params
+            .project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        match commands::r#type::get_type(params.location, project_root).await {
+            Ok(info) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&info).unwrap_or_else(|_| format!("{:?}", info))
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get type: {}",
+                e
+            ))])),
+        }
+    }
+
     /// Find all references to a symbol
     #[tool(description = "Find all references to a symbol across the codebase. Returns file paths, line numbers, and context.")]
     async fn find_references(
@@ -351,34 +647,73 @@ impl PowertoolsService {
         .await
         {
             Ok(references) => {
-                let total = references.len();
-                let paginated: Vec<_> = references
-                    .into_iter()
-                    .skip(params.offset)
-                    .take(params.limit)
-                    .collect();
-                let has_more = params.offset + paginated.len() < total;
+                let result = Page::new(references, params.limit, params.offset).to_json("references");
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to find references: {}",
+                e
+            ))])),
+        }
+    }
 
+    /// Find every call site of a function
+    #[tool(description = "Find every place a function or method is called. Returns call sites with the calling function and whether it's a direct or method call; overloads and same-named functions are reported separately. Uses the SCIP index when available, otherwise scans call expressions by name.")]
+    async fn find_callers(
+        &self,
+        Parameters(params): Parameters<FindCallersParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = params
+            .project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        match commands::callers::find_callers(params.function, project_root) {
+            Ok(sites) => {
+                let result = Page::new(sites, params.limit, params.offset).to_json("callers");
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to find callers: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Find types implementing a trait or interface
+    #[tool(description = "Find types implementing a trait (Rust), interface (TypeScript) or extending a base class. Returns each implementing type with its location; generic and blanket Rust impls include their constraints.")]
+    async fn find_implementations(
+        &self,
+        Parameters(params): Parameters<FindImplementationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = params
+            .project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        match commands::implementations::find_implementations(params.name, project_root) {
+            Ok(implementations) => {
                 let result = serde_json::json!({
-                    "count": total,
-                    "limit": params.limit,
-                    "offset": params.offset,
-                    "has_more": has_more,
-                    "references": paginated
+                    "implementations": implementations,
+                    "count": implementations.len(),
                 });
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
                 )]))
             },
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Failed to find references: {}",
+                "Failed to find implementations: {}",
                 e
             ))])),
         }
     }
 
     /// Search for code patterns using tree-sitter queries
-    #[tool(description = "Search for code patterns using tree-sitter queries. Useful for finding specific code structures.")]
+    #[tool(description = "Search for code patterns using tree-sitter queries. Useful for finding specific code structures. Pass `patterns` to run several queries in one pass; results then include the pattern_index that matched. Set `timeout_ms` to get partial results (with timed_out: true) instead of waiting on a slow search.")]
     async fn search_ast(
         &self,
         Parameters(params): Parameters<SearchAstParams>,
@@ -388,30 +723,28 @@ impl PowertoolsService {
         // Ensure max_results is large enough for pagination
         let effective_max_results = params.max_results.max(params.offset + params.limit);
 
+        let patterns = match commands::search_ast::collect_patterns(
+            params.pattern.into_iter().chain(params.patterns).collect(),
+            None,
+        ) {
+            Ok(patterns) => patterns,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
         match commands::search_ast::search_patterns(
-            params.pattern,
+            patterns,
             path,
             params.extensions,
             effective_max_results,
+            params.sort,
+            params.timeout_ms,
         )
         .await
         {
-            Ok(results) => {
-                let total = results.len();
-                let paginated: Vec<_> = results
-                    .into_iter()
-                    .skip(params.offset)
-                    .take(params.limit)
-                    .collect();
-                let has_more = params.offset + paginated.len() < total;
-
-                let result = serde_json::json!({
-                    "count": total,
-                    "limit": params.limit,
-                    "offset": params.offset,
-                    "has_more": has_more,
-                    "results": paginated
-                });
+            Ok(outcome) => {
+                let mut result = Page::new(outcome.results, params.limit, params.offset).to_json("results");
+                result["timed_out"] = serde_json::json!(outcome.timed_out);
+                result["truncated_files"] = serde_json::json!(outcome.truncated_files);
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
                 )]))
@@ -431,23 +764,9 @@ impl PowertoolsService {
     ) -> Result<CallToolResult, McpError> {
         let path = params.path.map(PathBuf::from);
 
-        match commands::functions::find_functions(path, params.include_private).await {
+        match commands::functions::find_functions(path, params.include_private, params.sort).await {
             Ok(functions) => {
-                let total = functions.len();
-                let paginated: Vec<_> = functions
-                    .into_iter()
-                    .skip(params.offset)
-                    .take(params.limit)
-                    .collect();
-                let has_more = params.offset + paginated.len() < total;
-
-                let result = serde_json::json!({
-                    "count": total,
-                    "limit": params.limit,
-                    "offset": params.offset,
-                    "has_more": has_more,
-                    "functions": paginated
-                });
+                let result = Page::new(functions, params.limit, params.offset).to_json("functions");
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
                 )]))
@@ -467,29 +786,136 @@ impl PowertoolsService {
     ) -> Result<CallToolResult, McpError> {
         let path = params.path.map(PathBuf::from);
 
-        match commands::classes::find_classes(path, params.include_nested).await {
+        match commands::classes::find_classes(path, params.include_nested, params.sort).await {
             Ok(classes) => {
-                let total = classes.len();
-                let paginated: Vec<_> = classes
-                    .into_iter()
-                    .skip(params.offset)
-                    .take(params.limit)
-                    .collect();
-                let has_more = params.offset + paginated.len() < total;
+                let result = Page::new(classes, params.limit, params.offset).to_json("classes");
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to list classes: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Find functions, methods and types with no references
+    #[tool(description = "Find dead code candidates: functions, methods and types with no references outside their own definition, using the SCIP index (run index_project first). Public/exported items are skipped unless include_public=true. Items used via reflection, dynamic dispatch or macros may be false positives.")]
+    async fn find_dead_code(
+        &self,
+        Parameters(params): Parameters<FindDeadCodeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = params.project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.project_root.clone());
+
+        match commands::find_dead_code::find_dead_code(project_root, params.include_public) {
+            Ok(dead) => {
+                let mut result = Page::new(dead, params.limit, params.offset).to_json("dead_code");
+                result["caveat"] = serde_json::json!(crate::analyzers::DEAD_CODE_CAVEAT);
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to find dead code: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Find copy-pasted code blocks
+    #[tool(description = "Find duplicated code blocks across a file or directory. Matches ignore identifier names, literal values and whitespace, so renamed copies are found too. Returns groups of file:line ranges with a similarity score (1.0 = verbatim copy). Good for spotting refactoring opportunities.")]
+    async fn find_duplicates(
+        &self,
+        Parameters(params): Parameters<FindDuplicatesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::analyzers::DuplicateOptions;
+
+        let path = params.path.map(PathBuf::from).or_else(|| Some(self.project_root.clone()));
+        let options = DuplicateOptions {
+            min_lines: params.min_lines,
+            min_tokens: params.min_tokens,
+        };
+
+        match commands::find_duplicates::find_duplicates(path, options) {
+            Ok(groups) => {
+                let result = Page::new(groups, params.limit, params.offset).to_json("duplicates");
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to find duplicates: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Score functions by complexity
+    #[tool(description = "Score every function in a file or directory by complexity, most complex first. metric='cyclomatic' counts decision points; metric='cognitive' (SonarSource-style) also weights nesting depth and is closer to how hard code is to read.")]
+    async fn complexity(
+        &self,
+        Parameters(params): Parameters<ComplexityParams>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::analyzers::ComplexityMetric;
 
+        let metric: ComplexityMetric = match params.metric.parse() {
+            Ok(metric) => metric,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+        let path = params.path.map(PathBuf::from);
+
+        match commands::complexity::analyze_complexity(path, metric).await {
+            Ok(functions) => {
+                let mut result = Page::new(functions, params.limit, params.offset).to_json("functions");
+                result["metric"] = serde_json::json!(metric);
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                )]))
+            },
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to analyze complexity: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// Build a caller -> callee graph of functions
+    #[tool(description = "Build the function call graph from the SCIP index (run index_project first). Give a root function to see everything it calls (optionally limited by depth), or omit it for the whole project. Returns nodes (qualified names and locations) and caller -> callee edges as JSON, or Graphviz DOT source clustered by file with format=\"dot\".")]
+    async fn call_graph(
+        &self,
+        Parameters(params): Parameters<CallGraphParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let project_root = params.project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.project_root.clone());
+
+        if params.format != "json" && params.format != "dot" {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown format '{}' (expected json or dot)",
+                params.format
+            ))]));
+        }
+
+        match commands::call_graph::build_call_graph(project_root.clone(), params.root.as_deref(), params.depth) {
+            Ok(graph) if params.format == "dot" => {
+                Ok(CallToolResult::success(vec![Content::text(graph.to_dot(&project_root))]))
+            },
+            Ok(graph) => {
                 let result = serde_json::json!({
-                    "count": total,
-                    "limit": params.limit,
-                    "offset": params.offset,
-                    "has_more": has_more,
-                    "classes": paginated
+                    "node_count": graph.nodes.len(),
+                    "edge_count": graph.edges.len(),
+                    "nodes": graph.nodes,
+                    "edges": graph.edges,
                 });
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
                 )]))
             },
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Failed to list classes: {}",
+                "Failed to build call graph: {}",
                 e
             ))])),
         }
@@ -553,11 +979,32 @@ impl PowertoolsService {
     #[tool(description = "Get the current status of the file watcher.")]
     async fn get_watcher_status(&self) -> Result<CallToolResult, McpError> {
         let is_running = self.is_watcher_running().await;
+        let watcher_status = self.watcher_status().await;
+
+        let last_reindex = watcher_status
+            .as_ref()
+            .and_then(|s| s.last_reindex.as_ref())
+            .map(|event| {
+                serde_json::json!({
+                    "language": event.language,
+                    "root": event.root.display().to_string(),
+                    "index_path": event.index_path.as_ref().map(|p| p.display().to_string()),
+                    "duration_ms": event.duration.as_millis() as u64,
+                    "finished_at_unix": event
+                        .finished_at
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|d| d.as_secs())
+                        .unwrap_or(0),
+                    "success": event.is_success(),
+                    "error": event.result.as_ref().err(),
+                })
+            });
 
         let status = serde_json::json!({
             "is_running": is_running,
             "project_root": self.project_root.display().to_string(),
             "debounce_ms": if is_running { 2000 } else { 0 },
+            "last_reindex": last_reindex,
         });
 
         Ok(CallToolResult::success(vec![Content::text(
@@ -566,22 +1013,30 @@ impl PowertoolsService {
     }
 
     /// Batch replace text across multiple files using regex
-    #[tool(description = "Replace text across multiple files using regex patterns. ALWAYS preview first (preview=true) to see changes before applying.")]
+    #[tool(description = "Replace text across multiple files using regex patterns. Use 'within' (function:NAME, class:NAME or FILE:START-END) to only touch matches inside one function, class or line range. ALWAYS preview first (preview=true) to see changes before applying.")]
     async fn batch_replace(
         &self,
         Parameters(params): Parameters<BatchReplaceParams>,
     ) -> Result<CallToolResult, McpError> {
-        use crate::refactor::BatchReplacer;
+        use crate::refactor::{BatchReplacer, ReplaceScope};
 
         let path = params.path.map(PathBuf::from).unwrap_or_else(|| self.project_root.clone());
 
+        let scope = match params.within.as_deref().map(str::parse::<ReplaceScope>).transpose() {
+            Ok(scope) => scope,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
         let replacer = match BatchReplacer::new(
             &params.pattern,
             params.replacement.clone(),
             params.file_pattern.clone(),
             path,
         ) {
-            Ok(r) => r,
+            Ok(r) => match scope {
+                Some(scope) => r.with_scope(scope),
+                None => r,
+            },
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!(
                 "Invalid regex pattern: {}",
                 e
@@ -596,6 +1051,7 @@ impl PowertoolsService {
                         "preview": true,
                         "num_files": diffs.len(),
                         "total_changes": diffs.iter().map(|d| d.num_changes).sum::<usize>(),
+                        "total_lines_changed": diffs.iter().map(|d| d.line_changes.len()).sum::<usize>(),
                         "diffs": diffs,
                     });
                     Ok(CallToolResult::success(vec![Content::text(
@@ -626,7 +1082,7 @@ impl PowertoolsService {
     }
 
     /// Rename a symbol across the codebase
-    #[tool(description = "Rename a symbol across the entire codebase with semantic awareness. ALWAYS preview first (preview=true) to see all changes. Uses SCIP indexes for precise symbol resolution.")]
+    #[tool(description = "Rename a symbol across the entire codebase with semantic awareness. ALWAYS preview first (preview=true) to see all changes. Uses SCIP indexes for precise symbol resolution. Refuses to apply if the new name conflicts with an existing symbol or import unless force=true.")]
     async fn rename_symbol(
         &self,
         Parameters(params): Parameters<RenameSymbolParams>,
@@ -662,6 +1118,7 @@ impl PowertoolsService {
             } else {
                 TransactionMode::Execute
             },
+            force: params.force,
         };
 
         if params.preview {
@@ -675,6 +1132,8 @@ impl PowertoolsService {
                         "total_import_changes": summary.total_import_changes,
                         "overall_risk": summary.overall_risk,
                         "warnings": summary.warnings,
+                        "conflicts": summary.conflicts,
+                        "files": summary.files,
                         "file_changes": summary.file_changes,
                     });
                     Ok(CallToolResult::success(vec![Content::text(
@@ -698,6 +1157,7 @@ impl PowertoolsService {
                         "files_modified": result.files_modified,
                         "imports_updated": result.imports_updated,
                         "modified_files": result.transaction_result.files_modified,
+                        "files": result.transaction_result.files,
                     });
                     Ok(CallToolResult::success(vec![Content::text(
                         serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string())
@@ -718,12 +1178,43 @@ impl PowertoolsService {
         Parameters(params): Parameters<InlineVariableParams>,
     ) -> Result<CallToolResult, McpError> {
         use crate::indexers::ScipQuery;
-        use crate::refactor::{InlineOptions, TransactionMode, VariableInliner};
+        use crate::commands::inline_variable::parse_position;
+        use crate::refactor::{char_column_from_visual, InlineOptions, TransactionMode, VariableInliner};
 
         let project_root = params.project_root
             .map(PathBuf::from)
             .unwrap_or_else(|| self.project_root.clone());
 
+        let at_usage = match params.at_usage.as_deref().map(parse_position).transpose() {
+            Ok(at_usage) => at_usage,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Invalid at_usage: {}",
+                e
+            ))])),
+        };
+
+        let (column, at_usage) = match params.tab_width {
+            Some(tab_width) => {
+                let converted = std::fs::read_to_string(&params.file)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|content| {
+                        let column = char_column_from_visual(&content, params.line, params.column, tab_width)?;
+                        let at_usage = at_usage
+                            .map(|(line, column)| char_column_from_visual(&content, line, column, tab_width).map(|c| (line, c)))
+                            .transpose()?;
+                        Ok((column, at_usage))
+                    });
+                match converted {
+                    Ok(converted) => converted,
+                    Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Invalid position: {}",
+                        e
+                    ))])),
+                }
+            }
+            None => (params.column, at_usage),
+        };
+
         // Load SCIP index
         let scip_query = match ScipQuery::from_project(project_root.clone()) {
             Ok(q) => q,
@@ -740,12 +1231,13 @@ impl PowertoolsService {
         let options = InlineOptions {
             file_path: PathBuf::from(&params.file),
             line: params.line,
-            column: params.column,
+            column,
             mode: if params.preview {
                 TransactionMode::DryRun
             } else {
                 TransactionMode::Execute
             },
+            only_at: at_usage,
         };
 
         if params.preview {
@@ -758,6 +1250,7 @@ impl PowertoolsService {
                         "total_changes": summary.total_changes,
                         "overall_risk": summary.overall_risk,
                         "warnings": summary.warnings,
+                        "files": summary.files,
                         "file_changes": summary.file_changes,
                     });
                     Ok(CallToolResult::success(vec![Content::text(
@@ -780,6 +1273,7 @@ impl PowertoolsService {
                         "usages_replaced": result.usages_replaced,
                         "files_modified": result.files_modified,
                         "modified_files": result.transaction_result.files_modified,
+                        "files": result.transaction_result.files,
                     });
                     Ok(CallToolResult::success(vec![Content::text(
                         serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string())
@@ -792,6 +1286,208 @@ impl PowertoolsService {
             }
         }
     }
+
+    /// Move or rename a file and update every import that references it
+    #[tool(description = "Move or rename a file and rewrite every import that references it (relative paths for TypeScript/JavaScript/Python, crate:: paths and the mod declaration for Rust). ALWAYS preview first (preview=true). The move and all import edits are applied as one transaction.")]
+    async fn rename_file(
+        &self,
+        Parameters(params): Parameters<RenameFileParams>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::refactor::{FileMover, MoveFileOptions, TransactionMode};
+
+        let project_root = params.project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.project_root.clone());
+
+        let mover = FileMover::new(project_root);
+
+        // Build options
+        let options = MoveFileOptions {
+            old_path: PathBuf::from(&params.old_path),
+            new_path: PathBuf::from(&params.new_path),
+            mode: if params.preview {
+                TransactionMode::DryRun
+            } else {
+                TransactionMode::Execute
+            },
+        };
+
+        if params.preview {
+            // Preview mode - show what would change
+            match mover.preview(options) {
+                Ok(summary) => {
+                    let result = serde_json::json!({
+                        "preview": true,
+                        "total_files": summary.total_files,
+                        "total_changes": summary.total_changes,
+                        "total_import_changes": summary.total_import_changes,
+                        "overall_risk": summary.overall_risk,
+                        "warnings": summary.warnings,
+                        "files": summary.files,
+                        "file_changes": summary.file_changes,
+                    });
+                    Ok(CallToolResult::success(vec![Content::text(
+                        serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                    )]))
+                }
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to preview file move: {}",
+                    e
+                ))])),
+            }
+        } else {
+            // Apply mode - make the changes
+            match mover.move_file(options) {
+                Ok(result) => {
+                    let response = serde_json::json!({
+                        "success": true,
+                        "old_path": result.old_path,
+                        "new_path": result.new_path,
+                        "importers_updated": result.importers_updated,
+                        "imports_updated": result.imports_updated,
+                        "warnings": result.warnings,
+                        "modified_files": result.transaction_result.files_modified,
+                        "files": result.transaction_result.files,
+                    });
+                    Ok(CallToolResult::success(vec![Content::text(
+                        serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string())
+                    )]))
+                }
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to move file: {}",
+                    e
+                ))])),
+            }
+        }
+    }
+
+    /// Convert every declaration of one kind from one naming convention to another
+    #[tool(description = "Bulk-rename Rust declarations from one naming convention to another (e.g. snake_case to camelCase) across a file or directory, updating every reference. kind is 'local' (let bindings and parameters), 'function' or 'type'; functions and types need a SCIP index. Trait methods, external symbols and renames that would collide with existing names are skipped and reported. ALWAYS preview first (preview=true).")]
+    async fn convert_case(
+        &self,
+        Parameters(params): Parameters<ConvertCaseParams>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::indexers::ScipQuery;
+        use crate::refactor::{CaseConverter, ConvertCaseOptions, DeclarationKind, TransactionMode};
+
+        let project_root = params.project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.project_root.clone());
+
+        let options = match (params.kind.parse::<DeclarationKind>(), params.from.parse(), params.to.parse()) {
+            (Ok(kind), Ok(from), Ok(to)) => ConvertCaseOptions {
+                path: project_root.join(&params.path),
+                kind,
+                from,
+                to,
+                mode: if params.preview {
+                    TransactionMode::DryRun
+                } else {
+                    TransactionMode::Execute
+                },
+            },
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+            }
+        };
+
+        // Locals are resolved with tree-sitter; functions and types need the index
+        let scip_query = if options.kind == DeclarationKind::Local {
+            None
+        } else {
+            match ScipQuery::from_project(project_root) {
+                Ok(q) => Some(q),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to load SCIP index: {}. Run index_project first.",
+                    e
+                ))])),
+            }
+        };
+        let converter = CaseConverter::new(scip_query.as_ref());
+
+        if params.preview {
+            // Preview mode - show what would change
+            match converter.preview(options) {
+                Ok(summary) => {
+                    let result = serde_json::json!({
+                        "preview": true,
+                        "total_files": summary.total_files,
+                        "total_changes": summary.total_changes,
+                        "overall_risk": summary.overall_risk,
+                        "warnings": summary.warnings,
+                        "files": summary.files,
+                        "file_changes": summary.file_changes,
+                    });
+                    Ok(CallToolResult::success(vec![Content::text(
+                        serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+                    )]))
+                }
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to preview case conversion: {}",
+                    e
+                ))])),
+            }
+        } else {
+            // Apply mode - make the changes
+            match converter.convert(options) {
+                Ok(result) => {
+                    let response = serde_json::json!({
+                        "success": true,
+                        "renamed": result.renamed,
+                        "skipped": result.skipped,
+                        "modified_files": result.transaction_result.files_modified,
+                        "files": result.transaction_result.files,
+                    });
+                    Ok(CallToolResult::success(vec![Content::text(
+                        serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string())
+                    )]))
+                }
+                Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to convert case: {}",
+                    e
+                ))])),
+            }
+        }
+    }
+
+    /// Report duplicate and conflicting imports, optionally merging the duplicates
+    #[tool(description = "Find duplicate and conflicting imports in a file or directory (TypeScript/JavaScript, Python, Rust, C/C++): the same symbol imported twice from one module, one module imported by several statements, and one name imported from different modules. Each issue has its line numbers and whether it's fixable. With fix=true, duplicate imports of a module are merged into one statement (journaled, so undo reverts it); conflicts are only reported.")]
+    async fn check_imports(
+        &self,
+        Parameters(params): Parameters<CheckImportsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        use crate::refactor::{CheckImportsOptions, ImportChecker};
+
+        let project_root = params.project_root
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.project_root.clone());
+
+        let checker = ImportChecker::new(project_root);
+        let options = CheckImportsOptions {
+            path: params.path.map(PathBuf::from).unwrap_or_else(|| PathBuf::from(".")),
+            fix: params.fix,
+        };
+
+        match checker.check(options) {
+            Ok(result) => {
+                let response = serde_json::json!({
+                    "files_checked": result.files_checked,
+                    "issues": result.issues,
+                    "warnings": result.warnings,
+                    "files_fixed": result.files_fixed,
+                    "imports_merged": result.imports_merged,
+                    "modified_files": result.transaction_result.as_ref().map(|t| &t.files_modified),
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string())
+                )]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to check imports: {}",
+                e
+            ))])),
+        }
+    }
 }
 
 // Server handler implementation
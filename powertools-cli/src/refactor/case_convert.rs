@@ -0,0 +1,689 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tree_sitter::{Node, Parser};
+
+use crate::core::{Location, Reference, ReferenceKind};
+use crate::indexers::ScipQuery;
+
+use super::conflicts::find_conflicts_in_file;
+use super::preview::RefactoringSummary;
+use super::rename::{apply_planned_renames, preview_planned_renames, PlannedRename};
+use super::syntax::is_in_comment_or_string;
+use super::transaction::{TransactionMode, TransactionResult};
+
+/// Words that can't be used as Rust identifiers
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "macro", "override", "priv",
+    "typeof", "unsized", "virtual", "yield", "try", "gen",
+];
+
+/// Identifier naming conventions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStyle {
+    /// `user_id`
+    Snake,
+    /// `userId`
+    Camel,
+    /// `UserId`
+    Pascal,
+    /// `user-id`
+    Kebab,
+}
+
+impl FromStr for CaseStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "snake" | "snake_case" => Ok(Self::Snake),
+            "camel" | "camelCase" => Ok(Self::Camel),
+            "pascal" | "PascalCase" => Ok(Self::Pascal),
+            "kebab" | "kebab-case" => Ok(Self::Kebab),
+            _ => anyhow::bail!("Unknown case style '{}' (expected snake, camel, pascal or kebab)", s),
+        }
+    }
+}
+
+impl CaseStyle {
+    /// Whether `name` (without leading underscores) follows this convention
+    fn matches(self, name: &str) -> bool {
+        let Some(first) = name.chars().next() else {
+            return false;
+        };
+        match self {
+            Self::Snake => {
+                !first.is_ascii_digit()
+                    && name.chars().all(|c| c.is_lowercase() || c.is_ascii_digit() || c == '_')
+            }
+            Self::Kebab => {
+                !first.is_ascii_digit()
+                    && name.chars().all(|c| c.is_lowercase() || c.is_ascii_digit() || c == '-')
+            }
+            Self::Camel => first.is_lowercase() && name.chars().all(char::is_alphanumeric),
+            Self::Pascal => {
+                first.is_uppercase()
+                    && name.chars().all(char::is_alphanumeric)
+                    && name.chars().any(char::is_lowercase)
+            }
+        }
+    }
+
+    /// Join lowercase words in this convention
+    fn join(self, words: &[String]) -> String {
+        match self {
+            Self::Snake => words.join("_"),
+            Self::Kebab => words.join("-"),
+            Self::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| if i == 0 { word.clone() } else { capitalize(word) })
+                .collect(),
+            Self::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+/// Convert `name` from one convention to another
+/// Leading underscores (`_unused`) are kept; returns None if `name` doesn't follow `from`
+/// or converting doesn't change it
+pub fn convert_case(name: &str, from: CaseStyle, to: CaseStyle) -> Option<String> {
+    let trimmed = name.trim_start_matches('_');
+    let prefix = &name[..name.len() - trimmed.len()];
+    if !from.matches(trimmed) {
+        return None;
+    }
+
+    let converted = format!("{}{}", prefix, to.join(&split_words(trimmed)));
+    (converted != name).then_some(converted)
+}
+
+/// Split an identifier into lowercase words: `parseHTTPRequest` -> ["parse", "http", "request"]
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            // "userId" splits before "I"; "HTTPServer" splits before the "S" that starts "Server"
+            if !previous.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Which declarations to convert
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclarationKind {
+    /// `let` bindings, `for` bindings, and function/closure parameters
+    Local,
+    /// Free functions and inherent methods (trait methods are left alone)
+    Function,
+    /// Structs, enums, unions, traits and type aliases
+    Type,
+}
+
+impl FromStr for DeclarationKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "local" | "locals" | "variable" | "variables" => Ok(Self::Local),
+            "function" | "functions" | "fn" => Ok(Self::Function),
+            "type" | "types" => Ok(Self::Type),
+            _ => anyhow::bail!("Unknown declaration kind '{}' (expected local, function or type)", s),
+        }
+    }
+}
+
+/// Options for a case conversion
+#[derive(Debug, Clone)]
+pub struct ConvertCaseOptions {
+    /// File or directory to convert declarations in
+    pub path: PathBuf,
+
+    /// Which declarations to convert
+    pub kind: DeclarationKind,
+
+    /// Convention the names currently follow
+    pub from: CaseStyle,
+
+    /// Convention to convert them to
+    pub to: CaseStyle,
+
+    /// Transaction mode (Execute or DryRun)
+    pub mode: TransactionMode,
+}
+
+/// One declaration that will be renamed
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseRename {
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub old_name: String,
+    pub new_name: String,
+
+    /// Number of references (including the declaration) that change
+    pub references: usize,
+}
+
+/// A declaration that matched the convention but was left alone
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedRename {
+    pub file_path: PathBuf,
+    pub line: usize,
+    pub name: String,
+    pub reason: String,
+}
+
+impl SkippedRename {
+    pub fn format(&self) -> String {
+        format!("{}:{} `{}`: {}", self.file_path.display(), self.line, self.name, self.reason)
+    }
+}
+
+/// Result of a case conversion
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertCaseResult {
+    pub renamed: Vec<CaseRename>,
+
+    pub skipped: Vec<SkippedRename>,
+
+    /// Transaction result
+    pub transaction_result: TransactionResult,
+}
+
+/// A declaration found by tree-sitter
+struct Declaration {
+    name: String,
+    line: usize,
+    column: usize,
+    start_byte: usize,
+    /// Byte range the name is visible in (locals only)
+    scope: Option<(usize, usize)>,
+}
+
+/// Everything a conversion will rename or skip
+struct ConversionPlan {
+    renames: Vec<PlannedRename>,
+    renamed: Vec<CaseRename>,
+    skipped: Vec<SkippedRename>,
+}
+
+/// Rename every declaration of one kind that follows a naming convention (Rust only for now)
+pub struct CaseConverter<'a> {
+    /// Needed for functions and types, whose references can live in other files
+    scip_query: Option<&'a ScipQuery>,
+}
+
+impl<'a> CaseConverter<'a> {
+    pub fn new(scip_query: Option<&'a ScipQuery>) -> Self {
+        Self { scip_query }
+    }
+
+    /// Perform the conversion
+    pub fn convert(&self, options: ConvertCaseOptions) -> Result<ConvertCaseResult> {
+        let plan = self.plan(&options)?;
+        let transaction_result = apply_planned_renames(&plan.renames, options.mode)?;
+
+        Ok(ConvertCaseResult {
+            renamed: plan.renamed,
+            skipped: plan.skipped,
+            transaction_result,
+        })
+    }
+
+    /// Generate a preview of the conversion
+    pub fn preview(&self, options: ConvertCaseOptions) -> Result<RefactoringSummary> {
+        let plan = self.plan(&options)?;
+        let mut summary = preview_planned_renames(&plan.renames)?;
+
+        summary.warnings.insert(
+            0,
+            format!(
+                "🔤 {} declaration{} to rename",
+                plan.renamed.len(),
+                if plan.renamed.len() == 1 { "" } else { "s" }
+            ),
+        );
+        summary
+            .warnings
+            .extend(plan.skipped.iter().map(|skipped| format!("⏭️  Skipped {}", skipped.format())));
+        Ok(summary)
+    }
+
+    fn plan(&self, options: &ConvertCaseOptions) -> Result<ConversionPlan> {
+        if options.to == CaseStyle::Kebab {
+            anyhow::bail!("kebab-case names aren't valid Rust identifiers");
+        }
+        if options.kind != DeclarationKind::Local && self.scip_query.is_none() {
+            anyhow::bail!("Converting functions and types needs a SCIP index. Run 'powertools index' first.");
+        }
+
+        let mut plan = ConversionPlan {
+            renames: Vec::new(),
+            renamed: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        for file_path in rust_files(&options.path)? {
+            let content = fs::read_to_string(&file_path)
+                .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+
+            let mut parser = Parser::new();
+            parser
+                .set_language(&tree_sitter_rust::LANGUAGE.into())
+                .context("Failed to load Rust grammar")?;
+            let tree = parser
+                .parse(&content, None)
+                .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", file_path.display()))?;
+
+            let mut declarations = Vec::new();
+            collect_declarations(tree.root_node(), &content, options.kind, &mut declarations);
+
+            for declaration in declarations {
+                let Some(new_name) = convert_case(&declaration.name, options.from, options.to) else {
+                    continue;
+                };
+                let skip = |reason: String| SkippedRename {
+                    file_path: file_path.clone(),
+                    line: declaration.line,
+                    name: declaration.name.clone(),
+                    reason,
+                };
+
+                if RUST_KEYWORDS.contains(&new_name.as_str()) {
+                    plan.skipped.push(skip(format!("`{}` is a keyword", new_name)));
+                    continue;
+                }
+
+                let references = match declaration.scope {
+                    Some(scope) => local_references(&tree, &content, &file_path, &declaration, scope),
+                    None => {
+                        let references = self
+                            .scip_query
+                            .map(|query| {
+                                query.find_symbol_occurrences(&file_path, declaration.line, declaration.column)
+                            })
+                            .transpose()?
+                            .unwrap_or_default();
+                        if references.is_empty() {
+                            plan.skipped.push(skip(
+                                "not in the SCIP index (re-run 'powertools index')".to_string(),
+                            ));
+                            continue;
+                        }
+                        if !references.iter().any(|r| matches!(r.kind, ReferenceKind::Definition)) {
+                            plan.skipped.push(skip("defined outside the project".to_string()));
+                            continue;
+                        }
+                        references
+                    }
+                };
+
+                if let Some(conflict) = first_conflict(&references, &new_name)? {
+                    plan.skipped.push(skip(format!("would conflict with {}", conflict)));
+                    continue;
+                }
+
+                plan.renamed.push(CaseRename {
+                    file_path: file_path.clone(),
+                    line: declaration.line,
+                    column: declaration.column,
+                    old_name: declaration.name.clone(),
+                    new_name: new_name.clone(),
+                    references: references.len(),
+                });
+                plan.renames.push(PlannedRename {
+                    old_name: declaration.name,
+                    new_name,
+                    references,
+                });
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+/// Rust files at `path` (a file, or a directory walked with .gitignore respected)
+fn rust_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let is_rust = |p: &Path| p.extension().and_then(|e| e.to_str()) == Some("rs");
+
+    if path.is_file() {
+        if !is_rust(path) {
+            anyhow::bail!("convert-case currently supports Rust files only: {}", path.display());
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+    if !path.is_dir() {
+        anyhow::bail!("Path not found: {}", path.display());
+    }
+
+    let mut files: Vec<PathBuf> = WalkBuilder::new(path)
+        .standard_filters(true)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() && is_rust(p))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Collect declarations of `kind` under `node`
+fn collect_declarations(node: Node, content: &str, kind: DeclarationKind, found: &mut Vec<Declaration>) {
+    match (kind, node.kind()) {
+        (DeclarationKind::Local, "let_declaration") => {
+            if let (Some(pattern), Some(scope)) = (node.child_by_field_name("pattern"), node.parent()) {
+                collect_bindings(pattern, content, scope, found);
+            }
+        }
+        (DeclarationKind::Local, "for_expression") => {
+            if let Some(pattern) = node.child_by_field_name("pattern") {
+                collect_bindings(pattern, content, node, found);
+            }
+        }
+        (DeclarationKind::Local, "parameter") => {
+            let scope = node.parent().and_then(|params| params.parent());
+            if let (Some(pattern), Some(scope)) = (node.child_by_field_name("pattern"), scope) {
+                collect_bindings(pattern, content, scope, found);
+            }
+        }
+        (DeclarationKind::Local, "closure_parameters") => {
+            // Typed parameters (`|x: u8|`) are `parameter` nodes, handled above
+            if let Some(scope) = node.parent() {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    if child.kind() != "parameter" {
+                        collect_bindings(child, content, scope, found);
+                    }
+                }
+            }
+        }
+        (DeclarationKind::Function, "function_item") if is_own_function(node) => {
+            if let Some(name) = node.child_by_field_name("name") {
+                if &content[name.byte_range()] != "main" {
+                    found.push(declaration_at(name, content, None));
+                }
+            }
+        }
+        (DeclarationKind::Type, "struct_item" | "enum_item" | "union_item" | "trait_item" | "type_item") => {
+            if let Some(name) = node.child_by_field_name("name") {
+                found.push(declaration_at(name, content, None));
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_declarations(child, content, kind, found);
+    }
+}
+
+/// Identifiers bound by a pattern (`x`, `(a, b)`, `Some(value)`, `mut count`)
+fn collect_bindings(pattern: Node, content: &str, scope: Node, found: &mut Vec<Declaration>) {
+    if pattern.kind() == "identifier" {
+        // `Some` in `Some(value)` names a variant, not a binding
+        let is_path = pattern.parent().is_some_and(|parent| {
+            parent.kind() == "tuple_struct_pattern"
+                && parent.child_by_field_name("type").map(|n| n.id()) == Some(pattern.id())
+        });
+        if !is_path {
+            found.push(declaration_at(pattern, content, Some((scope.start_byte(), scope.end_byte()))));
+        }
+        return;
+    }
+    // Paths, struct shorthand fields and literals don't bind new names we can rename safely
+    if matches!(pattern.kind(), "scoped_identifier" | "field_pattern" | "struct_pattern" | "type_identifier") {
+        return;
+    }
+
+    let mut cursor = pattern.walk();
+    for child in pattern.named_children(&mut cursor) {
+        collect_bindings(child, content, scope, found);
+    }
+}
+
+/// Free functions and inherent methods; trait definitions, trait impls and extern blocks
+/// tie the name to a declaration we don't control
+fn is_own_function(node: Node) -> bool {
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        match ancestor.kind() {
+            "trait_item" | "foreign_mod_item" => return false,
+            "impl_item" => return ancestor.child_by_field_name("trait").is_none(),
+            "function_item" | "source_file" | "mod_item" => return true,
+            _ => {}
+        }
+        current = ancestor.parent();
+    }
+    true
+}
+
+fn declaration_at(node: Node, content: &str, scope: Option<(usize, usize)>) -> Declaration {
+    Declaration {
+        name: content[node.byte_range()].to_string(),
+        line: node.start_position().row + 1,
+        column: node.start_position().column + 1,
+        start_byte: node.start_byte(),
+        scope,
+    }
+}
+
+/// References to a local: matching identifiers in its scope from the declaration on
+fn local_references(
+    tree: &tree_sitter::Tree,
+    content: &str,
+    file_path: &Path,
+    declaration: &Declaration,
+    (scope_start, scope_end): (usize, usize),
+) -> Vec<Reference> {
+    let Some(scope) = tree.root_node().descendant_for_byte_range(scope_start, scope_end) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+    collect_local_uses(scope, content, &declaration.name, declaration.start_byte, &mut nodes);
+
+    nodes
+        .into_iter()
+        .map(|node| Reference {
+            location: Location {
+                file_path: file_path.to_path_buf(),
+                line: node.start_position().row + 1,
+                column: node.start_position().column + 1,
+                end_line: Some(node.end_position().row + 1),
+                end_column: Some(node.end_position().column + 1),
+            },
+            kind: if node.start_byte() == declaration.start_byte {
+                ReferenceKind::Definition
+            } else {
+                ReferenceKind::Reference
+            },
+            context: None,
+        })
+        .collect()
+}
+
+fn collect_local_uses<'t>(node: Node<'t>, content: &str, name: &str, from_byte: usize, found: &mut Vec<Node<'t>>) {
+    if node.end_byte() <= from_byte {
+        return;
+    }
+    // Nested functions can't see the enclosing function's locals
+    if node.kind() == "function_item" && node.start_byte() > from_byte {
+        return;
+    }
+
+    let is_use = node.kind() == "identifier"
+        && node.start_byte() >= from_byte
+        && &content[node.byte_range()] == name
+        && node.parent().map_or(true, |parent| parent.kind() != "scoped_identifier")
+        && !is_in_comment_or_string(node);
+    if is_use {
+        found.push(node);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_local_uses(child, content, name, from_byte, found);
+    }
+}
+
+/// First place `new_name` would collide with existing code at any of the references
+fn first_conflict(references: &[Reference], new_name: &str) -> Result<Option<String>> {
+    let mut sites_by_file: HashMap<&Path, Vec<(usize, usize)>> = HashMap::new();
+    for reference in references {
+        sites_by_file
+            .entry(reference.location.file_path.as_path())
+            .or_default()
+            .push((reference.location.line, reference.location.column));
+    }
+
+    for (file_path, sites) in sites_by_file {
+        let content = fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        if let Some(conflict) = find_conflicts_in_file(file_path, &content, new_name, &sites)?.first() {
+            return Ok(Some(format!("{}:{}", conflict.file_path.display(), conflict.line)));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_convert_case() {
+        use CaseStyle::*;
+
+        assert_eq!(convert_case("user_id", Snake, Camel).as_deref(), Some("userId"));
+        assert_eq!(convert_case("_unused_value", Snake, Camel).as_deref(), Some("_unusedValue"));
+        assert_eq!(convert_case("parseHTTPRequest", Camel, Snake).as_deref(), Some("parse_http_request"));
+        assert_eq!(convert_case("HttpServer", Pascal, Snake).as_deref(), Some("http_server"));
+        assert_eq!(convert_case("max_retry_count", Snake, Pascal).as_deref(), Some("MaxRetryCount"));
+        // Single words and names in other conventions are left alone
+        assert_eq!(convert_case("count", Snake, Camel), None);
+        assert_eq!(convert_case("userId", Snake, Camel), None);
+    }
+
+    #[test]
+    fn test_convert_locals_updates_references_only() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(
+            &path,
+            r#"fn greet(user_name: &str, retry_count: u32) -> String {
+    // user_name is shown as-is
+    let greeting_text = format!("user_name: {}", user_name);
+    for attempt_no in 0..retry_count {
+        println!("{} {}", attempt_no, greeting_text);
+    }
+    config::user_name();
+    greeting_text
+}
+"#,
+        )
+        .unwrap();
+
+        let result = CaseConverter::new(None)
+            .convert(ConvertCaseOptions {
+                path: path.clone(),
+                kind: DeclarationKind::Local,
+                from: CaseStyle::Snake,
+                to: CaseStyle::Camel,
+                mode: TransactionMode::Execute,
+            })
+            .unwrap();
+
+        let names: Vec<&str> = result.renamed.iter().map(|r| r.new_name.as_str()).collect();
+        assert_eq!(names, vec!["userName", "retryCount", "greetingText", "attemptNo"]);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"fn greet(userName: &str, retryCount: u32) -> String {
+    // user_name is shown as-is
+    let greetingText = format!("user_name: {}", userName);
+    for attemptNo in 0..retryCount {
+        println!("{} {}", attemptNo, greetingText);
+    }
+    config::user_name();
+    greetingText
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_convert_functions_skips_trait_methods() {
+        use crate::indexers::scip_fixture::ScipFixture;
+
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        let path = root.join("src/lib.rs");
+        fs::write(
+            &path,
+            "struct Job;\n\nimpl Iterator for Job {\n    type Item = u8;\n    fn next(&mut self) -> Option<u8> { None }\n    fn size_hint(&self) -> (usize, Option<usize>) { (0, None) }\n}\n\nfn run_job() {}\n\npub fn start() { run_job(); }\n",
+        )
+        .unwrap();
+
+        // run_job: defined at 9:4, called at 11:18 (SCIP ranges are 0-indexed)
+        let scip_query = ScipFixture::new()
+            .document("src/lib.rs")
+            .definition("run_job().", 8, 3, 7)
+            .reference("run_job().", 10, 17, 7)
+            .query(root);
+
+        let summary = CaseConverter::new(Some(&scip_query))
+            .preview(ConvertCaseOptions {
+                path: root.join("src"),
+                kind: DeclarationKind::Function,
+                from: CaseStyle::Snake,
+                to: CaseStyle::Camel,
+                mode: TransactionMode::DryRun,
+            })
+            .unwrap();
+
+        // size_hint implements Iterator, so only run_job is renamed
+        assert_eq!(summary.total_changes, 2);
+        assert!(summary.file_changes[0].changes.iter().all(|c| c.replacement == "runJob"));
+    }
+}
@@ -0,0 +1,635 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::imports::{get_analyzer_for_file, ImportAnalyzer, ImportKind, ImportStatement};
+use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
+
+/// What's wrong with a file's imports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportIssueKind {
+    /// The same symbol imported from the same module more than once
+    DuplicateSymbol,
+
+    /// The same module imported by several statements of the same kind
+    DuplicateModule,
+
+    /// One name imported from different modules
+    ConflictingSource,
+}
+
+impl ImportIssueKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::DuplicateSymbol => "duplicate symbol",
+            Self::DuplicateModule => "duplicate module",
+            Self::ConflictingSource => "conflicting source",
+        }
+    }
+}
+
+/// A duplicate or conflicting import in one file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportIssue {
+    /// File, relative to the project root
+    pub file_path: PathBuf,
+
+    pub kind: ImportIssueKind,
+
+    /// The symbol, or the module for duplicate modules
+    pub name: String,
+
+    /// Modules involved, in file order (more than one only for conflicts)
+    pub sources: Vec<String>,
+
+    /// Line of each import involved (1-indexed)
+    pub lines: Vec<usize>,
+
+    /// Whether `--fix` merges it
+    pub fixable: bool,
+}
+
+impl ImportIssue {
+    pub fn message(&self) -> String {
+        let lines: Vec<String> = self.lines.iter().map(|line| line.to_string()).collect();
+        match self.kind {
+            ImportIssueKind::DuplicateSymbol => format!(
+                "'{}' is imported from '{}' {} times (lines {})",
+                self.name,
+                self.sources[0],
+                self.lines.len(),
+                lines.join(", ")
+            ),
+            ImportIssueKind::DuplicateModule => format!(
+                "'{}' is imported by {} statements (lines {})",
+                self.name,
+                self.lines.len(),
+                lines.join(", ")
+            ),
+            ImportIssueKind::ConflictingSource => {
+                let sources: Vec<String> = self.sources.iter().map(|source| format!("'{}'", source)).collect();
+                format!(
+                    "'{}' is imported from {} (lines {})",
+                    self.name,
+                    sources.join(" and "),
+                    lines.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Options for checking imports
+#[derive(Debug, Clone)]
+pub struct CheckImportsOptions {
+    /// File or directory to check (relative paths are resolved against the project root)
+    pub path: PathBuf,
+
+    /// Merge duplicate imports instead of only reporting them
+    pub fix: bool,
+}
+
+/// Result of an import check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckImportsResult {
+    pub files_checked: usize,
+
+    /// Issues found, before any fix was applied
+    pub issues: Vec<ImportIssue>,
+
+    /// Files that couldn't be parsed
+    pub warnings: Vec<String>,
+
+    pub files_fixed: usize,
+
+    /// Import statements folded into another by the fix
+    pub imports_merged: usize,
+
+    /// Transaction result, when fixes were applied
+    pub transaction_result: Option<TransactionResult>,
+}
+
+/// Statements importing one module, folded into the first of them
+struct Merge {
+    /// The first statement, carrying the symbols of all of them
+    statement: ImportStatement,
+
+    /// Whether the first statement must be re-rendered to hold the merged symbols
+    rewrite: bool,
+
+    /// The statements folded into it
+    dropped: Vec<ImportStatement>,
+}
+
+/// Find, and optionally merge, duplicate and conflicting imports
+pub struct ImportChecker {
+    project_root: PathBuf,
+}
+
+impl ImportChecker {
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+
+    /// Check every supported file under the path, merging duplicates when `fix` is set
+    pub fn check(&self, options: CheckImportsOptions) -> Result<CheckImportsResult> {
+        let root = fs::canonicalize(&self.project_root).with_context(|| {
+            format!("Project root not found: {}", self.project_root.display())
+        })?;
+        let path = fs::canonicalize(root.join(&options.path))
+            .with_context(|| format!("Path not found: {}", options.path.display()))?;
+
+        let files = source_files(&path)?;
+        let mut issues = Vec::new();
+        let mut warnings = Vec::new();
+        let mut edits = Vec::new();
+        let mut imports_merged = 0;
+
+        for file in &files {
+            let Some(analyzer) = get_analyzer_for_file(file) else {
+                continue;
+            };
+            let mut imports = match analyzer.find_imports(file) {
+                Ok(imports) => imports,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", file.display(), e));
+                    continue;
+                }
+            };
+            imports.sort_by_key(|import| (import.location.line, import.location.column));
+
+            let content = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let relative = file.strip_prefix(&root).unwrap_or(file);
+            let merges = plan_merges(&imports, &content);
+            issues.extend(find_issues(relative, &imports, &merges));
+
+            if options.fix && !merges.is_empty() {
+                let new_content = apply_merges(analyzer.as_ref(), &content, &merges)?;
+                imports_merged += merges.iter().map(|merge| merge.dropped.len()).sum::<usize>();
+                edits.push((file.clone(), content, new_content));
+            }
+        }
+
+        let files_fixed = edits.len();
+        let transaction_result = if edits.is_empty() {
+            None
+        } else {
+            let mut transaction = RefactoringTransaction::new(TransactionMode::Execute)
+                .with_journal(root.clone(), format!("merge duplicate imports in {}", options.path.display()));
+            for (file, original, new_content) in edits {
+                transaction.add_operation(file, original, new_content)?;
+            }
+            Some(transaction.commit()?)
+        };
+
+        Ok(CheckImportsResult {
+            files_checked: files.len(),
+            issues,
+            warnings,
+            files_fixed,
+            imports_merged,
+            transaction_result,
+        })
+    }
+}
+
+/// Files at `path` that have an import analyzer (a file, or a directory walked with .gitignore respected)
+fn source_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        if get_analyzer_for_file(path).is_none() {
+            anyhow::bail!(
+                "check-imports supports TypeScript/JavaScript, Python, Rust and C/C++ files: {}",
+                path.display()
+            );
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files: Vec<PathBuf> = WalkBuilder::new(path)
+        .standard_filters(true)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() && get_analyzer_for_file(p).is_some())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Names an import brings into scope, as the import model records them
+///
+/// Renamed named imports (`{ a as b }`, `from m import a as b`) are recorded under
+/// their original name. Plain `import pkg.mod` is skipped: several of those sharing
+/// a top-level package is normal.
+fn bound_names(import: &ImportStatement) -> Vec<String> {
+    match import.kind {
+        ImportKind::Named | ImportKind::FromImport | ImportKind::Default => import.symbols.clone(),
+        ImportKind::Namespace | ImportKind::SimpleImport => import.alias.iter().cloned().collect(),
+        ImportKind::Use => match &import.alias {
+            Some(alias) => vec![alias.clone()],
+            None => import
+                .symbols
+                .iter()
+                .filter(|symbol| *symbol != "*")
+                .map(|symbol| match symbol.as_str() {
+                    // `use a::b::{self}` brings in `b`
+                    "self" => import.source.rsplit("::").next().unwrap_or(symbol).to_string(),
+                    _ => symbol.clone(),
+                })
+                .collect(),
+        },
+        // Java wildcards bring in whole packages, so only single-type imports count
+        ImportKind::TypeImport | ImportKind::StaticImport => {
+            import.symbols.iter().filter(|symbol| *symbol != "*").cloned().collect()
+        }
+        ImportKind::SideEffect | ImportKind::Require | ImportKind::Include => Vec::new(),
+    }
+}
+
+/// Group values by key, keeping keys in order of first appearance
+fn group_by<K: PartialEq, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Vec<(K, Vec<V>)> {
+    let mut groups: Vec<(K, Vec<V>)> = Vec::new();
+    for (key, value) in pairs {
+        match groups.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, values)) => values.push(value),
+            None => groups.push((key, vec![value])),
+        }
+    }
+    groups
+}
+
+/// Statements of the same kind importing the same module, each listed once, for modules imported more than once
+///
+/// Rust is left out: a `use` imports items, and one per line is a common style. A Java
+/// import can only name one type, so those only count when the whole name repeats.
+fn duplicate_modules(imports: &[ImportStatement]) -> Vec<Vec<&ImportStatement>> {
+    let statements = imports
+        .iter()
+        .filter(|import| import.kind != ImportKind::Use)
+        .map(|import| {
+            let symbols = matches!(import.kind, ImportKind::TypeImport | ImportKind::StaticImport)
+                .then_some(import.symbols.as_slice());
+            ((import.source.as_str(), import.kind, symbols), import)
+        });
+
+    group_by(statements)
+        .into_iter()
+        .map(|(_, mut group)| {
+            group.dedup_by(|a, b| a.location == b.location);
+            group
+        })
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// The duplicate and conflicting imports of one file, in line order
+fn find_issues(file_path: &Path, imports: &[ImportStatement], merges: &[Merge]) -> Vec<ImportIssue> {
+    let merged_lines = |source: &str, lines: &[usize]| {
+        merges.iter().any(|merge| {
+            merge.statement.source == source
+                && lines.iter().all(|line| {
+                    std::iter::once(&merge.statement)
+                        .chain(&merge.dropped)
+                        .any(|import| import.location.line == *line)
+                })
+        })
+    };
+
+    let mut issues = Vec::new();
+    let named = || {
+        imports
+            .iter()
+            .flat_map(|import| bound_names(import).into_iter().map(move |name| (name, import)))
+    };
+
+    for ((source, name), occurrences) in group_by(named().map(|(name, import)| ((import.source.as_str(), name), import))) {
+        if occurrences.len() > 1 {
+            let lines: Vec<usize> = occurrences.iter().map(|import| import.location.line).collect();
+            issues.push(ImportIssue {
+                file_path: file_path.to_path_buf(),
+                kind: ImportIssueKind::DuplicateSymbol,
+                fixable: merged_lines(source, &lines),
+                name,
+                sources: vec![source.to_string()],
+                lines,
+            });
+        }
+    }
+
+    for group in duplicate_modules(imports) {
+        let source = &group[0].source;
+        let lines: Vec<usize> = group.iter().map(|import| import.location.line).collect();
+        issues.push(ImportIssue {
+            file_path: file_path.to_path_buf(),
+            kind: ImportIssueKind::DuplicateModule,
+            name: source.clone(),
+            sources: vec![source.clone()],
+            fixable: merged_lines(source, &lines),
+            lines,
+        });
+    }
+
+    for (name, occurrences) in group_by(named()) {
+        let mut sources: Vec<String> = Vec::new();
+        for import in &occurrences {
+            if !sources.contains(&import.source) {
+                sources.push(import.source.clone());
+            }
+        }
+        if sources.len() > 1 {
+            issues.push(ImportIssue {
+                file_path: file_path.to_path_buf(),
+                kind: ImportIssueKind::ConflictingSource,
+                name,
+                sources,
+                lines: occurrences.iter().map(|import| import.location.line).collect(),
+                fixable: false,
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.lines[0]);
+    issues
+}
+
+/// Source text of a statement's lines
+fn statement_text<'a>(content: &'a str, import: &ImportStatement) -> Vec<&'a str> {
+    content
+        .lines()
+        .skip(import.location.line.saturating_sub(1))
+        .take(import.location.end_line + 1 - import.location.line)
+        .collect()
+}
+
+/// Merges for the duplicate modules that can be folded safely
+///
+/// Named imports are merged into one statement; other kinds only when the statements
+/// are identical. Statements sharing a line with another import (`import os, sys`),
+/// renamed imports and TypeScript type-only imports are left alone, since re-rendering
+/// them would lose part of the line.
+fn plan_merges(imports: &[ImportStatement], content: &str) -> Vec<Merge> {
+    let shares_lines = |import: &ImportStatement| {
+        imports.iter().any(|other| {
+            other != import
+                && other.location.line <= import.location.end_line
+                && import.location.line <= other.location.end_line
+        })
+    };
+    let is_plain = |import: &ImportStatement| {
+        statement_text(content, import).iter().all(|line| {
+            let line = line.trim_start();
+            !line.contains(" as ") && !line.starts_with("import type ")
+        })
+    };
+
+    let mut merges = Vec::new();
+    for group in duplicate_modules(imports) {
+        if group.iter().any(|&import| shares_lines(import) || !is_plain(import)) {
+            continue;
+        }
+
+        let first = group[0];
+        let mut statement = first.clone();
+        match first.kind {
+            ImportKind::Named | ImportKind::FromImport => {
+                for import in &group[1..] {
+                    for symbol in &import.symbols {
+                        if !statement.symbols.contains(symbol) {
+                            statement.symbols.push(symbol.clone());
+                        }
+                    }
+                }
+            }
+            _ if group.iter().all(|import| import.symbols == first.symbols && import.alias == first.alias) => {}
+            _ => continue,
+        }
+
+        merges.push(Merge {
+            rewrite: statement.symbols != first.symbols,
+            statement,
+            dropped: group[1..].iter().map(|import| (*import).clone()).collect(),
+        });
+    }
+    merges
+}
+
+/// Fold each merge's statements into its first one: the first is re-rendered with the
+/// analyzer when its symbols grew, and the rest are removed
+fn apply_merges(analyzer: &dyn ImportAnalyzer, content: &str, merges: &[Merge]) -> Result<String> {
+    let mut lines: Vec<Option<String>> = content.split_inclusive('\n').map(|line| Some(line.to_string())).collect();
+    for merge in merges {
+        for import in &merge.dropped {
+            clear_lines(&mut lines, import);
+        }
+
+        if merge.rewrite {
+            let location = &merge.statement.location;
+            let original: String = lines
+                .iter()
+                .skip(location.line - 1)
+                .take(location.end_line + 1 - location.line)
+                .flatten()
+                .cloned()
+                .collect();
+            let rendered = match_style(&analyzer.render_import(&merge.statement)?, &original, &merge.statement.source);
+
+            clear_lines(&mut lines, &merge.statement);
+            lines[location.line - 1] = Some(rendered);
+        }
+    }
+
+    Ok(lines.into_iter().flatten().collect())
+}
+
+fn clear_lines(lines: &mut [Option<String>], import: &ImportStatement) {
+    for line in import.location.line..=import.location.end_line {
+        if let Some(slot) = lines.get_mut(line.wrapping_sub(1)) {
+            *slot = None;
+        }
+    }
+}
+
+/// Give a rendered statement the quotes, indentation and line ending of the one it replaces
+fn match_style(rendered: &str, original: &str, source: &str) -> String {
+    let mut text = rendered.to_string();
+    if original.contains(&format!("\"{}\"", source)) {
+        text = text.replace(&format!("'{}'", source), &format!("\"{}\"", source));
+    }
+
+    let indent: String = original.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+    let body = text.trim_end_matches('\n');
+    let ending = if original.ends_with("\r\n") {
+        "\r\n"
+    } else if original.ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    format!("{}{}{}", indent, body, ending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn check(root: &Path, path: &str, fix: bool) -> CheckImportsResult {
+        ImportChecker::new(root.to_path_buf())
+            .check(CheckImportsOptions {
+                path: PathBuf::from(path),
+                fix,
+            })
+            .unwrap()
+    }
+
+    fn kinds(result: &CheckImportsResult) -> Vec<(ImportIssueKind, &str, Vec<usize>, bool)> {
+        result
+            .issues
+            .iter()
+            .map(|issue| (issue.kind, issue.name.as_str(), issue.lines.clone(), issue.fixable))
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_symbol_and_module_are_merged() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "src/app.ts",
+            "import { a } from \"./x\";\nimport React from 'react';\nimport {\n  a,\n  b,\n} from \"./x\";\n\nconsole.log(a, b, React);\n",
+        );
+
+        let result = check(dir.path(), "src", false);
+        assert_eq!(result.files_checked, 1);
+        assert_eq!(
+            kinds(&result),
+            vec![
+                (ImportIssueKind::DuplicateSymbol, "a", vec![1, 3], true),
+                (ImportIssueKind::DuplicateModule, "./x", vec![1, 3], true),
+            ]
+        );
+        assert_eq!(result.issues[0].file_path, PathBuf::from("src/app.ts"));
+        assert!(result.issues[0].message().contains("'a' is imported from './x' 2 times (lines 1, 3)"));
+
+        // Checking alone changes nothing
+        assert!(result.transaction_result.is_none());
+        assert!(fs::read_to_string(&file).unwrap().starts_with("import { a } from \"./x\";\nimport React"));
+
+        let result = check(dir.path(), "src", true);
+        assert_eq!((result.files_fixed, result.imports_merged), (1, 1));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import { a, b } from \"./x\";\nimport React from 'react';\n\nconsole.log(a, b, React);\n"
+        );
+        assert!(check(dir.path(), "src", false).issues.is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_sources_are_reported_not_fixed() {
+        let dir = TempDir::new().unwrap();
+        let content = "import { parse } from './json';\nimport { parse } from './yaml';\nimport { Button } from './ui';\nimport Button from './legacy';\n";
+        let file = write(dir.path(), "app.ts", content);
+
+        let result = check(dir.path(), "app.ts", true);
+        assert_eq!(
+            kinds(&result),
+            vec![
+                (ImportIssueKind::ConflictingSource, "parse", vec![1, 2], false),
+                (ImportIssueKind::ConflictingSource, "Button", vec![3, 4], false),
+            ]
+        );
+        assert!(result.issues[0].message().contains("from './json' and './yaml'"));
+        assert_eq!(result.files_fixed, 0);
+        assert_eq!(fs::read_to_string(&file).unwrap(), content);
+    }
+
+    #[test]
+    fn test_python_duplicate_modules() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "pkg/main.py",
+            "import sys\nfrom os import path\nimport json, re\nfrom os import sep, path\nimport sys\nimport re\n\nprint(sys, path, sep, json, re)\n",
+        );
+
+        let result = check(dir.path(), "pkg", false);
+        assert_eq!(
+            kinds(&result),
+            vec![
+                (ImportIssueKind::DuplicateModule, "sys", vec![1, 5], true),
+                (ImportIssueKind::DuplicateSymbol, "path", vec![2, 4], true),
+                (ImportIssueKind::DuplicateModule, "os", vec![2, 4], true),
+                // `import json, re` shares its line, so it can't be dropped on its own
+                (ImportIssueKind::DuplicateModule, "re", vec![3, 6], false),
+            ]
+        );
+
+        let result = check(dir.path(), "pkg", true);
+        assert_eq!(result.imports_merged, 2);
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import sys\nfrom os import path, sep\nimport json, re\nimport re\n\nprint(sys, path, sep, json, re)\n"
+        );
+    }
+
+    #[test]
+    fn test_rust_duplicate_use_and_renamed_imports() {
+        let dir = TempDir::new().unwrap();
+        write(
+            dir.path(),
+            "src/lib.rs",
+            "use std::fmt::Display;\nuse std::fmt::{Debug, Display};\nuse std::io::Result;\nuse anyhow::Result;\n",
+        );
+        write(dir.path(), "src/view.ts", "import { a as b } from './x';\nimport { c } from './x';\n");
+
+        let result = check(dir.path(), ".", false);
+        let issues: Vec<_> = result
+            .issues
+            .iter()
+            .map(|issue| (issue.file_path.to_string_lossy().into_owned(), issue.kind, issue.name.clone(), issue.fixable))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("src/lib.rs".to_string(), ImportIssueKind::DuplicateSymbol, "Display".to_string(), false),
+                ("src/lib.rs".to_string(), ImportIssueKind::ConflictingSource, "Result".to_string(), false),
+                // Merging would drop the rename
+                ("src/view.ts".to_string(), ImportIssueKind::DuplicateModule, "./x".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_java_duplicate_and_conflicting_imports() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "src/Service.java",
+            "package app;\n\nimport java.util.*;\nimport java.util.List;\nimport java.awt.List;\nimport java.util.*;\nimport static java.lang.Math.max;\n\nclass Service {}\n",
+        );
+
+        let result = check(dir.path(), "src", true);
+        assert_eq!(
+            kinds(&result),
+            vec![
+                (ImportIssueKind::DuplicateModule, "java.util", vec![3, 6], true),
+                (ImportIssueKind::ConflictingSource, "List", vec![4, 5], false),
+            ]
+        );
+        assert_eq!(result.imports_merged, 1);
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "package app;\n\nimport java.util.*;\nimport java.util.List;\nimport java.awt.List;\nimport static java.lang.Math.max;\n\nclass Service {}\n"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tree_sitter::Node;
+
+use crate::core::location::byte_offset;
+
+use super::imports::get_analyzer_for_file;
+use super::syntax::parser_for_file;
+
+/// Node kinds that open a new scope, across the supported grammars
+const SCOPE_KINDS: &[&str] = &[
+    // Rust
+    "block",
+    "function_item",
+    "closure_expression",
+    "impl_item",
+    "trait_item",
+    "mod_item",
+    // TypeScript / JavaScript
+    "statement_block",
+    "function_declaration",
+    "function_expression",
+    "arrow_function",
+    "method_definition",
+    "class_body",
+    // Python
+    "function_definition",
+    "lambda",
+    "class_definition",
+    // C++
+    "compound_statement",
+    "lambda_expression",
+];
+
+/// Identifier node kinds that name a symbol (fields and properties are namespaced, so they can't collide)
+const IDENTIFIER_KINDS: &[&str] = &["identifier", "type_identifier"];
+
+/// Why a rename site would collide with the new name
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// The new name is already declared or used in a scope containing a rename site
+    ExistingSymbol,
+    /// The new name is already imported into the file
+    Import,
+}
+
+/// An existing use of the new name that the renamed symbol would clash with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenameConflict {
+    pub file_path: PathBuf,
+
+    /// Line of the existing symbol or import (1-indexed)
+    pub line: usize,
+
+    /// Column of the existing symbol or import (1-indexed)
+    pub column: usize,
+
+    pub kind: ConflictKind,
+
+    /// Human-readable explanation
+    pub message: String,
+}
+
+impl RenameConflict {
+    pub fn format(&self) -> String {
+        format!(
+            "{}:{}:{}: {}",
+            self.file_path.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// Find everything in one file that `new_name` would collide with at the given rename sites
+///
+/// Imports of `new_name` always conflict. Other identifiers named `new_name` conflict when
+/// their scope contains a rename site, since the renamed symbol would then shadow them
+/// or be shadowed by them.
+pub fn find_conflicts_in_file(
+    file_path: &Path,
+    content: &str,
+    new_name: &str,
+    rename_sites: &[(usize, usize)],
+) -> Result<Vec<RenameConflict>> {
+    let mut conflicts = Vec::new();
+    let mut import_lines = HashSet::new();
+
+    if let Some(analyzer) = get_analyzer_for_file(file_path) {
+        for import in analyzer.find_imports(file_path).unwrap_or_default() {
+            let imports_new_name = import.symbols.iter().any(|symbol| symbol == new_name)
+                || import.alias.as_deref() == Some(new_name);
+            if imports_new_name {
+                import_lines.insert(import.location.line);
+                conflicts.push(RenameConflict {
+                    file_path: file_path.to_path_buf(),
+                    line: import.location.line,
+                    column: import.location.column,
+                    kind: ConflictKind::Import,
+                    message: format!("'{}' is already imported from '{}'", new_name, import.source),
+                });
+            }
+        }
+    }
+
+    let Some(mut parser) = parser_for_file(file_path)? else {
+        return Ok(conflicts);
+    };
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse {}", file_path.display()))?;
+
+    let site_bytes: Vec<usize> = rename_sites
+        .iter()
+        .filter_map(|&(line, column)| byte_offset(content, line, column))
+        .collect();
+
+    let mut existing = Vec::new();
+    collect_named(tree.root_node(), content, new_name, &mut existing);
+
+    for node in existing {
+        let line = node.start_position().row + 1;
+        if import_lines.contains(&line) {
+            continue;
+        }
+
+        let scope = enclosing_scope(node).unwrap_or_else(|| tree.root_node());
+        if site_bytes.iter().any(|byte| scope.byte_range().contains(byte)) {
+            conflicts.push(RenameConflict {
+                file_path: file_path.to_path_buf(),
+                line,
+                column: node.start_position().column + 1,
+                kind: ConflictKind::ExistingSymbol,
+                message: format!("'{}' already exists in a scope the rename touches", new_name),
+            });
+        }
+    }
+
+    Ok(conflicts)
+}
+
+/// Collect identifier nodes whose text is `name`
+fn collect_named<'a>(node: Node<'a>, content: &str, name: &str, found: &mut Vec<Node<'a>>) {
+    if IDENTIFIER_KINDS.contains(&node.kind()) && &content[node.byte_range()] == name {
+        found.push(node);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_named(child, content, name, found);
+    }
+}
+
+/// Innermost scope an identifier is visible in
+/// A declaration's own name (e.g. `fn foo`) belongs to the scope around the declaration
+fn enclosing_scope(node: Node) -> Option<Node> {
+    let mut current = node.parent();
+    if let Some(parent) = current {
+        if parent.child_by_field_name("name").map(|n| n.id()) == Some(node.id()) {
+            current = parent.parent();
+        }
+    }
+
+    while let Some(candidate) = current {
+        if SCOPE_KINDS.contains(&candidate.kind()) {
+            return Some(candidate);
+        }
+        current = candidate.parent();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_rename_into_existing_local() {
+        let dir = TempDir::new().unwrap();
+        let content = "fn main() {\n    let total = 1;\n    let count = 2;\n    println!(\"{}\", count + total);\n}\n\nfn other() {\n    let total = 5;\n}\n";
+        let path = write(&dir, "main.rs", content);
+        // `count` is declared on line 3 and used on line 4
+        let sites = [(3, 9), (4, 20)];
+
+        let conflicts = find_conflicts_in_file(&path, content, "total", &sites).unwrap();
+        let lines: Vec<usize> = conflicts.iter().map(|c| c.line).collect();
+        assert!(lines.contains(&2), "expected the existing `total` local, got {:?}", conflicts);
+        // `other` has its own `total`, out of reach of the rename
+        assert!(!lines.contains(&8));
+        assert!(conflicts.iter().all(|c| c.kind == ConflictKind::ExistingSymbol));
+
+        let conflicts = find_conflicts_in_file(&path, content, "amount", &sites).unwrap();
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_rename_into_existing_import() {
+        let dir = TempDir::new().unwrap();
+        let content = "import { format } from './utils';\n\nexport function render(value: string) {\n  return value;\n}\n";
+        let path = write(&dir, "render.ts", content);
+        let sites = [(3, 24), (4, 10)];
+
+        let conflicts = find_conflicts_in_file(&path, content, "format", &sites).unwrap();
+        assert_eq!(conflicts.len(), 1, "got {:?}", conflicts);
+        assert_eq!(conflicts[0].kind, ConflictKind::Import);
+        assert_eq!(conflicts[0].line, 1);
+        assert!(conflicts[0].message.contains("./utils"));
+    }
+}
@@ -0,0 +1,493 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::imports::ImportKind;
+use super::move_file::{
+    edit_preview, find_script, normalize, render_python, render_script, resolve_python, resolve_script,
+    rewrite_file, script_import_path, FileEdit, ModuleKind,
+};
+use super::preview::RefactoringSummary;
+use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
+
+/// Which way import paths are converted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStyle {
+    /// `../utils/foo` (TypeScript) or `..utils.foo` (Python)
+    Relative,
+    /// `@/utils/foo` through a path alias (TypeScript) or `src.utils.foo` (Python)
+    Absolute,
+}
+
+impl ImportStyle {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Relative => "relative",
+            Self::Absolute => "absolute",
+        }
+    }
+}
+
+impl FromStr for ImportStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "relative" => Ok(Self::Relative),
+            "absolute" | "alias" => Ok(Self::Absolute),
+            _ => anyhow::bail!("Unknown import style '{}' (expected relative or absolute)", s),
+        }
+    }
+}
+
+/// Options for converting import styles
+#[derive(Debug, Clone)]
+pub struct ConvertImportsOptions {
+    /// File or directory whose imports are converted (relative paths are resolved against the project root)
+    pub path: PathBuf,
+
+    /// Style to convert imports to
+    pub to: ImportStyle,
+
+    /// TypeScript path alias prefix, e.g. `@/`; read from tsconfig.json's `paths` when not given
+    pub alias: Option<String>,
+
+    /// Directory the alias, or Python's absolute module paths, start from (defaults to the project root)
+    pub base: Option<PathBuf>,
+
+    /// Transaction mode (Execute or DryRun)
+    pub mode: TransactionMode,
+}
+
+/// Result of an import style conversion
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertImportsResult {
+    pub files_updated: usize,
+
+    pub imports_converted: usize,
+
+    /// Follow-up steps that couldn't be done automatically
+    pub warnings: Vec<String>,
+
+    /// Transaction result
+    pub transaction_result: TransactionResult,
+}
+
+/// A TypeScript path alias: imports starting with `prefix` name files under `dir`
+#[derive(Debug, Clone, PartialEq)]
+struct ScriptAlias {
+    prefix: String,
+    dir: PathBuf,
+}
+
+/// Everything a conversion will change, computed before touching the disk
+struct ConversionPlan {
+    edits: Vec<(PathBuf, FileEdit)>,
+    warnings: Vec<String>,
+}
+
+/// Rewrite imports between relative paths and absolute ones (TypeScript and Python)
+pub struct ImportConverter {
+    project_root: PathBuf,
+}
+
+impl ImportConverter {
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+
+    /// Perform the conversion
+    pub fn convert(&self, options: ConvertImportsOptions) -> Result<ConvertImportsResult> {
+        let plan = self.plan(&options)?;
+
+        let mut transaction = RefactoringTransaction::new(options.mode).with_journal(
+            self.project_root.clone(),
+            format!("convert imports in {} to {}", options.path.display(), options.to.label()),
+        );
+        for (path, edit) in &plan.edits {
+            transaction.add_operation(path.clone(), edit.original.clone(), edit.new_content.clone())?;
+        }
+        let transaction_result = transaction.commit()?;
+
+        Ok(ConvertImportsResult {
+            files_updated: plan.edits.len(),
+            imports_converted: plan.edits.iter().map(|(_, edit)| edit.rewrites.len()).sum(),
+            warnings: plan.warnings,
+            transaction_result,
+        })
+    }
+
+    /// Generate a preview of the conversion
+    pub fn preview(&self, options: ConvertImportsOptions) -> Result<RefactoringSummary> {
+        let plan = self.plan(&options)?;
+        let converted: usize = plan.edits.iter().map(|(_, edit)| edit.rewrites.len()).sum();
+        let file_changes = plan.edits.iter().map(|(path, edit)| edit_preview(path, edit)).collect();
+
+        let mut summary = RefactoringSummary::new(file_changes);
+        summary.warnings.insert(
+            0,
+            format!(
+                "🔁 {} import{} to convert to {} paths",
+                converted,
+                if converted == 1 { "" } else { "s" },
+                options.to.label()
+            ),
+        );
+        summary.warnings.extend(plan.warnings);
+        Ok(summary)
+    }
+
+    fn plan(&self, options: &ConvertImportsOptions) -> Result<ConversionPlan> {
+        let root = fs::canonicalize(&self.project_root).with_context(|| {
+            format!("Project root not found: {}", self.project_root.display())
+        })?;
+        let path = fs::canonicalize(root.join(&options.path))
+            .with_context(|| format!("Path not found: {}", options.path.display()))?;
+        let base = match &options.base {
+            Some(base) => fs::canonicalize(root.join(base))
+                .with_context(|| format!("Base directory not found: {}", base.display()))?,
+            None => root.clone(),
+        };
+
+        let files = source_files(&path)?;
+        let aliases = if files.iter().any(|file| ModuleKind::of(file) == Some(ModuleKind::Script)) {
+            match &options.alias {
+                Some(prefix) => vec![ScriptAlias {
+                    prefix: prefix.clone(),
+                    dir: base.clone(),
+                }],
+                None if options.base.is_some() => {
+                    anyhow::bail!("--base for TypeScript imports needs the alias it stands for (--alias)")
+                }
+                None => tsconfig_aliases(&root)?,
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut warnings = Vec::new();
+        let mut edits = Vec::new();
+        for file in files {
+            let dir = file.parent().unwrap_or(&root).to_path_buf();
+
+            let edit = match (ModuleKind::of(&file), options.to) {
+                (Some(ModuleKind::Script), _) if aliases.is_empty() => {
+                    anyhow::bail!(
+                        "No path alias for TypeScript imports: add `paths` to tsconfig.json or pass --alias"
+                    )
+                }
+                (Some(ModuleKind::Script), ImportStyle::Absolute) => rewrite_file(&file, &mut warnings, |import| {
+                    let target = resolve_script(&dir, &import.source)?;
+                    let new_source = alias_source(&aliases, &target, &import.source)?;
+                    Some((import.source.clone(), new_source))
+                })?,
+                (Some(ModuleKind::Script), ImportStyle::Relative) => rewrite_file(&file, &mut warnings, |import| {
+                    let target = resolve_alias(&aliases, &import.source)?;
+                    render_script(&dir, &target, &import.source)
+                })?,
+                (Some(ModuleKind::Python), ImportStyle::Absolute) => rewrite_file(&file, &mut warnings, |import| {
+                    if !import.source.starts_with('.') {
+                        return None;
+                    }
+                    let target = resolve_python(&file, &import.source, &base)?;
+                    let new_source = render_python(&dir, &target, false, &base)?;
+                    Some((import.source.clone(), new_source))
+                })?,
+                (Some(ModuleKind::Python), ImportStyle::Relative) => rewrite_file(&file, &mut warnings, |import| {
+                    // `import pkg.mod` has no relative form
+                    if import.source.starts_with('.') || import.kind != ImportKind::FromImport {
+                        return None;
+                    }
+                    let target = resolve_python(&file, &import.source, &base)?;
+                    let new_source = render_python(&dir, &target, true, &base)?;
+
+                    // Relative imports can't climb above the top-level package
+                    let levels = new_source.chars().take_while(|c| *c == '.').count();
+                    let depth = dir.strip_prefix(&base).ok()?.components().count();
+                    (levels <= depth).then(|| (import.source.clone(), new_source))
+                })?,
+                _ => None,
+            };
+
+            if let Some(edit) = edit {
+                edits.push((file, edit));
+            }
+        }
+
+        Ok(ConversionPlan { edits, warnings })
+    }
+}
+
+/// TypeScript and Python files at `path` (a file, or a directory walked with .gitignore respected)
+fn source_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let is_supported = |p: &Path| matches!(ModuleKind::of(p), Some(ModuleKind::Script | ModuleKind::Python));
+
+    if path.is_file() {
+        if !is_supported(path) {
+            anyhow::bail!("convert-imports supports TypeScript/JavaScript and Python files: {}", path.display());
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files: Vec<PathBuf> = WalkBuilder::new(path)
+        .standard_filters(true)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() && is_supported(p))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Aliases from tsconfig.json: each `"prefix/*": ["dir/*"]` entry of `compilerOptions.paths`,
+/// or a bare `baseUrl` (imports named from the base directory) when there are none
+fn tsconfig_aliases(root: &Path) -> Result<Vec<ScriptAlias>> {
+    let path = root.join("tsconfig.json");
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let json: serde_json::Value =
+        serde_json::from_str(&strip_jsonc(&text)).with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let options = &json["compilerOptions"];
+    let base_url = normalize(&root.join(options["baseUrl"].as_str().unwrap_or(".")));
+
+    let mut aliases = Vec::new();
+    if let Some(paths) = options["paths"].as_object() {
+        for (pattern, targets) in paths {
+            let target = targets[0].as_str().and_then(|t| t.strip_suffix('*'));
+            match (pattern.strip_suffix('*'), target) {
+                // A bare "*" catch-all maps every package name, not a project alias
+                (Some(prefix), Some(target)) if !prefix.is_empty() => aliases.push(ScriptAlias {
+                    prefix: prefix.to_string(),
+                    dir: normalize(&base_url.join(target)),
+                }),
+                _ => {}
+            }
+        }
+    }
+    if aliases.is_empty() && options["baseUrl"].is_string() {
+        aliases.push(ScriptAlias {
+            prefix: String::new(),
+            dir: base_url,
+        });
+    }
+    Ok(aliases)
+}
+
+/// tsconfig.json allows comments and trailing commas; drop them so it parses as JSON
+fn strip_jsonc(text: &str) -> String {
+    let mut without_comments = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            _ if in_string => {
+                without_comments.push(c);
+                match c {
+                    '\\' => without_comments.extend(chars.next()),
+                    '"' => in_string = false,
+                    _ => {}
+                }
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                while let Some(next) = chars.next() {
+                    if next == '*' && chars.next_if_eq(&'/').is_some() {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                in_string = c == '"';
+                without_comments.push(c);
+            }
+        }
+    }
+
+    let mut output = String::with_capacity(without_comments.len());
+    let mut chars = without_comments.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        let is_trailing_comma = c == ',' && matches!(chars.clone().find(|next| !next.is_whitespace()), Some('}' | ']'));
+        if !is_trailing_comma {
+            in_string = c == '"';
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// Aliased import of `target`, through the alias whose directory is the closest ancestor
+fn alias_source(aliases: &[ScriptAlias], target: &Path, original_source: &str) -> Option<String> {
+    let alias = aliases
+        .iter()
+        .filter(|alias| target.starts_with(&alias.dir))
+        .max_by_key(|alias| alias.dir.components().count())?;
+
+    let path = script_import_path(target, original_source)?;
+    let relative = path.strip_prefix(&alias.dir).ok()?.to_string_lossy().replace('\\', "/");
+    if relative.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", alias.prefix, relative))
+}
+
+/// The file an aliased import names, through the longest matching alias prefix
+fn resolve_alias(aliases: &[ScriptAlias], source: &str) -> Option<PathBuf> {
+    if source.starts_with('.') {
+        return None;
+    }
+    aliases
+        .iter()
+        .filter(|alias| source.starts_with(&alias.prefix))
+        .max_by_key(|alias| alias.prefix.len())
+        .and_then(|alias| find_script(&normalize(&alias.dir.join(&source[alias.prefix.len()..]))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn convert(root: &Path, path: &str, to: ImportStyle, alias: Option<&str>, base: Option<&str>) -> ConvertImportsResult {
+        ImportConverter::new(root.to_path_buf())
+            .convert(ConvertImportsOptions {
+                path: PathBuf::from(path),
+                to,
+                alias: alias.map(String::from),
+                base: base.map(PathBuf::from),
+                mode: TransactionMode::Execute,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_relative_to_alias_from_tsconfig() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "tsconfig.json",
+            "{\n  // Path aliases\n  \"compilerOptions\": {\n    \"baseUrl\": \".\",\n    \"paths\": { \"@/*\": [\"src/*\"], },\n  },\n}\n",
+        );
+        write(root, "src/utils/foo.ts", "export const foo = 1;\n");
+        write(root, "src/components/index.ts", "export {};\n");
+        let page = write(
+            root,
+            "src/pages/admin/home.ts",
+            "import { foo } from '../../utils/foo';\nimport '../../components';\nimport { x } from './local.js';\nimport React from 'react';\n",
+        );
+        write(root, "src/pages/admin/local.ts", "export const x = 1;\n");
+
+        let result = convert(root, "src/pages", ImportStyle::Absolute, None, None);
+        assert_eq!(result.files_updated, 1);
+        assert_eq!(result.imports_converted, 3);
+        assert_eq!(
+            fs::read_to_string(&page).unwrap(),
+            "import { foo } from '@/utils/foo';\nimport '@/components';\nimport { x } from '@/pages/admin/local.js';\nimport React from 'react';\n"
+        );
+
+        // ... and back again
+        let result = convert(root, "src/pages/admin/home.ts", ImportStyle::Relative, None, None);
+        assert_eq!(result.imports_converted, 3);
+        assert_eq!(
+            fs::read_to_string(&page).unwrap(),
+            "import { foo } from '../../utils/foo';\nimport '../../components';\nimport { x } from './local.js';\nimport React from 'react';\n"
+        );
+    }
+
+    #[test]
+    fn test_python_relative_and_absolute() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "src/__init__.py", "");
+        write(root, "src/utils/__init__.py", "");
+        write(root, "src/utils/foo.py", "def foo(): pass\n");
+        let handler = write(
+            root,
+            "src/api/handler.py",
+            "import os\nfrom ..utils.foo import foo\nfrom . import models\n",
+        );
+        write(root, "src/api/__init__.py", "");
+        write(root, "src/api/models.py", "");
+
+        let result = convert(root, "src", ImportStyle::Absolute, None, None);
+        assert_eq!(result.imports_converted, 2);
+        assert_eq!(
+            fs::read_to_string(&handler).unwrap(),
+            "import os\nfrom src.utils.foo import foo\nfrom src.api import models\n"
+        );
+
+        let result = convert(root, "src/api/handler.py", ImportStyle::Relative, None, None);
+        assert_eq!(result.imports_converted, 2);
+        assert_eq!(
+            fs::read_to_string(&handler).unwrap(),
+            "import os\nfrom ..utils.foo import foo\nfrom . import models\n"
+        );
+
+        // With src/ as the base, `src` is no longer a package to climb into
+        convert(root, "src", ImportStyle::Absolute, None, Some("src"));
+        assert_eq!(
+            fs::read_to_string(&handler).unwrap(),
+            "import os\nfrom utils.foo import foo\nfrom api import models\n"
+        );
+        let result = convert(root, "src/api/handler.py", ImportStyle::Relative, None, Some("src"));
+        assert_eq!(result.imports_converted, 1);
+        assert_eq!(
+            fs::read_to_string(&handler).unwrap(),
+            "import os\nfrom utils.foo import foo\nfrom . import models\n"
+        );
+    }
+
+    #[test]
+    fn test_explicit_alias_and_preview() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "app/lib/api.ts", "export {};\n");
+        let main = write(root, "app/main.ts", "import * as api from './lib/api';\n");
+
+        let summary = ImportConverter::new(root.to_path_buf())
+            .preview(ConvertImportsOptions {
+                path: PathBuf::from("app"),
+                to: ImportStyle::Absolute,
+                alias: Some("~/".to_string()),
+                base: Some(PathBuf::from("app")),
+                mode: TransactionMode::DryRun,
+            })
+            .unwrap();
+        assert_eq!(summary.total_changes, 1);
+        assert!(summary.file_changes[0].changes[0].line_content.contains("'~/lib/api'"));
+        assert_eq!(fs::read_to_string(&main).unwrap(), "import * as api from './lib/api';\n");
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        let text = "{ \"a\": \"http://x\", /* note */ \"b\": [1, 2,], // end\n}";
+        let json: serde_json::Value = serde_json::from_str(&strip_jsonc(text)).unwrap();
+        assert_eq!(json, serde_json::json!({ "a": "http://x", "b": [1, 2] }));
+    }
+}
@@ -0,0 +1,375 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::imports::{ImportSpecifier, RequireBinding, ScriptImport, ScriptRequire, TypeScriptImportAnalyzer};
+use super::merge_imports::stands_alone;
+use super::move_file::ModuleKind;
+use super::preview::{ChangeType, ImportChange, PreviewChange, PreviewDiff, RefactoringSummary};
+use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
+
+/// Options for converting CommonJS requires to ES imports
+#[derive(Debug, Clone)]
+pub struct ConvertRequireOptions {
+    /// File or directory to convert (relative paths are resolved against the project root)
+    pub path: PathBuf,
+
+    /// Transaction mode (Execute or DryRun)
+    pub mode: TransactionMode,
+}
+
+/// Result of a require conversion
+#[derive(Debug, Clone, Serialize)]
+pub struct ConvertRequireResult {
+    pub files_updated: usize,
+
+    pub requires_converted: usize,
+
+    /// Requires left as they were, and other follow-up steps
+    pub warnings: Vec<String>,
+
+    /// Transaction result
+    pub transaction_result: TransactionResult,
+}
+
+/// One file's requires and the imports replacing them
+struct RequireEdit {
+    original: String,
+    new_content: String,
+    conversions: Vec<(ScriptRequire, ScriptImport)>,
+}
+
+/// Everything a conversion will change, computed before touching the disk
+struct ConversionPlan {
+    edits: Vec<(PathBuf, RequireEdit)>,
+    warnings: Vec<String>,
+}
+
+/// Rewrite top-level `require` calls as the equivalent ES imports (TypeScript/JavaScript)
+pub struct RequireConverter {
+    project_root: PathBuf,
+}
+
+impl RequireConverter {
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+
+    /// Perform the conversion
+    pub fn convert(&self, options: ConvertRequireOptions) -> Result<ConvertRequireResult> {
+        let plan = self.plan(&options)?;
+
+        let mut transaction = RefactoringTransaction::new(options.mode);
+        if !plan.edits.is_empty() {
+            transaction = transaction.with_journal(
+                self.project_root.clone(),
+                format!("convert requires in {} to imports", options.path.display()),
+            );
+        }
+        for (path, edit) in &plan.edits {
+            transaction.add_operation(path.clone(), edit.original.clone(), edit.new_content.clone())?;
+        }
+        let transaction_result = transaction.commit()?;
+
+        Ok(ConvertRequireResult {
+            files_updated: plan.edits.len(),
+            requires_converted: plan.edits.iter().map(|(_, edit)| edit.conversions.len()).sum(),
+            warnings: plan.warnings,
+            transaction_result,
+        })
+    }
+
+    /// Generate a preview of the conversion
+    pub fn preview(&self, options: ConvertRequireOptions) -> Result<RefactoringSummary> {
+        let plan = self.plan(&options)?;
+
+        let mut file_changes = Vec::new();
+        for (path, edit) in &plan.edits {
+            let lines: Vec<&str> = edit.original.lines().collect();
+            let mut diff = PreviewDiff::new(path.clone());
+            for (require, import) in &edit.conversions {
+                let location = &require.location;
+                diff.add_change(PreviewChange {
+                    line: location.line,
+                    column: 1,
+                    original: lines[location.line - 1..location.end_line].join("\n").trim().to_string(),
+                    replacement: import.render(location.end_line > location.line),
+                    line_content: lines[location.line - 1].to_string(),
+                });
+                diff.add_import_change(ImportChange {
+                    change_type: ChangeType::ImportUpdate,
+                    source: require.source.clone(),
+                    symbols: require.bound_names(),
+                    line: location.line,
+                });
+            }
+            diff.calculate_risk();
+            file_changes.push(diff);
+        }
+
+        let converted: usize = plan.edits.iter().map(|(_, edit)| edit.conversions.len()).sum();
+        let mut summary = RefactoringSummary::new(file_changes);
+        summary.warnings.insert(
+            0,
+            format!(
+                "🔁 {} require{} to convert to ES imports",
+                converted,
+                if converted == 1 { "" } else { "s" }
+            ),
+        );
+        summary.warnings.extend(plan.warnings);
+        Ok(summary)
+    }
+
+    fn plan(&self, options: &ConvertRequireOptions) -> Result<ConversionPlan> {
+        let root = fs::canonicalize(&self.project_root).with_context(|| {
+            format!("Project root not found: {}", self.project_root.display())
+        })?;
+        let path = fs::canonicalize(root.join(&options.path))
+            .with_context(|| format!("Path not found: {}", options.path.display()))?;
+
+        let analyzer = TypeScriptImportAnalyzer::new();
+        let mut warnings = Vec::new();
+        let mut edits = Vec::new();
+        for file in script_files(&path)? {
+            let display = file.strip_prefix(&root).unwrap_or(&file).display().to_string();
+            let original = fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+            let requires = match analyzer.find_script_requires(&original) {
+                Ok(requires) => requires,
+                Err(e) => {
+                    warnings.push(format!("{}: {}", display, e));
+                    continue;
+                }
+            };
+            let lines: Vec<&str> = original.lines().collect();
+
+            let mut conversions = Vec::new();
+            for require in requires {
+                let import = to_import(&require).and_then(|import| {
+                    if stands_alone(&require.location, &require.source, &lines) {
+                        Ok(import)
+                    } else {
+                        Err("it shares its line with other code or has comments".to_string())
+                    }
+                });
+                match import {
+                    Ok(import) => conversions.push((require, import)),
+                    Err(reason) => warnings.push(format!(
+                        "{}:{}: left require('{}') as is: {}",
+                        display, require.location.line, require.source, reason
+                    )),
+                }
+            }
+            if conversions.is_empty() {
+                continue;
+            }
+
+            if original.contains("module.exports") || original.contains("exports.") {
+                warnings.push(format!(
+                    "{}: still exports through module.exports/exports, which an ES module can't; convert its exports too",
+                    display
+                ));
+            }
+            let new_content = apply(&original, &conversions);
+            edits.push((
+                file,
+                RequireEdit {
+                    original,
+                    new_content,
+                    conversions,
+                },
+            ));
+        }
+
+        Ok(ConversionPlan { edits, warnings })
+    }
+}
+
+/// TypeScript/JavaScript files at `path` (a file, or a directory walked with .gitignore respected)
+fn script_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let is_script = |p: &Path| ModuleKind::of(p) == Some(ModuleKind::Script);
+
+    if path.is_file() {
+        if !is_script(path) {
+            anyhow::bail!("convert-require supports TypeScript/JavaScript files: {}", path.display());
+        }
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files: Vec<PathBuf> = WalkBuilder::new(path)
+        .standard_filters(true)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|p| p.is_file() && is_script(p))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The ES import equivalent to a require, or why there's none
+///
+/// A whole-module require becomes a default import when it's called or constructed
+/// (`express()`), and a namespace import when it's only used as an object (`fs.readFileSync`).
+fn to_import(require: &ScriptRequire) -> Result<ScriptImport, String> {
+    if require.reassigned {
+        return Err(format!("{} is reassigned later", require.bound_names().join(", ")));
+    }
+
+    let import = ScriptImport {
+        source: require.source.clone(),
+        default: None,
+        namespace: None,
+        named: Vec::new(),
+        type_only: false,
+        location: require.location.clone(),
+        quote: require.quote,
+        semicolon: require.semicolon,
+    };
+    Ok(match &require.binding {
+        RequireBinding::Whole(name) if require.called => ScriptImport {
+            default: Some(name.clone()),
+            ..import
+        },
+        RequireBinding::Whole(name) => ScriptImport {
+            namespace: Some(name.clone()),
+            ..import
+        },
+        RequireBinding::Default(name) => ScriptImport {
+            default: Some(name.clone()),
+            ..import
+        },
+        RequireBinding::Member { property, name } => ScriptImport {
+            named: vec![ImportSpecifier {
+                name: property.clone(),
+                alias: (name != property).then(|| name.clone()),
+                type_only: false,
+            }],
+            ..import
+        },
+        RequireBinding::Destructured(specifiers) => ScriptImport {
+            named: specifiers.clone(),
+            ..import
+        },
+        RequireBinding::SideEffect => import,
+        RequireBinding::Unsupported(reason) => return Err(reason.clone()),
+    })
+}
+
+/// Replace each require statement's lines with its import
+fn apply(content: &str, conversions: &[(ScriptRequire, ScriptImport)]) -> String {
+    let mut lines: Vec<Option<String>> = content.split_inclusive('\n').map(|line| Some(line.to_string())).collect();
+
+    for (require, import) in conversions {
+        let location = &require.location;
+        for line in location.line..=location.end_line {
+            lines[line - 1] = None;
+        }
+
+        let last = content.split_inclusive('\n').nth(location.end_line - 1).unwrap_or_default();
+        let ending = if last.ends_with("\r\n") {
+            "\r\n"
+        } else if last.ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        let newline = if ending.is_empty() { "\n" } else { ending };
+        let rendered = import.render(location.end_line > location.line);
+        lines[location.line - 1] = Some(format!("{}{}", rendered.replace('\n', newline), ending));
+    }
+
+    lines.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn convert(root: &Path, path: &str) -> ConvertRequireResult {
+        RequireConverter::new(root.to_path_buf())
+            .convert(ConvertRequireOptions {
+                path: PathBuf::from(path),
+                mode: TransactionMode::Execute,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_destructured_requires() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "src/app.js",
+            "const { a, b: c } = require('./m');\nconst {\n  d,\n  e,\n} = require('./m');\nconst y = require('./n').y;\nconst z = require('./n').w;\n\nconsole.log(a, c, d, e, y, z);\n",
+        );
+
+        let result = convert(dir.path(), "src");
+        assert_eq!((result.files_updated, result.requires_converted), (1, 4));
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import { a, b as c } from './m';\nimport {\n  d,\n  e,\n} from './m';\nimport { y } from './n';\nimport { w as z } from './n';\n\nconsole.log(a, c, d, e, y, z);\n"
+        );
+    }
+
+    #[test]
+    fn test_default_and_namespace_requires() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "server.ts",
+            "const express = require('express');\nconst fs = require(\"fs\")\nconst Foo = require('./foo').default;\n\nconst app = express();\nfs.readFileSync('x');\nnew Foo();\n",
+        );
+
+        let summary = RequireConverter::new(dir.path().to_path_buf())
+            .preview(ConvertRequireOptions {
+                path: PathBuf::from("server.ts"),
+                mode: TransactionMode::DryRun,
+            })
+            .unwrap();
+        assert!(summary.warnings[0].contains("3 requires to convert"));
+        assert_eq!(summary.file_changes[0].changes[1].replacement, "import * as fs from \"fs\"");
+        assert!(fs::read_to_string(&file).unwrap().starts_with("const express"));
+
+        convert(dir.path(), "server.ts");
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import express from 'express';\nimport * as fs from \"fs\"\nimport Foo from './foo';\n\nconst app = express();\nfs.readFileSync('x');\nnew Foo();\n"
+        );
+    }
+
+    #[test]
+    fn test_side_effect_and_unconvertible_requires() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "index.js",
+            "require('./polyfill');\nlet config = require('./config');\nconst { a = 1 } = require('./d');\nconst x = require('./x'); // keep\n\nfunction load() {\n  return require('./lazy');\n}\nconfig = load();\nmodule.exports = { a, x };\n",
+        );
+
+        let result = convert(dir.path(), ".");
+        assert_eq!(result.requires_converted, 1);
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import './polyfill';\nlet config = require('./config');\nconst { a = 1 } = require('./d');\nconst x = require('./x'); // keep\n\nfunction load() {\n  return require('./lazy');\n}\nconfig = load();\nmodule.exports = { a, x };\n"
+        );
+
+        let warnings = result.warnings.join("\n");
+        assert!(warnings.contains("index.js:2: left require('./config') as is: config is reassigned later"), "{}", warnings);
+        assert!(warnings.contains("index.js:3: left require('./d') as is: destructures with default values"), "{}", warnings);
+        assert!(warnings.contains("index.js:4: left require('./x') as is: it shares its line"), "{}", warnings);
+        assert!(warnings.contains("index.js: still exports through module.exports"), "{}", warnings);
+        assert!(!warnings.contains("lazy"), "{}", warnings);
+    }
+}
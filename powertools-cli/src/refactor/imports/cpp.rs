@@ -88,10 +88,7 @@ impl ImportAnalyzer for CppImportAnalyzer {
         Ok(imports)
     }
 
-    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
-        let content = fs::read_to_string(file)
-            .with_context(|| format!("Failed to read file: {}", file.display()))?;
-
+    fn render_import(&self, import: &ImportStatement) -> Result<String> {
         // Determine if it's a system or local include
         let include_line = if import.source.contains('/') && !import.source.starts_with("std") {
             // Local include
@@ -101,6 +98,15 @@ impl ImportAnalyzer for CppImportAnalyzer {
             format!("#include <{}>\n", import.source)
         };
 
+        Ok(include_line)
+    }
+
+    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let include_line = self.render_import(import)?;
+
         // Find the position to insert (after the last #include, or at the start)
         let existing_imports = self.find_imports(file)?;
         let insert_pos = if let Some(last_import) = existing_imports.last() {
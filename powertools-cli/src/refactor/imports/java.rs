@@ -0,0 +1,307 @@
+use super::{ImportAnalyzer, ImportKind, ImportLocation, ImportStatement};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+pub struct JavaImportAnalyzer;
+
+impl JavaImportAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn create_parser() -> Parser {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .expect("Failed to load Java grammar");
+        parser
+    }
+
+    fn extract_import_from_node(&self, node: Node, source: &str) -> Option<ImportStatement> {
+        if node.kind() != "import_declaration" {
+            return None;
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+
+        let path_node = children
+            .iter()
+            .find(|n| n.kind() == "scoped_identifier" || n.kind() == "identifier")?;
+        let path = &source[path_node.byte_range()];
+        let is_static = children.iter().any(|n| n.kind() == "static");
+        let is_wildcard = children.iter().any(|n| n.kind() == "asterisk");
+
+        // `import a.b.C;` imports C from a.b; `import a.b.*;` imports everything in a.b
+        let (module, symbol) = if is_wildcard {
+            (path, "*")
+        } else {
+            path.rsplit_once('.').unwrap_or(("", path))
+        };
+
+        let location = ImportLocation {
+            line: node.start_position().row + 1,
+            column: node.start_position().column,
+            end_line: node.end_position().row + 1,
+            end_column: node.end_position().column,
+        };
+
+        Some(ImportStatement {
+            source: module.to_string(),
+            symbols: vec![symbol.to_string()],
+            location,
+            kind: if is_static {
+                ImportKind::StaticImport
+            } else {
+                ImportKind::TypeImport
+            },
+            alias: None,
+        })
+    }
+
+    /// Fully-qualified name of an import, e.g. `java.util.List` or `java.util.*`
+    fn qualified_name(import: &ImportStatement) -> String {
+        match import.symbols.first() {
+            Some(symbol) if !import.source.is_empty() => format!("{}.{}", import.source, symbol),
+            Some(symbol) => symbol.clone(),
+            None => import.source.clone(),
+        }
+    }
+
+    /// Order imports are kept in: static imports after type imports, each group by name
+    fn sort_key(import: &ImportStatement) -> (bool, String) {
+        (import.kind == ImportKind::StaticImport, Self::qualified_name(import))
+    }
+}
+
+/// Byte offset of the start of a 1-indexed line (the end of the content past the last line)
+fn line_start(content: &str, line: usize) -> usize {
+    content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum()
+}
+
+impl ImportAnalyzer for JavaImportAnalyzer {
+    fn find_imports(&self, file: &Path) -> Result<Vec<ImportStatement>> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let mut parser = Self::create_parser();
+        let tree = parser
+            .parse(&content, None)
+            .context("Failed to parse Java file")?;
+
+        // Imports can only appear at the top level, after the package declaration
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let imports = root
+            .children(&mut cursor)
+            .filter_map(|node| self.extract_import_from_node(node, &content))
+            .collect();
+
+        Ok(imports)
+    }
+
+    fn render_import(&self, import: &ImportStatement) -> Result<String> {
+        let keyword = match import.kind {
+            ImportKind::TypeImport => "import",
+            ImportKind::StaticImport => "import static",
+            _ => {
+                anyhow::bail!("Unsupported import kind for Java: {:?}", import.kind);
+            }
+        };
+
+        Ok(format!("{} {};\n", keyword, Self::qualified_name(import)))
+    }
+
+    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let import_line = self.render_import(import)?;
+
+        let existing_imports = self.find_imports(file)?;
+        let key = Self::sort_key(import);
+        if existing_imports.iter().any(|existing| Self::sort_key(existing) == key) {
+            return Ok(content);
+        }
+
+        // Before the first import that sorts after it, after the last import, or after the
+        // package declaration (separated by a blank line) when there are none
+        let next_import = existing_imports.iter().find(|existing| Self::sort_key(existing) > key);
+        let (insert_pos, text) = if let Some(next) = next_import {
+            (line_start(&content, next.location.line), import_line)
+        } else if let Some(last_import) = existing_imports.last() {
+            (line_start(&content, last_import.location.end_line + 1), import_line)
+        } else {
+            match content.lines().position(|line| line.trim_start().starts_with("package ")) {
+                Some(index) => (line_start(&content, index + 2), format!("\n{}", import_line)),
+                None => (0, import_line),
+            }
+        };
+
+        let mut new_content = content.clone();
+        if insert_pos < content.len() {
+            new_content.insert_str(insert_pos, &text);
+        } else {
+            if !content.is_empty() && !content.ends_with('\n') {
+                new_content.push('\n');
+            }
+            new_content.push_str(&text);
+        }
+
+        Ok(new_content)
+    }
+
+    fn remove_import(&self, file: &Path, symbol: &str) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let imports = self.find_imports(file)?;
+        let mut lines: Vec<Option<&str>> = content.split_inclusive('\n').map(Some).collect();
+
+        for import in imports {
+            // Match the fully-qualified name (`java.util.List`) or the simple one (`List`)
+            if Self::qualified_name(&import) == symbol || import.symbols.iter().any(|s| s == symbol) {
+                for line_num in import.location.line..=import.location.end_line {
+                    if line_num > 0 && line_num <= lines.len() {
+                        lines[line_num - 1] = None;
+                    }
+                }
+            }
+        }
+
+        Ok(lines.into_iter().flatten().collect())
+    }
+
+    fn update_import_path(&self, file: &Path, old_path: &str, new_path: &str) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let imports = self.find_imports(file)?;
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+
+        // Rewrite imports of the package itself and of anything nested in it
+        for import in imports {
+            let name = Self::qualified_name(&import);
+            let matches = name == old_path || name.starts_with(&format!("{}.", old_path));
+            if let Some(line) = lines.get_mut(import.location.line - 1).filter(|_| matches) {
+                *line = line.replacen(old_path, new_path, 1);
+            }
+        }
+
+        Ok(lines.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn java_file(code: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_find_type_static_and_wildcard_imports() {
+        let file = java_file(
+            r#"package com.example.service;
+
+import java.util.List;
+import java.util.concurrent.*;
+import static org.junit.Assert.assertEquals;
+import static java.lang.Math.*;
+
+public class Service {}
+"#,
+        );
+
+        let analyzer = JavaImportAnalyzer::new();
+        let imports = analyzer.find_imports(file.path()).unwrap();
+
+        assert_eq!(imports.len(), 4);
+        assert_eq!(imports[0].source, "java.util");
+        assert_eq!(imports[0].symbols, vec!["List"]);
+        assert_eq!(imports[0].kind, ImportKind::TypeImport);
+        assert_eq!(imports[0].location.line, 3);
+
+        assert_eq!(imports[1].source, "java.util.concurrent");
+        assert_eq!(imports[1].symbols, vec!["*"]);
+        assert_eq!(imports[1].kind, ImportKind::TypeImport);
+
+        assert_eq!(imports[2].source, "org.junit.Assert");
+        assert_eq!(imports[2].symbols, vec!["assertEquals"]);
+        assert_eq!(imports[2].kind, ImportKind::StaticImport);
+
+        assert_eq!(imports[3].source, "java.lang.Math");
+        assert_eq!(imports[3].symbols, vec!["*"]);
+        assert_eq!(imports[3].kind, ImportKind::StaticImport);
+    }
+
+    #[test]
+    fn test_add_import_in_sorted_position() {
+        let file = java_file(
+            "package com.example;\n\nimport java.util.List;\nimport java.util.Set;\nimport static org.junit.Assert.assertTrue;\n\nclass A {}\n",
+        );
+        let analyzer = JavaImportAnalyzer::new();
+        let import = |source: &str, symbol: &str, kind| ImportStatement {
+            source: source.to_string(),
+            symbols: vec![symbol.to_string()],
+            location: ImportLocation { line: 0, column: 0, end_line: 0, end_column: 0 },
+            kind,
+            alias: None,
+        };
+
+        let content = analyzer.add_import(file.path(), &import("java.util", "Map", ImportKind::TypeImport)).unwrap();
+        assert_eq!(
+            content,
+            "package com.example;\n\nimport java.util.List;\nimport java.util.Map;\nimport java.util.Set;\nimport static org.junit.Assert.assertTrue;\n\nclass A {}\n"
+        );
+
+        let content = analyzer.add_import(file.path(), &import("org.junit.Assert", "*", ImportKind::StaticImport)).unwrap();
+        assert!(content.contains("import static org.junit.Assert.*;\nimport static org.junit.Assert.assertTrue;\n"), "{}", content);
+
+        // Already imported: unchanged
+        let content = analyzer.add_import(file.path(), &import("java.util", "List", ImportKind::TypeImport)).unwrap();
+        assert_eq!(content.matches("import java.util.List;").count(), 1);
+
+        // No imports yet: after the package declaration
+        let bare = java_file("package com.example;\n\nclass A {}\n");
+        let content = analyzer.add_import(bare.path(), &import("java.util", "List", ImportKind::TypeImport)).unwrap();
+        assert_eq!(content, "package com.example;\n\nimport java.util.List;\n\nclass A {}\n");
+    }
+
+    #[test]
+    fn test_remove_import_by_simple_or_qualified_name() {
+        let file = java_file(
+            "import java.util.List;\nimport java.util.*;\n\nimport static java.lang.Math.max;\n\nclass A {}\n",
+        );
+        let analyzer = JavaImportAnalyzer::new();
+
+        let content = analyzer.remove_import(file.path(), "List").unwrap();
+        assert_eq!(content, "import java.util.*;\n\nimport static java.lang.Math.max;\n\nclass A {}\n");
+
+        let content = analyzer.remove_import(file.path(), "java.lang.Math.max").unwrap();
+        assert_eq!(content, "import java.util.List;\nimport java.util.*;\n\n\nclass A {}\n");
+
+        let content = analyzer.remove_import(file.path(), "java.util.*").unwrap();
+        assert_eq!(content, "import java.util.List;\n\nimport static java.lang.Math.max;\n\nclass A {}\n");
+    }
+
+    #[test]
+    fn test_update_import_path() {
+        let file = java_file("import com.old.Foo;\nimport com.old.sub.*;\nimport com.oldish.Bar;\n");
+        let analyzer = JavaImportAnalyzer::new();
+
+        let content = analyzer.update_import_path(file.path(), "com.old", "com.new").unwrap();
+        assert_eq!(content, "import com.new.Foo;\nimport com.new.sub.*;\nimport com.oldish.Bar;\n");
+    }
+}
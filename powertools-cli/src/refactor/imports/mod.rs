@@ -2,11 +2,13 @@ mod typescript;
 mod python;
 mod rust_lang;
 mod cpp;
+mod java;
 
-pub use typescript::TypeScriptImportAnalyzer;
+pub use typescript::{ImportSpecifier, RequireBinding, ScriptImport, ScriptRequire, TypeScriptImportAnalyzer};
 pub use python::PythonImportAnalyzer;
 pub use rust_lang::RustImportAnalyzer;
 pub use cpp::CppImportAnalyzer;
+pub use java::JavaImportAnalyzer;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -70,6 +72,12 @@ pub enum ImportKind {
 
     /// C++ include (e.g., `#include <vector>` or `#include "header.h"`)
     Include,
+
+    /// Java type import (e.g., `import java.util.List;` or `import java.util.*;`)
+    TypeImport,
+
+    /// Java static import (e.g., `import static org.junit.Assert.assertEquals;`)
+    StaticImport,
 }
 
 /// Change to an import statement
@@ -94,6 +102,9 @@ pub trait ImportAnalyzer {
     /// Find all imports in a file
     fn find_imports(&self, file: &Path) -> Result<Vec<ImportStatement>>;
 
+    /// Source text of a single import statement, ending in a newline
+    fn render_import(&self, import: &ImportStatement) -> Result<String>;
+
     /// Add an import to a file, returning the new file content
     #[allow(dead_code)] // Used in future refactoring implementations
     fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String>;
@@ -124,6 +135,9 @@ pub fn get_analyzer_for_file(file: &Path) -> Option<Box<dyn ImportAnalyzer>> {
         "cpp" | "cc" | "cxx" | "c" | "h" | "hpp" | "hxx" => {
             Some(Box::new(CppImportAnalyzer::new()))
         }
+        "java" => {
+            Some(Box::new(JavaImportAnalyzer::new()))
+        }
         _ => None,
     }
 }
@@ -157,6 +171,12 @@ mod tests {
         assert!(get_analyzer_for_file(&file).is_some());
     }
 
+    #[test]
+    fn test_get_analyzer_for_java() {
+        let file = PathBuf::from("Test.java");
+        assert!(get_analyzer_for_file(&file).is_some());
+    }
+
     #[test]
     fn test_get_analyzer_for_unknown() {
         let file = PathBuf::from("test.xyz");
@@ -1,5 +1,6 @@
 use super::{ImportAnalyzer, ImportKind, ImportLocation, ImportStatement};
 use anyhow::{Context, Result};
+use rustpython_parser::text_size::TextRange;
 use rustpython_parser::{ast, Parse};
 use std::fs;
 use std::path::Path;
@@ -11,7 +12,8 @@ impl PythonImportAnalyzer {
         Self
     }
 
-    fn extract_import(&self, stmt: &ast::StmtImport) -> Vec<ImportStatement> {
+    fn extract_import(&self, stmt: &ast::StmtImport, content: &str) -> Vec<ImportStatement> {
+        let location = self.location_of(stmt.range, content);
         stmt.names
             .iter()
             .map(|alias| {
@@ -21,12 +23,7 @@ impl PythonImportAnalyzer {
                 ImportStatement {
                     source: source.clone(),
                     symbols: vec![source],
-                    location: ImportLocation {
-                        line: 0, // TODO: Get proper location from rustpython
-                        column: 0,
-                        end_line: 0,
-                        end_column: 0,
-                    },
+                    location: location.clone(),
                     kind: ImportKind::SimpleImport,
                     alias: alias_name,
                 }
@@ -34,12 +31,15 @@ impl PythonImportAnalyzer {
             .collect()
     }
 
-    fn extract_import_from(&self, stmt: &ast::StmtImportFrom) -> ImportStatement {
-        let source = stmt
-            .module
-            .as_ref()
-            .map(|m| m.to_string())
-            .unwrap_or_else(|| ".".to_string());
+    fn extract_import_from(&self, stmt: &ast::StmtImportFrom, content: &str) -> ImportStatement {
+        // Relative imports keep their leading dots (e.g. `from ..utils import x` -> "..utils")
+        let level = stmt.level.as_ref().map_or(0, |level| level.to_u32() as usize);
+        let module = stmt.module.as_ref().map(|m| m.to_string()).unwrap_or_default();
+        let source = if level == 0 && module.is_empty() {
+            ".".to_string()
+        } else {
+            format!("{}{}", ".".repeat(level), module)
+        };
 
         let symbols: Vec<String> = stmt
             .names
@@ -53,12 +53,7 @@ impl PythonImportAnalyzer {
         ImportStatement {
             source,
             symbols,
-            location: ImportLocation {
-                line: 0, // TODO: Get proper location from rustpython
-                column: 0,
-                end_line: 0,
-                end_column: 0,
-            },
+            location: self.location_of(stmt.range, content),
             kind: if is_wildcard {
                 ImportKind::Namespace
             } else {
@@ -67,6 +62,25 @@ impl PythonImportAnalyzer {
             alias: None,
         }
     }
+
+    /// Convert a statement's byte range to 1-indexed line/column positions
+    fn location_of(&self, range: TextRange, content: &str) -> ImportLocation {
+        let position = |offset: usize| {
+            let before = &content[..offset.min(content.len())];
+            let line = before.matches('\n').count() + 1;
+            let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+            (line, column)
+        };
+        let (line, column) = position(usize::from(range.start()));
+        let (end_line, end_column) = position(usize::from(range.end()));
+
+        ImportLocation {
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
 }
 
 impl ImportAnalyzer for PythonImportAnalyzer {
@@ -82,10 +96,10 @@ impl ImportAnalyzer for PythonImportAnalyzer {
         for stmt in &module {
             match stmt {
                 ast::Stmt::Import(import_stmt) => {
-                    imports.extend(self.extract_import(import_stmt));
+                    imports.extend(self.extract_import(import_stmt, &content));
                 }
                 ast::Stmt::ImportFrom(from_stmt) => {
-                    imports.push(self.extract_import_from(from_stmt));
+                    imports.push(self.extract_import_from(from_stmt, &content));
                 }
                 _ => {}
             }
@@ -94,11 +108,7 @@ impl ImportAnalyzer for PythonImportAnalyzer {
         Ok(imports)
     }
 
-    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
-        let content = fs::read_to_string(file)
-            .with_context(|| format!("Failed to read file: {}", file.display()))?;
-
-        // Generate the import statement
+    fn render_import(&self, import: &ImportStatement) -> Result<String> {
         let import_line = match import.kind {
             ImportKind::SimpleImport => {
                 if let Some(alias) = &import.alias {
@@ -119,6 +129,15 @@ impl ImportAnalyzer for PythonImportAnalyzer {
             }
         };
 
+        Ok(import_line)
+    }
+
+    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let import_line = self.render_import(import)?;
+
         // Find the position to insert (after the last import, or at the start)
         let existing_imports = self.find_imports(file)?;
         let insert_pos = if let Some(last_import) = existing_imports.last() {
@@ -268,4 +287,23 @@ from os import *
         assert_eq!(imports[0].source, "os");
         assert_eq!(imports[0].kind, ImportKind::Namespace);
     }
+
+    #[test]
+    fn test_relative_import_source_and_location() {
+        let code = "import os\n\nfrom ..shared.utils import helper\nfrom . import sibling\n";
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+
+        let analyzer = PythonImportAnalyzer::new();
+        let imports = analyzer.find_imports(file.path()).unwrap();
+
+        assert_eq!(imports.len(), 3);
+        assert_eq!(imports[0].location.line, 1);
+        assert_eq!(imports[1].source, "..shared.utils");
+        assert_eq!(imports[1].location.line, 3);
+        assert_eq!(imports[1].location.column, 1);
+        assert_eq!(imports[2].source, ".");
+        assert_eq!(imports[2].location.line, 4);
+    }
 }
@@ -78,6 +78,21 @@ impl RustImportAnalyzer {
     }
 }
 
+/// Whether a source line starts a top-level `use` item (`use`, `pub use`, `pub(crate) use`, ...)
+fn is_top_level_use(line: &str) -> bool {
+    let rest = match line.strip_prefix("pub") {
+        Some(after_pub) => {
+            let after_vis = match after_pub.strip_prefix('(') {
+                Some(restricted) => restricted.split_once(')').map_or("", |(_, rest)| rest),
+                None => after_pub,
+            };
+            after_vis.trim_start()
+        }
+        None => line,
+    };
+    rest.starts_with("use ")
+}
+
 impl ImportAnalyzer for RustImportAnalyzer {
     fn find_imports(&self, file: &Path) -> Result<Vec<ImportStatement>> {
         let content = fs::read_to_string(file)
@@ -87,24 +102,35 @@ impl ImportAnalyzer for RustImportAnalyzer {
             .with_context(|| format!("Failed to parse Rust file: {}", file.display()))?;
 
         let mut imports = Vec::new();
-        let mut line = 1;
 
-        for item in ast.items {
-            if let Item::Use(use_item) = item {
-                self.extract_use_tree(&use_item.tree, String::new(), &mut imports, line);
-            }
-            // Approximate line counting (not precise, but good enough for now)
-            line += 1;
+        // syn doesn't track line numbers here, so pair each top-level `use` item with the
+        // unindented `use` lines in the source, in order
+        let use_lines: Vec<usize> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, text)| is_top_level_use(text))
+            .map(|(index, _)| index + 1)
+            .collect();
+        let use_items: Vec<_> = ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Use(use_item) => Some(use_item),
+                _ => None,
+            })
+            .collect();
+        let lines_match = use_lines.len() == use_items.len();
+
+        for (index, use_item) in use_items.into_iter().enumerate() {
+            // Fall back to the item index if the source layout is unusual
+            let line = if lines_match { use_lines[index] } else { index + 1 };
+            self.extract_use_tree(&use_item.tree, String::new(), &mut imports, line);
         }
 
         Ok(imports)
     }
 
-    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
-        let content = fs::read_to_string(file)
-            .with_context(|| format!("Failed to read file: {}", file.display()))?;
-
-        // Generate the use statement
+    fn render_import(&self, import: &ImportStatement) -> Result<String> {
         let use_line = if !import.source.is_empty() && !import.symbols.is_empty() {
             if import.symbols[0] == "*" {
                 format!("use {}::*;\n", import.source)
@@ -120,6 +146,15 @@ impl ImportAnalyzer for RustImportAnalyzer {
             format!("use {};\n", import.source)
         };
 
+        Ok(use_line)
+    }
+
+    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let use_line = self.render_import(import)?;
+
         // Find the position to insert (after the last use statement, or at the start)
         let existing_imports = self.find_imports(file)?;
         let insert_pos = if let Some(last_import) = existing_imports.last() {
@@ -176,7 +211,15 @@ impl ImportAnalyzer for RustImportAnalyzer {
         let mut new_content = content.clone();
 
         for import in imports {
-            if import.source == old_path || import.source.starts_with(&format!("{}::", old_path)) {
+            // `use crate::a::b;` has source `crate::a` and imports module `b` itself
+            let imports_module = import
+                .symbols
+                .iter()
+                .any(|symbol| format!("{}::{}", import.source, symbol) == old_path);
+            if import.source == old_path
+                || import.source.starts_with(&format!("{}::", old_path))
+                || imports_module
+            {
                 // Replace the source path
                 let lines: Vec<&str> = content.lines().collect();
                 if import.location.line > 0 && import.location.line <= lines.len() {
@@ -268,4 +311,20 @@ use std::collections::{HashMap, HashSet};
         assert_eq!(imports[0].symbols, vec!["HashMap"]);
         assert_eq!(imports[1].symbols, vec!["HashSet"]);
     }
+
+    #[test]
+    fn test_use_line_numbers() {
+        let code = "//! Crate docs\n\nuse std::fs;\n\nfn helper() {}\n\npub(crate) use crate::core::Location;\n";
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+
+        let analyzer = RustImportAnalyzer::new();
+        let imports = analyzer.find_imports(file.path()).unwrap();
+
+        assert_eq!(imports.len(), 2);
+        assert_eq!(imports[0].location.line, 3);
+        assert_eq!(imports[1].source, "crate::core");
+        assert_eq!(imports[1].location.line, 7);
+    }
 }
@@ -6,6 +6,168 @@ use tree_sitter::{Node, Parser};
 
 pub struct TypeScriptImportAnalyzer;
 
+/// One specifier of a named import: `name`, `name as alias`, or with an inline `type`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportSpecifier {
+    pub name: String,
+    pub alias: Option<String>,
+    pub type_only: bool,
+}
+
+impl ImportSpecifier {
+    fn render(&self) -> String {
+        let mut text = if self.type_only {
+            format!("type {}", self.name)
+        } else {
+            self.name.clone()
+        };
+        if let Some(alias) = &self.alias {
+            text.push_str(" as ");
+            text.push_str(alias);
+        }
+        text
+    }
+}
+
+/// An `import` statement with everything its clause binds
+///
+/// Unlike [`ImportStatement`], this keeps a default import next to named or namespace
+/// ones (`import React, { useState } from 'react'`), specifier aliases, `type`
+/// modifiers and the statement's quoting, so it can be rendered back faithfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptImport {
+    pub source: String,
+    pub default: Option<String>,
+    pub namespace: Option<String>,
+    pub named: Vec<ImportSpecifier>,
+
+    /// `import type ...`
+    pub type_only: bool,
+
+    pub location: ImportLocation,
+
+    /// Quote around the source, `'` or `"`
+    pub quote: char,
+
+    /// Whether the statement ends with `;`
+    pub semicolon: bool,
+}
+
+/// What a top-level CommonJS `require` binds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequireBinding {
+    /// `const x = require('m')`
+    Whole(String),
+
+    /// `const x = require('m').default`
+    Default(String),
+
+    /// `const x = require('m').y`: the module's `property`, bound to `name`
+    Member { property: String, name: String },
+
+    /// `const { a, b: c } = require('m')`
+    Destructured(Vec<ImportSpecifier>),
+
+    /// `require('m');`
+    SideEffect,
+
+    /// A shape with no ES import equivalent, and why
+    Unsupported(String),
+}
+
+/// A top-level `require` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptRequire {
+    pub source: String,
+    pub binding: RequireBinding,
+    pub location: ImportLocation,
+
+    /// Quote around the source, `'` or `"`
+    pub quote: char,
+
+    /// Whether the statement ends with `;`
+    pub semicolon: bool,
+
+    /// Whether a name it binds is called or constructed (`x()`, `new x()`)
+    pub called: bool,
+
+    /// Whether a name it binds is assigned later, which an import binding can't be
+    pub reassigned: bool,
+}
+
+impl ScriptRequire {
+    /// Names the statement binds
+    pub fn bound_names(&self) -> Vec<String> {
+        match &self.binding {
+            RequireBinding::Whole(name) | RequireBinding::Default(name) | RequireBinding::Member { name, .. } => {
+                vec![name.clone()]
+            }
+            RequireBinding::Destructured(specifiers) => specifiers
+                .iter()
+                .map(|specifier| specifier.alias.as_ref().unwrap_or(&specifier.name).clone())
+                .collect(),
+            RequireBinding::SideEffect | RequireBinding::Unsupported(_) => Vec::new(),
+        }
+    }
+
+    fn to_import_statement(&self) -> ImportStatement {
+        let (symbols, alias) = match &self.binding {
+            RequireBinding::Whole(name) | RequireBinding::Default(name) => (vec![name.clone()], Some(name.clone())),
+            RequireBinding::Member { property, name } => (vec![property.clone()], Some(name.clone())),
+            RequireBinding::Destructured(specifiers) => {
+                (specifiers.iter().map(|specifier| specifier.name.clone()).collect(), None)
+            }
+            RequireBinding::SideEffect | RequireBinding::Unsupported(_) => (Vec::new(), None),
+        };
+        ImportStatement {
+            source: self.source.clone(),
+            symbols,
+            location: self.location.clone(),
+            kind: ImportKind::Require,
+            alias,
+        }
+    }
+}
+
+impl ScriptImport {
+    /// Source text of the statement, with named specifiers one per line when `multiline`
+    pub fn render(&self, multiline: bool) -> String {
+        let mut clause = Vec::new();
+        if let Some(default) = &self.default {
+            clause.push(default.clone());
+        }
+        if let Some(namespace) = &self.namespace {
+            clause.push(format!("* as {}", namespace));
+        }
+        if !self.named.is_empty() {
+            let specifiers: Vec<String> = self.named.iter().map(ImportSpecifier::render).collect();
+            clause.push(if multiline {
+                format!("{{\n  {},\n}}", specifiers.join(",\n  "))
+            } else {
+                format!("{{ {} }}", specifiers.join(", "))
+            });
+        }
+
+        if clause.is_empty() {
+            return format!(
+                "import {quote}{}{quote}{}",
+                self.source,
+                if self.semicolon { ";" } else { "" },
+                quote = self.quote
+            );
+        }
+
+        format!(
+            "import {}{} from {quote}{}{quote}{}",
+            if self.type_only { "type " } else { "" },
+            clause.join(", "),
+            self.source,
+            if self.semicolon { ";" } else { "" },
+            quote = self.quote
+        )
+    }
+}
+
 impl TypeScriptImportAnalyzer {
     pub fn new() -> Self {
         Self
@@ -142,6 +304,262 @@ impl TypeScriptImportAnalyzer {
 
         ("*".to_string(), None)
     }
+
+    /// Every top-level `import` statement of a TypeScript/JavaScript source, in file order
+    pub fn find_script_imports(&self, content: &str) -> Result<Vec<ScriptImport>> {
+        let mut parser = Self::create_parser();
+        let tree = parser
+            .parse(content, None)
+            .context("Failed to parse TypeScript file")?;
+
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let imports = root
+            .children(&mut cursor)
+            .filter(|node| node.kind() == "import_statement")
+            .filter_map(|node| self.extract_script_import(node, content))
+            .collect();
+
+        Ok(imports)
+    }
+
+    fn extract_script_import(&self, node: Node, source: &str) -> Option<ScriptImport> {
+        let text = |node: Node| source[node.byte_range()].to_string();
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+
+        let quoted = &source[children.iter().find(|n| n.kind() == "string")?.byte_range()];
+        let mut import = ScriptImport {
+            source: quoted.trim_matches(|c| c == '"' || c == '\'').to_string(),
+            default: None,
+            namespace: None,
+            named: Vec::new(),
+            type_only: children.iter().any(|n| n.kind() == "type"),
+            location: ImportLocation {
+                line: node.start_position().row + 1,
+                column: node.start_position().column,
+                end_line: node.end_position().row + 1,
+                end_column: node.end_position().column,
+            },
+            quote: if quoted.starts_with('"') { '"' } else { '\'' },
+            semicolon: children.iter().any(|n| n.kind() == ";"),
+        };
+
+        let Some(clause) = children.iter().find(|n| n.kind() == "import_clause") else {
+            return Some(import);
+        };
+        let mut clause_cursor = clause.walk();
+        for child in clause.children(&mut clause_cursor) {
+            match child.kind() {
+                "identifier" => import.default = Some(text(child)),
+                "namespace_import" => {
+                    let mut namespace_cursor = child.walk();
+                    import.namespace = child
+                        .children(&mut namespace_cursor)
+                        .find(|n| n.kind() == "identifier")
+                        .map(text);
+                }
+                "named_imports" => {
+                    let mut named_cursor = child.walk();
+                    for specifier in child.children(&mut named_cursor).filter(|n| n.kind() == "import_specifier") {
+                        let Some(name) = specifier.child_by_field_name("name") else {
+                            continue;
+                        };
+                        // `{ type Foo }`, but not a specifier importing something named `type`
+                        let type_only = specifier
+                            .child(0)
+                            .is_some_and(|first| first.kind() == "type" && first.id() != name.id());
+                        import.named.push(ImportSpecifier {
+                            name: text(name),
+                            alias: specifier.child_by_field_name("alias").map(text),
+                            type_only,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(import)
+    }
+
+    /// Every top-level `require` statement of a TypeScript/JavaScript source, in file order
+    ///
+    /// Requires nested in functions or conditions are left out: they load lazily, which
+    /// an import can't.
+    pub fn find_script_requires(&self, content: &str) -> Result<Vec<ScriptRequire>> {
+        let mut parser = Self::create_parser();
+        let tree = parser
+            .parse(content, None)
+            .context("Failed to parse TypeScript file")?;
+
+        Ok(self.requires_in(tree.root_node(), content))
+    }
+
+    fn requires_in(&self, root: Node, source: &str) -> Vec<ScriptRequire> {
+        let mut cursor = root.walk();
+        let statements: Vec<Node> = root.children(&mut cursor).collect();
+        statements
+            .into_iter()
+            .filter_map(|node| {
+                let (source_node, binding) = self.extract_require(node, source)?;
+                let quoted = &source[source_node.byte_range()];
+                let mut require = ScriptRequire {
+                    source: quoted.trim_matches(|c| c == '"' || c == '\'').to_string(),
+                    binding,
+                    location: ImportLocation {
+                        line: node.start_position().row + 1,
+                        column: node.start_position().column,
+                        end_line: node.end_position().row + 1,
+                        end_column: node.end_position().column,
+                    },
+                    quote: if quoted.starts_with('"') { '"' } else { '\'' },
+                    semicolon: source[node.byte_range()].trim_end().ends_with(';'),
+                    called: false,
+                    reassigned: false,
+                };
+                for name in require.bound_names() {
+                    let (called, reassigned) = usage_of(root, source, &name, node);
+                    require.called |= called;
+                    require.reassigned |= reassigned;
+                }
+                Some(require)
+            })
+            .collect()
+    }
+
+    /// The source string and binding of a `require` statement, or `None` for any other statement
+    fn extract_require<'a>(&self, node: Node<'a>, source: &str) -> Option<(Node<'a>, RequireBinding)> {
+        let text = |node: Node| source[node.byte_range()].to_string();
+
+        match node.kind() {
+            "expression_statement" => {
+                let source_node = require_call(node.named_child(0)?, source)?;
+                Some((source_node, RequireBinding::SideEffect))
+            }
+            "lexical_declaration" | "variable_declaration" => {
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node
+                    .named_children(&mut cursor)
+                    .filter(|child| child.kind() == "variable_declarator")
+                    .collect();
+                let (source_node, property) = declarators
+                    .iter()
+                    .find_map(|declarator| require_value(declarator.child_by_field_name("value")?, source))?;
+                if declarators.len() > 1 {
+                    return Some((source_node, RequireBinding::Unsupported("declares several variables".to_string())));
+                }
+
+                let name = declarators[0].child_by_field_name("name")?;
+                let binding = match (name.kind(), property) {
+                    ("identifier", None) => RequireBinding::Whole(text(name)),
+                    ("identifier", Some(property)) if property == "default" => RequireBinding::Default(text(name)),
+                    ("identifier", Some(property)) => RequireBinding::Member { property, name: text(name) },
+                    ("object_pattern", None) => destructured(name, source),
+                    _ => RequireBinding::Unsupported("destructures a property of the module".to_string()),
+                };
+                Some((source_node, binding))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The source string of `require('m')`
+fn require_call<'a>(node: Node<'a>, source: &str) -> Option<Node<'a>> {
+    if node.kind() != "call_expression" {
+        return None;
+    }
+    let function = node.child_by_field_name("function")?;
+    if function.kind() != "identifier" || &source[function.byte_range()] != "require" {
+        return None;
+    }
+
+    let arguments = node.child_by_field_name("arguments")?;
+    let mut cursor = arguments.walk();
+    let args: Vec<Node> = arguments.named_children(&mut cursor).collect();
+    match args.as_slice() {
+        [arg] if arg.kind() == "string" => Some(*arg),
+        _ => None,
+    }
+}
+
+/// The source string of `require('m')` or `require('m').property`, with the property
+fn require_value<'a>(node: Node<'a>, source: &str) -> Option<(Node<'a>, Option<String>)> {
+    if node.kind() == "member_expression" {
+        let source_node = require_call(node.child_by_field_name("object")?, source)?;
+        let property = node.child_by_field_name("property")?;
+        return Some((source_node, Some(source[property.byte_range()].to_string())));
+    }
+    Some((require_call(node, source)?, None))
+}
+
+/// Specifiers of `{ a, b: c }`, or why the pattern can't be an import
+fn destructured(pattern: Node, source: &str) -> RequireBinding {
+    let text = |node: Node| source[node.byte_range()].to_string();
+    let mut specifiers = Vec::new();
+
+    let mut cursor = pattern.walk();
+    for child in pattern.named_children(&mut cursor) {
+        match child.kind() {
+            "shorthand_property_identifier_pattern" => specifiers.push(ImportSpecifier {
+                name: text(child),
+                alias: None,
+                type_only: false,
+            }),
+            "pair_pattern" => {
+                let key = child.child_by_field_name("key");
+                let value = child.child_by_field_name("value");
+                match (key, value) {
+                    (Some(key), Some(value)) if key.kind() == "property_identifier" && value.kind() == "identifier" => {
+                        let (name, alias) = (text(key), text(value));
+                        specifiers.push(ImportSpecifier {
+                            alias: (alias != name).then_some(alias),
+                            name,
+                            type_only: false,
+                        });
+                    }
+                    _ => return RequireBinding::Unsupported("uses nested or computed destructuring".to_string()),
+                }
+            }
+            "object_assignment_pattern" => {
+                return RequireBinding::Unsupported("destructures with default values".to_string())
+            }
+            "rest_pattern" => return RequireBinding::Unsupported("collects the rest of the module".to_string()),
+            "comment" => {}
+            _ => return RequireBinding::Unsupported(format!("destructures a {}", child.kind())),
+        }
+    }
+    RequireBinding::Destructured(specifiers)
+}
+
+/// Whether `name` is called or constructed, and whether it's assigned, anywhere outside `declaration`
+///
+/// Matches by name, so a shadowing local of the same name counts too.
+fn usage_of(root: Node, source: &str, name: &str, declaration: Node) -> (bool, bool) {
+    let (mut called, mut reassigned) = (false, false);
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "identifier"
+            && &source[node.byte_range()] == name
+            && !declaration.byte_range().contains(&node.start_byte())
+        {
+            if let Some(parent) = node.parent() {
+                let is_field = |field: &str| parent.child_by_field_name(field).is_some_and(|child| child.id() == node.id());
+                match parent.kind() {
+                    "call_expression" => called |= is_field("function"),
+                    "new_expression" => called |= is_field("constructor"),
+                    "assignment_expression" | "augmented_assignment_expression" => reassigned |= is_field("left"),
+                    "update_expression" => reassigned = true,
+                    _ => {}
+                }
+            }
+        }
+
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    (called, reassigned)
 }
 
 impl ImportAnalyzer for TypeScriptImportAnalyzer {
@@ -174,14 +592,13 @@ impl ImportAnalyzer for TypeScriptImportAnalyzer {
             }
         }
 
+        imports.extend(self.requires_in(root, &content).iter().map(ScriptRequire::to_import_statement));
+        imports.sort_by_key(|import| (import.location.line, import.location.column));
+
         Ok(imports)
     }
 
-    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
-        let content = fs::read_to_string(file)
-            .with_context(|| format!("Failed to read file: {}", file.display()))?;
-
-        // Generate the import statement
+    fn render_import(&self, import: &ImportStatement) -> Result<String> {
         let import_line = match import.kind {
             ImportKind::Named => {
                 let symbols = import.symbols.join(", ");
@@ -205,6 +622,15 @@ impl ImportAnalyzer for TypeScriptImportAnalyzer {
             }
         };
 
+        Ok(import_line)
+    }
+
+    fn add_import(&self, file: &Path, import: &ImportStatement) -> Result<String> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+        let import_line = self.render_import(import)?;
+
         // Find the position to insert (after the last import, or at the start)
         let existing_imports = self.find_imports(file)?;
         let insert_pos = if let Some(last_import) = existing_imports.last() {
@@ -331,4 +757,40 @@ import * as React from 'react';
         assert_eq!(imports[0].kind, ImportKind::Namespace);
         assert_eq!(imports[0].alias, Some("React".to_string()));
     }
+
+    #[test]
+    fn test_find_require_imports() {
+        let code = r#"
+import React from 'react';
+const { a, b: c } = require('./m');
+const fs = require('fs');
+require('./polyfill');
+
+function lazy() {
+    return require('./lazy');
+}
+"#;
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+
+        let analyzer = TypeScriptImportAnalyzer::new();
+        let imports = analyzer.find_imports(file.path()).unwrap();
+
+        let sources: Vec<&str> = imports.iter().map(|import| import.source.as_str()).collect();
+        assert_eq!(sources, vec!["react", "./m", "fs", "./polyfill"]);
+        assert_eq!(imports[1].kind, ImportKind::Require);
+        assert_eq!(imports[1].symbols, vec!["a", "b"]);
+        assert_eq!(imports[2].alias, Some("fs".to_string()));
+        assert!(imports[3].symbols.is_empty());
+
+        let requires = analyzer.find_script_requires(code).unwrap();
+        assert_eq!(
+            requires[0].binding,
+            RequireBinding::Destructured(vec![
+                ImportSpecifier { name: "a".to_string(), alias: None, type_only: false },
+                ImportSpecifier { name: "b".to_string(), alias: Some("c".to_string()), type_only: false },
+            ])
+        );
+    }
 }
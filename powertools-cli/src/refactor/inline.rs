@@ -4,11 +4,13 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use tree_sitter::{Node, Parser};
+use unicode_width::UnicodeWidthChar;
 
 use crate::core::{Location, Reference};
 use crate::indexers::ScipQuery;
 
 use super::preview::{PreviewChange, PreviewDiff, RefactoringSummary};
+use super::syntax::is_in_comment_or_string;
 use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
 
 /// Options for inline variable operation
@@ -20,11 +22,15 @@ pub struct InlineOptions {
     /// Line number (1-indexed)
     pub line: usize,
 
-    /// Column number (1-indexed)
+    /// Column number (1-indexed, in characters; a tab or a wide character is one column)
     pub column: usize,
 
     /// Transaction mode (Execute or DryRun)
     pub mode: TransactionMode,
+
+    /// Inline only the usage at this line and column (1-indexed, in characters),
+    /// keeping the declaration and every other usage
+    pub only_at: Option<(usize, usize)>,
 }
 
 /// Result of an inline operation
@@ -59,9 +65,7 @@ struct VariableDeclaration {
     location: Location,
 
     /// Start and end byte positions of the entire declaration statement
-    #[allow(dead_code)] // Reserved for potential multi-line declaration removal
     declaration_start_byte: usize,
-    #[allow(dead_code)] // Reserved for potential multi-line declaration removal
     declaration_end_byte: usize,
 
     /// Whether the variable is reassigned (mutable)
@@ -72,7 +76,6 @@ struct VariableDeclaration {
 pub struct VariableInliner<'a> {
     #[allow(dead_code)] // Reserved for future SCIP-based reference finding
     scip_query: &'a ScipQuery,
-    #[allow(dead_code)] // Reserved for future use in path resolution
     project_root: PathBuf,
 }
 
@@ -117,6 +120,7 @@ impl<'a> VariableInliner<'a> {
                 var_decl.name
             );
         }
+        let usages = only_usage_at(usages, &var_decl.name, options.only_at)?;
 
         // Step 5: Group references by file (should all be in same file for now)
         let mut references_by_file: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
@@ -128,7 +132,8 @@ impl<'a> VariableInliner<'a> {
         }
 
         // Step 6: Build a transaction with all file changes
-        let mut transaction = RefactoringTransaction::new(options.mode);
+        let mut transaction = RefactoringTransaction::new(options.mode)
+            .with_journal(self.project_root.clone(), format!("inline {}", var_decl.name));
 
         for (file_path, file_refs) in &references_by_file {
             let content = fs::read_to_string(file_path)
@@ -140,6 +145,7 @@ impl<'a> VariableInliner<'a> {
                 &var_decl.name,
                 &var_decl.initializer,
                 &var_decl,
+                options.only_at.is_none(),
             )?;
 
             transaction.add_operation(file_path.clone(), content, new_content)?;
@@ -187,6 +193,7 @@ impl<'a> VariableInliner<'a> {
                 var_decl.name
             );
         }
+        let usages = only_usage_at(usages, &var_decl.name, options.only_at)?;
 
         let mut references_by_file: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
         for reference in usages {
@@ -205,20 +212,24 @@ impl<'a> VariableInliner<'a> {
 
             let mut diff = PreviewDiff::new(file_path.clone());
 
-            // Add change for variable declaration removal
-            let decl_line_content = content
-                .lines()
-                .nth(var_decl.location.line - 1)
-                .unwrap_or("")
-                .to_string();
-
-            diff.add_change(PreviewChange {
-                line: var_decl.location.line,
-                column: var_decl.location.column,
-                original: decl_line_content.clone(),
-                replacement: String::new(), // Will be removed
-                line_content: decl_line_content,
-            });
+            // Add change for variable declaration removal, with any comment attached to it
+            if options.only_at.is_none() {
+                let (first, last) = self.declaration_line_span(&content, &var_decl);
+                let decl_line_content = content
+                    .lines()
+                    .skip(first)
+                    .take(last - first + 1)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                diff.add_change(PreviewChange {
+                    line: first + 1,
+                    column: 1,
+                    original: decl_line_content.clone(),
+                    replacement: String::new(), // Will be removed
+                    line_content: decl_line_content,
+                });
+            }
 
             // Add changes for each usage
             for reference in file_refs {
@@ -287,7 +298,7 @@ impl<'a> VariableInliner<'a> {
             .ok_or_else(|| anyhow::anyhow!("Failed to parse TypeScript code"))?;
 
         let root_node = tree.root_node();
-        let target_byte = self.position_to_byte(content, line, column)?;
+        let target_byte = position_to_byte(content, line, column)?;
 
         // Find the variable declaration node containing this position
         let var_node = self
@@ -391,7 +402,7 @@ impl<'a> VariableInliner<'a> {
             .ok_or_else(|| anyhow::anyhow!("Failed to parse Rust code"))?;
 
         let root_node = tree.root_node();
-        let target_byte = self.position_to_byte(content, line, column)?;
+        let target_byte = position_to_byte(content, line, column)?;
 
         let var_node = self
             .find_node_at_position(root_node, target_byte, "let_declaration")
@@ -469,7 +480,7 @@ impl<'a> VariableInliner<'a> {
             .ok_or_else(|| anyhow::anyhow!("Failed to parse Python code"))?;
 
         let root_node = tree.root_node();
-        let target_byte = self.position_to_byte(content, line, column)?;
+        let target_byte = position_to_byte(content, line, column)?;
 
         let var_node = self
             .find_node_at_position(root_node, target_byte, "assignment")
@@ -531,7 +542,7 @@ impl<'a> VariableInliner<'a> {
             .ok_or_else(|| anyhow::anyhow!("Failed to parse C++ code"))?;
 
         let root_node = tree.root_node();
-        let target_byte = self.position_to_byte(content, line, column)?;
+        let target_byte = position_to_byte(content, line, column)?;
 
         let var_node = self
             .find_node_at_position(root_node, target_byte, "declaration")
@@ -676,30 +687,6 @@ impl<'a> VariableInliner<'a> {
         Ok(symbol_name)
     }
 
-    /// Convert line/column position to byte offset
-    fn position_to_byte(&self, content: &str, line: usize, column: usize) -> Result<usize> {
-        let mut byte_offset = 0;
-        let mut current_line = 1;
-        let mut current_column = 0;
-
-        for ch in content.chars() {
-            if current_line == line && current_column == column - 1 {
-                return Ok(byte_offset);
-            }
-
-            if ch == '\n' {
-                current_line += 1;
-                current_column = 0;
-            } else {
-                current_column += 1;
-            }
-
-            byte_offset += ch.len_utf8();
-        }
-
-        Ok(byte_offset)
-    }
-
     /// Validate that the variable can be safely inlined
     fn validate_can_inline(&self, var_decl: &VariableDeclaration) -> Result<()> {
         // Check 1: Variable should not be mutable
@@ -729,7 +716,8 @@ impl<'a> VariableInliner<'a> {
         expr.contains('(') && expr.contains(')')
     }
 
-    /// Replace all occurrences of the variable with its initializer
+    /// Replace the given occurrences of the variable with its initializer, then remove
+    /// the declaration if `remove_declaration` is set
     fn inline_variable_in_file(
         &self,
         content: &str,
@@ -737,6 +725,7 @@ impl<'a> VariableInliner<'a> {
         var_name: &str,
         initializer: &str,
         var_decl: &VariableDeclaration,
+        remove_declaration: bool,
     ) -> Result<String> {
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
 
@@ -794,15 +783,62 @@ impl<'a> VariableInliner<'a> {
             lines[line_idx] = new_line;
         }
 
-        // Remove the variable declaration line
-        let decl_line_idx = var_decl.location.line - 1;
-        if decl_line_idx < lines.len() {
-            lines.remove(decl_line_idx);
+        if !remove_declaration {
+            return Ok(lines.join("\n"));
+        }
+
+        // Remove the whole declaration statement, along with a comment attached to it.
+        // Usages are all below the declaration, so its lines haven't moved.
+        let (first, last) = self.declaration_line_span(content, var_decl);
+        if first < lines.len() {
+            let last = last.min(lines.len() - 1);
+            lines.drain(first..=last);
+
+            // Where the declaration sat between two blank lines, keep only one of them
+            let is_blank = |idx: usize| lines.get(idx).is_some_and(|l| l.trim().is_empty());
+            if first > 0 && is_blank(first - 1) && is_blank(first) {
+                lines.remove(first);
+            }
         }
 
         Ok(lines.join("\n"))
     }
 
+    /// First and last line (0-indexed) to remove for a declaration
+    ///
+    /// Covers every line of the statement, so a trailing comment on its last line goes
+    /// with it, plus the comment lines directly above it. A blank line detaches a comment.
+    fn declaration_line_span(&self, content: &str, var_decl: &VariableDeclaration) -> (usize, usize) {
+        let line_of = |byte: usize| content[..byte.min(content.len())].matches('\n').count();
+        let first = line_of(var_decl.declaration_start_byte);
+        let last = line_of(var_decl.declaration_end_byte).max(first);
+
+        let lines: Vec<&str> = content.lines().collect();
+        let python = var_decl
+            .location
+            .file_path
+            .extension()
+            .is_some_and(|ext| ext == "py");
+
+        let mut start = first.min(lines.len());
+        while start > 0 {
+            let above = lines[start - 1].trim();
+            if is_line_comment(above, python) {
+                start -= 1;
+            } else if !python && above.ends_with("*/") {
+                // Take the whole block comment, up to the line that opens it
+                match (0..start).rev().find(|&i| lines[i].contains("/*")) {
+                    Some(open) if lines[open].trim_start().starts_with("/*") => start = open,
+                    _ => break,
+                }
+            } else {
+                break;
+            }
+        }
+
+        (start, last)
+    }
+
     /// Check if a symbol exists at a specific position in a line
     fn symbol_at_position(&self, line: &str, col_idx: usize, symbol: &str) -> bool {
         let chars: Vec<char> = line.chars().collect();
@@ -986,7 +1022,8 @@ impl<'a> VariableInliner<'a> {
         references: &mut Vec<Reference>,
     ) {
         // Check if this node is an identifier matching our variable name
-        if node.kind() == "identifier" {
+        // (grammars that parse string or comment contents must not contribute matches)
+        if node.kind() == "identifier" && !is_in_comment_or_string(node) {
             let node_text = &content[node.byte_range()];
             let node_line = node.start_position().row + 1; // tree-sitter uses 0-indexed rows
 
@@ -1012,3 +1049,264 @@ impl<'a> VariableInliner<'a> {
         }
     }
 }
+
+/// Keep only the usage covering `only_at` (line, column), if one was asked for
+fn only_usage_at(usages: Vec<Reference>, var_name: &str, only_at: Option<(usize, usize)>) -> Result<Vec<Reference>> {
+    let Some((line, column)) = only_at else {
+        return Ok(usages);
+    };
+
+    let usage = usages.into_iter().find(|usage| {
+        let location = &usage.location;
+        let end_column = location.end_column.unwrap_or(location.column + var_name.chars().count());
+        location.line == line && location.column <= column && column < end_column
+    });
+    match usage {
+        Some(usage) => Ok(vec![usage]),
+        None => anyhow::bail!("No usage of '{}' at line {}, column {}", var_name, line, column),
+    }
+}
+
+/// Whether a trimmed line is a whole-line comment, which documents the code below it.
+/// Shebangs and Rust inner doc comments belong to the file, not the next statement.
+fn is_line_comment(line: &str, python: bool) -> bool {
+    if python {
+        line.starts_with('#') && !line.starts_with("#!")
+    } else {
+        line.starts_with("//") && !line.starts_with("//!")
+    }
+}
+
+/// Byte offset of the start of a 1-indexed `line`
+fn line_start_byte(content: &str, line: usize) -> Result<usize> {
+    if line == 0 {
+        anyhow::bail!("Line numbers are 1-indexed, got 0");
+    }
+
+    let mut start = 0;
+    for _ in 1..line {
+        match content[start..].find('\n') {
+            Some(newline) => start += newline + 1,
+            None => anyhow::bail!(
+                "Line {} is past the end of the file ({} lines)",
+                line,
+                content.lines().count()
+            ),
+        }
+    }
+    Ok(start)
+}
+
+/// Text of the line starting at `start`, without its line ending
+fn line_text(content: &str, start: usize) -> &str {
+    let rest = &content[start..];
+    let line = rest.find('\n').map_or(rest, |end| &rest[..end]);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Convert a 1-indexed line and character column to a byte offset.
+///
+/// Columns count characters (Unicode scalar values), so a tab or a CJK character is
+/// a single column. Column `len + 1` is the end of the line; anything further is an error.
+pub fn position_to_byte(content: &str, line: usize, column: usize) -> Result<usize> {
+    if column == 0 {
+        anyhow::bail!("Column numbers are 1-indexed, got 0");
+    }
+
+    let start = line_start_byte(content, line)?;
+    let text = line_text(content, start);
+    match text.char_indices().nth(column - 1) {
+        Some((offset, _)) => Ok(start + offset),
+        None if column - 1 == text.chars().count() => Ok(start + text.len()),
+        None => anyhow::bail!(
+            "Column {} is past the end of line {} ({} characters)",
+            column,
+            line,
+            text.chars().count()
+        ),
+    }
+}
+
+/// Convert a 1-indexed line and editor (visual) column to a byte offset.
+///
+/// A tab advances to the next multiple of `tab_width` and wide characters take two
+/// columns, the way editors count them. A column inside a tab or wide character
+/// resolves to that character.
+pub fn visual_position_to_byte(content: &str, line: usize, column: usize, tab_width: usize) -> Result<usize> {
+    if column == 0 {
+        anyhow::bail!("Column numbers are 1-indexed, got 0");
+    }
+    if tab_width == 0 {
+        anyhow::bail!("Tab width must be at least 1");
+    }
+
+    let start = line_start_byte(content, line)?;
+    let text = line_text(content, start);
+    let target = column - 1;
+    let mut visual = 0;
+
+    for (offset, ch) in text.char_indices() {
+        let width = if ch == '\t' {
+            tab_width - visual % tab_width
+        } else {
+            UnicodeWidthChar::width(ch).unwrap_or(0)
+        };
+        if target < visual + width {
+            return Ok(start + offset);
+        }
+        visual += width;
+    }
+
+    if target == visual {
+        Ok(start + text.len())
+    } else {
+        anyhow::bail!(
+            "Column {} is past the end of line {} ({} columns)",
+            column,
+            line,
+            visual
+        )
+    }
+}
+
+/// Convert an editor column (see [`visual_position_to_byte`]) to the character column
+/// that [`position_to_byte`] and [`InlineOptions::column`] expect
+pub fn char_column_from_visual(content: &str, line: usize, column: usize, tab_width: usize) -> Result<usize> {
+    let byte = visual_position_to_byte(content, line, column, tab_width)?;
+    let start = line_start_byte(content, line)?;
+    Ok(content[start..byte].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABBED: &str = "fn main() {\n\tlet x = 1;\n\t\tprintln!(\"{}\", x);\n}\n";
+    const WIDE: &str = "let 名前 = \"値\"; let y = 2;\n";
+
+    #[test]
+    fn test_position_to_byte_counts_characters() {
+        // A tab is one character column
+        assert_eq!(position_to_byte(TABBED, 2, 2).unwrap(), TABBED.find("let").unwrap());
+        assert_eq!(position_to_byte(TABBED, 3, 3).unwrap(), TABBED.find("println").unwrap());
+
+        // So is a wide character, even though it is three bytes long
+        assert_eq!(position_to_byte(WIDE, 1, 5).unwrap(), WIDE.find("名前").unwrap());
+        assert_eq!(position_to_byte(WIDE, 1, 8).unwrap(), WIDE.find("= ").unwrap());
+        assert_eq!(position_to_byte(WIDE, 1, 19).unwrap(), WIDE.find("y =").unwrap());
+
+        // One past the last character is the end of the line
+        assert_eq!(position_to_byte(TABBED, 4, 2).unwrap(), TABBED.len() - 1);
+    }
+
+    #[test]
+    fn test_position_to_byte_rejects_out_of_range_positions() {
+        assert!(position_to_byte(TABBED, 2, 0).is_err());
+        assert!(position_to_byte(TABBED, 0, 1).is_err());
+        assert!(position_to_byte(TABBED, 4, 3).is_err());
+        assert!(position_to_byte(TABBED, 9, 1).is_err());
+    }
+
+    #[test]
+    fn test_visual_position_to_byte_expands_tabs_and_wide_chars() {
+        // Tab width 4: `let` starts at editor column 5, `println` at 9
+        assert_eq!(visual_position_to_byte(TABBED, 2, 5, 4).unwrap(), TABBED.find("let").unwrap());
+        assert_eq!(visual_position_to_byte(TABBED, 3, 9, 4).unwrap(), TABBED.find("println").unwrap());
+        assert_eq!(visual_position_to_byte(TABBED, 3, 17, 8).unwrap(), TABBED.find("println").unwrap());
+
+        // A column inside a tab resolves to the tab
+        let first_tab = TABBED.find('\t').unwrap();
+        assert_eq!(visual_position_to_byte(TABBED, 2, 3, 4).unwrap(), first_tab);
+
+        // Each CJK character takes two editor columns
+        assert_eq!(visual_position_to_byte(WIDE, 1, 5, 4).unwrap(), WIDE.find("名前").unwrap());
+        assert_eq!(visual_position_to_byte(WIDE, 1, 7, 4).unwrap(), WIDE.find("前").unwrap());
+        assert_eq!(visual_position_to_byte(WIDE, 1, 10, 4).unwrap(), WIDE.find("= ").unwrap());
+
+        assert!(visual_position_to_byte(TABBED, 2, 5, 0).is_err());
+        assert!(visual_position_to_byte(TABBED, 4, 3, 4).is_err());
+    }
+
+    #[test]
+    fn test_char_column_from_visual() {
+        assert_eq!(char_column_from_visual(TABBED, 3, 9, 4).unwrap(), 3);
+        assert_eq!(char_column_from_visual(WIDE, 1, 10, 4).unwrap(), 8);
+    }
+
+    /// Inline the variable declared at `line`:`column` of a file named `name` and return the result
+    fn inline_in(name: &str, source: &str, line: usize, column: usize) -> String {
+        inline_only_at(name, source, line, column, None)
+    }
+
+    fn inline_only_at(name: &str, source: &str, line: usize, column: usize, only_at: Option<(usize, usize)>) -> String {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file_path = temp.path().join(name);
+        fs::write(&file_path, source).unwrap();
+
+        let scip_query = ScipQuery::new(scip::types::Index::new(), temp.path().to_path_buf());
+        VariableInliner::new(&scip_query, temp.path().to_path_buf())
+            .inline(InlineOptions {
+                file_path: file_path.clone(),
+                line,
+                column,
+                mode: TransactionMode::Execute,
+                only_at,
+            })
+            .unwrap();
+        fs::read_to_string(&file_path).unwrap()
+    }
+
+    #[test]
+    fn test_inline_removes_attached_comments() {
+        let source = "function total() {\n    const a = 1;\n\n    // Rate applied to every order\n    const rate = 2; // percent\n\n    return a * rate;\n}\n";
+
+        assert_eq!(
+            inline_in("total.ts", source, 5, 11),
+            "function total() {\n    const a = 1;\n\n    return a * 2;\n}"
+        );
+
+        // A blank line between them means the comment isn't about the declaration
+        let source = "// Defaults\n\nconst limit = 10;\nconsole.log(limit);\n";
+        assert_eq!(inline_in("config.js", source, 3, 7), "// Defaults\n\nconsole.log(10);");
+    }
+
+    #[test]
+    fn test_inline_removes_multi_line_declaration() {
+        let source = "fn main() {\n    /* Requests per\n       second */\n    let limit =\n        10 + 20; // burst\n    let total = limit * 2;\n}\n";
+
+        assert_eq!(
+            inline_in("main.rs", source, 4, 9),
+            "fn main() {\n    let total = (10 + 20) * 2;\n}"
+        );
+    }
+
+    #[test]
+    fn test_inline_single_usage() {
+        let source = "fn main() {\n    let limit = 10;\n    let low = limit / 2;\n    let high = limit * 2;\n}\n";
+
+        // Only the usage on line 4 is replaced; the declaration and the other usage stay
+        assert_eq!(
+            inline_only_at("main.rs", source, 2, 9, Some((4, 17))),
+            "fn main() {\n    let limit = 10;\n    let low = limit / 2;\n    let high = 10 * 2;\n}"
+        );
+
+        // Pointing anywhere inside the identifier works, anywhere else is an error
+        let temp = tempfile::TempDir::new().unwrap();
+        let file_path = temp.path().join("main.rs");
+        fs::write(&file_path, source).unwrap();
+        let scip_query = ScipQuery::new(scip::types::Index::new(), temp.path().to_path_buf());
+        let inliner = VariableInliner::new(&scip_query, temp.path().to_path_buf());
+        let options = |only_at| InlineOptions {
+            file_path: file_path.clone(),
+            line: 2,
+            column: 9,
+            mode: TransactionMode::DryRun,
+            only_at: Some(only_at),
+        };
+
+        let summary = inliner.preview(options((3, 19))).unwrap();
+        assert_eq!(summary.total_changes, 1);
+        assert_eq!(summary.file_changes[0].changes[0].line, 3);
+        assert!(inliner.preview(options((3, 9))).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory, relative to the project root, holding one journal per applied refactor
+pub const UNDO_DIR: &str = ".powertools/undo";
+
+/// Journals kept per project; older ones are pruned when a new one is saved
+const MAX_JOURNALS: usize = 20;
+
+/// What a refactor did to one file, with enough to reverse it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Absolute path of the file
+    pub path: PathBuf,
+
+    /// Content before the refactor; `None` if the refactor created the file
+    pub original: Option<String>,
+
+    /// Hash of the content the refactor left; `None` if it deleted the file
+    pub result_hash: Option<String>,
+}
+
+/// A reversible record of the files one refactor touched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    /// What was done, e.g. "rename foo -> bar"
+    pub description: String,
+
+    /// When the refactor was applied, in seconds since the Unix epoch
+    pub timestamp: u64,
+
+    pub entries: Vec<JournalEntry>,
+}
+
+/// Result of undoing a refactor
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoResult {
+    pub description: String,
+    pub files_restored: Vec<PathBuf>,
+}
+
+impl Journal {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record a file's content before and after the refactor (`None` where it didn't exist)
+    pub fn record(&mut self, path: &Path, original: Option<String>, result: Option<&str>) -> Result<()> {
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir().context("Failed to get current directory")?.join(path)
+        };
+        self.entries.push(JournalEntry {
+            path,
+            original,
+            result_hash: result.map(content_hash),
+        });
+        Ok(())
+    }
+
+    /// Write the journal under the project's undo directory, returning its path
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf> {
+        let dir = project_root.join(UNDO_DIR);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        // Nanoseconds keep names unique and sort them in the order refactors were applied
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let path = dir.join(format!("{:024}.json", nanos));
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize undo journal")?;
+        fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let journals = journal_files(&dir)?;
+        for old in journals.iter().take(journals.len().saturating_sub(MAX_JOURNALS)) {
+            let _ = fs::remove_file(old);
+        }
+        Ok(path)
+    }
+
+    /// The most recently saved journal and its path, if any
+    pub fn latest(project_root: &Path) -> Result<Option<(PathBuf, Journal)>> {
+        let dir = project_root.join(UNDO_DIR);
+        if !dir.is_dir() {
+            return Ok(None);
+        }
+
+        let Some(path) = journal_files(&dir)?.pop() else {
+            return Ok(None);
+        };
+
+        let json = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let journal = serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some((path, journal)))
+    }
+
+    /// Files that changed since the refactor, which undoing would clobber
+    pub fn modified_since(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                let current = fs::read_to_string(&entry.path).ok();
+                current.as_deref().map(content_hash) != entry.result_hash
+            })
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    /// Put every file back the way it was before the refactor
+    fn restore(&self) -> Result<Vec<PathBuf>> {
+        let mut restored = Vec::new();
+        // Reverse order, so a moved file is recreated before its new copy is removed
+        for entry in self.entries.iter().rev() {
+            match &entry.original {
+                Some(content) => {
+                    if let Some(parent) = entry.path.parent() {
+                        fs::create_dir_all(parent)
+                            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+                    }
+                    fs::write(&entry.path, content)
+                        .with_context(|| format!("Failed to restore {}", entry.path.display()))?;
+                }
+                None => fs::remove_file(&entry.path)
+                    .with_context(|| format!("Failed to remove {}", entry.path.display()))?,
+            }
+            restored.push(entry.path.clone());
+        }
+        restored.reverse();
+        Ok(restored)
+    }
+}
+
+/// Journal files in an undo directory, oldest first
+fn journal_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Revert the most recent refactor in the project
+///
+/// Refuses if any file it touched has changed since, so later edits aren't lost.
+/// The journal is removed once the files are restored.
+pub fn undo_latest(project_root: &Path) -> Result<UndoResult> {
+    let (path, journal) = Journal::latest(project_root)?
+        .ok_or_else(|| anyhow::anyhow!("Nothing to undo: no refactor journal in {}", project_root.join(UNDO_DIR).display()))?;
+
+    let modified = journal.modified_since();
+    if !modified.is_empty() {
+        let files: Vec<String> = modified.iter().map(|p| format!("  {}", p.display())).collect();
+        anyhow::bail!(
+            "Cannot undo '{}': these files changed since it was applied:\n{}",
+            journal.description,
+            files.join("\n")
+        );
+    }
+
+    let files_restored = journal.restore()?;
+    fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+
+    Ok(UndoResult {
+        description: journal.description,
+        files_restored,
+    })
+}
+
+/// Stable 64-bit FNV-1a hash of file content, as hex
+///
+/// Only used to notice edits made after a refactor, so it needn't be cryptographic,
+/// but it must not change between builds the way `DefaultHasher` may.
+fn content_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_undo_restores_exactly() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let edited = root.join("src/app.ts");
+        let created = root.join("src/new.ts");
+        let deleted = root.join("src/old.ts");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(&edited, "const a = 1;\r\nexport { a };\n").unwrap();
+        fs::write(&created, "export {};").unwrap();
+
+        let mut journal = Journal::new("move src/old.ts -> src/new.ts");
+        journal.record(&edited, Some("const a = 1;\r\nexport {a}".to_string()), Some("const a = 1;\r\nexport { a };\n")).unwrap();
+        journal.record(&created, None, Some("export {};")).unwrap();
+        journal.record(&deleted, Some("export {};".to_string()), None).unwrap();
+        journal.save(root).unwrap();
+
+        let result = undo_latest(root).unwrap();
+        assert_eq!(result.files_restored, vec![edited.clone(), created.clone(), deleted.clone()]);
+        assert_eq!(fs::read_to_string(&edited).unwrap(), "const a = 1;\r\nexport {a}");
+        assert!(!created.exists());
+        assert_eq!(fs::read_to_string(&deleted).unwrap(), "export {};");
+
+        // The journal is used up
+        assert!(Journal::latest(root).unwrap().is_none());
+        assert!(undo_latest(root).is_err());
+    }
+
+    #[test]
+    fn test_undo_refuses_when_files_changed_since() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        let file = root.join("lib.rs");
+        fs::write(&file, "fn renamed() {}").unwrap();
+
+        let mut journal = Journal::new("rename original -> renamed");
+        journal.record(&file, Some("fn original() {}".to_string()), Some("fn renamed() {}")).unwrap();
+        journal.save(root).unwrap();
+
+        fs::write(&file, "fn renamed() {}\nfn added() {}").unwrap();
+        let err = undo_latest(root).unwrap_err().to_string();
+        assert!(err.contains("lib.rs"), "{}", err);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "fn renamed() {}\nfn added() {}");
+        assert!(Journal::latest(root).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_latest_is_most_recent_and_old_journals_are_pruned() {
+        let temp = TempDir::new().unwrap();
+        for i in 0..MAX_JOURNALS + 2 {
+            Journal::new(format!("refactor {}", i)).save(temp.path()).unwrap();
+        }
+
+        let (_, journal) = Journal::latest(temp.path()).unwrap().unwrap();
+        assert_eq!(journal.description, format!("refactor {}", MAX_JOURNALS + 1));
+        assert_eq!(journal_files(&temp.path().join(UNDO_DIR)).unwrap().len(), MAX_JOURNALS);
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+    }
+}
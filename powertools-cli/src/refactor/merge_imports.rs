@@ -0,0 +1,388 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+use super::imports::{ImportLocation, ImportSpecifier, ScriptImport, TypeScriptImportAnalyzer};
+use super::move_file::ModuleKind;
+use super::preview::{ChangeType, ImportChange, PreviewChange, PreviewDiff, RefactoringSummary};
+use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
+
+/// Options for merging the imports of a file
+#[derive(Debug, Clone)]
+pub struct MergeImportsOptions {
+    /// TypeScript/JavaScript file whose imports are merged (relative paths are resolved against the project root)
+    pub file: PathBuf,
+
+    /// Transaction mode (Execute or DryRun)
+    pub mode: TransactionMode,
+}
+
+/// Result of merging a file's imports
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeImportsResult {
+    pub file_path: PathBuf,
+
+    /// Modules whose import statements were merged
+    pub modules_merged: usize,
+
+    /// Import statements removed by merging
+    pub statements_removed: usize,
+
+    /// Transaction result
+    pub transaction_result: TransactionResult,
+}
+
+/// The import statements of one module, replaced by as few as can express them
+struct ModuleMerge {
+    /// Statements being merged, in file order
+    statements: Vec<ScriptImport>,
+
+    /// What replaces the first of them; the rest are removed
+    merged: Vec<ScriptImport>,
+
+    /// Render named specifiers one per line, because an original did
+    multiline: bool,
+}
+
+impl ModuleMerge {
+    fn render(&self) -> String {
+        let merged: Vec<String> = self.merged.iter().map(|import| import.render(self.multiline)).collect();
+        merged.join("\n")
+    }
+}
+
+/// Everything a merge will change, computed before touching the disk
+struct MergePlan {
+    file: PathBuf,
+    original: String,
+    new_content: String,
+    merges: Vec<ModuleMerge>,
+}
+
+/// Merge TypeScript/JavaScript imports that share a source into one statement
+pub struct ImportMerger {
+    project_root: PathBuf,
+}
+
+impl ImportMerger {
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+
+    /// Perform the merge
+    pub fn merge(&self, options: MergeImportsOptions) -> Result<MergeImportsResult> {
+        let plan = self.plan(&options)?;
+
+        // With nothing to merge, leave no journal for `undo` to pick up
+        let mut transaction = RefactoringTransaction::new(options.mode);
+        if !plan.merges.is_empty() {
+            transaction = transaction
+                .with_journal(self.project_root.clone(), format!("merge imports in {}", options.file.display()));
+            transaction.add_operation(plan.file.clone(), plan.original.clone(), plan.new_content.clone())?;
+        }
+        let transaction_result = transaction.commit()?;
+
+        Ok(MergeImportsResult {
+            modules_merged: plan.merges.len(),
+            statements_removed: statements_removed(&plan.merges),
+            file_path: plan.file,
+            transaction_result,
+        })
+    }
+
+    /// Generate a preview of the merge
+    pub fn preview(&self, options: MergeImportsOptions) -> Result<RefactoringSummary> {
+        let plan = self.plan(&options)?;
+        let lines: Vec<&str> = plan.original.lines().collect();
+        let line_text = |line: usize| lines.get(line - 1).copied().unwrap_or_default();
+
+        let mut diff = PreviewDiff::new(plan.file.clone());
+        for merge in &plan.merges {
+            for (index, statement) in merge.statements.iter().enumerate() {
+                let location = &statement.location;
+                let original = lines[location.line - 1..location.end_line].join("\n");
+                diff.add_change(PreviewChange {
+                    line: location.line,
+                    column: 1,
+                    original: original.trim().to_string(),
+                    replacement: if index == 0 { merge.render() } else { String::new() },
+                    line_content: line_text(location.line).to_string(),
+                });
+            }
+
+            let first = &merge.statements[0];
+            diff.add_import_change(ImportChange {
+                change_type: ChangeType::ImportUpdate,
+                source: first.source.clone(),
+                symbols: merge.merged.iter().flat_map(bound_names).collect(),
+                line: first.location.line,
+            });
+        }
+        diff.calculate_risk();
+
+        let file_changes = if plan.merges.is_empty() { Vec::new() } else { vec![diff] };
+        let mut summary = RefactoringSummary::new(file_changes);
+        let removed = statements_removed(&plan.merges);
+        summary.warnings.insert(
+            0,
+            format!(
+                "🔗 {} import statement{} to merge away across {} module{}",
+                removed,
+                if removed == 1 { "" } else { "s" },
+                plan.merges.len(),
+                if plan.merges.len() == 1 { "" } else { "s" }
+            ),
+        );
+        Ok(summary)
+    }
+
+    fn plan(&self, options: &MergeImportsOptions) -> Result<MergePlan> {
+        let file = self.project_root.join(&options.file);
+        if ModuleKind::of(&file) != Some(ModuleKind::Script) {
+            anyhow::bail!("merge-imports supports TypeScript/JavaScript files: {}", options.file.display());
+        }
+        let original = fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+
+        let imports = TypeScriptImportAnalyzer::new().find_script_imports(&original)?;
+        let lines: Vec<&str> = original.lines().collect();
+
+        let mut merges = Vec::new();
+        for statements in groups(imports) {
+            if !statements.iter().all(|statement| stands_alone(&statement.location, &statement.source, &lines)) {
+                continue;
+            }
+            let merged = combine(&statements);
+            if merged.len() < statements.len() {
+                merges.push(ModuleMerge {
+                    multiline: statements.iter().any(|s| s.location.end_line > s.location.line),
+                    statements,
+                    merged,
+                });
+            }
+        }
+
+        let new_content = apply(&original, &merges);
+        Ok(MergePlan {
+            file,
+            original,
+            new_content,
+            merges,
+        })
+    }
+}
+
+fn statements_removed(merges: &[ModuleMerge]) -> usize {
+    merges.iter().map(|merge| merge.statements.len() - merge.merged.len()).sum()
+}
+
+/// Local names an import binds
+fn bound_names(import: &ScriptImport) -> Vec<String> {
+    let named = import.named.iter().map(|specifier| specifier.alias.as_ref().unwrap_or(&specifier.name));
+    import.default.iter().chain(&import.namespace).chain(named).cloned().collect()
+}
+
+/// Statements importing the same module, for modules imported more than once
+///
+/// Type-only imports are grouped apart from value imports, and side-effect imports
+/// (`import './polyfills'`) are left out: they bind nothing to merge.
+fn groups(imports: Vec<ScriptImport>) -> Vec<Vec<ScriptImport>> {
+    let mut groups: Vec<Vec<ScriptImport>> = Vec::new();
+    for import in imports {
+        if bound_names(&import).is_empty() {
+            continue;
+        }
+        match groups
+            .iter_mut()
+            .find(|group| group[0].source == import.source && group[0].type_only == import.type_only)
+        {
+            Some(group) => group.push(import),
+            None => groups.push(vec![import]),
+        }
+    }
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+/// Whether a statement has its lines to itself and no comments, so it can be re-rendered or removed whole
+///
+/// The module `source` is blanked out first, so a URL in it doesn't read as a comment.
+pub(super) fn stands_alone(location: &ImportLocation, source: &str, lines: &[&str]) -> bool {
+    let (Some(first), Some(last)) = (lines.get(location.line - 1), lines.get(location.end_line - 1)) else {
+        return false;
+    };
+    let before = first.get(..location.column).unwrap_or_default();
+    let after = last.get(location.end_column..).unwrap_or_default();
+
+    let text = lines[location.line - 1..location.end_line].join("\n").replace(source, "");
+    before.trim().is_empty() && after.trim().is_empty() && !text.contains("//") && !text.contains("/*")
+}
+
+/// The fewest statements importing everything the given ones do
+///
+/// A default import joins the named or namespace import (`import Def, { a } from 'm'`)
+/// except in type-only imports, which can't combine them. Specifiers are sorted by name.
+fn combine(statements: &[ScriptImport]) -> Vec<ScriptImport> {
+    let first = &statements[0];
+    let mut defaults: Vec<String> = Vec::new();
+    let mut namespaces: Vec<String> = Vec::new();
+    let mut named: Vec<ImportSpecifier> = Vec::new();
+    for statement in statements {
+        if let Some(default) = statement.default.as_ref().filter(|default| !defaults.contains(default)) {
+            defaults.push(default.clone());
+        }
+        if let Some(namespace) = statement.namespace.as_ref().filter(|namespace| !namespaces.contains(namespace)) {
+            namespaces.push(namespace.clone());
+        }
+        for specifier in &statement.named {
+            if !named.contains(specifier) {
+                named.push(specifier.clone());
+            }
+        }
+    }
+    named.sort_by(|a, b| {
+        (a.name.to_lowercase(), &a.name, &a.alias).cmp(&(b.name.to_lowercase(), &b.name, &b.alias))
+    });
+
+    let blank = ScriptImport {
+        default: None,
+        namespace: None,
+        named: Vec::new(),
+        ..first.clone()
+    };
+    let mut defaults = defaults.into_iter();
+    let mut take_default = || if first.type_only { None } else { defaults.next() };
+
+    let mut merged = Vec::new();
+    if !named.is_empty() {
+        merged.push(ScriptImport {
+            default: take_default(),
+            named,
+            ..blank.clone()
+        });
+    }
+    for namespace in namespaces {
+        merged.push(ScriptImport {
+            default: take_default(),
+            namespace: Some(namespace),
+            ..blank.clone()
+        });
+    }
+    for default in defaults {
+        merged.push(ScriptImport {
+            default: Some(default),
+            ..blank.clone()
+        });
+    }
+    merged
+}
+
+/// Replace the first statement of each merge with the merged ones and drop the rest
+fn apply(content: &str, merges: &[ModuleMerge]) -> String {
+    let mut lines: Vec<Option<String>> = content.split_inclusive('\n').map(|line| Some(line.to_string())).collect();
+
+    for merge in merges {
+        for statement in &merge.statements {
+            for line in statement.location.line..=statement.location.end_line {
+                lines[line - 1] = None;
+            }
+        }
+
+        let first = &merge.statements[0].location;
+        let last = content.split_inclusive('\n').nth(first.end_line - 1).unwrap_or_default();
+        let ending = if last.ends_with("\r\n") {
+            "\r\n"
+        } else if last.ends_with('\n') {
+            "\n"
+        } else {
+            ""
+        };
+        let newline = if ending.is_empty() { "\n" } else { ending };
+        lines[first.line - 1] = Some(format!("{}{}", merge.render().replace('\n', newline), ending));
+    }
+
+    lines.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn options(file: &str, mode: TransactionMode) -> MergeImportsOptions {
+        MergeImportsOptions {
+            file: PathBuf::from(file),
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_merge_two_named_imports() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "src/app.ts",
+            "import { c, b } from './m';\nimport React from 'react';\nimport { a as x, type T, b } from './m';\n\nconsole.log(b, c, x, React);\n",
+        );
+        let merger = ImportMerger::new(dir.path().to_path_buf());
+
+        let summary = merger.preview(options("src/app.ts", TransactionMode::DryRun)).unwrap();
+        assert_eq!(summary.total_files, 1);
+        assert!(summary.warnings[0].contains("1 import statement to merge away across 1 module"));
+        assert_eq!(summary.file_changes[0].changes[0].replacement, "import { a as x, b, c, type T } from './m';");
+        assert!(fs::read_to_string(&file).unwrap().starts_with("import { c, b }"));
+
+        let result = merger.merge(options("src/app.ts", TransactionMode::Execute)).unwrap();
+        assert_eq!((result.modules_merged, result.statements_removed), (1, 1));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import { a as x, b, c, type T } from './m';\nimport React from 'react';\n\nconsole.log(b, c, x, React);\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_default_named_and_namespace() {
+        let dir = TempDir::new().unwrap();
+        let file = write(
+            dir.path(),
+            "view.tsx",
+            "import Def from \"m\";\nimport type { U } from \"m\";\nimport {\n  z,\n  y,\n} from \"m\";\nimport * as NS from \"m\";\nimport type { T } from \"m\";\nimport \"m\";\n",
+        );
+
+        let result = ImportMerger::new(dir.path().to_path_buf())
+            .merge(options("view.tsx", TransactionMode::Execute))
+            .unwrap();
+        assert_eq!((result.modules_merged, result.statements_removed), (2, 2));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "import Def, {\n  y,\n  z,\n} from \"m\";\nimport * as NS from \"m\";\nimport type { T, U } from \"m\";\nimport \"m\";\n"
+        );
+    }
+
+    #[test]
+    fn test_leaves_imports_that_cannot_merge() {
+        let dir = TempDir::new().unwrap();
+        let content = "import A from './m';\nimport B from './m';\nimport { a } from './n'; // keep\nimport { b } from './n';\n";
+        let file = write(dir.path(), "lib.js", content);
+
+        let result = ImportMerger::new(dir.path().to_path_buf())
+            .merge(options("lib.js", TransactionMode::Execute))
+            .unwrap();
+        assert_eq!(result.modules_merged, 0);
+        assert_eq!(fs::read_to_string(&file).unwrap(), content);
+
+        let err = ImportMerger::new(dir.path().to_path_buf())
+            .preview(options("main.py", TransactionMode::DryRun))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("TypeScript/JavaScript"), "{}", err);
+    }
+}
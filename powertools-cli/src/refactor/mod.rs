@@ -1,15 +1,34 @@
 pub mod imports;
+mod case_convert;
+mod check_imports;
+mod conflicts;
+mod convert_imports;
+mod convert_require;
 mod inline;
+mod journal;
+mod merge_imports;
+mod move_file;
 mod preview;
 mod rename;
 mod replacer;
+mod scope;
+mod syntax;
 mod transaction;
 pub mod unified_refactor;
 
-pub use inline::{InlineOptions, VariableInliner};
-pub use preview::generate_preview;
+pub use case_convert::{CaseConverter, ConvertCaseOptions, DeclarationKind};
+pub use check_imports::{CheckImportsOptions, ImportChecker};
+pub use convert_imports::{ConvertImportsOptions, ImportConverter};
+pub use convert_require::{ConvertRequireOptions, RequireConverter};
+pub use inline::{char_column_from_visual, InlineOptions, VariableInliner};
+pub use journal::{undo_latest, UndoResult};
+pub use merge_imports::{ImportMerger, MergeImportsOptions};
+pub use move_file::{FileMover, MoveFileOptions};
+pub use preview::{format_file_changes, generate_preview};
 pub use rename::{RenameOptions, SymbolRenamer};
 pub use replacer::BatchReplacer;
+pub use scope::ReplaceScope;
+pub use syntax::parser_for_file;
 #[allow(unused_imports)]
 pub use replacer::ReplacementMode;
 pub use transaction::TransactionMode;
@@ -0,0 +1,810 @@
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::imports::{get_analyzer_for_file, ImportKind, ImportStatement};
+use super::preview::{ChangeType, ImportChange, PreviewChange, PreviewDiff, RefactoringSummary};
+use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
+
+/// Extensions resolved by TypeScript/JavaScript relative imports
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs"];
+
+/// Options for moving (or renaming) a file
+#[derive(Debug, Clone)]
+pub struct MoveFileOptions {
+    /// The file to move (relative paths are resolved against the project root)
+    pub old_path: PathBuf,
+
+    /// Where to move it (relative paths are resolved against the project root)
+    pub new_path: PathBuf,
+
+    /// Transaction mode (Execute or DryRun)
+    pub mode: TransactionMode,
+}
+
+/// Result of a file move
+#[derive(Debug, Clone, Serialize)]
+pub struct MoveFileResult {
+    pub old_path: PathBuf,
+
+    pub new_path: PathBuf,
+
+    /// Number of other files whose imports were rewritten
+    pub importers_updated: usize,
+
+    /// Number of import paths rewritten, including the moved file's own
+    pub imports_updated: usize,
+
+    /// Follow-up steps that couldn't be done automatically
+    pub warnings: Vec<String>,
+
+    /// Transaction result
+    pub transaction_result: TransactionResult,
+}
+
+/// Import families whose paths can be rewritten
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ModuleKind {
+    Script,
+    Python,
+    Rust,
+}
+
+impl ModuleKind {
+    pub(super) fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            ext if SCRIPT_EXTENSIONS.contains(&ext) => Some(Self::Script),
+            "py" | "pyi" => Some(Self::Python),
+            "rs" => Some(Self::Rust),
+            _ => None,
+        }
+    }
+}
+
+/// One import path rewrite in a file
+#[derive(Debug, Clone)]
+pub(super) struct PathRewrite {
+    pub(super) line: usize,
+    pub(super) old_source: String,
+    pub(super) new_source: String,
+}
+
+/// Rewritten content for one file
+#[derive(Debug, Clone)]
+pub(super) struct FileEdit {
+    pub(super) original: String,
+    pub(super) new_content: String,
+    pub(super) rewrites: Vec<PathRewrite>,
+}
+
+/// Rust module paths of the file before and after the move
+struct RustModules {
+    src_dir: PathBuf,
+    old_segments: Vec<String>,
+    new_segments: Vec<String>,
+}
+
+impl RustModules {
+    fn old_path(&self) -> String {
+        format!("crate::{}", self.old_segments.join("::"))
+    }
+
+    fn new_path(&self) -> String {
+        format!("crate::{}", self.new_segments.join("::"))
+    }
+}
+
+/// Everything a move will change, computed before touching the disk
+struct MovePlan {
+    old_path: PathBuf,
+    new_path: PathBuf,
+    moved: FileEdit,
+    importers: BTreeMap<PathBuf, FileEdit>,
+    warnings: Vec<String>,
+}
+
+/// Move a file and rewrite the imports that reference it
+pub struct FileMover {
+    project_root: PathBuf,
+}
+
+impl FileMover {
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+
+    /// Perform the move
+    pub fn move_file(&self, options: MoveFileOptions) -> Result<MoveFileResult> {
+        let plan = self.plan(&options)?;
+
+        let mut transaction = RefactoringTransaction::new(options.mode).with_journal(
+            self.project_root.clone(),
+            format!("move {} -> {}", options.old_path.display(), options.new_path.display()),
+        );
+        transaction.add_move(
+            plan.old_path.clone(),
+            plan.new_path.clone(),
+            plan.moved.new_content.clone(),
+        )?;
+        for (path, edit) in &plan.importers {
+            transaction.add_operation(path.clone(), edit.original.clone(), edit.new_content.clone())?;
+        }
+
+        let transaction_result = transaction.commit()?;
+
+        Ok(MoveFileResult {
+            importers_updated: plan.importers.len(),
+            imports_updated: plan.moved.rewrites.len()
+                + plan.importers.values().map(|edit| edit.rewrites.len()).sum::<usize>(),
+            old_path: plan.old_path,
+            new_path: plan.new_path,
+            warnings: plan.warnings,
+            transaction_result,
+        })
+    }
+
+    /// Generate a preview of the move
+    pub fn preview(&self, options: MoveFileOptions) -> Result<RefactoringSummary> {
+        let plan = self.plan(&options)?;
+
+        // The moved file is shown at its new location
+        let mut file_changes = vec![edit_preview(&plan.new_path, &plan.moved)];
+        for (path, edit) in &plan.importers {
+            file_changes.push(edit_preview(path, edit));
+        }
+
+        let mut summary = RefactoringSummary::new(file_changes);
+        summary.warnings.insert(
+            0,
+            format!("📁 {} → {}", plan.old_path.display(), plan.new_path.display()),
+        );
+        summary.warnings.extend(plan.warnings);
+        Ok(summary)
+    }
+
+    fn plan(&self, options: &MoveFileOptions) -> Result<MovePlan> {
+        let root = fs::canonicalize(&self.project_root).with_context(|| {
+            format!("Project root not found: {}", self.project_root.display())
+        })?;
+
+        let old_path = fs::canonicalize(root.join(&options.old_path))
+            .with_context(|| format!("File not found: {}", options.old_path.display()))?;
+        if !old_path.is_file() {
+            anyhow::bail!("Not a file: {}", old_path.display());
+        }
+        let new_path = resolve_new_path(&root.join(&options.new_path))?;
+        if new_path.exists() {
+            anyhow::bail!("Destination already exists: {}", new_path.display());
+        }
+
+        let old_dir = old_path.parent().unwrap_or(&root).to_path_buf();
+        let new_dir = new_path.parent().unwrap_or(&root).to_path_buf();
+        let mut warnings = Vec::new();
+        let kind = ModuleKind::of(&old_path);
+
+        let rust_modules = match kind {
+            Some(ModuleKind::Rust) => {
+                let modules = rust_modules(&old_path, &new_path);
+                if modules.is_none() {
+                    warnings.push(
+                        "Rust `use` paths and `mod` declarations are only updated for moves within one crate's src/"
+                            .to_string(),
+                    );
+                }
+                modules
+            }
+            _ => None,
+        };
+
+        // The moved file's own relative imports now start from a different directory
+        let moved = match kind {
+            Some(ModuleKind::Script) => rewrite_file(&old_path, &mut warnings, |import| {
+                let target = resolve_script(&old_dir, &import.source)?;
+                render_script(&new_dir, &target, &import.source)
+            })?,
+            Some(ModuleKind::Python) => rewrite_file(&old_path, &mut warnings, |import| {
+                if !import.source.starts_with('.') {
+                    return None;
+                }
+                let target = resolve_python(&old_path, &import.source, &root)?;
+                let new_source = render_python(&new_dir, &target, true, &root)?;
+                Some((import.source.clone(), new_source))
+            })?,
+            Some(ModuleKind::Rust) => {
+                if old_dir != new_dir {
+                    let content = fs::read_to_string(&old_path)?;
+                    if content.contains("super::") || content.contains("self::") {
+                        warnings.push(format!(
+                            "{} uses relative `super::`/`self::` paths; check them after the move",
+                            new_path.display()
+                        ));
+                    }
+                }
+                None
+            }
+            None => None,
+        };
+        let moved = match moved {
+            Some(edit) => edit,
+            None => {
+                let content = fs::read_to_string(&old_path)
+                    .with_context(|| format!("Failed to read file: {}", old_path.display()))?;
+                FileEdit {
+                    original: content.clone(),
+                    new_content: content,
+                    rewrites: Vec::new(),
+                }
+            }
+        };
+
+        // Rewrite every importer of the moved file
+        let mut importers = BTreeMap::new();
+        match kind {
+            Some(kind) => {
+                for file in project_files(&root, kind) {
+                    if file == old_path {
+                        continue;
+                    }
+                    let file_dir = file.parent().unwrap_or(&root).to_path_buf();
+
+                    let edit = match kind {
+                        ModuleKind::Script => rewrite_file(&file, &mut warnings, |import| {
+                            if resolve_script(&file_dir, &import.source)? != old_path {
+                                return None;
+                            }
+                            render_script(&file_dir, &new_path, &import.source)
+                        })?,
+                        ModuleKind::Python => rewrite_file(&file, &mut warnings, |import| {
+                            if resolve_python(&file, &import.source, &root)? != old_path {
+                                return None;
+                            }
+                            let relative = import.source.starts_with('.');
+                            let new_source = render_python(&file_dir, &new_path, relative, &root)?;
+                            Some((import.source.clone(), new_source))
+                        })?,
+                        ModuleKind::Rust => match &rust_modules {
+                            Some(modules) if file.starts_with(&modules.src_dir) => {
+                                rewrite_file(&file, &mut warnings, |import| {
+                                    imports_rust_module(import, &modules.old_path())
+                                        .then(|| (modules.old_path(), modules.new_path()))
+                                })?
+                            }
+                            _ => None,
+                        },
+                    };
+
+                    if let Some(edit) = edit {
+                        importers.insert(file, edit);
+                    }
+                }
+            }
+            None => warnings.push(format!(
+                "Imports of {} aren't updated for this file type",
+                old_path.display()
+            )),
+        }
+
+        if let Some(modules) = &rust_modules {
+            update_mod_declaration(modules, &mut importers, &mut warnings)?;
+        }
+
+        Ok(MovePlan {
+            old_path,
+            new_path,
+            moved,
+            importers,
+            warnings,
+        })
+    }
+}
+
+/// Rewrite the import paths `retarget` maps to new sources (as `(old, new)` path pairs)
+/// using the file's `ImportAnalyzer`; None if nothing in the file changes
+pub(super) fn rewrite_file<F>(file: &Path, warnings: &mut Vec<String>, retarget: F) -> Result<Option<FileEdit>>
+where
+    F: Fn(&ImportStatement) -> Option<(String, String)>,
+{
+    let Some(analyzer) = get_analyzer_for_file(file) else {
+        return Ok(None);
+    };
+    // Files that don't parse can't import anything we can rewrite
+    let Ok(imports) = analyzer.find_imports(file) else {
+        return Ok(None);
+    };
+
+    let mut rewrites: Vec<PathRewrite> = Vec::new();
+    for import in &imports {
+        let Some((old_source, new_source)) = retarget(import) else {
+            continue;
+        };
+        if old_source == new_source || rewrites.iter().any(|r| r.old_source == old_source) {
+            continue;
+        }
+        if import.kind == ImportKind::SimpleImport && import.alias.is_none() {
+            warnings.push(format!(
+                "{}: references to `{}` in the code also need to become `{}`",
+                file.display(),
+                old_source,
+                new_source
+            ));
+        }
+        rewrites.push(PathRewrite {
+            line: import.location.line,
+            old_source,
+            new_source,
+        });
+    }
+    if rewrites.is_empty() {
+        return Ok(None);
+    }
+
+    let original = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+
+    // update_import_path works from the file on disk, so merge the lines each rewrite changed
+    let mut lines: Vec<String> = original.lines().map(String::from).collect();
+    for rewrite in &rewrites {
+        let updated = analyzer.update_import_path(file, &rewrite.old_source, &rewrite.new_source)?;
+        let mut changed = false;
+        for (index, (before, after)) in original.lines().zip(updated.lines()).enumerate() {
+            if before != after {
+                lines[index] = after.to_string();
+                changed = true;
+            }
+        }
+        if !changed {
+            warnings.push(format!(
+                "{}:{}: couldn't rewrite `{}` to `{}`; update it manually",
+                file.display(),
+                rewrite.line,
+                rewrite.old_source,
+                rewrite.new_source
+            ));
+        }
+    }
+
+    Ok(Some(FileEdit {
+        new_content: join_lines(lines, &original),
+        original,
+        rewrites,
+    }))
+}
+
+fn join_lines(lines: Vec<String>, original: &str) -> String {
+    let mut content = lines.join("\n");
+    if original.ends_with('\n') {
+        content.push('\n');
+    }
+    content
+}
+
+/// Source files of the given kind under the project root (respecting .gitignore)
+fn project_files(root: &Path, kind: ModuleKind) -> Vec<PathBuf> {
+    WalkBuilder::new(root)
+        .standard_filters(true)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && ModuleKind::of(path) == Some(kind))
+        .collect()
+}
+
+/// Resolve a relative script import (`./utils`, `../lib/api.js`) to the file it names
+pub(super) fn resolve_script(from_dir: &Path, source: &str) -> Option<PathBuf> {
+    if !source.starts_with('.') {
+        return None;
+    }
+    find_script(&normalize(&from_dir.join(source)))
+}
+
+/// The script file an import path without its quotes (`<dir>/utils`, `<dir>/api.js`) names
+pub(super) fn find_script(base: &Path) -> Option<PathBuf> {
+    let base = base.to_path_buf();
+    let mut candidates = vec![base.clone()];
+    // ESM-style imports name the compiled file (`./utils.js` for utils.ts)
+    if base.extension().is_some() {
+        candidates.extend(SCRIPT_EXTENSIONS.iter().map(|ext| base.with_extension(ext)));
+    }
+    candidates.extend(SCRIPT_EXTENSIONS.iter().map(|ext| with_added_extension(&base, ext)));
+    candidates.extend(SCRIPT_EXTENSIONS.iter().map(|ext| base.join(format!("index.{}", ext))));
+
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+/// Relative script import of `target` from `from_dir`, in the same style as `original_source`
+pub(super) fn render_script(from_dir: &Path, target: &Path, original_source: &str) -> Option<(String, String)> {
+    let target = script_import_path(target, original_source)?;
+    let relative = pathdiff::diff_paths(&target, from_dir)?;
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let new_source = if relative.is_empty() {
+        ".".to_string()
+    } else if relative == ".." || relative.starts_with("../") {
+        relative
+    } else {
+        format!("./{}", relative)
+    };
+
+    Some((original_source.to_string(), new_source))
+}
+
+/// The path an import of `target` names, keeping `original_source`'s extension (or lack of
+/// one) and its choice between naming a directory or its `index` file
+pub(super) fn script_import_path(target: &Path, original_source: &str) -> Option<PathBuf> {
+    let original_extension = Path::new(original_source)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| SCRIPT_EXTENSIONS.contains(ext));
+    let is_index = target.file_stem().and_then(|stem| stem.to_str()) == Some("index");
+
+    Some(match original_extension {
+        Some(ext) => target.with_extension(ext),
+        None if is_index && !original_source.ends_with("index") => target.parent()?.to_path_buf(),
+        None => target.with_extension(""),
+    })
+}
+
+/// Resolve a Python import (`pkg.utils`, `..utils`) to the module file it names
+pub(super) fn resolve_python(importer: &Path, source: &str, root: &Path) -> Option<PathBuf> {
+    let level = source.chars().take_while(|c| *c == '.').count();
+    let module = &source[level..];
+
+    let mut base = if level == 0 {
+        root.to_path_buf()
+    } else {
+        let mut dir = importer.parent()?;
+        for _ in 1..level {
+            dir = dir.parent()?;
+        }
+        dir.to_path_buf()
+    };
+    if !module.is_empty() {
+        base.extend(module.split('.'));
+    }
+
+    [with_added_extension(&base, "py"), base.join("__init__.py")]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+}
+
+/// Python import of `target`: relative to `from_dir`, or a dotted path from `root`
+pub(super) fn render_python(from_dir: &Path, target: &Path, relative: bool, root: &Path) -> Option<String> {
+    let module_path = if target.file_name().and_then(|name| name.to_str()) == Some("__init__.py") {
+        target.parent()?.to_path_buf()
+    } else {
+        target.with_extension("")
+    };
+
+    let new_source = if relative {
+        let relative = pathdiff::diff_paths(&module_path, from_dir)?;
+        let mut levels = 1;
+        let mut parts = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::ParentDir => levels += 1,
+                Component::Normal(part) => parts.push(part.to_str()?.to_string()),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        format!("{}{}", ".".repeat(levels), parts.join("."))
+    } else {
+        let relative = module_path.strip_prefix(root).ok()?;
+        let parts = relative
+            .components()
+            .map(|component| match component {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if parts.is_empty() {
+            return None;
+        }
+        parts.join(".")
+    };
+
+    Some(new_source)
+}
+
+/// Module paths for a Rust file moved within the same crate's `src/`
+fn rust_modules(old_path: &Path, new_path: &Path) -> Option<RustModules> {
+    let src_dir = old_path
+        .ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file())?
+        .join("src");
+
+    let old_segments = rust_module_segments(&src_dir, old_path)?;
+    let new_segments = rust_module_segments(&src_dir, new_path)?;
+    Some(RustModules {
+        src_dir,
+        old_segments,
+        new_segments,
+    })
+}
+
+/// `src/a/b.rs` -> ["a", "b"]; None for crate roots and files outside `src/`
+fn rust_module_segments(src_dir: &Path, file: &Path) -> Option<Vec<String>> {
+    let relative = file.strip_prefix(src_dir).ok()?.with_extension("");
+    let mut segments = relative
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => part.to_str().map(String::from),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if segments.last().map(String::as_str) == Some("mod") {
+        segments.pop();
+    }
+    if segments.is_empty() || segments == ["lib"] || segments == ["main"] {
+        return None;
+    }
+    Some(segments)
+}
+
+/// Whether a `use` item refers to `module` or something inside it
+fn imports_rust_module(import: &ImportStatement, module: &str) -> bool {
+    let inside = format!("{}::", module);
+    std::iter::once(import.source.clone())
+        .chain(import.symbols.iter().map(|symbol| format!("{}::{}", import.source, symbol)))
+        .any(|path| path == module || path.starts_with(&inside))
+}
+
+/// Rename the `mod` declaration in the parent module, or explain what to do if the parent changes
+fn update_mod_declaration(
+    modules: &RustModules,
+    importers: &mut BTreeMap<PathBuf, FileEdit>,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    let (old_parent, old_name) = modules.old_segments.split_at(modules.old_segments.len() - 1);
+    let (new_parent, new_name) = modules.new_segments.split_at(modules.new_segments.len() - 1);
+    let (old_name, new_name) = (&old_name[0], &new_name[0]);
+
+    let parent_file = parent_module_file(&modules.src_dir, old_parent);
+    if old_parent != new_parent {
+        warnings.push(format!(
+            "Move `mod {};` from {} to `mod {};` in the {} module",
+            old_name,
+            parent_file.as_deref().map_or("its parent module".into(), |p| p.display().to_string()),
+            new_name,
+            if new_parent.is_empty() { "crate root".to_string() } else { new_parent.join("::") }
+        ));
+        return Ok(());
+    }
+    if old_name == new_name {
+        return Ok(());
+    }
+    let Some(parent_file) = parent_file else {
+        return Ok(());
+    };
+
+    let (original, current, mut rewrites) = match importers.remove(&parent_file) {
+        Some(edit) => (edit.original, edit.new_content, edit.rewrites),
+        None => {
+            let content = fs::read_to_string(&parent_file)
+                .with_context(|| format!("Failed to read file: {}", parent_file.display()))?;
+            (content.clone(), content, Vec::new())
+        }
+    };
+
+    let declaration = format!("mod {};", old_name);
+    let mut found = None;
+    let lines: Vec<String> = current
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let trimmed = line.trim_start();
+            let is_declaration = trimmed == declaration
+                || (trimmed.starts_with("pub") && trimmed.ends_with(&format!(" {}", declaration)));
+            if is_declaration && found.is_none() {
+                found = Some(index + 1);
+                line.replacen(&declaration, &format!("mod {};", new_name), 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    match found {
+        Some(line) => rewrites.push(PathRewrite {
+            line,
+            old_source: format!("mod {}", old_name),
+            new_source: format!("mod {}", new_name),
+        }),
+        None => warnings.push(format!(
+            "No `mod {};` declaration found in {}",
+            old_name,
+            parent_file.display()
+        )),
+    }
+
+    let new_content = join_lines(lines, &current);
+    if new_content != original {
+        importers.insert(
+            parent_file,
+            FileEdit {
+                original,
+                new_content,
+                rewrites,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// The file declaring the module at `segments` (`src/a.rs` or `src/a/mod.rs`, or the crate root)
+fn parent_module_file(src_dir: &Path, segments: &[String]) -> Option<PathBuf> {
+    let candidates = if segments.is_empty() {
+        vec![src_dir.join("lib.rs"), src_dir.join("main.rs")]
+    } else {
+        let dir = segments.iter().fold(src_dir.to_path_buf(), |dir, s| dir.join(s));
+        vec![dir.with_extension("rs"), dir.join("mod.rs")]
+    };
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+/// Preview of one file's rewritten lines
+pub(super) fn edit_preview(path: &Path, edit: &FileEdit) -> PreviewDiff {
+    let mut diff = PreviewDiff::new(path.to_path_buf());
+
+    for (index, (before, after)) in edit.original.lines().zip(edit.new_content.lines()).enumerate() {
+        if before != after {
+            diff.add_change(PreviewChange {
+                line: index + 1,
+                column: 1,
+                original: before.trim().to_string(),
+                replacement: after.trim().to_string(),
+                line_content: after.to_string(),
+            });
+        }
+    }
+    for rewrite in &edit.rewrites {
+        diff.add_import_change(ImportChange {
+            change_type: ChangeType::ImportUpdate,
+            source: rewrite.old_source.clone(),
+            symbols: Vec::new(),
+            line: rewrite.line,
+        });
+    }
+
+    diff
+}
+
+/// Absolute destination path, with existing ancestors canonicalized to match the project root
+fn resolve_new_path(path: &Path) -> Result<PathBuf> {
+    let path = normalize(path);
+    for ancestor in path.ancestors().skip(1) {
+        if ancestor.exists() {
+            let rest = path.strip_prefix(ancestor)?;
+            return Ok(fs::canonicalize(ancestor)?.join(rest));
+        }
+    }
+    Ok(path)
+}
+
+/// Resolve `.` and `..` without touching the file system
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// `utils` + `ts` -> `utils.ts` (unlike `with_extension`, keeps dotted names like `api.v2`)
+fn with_added_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, relative: &str, content: &str) -> PathBuf {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn move_file(root: &Path, old: &str, new: &str, mode: TransactionMode) -> MoveFileResult {
+        FileMover::new(root.to_path_buf())
+            .move_file(MoveFileOptions {
+                old_path: PathBuf::from(old),
+                new_path: PathBuf::from(new),
+                mode,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_move_typescript_file_updates_importers() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "src/utils.ts", "import { config } from './config';\nexport const add = (a: number, b: number) => a + b;\n");
+        write(root, "src/config.ts", "export const config = {};\n");
+        let app = write(root, "src/app.ts", "import { add } from './utils';\n\nconsole.log(add(1, 2));\n");
+        let page = write(root, "src/pages/home.ts", "import { add } from '../utils';\nimport { config } from '../config';\n");
+
+        let result = move_file(root, "src/utils.ts", "src/lib/math.ts", TransactionMode::Execute);
+        assert_eq!(result.importers_updated, 2);
+        assert_eq!(result.imports_updated, 3);
+
+        assert!(!root.join("src/utils.ts").exists());
+        let moved = fs::read_to_string(root.join("src/lib/math.ts")).unwrap();
+        assert!(moved.starts_with("import { config } from '../config';\n"));
+
+        assert_eq!(
+            fs::read_to_string(app).unwrap(),
+            "import { add } from './lib/math';\n\nconsole.log(add(1, 2));\n"
+        );
+        assert_eq!(
+            fs::read_to_string(page).unwrap(),
+            "import { add } from '../lib/math';\nimport { config } from '../config';\n"
+        );
+    }
+
+    #[test]
+    fn test_preview_does_not_touch_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "utils.ts", "export const x = 1;\n");
+        write(root, "app.ts", "import { x } from './utils';\n");
+
+        let summary = FileMover::new(root.to_path_buf())
+            .preview(MoveFileOptions {
+                old_path: PathBuf::from("utils.ts"),
+                new_path: PathBuf::from("shared/utils.ts"),
+                mode: TransactionMode::DryRun,
+            })
+            .unwrap();
+
+        assert_eq!(summary.total_files, 2);
+        assert_eq!(summary.total_import_changes, 1);
+        assert!(root.join("utils.ts").exists());
+        assert!(!root.join("shared").exists());
+    }
+
+    #[test]
+    fn test_move_python_module_updates_relative_and_absolute_imports() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "pkg/__init__.py", "");
+        write(root, "pkg/helpers.py", "def helper():\n    pass\n");
+        write(root, "pkg/api.py", "from .helpers import helper\n");
+        let main = write(root, "main.py", "from pkg.helpers import helper\n");
+
+        move_file(root, "pkg/helpers.py", "pkg/util/helpers.py", TransactionMode::Execute);
+
+        assert_eq!(fs::read_to_string(root.join("pkg/api.py")).unwrap(), "from .util.helpers import helper\n");
+        assert_eq!(fs::read_to_string(main).unwrap(), "from pkg.util.helpers import helper\n");
+    }
+
+    #[test]
+    fn test_rust_rename_updates_use_and_mod() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "Cargo.toml", "[package]\nname = \"demo\"\n");
+        write(root, "src/lib.rs", "pub mod parser;\nmod cli;\n");
+        write(root, "src/parser.rs", "pub fn parse() {}\n");
+        let cli = write(root, "src/cli.rs", "use crate::parser::parse;\n\npub fn run() { parse() }\n");
+
+        move_file(root, "src/parser.rs", "src/reader.rs", TransactionMode::Execute);
+
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), "pub mod reader;\nmod cli;\n");
+        assert!(fs::read_to_string(cli).unwrap().starts_with("use crate::reader::parse;\n"));
+    }
+}
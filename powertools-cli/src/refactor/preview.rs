@@ -1,9 +1,12 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use super::conflicts::RenameConflict;
+use super::transaction::FileAction;
+
 /// A single change in a file (one line with replacement)
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreviewChange {
     /// Line number (1-indexed)
     pub line: usize,
@@ -21,8 +24,96 @@ pub struct PreviewChange {
     pub line_content: String,
 }
 
+/// One changed line, before and after, as a sample of what happens to a file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChangeSample {
+    /// Line number (1-indexed) in the original file
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// How much a refactoring changes one file: the per-file line of a plan or receipt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChangeSummary {
+    pub file_path: PathBuf,
+
+    /// What happens to the file as a whole
+    pub action: FileAction,
+
+    /// Number of changes (changed lines, for applied transactions)
+    pub changes: usize,
+
+    /// The first change, to show what the edits look like
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample: Option<ChangeSample>,
+}
+
+/// Longest a sample line is shown before being cut off
+const SAMPLE_WIDTH: usize = 80;
+
+/// Render per-file change counts with samples, followed by the grand total
+pub fn format_file_changes(files: &[FileChangeSummary]) -> String {
+    let mut output = String::new();
+    let shorten = |line: &str| {
+        let line = line.trim();
+        if line.chars().count() > SAMPLE_WIDTH {
+            format!("{}…", line.chars().take(SAMPLE_WIDTH).collect::<String>())
+        } else {
+            line.to_string()
+        }
+    };
+
+    for file in files {
+        let action = match file.action {
+            FileAction::Write => "",
+            FileAction::Create => " (created)",
+            FileAction::Delete => " (deleted)",
+        };
+        output.push_str(&format!(
+            "   {:>3} change{}  {}{}\n",
+            file.changes,
+            if file.changes == 1 { " " } else { "s" },
+            file.file_path.display(),
+            action
+        ));
+        if let Some(sample) = &file.sample {
+            output.push_str(&format!("               {}: {}\n", sample.line, shorten(&sample.before)));
+            output.push_str(&format!("               {}→ {}\n", " ".repeat(sample.line.to_string().len()), shorten(&sample.after)));
+        }
+    }
+
+    let total: usize = files.iter().map(|f| f.changes).sum();
+    output.push_str(&format!(
+        "   Total: {} change{} in {} file{}\n",
+        total,
+        if total == 1 { "" } else { "s" },
+        files.len(),
+        if files.len() == 1 { "" } else { "s" }
+    ));
+    output
+}
+
+impl PreviewChange {
+    /// The line with this change applied, if `original` is found at the change's column
+    pub fn applied_line(&self) -> Option<String> {
+        let chars: Vec<char> = self.line_content.chars().collect();
+        let start = self.column.checked_sub(1)?;
+        let end = start + self.original.chars().count();
+        if end > chars.len() || chars[start..end].iter().copied().ne(self.original.chars()) {
+            return None;
+        }
+        Some(format!(
+            "{}{}{}",
+            chars[..start].iter().collect::<String>(),
+            self.replacement,
+            chars[end..].iter().collect::<String>()
+        ))
+    }
+}
+
 /// Type of change being made
-#[derive(Debug, Clone, Serialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[allow(dead_code)] // Used in future refactoring implementations
 pub enum ChangeType {
     /// Renaming a symbol
@@ -44,7 +135,7 @@ pub enum ChangeType {
 }
 
 /// Risk level for a change
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RiskLevel {
     /// Safe change - unlikely to break anything
     Low,
@@ -54,8 +145,18 @@ pub enum RiskLevel {
     High,
 }
 
+impl RiskLevel {
+    fn label(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
 /// Import change being tracked
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)] // Used in future refactoring implementations
 pub struct ImportChange {
     /// Type of import change
@@ -69,7 +170,7 @@ pub struct ImportChange {
 }
 
 /// Preview of all changes in a single file
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreviewDiff {
     /// File path
     pub file_path: PathBuf,
@@ -80,6 +181,11 @@ pub struct PreviewDiff {
     /// Individual changes
     pub changes: Vec<PreviewChange>,
 
+    /// Each changed line before and after all of its changes, when the refactoring
+    /// reports them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub line_changes: Vec<ChangeSample>,
+
     /// Import changes in this file
     pub import_changes: Vec<ImportChange>,
 
@@ -93,6 +199,7 @@ impl PreviewDiff {
             file_path,
             num_changes: 0,
             changes: Vec::new(),
+            line_changes: Vec::new(),
             import_changes: Vec::new(),
             risk_level: RiskLevel::Low,
         }
@@ -135,6 +242,21 @@ impl PreviewDiff {
         };
     }
 
+    /// Change count and first change of this file
+    pub fn file_summary(&self) -> FileChangeSummary {
+        let sample = self.changes.first().map(|change| ChangeSample {
+            line: change.line,
+            before: change.line_content.clone(),
+            after: change.applied_line().unwrap_or_else(|| change.replacement.clone()),
+        });
+        FileChangeSummary {
+            file_path: self.file_path.clone(),
+            action: FileAction::Write,
+            changes: self.num_changes,
+            sample,
+        }
+    }
+
     /// Generate a human-readable diff output
     pub fn format_diff(&self) -> String {
         let mut output = String::new();
@@ -195,7 +317,7 @@ impl PreviewDiff {
 }
 
 /// Multi-file refactoring summary with cross-file analysis
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefactoringSummary {
     /// All file changes
     pub file_changes: Vec<PreviewDiff>,
@@ -212,11 +334,18 @@ pub struct RefactoringSummary {
     /// Total import changes across all files
     pub total_import_changes: usize,
 
+    /// Per-file change counts, with a sample change from each
+    pub files: Vec<FileChangeSummary>,
+
     /// Files grouped by risk level
     pub risk_breakdown: HashMap<String, usize>,
 
     /// Warnings/recommendations for the user
     pub warnings: Vec<String>,
+
+    /// Existing symbols the refactoring would collide with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<RenameConflict>,
 }
 
 impl RefactoringSummary {
@@ -229,6 +358,7 @@ impl RefactoringSummary {
         let total_files = file_changes.len();
         let total_changes: usize = file_changes.iter().map(|d| d.num_changes).sum();
         let total_import_changes: usize = file_changes.iter().map(|d| d.import_changes.len()).sum();
+        let files = file_changes.iter().map(PreviewDiff::file_summary).collect();
 
         // Find highest risk
         let overall_risk = file_changes.iter()
@@ -240,12 +370,7 @@ impl RefactoringSummary {
         // Risk breakdown
         let mut risk_breakdown = HashMap::new();
         for diff in &file_changes {
-            let key = match diff.risk_level {
-                RiskLevel::Low => "low",
-                RiskLevel::Medium => "medium",
-                RiskLevel::High => "high",
-            };
-            *risk_breakdown.entry(key.to_string()).or_insert(0) += 1;
+            *risk_breakdown.entry(diff.risk_level.label().to_string()).or_insert(0) += 1;
         }
 
         // Generate warnings
@@ -267,9 +392,25 @@ impl RefactoringSummary {
             total_files,
             total_changes,
             total_import_changes,
+            files,
             risk_breakdown,
             warnings,
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Attach name conflicts; any conflict makes the whole refactoring high risk
+    pub fn with_conflicts(mut self, conflicts: Vec<RenameConflict>) -> Self {
+        if !conflicts.is_empty() {
+            self.overall_risk = RiskLevel::High;
+            self.warnings.push(format!(
+                "💥 {} naming conflict{} detected. Applying requires --force.",
+                conflicts.len(),
+                if conflicts.len() == 1 { "" } else { "s" }
+            ));
         }
+        self.conflicts = conflicts;
+        self
     }
 
     /// Format the summary for display
@@ -302,6 +443,11 @@ impl RefactoringSummary {
             ));
         }
 
+        if !self.files.is_empty() {
+            output.push_str("\n📋 Plan:\n");
+            output.push_str(&format_file_changes(&self.files));
+        }
+
         // Risk breakdown
         if !self.risk_breakdown.is_empty() {
             output.push_str("\n🎯 Risk Assessment:\n");
@@ -324,6 +470,14 @@ impl RefactoringSummary {
             }
         }
 
+        // Conflicts
+        if !self.conflicts.is_empty() {
+            output.push_str("\n💥 Conflicts:\n");
+            for conflict in &self.conflicts {
+                output.push_str(&format!("   {}\n", conflict.format()));
+            }
+        }
+
         output.push_str("\n========================================\n\n");
 
         // Individual file diffs
@@ -337,6 +491,55 @@ impl RefactoringSummary {
         output.push_str("\n========================================\n");
         output
     }
+
+    /// Format the summary as Markdown for a PR comment, with a collapsible diff per file
+    pub fn format_markdown(&self, title: &str) -> String {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        let mut output = format!("### {}\n\n", title);
+        output.push_str(&format!(
+            "**{} file{}, {} change{}** ({} risk)\n",
+            self.total_files,
+            plural(self.total_files),
+            self.total_changes,
+            plural(self.total_changes),
+            self.overall_risk.label()
+        ));
+
+        if !self.warnings.is_empty() {
+            output.push('\n');
+            for warning in &self.warnings {
+                output.push_str(&format!("- {}\n", warning));
+            }
+        }
+
+        if !self.conflicts.is_empty() {
+            output.push_str("\n**Conflicts:**\n\n");
+            for conflict in &self.conflicts {
+                output.push_str(&format!("- `{}`\n", conflict.format()));
+            }
+        }
+
+        for diff in &self.file_changes {
+            output.push_str(&format!(
+                "\n<details>\n<summary><code>{}</code>: {} change{} ({} risk)</summary>\n\n```diff\n",
+                diff.file_path.display(),
+                diff.num_changes,
+                plural(diff.num_changes),
+                diff.risk_level.label()
+            ));
+            for change in &diff.changes {
+                let after = change.applied_line().unwrap_or_else(|| change.replacement.clone());
+                output.push_str(&format!(
+                    "@@ -{line} +{line} @@\n-{}\n+{}\n",
+                    change.line_content,
+                    after,
+                    line = change.line
+                ));
+            }
+            output.push_str("```\n\n</details>\n");
+        }
+        output
+    }
 }
 
 /// Generate preview for all files (legacy function - now wraps RefactoringSummary)
@@ -366,4 +569,90 @@ mod tests {
         assert!(formatted.contains("- foo"));
         assert!(formatted.contains("+ bar"));
     }
+
+    #[test]
+    fn test_summary_counts_changes_per_file() {
+        let change = |line: usize, line_content: &str| PreviewChange {
+            line,
+            column: 13,
+            original: "total".to_string(),
+            replacement: "sum".to_string(),
+            line_content: line_content.to_string(),
+        };
+        let mut lib = PreviewDiff::new(PathBuf::from("src/lib.rs"));
+        lib.add_change(change(3, "    let x = total(1);"));
+        lib.add_change(change(8, "    let y = total(2);"));
+        let mut util = PreviewDiff::new(PathBuf::from("src/util.rs"));
+        util.add_change(change(1, "use crate::total;"));
+
+        let summary = RefactoringSummary::new(vec![lib, util]);
+        let counts: Vec<_> = summary.files.iter().map(|f| (f.file_path.to_str().unwrap(), f.changes)).collect();
+        assert_eq!(counts, vec![("src/lib.rs", 2), ("src/util.rs", 1)]);
+
+        // The sample is the first change's line, before and after
+        assert_eq!(
+            summary.files[0].sample,
+            Some(ChangeSample {
+                line: 3,
+                before: "    let x = total(1);".to_string(),
+                after: "    let x = sum(1);".to_string(),
+            })
+        );
+        // ... falling back to the replacement text when the line doesn't match
+        assert_eq!(summary.files[1].sample.as_ref().unwrap().after, "sum");
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["files"][0]["changes"], 2);
+        assert_eq!(json["files"][1]["file_path"], "src/util.rs");
+
+        let formatted = summary.format_summary();
+        assert!(formatted.contains("Plan:"));
+        assert!(formatted.contains("Total: 3 changes in 2 files"));
+
+        // JSON previews read back as the same summary
+        let parsed: RefactoringSummary = serde_json::from_str(&serde_json::to_string_pretty(&summary).unwrap()).unwrap();
+        assert_eq!(parsed.total_changes, 3);
+        assert_eq!(parsed.file_changes[0].changes[1].line, 8);
+        assert_eq!(parsed.files[0].sample, summary.files[0].sample);
+        assert_eq!(parsed.overall_risk, summary.overall_risk);
+    }
+
+    #[test]
+    fn test_summary_markdown() {
+        let mut diff = PreviewDiff::new(PathBuf::from("src/util.rs"));
+        diff.add_change(PreviewChange {
+            line: 4,
+            column: 12,
+            original: "total".to_string(),
+            replacement: "sum".to_string(),
+            line_content: "    return total;".to_string(),
+        });
+        let markdown = RefactoringSummary::new(vec![diff]).format_markdown("Rename preview");
+
+        assert!(markdown.starts_with("### Rename preview\n\n**1 file, 1 change** (low risk)\n"));
+        assert!(markdown.contains("<summary><code>src/util.rs</code>: 1 change (low risk)</summary>"));
+        assert!(markdown.contains("```diff\n@@ -4 +4 @@\n-    return total;\n+    return sum;\n```\n\n</details>\n"));
+    }
+
+    #[test]
+    fn test_summary_with_conflicts() {
+        use super::super::conflicts::ConflictKind;
+
+        let summary = RefactoringSummary::new(Vec::new()).with_conflicts(vec![RenameConflict {
+            file_path: PathBuf::from("src/lib.rs"),
+            line: 3,
+            column: 9,
+            kind: ConflictKind::ExistingSymbol,
+            message: "'total' already exists in a scope the rename touches".to_string(),
+        }]);
+
+        assert_eq!(summary.overall_risk, RiskLevel::High);
+        let formatted = summary.format_summary();
+        assert!(formatted.contains("Conflicts:"));
+        assert!(formatted.contains("src/lib.rs:3:9"));
+
+        let parsed: RefactoringSummary = serde_json::from_value(serde_json::to_value(&summary).unwrap()).unwrap();
+        assert_eq!(parsed.conflicts[0].kind, ConflictKind::ExistingSymbol);
+        assert!(summary.format_markdown("Rename preview").contains("- `src/lib.rs:3:9: 'total' already exists"));
+    }
 }
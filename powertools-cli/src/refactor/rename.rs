@@ -1,13 +1,16 @@
 use anyhow::{Context, Result};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::core::location::byte_offset;
 use crate::core::{Location, Reference};
 use crate::indexers::ScipQuery;
 
+use super::conflicts::{find_conflicts_in_file, RenameConflict};
 use super::imports::get_analyzer_for_file;
+use super::syntax::{is_in_comment_or_string, parser_for_file};
 use super::preview::{ChangeType, ImportChange, PreviewChange, PreviewDiff, RefactoringSummary};
 use super::transaction::{RefactoringTransaction, TransactionMode, TransactionResult};
 
@@ -31,6 +34,9 @@ pub struct RenameOptions {
 
     /// Transaction mode (Execute or DryRun)
     pub mode: TransactionMode,
+
+    /// Apply the rename even if the new name conflicts with existing symbols
+    pub force: bool,
 }
 
 /// Result of a rename operation
@@ -58,7 +64,6 @@ pub struct RenameResult {
 /// Rename a symbol across the codebase
 pub struct SymbolRenamer<'a> {
     scip_query: &'a ScipQuery,
-    #[allow(dead_code)] // Reserved for future use in path resolution
     project_root: PathBuf,
 }
 
@@ -90,17 +95,26 @@ impl<'a> SymbolRenamer<'a> {
             anyhow::bail!("No references found for symbol '{}'", old_name);
         }
 
-        // Step 3: Group references by file
-        let mut references_by_file: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        for reference in references {
-            references_by_file
-                .entry(reference.location.file_path.clone())
-                .or_insert_with(Vec::new)
-                .push(reference);
+        // Step 3: Group references by file, dropping matches in comments and strings
+        let references_by_file = group_code_references(references)?;
+
+        // Step 4: Refuse to rename into an existing name unless forced
+        if !options.force {
+            let conflicts = self.find_conflicts(&options.new_name, &references_by_file)?;
+            if !conflicts.is_empty() {
+                let details: Vec<String> = conflicts.iter().map(|c| format!("  {}", c.format())).collect();
+                anyhow::bail!(
+                    "Renaming '{}' to '{}' would conflict with existing symbols:\n{}\nUse --force to rename anyway",
+                    old_name,
+                    options.new_name,
+                    details.join("\n")
+                );
+            }
         }
 
-        // Step 4: Build a transaction with all file changes
-        let mut transaction = RefactoringTransaction::new(options.mode);
+        // Step 5: Build a transaction with all file changes
+        let mut transaction = RefactoringTransaction::new(options.mode)
+            .with_journal(self.project_root.clone(), format!("rename {} -> {}", old_name, options.new_name));
 
         for (file_path, file_refs) in &references_by_file {
             let content = fs::read_to_string(file_path)
@@ -111,7 +125,7 @@ impl<'a> SymbolRenamer<'a> {
             transaction.add_operation(file_path.clone(), content, new_content)?;
         }
 
-        // Step 5: Update imports/exports if requested
+        // Step 6: Update imports/exports if requested
         let mut imports_updated = 0;
         if options.update_imports {
             imports_updated = self.update_imports_for_rename(
@@ -122,7 +136,7 @@ impl<'a> SymbolRenamer<'a> {
             )?;
         }
 
-        // Step 6: Commit the transaction
+        // Step 7: Commit the transaction
         let transaction_result = transaction.commit()?;
 
         Ok(RenameResult {
@@ -152,14 +166,8 @@ impl<'a> SymbolRenamer<'a> {
             anyhow::bail!("No references found for symbol '{}'", old_name);
         }
 
-        // Group references by file
-        let mut references_by_file: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
-        for reference in references {
-            references_by_file
-                .entry(reference.location.file_path.clone())
-                .or_insert_with(Vec::new)
-                .push(reference);
-        }
+        // Group references by file, dropping matches in comments and strings
+        let references_by_file = group_code_references(references)?;
 
         // Build preview diffs
         let mut file_changes = Vec::new();
@@ -208,7 +216,28 @@ impl<'a> SymbolRenamer<'a> {
             file_changes.push(diff);
         }
 
-        Ok(RefactoringSummary::new(file_changes))
+        let conflicts = self.find_conflicts(&options.new_name, &references_by_file)?;
+        Ok(RefactoringSummary::new(file_changes).with_conflicts(conflicts))
+    }
+
+    /// Find existing symbols and imports the new name would collide with at any rename site
+    fn find_conflicts(
+        &self,
+        new_name: &str,
+        references_by_file: &HashMap<PathBuf, Vec<Reference>>,
+    ) -> Result<Vec<RenameConflict>> {
+        let mut conflicts = Vec::new();
+        for (file_path, file_refs) in references_by_file {
+            let content = fs::read_to_string(file_path)
+                .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+            let sites: Vec<(usize, usize)> = file_refs
+                .iter()
+                .map(|r| (r.location.line, r.location.column))
+                .collect();
+            conflicts.extend(find_conflicts_in_file(file_path, &content, new_name, &sites)?);
+        }
+        conflicts.sort_by(|a, b| (&a.file_path, a.line, a.column).cmp(&(&b.file_path, b.line, b.column)));
+        Ok(conflicts)
     }
 
     /// Extract symbol name from location
@@ -258,79 +287,11 @@ impl<'a> SymbolRenamer<'a> {
         old_name: &str,
         new_name: &str,
     ) -> Result<String> {
-        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-
-        // Sort references by line and column in reverse order
-        // This ensures we don't mess up positions when replacing
-        let mut sorted_refs = references.to_vec();
-        sorted_refs.sort_by(|a, b| {
-            b.location.line.cmp(&a.location.line)
-                .then(b.location.column.cmp(&a.location.column))
-        });
-
-        for reference in sorted_refs {
-            let line_idx = reference.location.line - 1;
-            if line_idx >= lines.len() {
-                continue;
-            }
-
-            let line = &lines[line_idx];
-            let col_idx = reference.location.column - 1;
-
-            // Ensure the symbol actually exists at this location
-            if !self.symbol_at_position(line, col_idx, old_name) {
-                continue;
-            }
-
-            // Replace the symbol
-            let chars: Vec<char> = line.chars().collect();
-
-            // Find the actual bounds of the identifier
-            let mut start = col_idx;
-            while start > 0 && (chars[start - 1].is_alphanumeric() || chars[start - 1] == '_') {
-                start -= 1;
-            }
-
-            let mut end = col_idx;
-            while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
-                end += 1;
-            }
-
-            // Replace
-            let new_line = format!(
-                "{}{}{}",
-                chars[..start].iter().collect::<String>(),
-                new_name,
-                chars[end..].iter().collect::<String>()
-            );
-
-            lines[line_idx] = new_line;
-        }
-
-        Ok(lines.join("\n"))
-    }
-
-    /// Check if a symbol exists at a specific position in a line
-    fn symbol_at_position(&self, line: &str, col_idx: usize, symbol: &str) -> bool {
-        let chars: Vec<char> = line.chars().collect();
-
-        if col_idx >= chars.len() {
-            return false;
-        }
-
-        // Find identifier bounds
-        let mut start = col_idx;
-        while start > 0 && (chars[start - 1].is_alphanumeric() || chars[start - 1] == '_') {
-            start -= 1;
-        }
-
-        let mut end = col_idx;
-        while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
-            end += 1;
-        }
-
-        let found_symbol: String = chars[start..end].iter().collect();
-        found_symbol == symbol
+        let edits: Vec<(&Location, &str, &str)> = references
+            .iter()
+            .map(|reference| (&reference.location, old_name, new_name))
+            .collect();
+        Ok(replace_identifiers(content, &edits))
     }
 
     /// Update imports/exports after renaming
@@ -394,20 +355,217 @@ impl<'a> SymbolRenamer<'a> {
     }
 }
 
+/// A symbol to rename along with every reference to it, resolved up front
+#[derive(Debug, Clone)]
+pub struct PlannedRename {
+    pub old_name: String,
+    pub new_name: String,
+    pub references: Vec<Reference>,
+}
+
+/// Apply several renames in one transaction
+/// All references must come from the same snapshot of the code, since each rename shifts
+/// the columns later ones would see
+pub fn apply_planned_renames(renames: &[PlannedRename], mode: TransactionMode) -> Result<TransactionResult> {
+    let mut transaction = RefactoringTransaction::new(mode);
+
+    for (file_path, edits) in edits_by_file(renames) {
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let new_content = replace_identifiers(&content, &edits);
+        if new_content != content {
+            transaction.add_operation(file_path, content, new_content)?;
+        }
+    }
+
+    transaction.commit()
+}
+
+/// Preview several renames applied together
+pub fn preview_planned_renames(renames: &[PlannedRename]) -> Result<RefactoringSummary> {
+    let mut file_changes = Vec::new();
+
+    for (file_path, edits) in edits_by_file(renames) {
+        let content = fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut diff = PreviewDiff::new(file_path);
+        for (location, old_name, new_name) in edits {
+            let line_content = lines.get(location.line - 1).copied().unwrap_or("");
+            if !symbol_at_position(line_content, location.column - 1, old_name) {
+                continue;
+            }
+            diff.add_change(PreviewChange {
+                line: location.line,
+                column: location.column,
+                original: old_name.to_string(),
+                replacement: new_name.to_string(),
+                line_content: line_content.to_string(),
+            });
+        }
+        if diff.num_changes > 0 {
+            file_changes.push(diff);
+        }
+    }
+
+    Ok(RefactoringSummary::new(file_changes))
+}
+
+/// Every (location, old, new) edit grouped by file, with duplicate locations dropped
+fn edits_by_file(renames: &[PlannedRename]) -> BTreeMap<PathBuf, Vec<(&Location, &str, &str)>> {
+    let mut by_file: BTreeMap<PathBuf, Vec<(&Location, &str, &str)>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+
+    for rename in renames {
+        for reference in &rename.references {
+            let location = &reference.location;
+            if seen.insert((location.file_path.clone(), location.line, location.column)) {
+                by_file
+                    .entry(location.file_path.clone())
+                    .or_default()
+                    .push((location, rename.old_name.as_str(), rename.new_name.as_str()));
+            }
+        }
+    }
+
+    by_file
+}
+
+/// Replace identifiers at the given locations, skipping any whose text no longer matches
+fn replace_identifiers(content: &str, edits: &[(&Location, &str, &str)]) -> String {
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+
+    // Sort edits by line and column in reverse order
+    // This ensures we don't mess up positions when replacing
+    let mut sorted_edits = edits.to_vec();
+    sorted_edits.sort_by(|(a, _, _), (b, _, _)| b.line.cmp(&a.line).then(b.column.cmp(&a.column)));
+
+    for (location, old_name, new_name) in sorted_edits {
+        let line_idx = location.line - 1;
+        if line_idx >= lines.len() {
+            continue;
+        }
+
+        let line = &lines[line_idx];
+        let col_idx = location.column - 1;
+
+        // Ensure the symbol actually exists at this location
+        if !symbol_at_position(line, col_idx, old_name) {
+            continue;
+        }
+
+        // Replace the symbol
+        let chars: Vec<char> = line.chars().collect();
+        let (start, end) = identifier_bounds(&chars, col_idx);
+
+        // Replace
+        let new_line = format!(
+            "{}{}{}",
+            chars[..start].iter().collect::<String>(),
+            new_name,
+            chars[end..].iter().collect::<String>()
+        );
+
+        lines[line_idx] = new_line;
+    }
+
+    let mut new_content = lines.join("\n");
+    if content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    new_content
+}
+
+/// Check if a symbol exists at a specific position in a line
+fn symbol_at_position(line: &str, col_idx: usize, symbol: &str) -> bool {
+    let chars: Vec<char> = line.chars().collect();
+
+    if col_idx >= chars.len() {
+        return false;
+    }
+
+    let (start, end) = identifier_bounds(&chars, col_idx);
+    let found_symbol: String = chars[start..end].iter().collect();
+    found_symbol == symbol
+}
+
+/// Start and end (exclusive) of the identifier around `col_idx`
+fn identifier_bounds(chars: &[char], col_idx: usize) -> (usize, usize) {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+
+    let mut start = col_idx;
+    while start > 0 && is_identifier_char(chars[start - 1]) {
+        start -= 1;
+    }
+
+    let mut end = col_idx;
+    while end < chars.len() && is_identifier_char(chars[end]) {
+        end += 1;
+    }
+
+    (start, end)
+}
+
+/// Group references by file, keeping only those that land on code
+/// Name-based lookups also match text in comments and string literals, which must not be renamed
+fn group_code_references(references: Vec<Reference>) -> Result<HashMap<PathBuf, Vec<Reference>>> {
+    let mut references_by_file: HashMap<PathBuf, Vec<Reference>> = HashMap::new();
+    for reference in references {
+        references_by_file
+            .entry(reference.location.file_path.clone())
+            .or_insert_with(Vec::new)
+            .push(reference);
+    }
+
+    for (file_path, file_refs) in references_by_file.iter_mut() {
+        let content = fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        *file_refs = retain_code_references(file_path, &content, std::mem::take(file_refs))?;
+    }
+    references_by_file.retain(|_, file_refs| !file_refs.is_empty());
+
+    if references_by_file.is_empty() {
+        anyhow::bail!("All references are inside comments or string literals");
+    }
+    Ok(references_by_file)
+}
+
+/// Drop references inside comments or string literals (interpolated identifiers are kept)
+fn retain_code_references(
+    file_path: &Path,
+    content: &str,
+    references: Vec<Reference>,
+) -> Result<Vec<Reference>> {
+    let Some(mut parser) = parser_for_file(file_path)? else {
+        return Ok(references);
+    };
+    let Some(tree) = parser.parse(content, None) else {
+        return Ok(references);
+    };
+
+    Ok(references
+        .into_iter()
+        .filter(|reference| {
+            let Some(byte) = byte_offset(content, reference.location.line, reference.location.column) else {
+                return false;
+            };
+            tree.root_node()
+                .descendant_for_byte_range(byte, byte)
+                .map_or(true, |node| !is_in_comment_or_string(node))
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_symbol_at_position() {
-        let renamer = SymbolRenamer {
-            scip_query: unsafe { &*(std::ptr::null() as *const ScipQuery) }, // Dummy for testing
-            project_root: PathBuf::new(),
-        };
-
-        assert!(renamer.symbol_at_position("let foo = 42;", 4, "foo"));
-        assert!(renamer.symbol_at_position("  myVar = 10", 2, "myVar"));
-        assert!(!renamer.symbol_at_position("let foo = 42;", 4, "bar"));
+        assert!(symbol_at_position("let foo = 42;", 4, "foo"));
+        assert!(symbol_at_position("  myVar = 10", 2, "myVar"));
+        assert!(!symbol_at_position("let foo = 42;", 4, "bar"));
     }
 
     #[test]
@@ -415,4 +573,56 @@ mod tests {
         // This would need a real file and location to test properly
         // Skipping for now as it requires file I/O
     }
+
+    fn reference_at(file: &str, line: usize, column: usize) -> Reference {
+        Reference {
+            location: Location {
+                file_path: PathBuf::from(file),
+                line,
+                column,
+                end_line: None,
+                end_column: None,
+            },
+            kind: crate::core::ReferenceKind::Reference,
+            context: None,
+        }
+    }
+
+    fn positions(references: &[Reference]) -> Vec<(usize, usize)> {
+        references.iter().map(|r| (r.location.line, r.location.column)).collect()
+    }
+
+    #[test]
+    fn test_skips_comment_and_string_matches() {
+        let content = "// count the items
+fn main() {
+    let count = 1;
+    println!(\"count: {}\", count);
+}
+";
+        let references = vec![
+            reference_at("main.rs", 1, 4),
+            reference_at("main.rs", 3, 9),
+            reference_at("main.rs", 4, 15),
+            reference_at("main.rs", 4, 27),
+        ];
+
+        let kept = retain_code_references(Path::new("main.rs"), content, references).unwrap();
+        assert_eq!(positions(&kept), vec![(3, 9), (4, 27)]);
+    }
+
+    #[test]
+    fn test_keeps_interpolated_identifiers() {
+        let content = "const count = 1;
+const label = `count is ${count}`;
+";
+        let references = vec![
+            reference_at("app.ts", 1, 7),
+            reference_at("app.ts", 2, 16),
+            reference_at("app.ts", 2, 27),
+        ];
+
+        let kept = retain_code_references(Path::new("app.ts"), content, references).unwrap();
+        assert_eq!(positions(&kept), vec![(1, 7), (2, 27)]);
+    }
 }
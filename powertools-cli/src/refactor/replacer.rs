@@ -1,10 +1,12 @@
 use anyhow::{Context, Result};
-use regex::Regex;
+use regex::{Captures, Regex};
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use super::preview::{PreviewChange, PreviewDiff};
+use super::preview::{ChangeSample, PreviewChange, PreviewDiff};
+use super::scope::ReplaceScope;
 use super::BatchResult;
 
 /// Mode for performing replacements
@@ -22,7 +24,7 @@ pub struct BatchReplacer {
     /// Regex pattern to search for
     pattern: Regex,
 
-    /// Replacement string (supports capture groups)
+    /// Replacement string (supports `$1`, `${name}` and `$$`, as in `Captures::expand`)
     replacement: String,
 
     /// File glob pattern (e.g., "*.rs", "**/*.ts")
@@ -30,6 +32,9 @@ pub struct BatchReplacer {
 
     /// Root path to search from
     root_path: PathBuf,
+
+    /// Only replace matches inside this function, class or line range
+    scope: Option<ReplaceScope>,
 }
 
 impl BatchReplacer {
@@ -42,15 +47,23 @@ impl BatchReplacer {
     ) -> Result<Self> {
         let regex = Regex::new(pattern)
             .with_context(|| format!("Invalid regex pattern: {}", pattern))?;
+        validate_group_references(&regex, &replacement)?;
 
         Ok(Self {
             pattern: regex,
             replacement,
             file_pattern,
             root_path,
+            scope: None,
         })
     }
 
+    /// Restrict replacements to matches inside `scope`
+    pub fn with_scope(mut self, scope: ReplaceScope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
     /// Preview changes without applying them
     pub fn preview(&self) -> Result<Vec<PreviewDiff>> {
         let files = self.collect_files()?;
@@ -94,37 +107,82 @@ impl BatchReplacer {
     /// Preview changes for a single file
     fn preview_file(&self, file_path: &Path, content: &str) -> Result<PreviewDiff> {
         let mut diff = PreviewDiff::new(file_path.to_path_buf());
+        let scope_ranges = self.scope_ranges(file_path, content)?;
 
         for (line_num, line) in content.lines().enumerate() {
-            for mat in self.pattern.find_iter(line) {
+            let line_start = offset_in(content, line);
+            let mut replaced = String::with_capacity(line.len());
+            let mut last_end = 0;
+            for caps in self.pattern.captures_iter(line) {
+                let mat = caps.get(0).expect("group 0 is always present");
+                if !in_scope(scope_ranges.as_deref(), line_start + mat.start(), line_start + mat.end()) {
+                    continue;
+                }
                 let original = mat.as_str().to_string();
-                let replacement = self.pattern.replace(line, &self.replacement).to_string();
+                let replacement = self.expand(&caps);
+                replaced.push_str(&line[last_end..mat.start()]);
+                replaced.push_str(&replacement);
+                last_end = mat.end();
 
                 diff.add_change(PreviewChange {
                     line: line_num + 1, // 1-indexed
                     column: mat.start() + 1, // 1-indexed
                     original,
-                    replacement: replacement.clone(),
+                    replacement,
                     line_content: line.to_string(),
                 });
             }
+            replaced.push_str(&line[last_end..]);
+
+            // The line as `apply` would write it, with every match on it replaced
+            if replaced != line {
+                diff.line_changes.push(ChangeSample {
+                    line: line_num + 1,
+                    before: line.to_string(),
+                    after: replaced,
+                });
+            }
         }
 
         Ok(diff)
     }
 
+    /// Replacement text for one match, with group references filled in
+    fn expand(&self, caps: &Captures) -> String {
+        let mut expanded = String::new();
+        caps.expand(&self.replacement, &mut expanded);
+        expanded
+    }
+
     /// Apply replacements to a single file
     fn apply_to_file(&self, file_path: &Path) -> Result<usize> {
         let content = fs::read_to_string(file_path)
             .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
 
+        let scope_ranges = self.scope_ranges(file_path, &content)?;
         let mut num_replacements = 0;
         let mut modified_content = String::new();
 
         for line in content.lines() {
-            let replaced = self.pattern.replace_all(line, &self.replacement);
+            let line_start = offset_in(&content, line);
+            let mut replaced = String::with_capacity(line.len());
+            let mut last_end = 0;
+            let mut line_replacements = 0;
+
+            for caps in self.pattern.captures_iter(line) {
+                let mat = caps.get(0).expect("group 0 is always present");
+                if !in_scope(scope_ranges.as_deref(), line_start + mat.start(), line_start + mat.end()) {
+                    continue;
+                }
+                replaced.push_str(&line[last_end..mat.start()]);
+                caps.expand(&self.replacement, &mut replaced);
+                last_end = mat.end();
+                line_replacements += 1;
+            }
+            replaced.push_str(&line[last_end..]);
+
             if replaced != line {
-                num_replacements += self.pattern.find_iter(line).count();
+                num_replacements += line_replacements;
             }
             modified_content.push_str(&replaced);
             modified_content.push('\n');
@@ -139,6 +197,14 @@ impl BatchReplacer {
         Ok(num_replacements)
     }
 
+    /// Byte ranges of the file inside the scope, or None when replacements aren't scoped
+    fn scope_ranges(&self, file_path: &Path, content: &str) -> Result<Option<Vec<Range<usize>>>> {
+        self.scope
+            .as_ref()
+            .map(|scope| scope.byte_ranges(file_path, content, &self.root_path))
+            .transpose()
+    }
+
     /// Collect all files matching the pattern
     fn collect_files(&self) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
@@ -249,6 +315,71 @@ impl BatchReplacer {
     }
 }
 
+/// Byte offset of `line` (a slice of `content`) within `content`
+fn offset_in(content: &str, line: &str) -> usize {
+    line.as_ptr() as usize - content.as_ptr() as usize
+}
+
+/// Whether a match spanning `start..end` lies entirely inside one of the scope ranges
+fn in_scope(ranges: Option<&[Range<usize>]>, start: usize, end: usize) -> bool {
+    ranges.map_or(true, |ranges| ranges.iter().any(|range| range.start <= start && end <= range.end))
+}
+
+/// Group references in a replacement string, parsed the way `Captures::expand` does:
+/// `$$` is a literal dollar, `${name}` is braced, and an unbraced `$name` takes the longest
+/// run of `[_0-9A-Za-z]` (so `$10` is group 10; write `${1}0` for group 1 then "0")
+fn group_references(replacement: &str) -> Vec<&str> {
+    let mut references = Vec::new();
+    let mut rest = replacement;
+
+    while let Some(dollar) = rest.find('$') {
+        rest = &rest[dollar + 1..];
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+        } else if let Some(braced) = rest.strip_prefix('{') {
+            // An unclosed brace is a literal `${`
+            if let Some(close) = braced.find('}') {
+                references.push(&braced[..close]);
+                rest = &braced[close + 1..];
+            }
+        } else {
+            let len = rest
+                .find(|c: char| !(c == '_' || c.is_ascii_alphanumeric()))
+                .unwrap_or(rest.len());
+            if len > 0 {
+                references.push(&rest[..len]);
+                rest = &rest[len..];
+            }
+        }
+    }
+
+    references
+}
+
+/// Reject references to groups the pattern doesn't define, which would otherwise expand to ""
+fn validate_group_references(pattern: &Regex, replacement: &str) -> Result<()> {
+    for reference in group_references(replacement) {
+        let exists = match reference.parse::<usize>() {
+            Ok(index) => index < pattern.captures_len(),
+            Err(_) => pattern.capture_names().flatten().any(|name| name == reference),
+        };
+        if !exists {
+            let hint = if reference.len() > 1 && reference.starts_with(|c: char| c.is_ascii_digit()) {
+                let (group, literal) = reference.split_at(1);
+                format!(" (use ${{{}}}{} for group {} followed by literal text)", group, literal, group)
+            } else {
+                String::new()
+            };
+            anyhow::bail!(
+                "Replacement references capture group '{}' which the pattern doesn't define{}",
+                reference,
+                hint
+            );
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,6 +419,54 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_preview_includes_lines_before_and_after() -> Result<()> {
+        let temp = TempDir::new()?;
+        let file_path = temp.path().join("urls.ts");
+        fs::write(&file_path, "get(old.com/a, old.com/b)
+keep old.org
+post(old.com/c)")?;
+
+        let replacer = BatchReplacer::new(
+            r"old\.com/(\w)",
+            "new.com/$1".to_string(),
+            None,
+            temp.path().to_path_buf(),
+        )?;
+
+        let diffs = replacer.preview()?;
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].num_changes, 3);
+        assert_eq!(
+            diffs[0].line_changes,
+            vec![
+                ChangeSample {
+                    line: 1,
+                    before: "get(old.com/a, old.com/b)".to_string(),
+                    after: "get(new.com/a, new.com/b)".to_string(),
+                },
+                ChangeSample {
+                    line: 3,
+                    before: "post(old.com/c)".to_string(),
+                    after: "post(new.com/c)".to_string(),
+                },
+            ]
+        );
+
+        // The after lines are exactly what apply writes
+        replacer.apply()?;
+        let applied = fs::read_to_string(&file_path)?;
+        for change in &diffs[0].line_changes {
+            assert_eq!(applied.lines().nth(change.line - 1), Some(change.after.as_str()));
+        }
+
+        let json = serde_json::to_value(&diffs[0])?;
+        assert_eq!(json["line_changes"][1]["before"], "post(old.com/c)");
+        assert_eq!(json["line_changes"][1]["after"], "post(new.com/c)");
+
+        Ok(())
+    }
+
     #[test]
     fn test_apply_to_file() -> Result<()> {
         let temp = TempDir::new()?;
@@ -309,4 +488,80 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_named_groups_and_escaped_dollar() -> Result<()> {
+        let temp = TempDir::new()?;
+        let file_path = temp.path().join("prices.txt");
+        fs::write(&file_path, "due 2024-03-15 cost 10\nnothing here")?;
+
+        let replacer = BatchReplacer::new(
+            r"(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2}) cost (\d+)",
+            "${day}/${month}/${year} cost $$$4".to_string(),
+            None,
+            temp.path().to_path_buf(),
+        )?;
+
+        let content = fs::read_to_string(&file_path)?;
+        let diff = replacer.preview_file(&file_path, &content)?;
+        assert_eq!(diff.num_changes, 1);
+        assert_eq!(diff.changes[0].original, "2024-03-15 cost 10");
+        assert_eq!(diff.changes[0].replacement, "15/03/2024 cost $10");
+
+        replacer.apply_to_file(&file_path)?;
+        assert_eq!(fs::read_to_string(&file_path)?, "due 15/03/2024 cost $10\nnothing here");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_references() {
+        assert_eq!(group_references("$1-${name}_$$x"), vec!["1", "name"]);
+        assert_eq!(group_references("${1}0 $10 $ ${open"), vec!["1", "10"]);
+
+        // `$10` means group 10, so a two-group pattern rejects it and suggests `${1}0`
+        let err = BatchReplacer::new("(a)(b)", "$10".to_string(), None, PathBuf::from("."))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("${1}0"), "{}", err);
+        assert!(BatchReplacer::new("(a)(b)", "${1}0".to_string(), None, PathBuf::from(".")).is_ok());
+        assert!(BatchReplacer::new("(?P<x>a)", "${y}".to_string(), None, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_scoped_replace_preserves_matches_outside_function() -> Result<()> {
+        let temp = TempDir::new()?;
+        let file_path = temp.path().join("lib.rs");
+        fs::write(
+            &file_path,
+            "fn load() -> u32 {\n    retries + 1\n}\n\nfn save() -> u32 {\n    retries + 2\n}\n\nconst LIMIT: u32 = retries;",
+        )?;
+
+        let replacer = BatchReplacer::new(
+            r"\bretries\b",
+            "attempts".to_string(),
+            None,
+            temp.path().to_path_buf(),
+        )?
+        .with_scope("function:save".parse()?);
+
+        let content = fs::read_to_string(&file_path)?;
+        let diff = replacer.preview_file(&file_path, &content)?;
+        assert_eq!(diff.num_changes, 1);
+        assert_eq!(diff.changes[0].line, 6);
+
+        assert_eq!(replacer.apply_to_file(&file_path)?, 1);
+        assert_eq!(
+            fs::read_to_string(&file_path)?,
+            "fn load() -> u32 {\n    retries + 1\n}\n\nfn save() -> u32 {\n    attempts + 2\n}\n\nconst LIMIT: u32 = retries;"
+        );
+
+        // A line range of a different file matches nothing here
+        let replacer = BatchReplacer::new("retries", "x".to_string(), None, temp.path().to_path_buf())?
+            .with_scope("other.rs:1-3".parse()?);
+        assert_eq!(replacer.apply_to_file(&file_path)?, 0);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,191 @@
+use anyhow::{Context, Result};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tree_sitter::Node;
+
+use super::syntax::parser_for_file;
+
+/// Function-like node kinds across the supported grammars
+const FUNCTION_KINDS: &[&str] = &[
+    "function_item",                  // Rust
+    "function_declaration",           // TypeScript / JavaScript
+    "generator_function_declaration", // TypeScript / JavaScript
+    "method_definition",              // TypeScript / JavaScript
+    "function_definition",            // Python / C++
+];
+
+/// Class-like node kinds across the supported grammars
+const CLASS_KINDS: &[&str] = &[
+    "struct_item", // Rust
+    "enum_item",
+    "trait_item",
+    "impl_item",
+    "class_declaration", // TypeScript / JavaScript
+    "abstract_class_declaration",
+    "class_definition", // Python
+    "class_specifier",  // C++
+    "struct_specifier",
+];
+
+/// Part of the code a batch replace is restricted to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaceScope {
+    /// Every function or method with this name (`function:foo`)
+    Function(String),
+    /// Every class with this name, including Rust `impl` blocks for the type (`class:Bar`)
+    Class(String),
+    /// An inclusive, 1-indexed line range of one file (`src/lib.rs:10-20`)
+    Lines {
+        file_path: PathBuf,
+        start: usize,
+        end: usize,
+    },
+}
+
+impl FromStr for ReplaceScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(name) = s.strip_prefix("function:") {
+            return Ok(Self::Function(name.to_string()));
+        }
+        if let Some(name) = s.strip_prefix("class:") {
+            return Ok(Self::Class(name.to_string()));
+        }
+
+        let parsed = s.rsplit_once(':').and_then(|(file, range)| {
+            let (start, end) = range.split_once('-')?;
+            Some((file, start.parse::<usize>().ok()?, end.parse::<usize>().ok()?))
+        });
+        match parsed {
+            Some((file, start, end)) if start >= 1 && start <= end => Ok(Self::Lines {
+                file_path: PathBuf::from(file),
+                start,
+                end,
+            }),
+            _ => anyhow::bail!(
+                "Invalid scope '{}' (expected function:NAME, class:NAME or FILE:START-END)",
+                s
+            ),
+        }
+    }
+}
+
+impl ReplaceScope {
+    /// Byte ranges of `content` inside the scope (empty when the file doesn't contain it)
+    pub fn byte_ranges(&self, file_path: &Path, content: &str, root: &Path) -> Result<Vec<Range<usize>>> {
+        let (kinds, name) = match self {
+            Self::Lines { file_path: scope_file, start, end } => {
+                if !same_file(file_path, scope_file, root) {
+                    return Ok(Vec::new());
+                }
+                return Ok(line_range(content, *start, *end).into_iter().collect());
+            }
+            Self::Function(name) => (FUNCTION_KINDS, name),
+            Self::Class(name) => (CLASS_KINDS, name),
+        };
+
+        let Some(mut parser) = parser_for_file(file_path)? else {
+            return Ok(Vec::new());
+        };
+        let tree = parser
+            .parse(content, None)
+            .with_context(|| format!("Failed to parse {}", file_path.display()))?;
+
+        let mut ranges = Vec::new();
+        collect_named_nodes(tree.root_node(), content, kinds, name, &mut ranges);
+        Ok(ranges)
+    }
+}
+
+/// Whether `file_path` (as walked) is the scope's file (absolute, or relative to the search root)
+fn same_file(file_path: &Path, scope_file: &Path, root: &Path) -> bool {
+    let scope_file = if scope_file.is_absolute() {
+        scope_file.to_path_buf()
+    } else {
+        root.join(scope_file)
+    };
+    match (file_path.canonicalize(), scope_file.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => file_path == scope_file,
+    }
+}
+
+/// Byte range covering lines `start..=end` (1-indexed)
+fn line_range(content: &str, start: usize, end: usize) -> Option<Range<usize>> {
+    let mut offset = 0;
+    let mut range_start = None;
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        if line_number == start {
+            range_start = Some(offset);
+        }
+        offset += line.len();
+        if line_number == end {
+            break;
+        }
+    }
+    range_start.map(|range_start| range_start..offset)
+}
+
+fn collect_named_nodes(node: Node, content: &str, kinds: &[&str], name: &str, found: &mut Vec<Range<usize>>) {
+    if kinds.contains(&node.kind()) && node_name(node, content).as_deref() == Some(name) {
+        found.push(node.byte_range());
+        // Nested matches are already covered
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_named_nodes(child, content, kinds, name, found);
+    }
+}
+
+/// Declared name of a function or class node
+fn node_name(node: Node, content: &str) -> Option<String> {
+    if let Some(name) = node.child_by_field_name("name") {
+        return Some(content[name.byte_range()].to_string());
+    }
+
+    // Rust `impl Bar` / `impl<T> Trait for Bar<T>`: the implementing type, without generics
+    if let Some(ty) = node.child_by_field_name("type") {
+        let text = &content[ty.byte_range()];
+        return Some(text.split('<').next().unwrap_or(text).trim().to_string());
+    }
+
+    // C++ `int Foo::bar(int x)`: the last segment of the declarator
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while let Some(inner) = declarator.child_by_field_name("declarator") {
+        declarator = inner;
+    }
+    let text = &content[declarator.byte_range()];
+    Some(text.rsplit("::").next().unwrap_or(text).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scope() {
+        assert_eq!("function:foo".parse::<ReplaceScope>().unwrap(), ReplaceScope::Function("foo".into()));
+        assert_eq!("class:Bar".parse::<ReplaceScope>().unwrap(), ReplaceScope::Class("Bar".into()));
+        assert_eq!(
+            "src/lib.rs:10-20".parse::<ReplaceScope>().unwrap(),
+            ReplaceScope::Lines { file_path: PathBuf::from("src/lib.rs"), start: 10, end: 20 }
+        );
+        assert!("src/lib.rs:20-10".parse::<ReplaceScope>().is_err());
+        assert!("foo".parse::<ReplaceScope>().is_err());
+    }
+
+    #[test]
+    fn test_class_scope_includes_rust_impl_blocks() {
+        let content = "struct Bar;\n\nimpl<T> Bar {\n    fn go(&self) {}\n}\n\nfn go() {}\n";
+        let ranges = ReplaceScope::Class("Bar".into())
+            .byte_ranges(Path::new("lib.rs"), content, Path::new("."))
+            .unwrap();
+        assert_eq!(ranges.len(), 2);
+        assert!(ranges.iter().all(|r| r.end <= content.find("fn go() {}").unwrap()));
+    }
+}
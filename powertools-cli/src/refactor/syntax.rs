@@ -0,0 +1,89 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+/// Node kinds that embed code inside a string (`${x}` in TS, `{x}` in Python f-strings)
+const INTERPOLATION_KINDS: &[&str] = &["template_substitution", "interpolation"];
+
+/// Tree-sitter parser for the file's language, or None for unsupported languages
+pub fn parser_for_file(file_path: &Path) -> Result<Option<Parser>> {
+    let extension = file_path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let language: tree_sitter::Language = match extension {
+        "rs" => tree_sitter_rust::LANGUAGE.into(),
+        "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "js" | "jsx" | "mjs" => tree_sitter_javascript::LANGUAGE.into(),
+        "py" | "pyi" => tree_sitter_python::LANGUAGE.into(),
+        "cpp" | "cc" | "cxx" | "c" | "h" | "hpp" | "hxx" => tree_sitter_cpp::LANGUAGE.into(),
+        "rb" | "rake" | "gemspec" => tree_sitter_ruby::LANGUAGE.into(),
+        _ => return Ok(None),
+    };
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language)
+        .with_context(|| format!("Failed to load grammar for .{} files", extension))?;
+    Ok(Some(parser))
+}
+
+/// Whether a node is comment or string-literal text rather than code
+/// Identifiers inside an interpolation (`${name}`, f"{name}") count as code
+pub fn is_in_comment_or_string(node: Node) -> bool {
+    let mut current = Some(node);
+    while let Some(candidate) = current {
+        let kind = candidate.kind();
+        if INTERPOLATION_KINDS.contains(&kind) {
+            return false;
+        }
+        if is_comment_kind(kind) || is_string_kind(kind) {
+            return true;
+        }
+        current = candidate.parent();
+    }
+    false
+}
+
+fn is_comment_kind(kind: &str) -> bool {
+    kind.contains("comment")
+}
+
+fn is_string_kind(kind: &str) -> bool {
+    kind.ends_with("string")
+        || kind.ends_with("string_literal")
+        || matches!(kind, "string_content" | "string_fragment" | "char_literal")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::location::byte_offset;
+
+    /// Whether the text at `line:column` of `content` parses as code
+    fn is_code_at(file: &str, content: &str, line: usize, column: usize) -> bool {
+        let mut parser = parser_for_file(Path::new(file)).unwrap().unwrap();
+        let tree = parser.parse(content, None).unwrap();
+        let byte = byte_offset(content, line, column).unwrap();
+        let node = tree.root_node().descendant_for_byte_range(byte, byte).unwrap();
+        !is_in_comment_or_string(node)
+    }
+
+    #[test]
+    fn test_comments_and_strings_are_not_code() {
+        let content = "fn main() {\n    // count the items\n    let count = 1;\n    println!(\"count: {}\", count);\n}\n";
+        assert!(!is_code_at("main.rs", content, 2, 8));
+        assert!(is_code_at("main.rs", content, 3, 9));
+        assert!(!is_code_at("main.rs", content, 4, 15));
+        assert!(is_code_at("main.rs", content, 4, 27));
+    }
+
+    #[test]
+    fn test_interpolated_identifiers_are_code() {
+        let content = "const label = `count is ${count}`;\n";
+        assert!(!is_code_at("app.ts", content, 1, 16));
+        assert!(is_code_at("app.ts", content, 1, 27));
+
+        let content = "label = f\"count is {count}\"\n";
+        assert!(!is_code_at("app.py", content, 1, 11));
+        assert!(is_code_at("app.py", content, 1, 21));
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-use super::preview::RefactoringSummary;
+use super::journal::Journal;
+use super::preview::{format_file_changes, ChangeSample, FileChangeSummary, RefactoringSummary};
 
 /// A single file operation in a transaction
 #[derive(Debug, Clone, Serialize)]
@@ -20,6 +21,23 @@ pub struct FileOperation {
 
     /// Whether this operation has been applied
     pub applied: bool,
+
+    /// What the operation does to the file
+    pub action: FileAction,
+}
+
+/// What a file operation does when applied
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileAction {
+    /// Overwrite an existing file
+    Write,
+
+    /// Create a new file (removed again on rollback)
+    Create,
+
+    /// Delete the file (restored on rollback)
+    Delete,
 }
 
 /// Transaction execution mode
@@ -46,6 +64,9 @@ pub struct RefactoringTransaction {
 
     /// Transaction mode
     mode: TransactionMode,
+
+    /// Project root and description for the undo journal written on commit
+    journal: Option<(PathBuf, String)>,
 }
 
 impl RefactoringTransaction {
@@ -56,9 +77,17 @@ impl RefactoringTransaction {
             backup: HashMap::new(),
             committed: false,
             mode,
+            journal: None,
         }
     }
 
+    /// Save an undo journal under the project root when the transaction is executed,
+    /// so `powertools undo` can revert it
+    pub fn with_journal(mut self, project_root: PathBuf, description: impl Into<String>) -> Self {
+        self.journal = Some((project_root, description.into()));
+        self
+    }
+
     /// Add a file operation to the transaction
     pub fn add_operation(
         &mut self,
@@ -80,6 +109,40 @@ impl RefactoringTransaction {
             original_content,
             new_content,
             applied: false,
+            action: FileAction::Write,
+        });
+
+        Ok(())
+    }
+
+    /// Move a file: create `to` with `new_content`, then delete `from`
+    pub fn add_move(&mut self, from: PathBuf, to: PathBuf, new_content: String) -> Result<()> {
+        if self.committed {
+            anyhow::bail!("Cannot add operations to a committed transaction");
+        }
+        if to.exists() {
+            anyhow::bail!("Destination already exists: {}", to.display());
+        }
+
+        let original_content = fs::read_to_string(&from)
+            .with_context(|| format!("Failed to read file: {}", from.display()))?;
+        self.backup
+            .entry(from.clone())
+            .or_insert_with(|| original_content.clone());
+
+        self.operations.push(FileOperation {
+            path: to,
+            original_content: String::new(),
+            new_content,
+            applied: false,
+            action: FileAction::Create,
+        });
+        self.operations.push(FileOperation {
+            path: from,
+            original_content,
+            new_content: String::new(),
+            applied: false,
+            action: FileAction::Delete,
         });
 
         Ok(())
@@ -110,6 +173,7 @@ impl RefactoringTransaction {
             successful_operations: 0,
             failed_operations: 0,
             files_modified: Vec::new(),
+            files: self.file_summaries(),
             errors: Vec::new(),
         };
 
@@ -154,11 +218,87 @@ impl RefactoringTransaction {
         }
 
         self.committed = true;
+
+        // The changes are already on disk, so a journal that can't be saved is reported, not fatal
+        if let Some((project_root, description)) = &self.journal {
+            if let Err(e) = self.journal_entries(description).and_then(|journal| journal.save(project_root)) {
+                result.errors.push(format!("Failed to save undo journal: {}", e));
+            }
+        }
+
         Ok(result)
     }
 
+    /// Each touched file with its content before and after the transaction, in the order
+    /// first touched; `None` where the file doesn't exist
+    fn net_changes(&self) -> Vec<(&PathBuf, Option<&str>, Option<&str>)> {
+        let mut seen = Vec::new();
+        let mut changes = Vec::new();
+        for operation in &self.operations {
+            if seen.contains(&&operation.path) {
+                continue;
+            }
+            seen.push(&operation.path);
+
+            let original = match operation.action {
+                FileAction::Create => None,
+                FileAction::Write | FileAction::Delete => Some(
+                    self.backup
+                        .get(&operation.path)
+                        .unwrap_or(&operation.original_content)
+                        .as_str(),
+                ),
+            };
+            // Later operations on the same file decide what it ends up as
+            let last = self.operations.iter().rev().find(|op| op.path == operation.path).unwrap_or(operation);
+            let result = match last.action {
+                FileAction::Delete => None,
+                FileAction::Write | FileAction::Create => Some(last.new_content.as_str()),
+            };
+            changes.push((&operation.path, original, result));
+        }
+        changes
+    }
+
+    fn journal_entries(&self, description: &str) -> Result<Journal> {
+        let mut journal = Journal::new(description);
+        for (path, original, result) in self.net_changes() {
+            journal.record(path, original.map(str::to_string), result)?;
+        }
+        Ok(journal)
+    }
+
+    /// Changed-line counts and a sample change for each touched file
+    fn file_summaries(&self) -> Vec<FileChangeSummary> {
+        self.net_changes()
+            .into_iter()
+            .map(|(path, original, result)| {
+                let (action, changes, sample) = match (original, result) {
+                    (Some(original), Some(result)) => {
+                        let (changes, sample) = changed_lines(original, result);
+                        (FileAction::Write, changes, sample)
+                    }
+                    (None, result) => (FileAction::Create, result.map_or(0, |r| r.lines().count()), None),
+                    (Some(original), None) => (FileAction::Delete, original.lines().count(), None),
+                };
+                FileChangeSummary {
+                    file_path: path.clone(),
+                    action,
+                    changes,
+                    sample,
+                }
+            })
+            .collect()
+    }
+
     /// Apply a single operation (write file) - static method to avoid borrow issues
     fn apply_operation_static(operation: &FileOperation) -> Result<()> {
+        if operation.action == FileAction::Delete {
+            fs::remove_file(&operation.path)
+                .with_context(|| format!("Failed to delete file: {}", operation.path.display()))?;
+            return Ok(());
+        }
+
         // Create parent directory if needed
         if let Some(parent) = operation.path.parent() {
             fs::create_dir_all(parent).with_context(|| {
@@ -182,8 +322,13 @@ impl RefactoringTransaction {
                 continue;
             }
 
-            // Restore original content
-            if let Err(e) = fs::write(&operation.path, &operation.original_content) {
+            // Remove created files, restore everything else
+            let restored = if operation.action == FileAction::Create {
+                fs::remove_file(&operation.path)
+            } else {
+                fs::write(&operation.path, &operation.original_content)
+            };
+            if let Err(e) = restored {
                 errors.push(format!("{}: {}", operation.path.display(), e));
             }
         }
@@ -268,6 +413,42 @@ impl RefactoringTransaction {
     }
 }
 
+/// Number of lines that differ between two versions of a file, and the first of them
+///
+/// Lines are compared after trimming the unchanged start and end of the file. Where
+/// the rest has the same number of lines, they're compared pairwise; otherwise every
+/// line in the longer side counts.
+fn changed_lines(old: &str, new: &str) -> (usize, Option<ChangeSample>) {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let max_suffix = old_lines.len().min(new_lines.len()) - prefix;
+    let suffix = old_lines
+        .iter()
+        .rev()
+        .zip(new_lines.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let (changes, first) = if old_mid.len() == new_mid.len() {
+        let differing: Vec<usize> = (0..old_mid.len()).filter(|&i| old_mid[i] != new_mid[i]).collect();
+        (differing.len(), differing.first().copied().unwrap_or(0))
+    } else {
+        (old_mid.len().max(new_mid.len()), 0)
+    };
+
+    let sample = (changes > 0).then(|| ChangeSample {
+        line: prefix + first + 1,
+        before: old_mid.get(first).copied().unwrap_or_default().to_string(),
+        after: new_mid.get(first).copied().unwrap_or_default().to_string(),
+    });
+    (changes, sample)
+}
+
 /// Result of a transaction execution
 #[derive(Debug, Clone, Serialize)]
 pub struct TransactionResult {
@@ -286,6 +467,9 @@ pub struct TransactionResult {
     /// Files that were modified
     pub files_modified: Vec<PathBuf>,
 
+    /// Per-file changed-line counts, with a sample change from each
+    pub files: Vec<FileChangeSummary>,
+
     /// Errors encountered
     pub errors: Vec<String>,
 }
@@ -330,19 +514,13 @@ impl TransactionResult {
             output.push_str(&format!("❌ {} failed\n", self.failed_operations));
         }
 
-        if !self.files_modified.is_empty() {
-            output.push_str(&format!(
-                "\n📝 {} file{} modified:\n",
-                self.files_modified.len(),
-                if self.files_modified.len() == 1 {
-                    ""
-                } else {
-                    "s"
-                }
-            ));
-            for file in &self.files_modified {
-                output.push_str(&format!("   {}\n", file.display()));
-            }
+        if !self.files.is_empty() {
+            output.push_str(if self.mode == TransactionMode::DryRun {
+                "\n📋 Would change:\n"
+            } else {
+                "\n🧾 Changed:\n"
+            });
+            output.push_str(&format_file_changes(&self.files));
         }
 
         if !self.errors.is_empty() {
@@ -372,6 +550,7 @@ impl Serialize for TransactionMode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::refactor::journal::{undo_latest, UNDO_DIR};
     use tempfile::TempDir;
 
     #[test]
@@ -464,4 +643,109 @@ mod tests {
         assert_eq!(preview.total_files, 1);
         assert_eq!(preview.total_changes, 1);
     }
+
+    #[test]
+    fn test_result_counts_changed_lines_per_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let lib = temp_dir.path().join("lib.rs");
+        let created = temp_dir.path().join("new.rs");
+        fs::write(&lib, "fn a() { foo() }\nfn b() {}\nfn c() { foo() }\n").unwrap();
+
+        let mut tx = RefactoringTransaction::new(TransactionMode::Execute);
+        tx.add_operation(
+            lib.clone(),
+            "fn a() { foo() }\nfn b() {}\nfn c() { foo() }\n".to_string(),
+            "fn a() { bar() }\nfn b() {}\nfn c() { bar() }\n".to_string(),
+        )
+        .unwrap();
+        tx.add_file_change(created.clone(), "fn bar() {}\n".to_string()).unwrap();
+        let result = tx.commit().unwrap();
+
+        let counts: Vec<_> = result.files.iter().map(|f| (f.file_path.clone(), f.changes)).collect();
+        assert_eq!(counts, vec![(lib, 2), (created, 1)]);
+        assert_eq!(
+            result.files[0].sample,
+            Some(ChangeSample {
+                line: 1,
+                before: "fn a() { foo() }".to_string(),
+                after: "fn a() { bar() }".to_string(),
+            })
+        );
+
+        let formatted = result.format_summary();
+        assert!(formatted.contains("Changed:"));
+        assert!(formatted.contains("Total: 3 changes in 2 files"));
+    }
+
+    #[test]
+    fn test_changed_lines_with_inserted_lines() {
+        let (changes, sample) = changed_lines("a\nb\nc\n", "a\nx\ny\nb\nc\n");
+        assert_eq!(changes, 2);
+        assert_eq!(sample.unwrap().line, 2);
+        assert_eq!(changed_lines("same", "same"), (0, None));
+    }
+
+    #[test]
+    fn test_undo_reverts_executed_transaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let edited = root.join("main.ts");
+        let from = root.join("old.ts");
+        let to = root.join("lib/new.ts");
+        fs::write(&edited, "import { a } from './old';\n").unwrap();
+        fs::write(&from, "export const a = 1;").unwrap();
+
+        let mut tx = RefactoringTransaction::new(TransactionMode::Execute)
+            .with_journal(root.to_path_buf(), "move old.ts -> lib/new.ts");
+        tx.add_move(from.clone(), to.clone(), "export const a = 1;".to_string())
+            .unwrap();
+        tx.add_operation(
+            edited.clone(),
+            "import { a } from './old';\n".to_string(),
+            "import { a } from './lib/new';\n".to_string(),
+        )
+        .unwrap();
+        let result = tx.commit().unwrap();
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let undone = undo_latest(root).unwrap();
+        assert_eq!(undone.description, "move old.ts -> lib/new.ts");
+        assert!(!to.exists());
+        assert_eq!(fs::read_to_string(&from).unwrap(), "export const a = 1;");
+        assert_eq!(fs::read_to_string(&edited).unwrap(), "import { a } from './old';\n");
+    }
+
+    #[test]
+    fn test_dry_run_writes_no_journal() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "original").unwrap();
+
+        let mut tx = RefactoringTransaction::new(TransactionMode::DryRun)
+            .with_journal(temp_dir.path().to_path_buf(), "edit");
+        tx.add_operation(file_path, "original".to_string(), "modified".to_string())
+            .unwrap();
+        tx.commit().unwrap();
+
+        assert!(!temp_dir.path().join(UNDO_DIR).exists());
+    }
+
+    #[test]
+    fn test_transaction_move_and_rollback() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("old.ts");
+        let to = temp_dir.path().join("lib/new.ts");
+        fs::write(&from, "export const a = 1;").unwrap();
+
+        let mut tx = RefactoringTransaction::new(TransactionMode::Execute);
+        tx.add_move(from.clone(), to.clone(), "export const a = 1;".to_string())
+            .unwrap();
+        tx.commit().unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "export const a = 1;");
+
+        tx.rollback().unwrap();
+        assert!(!to.exists());
+        assert_eq!(fs::read_to_string(&from).unwrap(), "export const a = 1;");
+    }
 }
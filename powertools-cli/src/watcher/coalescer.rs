@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::ReindexRequest;
+
+/// Coalesces reindex requests so each (root, language) pair reindexes at most
+/// once per interval, no matter how requests for different languages interleave
+#[derive(Debug)]
+pub struct ReindexCoalescer {
+    interval: Duration,
+    /// Requests waiting to run, in arrival order
+    pending: Vec<ReindexRequest>,
+    last_reindex: HashMap<ReindexRequest, Instant>,
+}
+
+impl ReindexCoalescer {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            pending: Vec::new(),
+            last_reindex: HashMap::new(),
+        }
+    }
+
+    /// Mark a request as pending (duplicates collapse into one)
+    pub fn record(&mut self, request: ReindexRequest) {
+        if !self.pending.contains(&request) {
+            self.pending.push(request);
+        }
+    }
+
+    /// Check if anything is waiting to run
+    #[allow(dead_code)]
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Take every pending request whose interval has elapsed, marking it as reindexed at `now`
+    pub fn take_due(&mut self, now: Instant) -> Vec<ReindexRequest> {
+        let mut due = Vec::new();
+        let mut still_pending = Vec::new();
+
+        for request in self.pending.drain(..) {
+            let ready = self
+                .last_reindex
+                .get(&request)
+                .map_or(true, |last| now.saturating_duration_since(*last) >= self.interval);
+
+            if ready {
+                self.last_reindex.insert(request.clone(), now);
+                due.push(request);
+            } else {
+                still_pending.push(request);
+            }
+        }
+
+        self.pending = still_pending;
+        due
+    }
+
+    /// How long until the earliest pending request becomes due
+    pub fn next_due(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .iter()
+            .map(|request| match self.last_reindex.get(request) {
+                Some(last) => (*last + self.interval).saturating_duration_since(now),
+                None => Duration::ZERO,
+            })
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Language;
+    use std::path::PathBuf;
+
+    fn request(language: Language) -> ReindexRequest {
+        ReindexRequest {
+            root: PathBuf::from("/project"),
+            language,
+        }
+    }
+
+    #[test]
+    fn test_interleaved_languages_reindex_once_each() {
+        let mut coalescer = ReindexCoalescer::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+
+        // Codegen step touching .ts and .rs files alternately
+        for _ in 0..5 {
+            coalescer.record(request(Language::TypeScript));
+            coalescer.record(request(Language::Rust));
+        }
+
+        let due = coalescer.take_due(t0);
+        assert_eq!(due, vec![request(Language::TypeScript), request(Language::Rust)]);
+        assert!(!coalescer.has_pending());
+
+        // More interleaved events inside the interval are held back
+        coalescer.record(request(Language::Rust));
+        coalescer.record(request(Language::TypeScript));
+        coalescer.record(request(Language::Rust));
+        assert!(coalescer.take_due(t0 + Duration::from_millis(500)).is_empty());
+        assert_eq!(
+            coalescer.next_due(t0 + Duration::from_millis(500)),
+            Some(Duration::from_millis(1500))
+        );
+
+        // ...and run exactly once per language when the interval elapses
+        let due = coalescer.take_due(t0 + Duration::from_secs(2));
+        assert_eq!(due, vec![request(Language::Rust), request(Language::TypeScript)]);
+        assert!(!coalescer.has_pending());
+        assert_eq!(coalescer.next_due(t0 + Duration::from_secs(2)), None);
+    }
+
+    #[test]
+    fn test_languages_have_independent_intervals() {
+        let mut coalescer = ReindexCoalescer::new(Duration::from_secs(2));
+        let t0 = Instant::now();
+
+        coalescer.record(request(Language::Rust));
+        assert_eq!(coalescer.take_due(t0), vec![request(Language::Rust)]);
+
+        // Python hasn't been reindexed yet, so it runs right away even though Rust just did
+        coalescer.record(request(Language::Rust));
+        coalescer.record(request(Language::Python));
+        let due = coalescer.take_due(t0 + Duration::from_millis(100));
+        assert_eq!(due, vec![request(Language::Python)]);
+        assert!(coalescer.has_pending());
+    }
+}
@@ -1,5 +1,8 @@
-use std::path::Path;
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
 use crate::core::Language;
+use crate::indexers::IndexFilter;
 
 /// Check if a path should be ignored by the watcher
 pub fn should_ignore(path: &Path) -> bool {
@@ -11,6 +14,7 @@ pub fn should_ignore(path: &Path) -> bool {
         "target/",
         "node_modules/",
         ".scip",
+        ".powertools/",
         "dist/",
         "build/",
         ".next/",
@@ -44,6 +48,7 @@ pub fn detect_language_from_path(path: &Path) -> Option<Language> {
             "py" | "pyi" => Some(Language::Python),
             "cpp" | "cc" | "cxx" | "hpp" | "hxx" | "h" => Some(Language::Cpp),
             "c" => Some(Language::C),
+            "rb" | "rake" | "gemspec" => Some(Language::Ruby),
             _ => None,
         })
 }
@@ -53,6 +58,102 @@ pub fn is_relevant_file(path: &Path) -> bool {
     !should_ignore(path) && detect_language_from_path(path).is_some()
 }
 
+/// Ignore rules for a set of watched roots
+///
+/// Combines the built-in ignore list with user-supplied globs, the include/exclude
+/// filters `powertools index` saved for each root and, optionally, each root's `.gitignore`.
+#[derive(Debug, Default)]
+pub struct WatchFilter {
+    roots: Vec<PathBuf>,
+    extra_patterns: Vec<glob::Pattern>,
+    gitignores: Vec<Gitignore>,
+    /// Saved index filter for each root, in the same order as `roots`
+    index_filters: Vec<IndexFilter>,
+}
+
+impl WatchFilter {
+    /// Build a filter for the given roots
+    pub fn new(roots: &[PathBuf], ignore_globs: &[String], respect_gitignore: bool) -> Result<Self> {
+        let extra_patterns = ignore_globs
+            .iter()
+            .map(|g| glob::Pattern::new(g).with_context(|| format!("Invalid ignore pattern: {}", g)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut gitignores = Vec::new();
+        if respect_gitignore {
+            for root in roots {
+                let mut builder = GitignoreBuilder::new(root);
+                let gitignore_path = root.join(".gitignore");
+                if gitignore_path.exists() {
+                    if let Some(e) = builder.add(&gitignore_path) {
+                        return Err(e).context("Failed to parse .gitignore");
+                    }
+                }
+                gitignores.push(builder.build().context("Failed to build gitignore matcher")?);
+            }
+        }
+
+        let index_filters = roots
+            .iter()
+            .map(|root| IndexFilter::load(root))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            roots: roots.to_vec(),
+            extra_patterns,
+            gitignores,
+            index_filters,
+        })
+    }
+
+    /// Find the watched root that contains a path
+    pub fn root_for(&self, path: &Path) -> Option<&PathBuf> {
+        // Prefer the most specific root when roots are nested
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+    }
+
+    /// Check if a path is ignored by the built-in list, extra globs, or `.gitignore`
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if should_ignore(path) {
+            return true;
+        }
+
+        let root = self.root_for(path);
+        let relative = root
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+
+        // Files left out of the index don't need to trigger re-indexing
+        let index_filter = root
+            .and_then(|root| self.roots.iter().position(|r| r == root))
+            .and_then(|i| self.index_filters.get(i));
+        if index_filter.is_some_and(|filter| !filter.allows(relative)) {
+            return true;
+        }
+
+        if self
+            .extra_patterns
+            .iter()
+            .any(|p| p.matches_path(relative) || p.matches_path(path))
+        {
+            return true;
+        }
+
+        self.gitignores.iter().any(|gi| {
+            path.starts_with(gi.path())
+                && gi.matched_path_or_any_parents(path, false).is_ignore()
+        })
+    }
+
+    /// Check if a path is relevant for watching under these rules
+    pub fn is_relevant_file(&self, path: &Path) -> bool {
+        !self.is_ignored(path) && detect_language_from_path(path).is_some()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,6 +182,14 @@ mod tests {
             detect_language_from_path(Path::new("test.py")),
             Some(Language::Python)
         );
+        assert_eq!(
+            detect_language_from_path(Path::new("lib/tasks/db.rake")),
+            Some(Language::Ruby)
+        );
+        assert_eq!(
+            detect_language_from_path(Path::new("app.gemspec")),
+            Some(Language::Ruby)
+        );
         assert_eq!(detect_language_from_path(Path::new("README.md")), None);
     }
 
@@ -88,7 +197,55 @@ mod tests {
     fn test_is_relevant_file() {
         assert!(is_relevant_file(Path::new("src/main.rs")));
         assert!(is_relevant_file(Path::new("app.ts")));
+        assert!(is_relevant_file(Path::new("app/models/user.rb")));
         assert!(!is_relevant_file(Path::new("README.md")));
         assert!(!is_relevant_file(Path::new("target/debug/main.rs")));
     }
+
+    #[test]
+    fn test_watch_filter_extra_globs() {
+        let roots = vec![PathBuf::from("/project")];
+        let filter = WatchFilter::new(&roots, &["generated/**".to_string()], false).unwrap();
+
+        assert!(filter.is_relevant_file(Path::new("/project/src/main.rs")));
+        assert!(!filter.is_relevant_file(Path::new("/project/generated/api.rs")));
+        assert!(!filter.is_relevant_file(Path::new("/project/target/debug/main.rs")));
+    }
+
+    #[test]
+    fn test_watch_filter_gitignore() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        std::fs::write(root.join(".gitignore"), "out/\n*.gen.ts\n").unwrap();
+
+        let filter = WatchFilter::new(&[root.clone()], &[], true).unwrap();
+        assert!(filter.is_ignored(&root.join("out/lib.rs")));
+        assert!(filter.is_ignored(&root.join("src/api.gen.ts")));
+        assert!(!filter.is_ignored(&root.join("src/api.ts")));
+
+        let no_gitignore = WatchFilter::new(&[root.clone()], &[], false).unwrap();
+        assert!(!no_gitignore.is_ignored(&root.join("out/lib.rs")));
+    }
+
+    #[test]
+    fn test_watch_filter_honors_index_excludes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_path_buf();
+        IndexFilter::new(&[], &["generated/**".to_string()]).unwrap().save(&root).unwrap();
+
+        let filter = WatchFilter::new(&[root.clone()], &[], false).unwrap();
+        assert!(filter.is_ignored(&root.join("generated/api.ts")));
+        assert!(!filter.is_relevant_file(&root.join("generated/api.ts")));
+        assert!(filter.is_relevant_file(&root.join("src/api.ts")));
+    }
+
+    #[test]
+    fn test_watch_filter_root_for() {
+        let roots = vec![PathBuf::from("/a"), PathBuf::from("/a/nested"), PathBuf::from("/b")];
+        let filter = WatchFilter::new(&roots, &[], false).unwrap();
+
+        assert_eq!(filter.root_for(Path::new("/a/x.rs")), Some(&PathBuf::from("/a")));
+        assert_eq!(filter.root_for(Path::new("/a/nested/x.rs")), Some(&PathBuf::from("/a/nested")));
+        assert_eq!(filter.root_for(Path::new("/c/x.rs")), None);
+    }
 }
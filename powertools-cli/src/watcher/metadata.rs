@@ -109,6 +109,7 @@ pub fn check_staleness(project_root: &Path) -> Result<Option<String>> {
         "index.python.scip",
         "index.rust.scip",
         "index.cpp.scip",
+        "index.ruby.scip",
     ];
 
     for index_file in &index_files {
@@ -1,46 +1,138 @@
+mod coalescer;
 mod filters;
 mod metadata;
 
-pub use filters::{detect_language_from_path, is_relevant_file};
+pub use coalescer::ReindexCoalescer;
+pub use filters::{detect_language_from_path, WatchFilter};
 pub use metadata::IndexMetadata;
 
 use anyhow::{Context, Result};
 use notify_debouncer_full::{
     new_debouncer,
-    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    notify::{
+        event::{ModifyKind, RenameMode},
+        EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    },
     DebounceEventResult, Debouncer, FileIdMap,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, error, info, warn};
 
+use crate::analyzers::tree_cache;
 use crate::core::Language;
-use crate::indexers::ScipIndexer;
+use crate::indexers::{IndexInProgress, ScipIndexer};
+
+/// A request to re-index one language within one watched root
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReindexRequest {
+    pub root: PathBuf,
+    pub language: Language,
+}
+
+/// A change observed by the watcher, after filtering
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchMessage {
+    /// A relevant file was created or modified
+    Changed(ReindexRequest),
+    /// A relevant file was deleted or moved away; its symbols are stale
+    Removed(ReindexRequest, PathBuf),
+}
+
+impl WatchMessage {
+    fn request(&self) -> &ReindexRequest {
+        match self {
+            WatchMessage::Changed(request) | WatchMessage::Removed(request, _) => request,
+        }
+    }
+}
+
+/// Outcome of a single re-index, fanned out to subscribers
+#[derive(Debug, Clone)]
+pub struct ReindexEvent {
+    pub language: Language,
+    pub root: PathBuf,
+    /// Index file that was written (None if the re-index failed)
+    pub index_path: Option<PathBuf>,
+    pub duration: Duration,
+    pub finished_at: SystemTime,
+    /// Ok on success, or the error message on failure
+    pub result: Result<(), String>,
+}
+
+impl ReindexEvent {
+    pub fn is_success(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Capacity of the reindex event channel; slow subscribers see `Lagged` past this
+const EVENT_CHANNEL_CAPACITY: usize = 64;
 
 /// File watcher that triggers automatic re-indexing
 pub struct FileWatcher {
-    project_root: PathBuf,
+    roots: Vec<PathBuf>,
+    ignore_patterns: Vec<String>,
+    respect_gitignore: bool,
     debouncer: Option<Debouncer<RecommendedWatcher, FileIdMap>>,
     is_running: Arc<AtomicBool>,
-    reindex_tx: mpsc::UnboundedSender<Language>,
+    reindex_tx: mpsc::UnboundedSender<WatchMessage>,
+    events_tx: broadcast::Sender<ReindexEvent>,
+    last_event: Arc<Mutex<Option<ReindexEvent>>>,
 }
 
 impl FileWatcher {
-    /// Create a new file watcher
+    /// Create a new file watcher for a single project root
     pub fn new(project_root: PathBuf) -> Result<Self> {
+        Self::with_roots(vec![project_root])
+    }
+
+    /// Create a new file watcher for several project roots
+    pub fn with_roots(roots: Vec<PathBuf>) -> Result<Self> {
+        if roots.is_empty() {
+            anyhow::bail!("File watcher needs at least one root");
+        }
+
         let (reindex_tx, _reindex_rx) = mpsc::unbounded_channel();
+        let (events_tx, _events_rx) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
 
         Ok(Self {
-            project_root,
+            roots,
+            ignore_patterns: Vec::new(),
+            respect_gitignore: true,
             debouncer: None,
             is_running: Arc::new(AtomicBool::new(false)),
             reindex_tx,
+            events_tx,
+            last_event: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// Subscribe to re-index events
+    ///
+    /// Each completed re-index (successful or not) is sent to every subscriber.
+    pub fn subscribe(&self) -> broadcast::Receiver<ReindexEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// The most recent re-index event, if any
+    pub fn last_event(&self) -> Option<ReindexEvent> {
+        self.last_event.lock().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Add extra glob patterns to ignore (relative to each root)
+    pub fn set_ignore_patterns(&mut self, patterns: Vec<String>) {
+        self.ignore_patterns = patterns;
+    }
+
+    /// Whether to honor each root's `.gitignore` (default: true)
+    pub fn set_respect_gitignore(&mut self, respect_gitignore: bool) {
+        self.respect_gitignore = respect_gitignore;
+    }
+
     /// Start watching for file changes
     pub async fn start(
         &mut self,
@@ -54,14 +146,18 @@ impl FileWatcher {
 
         info!(
             "Starting file watcher for {} with {:?} debounce",
-            self.project_root.display(),
+            self.roots
+                .iter()
+                .map(|r| r.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
             debounce_duration
         );
 
         let (reindex_tx, mut reindex_rx) = mpsc::unbounded_channel();
         self.reindex_tx = reindex_tx.clone();
 
-        let project_root = self.project_root.clone();
+        let filter = WatchFilter::new(&self.roots, &self.ignore_patterns, self.respect_gitignore)?;
 
         // Create the debounced file watcher
         let mut debouncer = new_debouncer(
@@ -71,14 +167,10 @@ impl FileWatcher {
                 match result {
                     Ok(events) => {
                         for event in events {
-                            for path in &event.paths {
-                                if is_relevant_file(path) {
-                                    if let Some(lang) = detect_language_from_path(path) {
-                                        debug!("File change detected: {} ({:?})", path.display(), lang);
-                                        if let Err(e) = reindex_tx.send(lang) {
-                                            error!("Failed to send reindex request: {}", e);
-                                        }
-                                    }
+                            for message in classify_event(&event.kind, &event.paths, &filter) {
+                                debug!("File change detected: {:?}", message);
+                                if let Err(e) = reindex_tx.send(message) {
+                                    error!("Failed to send reindex request: {}", e);
                                 }
                             }
                         }
@@ -93,51 +185,92 @@ impl FileWatcher {
         )
         .context("Failed to create file watcher")?;
 
-        // Watch the project root recursively
-        debouncer
-            .watcher()
-            .watch(&self.project_root, RecursiveMode::Recursive)
-            .context("Failed to start watching project directory")?;
+        // Watch each root recursively
+        for root in &self.roots {
+            debouncer
+                .watcher()
+                .watch(root, RecursiveMode::Recursive)
+                .with_context(|| format!("Failed to start watching {}", root.display()))?;
+        }
 
         self.debouncer = Some(debouncer);
         self.is_running.store(true, Ordering::Relaxed);
 
         // Spawn task to handle reindex requests
-        let project_root_clone = project_root.clone();
+        let events_tx = self.events_tx.clone();
+        let last_event = self.last_event.clone();
         tokio::spawn(async move {
-            let mut last_reindex: Option<(Language, std::time::Instant)> = None;
-            let min_reindex_interval = Duration::from_secs(1);
-
-            while let Some(language) = reindex_rx.recv().await {
-                // Deduplicate rapid requests for the same language
-                if let Some((last_lang, last_time)) = last_reindex {
-                    if last_lang == language && last_time.elapsed() < min_reindex_interval {
-                        debug!("Skipping duplicate reindex request for {:?}", language);
-                        continue;
-                    }
+            // Each language reindexes at most once per debounce interval
+            let mut coalescer = ReindexCoalescer::new(debounce_duration);
+
+            loop {
+                let mut messages = Vec::new();
+                let wait = coalescer.next_due(Instant::now());
+                tokio::select! {
+                    message = reindex_rx.recv() => match message {
+                        Some(message) => messages.push(message),
+                        None => break,
+                    },
+                    _ = tokio::time::sleep(wait.unwrap_or_default()), if wait.is_some() => {}
+                }
+
+                // Fold in everything already queued so a burst coalesces together
+                while let Ok(message) = reindex_rx.try_recv() {
+                    messages.push(message);
+                }
+
+                // Drop stale documents right away so queries stop returning
+                // removed files even before the (slower) reindex finishes
+                prune_removed_files(&messages);
+
+                for message in messages {
+                    coalescer.record(message.request().clone());
                 }
 
-                info!("Re-indexing {:?}...", language);
-                let mut indexer = ScipIndexer::new(project_root_clone.clone());
-                indexer.set_auto_install(auto_install);
+                for request in coalescer.take_due(Instant::now()) {
+                    let language = request.language;
+                    let project_root = request.root.clone();
+
+                    info!("Re-indexing {:?} in {}...", language, project_root.display());
+                    let mut indexer = ScipIndexer::new(project_root.clone());
+                    indexer.set_auto_install(auto_install);
+                    indexer.set_wait_for_lock(false);
 
-                match indexer.reindex_language(language) {
-                    Ok(index_path) => {
-                        info!("✓ Re-indexed {:?}: {}", language, index_path.display());
+                    let started = Instant::now();
+                    let (index_path, result) = match indexer.reindex_language(language) {
+                        // A manual `index` (or another watcher) is writing; try again next interval
+                        Err(e) if e.is::<IndexInProgress>() => {
+                            info!("Skipping {:?} re-index: {}", language, e);
+                            coalescer.record(request);
+                            continue;
+                        }
+                        Ok(index_path) => {
+                            info!("✓ Re-indexed {:?}: {}", language, index_path.display());
 
-                        // Generate and save metadata
-                        if let Ok(metadata) = IndexMetadata::generate(&project_root_clone) {
-                            if let Err(e) = metadata.save(&index_path) {
-                                warn!("Failed to save metadata: {}", e);
+                            // Generate and save metadata
+                            if let Ok(metadata) = IndexMetadata::generate(&project_root) {
+                                if let Err(e) = metadata.save(&index_path) {
+                                    warn!("Failed to save metadata: {}", e);
+                                }
                             }
+                            (Some(index_path), Ok(()))
                         }
-                    }
-                    Err(e) => {
-                        error!("Failed to re-index {:?}: {}", language, e);
-                    }
-                }
+                        Err(e) => {
+                            error!("Failed to re-index {:?}: {}", language, e);
+                            (None, Err(e.to_string()))
+                        }
+                    };
 
-                last_reindex = Some((language, std::time::Instant::now()));
+                    let event = ReindexEvent {
+                        language,
+                        root: project_root,
+                        index_path,
+                        duration: started.elapsed(),
+                        finished_at: SystemTime::now(),
+                        result,
+                    };
+                    publish_event(&events_tx, &last_event, event);
+                }
             }
         });
 
@@ -162,20 +295,116 @@ impl FileWatcher {
     }
 
     /// Get watcher status information
-    #[allow(dead_code)]
     pub fn status(&self) -> WatcherStatus {
         WatcherStatus {
             is_running: self.is_running(),
-            project_root: self.project_root.clone(),
+            roots: self.roots.clone(),
+            ignore_patterns: self.ignore_patterns.clone(),
+            last_reindex: self.last_event(),
+        }
+    }
+}
+
+/// Record an event as the latest and fan it out to subscribers
+fn publish_event(
+    events_tx: &broadcast::Sender<ReindexEvent>,
+    last_event: &Mutex<Option<ReindexEvent>>,
+    event: ReindexEvent,
+) {
+    if let Ok(mut guard) = last_event.lock() {
+        *guard = Some(event.clone());
+    }
+    // Sending only fails when nobody is subscribed, which is fine
+    let _ = events_tx.send(event);
+}
+
+/// Remove documents for deleted/moved files from their language's index
+///
+/// Their cached parse trees are dropped too.
+fn prune_removed_files(messages: &[WatchMessage]) {
+    let mut removed: Vec<(&ReindexRequest, Vec<PathBuf>)> = Vec::new();
+    for message in messages {
+        if let WatchMessage::Removed(request, path) = message {
+            if let Some(cache) = tree_cache::shared() {
+                cache.invalidate(path);
+            }
+            match removed.iter_mut().find(|(r, _)| *r == request) {
+                Some((_, paths)) => paths.push(path.clone()),
+                None => removed.push((request, vec![path.clone()])),
+            }
         }
     }
+
+    for (request, paths) in removed {
+        let mut indexer = ScipIndexer::new(request.root.clone());
+        indexer.set_wait_for_lock(false);
+        match indexer.remove_documents(request.language, &paths) {
+            Ok(0) => {}
+            // The run holding the lock rewrites the index anyway
+            Err(e) if e.is::<IndexInProgress>() => info!("Skipping {:?} prune: {}", request.language, e),
+            Ok(count) => info!("Removed {} stale {:?} document(s) from index", count, request.language),
+            Err(e) => warn!("Failed to prune {:?} index: {}", request.language, e),
+        }
+    }
+}
+
+/// Turn a raw notify event into watch messages
+///
+/// Deletions and the source side of a rename become `Removed`; everything else
+/// relevant becomes `Changed`.
+pub fn classify_event(kind: &EventKind, paths: &[PathBuf], filter: &WatchFilter) -> Vec<WatchMessage> {
+    let (removed, changed): (&[PathBuf], &[PathBuf]) = match kind {
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => (paths, &[]),
+        // Rename with both sides known: paths are [from, to]
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() >= 2 => {
+            paths.split_at(1)
+        }
+        _ => (&[], paths),
+    };
+
+    let mut messages: Vec<WatchMessage> = removed
+        .iter()
+        .filter_map(|path| {
+            request_for_path(path, filter).map(|request| WatchMessage::Removed(request, path.clone()))
+        })
+        .collect();
+    messages.extend(
+        reindex_requests_for_paths(changed, filter)
+            .into_iter()
+            .map(WatchMessage::Changed),
+    );
+    messages
+}
+
+/// Map changed paths to reindex requests, dropping ignored and irrelevant files
+pub fn reindex_requests_for_paths(paths: &[PathBuf], filter: &WatchFilter) -> Vec<ReindexRequest> {
+    paths
+        .iter()
+        .filter_map(|path| request_for_path(path, filter))
+        .collect()
+}
+
+fn request_for_path(path: &Path, filter: &WatchFilter) -> Option<ReindexRequest> {
+    if !filter.is_relevant_file(path) {
+        return None;
+    }
+
+    let root = filter.root_for(path)?;
+    let language = detect_language_from_path(path)?;
+
+    Some(ReindexRequest {
+        root: root.clone(),
+        language,
+    })
 }
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct WatcherStatus {
     pub is_running: bool,
-    pub project_root: PathBuf,
+    pub roots: Vec<PathBuf>,
+    pub ignore_patterns: Vec<String>,
+    pub last_reindex: Option<ReindexEvent>,
 }
 
 impl Drop for FileWatcher {
@@ -185,3 +414,127 @@ impl Drop for FileWatcher {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignored_path_produces_no_reindex() {
+        let roots = vec![PathBuf::from("/project")];
+        let filter = WatchFilter::new(&roots, &["vendor/**".to_string()], false).unwrap();
+
+        let paths = vec![
+            PathBuf::from("/project/vendor/lib.rs"),
+            PathBuf::from("/project/target/debug/build.rs"),
+            PathBuf::from("/project/node_modules/pkg/index.ts"),
+        ];
+
+        assert!(reindex_requests_for_paths(&paths, &filter).is_empty());
+    }
+
+    #[test]
+    fn test_reindex_request_uses_owning_root() {
+        let roots = vec![PathBuf::from("/frontend"), PathBuf::from("/backend")];
+        let filter = WatchFilter::new(&roots, &[], false).unwrap();
+
+        let paths = vec![
+            PathBuf::from("/frontend/src/app.ts"),
+            PathBuf::from("/backend/src/main.rs"),
+            PathBuf::from("/elsewhere/main.rs"),
+        ];
+
+        let requests = reindex_requests_for_paths(&paths, &filter);
+        assert_eq!(
+            requests,
+            vec![
+                ReindexRequest { root: PathBuf::from("/frontend"), language: Language::TypeScript },
+                ReindexRequest { root: PathBuf::from("/backend"), language: Language::Rust },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subscribers_receive_reindex_events() {
+        let watcher = FileWatcher::new(PathBuf::from("/project")).unwrap();
+        let mut rx1 = watcher.subscribe();
+        let mut rx2 = watcher.subscribe();
+        assert!(watcher.last_event().is_none());
+
+        let event = ReindexEvent {
+            language: Language::Rust,
+            root: PathBuf::from("/project"),
+            index_path: None,
+            duration: Duration::from_millis(5),
+            finished_at: SystemTime::now(),
+            result: Err("rust-analyzer not found".to_string()),
+        };
+        publish_event(&watcher.events_tx, &watcher.last_event, event);
+
+        for rx in [&mut rx1, &mut rx2] {
+            let received = rx.try_recv().unwrap();
+            assert_eq!(received.language, Language::Rust);
+            assert!(!received.is_success());
+        }
+        assert!(watcher.last_event().is_some());
+        assert!(watcher.status().last_reindex.is_some());
+    }
+
+    #[test]
+    fn test_classify_rename_event() {
+        let roots = vec![PathBuf::from("/project")];
+        let filter = WatchFilter::new(&roots, &[], false).unwrap();
+        let rust = ReindexRequest { root: PathBuf::from("/project"), language: Language::Rust };
+
+        let paths = vec![
+            PathBuf::from("/project/src/old.rs"),
+            PathBuf::from("/project/src/new.rs"),
+        ];
+        let messages = classify_event(
+            &EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &paths,
+            &filter,
+        );
+        assert_eq!(
+            messages,
+            vec![
+                WatchMessage::Removed(rust.clone(), PathBuf::from("/project/src/old.rs")),
+                WatchMessage::Changed(rust.clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_remove_and_create_events() {
+        use notify_debouncer_full::notify::event::{CreateKind, RemoveKind};
+
+        let roots = vec![PathBuf::from("/project")];
+        let filter = WatchFilter::new(&roots, &[], false).unwrap();
+        let python = ReindexRequest { root: PathBuf::from("/project"), language: Language::Python };
+
+        let removed = classify_event(
+            &EventKind::Remove(RemoveKind::File),
+            &[PathBuf::from("/project/app.py")],
+            &filter,
+        );
+        assert_eq!(
+            removed,
+            vec![WatchMessage::Removed(python.clone(), PathBuf::from("/project/app.py"))]
+        );
+
+        let created = classify_event(
+            &EventKind::Create(CreateKind::File),
+            &[PathBuf::from("/project/app.py"), PathBuf::from("/project/README.md")],
+            &filter,
+        );
+        assert_eq!(created, vec![WatchMessage::Changed(python)]);
+
+        // Moving an ignored file produces nothing
+        let ignored = classify_event(
+            &EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &[PathBuf::from("/project/target/a.rs"), PathBuf::from("/project/target/b.rs")],
+            &filter,
+        );
+        assert!(ignored.is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+module Billing
+  class Invoice < ApplicationRecord
+    def self.overdue
+      where("due_at < ?", Time.current)
+    end
+
+    def total(tax_rate = 0.0, currency: "USD")
+      subtotal * (1 + tax_rate)
+    end
+
+    private
+
+    def subtotal
+      line_items.sum(&:amount)
+    end
+
+    class LineItem
+      def amount
+        0
+      end
+    end
+  end
+
+  module Reports
+    def summary; end
+  end
+end
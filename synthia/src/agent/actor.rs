@@ -1,26 +1,37 @@
 use super::messages::{Command, UIUpdate};
+use super::prompt::{build_system_prompt, DEFAULT_SYSTEM_PROMPT};
+use crate::config::AutoSave;
 use crate::context_manager::ContextManager;
-use crate::jsonl_logger::{JsonlLogger, JsonlEntry, RequestLog, ResponseLog, TokenUsageLog, MessageLog};
+use crate::jsonl_logger::{JsonlLogger, JsonlEntry, LogEvent, RequestLog, ResponseLog, TokenUsageLog, MessageLog};
 use crate::llm::json_parser::JsonParser;
+use crate::llm::thinking::split_think_tags;
 use crate::llm::{GenerationConfig, LLMProvider, StreamEvent};
 use crate::project::{detect_project_root, extract_project_name, normalize_project_name};
 use crate::session::Session;
 use crate::tools::registry::ToolRegistry;
+use crate::tools::todo::SharedTodoList;
+use crate::tools::ToolResult;
 use crate::types::{ContentBlock, Message, Role, StopReason, TokenUsage};
 use anyhow::Result;
 use chrono::Utc;
 use futures::future::join_all;
 use futures::StreamExt;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio_util::sync::CancellationToken;
 
-// System prompt is loaded from markdown file at compile time
-// To support different modes in the future (CLI vs non-interactive vs sub-agent),
-// create additional prompt files (e.g., system_prompt_subagent.md) and select
-// based on initialization parameters
-const SYSTEM_PROMPT: &str = include_str!("../../prompts/system_prompt.md");
+/// Outcome of a single tool call: (id, name, input, result, duration_ms)
+type ToolExecution = (String, String, Value, Result<ToolResult>, u64);
+
+/// A named provider and generation settings that can be switched to at runtime
+#[derive(Clone)]
+pub struct LlmProfile {
+    pub provider: Arc<dyn LLMProvider>,
+    pub config: GenerationConfig,
+}
 
 // Safeguard limits to prevent runaway code generation
 const MAX_WRITES_PER_FILE: usize = 2; // Prevent write loops to same file
@@ -30,27 +41,38 @@ pub struct AgentActor {
     tool_registry: Arc<ToolRegistry>,
     conversation: Vec<Message>,
     project_context: Option<String>,
+    system_prompt: String, // Base prompt; its tool list is filled in from the registry
+    prompt_fragment: Option<String>, // Project-specific text appended to the system prompt
     context_manager: ContextManager,
     config: GenerationConfig,
     ui_tx: Sender<UIUpdate>,
     cmd_rx: Receiver<Command>,
     session: Session,
-    auto_save: bool,
+    autosave: AutoSave,
+    autosave_interval: Duration, // Minimum time between throttled autosaves
+    last_autosave: Option<Instant>,
     cancel_requested: bool,
+    new_session_requested: bool, // NewSession arrived mid-generation; start it once canceled
     tool_call_count: usize, // Track tool calls in current turn
     json_parser: JsonParser, // For robust JSON parsing
     jsonl_logger: JsonlLogger, // For logging request/response turns
     file_write_counts: HashMap<String, usize>, // Track writes per file per turn
+    llm_profiles: BTreeMap<String, LlmProfile>, // Profiles selectable via SetLlmProfile
+    todos: SharedTodoList, // Written by the todo tool, persisted in the session
 }
 
 impl AgentActor {
     /// Create the system prompt that teaches the model to use tools proactively
-    /// The prompt is loaded from prompts/system_prompt.md at compile time
+    /// The tool list comes from the registry, so it matches the tools actually available
     fn create_system_prompt(&self) -> Message {
         Message {
             role: Role::System,
             content: vec![ContentBlock::Text {
-                text: SYSTEM_PROMPT.to_string(),
+                text: build_system_prompt(
+                    &self.system_prompt,
+                    &self.tool_registry.definitions(),
+                    self.prompt_fragment.as_deref(),
+                ),
             }],
         }
     }
@@ -82,17 +104,24 @@ impl AgentActor {
             tool_registry: tool_registry.clone(),
             conversation: Vec::new(),
             project_context: project_context.clone(),
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            prompt_fragment: None,
             context_manager: ContextManager::new(llm_provider.clone()),
             config: config.clone(),
             ui_tx: ui_tx.clone(),
             cmd_rx,
             session,
-            auto_save: true,
+            autosave: AutoSave::default(),
+            autosave_interval: Duration::ZERO,
+            last_autosave: None,
             cancel_requested: false,
+            new_session_requested: false,
             tool_call_count: 0,
             json_parser: JsonParser::new(),
             jsonl_logger: JsonlLogger::new("temp").unwrap(),
             file_write_counts: HashMap::new(),
+            llm_profiles: BTreeMap::new(),
+            todos: SharedTodoList::default(),
         };
 
         // Build conversation with system messages
@@ -145,17 +174,24 @@ impl AgentActor {
             tool_registry: tool_registry.clone(),
             conversation: Vec::new(),
             project_context: project_context.clone(),
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            prompt_fragment: None,
             context_manager: ContextManager::new(llm_provider.clone()),
             config: config.clone(),
             ui_tx: ui_tx.clone(),
             cmd_rx,
             session: Session::new(config.model.clone()),
-            auto_save: true,
+            autosave: AutoSave::default(),
+            autosave_interval: Duration::ZERO,
+            last_autosave: None,
             cancel_requested: false,
+            new_session_requested: false,
             tool_call_count: 0,
             json_parser: JsonParser::new(),
             jsonl_logger: JsonlLogger::new("temp").unwrap(),
             file_write_counts: HashMap::new(),
+            llm_profiles: BTreeMap::new(),
+            todos: SharedTodoList::default(),
         };
 
         let mut conversation = session.messages.clone();
@@ -198,20 +234,126 @@ impl AgentActor {
             tool_registry,
             conversation,
             project_context,
+            system_prompt: temp.system_prompt,
+            prompt_fragment: temp.prompt_fragment,
             context_manager,
             config,
             ui_tx: temp.ui_tx,
             cmd_rx: temp.cmd_rx,
             session,
-            auto_save: true,
+            autosave: AutoSave::default(),
+            autosave_interval: Duration::ZERO,
+            last_autosave: None,
             cancel_requested: false,
+            new_session_requested: false,
             tool_call_count: 0,
             json_parser: JsonParser::new(),
             jsonl_logger,
             file_write_counts: HashMap::new(),
+            llm_profiles: BTreeMap::new(),
+            todos: SharedTodoList::default(),
         }
     }
 
+    /// Share the todo tool's list so it's saved with (and restored from) the session
+    /// The current session's todos are loaded into the list
+    pub fn set_todo_list(&mut self, todos: SharedTodoList) {
+        *todos.lock().unwrap() = self.session.todos.clone();
+        self.todos = todos;
+    }
+
+    /// The image a successful `read` returned a placeholder for, when the model has vision
+    async fn read_image_block(&self, name: &str, input: &Value, tool_result: &ToolResult) -> Option<ContentBlock> {
+        if !self.config.vision
+            || name != "read"
+            || tool_result.is_error
+            || !tool_result.content.starts_with(crate::tools::read::IMAGE_PLACEHOLDER_PREFIX)
+        {
+            return None;
+        }
+
+        let path = crate::tools::expand_path(input.get("file_path")?.as_str()?).ok()?;
+        match crate::tools::read::load_image_block(&path).await {
+            Ok(block) => block,
+            Err(e) => {
+                tracing::warn!("Failed to attach image {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Copy the todo list into the session and show it in the UI
+    async fn sync_todos(&mut self) -> Result<()> {
+        let todos = self.todos.lock().unwrap().clone();
+        self.session.set_todos(todos.clone());
+        self.ui_tx.send(UIUpdate::TodosUpdated(todos)).await?;
+        Ok(())
+    }
+
+    /// Replace the todo list with the current session's (after a session switch)
+    async fn restore_todos(&mut self) -> Result<()> {
+        *self.todos.lock().unwrap() = self.session.todos.clone();
+        self.ui_tx
+            .send(UIUpdate::TodosUpdated(self.session.todos.clone()))
+            .await?;
+        Ok(())
+    }
+
+    /// Replace the built-in system prompt with `base` (None keeps it) and append the
+    /// project's `fragment`, updating the prompt already at the start of the conversation
+    pub fn set_system_prompt(&mut self, base: Option<String>, fragment: Option<String>) {
+        self.system_prompt = base.unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string());
+        self.prompt_fragment = fragment;
+
+        if !matches!(self.conversation.first(), Some(msg) if matches!(msg.role, Role::System)) {
+            return;
+        }
+        self.conversation[0] = self.create_system_prompt();
+        self.context_manager = ContextManager::new(self.llm_provider.clone());
+        self.context_manager.set_max_token_limit(self.config.context_window);
+        for msg in &self.conversation {
+            self.context_manager.add_message(msg.clone());
+        }
+    }
+
+    /// Register the LLM profiles the user can switch between
+    pub fn set_llm_profiles(&mut self, profiles: BTreeMap<String, LlmProfile>) {
+        self.llm_profiles = profiles;
+    }
+
+    /// Choose when the session is saved after a response; shutdown always saves
+    pub fn set_autosave(&mut self, mode: AutoSave, interval: Duration) {
+        self.autosave = mode;
+        self.autosave_interval = interval;
+    }
+
+    /// Save the session after a completed response, if the autosave setting allows it now
+    fn autosave_session(&mut self) {
+        let due = match self.autosave {
+            AutoSave::Off => false,
+            AutoSave::OnComplete => true,
+            AutoSave::Throttled => self
+                .last_autosave
+                .is_none_or(|saved| saved.elapsed() >= self.autosave_interval),
+        };
+        if !due {
+            return;
+        }
+
+        if let Err(e) = self.session.save() {
+            tracing::error!("Failed to auto-save session: {}", e);
+        } else {
+            tracing::debug!("Session auto-saved: {}", self.session.id);
+            self.last_autosave = Some(Instant::now());
+        }
+    }
+
+    /// Write turn entries and events with `logger` instead of the per-project log
+    #[allow(dead_code)]
+    pub fn set_jsonl_logger(&mut self, logger: JsonlLogger) {
+        self.jsonl_logger = logger;
+    }
+
     #[allow(dead_code)]
     pub fn session_id(&self) -> &str {
         &self.session.id
@@ -241,6 +383,8 @@ impl AgentActor {
                     let prefix = if *is_error { "[Tool Error]" } else { "[Tool Result]" };
                     Some(format!("{} {}", prefix, content))
                 }
+                ContentBlock::Thinking { text } => Some(format!("[Thinking] {}", text)),
+                ContentBlock::Image { media_type, .. } => Some(format!("[Image: {}]", media_type)),
             })
             .collect::<Vec<_>>()
             .join("\n");
@@ -303,12 +447,60 @@ impl AgentActor {
         }
     }
 
+    /// Record a structured event in the JSONL log
+    fn log_event(&mut self, event: LogEvent) {
+        if let Err(e) = self.jsonl_logger.log_event(event) {
+            tracing::error!("Failed to log event to JSONL: {}", e);
+        }
+    }
+
+    /// Record the model's reply and the tokens its request used
+    fn log_response_events(&mut self, content: &[ContentBlock], usage: &TokenUsage) {
+        let length = content
+            .iter()
+            .map(|block| match block {
+                ContentBlock::Text { text } => text.chars().count(),
+                _ => 0,
+            })
+            .sum();
+        let tool_calls = content
+            .iter()
+            .filter(|block| matches!(block, ContentBlock::ToolUse { .. }))
+            .count();
+        self.log_event(LogEvent::AssistantText { length, tool_calls });
+        self.log_event(LogEvent::TurnUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+        });
+    }
+
+    /// Record the context size and show it in the UI
+    /// Without reported usage (or when a server reports zeros) the size is estimated
+    async fn send_token_stats(&mut self, usage: Option<&TokenUsage>) {
+        match usage.filter(|u| u.input_tokens > 0 || u.output_tokens > 0) {
+            Some(usage) => self.context_manager.update_token_count(
+                usage.input_tokens as usize,
+                usage.output_tokens as usize,
+            ),
+            None => self.context_manager.estimate_token_count(),
+        }
+
+        let stats = self.context_manager.get_token_stats();
+        let _ = self.ui_tx.send(UIUpdate::TokenStatsUpdate(stats)).await;
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         tracing::info!("Agent actor starting with session: {}", self.session.id);
 
+        // Show the context size up front (non-zero when resuming a session)
+        self.send_token_stats(None).await;
+
         while let Some(cmd) = self.cmd_rx.recv().await {
             match cmd {
                 Command::SendMessage(text) => {
+                    self.log_event(LogEvent::UserMessage {
+                        length: text.chars().count(),
+                    });
                     let message = Message {
                         role: Role::User,
                         content: vec![ContentBlock::Text { text }],
@@ -328,6 +520,11 @@ impl AgentActor {
                             .send(UIUpdate::Error(format!("Agent error: {}", e)))
                             .await?;
                     }
+
+                    // The user asked for a new session while this turn was running
+                    if std::mem::take(&mut self.new_session_requested) {
+                        self.start_new_session().await?;
+                    }
                 }
                 Command::Cancel => {
                     tracing::info!("Cancellation requested");
@@ -355,36 +552,7 @@ impl AgentActor {
                             .await?;
                     }
                 }
-                Command::NewSession => {
-                    // Save current session first
-                    if let Err(e) = self.session.save() {
-                        tracing::error!("Failed to save current session: {}", e);
-                    }
-
-                    // Create new session
-                    self.session = Session::new(self.config.model.clone());
-                    self.conversation.clear();
-                    self.conversation.push(self.create_system_prompt()); // Add system prompt to new session
-                    if let Some(project_msg) = self.create_project_context_message() {
-                        self.conversation.push(project_msg);
-                    }
-
-                    // Reset context manager with new system prompts
-                    self.context_manager = ContextManager::new(self.llm_provider.clone());
-                    self.context_manager.set_max_token_limit(self.config.context_window);
-                    for msg in &self.conversation {
-                        self.context_manager.add_message(msg.clone());
-                    }
-
-                    // Tell UI to clear displayed conversation
-                    self.ui_tx.send(UIUpdate::ConversationCleared).await?;
-
-                    self.ui_tx
-                        .send(UIUpdate::SessionLoaded {
-                            session_id: self.session.id.clone(),
-                        })
-                        .await?;
-                }
+                Command::NewSession => self.start_new_session().await?,
                 Command::LoadSession(session_id) => {
                     match Session::load(&session_id) {
                         Ok(session) => {
@@ -402,6 +570,8 @@ impl AgentActor {
                             for message in &self.conversation {
                                 self.context_manager.add_message(message.clone());
                             }
+                            self.restore_todos().await?;
+                            self.send_token_stats(None).await;
 
                             self.ui_tx
                                 .send(UIUpdate::SessionLoaded {
@@ -430,6 +600,87 @@ impl AgentActor {
                         }
                     }
                 }
+                Command::ListSessionsTagged(tag) => {
+                    match crate::session::list_sessions_tagged(&tag) {
+                        Ok(sessions) if sessions.is_empty() => {
+                            self.ui_tx
+                                .send(UIUpdate::SystemMessage(format!("No sessions tagged \"{}\"", tag)))
+                                .await?;
+                        }
+                        Ok(sessions) => {
+                            self.ui_tx
+                                .send(UIUpdate::SessionList { sessions })
+                                .await?;
+                        }
+                        Err(e) => {
+                            self.ui_tx
+                                .send(UIUpdate::Error(format!("Failed to list sessions: {}", e)))
+                                .await?;
+                        }
+                    }
+                }
+                Command::AddSessionTag(tag) => {
+                    if self.session.add_tag(&tag) {
+                        self.save_tags().await?;
+                    }
+                }
+                Command::RemoveSessionTag(tag) => {
+                    if self.session.remove_tag(&tag) {
+                        self.save_tags().await?;
+                    }
+                }
+                Command::ForkSession(message_count) => {
+                    // Save the parent first so it stays intact on disk
+                    if let Err(e) = self.session.save() {
+                        tracing::error!("Failed to save current session: {}", e);
+                    }
+
+                    let fork = self.session.fork(message_count);
+                    if let Err(e) = fork.save() {
+                        self.ui_tx
+                            .send(UIUpdate::Error(format!("Failed to save forked session: {}", e)))
+                            .await?;
+                        continue;
+                    }
+
+                    // Session messages are the tail of the conversation, after the system prompts
+                    let dropped = self.session.messages.len() - fork.messages.len();
+                    self.conversation.truncate(self.conversation.len().saturating_sub(dropped));
+                    let parent_id = std::mem::replace(&mut self.session, fork).id;
+
+                    self.context_manager = ContextManager::new(self.llm_provider.clone());
+                    self.context_manager.set_max_token_limit(self.config.context_window);
+                    for message in &self.conversation {
+                        self.context_manager.add_message(message.clone());
+                    }
+                    self.send_token_stats(None).await;
+
+                    self.ui_tx
+                        .send(UIUpdate::SystemMessage(format!(
+                            "Forked session {} from {}",
+                            self.session.id, parent_id
+                        )))
+                        .await?;
+                    self.ui_tx
+                        .send(UIUpdate::SessionLoaded {
+                            session_id: self.session.id.clone(),
+                        })
+                        .await?;
+                }
+                Command::SearchSessions(query) => {
+                    match crate::session::search(&query) {
+                        Ok(matches) => {
+                            self.ui_tx
+                                .send(UIUpdate::SessionSearchResults { query, matches })
+                                .await?;
+                        }
+                        Err(e) => {
+                            self.ui_tx
+                                .send(UIUpdate::Error(format!("Failed to search sessions: {}", e)))
+                                .await?;
+                        }
+                    }
+                }
                 Command::SetSessionName(name) => {
                     self.session.set_name(name.clone());
                     if let Err(e) = self.session.save() {
@@ -447,6 +698,32 @@ impl AgentActor {
                     self.config.reasoning_level = level.clone();
                     tracing::info!("Reasoning level set to: {}", level);
                 }
+                Command::SetLlmProfile(name) => {
+                    let Some(profile) = self.llm_profiles.get(&name).cloned() else {
+                        self.ui_tx
+                            .send(UIUpdate::Error(format!("Unknown LLM profile: {}", name)))
+                            .await?;
+                        continue;
+                    };
+
+                    // Keep the conversation and the user's reasoning level; only the model changes
+                    let reasoning_level = self.config.reasoning_level.clone();
+                    self.config = GenerationConfig {
+                        reasoning_level,
+                        ..profile.config
+                    };
+                    self.llm_provider = profile.provider.clone();
+                    self.context_manager.set_llm_provider(profile.provider);
+                    self.context_manager.set_max_token_limit(self.config.context_window);
+                    tracing::info!("Switched to LLM profile '{}' ({})", name, self.config.model);
+
+                    self.ui_tx
+                        .send(UIUpdate::LlmProfileChanged {
+                            name,
+                            model: self.config.model.clone(),
+                        })
+                        .await?;
+                }
                 Command::ShowMenu => {
                     // UI will handle the menu display, just send a response
                     let _ = self.ui_tx.send(UIUpdate::MenuDisplayRequested).await;
@@ -454,26 +731,35 @@ impl AgentActor {
                 Command::CompactContext => {
                     tracing::info!("Manual compaction requested");
 
-                    if let Err(e) = self.context_manager.compact_if_needed().await {
-                        tracing::error!("Failed to compact context: {}", e);
-                        let _ = self.ui_tx.send(UIUpdate::SystemMessage(
-                            format!("Compaction failed: {}", e)
-                        )).await;
-                    } else {
-                        // Update conversation
-                        self.conversation = self.context_manager.get_messages().to_vec();
+                    let message = match self.context_manager.compact().await {
+                        Ok(Some(result)) => {
+                            // Update conversation
+                            self.conversation = self.context_manager.get_messages().to_vec();
 
-                        let stats = self.context_manager.get_token_stats();
-                        let _ = self.ui_tx.send(UIUpdate::SystemMessage(
+                            let stats = self.context_manager.get_token_stats();
+                            let _ = self.ui_tx.send(UIUpdate::TokenStatsUpdate(stats.clone())).await;
                             format!(
-                                "Context compacted successfully. Usage: {} / {} tokens ({:.1}%)",
-                                stats.current, stats.max, stats.usage_percent
+                                "Summarized {} messages: ~{} → ~{} tokens (saved ~{}). Usage: {} / {} tokens ({:.1}%)",
+                                result.messages_summarized,
+                                result.tokens_before,
+                                result.tokens_after,
+                                result.tokens_before.saturating_sub(result.tokens_after),
+                                stats.current,
+                                stats.max,
+                                stats.usage_percent
                             )
-                        )).await;
-                    }
+                        }
+                        Ok(None) => "Nothing to compact: the conversation is too short to summarize".to_string(),
+                        Err(e) => {
+                            tracing::error!("Failed to compact context: {}", e);
+                            format!("Compaction failed: {}", e)
+                        }
+                    };
+                    let _ = self.ui_tx.send(UIUpdate::SystemMessage(message)).await;
                 }
                 Command::ViewContextStats => {
                     let stats = self.context_manager.get_token_stats();
+                    let _ = self.ui_tx.send(UIUpdate::TokenStatsUpdate(stats.clone())).await;
                     let _ = self.ui_tx.send(UIUpdate::SystemMessage(
                         format!(
                             "Context Usage: {} / {} tokens ({:.1}%) | Threshold: {} tokens (80%)",
@@ -487,6 +773,52 @@ impl AgentActor {
         Ok(())
     }
 
+    /// Save the current session and switch to an empty one
+    async fn start_new_session(&mut self) -> Result<()> {
+        // Save current session first
+        if let Err(e) = self.session.save() {
+            tracing::error!("Failed to save current session: {}", e);
+        }
+
+        // Create new session
+        self.session = Session::new(self.config.model.clone());
+        self.conversation.clear();
+        self.conversation.push(self.create_system_prompt()); // Add system prompt to new session
+        if let Some(project_msg) = self.create_project_context_message() {
+            self.conversation.push(project_msg);
+        }
+
+        // Reset context manager with new system prompts
+        self.context_manager = ContextManager::new(self.llm_provider.clone());
+        self.context_manager.set_max_token_limit(self.config.context_window);
+        for msg in &self.conversation {
+            self.context_manager.add_message(msg.clone());
+        }
+
+        // Tell UI to clear displayed conversation
+        self.ui_tx.send(UIUpdate::ConversationCleared).await?;
+        self.restore_todos().await?;
+        self.send_token_stats(None).await;
+
+        self.ui_tx
+            .send(UIUpdate::SessionLoaded {
+                session_id: self.session.id.clone(),
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Note a Cancel or NewSession command received mid-generation; false for any other command
+    fn request_cancel(&mut self, cmd: &Command) -> bool {
+        match cmd {
+            Command::Cancel => {}
+            Command::NewSession => self.new_session_requested = true,
+            _ => return false,
+        }
+        self.cancel_requested = true;
+        true
+    }
+
     async fn generate_response(&mut self) -> Result<()> {
         // Reset cancellation flag and tool call count at the start
         self.cancel_requested = false;
@@ -504,11 +836,9 @@ impl AgentActor {
                         self.ui_tx.send(UIUpdate::Error("Generation canceled by user".to_string())).await?;
                     }
                     Command::NewSession => {
+                        // The new session is started once this turn has unwound
                         tracing::info!("NewSession command received during generation - canceling first");
-                        self.cancel_requested = true;
-                        // We'll handle the actual new session after cancellation completes
-                        // For now, just cancel and let the user try again
-                        self.ui_tx.send(UIUpdate::Error("Please wait for current generation to cancel before starting new session".to_string())).await?;
+                        self.request_cancel(&cmd);
                     }
                     _ => {
                         // Other commands received during generation are ignored
@@ -552,14 +882,7 @@ impl AgentActor {
             if !should_continue {
                 self.ui_tx.send(UIUpdate::Complete).await?;
 
-                // Auto-save session after complete response
-                if self.auto_save {
-                    if let Err(e) = self.session.save() {
-                        tracing::error!("Failed to auto-save session: {}", e);
-                    } else {
-                        tracing::debug!("Session auto-saved: {}", self.session.id);
-                    }
-                }
+                self.autosave_session();
                 break;
             }
         }
@@ -567,6 +890,15 @@ impl AgentActor {
         Ok(())
     }
 
+    /// Send streamed reasoning to the UI the first time something else is about to be shown
+    async fn show_reasoning_once(&self, thinking: &str, shown: &mut bool) -> Result<()> {
+        if !*shown && !thinking.is_empty() {
+            *shown = true;
+            self.ui_tx.send(UIUpdate::AssistantReasoning(thinking.to_string())).await?;
+        }
+        Ok(())
+    }
+
     async fn generate_response_streaming(&mut self) -> Result<()> {
         // Send thinking indicator
         self.ui_tx.send(UIUpdate::AssistantThinking).await?;
@@ -586,6 +918,8 @@ impl AgentActor {
             .await?;
 
         let mut accumulated_text = String::new();
+        let mut accumulated_thinking = String::new();
+        let mut thinking_shown = false; // Reasoning is shown once, before the answer starts
         let mut tool_calls: HashMap<String, (String, String)> = HashMap::new(); // id -> (name, accumulated_args)
         let mut token_usage = TokenUsage {
             input_tokens: 0,
@@ -596,9 +930,8 @@ impl AgentActor {
         while let Some(event_result) = stream.next().await {
             // Check for pending cancel commands (non-blocking)
             while let Ok(cmd) = self.cmd_rx.try_recv() {
-                if matches!(cmd, Command::Cancel | Command::NewSession) {
+                if self.request_cancel(&cmd) {
                     tracing::info!("Cancel/NewSession command received during streaming");
-                    self.cancel_requested = true;
                 }
             }
 
@@ -610,11 +943,16 @@ impl AgentActor {
 
             match event_result {
                 Ok(event) => match event {
+                    StreamEvent::ThinkingDelta(delta) => {
+                        accumulated_thinking.push_str(&delta);
+                    }
                     StreamEvent::TextDelta(delta) => {
+                        self.show_reasoning_once(&accumulated_thinking, &mut thinking_shown).await?;
                         accumulated_text.push_str(&delta);
                         self.ui_tx.send(UIUpdate::AssistantTextDelta(delta)).await?;
                     }
                     StreamEvent::ToolCallStart { id, name } => {
+                        self.show_reasoning_once(&accumulated_thinking, &mut thinking_shown).await?;
                         tool_calls.insert(id.clone(), (name.clone(), String::new()));
                         self.ui_tx
                             .send(UIUpdate::ToolExecutionStarted {
@@ -645,9 +983,23 @@ impl AgentActor {
             }
         }
 
+        // Reasoning-only response (e.g. cut off before answering)
+        self.show_reasoning_once(&accumulated_thinking, &mut thinking_shown).await?;
+
         // Build the assistant message from accumulated data
         let mut content = Vec::new();
 
+        // Inline <think> blocks are split out so they aren't sent back to the model
+        let (inline_thinking, accumulated_text) = split_think_tags(&accumulated_text);
+        let thinking: Vec<String> = [Some(accumulated_thinking), inline_thinking]
+            .into_iter()
+            .flatten()
+            .filter(|t| !t.trim().is_empty())
+            .collect();
+        if !thinking.is_empty() {
+            content.push(ContentBlock::Thinking { text: thinking.join("\n\n") });
+        }
+
         if !accumulated_text.is_empty() {
             content.push(ContentBlock::Text { text: accumulated_text });
         }
@@ -660,6 +1012,7 @@ impl AgentActor {
             content.push(ContentBlock::ToolUse { id, name, input });
         }
 
+        self.log_response_events(&content, &token_usage);
         let assistant_message = Message {
             role: Role::Assistant,
             content: content.clone(),
@@ -715,9 +1068,8 @@ impl AgentActor {
         if !tool_calls.is_empty() {
             // Check for cancellation before starting tool execution
             while let Ok(cmd) = self.cmd_rx.try_recv() {
-                if matches!(cmd, Command::Cancel | Command::NewSession) {
+                if self.request_cancel(&cmd) {
                     tracing::info!("Cancel/NewSession command received before tool execution");
-                    self.cancel_requested = true;
                     return Ok(());
                 }
             }
@@ -729,32 +1081,17 @@ impl AgentActor {
             tracing::info!("Executing {} tools in parallel", tool_calls.len());
             self.tool_call_count += tool_calls.len(); // Track tool calls to prevent infinite loops
 
-            let futures: Vec<_> = tool_calls
-                .iter()
-                .map(|(id, name, input)| {
-                    let registry = self.tool_registry.clone();
-                    let name = name.clone();
-                    let input = input.clone();
-                    let id = id.clone();
-
-                    async move {
-                        let start = Instant::now();
-                        let result = registry.execute(&name, input.clone()).await;
-                        let duration_ms = start.elapsed().as_millis() as u64;
-                        (id, name, input, result, duration_ms)
-                    }
-                })
-                .collect();
-
-            let results = join_all(futures).await;
+            let results = match self.execute_tools_cancellable(&tool_calls).await? {
+                Some(results) => results,
+                None => return Ok(()),
+            };
 
             // Process results in order
             for (id, name, input, result, duration_ms) in results {
                 // Check for cancellation between result processing
                 while let Ok(cmd) = self.cmd_rx.try_recv() {
-                    if matches!(cmd, Command::Cancel | Command::NewSession) {
+                    if self.request_cancel(&cmd) {
                         tracing::info!("Cancel/NewSession command received during result processing");
-                        self.cancel_requested = true;
                         return Ok(());
                     }
                 }
@@ -763,6 +1100,12 @@ impl AgentActor {
                     return Ok(());
                 }
 
+                self.log_event(LogEvent::ToolCall {
+                    name: name.clone(),
+                    duration_ms,
+                    success: matches!(&result, Ok(tool_result) if !tool_result.is_error),
+                });
+
                 match result {
                     Ok(tool_result) => {
                         // Truncate output to first 500 chars for UI display
@@ -783,18 +1126,25 @@ impl AgentActor {
                             })
                             .await?;
 
-                        // Add tool result to conversation
+                        // Add tool result to conversation, followed by any image it read
+                        let image = self.read_image_block(&name, &input, &tool_result).await;
+                        let mut content = vec![ContentBlock::ToolResult {
+                            tool_use_id: id,
+                            content: tool_result.content,
+                            is_error: tool_result.is_error,
+                        }];
+                        content.extend(image);
                         let result_message = Message {
                             role: Role::User,
-                            content: vec![ContentBlock::ToolResult {
-                                tool_use_id: id,
-                                content: tool_result.content,
-                                is_error: tool_result.is_error,
-                            }],
+                            content,
                         };
                         self.conversation.push(result_message.clone());
                         self.context_manager.add_message(result_message.clone());
                         self.session.add_message(result_message);
+
+                        if name == "todo" && !tool_result.is_error {
+                            self.sync_todos().await?;
+                        }
                     }
                     Err(e) => {
                         let error_msg = e.to_string();
@@ -843,15 +1193,7 @@ impl AgentActor {
             }
         }
 
-        // Update token count in context manager
-        self.context_manager.update_token_count(
-            token_usage.input_tokens as usize,
-            token_usage.output_tokens as usize,
-        );
-
-        // Send token stats to UI
-        let stats = self.context_manager.get_token_stats();
-        let _ = self.ui_tx.send(UIUpdate::TokenStatsUpdate(stats)).await;
+        self.send_token_stats(Some(&token_usage)).await;
 
         // Check if auto-compaction should trigger
         if self.context_manager.should_compact() {
@@ -876,6 +1218,101 @@ impl AgentActor {
         Ok(())
     }
 
+    /// Run tool calls in parallel, aborting them if the user cancels mid-flight.
+    /// Returns None when canceled; any partial output is discarded and each call
+    /// is recorded as canceled so the conversation stays well-formed.
+    async fn execute_tools_cancellable(
+        &mut self,
+        tool_calls: &[(String, String, Value)],
+    ) -> Result<Option<Vec<ToolExecution>>> {
+        let cancel = CancellationToken::new();
+
+        let futures: Vec<_> = tool_calls
+            .iter()
+            .map(|(id, name, input)| {
+                let registry = self.tool_registry.clone();
+                let name = name.clone();
+                let input = input.clone();
+                let id = id.clone();
+                let cancel = cancel.clone();
+
+                async move {
+                    let start = Instant::now();
+                    let result = registry
+                        .execute_cancellable(&name, input.clone(), &cancel)
+                        .await;
+                    let duration_ms = start.elapsed().as_millis() as u64;
+                    (id, name, input, result, duration_ms)
+                }
+            })
+            .collect();
+
+        let mut running = Box::pin(join_all(futures));
+
+        tokio::select! {
+            results = &mut running => Ok(Some(results)),
+            cmd = Self::wait_for_cancel(&mut self.cmd_rx) => {
+                tracing::info!("Cancel/NewSession command received during tool execution");
+                self.cancel_requested = true;
+                self.new_session_requested |= matches!(cmd, Command::NewSession);
+                cancel.cancel();
+
+                // Let the tools observe the cancellation (killing child processes) before moving on
+                running.await;
+
+                let canceled = Message {
+                    role: Role::User,
+                    content: tool_calls
+                        .iter()
+                        .map(|(id, _, _)| ContentBlock::ToolResult {
+                            tool_use_id: id.clone(),
+                            content: "Tool execution canceled by user".to_string(),
+                            is_error: true,
+                        })
+                        .collect(),
+                };
+                self.conversation.push(canceled.clone());
+                self.context_manager.add_message(canceled.clone());
+                self.session.add_message(canceled);
+
+                self.ui_tx
+                    .send(UIUpdate::Error("Tool execution canceled by user".to_string()))
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Persist a tag change and show the session's tags
+    async fn save_tags(&mut self) -> Result<()> {
+        if let Err(e) = self.session.save() {
+            tracing::error!("Failed to save session after changing tags: {}", e);
+        }
+        let tags = if self.session.tags.is_empty() {
+            "(none)".to_string()
+        } else {
+            self.session.tags.join(", ")
+        };
+        self.ui_tx
+            .send(UIUpdate::SystemMessage(format!("Session tags: {}", tags)))
+            .await?;
+        Ok(())
+    }
+
+    /// Resolve to the first Cancel or NewSession command to arrive; other commands are ignored
+    async fn wait_for_cancel(cmd_rx: &mut Receiver<Command>) -> Command {
+        loop {
+            match cmd_rx.recv().await {
+                Some(cmd @ (Command::Cancel | Command::NewSession)) => return cmd,
+                Some(_) => {
+                    tracing::warn!("Received command during tool execution, ignoring until complete");
+                }
+                // Sender gone: nothing can cancel us anymore
+                None => return std::future::pending().await,
+            }
+        }
+    }
+
     async fn generate_response_non_streaming(&mut self) -> Result<()> {
         // Compact context if needed before making LLM call
         if let Err(e) = self.context_manager.compact_if_needed().await {
@@ -896,6 +1333,7 @@ impl AgentActor {
         let stop_reason = response.stop_reason.clone();
 
         // Add the full assistant response as a single message
+        self.log_response_events(&response.content, &token_usage);
         let assistant_message = Message {
             role: Role::Assistant,
             content: response.content.clone(),
@@ -904,10 +1342,16 @@ impl AgentActor {
         self.context_manager.add_message(assistant_message.clone());
         self.session.add_message(assistant_message);
 
-        // Display text content immediately
+        // Display reasoning and text content immediately
         for block in &response.content {
-            if let ContentBlock::Text { text } = block {
-                self.ui_tx.send(UIUpdate::AssistantText(text.clone())).await?;
+            match block {
+                ContentBlock::Thinking { text } => {
+                    self.ui_tx.send(UIUpdate::AssistantReasoning(text.clone())).await?;
+                }
+                ContentBlock::Text { text } => {
+                    self.ui_tx.send(UIUpdate::AssistantText(text.clone())).await?;
+                }
+                _ => {}
             }
         }
 
@@ -965,9 +1409,8 @@ impl AgentActor {
         if !tool_calls.is_empty() {
             // Check for cancellation before starting tool execution
             while let Ok(cmd) = self.cmd_rx.try_recv() {
-                if matches!(cmd, Command::Cancel | Command::NewSession) {
+                if self.request_cancel(&cmd) {
                     tracing::info!("Cancel/NewSession command received before tool execution (non-streaming)");
-                    self.cancel_requested = true;
                     return Ok(());
                 }
             }
@@ -979,32 +1422,17 @@ impl AgentActor {
             tracing::info!("Executing {} tools in parallel (non-streaming)", tool_calls.len());
             self.tool_call_count += tool_calls.len(); // Track tool calls to prevent infinite loops
 
-            let futures: Vec<_> = tool_calls
-                .iter()
-                .map(|(id, name, input)| {
-                    let registry = self.tool_registry.clone();
-                    let name = name.clone();
-                    let input = input.clone();
-                    let id = id.clone();
-
-                    async move {
-                        let start = Instant::now();
-                        let result = registry.execute(&name, input.clone()).await;
-                        let duration_ms = start.elapsed().as_millis() as u64;
-                        (id, name, input, result, duration_ms)
-                    }
-                })
-                .collect();
-
-            let results = join_all(futures).await;
+            let results = match self.execute_tools_cancellable(&tool_calls).await? {
+                Some(results) => results,
+                None => return Ok(()),
+            };
 
             // Process results in order
             for (id, name, input, result, duration_ms) in results {
                 // Check for cancellation between result processing
                 while let Ok(cmd) = self.cmd_rx.try_recv() {
-                    if matches!(cmd, Command::Cancel | Command::NewSession) {
+                    if self.request_cancel(&cmd) {
                         tracing::info!("Cancel/NewSession command received during result processing (non-streaming)");
-                        self.cancel_requested = true;
                         return Ok(());
                     }
                 }
@@ -1013,6 +1441,12 @@ impl AgentActor {
                     return Ok(());
                 }
 
+                self.log_event(LogEvent::ToolCall {
+                    name: name.clone(),
+                    duration_ms,
+                    success: matches!(&result, Ok(tool_result) if !tool_result.is_error),
+                });
+
                 match result {
                     Ok(tool_result) => {
                         // Truncate output to first 500 chars for UI display
@@ -1033,18 +1467,25 @@ impl AgentActor {
                             })
                             .await?;
 
-                        // Add tool result to conversation
+                        // Add tool result to conversation, followed by any image it read
+                        let image = self.read_image_block(&name, &input, &tool_result).await;
+                        let mut content = vec![ContentBlock::ToolResult {
+                            tool_use_id: id,
+                            content: tool_result.content,
+                            is_error: tool_result.is_error,
+                        }];
+                        content.extend(image);
                         let result_message = Message {
                             role: Role::User,
-                            content: vec![ContentBlock::ToolResult {
-                                tool_use_id: id,
-                                content: tool_result.content,
-                                is_error: tool_result.is_error,
-                            }],
+                            content,
                         };
                         self.conversation.push(result_message.clone());
                         self.context_manager.add_message(result_message.clone());
                         self.session.add_message(result_message);
+
+                        if name == "todo" && !tool_result.is_error {
+                            self.sync_todos().await?;
+                        }
                     }
                     Err(e) => {
                         let error_msg = e.to_string();
@@ -1093,15 +1534,7 @@ impl AgentActor {
             }
         }
 
-        // Update token count in context manager
-        self.context_manager.update_token_count(
-            token_usage.input_tokens as usize,
-            token_usage.output_tokens as usize,
-        );
-
-        // Send token stats to UI
-        let stats = self.context_manager.get_token_stats();
-        let _ = self.ui_tx.send(UIUpdate::TokenStatsUpdate(stats)).await;
+        self.send_token_stats(Some(&token_usage)).await;
 
         // Check if auto-compaction should trigger
         if self.context_manager.should_compact() {
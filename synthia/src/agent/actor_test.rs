@@ -173,6 +173,10 @@ mod tests {
             streaming: false,
             reasoning_level: "medium".to_string(),
             context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
         };
 
         // Create actor (this would normally be done via AgentActor::new, but we'll construct manually for testing)
@@ -239,6 +243,272 @@ mod tests {
         handle.abort();
     }
 
+    /// Mock LLM provider that returns a fixed sequence of responses
+    struct ScriptedLLMProvider {
+        responses: std::sync::Mutex<std::collections::VecDeque<LLMResponse>>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for ScriptedLLMProvider {
+        async fn chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<LLMResponse> {
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| anyhow::anyhow!("No scripted response left"))
+        }
+
+        async fn stream_chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<crate::llm::provider::StreamResult> {
+            unimplemented!("Streaming not used in this test")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_turn_logs_expected_events() {
+        use crate::jsonl_logger::{JsonlLogger, LogEvent};
+
+        let mut registry = create_test_registry();
+        registry
+            .register(Arc::new(SleepTool {
+                name: "sleep1".to_string(),
+                sleep_ms: 1,
+            }))
+            .unwrap();
+
+        let usage = |input_tokens, output_tokens| TokenUsage { input_tokens, output_tokens };
+        let llm = Arc::new(ScriptedLLMProvider {
+            responses: std::sync::Mutex::new(
+                vec![
+                    LLMResponse {
+                        content: vec![
+                            ContentBlock::Text { text: "Sleeping".to_string() },
+                            ContentBlock::ToolUse {
+                                id: "call1".to_string(),
+                                name: "sleep1".to_string(),
+                                input: serde_json::json!({"duration_ms": 1}),
+                            },
+                        ],
+                        stop_reason: StopReason::StopSequence,
+                        usage: usage(100, 10),
+                    },
+                    LLMResponse {
+                        content: vec![ContentBlock::Text { text: "Done".to_string() }],
+                        stop_reason: StopReason::EndTurn,
+                        usage: usage(130, 2),
+                    },
+                ]
+                .into(),
+            ),
+        });
+
+        let (ui_tx, mut ui_rx) = mpsc::channel(100);
+        let (cmd_tx, cmd_rx) = mpsc::channel(100);
+        let config = GenerationConfig {
+            model: "test-model".to_string(),
+            temperature: 1.0,
+            max_tokens: Some(1000),
+            streaming: false,
+            reasoning_level: "medium".to_string(),
+            context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        };
+
+        let log_dir = std::env::temp_dir().join(format!("synthia_test_events_{}", uuid::Uuid::new_v4()));
+        let mut actor = AgentActor::new(llm, Arc::new(registry), config, ui_tx, cmd_rx, None);
+        actor.set_jsonl_logger(JsonlLogger::with_dir(log_dir.clone()).unwrap());
+        tokio::spawn(async move { while ui_rx.recv().await.is_some() {} });
+
+        cmd_tx.send(Command::SendMessage("Please sleep".to_string())).await.unwrap();
+        drop(cmd_tx);
+        tokio::time::timeout(Duration::from_secs(5), actor.run())
+            .await
+            .expect("actor should finish once the command channel closes")
+            .unwrap();
+
+        let events: Vec<LogEvent> = JsonlLogger::with_dir(log_dir.clone())
+            .unwrap()
+            .read_events()
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.event)
+            .map(|event| match event {
+                // Durations vary between runs
+                LogEvent::ToolCall { name, success, .. } => LogEvent::ToolCall { name, duration_ms: 0, success },
+                other => other,
+            })
+            .collect();
+        std::fs::remove_dir_all(&log_dir).ok();
+
+        assert_eq!(
+            events,
+            vec![
+                LogEvent::UserMessage { length: 12 },
+                LogEvent::AssistantText { length: 8, tool_calls: 1 },
+                LogEvent::TurnUsage { input_tokens: 100, output_tokens: 10 },
+                LogEvent::ToolCall { name: "sleep1".to_string(), duration_ms: 0, success: true },
+                LogEvent::AssistantText { length: 4, tool_calls: 0 },
+                LogEvent::TurnUsage { input_tokens: 130, output_tokens: 2 },
+            ]
+        );
+    }
+
+    /// Mock LLM provider whose stream sends a text delta every 50ms, never finishing on its own
+    struct SlowStreamProvider;
+
+    #[async_trait]
+    impl LLMProvider for SlowStreamProvider {
+        async fn chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<LLMResponse> {
+            unimplemented!("Only streaming is used in this test")
+        }
+
+        async fn stream_chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<crate::llm::provider::StreamResult> {
+            Ok(Box::pin(futures::stream::unfold((), |()| async {
+                sleep(Duration::from_millis(50)).await;
+                Some((Ok(crate::llm::StreamEvent::TextDelta("word ".to_string())), ()))
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_session_during_streaming_starts_fresh_session() {
+        let (ui_tx, mut ui_rx) = mpsc::channel(100);
+        let (cmd_tx, cmd_rx) = mpsc::channel(100);
+        let config = GenerationConfig {
+            model: "test-model".to_string(),
+            temperature: 1.0,
+            max_tokens: Some(1000),
+            streaming: true,
+            reasoning_level: "medium".to_string(),
+            context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        };
+        let mut actor =
+            AgentActor::new(Arc::new(SlowStreamProvider), Arc::new(create_test_registry()), config, ui_tx, cmd_rx, None);
+        let handle = tokio::spawn(async move { actor.run().await });
+
+        cmd_tx.send(Command::SendMessage("Write an essay".to_string())).await.unwrap();
+
+        let mut updates = Vec::new();
+        let mut new_session_sent = false;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        loop {
+            let update = tokio::time::timeout_at(deadline, ui_rx.recv())
+                .await
+                .expect("a new session should start after the turn is canceled")
+                .unwrap();
+            match update {
+                UIUpdate::AssistantTextDelta(_) if !new_session_sent => {
+                    cmd_tx.send(Command::NewSession).await.unwrap();
+                    new_session_sent = true;
+                }
+                UIUpdate::SessionLoaded { .. } => {
+                    updates.push(update);
+                    break;
+                }
+                update => updates.push(update),
+            }
+        }
+
+        assert!(updates.iter().any(|u| matches!(u, UIUpdate::ConversationCleared)));
+        assert!(
+            !updates.iter().any(|u| matches!(u, UIUpdate::Error(_))),
+            "NewSession shouldn't be refused mid-generation: {:?}",
+            updates
+        );
+
+        drop(cmd_tx);
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("actor should finish once the command channel closes")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_autosave_off_still_saves_on_shutdown() {
+        use crate::config::AutoSave;
+        use crate::session::Session;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+
+        let llm = Arc::new(ScriptedLLMProvider {
+            responses: std::sync::Mutex::new(
+                vec![LLMResponse {
+                    content: vec![ContentBlock::Text { text: "Hello".to_string() }],
+                    stop_reason: StopReason::EndTurn,
+                    usage: TokenUsage { input_tokens: 10, output_tokens: 1 },
+                }]
+                .into(),
+            ),
+        });
+        let (ui_tx, mut ui_rx) = mpsc::channel(100);
+        let (cmd_tx, cmd_rx) = mpsc::channel(100);
+        let config = GenerationConfig {
+            model: "test-model".to_string(),
+            temperature: 1.0,
+            max_tokens: Some(1000),
+            streaming: false,
+            reasoning_level: "medium".to_string(),
+            context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        };
+        let mut actor = AgentActor::new(llm, Arc::new(create_test_registry()), config, ui_tx, cmd_rx, None);
+        actor.set_autosave(AutoSave::Off, Duration::ZERO);
+        let session_id = actor.session_id().to_string();
+        let handle = tokio::spawn(async move { actor.run().await });
+
+        cmd_tx.send(Command::SendMessage("Hi".to_string())).await.unwrap();
+        loop {
+            let update = tokio::time::timeout(Duration::from_secs(5), ui_rx.recv())
+                .await
+                .expect("the response should complete")
+                .unwrap();
+            if matches!(update, UIUpdate::Complete) {
+                break;
+            }
+        }
+        assert!(Session::load(&session_id).is_err(), "autosave is off, nothing should be written");
+
+        cmd_tx.send(Command::Shutdown).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("actor should stop on shutdown")
+            .unwrap()
+            .unwrap();
+        let saved = Session::load(&session_id).expect("shutdown should save the session");
+        assert_eq!(saved.messages.len(), 2);
+    }
+
     #[test]
     fn test_parallel_execution_documentation() {
         // This test serves as documentation for how parallel execution works
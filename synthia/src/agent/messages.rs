@@ -7,8 +7,14 @@ pub enum Command {
     NewSession,
     LoadSession(String),
     ListSessions,
+    SearchSessions(String), // Full-text search over saved sessions
+    ListSessionsTagged(String), // Like ListSessions, limited to sessions with this tag
+    AddSessionTag(String),
+    RemoveSessionTag(String),
     SetSessionName(String),
+    ForkSession(Option<usize>), // Copy the first N session messages (all if None) into a new session
     SetReasoningLevel(String),
+    SetLlmProfile(String),
     #[allow(dead_code)]
     ShowMenu,
     CompactContext,
@@ -35,10 +41,10 @@ pub enum UIUpdate {
     AssistantText(String),
     AssistantTextDelta(String), // For streaming text chunks
     AssistantThinking, // Indicator that agent is thinking
-    ToolExecutionStarted { name: String, #[allow(dead_code)] id: String },
+    AssistantReasoning(String), // Model reasoning, shown dimmed ahead of the answer
+    ToolExecutionStarted { name: String, id: String },
     ToolResult {
         name: String,
-        #[allow(dead_code)]
         id: String,
         input: serde_json::Value,
         output: String,
@@ -50,6 +56,7 @@ pub enum UIUpdate {
     SessionSaved { session_id: String },
     SessionLoaded { session_id: String },
     SessionList { sessions: Vec<crate::session::SessionInfo> },
+    SessionSearchResults { query: String, matches: Vec<crate::session::SessionMatch> },
     ConversationCleared, // Signal UI to clear displayed conversation
     EditPreview {
         file_path: String,
@@ -61,9 +68,12 @@ pub enum UIUpdate {
     MenuDisplayRequested,  // Signal UI to display menu
     SystemMessage(String), // System notifications (e.g., auto-compaction)
     TokenStatsUpdate(crate::context_manager::TokenStats), // Token usage stats update
+    LlmProfileChanged { name: String, model: String }, // Active LLM profile switched
+    TodosUpdated(Vec<crate::tools::todo::Todo>), // Todo list changed (tool call or session switch)
     PermissionPrompt {
         tool_name: String,
         operation_details: String,
+        risk: crate::permission_manager::OperationRisk,
         suggested_pattern: String,
         response_tx: tokio::sync::oneshot::Sender<PermissionResponse>,
     },
@@ -1,7 +1,8 @@
 pub mod actor;
 pub mod messages;
+pub mod prompt;
 
 #[cfg(test)]
 mod actor_test;
 
-pub use actor::AgentActor;
+pub use actor::{AgentActor, LlmProfile};
@@ -0,0 +1,106 @@
+use serde_json::Value;
+
+// Default prompt is loaded from markdown file at compile time
+// To support different modes in the future (CLI vs non-interactive vs sub-agent),
+// create additional prompt files (e.g., system_prompt_subagent.md) and select
+// based on initialization parameters
+pub const DEFAULT_SYSTEM_PROMPT: &str = include_str!("../../prompts/system_prompt.md");
+
+/// Marker in the base prompt replaced by the list of registered tools
+pub const TOOLS_PLACEHOLDER: &str = "{{tools}}";
+
+/// Build the full system prompt: the base text with its tool list filled in from
+/// `tools` (`ToolRegistry::definitions()`), followed by the project's fragment
+/// A base prompt without the placeholder gets the tool list appended instead
+pub fn build_system_prompt(base: &str, tools: &[Value], fragment: Option<&str>) -> String {
+    let tool_list = tool_list(tools);
+    let mut prompt = if base.contains(TOOLS_PLACEHOLDER) {
+        base.replace(TOOLS_PLACEHOLDER, &tool_list)
+    } else {
+        format!("{}\n\n# Available Tools\n\n{}", base.trim_end(), tool_list)
+    };
+
+    if let Some(fragment) = fragment.map(str::trim).filter(|f| !f.is_empty()) {
+        prompt = format!("{}\n\n{}", prompt.trim_end(), fragment);
+    }
+    prompt
+}
+
+/// One `- **name**: summary` line per tool, sorted by name
+fn tool_list(tools: &[Value]) -> String {
+    let mut lines: Vec<(&str, &str)> = tools
+        .iter()
+        .filter_map(|tool| {
+            let name = tool["name"].as_str()?;
+            Some((name, summary(tool["description"].as_str().unwrap_or(""))))
+        })
+        .collect();
+    lines.sort();
+
+    lines
+        .into_iter()
+        .map(|(name, summary)| {
+            if summary.is_empty() {
+                format!("- **{}**", name)
+            } else {
+                format!("- **{}**: {}", name, summary)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// First sentence of a tool description; the rest is in the tool's schema anyway
+fn summary(description: &str) -> &str {
+    let first_line = description.trim().lines().next().unwrap_or("");
+    let sentence = first_line.split(". ").next().unwrap_or(first_line);
+    sentence.trim().trim_end_matches('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::permission_manager::PermissionManager;
+    use crate::tools::glob::GlobTool;
+    use crate::tools::grep::GrepTool;
+    use crate::tools::registry::ToolRegistry;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_prompt_lists_exactly_the_registered_tools() {
+        let project_root = std::env::temp_dir().join(format!("test_prompt_{}", uuid::Uuid::new_v4()));
+        let permission_manager = Arc::new(Mutex::new(PermissionManager::new(project_root).unwrap()));
+        let mut registry = ToolRegistry::new(permission_manager);
+        registry.register(Arc::new(GrepTool::new())).unwrap();
+        registry.register(Arc::new(GlobTool::new())).unwrap();
+
+        let prompt = build_system_prompt(DEFAULT_SYSTEM_PROMPT, &registry.definitions(), None);
+        let section = prompt.split("# Available Tools\n\n").nth(1).unwrap();
+        let section = section.split("\n\n").next().unwrap();
+        let listed: Vec<&str> = section
+            .lines()
+            .filter_map(|line| line.strip_prefix("- **")?.split("**").next())
+            .collect();
+        assert_eq!(listed, vec!["glob", "grep"]);
+        assert!(!prompt.contains(TOOLS_PLACEHOLDER));
+    }
+
+    #[test]
+    fn test_custom_prompt_and_project_fragment() {
+        let tools = vec![serde_json::json!({
+            "name": "bash",
+            "description": "Execute a bash command and return stdout/stderr. Long-running commands time out.",
+        })];
+
+        let prompt = build_system_prompt("You are terse.", &tools, Some("  Always run cargo fmt.\n"));
+        assert_eq!(
+            prompt,
+            "You are terse.\n\n# Available Tools\n\n\
+             - **bash**: Execute a bash command and return stdout/stderr\n\n\
+             Always run cargo fmt."
+        );
+
+        let prompt = build_system_prompt("Tools:\n{{tools}}\nBe careful.", &tools, Some(""));
+        assert_eq!(prompt, "Tools:\n- **bash**: Execute a bash command and return stdout/stderr\nBe careful.");
+    }
+}
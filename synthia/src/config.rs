@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Main configuration structure for Synthia
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,11 +19,72 @@ pub struct Config {
 
     #[serde(default)]
     pub tools: ToolsConfig,
+
+    #[serde(default)]
+    pub agent: AgentConfig,
+
+    #[serde(default)]
+    pub session: SessionConfig,
+}
+
+/// Agent behavior (`[agent]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// Base system prompt replacing the built-in one; `{{tools}}` marks where the
+    /// list of registered tools goes (appended at the end if absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+}
+
+/// Session persistence (`[session]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionConfig {
+    /// When the session is saved automatically; it's always saved on exit
+    #[serde(default)]
+    pub autosave: AutoSave,
+
+    /// Minimum seconds between saves in `throttled` mode
+    #[serde(default = "default_autosave_interval_secs")]
+    pub autosave_interval_secs: u64,
+}
+
+/// When the current session is written to disk without being asked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoSave {
+    /// Only on exit or `/save`
+    Off,
+    /// After every completed response
+    #[default]
+    OnComplete,
+    /// After a completed response, at most once per `autosave_interval_secs`
+    Throttled,
+}
+
+impl SessionConfig {
+    pub fn autosave_interval(&self) -> Duration {
+        Duration::from_secs(self.autosave_interval_secs)
+    }
+}
+
+/// Which API the LLM settings talk to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// Any OpenAI-compatible chat completions server (LM Studio, OpenAI, vLLM, ...)
+    #[default]
+    OpenAI,
+    /// Google Gemini's `generateContent` API
+    Gemini,
 }
 
 /// LLM provider configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LLMConfig {
+    /// API flavour: "openai" (default) or "gemini"
+    #[serde(default)]
+    pub provider: ProviderKind,
+
     /// Base URL for the LLM API (e.g., "http://localhost:1234/v1")
     #[serde(default = "default_api_base")]
     pub api_base: String,
@@ -34,7 +97,7 @@ pub struct LLMConfig {
     #[serde(default = "default_model")]
     pub model: String,
 
-    /// Temperature for generation (0.0 to 1.0)
+    /// Temperature for generation (0.0 to 2.0)
     #[serde(default = "default_temperature")]
     pub temperature: f32,
 
@@ -49,6 +112,79 @@ pub struct LLMConfig {
     /// Context window size for the model (default: 8192)
     #[serde(default = "default_context_window")]
     pub context_window: Option<usize>,
+
+    /// Stop sequences: generation ends when the model outputs any of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+
+    /// Sampling seed for reproducible output (if the server supports it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+
+    /// Send images read by the agent to the model (only for vision-capable models)
+    #[serde(default)]
+    pub vision: bool,
+
+    /// Seconds to wait for the LLM server to connect and answer (and, when streaming,
+    /// between chunks) before giving up on a stalled server
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    /// Retries after an HTTP 429 rate limit, waiting as long as `Retry-After` asks (0 disables)
+    #[serde(default = "default_rate_limit_retries")]
+    pub rate_limit_retries: u32,
+
+    /// Named profiles that can be switched between at runtime (`[llm.profiles.<name>]`)
+    /// Fields a profile leaves unset are inherited from this section
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, LLMProfileConfig>,
+
+    /// Profile to start with (None uses the settings in this section directly)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+}
+
+/// Overrides for one named LLM profile
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LLMProfileConfig {
+    #[serde(default)]
+    pub provider: Option<ProviderKind>,
+
+    #[serde(default)]
+    pub api_base: Option<String>,
+
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    #[serde(default)]
+    pub model: Option<String>,
+
+    #[serde(default)]
+    pub temperature: Option<f32>,
+
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+
+    #[serde(default)]
+    pub streaming: Option<bool>,
+
+    #[serde(default)]
+    pub context_window: Option<usize>,
+
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    #[serde(default)]
+    pub vision: Option<bool>,
+
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+
+    #[serde(default)]
+    pub rate_limit_retries: Option<u32>,
 }
 
 /// Timeout configuration for various tools
@@ -69,6 +205,47 @@ pub struct TimeoutConfig {
     /// Timeout for powertools operations in seconds
     #[serde(default = "default_powertools_timeout")]
     pub powertools_timeout: u64,
+
+    /// Fallback timeout in seconds for tools without their own entry
+    #[serde(default = "default_tool_timeout")]
+    pub default_tool_timeout: u64,
+
+    /// Per-tool timeouts in seconds keyed by tool name (e.g. `webfetch = 30`, `read = 10`)
+    #[serde(flatten)]
+    pub per_tool: HashMap<String, u64>,
+}
+
+/// Extra time given to tools that enforce their own timeout, so their own
+/// (more descriptive) timeout error wins over the registry's backstop
+const TIMEOUT_GRACE_SECS: u64 = 5;
+
+impl TimeoutConfig {
+    /// Timeout applied by the tool registry around a tool's execution
+    pub fn timeout_for(&self, tool_name: &str) -> Duration {
+        let secs = self.per_tool.get(tool_name).copied().unwrap_or_else(|| match tool_name {
+            "bash" => self.bash_timeout + TIMEOUT_GRACE_SECS,
+            "git" => self.git_timeout + TIMEOUT_GRACE_SECS,
+            "workshop" => self.workshop_timeout + TIMEOUT_GRACE_SECS,
+            "powertools" => self.powertools_timeout + TIMEOUT_GRACE_SECS,
+            "webfetch" => default_webfetch_timeout(),
+            "read" => default_read_timeout(),
+            "spawn_subagent" => default_subagent_timeout(),
+            _ => self.default_tool_timeout,
+        });
+        Duration::from_secs(secs)
+    }
+
+    /// Per-tool keys that don't name any of `tool_names` (likely typos), sorted
+    pub fn unknown_tools(&self, tool_names: &[String]) -> Vec<String> {
+        let mut unknown: Vec<String> = self
+            .per_tool
+            .keys()
+            .filter(|key| !tool_names.contains(key))
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown
+    }
 }
 
 /// UI configuration
@@ -82,9 +259,65 @@ pub struct UIConfig {
     #[serde(default = "default_max_output_lines")]
     pub max_output_lines: usize,
 
-    /// Enable edit approval prompts
-    #[serde(default = "default_edit_approval")]
-    pub edit_approval: bool,
+    /// When edits and writes the permission rules leave to the user are prompted for
+    #[serde(default, deserialize_with = "deserialize_edit_approval")]
+    pub edit_approval: EditApproval,
+
+    /// Color theme
+    #[serde(default)]
+    pub theme: ThemeConfig,
+}
+
+/// When to ask before applying an edit, write, or patch that permissions leave to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditApproval {
+    /// Prompt for every edit
+    #[default]
+    Always,
+    /// Apply edits without prompting, still showing the diff
+    Never,
+    /// Prompt for the first edit to each file, then apply later ones without asking
+    FirstTime,
+}
+
+/// Accept the policy names, or the older `true`/`false` (always/never)
+fn deserialize_edit_approval<'de, D>(deserializer: D) -> Result<EditApproval, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Setting {
+        Enabled(bool),
+        Policy(EditApproval),
+    }
+
+    Ok(match Setting::deserialize(deserializer)? {
+        Setting::Enabled(true) => EditApproval::Always,
+        Setting::Enabled(false) => EditApproval::Never,
+        Setting::Policy(policy) => policy,
+    })
+}
+
+/// TUI color theme: a built-in theme plus optional per-role overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Built-in theme: "pastel" (default), "high-contrast", or "mono"
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Per-role overrides, as "#rrggbb" or a color name like "cyan" or "dark_grey"
+    #[serde(default)]
+    pub header: Option<String>,
+    #[serde(default)]
+    pub assistant: Option<String>,
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub success: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 /// Tools configuration
@@ -109,11 +342,98 @@ pub struct ToolsConfig {
     /// Default: 100,000 chars (~25,000 tokens)
     #[serde(default = "default_read_warn_at_chars")]
     pub read_warn_at_chars: usize,
+
+    /// Seconds before cached read/grep/glob results expire, so files changed
+    /// outside Synthia are picked up. Set to 0 to keep entries until invalidated.
+    /// Default: 300 seconds
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+
+    /// Run read-only tool calls (read, grep, git status, ...) without a permission
+    /// prompt when no permission rule covers them. Deny rules still apply.
+    /// Default: false
+    #[serde(default)]
+    pub auto_allow_read_only: bool,
+
+    /// Automatic retry of tool calls that fail with a transient error
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    /// Cleanup applied to tool output before it's stored in the conversation
+    #[serde(default)]
+    pub output: OutputConfig,
+}
+
+/// Post-processing of tool output (`[tools.output]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Tools whose output is cleaned up (others are stored as-is)
+    #[serde(default = "default_output_tools")]
+    pub tools: Vec<String>,
+
+    /// Cap on stored output in bytes; the middle is cut with a "[N bytes truncated]" marker (0 = no cap)
+    #[serde(default = "default_output_max_bytes")]
+    pub max_bytes: usize,
+
+    /// Collapse runs of at least this many identical lines into one line and a count (0 disables)
+    #[serde(default = "default_collapse_repeated_lines")]
+    pub collapse_repeated_lines: usize,
+
+    /// Remove ANSI escape codes (colors, cursor movement)
+    #[serde(default = "default_strip_ansi")]
+    pub strip_ansi: bool,
+
+    /// Remove indentation shared by every line
+    #[serde(default = "default_dedent")]
+    pub dedent: bool,
+}
+
+/// Retry policy for transient tool failures (`[tools.retry]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Extra attempts after a transient failure (0 disables retries)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before each retry in milliseconds
+    #[serde(default = "default_retry_delay_ms")]
+    pub delay_ms: u64,
+
+    /// Case-insensitive substrings of an error message that mark it as transient
+    #[serde(default = "default_retryable_errors")]
+    pub retryable_errors: Vec<String>,
 }
 
+/// Errors about the call itself (bad or missing parameters) fail the same way
+/// every time, so they're never retried even if they match a retryable signature
+const NON_RETRYABLE_ERRORS: &[&str] = &["missing", "invalid", "malformed", "parameter"];
+
+impl OutputConfig {
+    /// Whether output from `tool` should be cleaned up
+    pub fn applies_to(&self, tool: &str) -> bool {
+        self.tools.iter().any(|t| t == tool)
+    }
+}
+
+impl RetryConfig {
+    /// Whether a failure with this message is worth retrying
+    pub fn is_retryable(&self, message: &str) -> bool {
+        let message = message.to_lowercase();
+        if NON_RETRYABLE_ERRORS.iter().any(|s| message.contains(s)) {
+            return false;
+        }
+        self.retryable_errors
+            .iter()
+            .any(|s| message.contains(&s.to_lowercase()))
+    }
+}
+
+/// `api_base` used when none is configured
+pub const DEFAULT_API_BASE: &str = "http://localhost:1234/v1";
+
 // Default value functions
 fn default_api_base() -> String {
-    "http://localhost:1234/v1".to_string()
+    DEFAULT_API_BASE.to_string()
 }
 
 fn default_model() -> String {
@@ -144,6 +464,27 @@ fn default_powertools_timeout() -> u64 {
     60
 }
 
+fn default_tool_timeout() -> u64 {
+    120
+}
+
+fn default_webfetch_timeout() -> u64 {
+    60
+}
+
+fn default_autosave_interval_secs() -> u64 {
+    60
+}
+
+/// Sub-agents run many tool calls of their own, so they get far longer than a single tool
+fn default_subagent_timeout() -> u64 {
+    600
+}
+
+fn default_read_timeout() -> u64 {
+    30
+}
+
 fn default_syntax_highlighting() -> bool {
     true
 }
@@ -152,10 +493,6 @@ fn default_max_output_lines() -> usize {
     1000
 }
 
-fn default_edit_approval() -> bool {
-    true
-}
-
 fn default_streaming() -> bool {
     true
 }
@@ -164,6 +501,14 @@ fn default_context_window() -> Option<usize> {
     Some(8192)
 }
 
+fn default_request_timeout_secs() -> u64 {
+    120
+}
+
+fn default_rate_limit_retries() -> u32 {
+    3
+}
+
 fn default_max_bash_output_chars() -> usize {
     50_000 // ~12,500 tokens
 }
@@ -176,9 +521,60 @@ fn default_read_warn_at_chars() -> usize {
     100_000 // ~25,000 tokens
 }
 
+fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_output_tools() -> Vec<String> {
+    vec!["bash".to_string(), "grep".to_string()]
+}
+
+fn default_output_max_bytes() -> usize {
+    30_000
+}
+
+fn default_collapse_repeated_lines() -> usize {
+    3
+}
+
+fn default_strip_ansi() -> bool {
+    true
+}
+
+fn default_dedent() -> bool {
+    true
+}
+
+fn default_max_retries() -> u32 {
+    1
+}
+
+fn default_retry_delay_ms() -> u64 {
+    500
+}
+
+fn default_retryable_errors() -> Vec<String> {
+    [
+        "Connection failed",
+        "Network error",
+        "Failed to read response body",
+        "HTTP error 429",
+        "HTTP error 502",
+        "HTTP error 503",
+        "HTTP error 504",
+        "Resource temporarily unavailable",
+        "Resource busy",
+        "being used by another process",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
 impl Default for LLMConfig {
     fn default() -> Self {
         Self {
+            provider: ProviderKind::default(),
             api_base: default_api_base(),
             api_key: None,
             model: default_model(),
@@ -186,10 +582,82 @@ impl Default for LLMConfig {
             max_tokens: default_max_tokens(),
             streaming: default_streaming(),
             context_window: default_context_window(),
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            request_timeout_secs: default_request_timeout_secs(),
+            rate_limit_retries: default_rate_limit_retries(),
+            profiles: BTreeMap::new(),
+            default_profile: None,
         }
     }
 }
 
+/// Name used for the base `[llm]` settings when listing profiles
+pub const BASE_PROFILE_NAME: &str = "default";
+
+impl LLMConfig {
+    /// Names of all selectable profiles, starting with the base settings
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names = vec![BASE_PROFILE_NAME.to_string()];
+        names.extend(
+            self.profiles
+                .keys()
+                .filter(|name| name.as_str() != BASE_PROFILE_NAME)
+                .cloned(),
+        );
+        names
+    }
+
+    /// Name of the profile active at startup
+    pub fn active_profile_name(&self) -> &str {
+        self.default_profile.as_deref().unwrap_or(BASE_PROFILE_NAME)
+    }
+
+    /// Effective settings for `name`, with unset profile fields inherited from the base section
+    pub fn resolve_profile(&self, name: &str) -> Result<LLMConfig> {
+        let base = LLMConfig {
+            profiles: BTreeMap::new(),
+            default_profile: None,
+            ..self.clone()
+        };
+
+        let Some(profile) = self.profiles.get(name) else {
+            if name == BASE_PROFILE_NAME {
+                return Ok(base);
+            }
+            anyhow::bail!(
+                "Unknown LLM profile '{}' (available: {})",
+                name,
+                self.profile_names().join(", ")
+            );
+        };
+
+        Ok(LLMConfig {
+            provider: profile.provider.unwrap_or(base.provider),
+            api_base: profile.api_base.clone().unwrap_or(base.api_base),
+            api_key: profile.api_key.clone().or(base.api_key),
+            model: profile.model.clone().unwrap_or(base.model),
+            temperature: profile.temperature.unwrap_or(base.temperature),
+            max_tokens: profile.max_tokens.or(base.max_tokens),
+            streaming: profile.streaming.unwrap_or(base.streaming),
+            context_window: profile.context_window.or(base.context_window),
+            stop: profile.stop.clone().unwrap_or(base.stop),
+            seed: profile.seed.or(base.seed),
+            vision: profile.vision.unwrap_or(base.vision),
+            request_timeout_secs: profile.request_timeout_secs.unwrap_or(base.request_timeout_secs),
+            rate_limit_retries: profile.rate_limit_retries.unwrap_or(base.rate_limit_retries),
+            profiles: BTreeMap::new(),
+            default_profile: None,
+        })
+    }
+
+    /// Effective settings for the startup profile
+    pub fn active(&self) -> Result<LLMConfig> {
+        self.resolve_profile(self.active_profile_name())
+    }
+}
+
 impl Default for TimeoutConfig {
     fn default() -> Self {
         Self {
@@ -197,6 +665,17 @@ impl Default for TimeoutConfig {
             git_timeout: default_git_timeout(),
             workshop_timeout: default_workshop_timeout(),
             powertools_timeout: default_powertools_timeout(),
+            default_tool_timeout: default_tool_timeout(),
+            per_tool: HashMap::new(),
+        }
+    }
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            autosave: AutoSave::default(),
+            autosave_interval_secs: default_autosave_interval_secs(),
         }
     }
 }
@@ -206,7 +685,8 @@ impl Default for UIConfig {
         Self {
             syntax_highlighting: default_syntax_highlighting(),
             max_output_lines: default_max_output_lines(),
-            edit_approval: default_edit_approval(),
+            edit_approval: EditApproval::default(),
+            theme: ThemeConfig::default(),
         }
     }
 }
@@ -218,10 +698,43 @@ impl Default for ToolsConfig {
             max_bash_output_chars: default_max_bash_output_chars(),
             max_read_output_chars: default_max_read_output_chars(),
             read_warn_at_chars: default_read_warn_at_chars(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            auto_allow_read_only: false,
+            retry: RetryConfig::default(),
+            output: OutputConfig::default(),
         }
     }
 }
 
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            tools: default_output_tools(),
+            max_bytes: default_output_max_bytes(),
+            collapse_repeated_lines: default_collapse_repeated_lines(),
+            strip_ansi: default_strip_ansi(),
+            dedent: default_dedent(),
+        }
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            delay_ms: default_retry_delay_ms(),
+            retryable_errors: default_retryable_errors(),
+        }
+    }
+}
+
+impl ToolsConfig {
+    /// Cache TTL as a duration, or None when expiry is disabled
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        (self.cache_ttl_secs > 0).then(|| Duration::from_secs(self.cache_ttl_secs))
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -229,6 +742,8 @@ impl Default for Config {
             timeouts: TimeoutConfig::default(),
             ui: UIConfig::default(),
             tools: ToolsConfig::default(),
+            agent: AgentConfig::default(),
+            session: SessionConfig::default(),
         }
     }
 }
@@ -237,50 +752,143 @@ impl Config {
     /// Load configuration with proper hierarchy and merging
     ///
     /// **Config Priority (highest to lowest):**
-    /// 1. `./synthia.toml` (project-level config - HIGHEST priority)
-    /// 2. `~/.config/synthia/config.toml` (global user config)
-    /// 3. Hardcoded defaults (fallback)
+    /// 1. `<project root>/.synthia/config.toml` (project-level config - HIGHEST priority)
+    /// 2. `./synthia.toml` (legacy project-level config)
+    /// 3. `~/.config/synthia/config.toml` (global user config)
+    /// 4. Hardcoded defaults (fallback)
     ///
     /// **Merging behavior:**
-    /// - Project config overrides specific fields in global config
-    /// - Missing fields use global config or defaults
-    ///
-    /// **Single Source of Truth:**
-    /// - Global config: `~/.config/synthia/config.toml`
-    /// - Project config: `./synthia.toml` (optional, overrides global)
-    /// - No other config locations are checked
+    /// - Files are merged field by field, including nested tables like `[llm.profiles.x]`
+    ///   and `[ui.theme]`, so a project file only needs the settings it changes
+    /// - Missing fields use lower-priority files or defaults
     pub fn load() -> Result<Self> {
-        // Start with defaults
-        let mut config = Config::default();
+        let config = Self::load_from_paths(&Self::config_sources())?;
+        let active = config.llm.active()?;
 
-        // Load global config if it exists (lowest priority override)
-        if let Some(global_path) = Self::global_config_path() {
-            if global_path.exists() {
-                tracing::info!("Loading global config from: {}", global_path.display());
-                let global_config = Self::load_from_file(&global_path)?;
-                config = Self::merge_configs(config, global_config);
-            }
+        tracing::info!(
+            "Final config: profile={}, model={}, api_base={}",
+            config.llm.active_profile_name(),
+            active.model,
+            active.api_base
+        );
+
+        Ok(config)
+    }
+
+    /// Config files that exist, lowest priority first
+    pub fn config_sources() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Self::global_config_path().into_iter().collect();
+        paths.push(Self::project_config_path());
+        paths.push(Self::project_dir_config_path());
+        paths.retain(|path| path.exists());
+        paths
+    }
+
+    /// Merge `paths` (lowest priority first) over the defaults and validate the result
+    fn load_from_paths(paths: &[PathBuf]) -> Result<Self> {
+        let mut merged = toml::Table::new();
+        for path in paths {
+            tracing::info!("Loading config from: {}", path.display());
+            merge_tables(&mut merged, Self::load_table(path)?);
         }
 
-        // Load project config if it exists (highest priority override)
-        let project_path = Self::project_config_path();
-        if project_path.exists() {
-            tracing::info!("Loading project config from: {}", project_path.display());
-            let project_config = Self::load_from_file(&project_path)?;
-            config = Self::merge_configs(config, project_config);
+        let sources: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+        let config: Config = toml::Value::Table(merged)
+            .try_into()
+            .with_context(|| format!("Invalid config (merged from: {})", sources.join(", ")))?;
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// The effective config as TOML, with API keys redacted so it's safe to share
+    pub fn to_effective_toml(&self) -> Result<String> {
+        let mut config = self.clone();
+        let redact = |key: &mut Option<String>| {
+            if key.is_some() {
+                *key = Some("<redacted>".to_string());
+            }
+        };
+        redact(&mut config.llm.api_key);
+        for profile in config.llm.profiles.values_mut() {
+            redact(&mut profile.api_key);
         }
 
-        tracing::info!(
-            "Final config: model={}, api_base={}",
-            config.llm.model,
-            config.llm.api_base
+        toml::to_string_pretty(&config).context("Failed to serialize config")
+    }
+
+    /// Check invariants serde can't express (URL format, value ranges, profile names)
+    /// Every problem is reported at once, naming the field, the bad value, and a fix
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        let llm = &self.llm;
+
+        check_llm_fields(
+            "llm",
+            Some(&llm.api_base),
+            Some(llm.temperature),
+            llm.max_tokens,
+            llm.context_window,
+            Some(llm.request_timeout_secs),
+            &mut problems,
         );
+        for (name, profile) in &llm.profiles {
+            check_llm_fields(
+                &format!("llm.profiles.{}", name),
+                profile.api_base.as_ref(),
+                profile.temperature,
+                profile.max_tokens,
+                profile.context_window,
+                profile.request_timeout_secs,
+                &mut problems,
+            );
+        }
 
-        Ok(config)
+        if let Some(name) = &llm.default_profile {
+            if !llm.profile_names().contains(name) {
+                problems.push(format!(
+                    "llm.default_profile = {:?}: no such profile (available: {}; add a [llm.profiles.{}] section or fix the name)",
+                    name,
+                    llm.profile_names().join(", "),
+                    name
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid configuration:\n  - {}", problems.join("\n  - "))
+        }
+    }
+
+    /// Write a commented config template to the global path if no config file exists yet
+    /// Returns the path written so the user can be told where to find it
+    pub fn write_template_if_missing() -> Result<Option<PathBuf>> {
+        let Some(global_path) = Self::global_config_path() else {
+            return Ok(None);
+        };
+        if !Self::config_sources().is_empty() {
+            return Ok(None);
+        }
+
+        write_config_template(&global_path)?;
+        tracing::info!("Wrote config template to: {}", global_path.display());
+        Ok(Some(global_path))
     }
 
-    /// Load config from a specific file
-    fn load_from_file(path: &Path) -> Result<Self> {
+    /// Write the commented config template to the global path (`synthia init`)
+    /// Refuses to replace an existing file unless `force` is set; returns the path written
+    pub fn init(force: bool) -> Result<PathBuf> {
+        let global_path = Self::global_config_path()
+            .context("Could not determine the home directory for ~/.config/synthia/config.toml")?;
+        init_config(&global_path, force)?;
+        Ok(global_path)
+    }
+
+    /// Read a config file as a raw TOML table (merged before deserializing so
+    /// fields a file doesn't mention don't override lower-priority files)
+    fn load_table(path: &Path) -> Result<toml::Table> {
         let contents = fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
 
@@ -288,13 +896,6 @@ impl Config {
             .with_context(|| format!("Failed to parse config file: {}", path.display()))
     }
 
-    /// Merge two configs (override takes precedence for non-default values)
-    fn merge_configs(_base: Config, override_config: Config) -> Config {
-        // For now, override completely replaces base
-        // TODO: Could implement field-level merging if needed
-        override_config
-    }
-
     /// Get the global config path (~/.config/synthia/config.toml)
     fn global_config_path() -> Option<PathBuf> {
         dirs::home_dir().map(|home| home.join(".config").join("synthia").join("config.toml"))
@@ -305,6 +906,14 @@ impl Config {
         PathBuf::from("synthia.toml")
     }
 
+    /// Get the per-project config path (.synthia/config.toml in the project root)
+    fn project_dir_config_path() -> PathBuf {
+        crate::project::detect_project_root()
+            .unwrap_or_else(|_| PathBuf::from("."))
+            .join(".synthia")
+            .join("config.toml")
+    }
+
     /// DEPRECATED: Use global_config_path() or project_config_path()
     #[deprecated(note = "Use global_config_path() or project_config_path() instead")]
     #[allow(dead_code)]
@@ -349,6 +958,97 @@ impl Config {
     }
 }
 
+/// Deep-merge `overlay` into `base`: nested tables merge key by key, any other
+/// value in `overlay` (including arrays) replaces the one in `base`
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Commented template written on first run (the same file shipped as config.toml.example)
+const CONFIG_TEMPLATE: &str = include_str!("../config.toml.example");
+
+fn write_config_template(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(path, CONFIG_TEMPLATE)
+        .with_context(|| format!("Failed to write config template: {}", path.display()))
+}
+
+fn init_config(path: &Path, force: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!(
+            "Config already exists at {} (use `synthia init --force` to overwrite it)",
+            path.display()
+        );
+    }
+    write_config_template(path)
+}
+
+/// Validate the LLM settings under `section` (either [llm] or one of its profiles)
+/// Fields that are None were not set and are skipped
+fn check_llm_fields(
+    section: &str,
+    api_base: Option<&String>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    context_window: Option<usize>,
+    request_timeout_secs: Option<u64>,
+    problems: &mut Vec<String>,
+) {
+    if let Some(api_base) = api_base {
+        let is_http_url = url::Url::parse(api_base)
+            .map(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .unwrap_or(false);
+        if !is_http_url {
+            problems.push(format!(
+                "{}.api_base = {:?}: not an http(s) URL (e.g. api_base = \"http://localhost:1234/v1\")",
+                section, api_base
+            ));
+        }
+    }
+
+    if let Some(temperature) = temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            problems.push(format!(
+                "{}.temperature = {}: must be between 0.0 and 2.0 (e.g. temperature = 0.7)",
+                section, temperature
+            ));
+        }
+    }
+
+    if max_tokens == Some(0) {
+        problems.push(format!(
+            "{}.max_tokens = 0: must be greater than 0 (e.g. max_tokens = 4096, or remove it for no limit)",
+            section
+        ));
+    }
+
+    if context_window == Some(0) {
+        problems.push(format!(
+            "{}.context_window = 0: must be greater than 0 (set it to your model's context size, e.g. context_window = 8192)",
+            section
+        ));
+    }
+
+    if request_timeout_secs == Some(0) {
+        problems.push(format!(
+            "{}.request_timeout_secs = 0: must be greater than 0 (e.g. request_timeout_secs = 120)",
+            section
+        ));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -368,6 +1068,55 @@ mod tests {
         assert_eq!(config.timeouts.powertools_timeout, 60);
     }
 
+    #[test]
+    fn test_per_tool_timeouts() {
+        let toml_str = r#"
+            [timeouts]
+            bash_timeout = 10
+            webfetch = 15
+            list_directory = 3
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let timeouts = &config.timeouts;
+        assert_eq!(timeouts.bash_timeout, 10);
+        assert_eq!(timeouts.timeout_for("webfetch"), Duration::from_secs(15));
+        assert_eq!(timeouts.timeout_for("list_directory"), Duration::from_secs(3));
+        // Tools with their own timeout get a grace period on top
+        assert_eq!(timeouts.timeout_for("bash"), Duration::from_secs(10 + TIMEOUT_GRACE_SECS));
+        assert_eq!(timeouts.timeout_for("read"), Duration::from_secs(30));
+        assert_eq!(timeouts.timeout_for("edit"), Duration::from_secs(120));
+        assert!(!timeouts.per_tool.contains_key("bash_timeout"));
+
+        // A misspelled tool name is reported rather than silently ignored
+        let config: Config = toml::from_str("[timeouts]\nwebfetch = 15\nwebfecth = 20\nbash_timout = 5\n").unwrap();
+        let tools = vec!["bash".to_string(), "webfetch".to_string()];
+        assert_eq!(config.timeouts.unknown_tools(&tools), vec!["bash_timout", "webfecth"]);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let retry = RetryConfig::default();
+        assert!(retry.is_retryable("Connection failed: connection refused"));
+        assert!(retry.is_retryable("HTTP error 503: Service Unavailable"));
+        assert!(!retry.is_retryable("HTTP error 404: Not Found"));
+        // Validation errors are never retried, even when a signature matches
+        assert!(!retry.is_retryable("Network error: invalid header value"));
+
+        let config: Config = toml::from_str(
+            r#"
+            [tools.retry]
+            max_retries = 3
+            retryable_errors = ["database is locked"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.tools.retry.max_retries, 3);
+        assert_eq!(config.tools.retry.delay_ms, 500);
+        assert!(config.tools.retry.is_retryable("Error: DATABASE IS LOCKED"));
+        assert!(!config.tools.retry.is_retryable("Connection failed"));
+    }
+
     #[test]
     fn test_toml_serialization() {
         let config = Config::default();
@@ -392,6 +1141,254 @@ mod tests {
         assert_eq!(config.llm.temperature, 0.7);
     }
 
+    #[test]
+    fn test_llm_profiles_inherit_base_settings() {
+        let toml_str = r#"
+            [llm]
+            model = "local-model"
+            temperature = 0.5
+            default_profile = "fast"
+
+            [llm.profiles.fast]
+            model = "small-model"
+            max_tokens = 512
+            stop = ["</answer>"]
+            seed = 7
+
+            [llm.profiles.remote]
+            api_base = "https://api.example.com/v1"
+            api_key = "secret"
+
+            [llm.profiles.gemini]
+            provider = "gemini"
+            model = "gemini-2.5-flash"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.llm.profile_names(), vec!["default", "fast", "gemini", "remote"]);
+
+        let fast = config.llm.active().unwrap();
+        assert_eq!(fast.model, "small-model");
+        assert_eq!(fast.max_tokens, Some(512));
+        assert_eq!(fast.temperature, 0.5);
+        assert_eq!(fast.api_base, "http://localhost:1234/v1");
+        assert_eq!(fast.stop, vec!["</answer>"]);
+        assert_eq!(fast.seed, Some(7));
+
+        let remote = config.llm.resolve_profile("remote").unwrap();
+        assert_eq!(remote.model, "local-model");
+        assert_eq!(remote.api_key.as_deref(), Some("secret"));
+        assert_eq!(remote.provider, ProviderKind::OpenAI);
+
+        let gemini = config.llm.resolve_profile("gemini").unwrap();
+        assert_eq!(gemini.provider, ProviderKind::Gemini);
+        assert_eq!(gemini.model, "gemini-2.5-flash");
+
+        let base = config.llm.resolve_profile("default").unwrap();
+        assert_eq!(base.model, "local-model");
+        assert!(base.stop.is_empty());
+        assert_eq!(base.seed, None);
+        assert!(base.profiles.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_default_profile_is_rejected() {
+        let toml_str = r#"
+            [llm]
+            default_profile = "missing"
+
+            [llm.profiles.fast]
+            model = "small-model"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let err = config.llm.active().unwrap_err().to_string();
+        assert!(err.contains("'missing'"));
+        assert!(err.contains("default, fast"));
+    }
+
+    #[test]
+    fn test_validate_accepts_defaults_and_template() {
+        assert!(Config::default().validate().is_ok());
+
+        let template: Config = toml::from_str(CONFIG_TEMPLATE).unwrap();
+        template.validate().unwrap();
+    }
+
+    #[test]
+    fn test_validate_reports_field_value_and_fix() {
+        let toml_str = r#"
+            [llm]
+            api_base = "localhost:1234"
+            temperature = 3.5
+            max_tokens = 0
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("llm.api_base = \"localhost:1234\": not an http(s) URL"));
+        assert!(err.contains("llm.temperature = 3.5: must be between 0.0 and 2.0"));
+        assert!(err.contains("llm.max_tokens = 0"));
+        assert!(err.contains("e.g. temperature = 0.7"));
+        assert!(!err.contains("context_window"));
+    }
+
+    #[test]
+    fn test_validate_checks_profiles() {
+        let toml_str = r#"
+            [llm]
+            default_profile = "fats"
+
+            [llm.profiles.fast]
+            context_window = 0
+            request_timeout_secs = 0
+
+            [llm.profiles.remote]
+            api_base = "ftp://example.com"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("llm.profiles.fast.context_window = 0"));
+        assert!(err.contains("llm.profiles.fast.request_timeout_secs = 0: must be greater than 0"));
+        assert!(err.contains("llm.profiles.remote.api_base = \"ftp://example.com\""));
+        assert!(err.contains("llm.default_profile = \"fats\": no such profile (available: default, fast, remote"));
+    }
+
+    #[test]
+    fn test_write_config_template() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("synthia").join("config.toml");
+
+        write_config_template(&path).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("# Synthia Configuration File"));
+        assert!(toml::from_str::<Config>(&contents).is_ok());
+    }
+
+    #[test]
+    fn test_init_config_parses_back_and_respects_force() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("synthia").join("config.toml");
+
+        init_config(&path, false).unwrap();
+        let config = Config::load_from_paths(&[path.clone()]).unwrap();
+        assert_eq!(config.llm.api_base, "http://localhost:1234/v1");
+
+        // Every field the default config serializes is documented in the template
+        let defaults = toml::to_string(&Config::default()).unwrap();
+        let template = fs::read_to_string(&path).unwrap();
+        for line in defaults.lines().filter(|line| line.contains(" = ")) {
+            let key = line.split(" = ").next().unwrap();
+            assert!(template.contains(&format!("{} = ", key)), "template doesn't document `{}`", key);
+        }
+
+        fs::write(&path, "[llm]\nmodel = \"mine\"\n").unwrap();
+        let err = init_config(&path, false).unwrap_err().to_string();
+        assert!(err.contains("--force"));
+        assert!(fs::read_to_string(&path).unwrap().contains("mine"));
+
+        init_config(&path, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), CONFIG_TEMPLATE);
+    }
+
+    #[test]
+    fn test_project_config_overrides_global_field_by_field() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = temp_dir.path().join("global.toml");
+        let project = temp_dir.path().join("project.toml");
+        fs::write(
+            &global,
+            r#"
+            [llm]
+            model = "global-model"
+            temperature = 0.2
+
+            [llm.profiles.remote]
+            api_base = "https://api.example.com/v1"
+            model = "remote-model"
+
+            [timeouts]
+            bash_timeout = 10
+            webfetch = 20
+
+            [ui.theme]
+            name = "mono"
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            &project,
+            r#"
+            [llm]
+            model = "project-model"
+
+            [llm.profiles.remote]
+            model = "bigger-remote-model"
+
+            [timeouts]
+            git_timeout = 5
+
+            [ui.theme]
+            error = "red"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load_from_paths(&[global, project]).unwrap();
+        // Project wins where both set a field
+        assert_eq!(config.llm.model, "project-model");
+        assert_eq!(config.llm.profiles["remote"].model.as_deref(), Some("bigger-remote-model"));
+        // Global fills in what the project leaves out, even inside the same section
+        assert_eq!(config.llm.temperature, 0.2);
+        assert_eq!(
+            config.llm.profiles["remote"].api_base.as_deref(),
+            Some("https://api.example.com/v1")
+        );
+        assert_eq!(config.timeouts.bash_timeout, 10);
+        assert_eq!(config.timeouts.per_tool["webfetch"], 20);
+        assert_eq!(config.ui.theme.name.as_deref(), Some("mono"));
+        assert_eq!(config.timeouts.git_timeout, 5);
+        assert_eq!(config.ui.theme.error.as_deref(), Some("red"));
+        // Built-in defaults fill in the rest
+        assert_eq!(config.llm.max_tokens, Some(4096));
+        assert_eq!(config.timeouts.workshop_timeout, 30);
+    }
+
+    #[test]
+    fn test_merged_config_is_validated_and_names_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let global = temp_dir.path().join("global.toml");
+        let project = temp_dir.path().join("project.toml");
+        fs::write(&global, "[llm]\ntemperature = 0.5\n").unwrap();
+        fs::write(&project, "[llm]\ntemperature = 5.0\n").unwrap();
+
+        let err = Config::load_from_paths(&[global.clone(), project.clone()]).unwrap_err();
+        assert!(err.to_string().contains("llm.temperature = 5"));
+
+        fs::write(&project, "[llm]\ntemperature = \"hot\"\n").unwrap();
+        let err = format!("{:#}", Config::load_from_paths(&[global, project.clone()]).unwrap_err());
+        assert!(err.contains(&project.display().to_string()));
+    }
+
+    #[test]
+    fn test_effective_toml_redacts_api_keys() {
+        let mut config = Config::default();
+        config.llm.api_key = Some("sk-secret".to_string());
+        config.llm.profiles.insert(
+            "remote".to_string(),
+            LLMProfileConfig {
+                api_key: Some("sk-other".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let printed = config.to_effective_toml().unwrap();
+        assert!(!printed.contains("sk-"));
+        assert!(printed.contains("<redacted>"));
+        assert!(toml::from_str::<Config>(&printed).is_ok());
+    }
+
     #[test]
     fn test_invalid_toml() {
         let toml_str = r#"
@@ -438,10 +1435,23 @@ mod tests {
         assert_eq!(config.llm.model, "google/gemma-3-12b");
     }
 
+    #[test]
+    fn test_session_autosave_from_toml() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.session.autosave, AutoSave::OnComplete);
+
+        let config: Config = toml::from_str("[session]\nautosave = \"throttled\"\nautosave_interval_secs = 300\n").unwrap();
+        assert_eq!(config.session.autosave, AutoSave::Throttled);
+        assert_eq!(config.session.autosave_interval(), Duration::from_secs(300));
+
+        let config: Config = toml::from_str("[session]\nautosave = \"off\"\n").unwrap();
+        assert_eq!(config.session.autosave, AutoSave::Off);
+    }
+
     #[test]
     fn test_edit_approval_default() {
         let config = UIConfig::default();
-        assert_eq!(config.edit_approval, true); // Enabled by default
+        assert_eq!(config.edit_approval, EditApproval::Always); // Enabled by default
     }
 
     #[test]
@@ -452,7 +1462,18 @@ mod tests {
         "#;
 
         let config: Config = toml::from_str(toml_str).unwrap();
-        assert_eq!(config.ui.edit_approval, false);
+        assert_eq!(config.ui.edit_approval, EditApproval::Never);
+
+        for (value, expected) in [
+            ("true", EditApproval::Always),
+            ("\"always\"", EditApproval::Always),
+            ("\"never\"", EditApproval::Never),
+            ("\"first-time\"", EditApproval::FirstTime),
+        ] {
+            let config: Config = toml::from_str(&format!("[ui]\nedit_approval = {}", value)).unwrap();
+            assert_eq!(config.ui.edit_approval, expected, "{}", value);
+        }
+        assert!(toml::from_str::<Config>("[ui]\nedit_approval = \"sometimes\"").is_err());
     }
 }
 
@@ -8,6 +8,9 @@ const SUMMARY_THRESHOLD: usize = 80;
 #[allow(dead_code)]
 const SUMMARY_CHUNK_SIZE: usize = 20;
 
+/// Rough token cost of one attached image
+const IMAGE_TOKEN_ESTIMATE: usize = 1000;
+
 pub struct ContextManager {
     messages: Vec<Message>,
     max_messages: usize,
@@ -19,6 +22,14 @@ pub struct ContextManager {
     token_threshold_percent: f32,    // Auto-compact at this % (default 0.8)
 }
 
+/// Outcome of a manual compaction, with token counts estimated from message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionResult {
+    pub messages_summarized: usize,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenStats {
     pub current: usize,
@@ -44,6 +55,11 @@ impl ContextManager {
         self.messages.push(message);
     }
 
+    /// Use a different provider for summarization (e.g. after switching LLM profiles)
+    pub fn set_llm_provider(&mut self, llm_provider: Arc<dyn LLMProvider>) {
+        self.llm_provider = llm_provider;
+    }
+
     /// Set the max token limit for this model
     pub fn set_max_token_limit(&mut self, limit: usize) {
         self.max_token_limit = limit;
@@ -70,6 +86,28 @@ impl ContextManager {
         }
     }
 
+    /// Estimate the token count from message text (~4 chars per token)
+    /// Used when the provider doesn't report usage, and for freshly loaded sessions
+    pub fn estimate_token_count(&mut self) {
+        self.update_token_count(self.estimated_tokens(), 0);
+    }
+
+    fn estimated_tokens(&self) -> usize {
+        let chars: usize = self
+            .messages
+            .iter()
+            .flat_map(|msg| &msg.content)
+            .map(|block| match block {
+                ContentBlock::Text { text } | ContentBlock::Thinking { text } => text.len(),
+                ContentBlock::ToolUse { name, input, .. } => name.len() + input.to_string().len(),
+                ContentBlock::ToolResult { content, .. } => content.len(),
+                // Providers bill images by resolution, not payload size; ~1k tokens is typical
+                ContentBlock::Image { .. } => IMAGE_TOKEN_ESTIMATE * 4,
+            })
+            .sum();
+        chars / 4
+    }
+
     /// Check if auto-compaction should trigger
     pub fn should_compact(&self) -> bool {
         let threshold = (self.max_token_limit as f32 * self.token_threshold_percent) as usize;
@@ -109,14 +147,33 @@ impl ContextManager {
         Ok(())
     }
 
-    async fn summarize_oldest_messages(&mut self) -> Result<()> {
+    /// Summarize older messages now, whatever the token usage
+    ///
+    /// Returns None when the conversation is too short to have anything to summarize.
+    pub async fn compact(&mut self) -> Result<Option<CompactionResult>> {
+        let tokens_before = self.estimated_tokens();
+        let messages_summarized = self.summarize_oldest_messages().await?;
+        if messages_summarized == 0 {
+            return Ok(None);
+        }
+
+        self.estimate_token_count();
+        Ok(Some(CompactionResult {
+            messages_summarized,
+            tokens_before,
+            tokens_after: self.current_token_count,
+        }))
+    }
+
+    /// Replace the oldest messages with an LLM-written summary, returning how many were replaced
+    async fn summarize_oldest_messages(&mut self) -> Result<usize> {
         // Keep first message (system) and last 60%
         let keep_recent = (self.messages.len() as f32 * 0.6) as usize;
         let summarize_start = 1; // Skip system message
         let summarize_end = self.messages.len() - keep_recent;
 
         if summarize_end <= summarize_start {
-            return Ok(()); // Nothing to summarize
+            return Ok(0); // Nothing to summarize
         }
 
         let to_summarize = &self.messages[summarize_start..summarize_end];
@@ -144,6 +201,10 @@ impl ContextManager {
                     streaming: false,
                     reasoning_level: "medium".to_string(),
                     context_window: 8192, // Default context window
+                    stop: Vec::new(),
+                    seed: None,
+                    vision: false,
+                    response_format: None,
                 },
             )
             .await?;
@@ -177,7 +238,7 @@ impl ContextManager {
             summarize_end - summarize_start
         );
 
-        Ok(())
+        Ok(summarize_end - summarize_start)
     }
 
     fn format_messages_for_summary(&self, messages: &[Message]) -> String {
@@ -193,23 +254,26 @@ impl ContextManager {
                 let content = msg
                     .content
                     .iter()
-                    .map(|block| match block {
-                        ContentBlock::Text { text } => text.clone(),
+                    .filter_map(|block| match block {
+                        ContentBlock::Text { text } => Some(text.clone()),
                         ContentBlock::ToolUse { name, .. } => {
-                            format!("[Called tool: {}]", name)
+                            Some(format!("[Called tool: {}]", name))
                         }
                         ContentBlock::ToolResult {
                             content, is_error, ..
                         } => {
                             if *is_error {
-                                format!("[Tool error: {}]", content)
+                                Some(format!("[Tool error: {}]", content))
                             } else {
-                                format!(
+                                Some(format!(
                                     "[Tool result: {}]",
                                     content.chars().take(100).collect::<String>()
-                                )
+                                ))
                             }
                         }
+                        // Reasoning isn't worth summarizing
+                        ContentBlock::Thinking { .. } => None,
+                        ContentBlock::Image { .. } => Some("[Image]".to_string()),
                     })
                     .collect::<Vec<_>>()
                     .join(" ");
@@ -282,6 +346,26 @@ mod tests {
         assert_eq!(context_manager.get_messages().len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_estimate_token_count() {
+        let provider = Arc::new(MockLLMProvider);
+        let mut context_manager = ContextManager::new(provider);
+        context_manager.set_max_token_limit(1000);
+
+        context_manager.add_message(Message {
+            role: Role::User,
+            content: vec![ContentBlock::Text {
+                text: "x".repeat(400),
+            }],
+        });
+        context_manager.estimate_token_count();
+
+        let stats = context_manager.get_token_stats();
+        assert_eq!(stats.current, 100);
+        assert_eq!(stats.max, 1000);
+        assert!((stats.usage_percent - 10.0).abs() < f32::EPSILON);
+    }
+
     #[tokio::test]
     async fn test_compact_at_token_threshold() {
         let provider = Arc::new(MockLLMProvider);
@@ -325,6 +409,45 @@ mod tests {
         assert!(context_manager.get_messages().len() < initial_count);
     }
 
+    #[tokio::test]
+    async fn test_manual_compact_below_threshold() {
+        let provider = Arc::new(MockLLMProvider);
+        let mut context_manager = ContextManager::new(provider);
+        context_manager.set_max_token_limit(100_000);
+
+        context_manager.add_message(Message {
+            role: Role::System,
+            content: vec![ContentBlock::Text {
+                text: "System prompt".to_string(),
+            }],
+        });
+
+        // Too short to summarize anything
+        assert_eq!(context_manager.compact().await.unwrap(), None);
+
+        for i in 0..10 {
+            context_manager.add_message(Message {
+                role: Role::User,
+                content: vec![ContentBlock::Text {
+                    text: format!("Message {} {}", i, "x".repeat(400)),
+                }],
+            });
+        }
+        assert!(!context_manager.should_compact());
+
+        let result = context_manager.compact().await.unwrap().unwrap();
+        assert_eq!(result.messages_summarized, 4);
+        assert!(result.tokens_after < result.tokens_before);
+        assert_eq!(context_manager.get_token_stats().current, result.tokens_after);
+
+        let messages = context_manager.get_messages();
+        assert_eq!(messages.len(), 8);
+        assert!(matches!(
+            &messages[1].content[0],
+            ContentBlock::Text { text } if text.starts_with("[Conversation Summary]")
+        ));
+    }
+
     #[tokio::test]
     async fn test_hard_truncate_at_max() {
         let provider = Arc::new(MockLLMProvider);
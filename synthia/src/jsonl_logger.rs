@@ -39,6 +39,29 @@ pub struct TokenUsageLog {
     pub output_tokens: u32,
 }
 
+/// A structured event in an agent run, written as one JSONL line next to the turn entries
+/// (e.g. `{"timestamp":"...","event":"tool_call","name":"bash","duration_ms":42,"success":true}`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LogEvent {
+    /// The user sent a message
+    UserMessage { length: usize },
+    /// The model replied; `length` counts the characters of its text (not reasoning)
+    AssistantText { length: usize, tool_calls: usize },
+    /// A tool finished; `success` is false for errors and failed results
+    ToolCall { name: String, duration_ms: u64, success: bool },
+    /// Tokens used by one model request
+    TurnUsage { input_tokens: u32, output_tokens: u32 },
+}
+
+/// A `LogEvent` with the time it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEventEntry {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: LogEvent,
+}
+
 pub struct JsonlLogger {
     #[allow(dead_code)]
     project_name: String,
@@ -61,6 +84,19 @@ impl JsonlLogger {
         })
     }
 
+    /// Create a logger writing to `log_dir` instead of the per-project directory
+    #[allow(dead_code)]
+    pub fn with_dir(log_dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&log_dir)?;
+
+        Ok(Self {
+            project_name: String::new(),
+            log_dir,
+            current_log_file: None,
+            max_file_size: MAX_LOG_FILE_SIZE,
+        })
+    }
+
     /// Get the base log directory for all projects
     pub fn get_projects_log_dir() -> PathBuf {
         let home = std::env::var("HOME").expect("HOME environment variable not set");
@@ -96,6 +132,32 @@ impl JsonlLogger {
 
     /// Log a turn (request/response pair)
     pub fn log_turn(&mut self, entry: JsonlEntry) -> std::io::Result<()> {
+        self.append(&entry)
+    }
+
+    /// Log a structured event, timestamped now
+    pub fn log_event(&mut self, event: LogEvent) -> std::io::Result<()> {
+        self.append(&LogEventEntry {
+            timestamp: Utc::now(),
+            event,
+        })
+    }
+
+    /// Events recorded in this logger's files, oldest first (turn entries are skipped)
+    #[allow(dead_code)]
+    pub fn read_events(&self) -> std::io::Result<Vec<LogEventEntry>> {
+        let mut events = Vec::new();
+        for file in self.list_log_files()? {
+            for line in fs::read_to_string(file)?.lines() {
+                if let Ok(entry) = serde_json::from_str::<LogEventEntry>(line) {
+                    events.push(entry);
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn append(&mut self, record: &impl Serialize) -> std::io::Result<()> {
         let log_file = self.get_or_create_log_file()?;
 
         let file = OpenOptions::new()
@@ -104,7 +166,7 @@ impl JsonlLogger {
             .open(&log_file)?;
 
         let mut writer = BufWriter::new(file);
-        let json = serde_json::to_string(&entry)?;
+        let json = serde_json::to_string(record)?;
         writeln!(writer, "{}", json)?;
         writer.flush()?;
 
@@ -182,6 +244,30 @@ mod tests {
         assert_eq!(deserialized.token_usage.output_tokens, 15);
     }
 
+    #[test]
+    fn test_log_event_serialization() {
+        let entry = LogEventEntry {
+            timestamp: Utc::now(),
+            event: LogEvent::ToolCall {
+                name: "bash".to_string(),
+                duration_ms: 42,
+                success: true,
+            },
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["event"], "tool_call");
+        assert_eq!(json["name"], "bash");
+        assert_eq!(json["duration_ms"], 42);
+        assert!(json["timestamp"].is_string());
+
+        let parsed: LogEventEntry = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.event, entry.event);
+
+        // Turn entries aren't mistaken for events
+        assert!(serde_json::from_str::<LogEventEntry>(r#"{"timestamp":"2025-01-01T00:00:00Z","request":{}}"#).is_err());
+    }
+
     #[test]
     fn test_logger_creation() {
         use std::sync::Mutex;
@@ -0,0 +1,668 @@
+use super::provider::{GenerationConfig, LLMProvider, LLMResponse, StreamEvent, StreamResult};
+use super::response_format::{validate_stream, ResponseFormat};
+use crate::types::{ContentBlock, Message, Role, StopReason, TokenUsage};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use reqwest::Client;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Endpoint used when `llm.api_base` is left at its (OpenAI-compatible) default
+pub const DEFAULT_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Environment variables checked for an API key when `llm.api_key` isn't set
+pub const API_KEY_ENV_VARS: &[&str] = &["GEMINI_API_KEY", "GOOGLE_API_KEY"];
+
+/// JSON Schema keywords Gemini's function and response schemas reject
+const UNSUPPORTED_SCHEMA_KEYS: &[&str] = &["$schema", "additionalProperties"];
+
+/// Google Gemini via the `generateContent` REST API
+pub struct GeminiProvider {
+    client: Client,
+    api_base: String,
+    api_key: String,
+    request_timeout: Duration,
+}
+
+impl GeminiProvider {
+    pub fn new(api_base: String, api_key: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_key,
+            request_timeout: Duration::from_secs(120),
+        }
+    }
+
+    /// Give up on the server after `timeout`: to connect, for a whole non-streaming
+    /// response, for the first streamed bytes, and between streamed chunks
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// The API key from config, falling back to `GEMINI_API_KEY` / `GOOGLE_API_KEY`
+    pub fn resolve_api_key(configured: Option<&str>) -> Option<String> {
+        configured
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+            .or_else(|| {
+                API_KEY_ENV_VARS
+                    .iter()
+                    .find_map(|var| std::env::var(var).ok().filter(|key| !key.is_empty()))
+            })
+    }
+
+    /// Split our messages into Gemini's `systemInstruction` and `contents`.
+    /// Tool results need the function's name, which Gemini matches on instead of a call id
+    fn convert_messages(messages: &[Message], vision: bool) -> (Option<Value>, Vec<Value>) {
+        let mut system_parts = Vec::new();
+        let mut contents = Vec::new();
+        let mut tool_names: HashMap<&str, &str> = HashMap::new();
+
+        for msg in messages {
+            let mut parts = Vec::new();
+
+            for block in &msg.content {
+                match block {
+                    ContentBlock::Text { text } => parts.push(json!({ "text": text })),
+                    ContentBlock::ToolUse { id, name, input } => {
+                        tool_names.insert(id, name);
+                        parts.push(json!({ "functionCall": { "name": name, "args": input } }));
+                    }
+                    ContentBlock::ToolResult {
+                        tool_use_id,
+                        content,
+                        is_error,
+                    } => {
+                        let name = tool_names.get(tool_use_id.as_str()).copied().unwrap_or("unknown");
+                        let response = if *is_error {
+                            json!({ "error": content })
+                        } else {
+                            json!({ "content": content })
+                        };
+                        parts.push(json!({ "functionResponse": { "name": name, "response": response } }));
+                    }
+                    ContentBlock::Thinking { .. } => {
+                        // Reasoning is for the user; replaying it wastes context
+                    }
+                    ContentBlock::Image { media_type, data } => {
+                        if vision {
+                            parts.push(json!({ "inlineData": { "mimeType": media_type, "data": data } }));
+                        } else {
+                            parts.push(json!({ "text": "[image omitted: vision is disabled for this model]" }));
+                        }
+                    }
+                }
+            }
+
+            if parts.is_empty() {
+                continue;
+            }
+            match msg.role {
+                Role::System => system_parts.extend(parts),
+                Role::User => contents.push(json!({ "role": "user", "parts": parts })),
+                Role::Assistant => contents.push(json!({ "role": "model", "parts": parts })),
+            }
+        }
+
+        let system = (!system_parts.is_empty()).then(|| json!({ "parts": system_parts }));
+        (system, contents)
+    }
+
+    /// Our `{name, description, input_schema}` tool definitions as one Gemini tool
+    /// holding every `functionDeclaration`
+    fn convert_tools(tools: Vec<Value>) -> Value {
+        let declarations: Vec<Value> = tools
+            .into_iter()
+            .map(|tool| {
+                json!({
+                    "name": tool["name"],
+                    "description": tool["description"],
+                    "parameters": sanitize_schema(&tool["input_schema"]),
+                })
+            })
+            .collect();
+        json!([{ "functionDeclarations": declarations }])
+    }
+
+    fn build_request_body(messages: &[Message], tools: Vec<Value>, config: &GenerationConfig) -> Value {
+        let (system, contents) = Self::convert_messages(messages, config.vision);
+
+        let mut generation_config = json!({ "temperature": config.temperature });
+        if let Some(max_tokens) = config.max_tokens {
+            generation_config["maxOutputTokens"] = json!(max_tokens);
+        }
+        if !config.stop.is_empty() {
+            generation_config["stopSequences"] = json!(config.stop);
+        }
+        if let Some(seed) = config.seed {
+            generation_config["seed"] = json!(seed);
+        }
+        match &config.response_format {
+            Some(ResponseFormat::JsonObject) => {
+                generation_config["responseMimeType"] = json!("application/json");
+            }
+            Some(ResponseFormat::JsonSchema { schema, .. }) => {
+                generation_config["responseMimeType"] = json!("application/json");
+                generation_config["responseSchema"] = sanitize_schema(schema);
+            }
+            None => {}
+        }
+
+        let mut body = json!({
+            "contents": contents,
+            "generationConfig": generation_config,
+        });
+        if let Some(system) = system {
+            body["systemInstruction"] = system;
+        }
+        if !tools.is_empty() {
+            body["tools"] = Self::convert_tools(tools);
+        }
+        body
+    }
+
+    async fn send_request(&self, model: &str, body: &Value, stream: bool) -> Result<reqwest::Response> {
+        let url = if stream {
+            format!("{}/models/{}:streamGenerateContent?alt=sse", self.api_base, model)
+        } else {
+            format!("{}/models/{}:generateContent", self.api_base, model)
+        };
+
+        let mut req = self
+            .client
+            .post(&url)
+            .header("x-goog-api-key", &self.api_key)
+            .json(body);
+        if !stream {
+            req = req.timeout(self.request_timeout);
+        }
+
+        let response = tokio::time::timeout(self.request_timeout, req.send())
+            .await
+            .map_err(|_| self.timeout_error())?
+            .map_err(|e| {
+                if e.is_timeout() {
+                    self.timeout_error()
+                } else {
+                    anyhow!("Request to Gemini failed: {}", e)
+                }
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: Value = response.json().await.unwrap_or(Value::Null);
+            let message = body["error"]["message"].as_str().unwrap_or("Unknown error");
+            return Err(anyhow!("Gemini HTTP error {}: {}", status, message));
+        }
+        Ok(response)
+    }
+
+    fn timeout_error(&self) -> anyhow::Error {
+        anyhow!(
+            "Gemini did not respond within {}s (raise llm.request_timeout_secs if the model is just slow)",
+            self.request_timeout.as_secs()
+        )
+    }
+
+    /// Parse Gemini's SSE stream; each event is a full `GenerateContentResponse` chunk
+    /// and the stream simply ends after the last one (there is no `[DONE]`)
+    fn parse_sse_stream(
+        stream: impl Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Send + 'static,
+        idle_timeout: Option<Duration>,
+    ) -> impl Stream<Item = Result<StreamEvent>> + Send {
+        use futures::stream::unfold;
+
+        struct State {
+            buffer: String,
+            stream: Pin<Box<dyn Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Send>>,
+            pending: VecDeque<StreamEvent>,
+            stop_reason: Option<StopReason>,
+            usage: Option<TokenUsage>,
+            finished: bool,
+            idle_timeout: Option<Duration>,
+        }
+
+        let initial_state = State {
+            buffer: String::new(),
+            stream: Box::pin(stream),
+            pending: VecDeque::new(),
+            stop_reason: None,
+            usage: None,
+            finished: false,
+            idle_timeout,
+        };
+
+        unfold(initial_state, |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((Ok(event), state));
+                }
+                if state.finished {
+                    return None;
+                }
+
+                // Gemini separates events with CRLF pairs
+                if let Some((pos, len)) = find_event_end(&state.buffer) {
+                    let message = state.buffer[..pos].to_string();
+                    state.buffer = state.buffer[pos + len..].to_string();
+
+                    for line in message.lines() {
+                        let Some(data) = line.strip_prefix("data: ") else {
+                            continue;
+                        };
+                        match serde_json::from_str::<Value>(data) {
+                            Ok(chunk) => {
+                                if let Some(error) = chunk.get("error") {
+                                    let message = error["message"].as_str().unwrap_or("Unknown error");
+                                    state.pending.push_back(StreamEvent::Error(message.to_string()));
+                                    continue;
+                                }
+                                if let Some(usage) = parse_usage(&chunk) {
+                                    state.usage = Some(usage);
+                                }
+                                let candidate = &chunk["candidates"][0];
+                                let (events, has_calls) = part_events(&candidate["content"]["parts"]);
+                                state.pending.extend(events);
+                                if has_calls {
+                                    state.stop_reason = Some(StopReason::StopSequence);
+                                }
+                                if let Some(reason) = candidate["finishReason"].as_str() {
+                                    if state.stop_reason.is_none() {
+                                        state.stop_reason = Some(stop_reason(reason));
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                tracing::warn!("Failed to parse Gemini SSE chunk: {} (data: {})", e, data);
+                            }
+                        }
+                    }
+                    continue;
+                }
+
+                let next = match state.idle_timeout {
+                    Some(idle) => match tokio::time::timeout(idle, state.stream.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            state.finished = true;
+                            let error = anyhow!(
+                                "Gemini stopped sending for {}s mid-response (raise llm.request_timeout_secs if the model is just slow)",
+                                idle.as_secs()
+                            );
+                            return Some((Err(error), state));
+                        }
+                    },
+                    None => state.stream.next().await,
+                };
+                match next {
+                    Some(Ok(chunk)) => state.buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                    Some(Err(e)) => return Some((Err(anyhow!("Stream error: {}", e)), state)),
+                    None => {
+                        // A trailing event may lack its blank line; parse it without polling again
+                        if !state.buffer.trim().is_empty() {
+                            state.buffer.push_str("\n\n");
+                            state.stream = Box::pin(futures::stream::empty());
+                            continue;
+                        }
+                        state.finished = true;
+                        state.pending.push_back(StreamEvent::Done {
+                            stop_reason: state.stop_reason.take().unwrap_or(StopReason::EndTurn),
+                            usage: state.usage.take().unwrap_or(TokenUsage {
+                                input_tokens: 0,
+                                output_tokens: 0,
+                            }),
+                        });
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Gemini's schema dialect is an OpenAPI subset; drop the JSON Schema keywords it rejects
+fn sanitize_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter(|(key, _)| !UNSUPPORTED_SCHEMA_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), sanitize_schema(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(sanitize_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Position and length of the first blank line ending an SSE event
+fn find_event_end(buffer: &str) -> Option<(usize, usize)> {
+    let lf = buffer.find("\n\n").map(|pos| (pos, 2));
+    let crlf = buffer.find("\r\n\r\n").map(|pos| (pos, 4));
+    match (lf, crlf) {
+        (Some(a), Some(b)) => Some(if a.0 < b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Gemini calls have no ids of their own, so we mint one per call
+fn new_call_id() -> String {
+    format!("call_{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Stream events for a chunk's parts, and whether any of them is a function call
+fn part_events(parts: &Value) -> (Vec<StreamEvent>, bool) {
+    let mut events = Vec::new();
+    let mut has_calls = false;
+
+    for part in parts.as_array().into_iter().flatten() {
+        if let Some(text) = part["text"].as_str().filter(|text| !text.is_empty()) {
+            if part["thought"].as_bool() == Some(true) {
+                events.push(StreamEvent::ThinkingDelta(text.to_string()));
+            } else {
+                events.push(StreamEvent::TextDelta(text.to_string()));
+            }
+        }
+        if let Some(call) = part.get("functionCall") {
+            has_calls = true;
+            let id = new_call_id();
+            events.push(StreamEvent::ToolCallStart {
+                id: id.clone(),
+                name: call["name"].as_str().unwrap_or_default().to_string(),
+            });
+            events.push(StreamEvent::ToolCallDelta {
+                id,
+                arguments_delta: call.get("args").unwrap_or(&json!({})).to_string(),
+            });
+        }
+    }
+    (events, has_calls)
+}
+
+fn stop_reason(finish_reason: &str) -> StopReason {
+    match finish_reason {
+        "MAX_TOKENS" => StopReason::MaxTokens,
+        _ => StopReason::EndTurn,
+    }
+}
+
+fn parse_usage(response: &Value) -> Option<TokenUsage> {
+    let usage = response.get("usageMetadata")?;
+    Some(TokenUsage {
+        input_tokens: usage["promptTokenCount"].as_u64().unwrap_or(0) as u32,
+        output_tokens: usage["candidatesTokenCount"].as_u64().unwrap_or(0) as u32,
+    })
+}
+
+#[async_trait]
+impl LLMProvider for GeminiProvider {
+    async fn chat_completion(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<Value>,
+        config: &GenerationConfig,
+    ) -> Result<LLMResponse> {
+        let body = Self::build_request_body(&messages, tools, config);
+        let response_json: Value = self
+            .send_request(&config.model, &body, false)
+            .await?
+            .json()
+            .await
+            .map_err(|e| anyhow!("Invalid response from Gemini: {}", e))?;
+
+        let candidate = response_json["candidates"]
+            .get(0)
+            .ok_or_else(|| anyhow!("No candidates in Gemini response: {}", response_json))?;
+
+        let mut content = Vec::new();
+        let mut thinking = Vec::new();
+        let mut text = Vec::new();
+        let mut has_calls = false;
+
+        for part in candidate["content"]["parts"].as_array().into_iter().flatten() {
+            if let Some(part_text) = part["text"].as_str() {
+                if part["thought"].as_bool() == Some(true) {
+                    thinking.push(part_text);
+                } else {
+                    text.push(part_text);
+                }
+            }
+            if let Some(call) = part.get("functionCall") {
+                has_calls = true;
+                content.push(ContentBlock::ToolUse {
+                    id: new_call_id(),
+                    name: call["name"].as_str().unwrap_or_default().to_string(),
+                    input: call.get("args").cloned().unwrap_or_else(|| json!({})),
+                });
+            }
+        }
+
+        let text = text.concat();
+        if !text.is_empty() {
+            content.insert(0, ContentBlock::Text { text });
+        }
+        if !thinking.is_empty() {
+            content.insert(0, ContentBlock::Thinking { text: thinking.concat() });
+        }
+
+        if let Some(format) = &config.response_format {
+            let answer = content.iter().find_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            });
+            format.validate(answer.unwrap_or(""))?;
+        }
+
+        let stop_reason = if has_calls {
+            StopReason::StopSequence
+        } else {
+            stop_reason(candidate["finishReason"].as_str().unwrap_or("STOP"))
+        };
+
+        Ok(LLMResponse {
+            content,
+            stop_reason,
+            usage: parse_usage(&response_json).unwrap_or(TokenUsage {
+                input_tokens: 0,
+                output_tokens: 0,
+            }),
+        })
+    }
+
+    async fn stream_chat_completion(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<Value>,
+        config: &GenerationConfig,
+    ) -> Result<StreamResult> {
+        let body = Self::build_request_body(&messages, tools, config);
+        let response = self.send_request(&config.model, &body, true).await?;
+        let events = Self::parse_sse_stream(response.bytes_stream(), Some(self.request_timeout));
+
+        match config.response_format.clone() {
+            Some(format) => Ok(Box::pin(validate_stream(events, format))),
+            None => Ok(Box::pin(events)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> GenerationConfig {
+        GenerationConfig {
+            temperature: 0.7,
+            max_tokens: Some(1000),
+            model: "gemini-2.5-flash".to_string(),
+            streaming: true,
+            reasoning_level: "medium".to_string(),
+            context_window: 1_000_000,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        }
+    }
+
+    #[test]
+    fn test_convert_messages_text() {
+        let messages = vec![
+            Message {
+                role: Role::System,
+                content: vec![ContentBlock::Text {
+                    text: "You are helpful".to_string(),
+                }],
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentBlock::Text {
+                    text: "Hello".to_string(),
+                }],
+            },
+        ];
+
+        let (system, contents) = GeminiProvider::convert_messages(&messages, false);
+        assert_eq!(system.unwrap()["parts"][0]["text"], "You are helpful");
+        assert_eq!(contents.len(), 1);
+        assert_eq!(contents[0]["role"], "user");
+        assert_eq!(contents[0]["parts"][0]["text"], "Hello");
+    }
+
+    #[test]
+    fn test_convert_messages_with_tool_use_and_result() {
+        let messages = vec![
+            Message {
+                role: Role::Assistant,
+                content: vec![
+                    ContentBlock::Text {
+                        text: "I'll use a tool".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "call_123".to_string(),
+                        name: "get_weather".to_string(),
+                        input: json!({"city": "San Francisco"}),
+                    },
+                ],
+            },
+            Message {
+                role: Role::User,
+                content: vec![ContentBlock::ToolResult {
+                    tool_use_id: "call_123".to_string(),
+                    content: "Temperature: 72F".to_string(),
+                    is_error: false,
+                }],
+            },
+        ];
+
+        let (system, contents) = GeminiProvider::convert_messages(&messages, false);
+        assert!(system.is_none());
+        assert_eq!(contents.len(), 2);
+
+        assert_eq!(contents[0]["role"], "model");
+        assert_eq!(contents[0]["parts"][0]["text"], "I'll use a tool");
+        assert_eq!(contents[0]["parts"][1]["functionCall"]["name"], "get_weather");
+        assert_eq!(contents[0]["parts"][1]["functionCall"]["args"]["city"], "San Francisco");
+
+        // The result is matched to its call by function name
+        let response = &contents[1]["parts"][0]["functionResponse"];
+        assert_eq!(contents[1]["role"], "user");
+        assert_eq!(response["name"], "get_weather");
+        assert_eq!(response["response"]["content"], "Temperature: 72F");
+    }
+
+    #[test]
+    fn test_thinking_is_not_sent_back_to_api() {
+        let messages = vec![Message {
+            role: Role::Assistant,
+            content: vec![
+                ContentBlock::Thinking {
+                    text: "Private reasoning".to_string(),
+                },
+                ContentBlock::Text {
+                    text: "Final answer".to_string(),
+                },
+            ],
+        }];
+
+        let (_, contents) = GeminiProvider::convert_messages(&messages, false);
+        assert_eq!(contents[0]["parts"].as_array().unwrap().len(), 1);
+        assert_eq!(contents[0]["parts"][0]["text"], "Final answer");
+    }
+
+    #[test]
+    fn test_tools_become_function_declarations() {
+        let tools = vec![json!({
+            "name": "read",
+            "description": "Read a file",
+            "input_schema": {
+                "type": "object",
+                "properties": { "file_path": { "type": "string" } },
+                "required": ["file_path"],
+                "additionalProperties": false
+            }
+        })];
+        let config = GenerationConfig {
+            stop: vec!["</answer>".to_string()],
+            ..test_config()
+        };
+
+        let body = GeminiProvider::build_request_body(&[], tools, &config);
+        let declaration = &body["tools"][0]["functionDeclarations"][0];
+        assert_eq!(declaration["name"], "read");
+        assert_eq!(declaration["description"], "Read a file");
+        assert_eq!(declaration["parameters"]["required"], json!(["file_path"]));
+        assert!(declaration["parameters"].get("additionalProperties").is_none());
+
+        assert_eq!(body["generationConfig"]["maxOutputTokens"], 1000);
+        assert_eq!(body["generationConfig"]["stopSequences"], json!(["</answer>"]));
+        assert!(body.get("systemInstruction").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_stream_text_and_function_call() {
+        use bytes::Bytes;
+        use futures::{pin_mut, stream};
+
+        let sse_data = vec![
+            Bytes::from("data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"Let me check\"}]}}]}\r\n\r\n"),
+            Bytes::from("data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"functionCall\":{\"name\":\"read\",\"args\":{\"file_path\":\"a.rs\"}}}]},\"finishReason\":\"STOP\"}],"),
+            Bytes::from("\"usageMetadata\":{\"promptTokenCount\":12,\"candidatesTokenCount\":5}}\r\n\r\n"),
+        ];
+
+        let byte_stream = stream::iter(sse_data.into_iter().map(Ok::<_, reqwest::Error>));
+        let event_stream = GeminiProvider::parse_sse_stream(byte_stream, None);
+        pin_mut!(event_stream);
+
+        let text = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(text, StreamEvent::TextDelta(ref s) if s == "Let me check"));
+
+        let start = event_stream.next().await.unwrap().unwrap();
+        let StreamEvent::ToolCallStart { id, name } = start else {
+            panic!("expected a tool call start");
+        };
+        assert_eq!(name, "read");
+
+        let delta = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(delta, StreamEvent::ToolCallDelta { id: ref delta_id, ref arguments_delta }
+            if *delta_id == id && arguments_delta == "{\"file_path\":\"a.rs\"}"));
+
+        let done = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(
+            done,
+            StreamEvent::Done {
+                stop_reason: StopReason::StopSequence,
+                usage: TokenUsage { input_tokens: 12, output_tokens: 5 },
+            }
+        ));
+        assert!(event_stream.next().await.is_none());
+    }
+}
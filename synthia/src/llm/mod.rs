@@ -1,5 +1,8 @@
+pub mod gemini;
 pub mod json_parser;
 pub mod openai;
 pub mod provider;
+pub mod response_format;
+pub mod thinking;
 
-pub use provider::{GenerationConfig, LLMProvider, StreamEvent};
+pub use provider::{GenerationConfig, LLMProvider, LLMResponse, StreamEvent};
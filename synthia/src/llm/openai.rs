@@ -1,32 +1,165 @@
 use super::json_parser::JsonParser;
 use super::provider::{GenerationConfig, LLMProvider, LLMResponse, StreamEvent, StreamResult};
+use super::response_format::validate_stream;
+use super::thinking::split_think_tags;
+use crate::agent::messages::UIUpdate;
 use crate::types::{ContentBlock, Message, Role, StopReason, TokenUsage};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures::{Stream, StreamExt};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+
+/// How long to wait on a stalled server unless `with_request_timeout` says otherwise
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Retries after a 429 unless `with_rate_limit_retries` says otherwise
+const DEFAULT_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest `Retry-After` worth waiting out; anything longer fails right away
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(300);
 
 pub struct OpenAICompatibleProvider {
     client: Client,
     api_base: String,
     api_key: Option<String>,
     json_parser: JsonParser,
+    request_timeout: Duration,
+    rate_limit_retries: u32,
+    /// Where "rate limited, retrying" notices go, when a UI is attached
+    ui_tx: Option<Sender<UIUpdate>>,
 }
 
 impl OpenAICompatibleProvider {
     pub fn new(api_base: String, api_key: Option<String>) -> Self {
         Self {
-            client: Client::new(),
+            client: Self::build_client(DEFAULT_REQUEST_TIMEOUT),
             api_base,
             api_key,
             json_parser: JsonParser::new(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            rate_limit_retries: DEFAULT_RATE_LIMIT_RETRIES,
+            ui_tx: None,
         }
     }
 
-    fn convert_messages(&self, messages: Vec<Message>, reasoning_level: &str) -> Vec<Value> {
+    /// Retry up to `retries` times after an HTTP 429, waiting as long as `Retry-After` asks
+    pub fn with_rate_limit_retries(mut self, retries: u32) -> Self {
+        self.rate_limit_retries = retries;
+        self
+    }
+
+    /// Tell the user when a request is waiting out a rate limit
+    pub fn with_ui_sender(mut self, ui_tx: Sender<UIUpdate>) -> Self {
+        self.ui_tx = Some(ui_tx);
+        self
+    }
+
+    /// Give up on the server after `timeout`: to connect, for a whole non-streaming
+    /// response, for the first streamed bytes, and between streamed chunks
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Self::build_client(timeout);
+        self.request_timeout = timeout;
+        self
+    }
+
+    fn build_client(timeout: Duration) -> Client {
+        // No overall timeout on the client: a streamed answer may legitimately take minutes
+        Client::builder()
+            .connect_timeout(timeout)
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    }
+
+    /// Name the stalled server and the setting to raise, instead of a bare reqwest error
+    fn describe_request_error(&self, e: reqwest::Error) -> anyhow::Error {
+        if e.is_timeout() {
+            self.timeout_error()
+        } else if e.is_connect() {
+            anyhow!("Could not connect to the LLM server at {}: {}", self.api_base, e)
+        } else {
+            e.into()
+        }
+    }
+
+    /// POST `body` to /chat/completions, waiting out 429 responses up to `rate_limit_retries` times.
+    /// Non-streaming requests are bounded as a whole; streaming ones only until the headers arrive
+    async fn send_request(&self, body: &Value, stream: bool) -> Result<reqwest::Response> {
+        let url = format!("{}/chat/completions", self.api_base);
+        let mut attempt = 0;
+
+        loop {
+            let mut req = self.client.post(&url).json(body);
+            if !stream {
+                req = req.timeout(self.request_timeout);
+            }
+            if let Some(key) = &self.api_key {
+                req = req.header("Authorization", format!("Bearer {}", key));
+            }
+
+            let response = tokio::time::timeout(self.request_timeout, req.send())
+                .await
+                .map_err(|_| self.timeout_error())?
+                .map_err(|e| self.describe_request_error(e))?;
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            // Without a Retry-After header, back off 1s, 2s, 4s, ...
+            let wait = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()))
+                .unwrap_or_else(|| Duration::from_secs(1 << attempt.min(6)));
+
+            if attempt >= self.rate_limit_retries || wait > MAX_RATE_LIMIT_WAIT {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "Rate limited by the LLM server at {} (HTTP 429) after {} retries{}: {}",
+                    self.api_base,
+                    attempt,
+                    if wait > MAX_RATE_LIMIT_WAIT {
+                        format!("; it asked to wait {}s", wait.as_secs())
+                    } else {
+                        String::new()
+                    },
+                    error_text
+                ));
+            }
+
+            attempt += 1;
+            let notice = format!(
+                "Rate limited by the LLM server, retrying in {}s ({}/{})",
+                wait.as_secs(),
+                attempt,
+                self.rate_limit_retries
+            );
+            tracing::warn!("{}", notice);
+            if let Some(ui_tx) = &self.ui_tx {
+                let _ = ui_tx.send(UIUpdate::SystemMessage(notice)).await;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn timeout_error(&self) -> anyhow::Error {
+        anyhow!(
+            "LLM server at {} did not respond within {}s (raise llm.request_timeout_secs if the model is just slow)",
+            self.api_base,
+            self.request_timeout.as_secs()
+        )
+    }
+
+    /// Images become `image_url` parts in a user message after the rest of the message,
+    /// since tool messages can only carry text; without `vision` they become a text placeholder
+    fn convert_messages(&self, messages: Vec<Message>, reasoning_level: &str, vision: bool) -> Vec<Value> {
         let mut result = Vec::new();
 
         for (idx, msg) in messages.iter().enumerate() {
@@ -40,6 +173,7 @@ impl OpenAICompatibleProvider {
             let mut text_parts = Vec::new();
             let mut tool_calls = Vec::new();
             let mut tool_results = Vec::new();
+            let mut images = Vec::new();
 
             for block in &msg.content {
                 match block {
@@ -63,6 +197,15 @@ impl OpenAICompatibleProvider {
                     } => {
                         tool_results.push((tool_use_id.clone(), content.clone(), *is_error));
                     }
+                    ContentBlock::Thinking { .. } => {
+                        // Reasoning is for the user; replaying it wastes context
+                    }
+                    ContentBlock::Image { media_type, data } => {
+                        images.push(json!({
+                            "type": "image_url",
+                            "image_url": { "url": format!("data:{};base64,{}", media_type, data) }
+                        }));
+                    }
                 }
             }
 
@@ -103,39 +246,59 @@ impl OpenAICompatibleProvider {
                     "content": content
                 }));
             }
+
+            if !images.is_empty() {
+                let content = if vision {
+                    json!(images)
+                } else {
+                    json!(format!("[{} image(s) omitted: vision is disabled for this model]", images.len()))
+                };
+                result.push(json!({
+                    "role": "user",
+                    "content": content
+                }));
+            }
         }
 
         result
     }
-}
 
-#[async_trait]
-impl LLMProvider for OpenAICompatibleProvider {
-    async fn chat_completion(
-        &self,
-        messages: Vec<Message>,
+    /// Build the /chat/completions request body shared by the streaming and non-streaming paths
+    /// Optional settings (max_tokens, stop, seed, response_format, tools) are only sent when set
+    fn build_request_body(
+        messages: Vec<Value>,
         tools: Vec<Value>,
         config: &GenerationConfig,
-    ) -> Result<LLMResponse> {
-        let url = format!("{}/chat/completions", self.api_base);
-
-        let converted_messages = self.convert_messages(messages, &config.reasoning_level);
-
-        // Log the first message to verify reasoning level injection
-        if let Some(first_msg) = converted_messages.first() {
-            tracing::debug!("First message after conversion: {}", serde_json::to_string_pretty(&first_msg).unwrap_or_else(|_| "{}".to_string()));
-        }
-
+        stream: bool,
+    ) -> Value {
         let mut request_body = json!({
             "model": config.model,
-            "messages": converted_messages,
+            "messages": messages,
             "temperature": config.temperature,
         });
 
+        if stream {
+            request_body["stream"] = json!(true);
+            // Ask for a final usage chunk; streams otherwise report no token counts
+            request_body["stream_options"] = json!({ "include_usage": true });
+        }
+
         if let Some(max_tokens) = config.max_tokens {
             request_body["max_tokens"] = json!(max_tokens);
         }
 
+        if !config.stop.is_empty() {
+            request_body["stop"] = json!(config.stop);
+        }
+
+        if let Some(seed) = config.seed {
+            request_body["seed"] = json!(seed);
+        }
+
+        if let Some(format) = &config.response_format {
+            request_body["response_format"] = format.to_request();
+        }
+
         if !tools.is_empty() {
             // Convert tool definitions to OpenAI format
             // Tools from registry come as: {"name": "...", "description": "...", "input_schema": {...}}
@@ -154,14 +317,32 @@ impl LLMProvider for OpenAICompatibleProvider {
             request_body["tools"] = json!(openai_tools);
         }
 
-        let mut req = self.client.post(&url).json(&request_body);
+        request_body
+    }
+}
+
+#[async_trait]
+impl LLMProvider for OpenAICompatibleProvider {
+    async fn chat_completion(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<Value>,
+        config: &GenerationConfig,
+    ) -> Result<LLMResponse> {
+        let converted_messages = self.convert_messages(messages, &config.reasoning_level, config.vision);
 
-        if let Some(key) = &self.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
+        // Log the first message to verify reasoning level injection
+        if let Some(first_msg) = converted_messages.first() {
+            tracing::debug!("First message after conversion: {}", serde_json::to_string_pretty(&first_msg).unwrap_or_else(|_| "{}".to_string()));
         }
 
-        let response = req.send().await?;
-        let response_json: Value = response.json().await?;
+        let request_body = Self::build_request_body(converted_messages, tools, config, false);
+
+        let response = self.send_request(&request_body, false).await?;
+        let response_json: Value = response
+            .json()
+            .await
+            .map_err(|e| self.describe_request_error(e))?;
 
         // Debug: Log the response to see what LM Studio is returning
         tracing::debug!("LM Studio response: {}", serde_json::to_string_pretty(&response_json).unwrap_or_else(|_| "Failed to serialize".to_string()));
@@ -183,13 +364,25 @@ impl LLMProvider for OpenAICompatibleProvider {
         // Parse content blocks
         let mut content = Vec::new();
 
+        // Reasoning comes either as a separate field or inline <think> tags in the content
+        let mut thinking: Vec<String> = reasoning_field(message)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .into_iter()
+            .collect();
+
         // C2: Parse text content
-        if let Some(text) = message.get("content").and_then(|c| c.as_str()) {
-            if !text.is_empty() {
-                content.push(ContentBlock::Text {
-                    text: text.to_string(),
-                });
-            }
+        let text = message.get("content").and_then(|c| c.as_str()).unwrap_or("");
+        let (inline_thinking, text) = split_think_tags(text);
+        thinking.extend(inline_thinking);
+
+        if !thinking.is_empty() {
+            content.push(ContentBlock::Thinking {
+                text: thinking.join("\n\n"),
+            });
+        }
+        if !text.is_empty() {
+            content.push(ContentBlock::Text { text });
         }
 
         // C2: Parse tool_calls into ContentBlock::ToolUse
@@ -257,6 +450,14 @@ impl LLMProvider for OpenAICompatibleProvider {
                 .unwrap_or(0) as u32,
         };
 
+        if let Some(format) = &config.response_format {
+            let answer = content.iter().find_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            });
+            format.validate(answer.unwrap_or(""))?;
+        }
+
         Ok(LLMResponse {
             content,
             stop_reason,
@@ -270,41 +471,11 @@ impl LLMProvider for OpenAICompatibleProvider {
         tools: Vec<Value>,
         config: &GenerationConfig,
     ) -> Result<StreamResult> {
-        let url = format!("{}/chat/completions", self.api_base);
+        let converted_messages = self.convert_messages(messages, &config.reasoning_level, config.vision);
+        let request_body = Self::build_request_body(converted_messages, tools, config, true);
 
-        let mut request_body = json!({
-            "model": config.model,
-            "messages": self.convert_messages(messages, &config.reasoning_level),
-            "temperature": config.temperature,
-            "stream": true,
-        });
-
-        if let Some(max_tokens) = config.max_tokens {
-            request_body["max_tokens"] = json!(max_tokens);
-        }
-
-        if !tools.is_empty() {
-            let openai_tools: Vec<Value> = tools
-                .into_iter()
-                .map(|tool| json!({
-                    "type": "function",
-                    "function": {
-                        "name": tool["name"],
-                        "description": tool["description"],
-                        "parameters": tool["input_schema"]
-                    }
-                }))
-                .collect();
-            request_body["tools"] = json!(openai_tools);
-        }
-
-        let mut req = self.client.post(&url).json(&request_body);
-
-        if let Some(key) = &self.api_key {
-            req = req.header("Authorization", format!("Bearer {}", key));
-        }
-
-        let response = req.send().await?;
+        // The body is bounded per chunk below
+        let response = self.send_request(&request_body, true).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -314,15 +485,28 @@ impl LLMProvider for OpenAICompatibleProvider {
 
         // Convert response to SSE stream
         let stream = response.bytes_stream();
-        let event_stream = Self::parse_sse_stream(stream);
+        let event_stream = Self::parse_sse_stream_with_idle_timeout(stream, Some(self.request_timeout));
 
-        Ok(Box::pin(event_stream))
+        match config.response_format.clone() {
+            Some(format) => Ok(Box::pin(validate_stream(event_stream, format))),
+            None => Ok(Box::pin(event_stream)),
+        }
     }
 }
 
 impl OpenAICompatibleProvider {
+    /// Parse the SSE stream with no idle timeout
+    #[cfg(test)]
     fn parse_sse_stream(
         stream: impl Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Send + 'static,
+    ) -> impl Stream<Item = Result<StreamEvent>> + Send {
+        Self::parse_sse_stream_with_idle_timeout(stream, None)
+    }
+
+    /// Parse the SSE stream, failing when no chunk arrives within `idle_timeout`
+    fn parse_sse_stream_with_idle_timeout(
+        stream: impl Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Send + 'static,
+        idle_timeout: Option<Duration>,
     ) -> impl Stream<Item = Result<StreamEvent>> + Send {
         // Use a shared state approach
         use futures::stream::unfold;
@@ -331,15 +515,41 @@ impl OpenAICompatibleProvider {
             buffer: String,
             tool_calls: HashMap<String, (String, String)>,
             stream: Pin<Box<dyn Stream<Item = Result<bytes::Bytes, reqwest::Error>> + Send>>,
+            // Usage often arrives in a chunk of its own after finish_reason, so the
+            // stop reason is held until then (or until [DONE])
+            stop_reason: Option<StopReason>,
+            usage: Option<TokenUsage>,
+            finished: bool,
+            idle_timeout: Option<Duration>,
+        }
+
+        impl State {
+            fn done(&mut self) -> StreamEvent {
+                self.finished = true;
+                StreamEvent::Done {
+                    stop_reason: self.stop_reason.take().unwrap_or(StopReason::EndTurn),
+                    usage: self.usage.take().unwrap_or(TokenUsage {
+                        input_tokens: 0,
+                        output_tokens: 0,
+                    }),
+                }
+            }
         }
 
         let initial_state = State {
             buffer: String::new(),
             tool_calls: HashMap::new(),
             stream: Box::pin(stream),
+            stop_reason: None,
+            usage: None,
+            finished: false,
+            idle_timeout,
         };
 
         unfold(initial_state, |mut state| async move {
+            if state.finished {
+                return None;
+            }
             loop {
                 // Try to process buffered data first
                 if let Some(pos) = state.buffer.find("\n\n") {
@@ -350,14 +560,8 @@ impl OpenAICompatibleProvider {
                     for line in message.lines() {
                         if let Some(data) = line.strip_prefix("data: ") {
                             if data.trim() == "[DONE]" {
-                                let usage = TokenUsage {
-                                    input_tokens: 0,
-                                    output_tokens: 0,
-                                };
-                                return Some((Ok(StreamEvent::Done {
-                                    stop_reason: StopReason::EndTurn,
-                                    usage,
-                                }), state));
+                                let done = state.done();
+                                return Some((Ok(done), state));
                             }
 
                             match serde_json::from_str::<Value>(data) {
@@ -371,10 +575,28 @@ impl OpenAICompatibleProvider {
                                         )), state));
                                     }
 
+                                    if let Some(usage) = json.get("usage").filter(|u| u.is_object()) {
+                                        state.usage = Some(TokenUsage {
+                                            input_tokens: usage.get("prompt_tokens").and_then(|pt| pt.as_u64()).unwrap_or(0) as u32,
+                                            output_tokens: usage.get("completion_tokens").and_then(|ct| ct.as_u64()).unwrap_or(0) as u32,
+                                        });
+                                        if state.stop_reason.is_some() {
+                                            let done = state.done();
+                                            return Some((Ok(done), state));
+                                        }
+                                    }
+
                                     if let Some(choices) = json.get("choices").and_then(|c| c.as_array()) {
                                         if let Some(choice) = choices.get(0) {
                                             let delta = choice.get("delta");
 
+                                            // Handle reasoning content
+                                            if let Some(reasoning) = delta.and_then(reasoning_field) {
+                                                if !reasoning.is_empty() {
+                                                    return Some((Ok(StreamEvent::ThinkingDelta(reasoning.to_string())), state));
+                                                }
+                                            }
+
                                             // Handle text content
                                             if let Some(content) = delta.and_then(|d| d.get("content")).and_then(|c| c.as_str()) {
                                                 if !content.is_empty() {
@@ -425,15 +647,11 @@ impl OpenAICompatibleProvider {
                                                     _ => StopReason::EndTurn,
                                                 };
 
-                                                let usage = json.get("usage").map(|u| TokenUsage {
-                                                    input_tokens: u.get("prompt_tokens").and_then(|pt| pt.as_u64()).unwrap_or(0) as u32,
-                                                    output_tokens: u.get("completion_tokens").and_then(|ct| ct.as_u64()).unwrap_or(0) as u32,
-                                                }).unwrap_or_else(|| TokenUsage { input_tokens: 0, output_tokens: 0 });
-
-                                                return Some((Ok(StreamEvent::Done {
-                                                    stop_reason,
-                                                    usage,
-                                                }), state));
+                                                state.stop_reason = Some(stop_reason);
+                                                if state.usage.is_some() {
+                                                    let done = state.done();
+                                                    return Some((Ok(done), state));
+                                                }
                                             }
                                         }
                                     }
@@ -448,7 +666,21 @@ impl OpenAICompatibleProvider {
                 }
 
                 // Need more data, fetch next chunk
-                match state.stream.next().await {
+                let next = match state.idle_timeout {
+                    Some(idle) => match tokio::time::timeout(idle, state.stream.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            state.finished = true;
+                            let error = anyhow!(
+                                "LLM server stopped sending for {}s mid-response (raise llm.request_timeout_secs if the model is just slow)",
+                                idle.as_secs()
+                            );
+                            return Some((Err(error), state));
+                        }
+                    },
+                    None => state.stream.next().await,
+                };
+                match next {
                     Some(Ok(chunk)) => {
                         state.buffer.push_str(&String::from_utf8_lossy(&chunk));
                         continue;
@@ -457,7 +689,11 @@ impl OpenAICompatibleProvider {
                         return Some((Err(anyhow!("Stream error: {}", e)), state));
                     }
                     None => {
-                        // Stream ended
+                        // Stream ended; finish a response that never sent [DONE]
+                        if state.stop_reason.is_some() {
+                            let done = state.done();
+                            return Some((Ok(done), state));
+                        }
                         return None;
                     }
                 }
@@ -466,9 +702,29 @@ impl OpenAICompatibleProvider {
     }
 }
 
+/// Reasoning text from a message or delta; servers disagree on the field name
+fn reasoning_field(value: &Value) -> Option<&str> {
+    value
+        .get("reasoning_content")
+        .or_else(|| value.get("reasoning"))
+        .and_then(|r| r.as_str())
+}
+
+/// Wait asked for by a `Retry-After` header: delay-seconds or an HTTP-date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date in the past means "retry now"
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm::response_format::ResponseFormat;
     use crate::types::Role;
 
     #[test]
@@ -485,7 +741,7 @@ mod tests {
             }],
         }];
 
-        let converted = provider.convert_messages(messages, "medium");
+        let converted = provider.convert_messages(messages, "medium", false);
         assert_eq!(converted.len(), 1);
         assert_eq!(converted[0]["role"], "user");
         assert_eq!(converted[0]["content"], "Hello");
@@ -505,7 +761,7 @@ mod tests {
             }],
         }];
 
-        let converted = provider.convert_messages(messages, "high");
+        let converted = provider.convert_messages(messages, "high", false);
         assert_eq!(converted.len(), 1);
         assert_eq!(converted[0]["role"], "system");
         assert_eq!(converted[0]["content"], "You are a helpful assistant.\n\nReasoning: high");
@@ -532,7 +788,7 @@ mod tests {
             ],
         }];
 
-        let converted = provider.convert_messages(messages, "medium");
+        let converted = provider.convert_messages(messages, "medium", false);
         assert_eq!(converted.len(), 1);
         assert_eq!(converted[0]["role"], "assistant");
         assert_eq!(converted[0]["content"], "I'll use a tool");
@@ -559,13 +815,289 @@ mod tests {
             }],
         }];
 
-        let converted = provider.convert_messages(messages, "medium");
+        let converted = provider.convert_messages(messages, "medium", false);
         assert_eq!(converted.len(), 1);
         assert_eq!(converted[0]["role"], "tool");
         assert_eq!(converted[0]["tool_call_id"], "call_123");
         assert_eq!(converted[0]["content"], "Temperature: 72F");
     }
 
+    /// A server that accepts requests, writes `preamble`, then goes silent
+    async fn stalled_server(preamble: &'static [u8]) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = [0u8; 4096];
+                    let _ = socket.read(&mut request).await;
+                    let _ = socket.write_all(preamble).await;
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                });
+            }
+        });
+        format!("http://{}/v1", addr)
+    }
+
+    fn hello() -> Vec<Message> {
+        vec![Message {
+            role: Role::User,
+            content: vec![ContentBlock::Text {
+                text: "Hello".to_string(),
+            }],
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_never_responding_server_times_out() {
+        let provider = OpenAICompatibleProvider::new(stalled_server(b"").await, None)
+            .with_request_timeout(Duration::from_secs(1));
+
+        let err = provider
+            .chat_completion(hello(), Vec::new(), &test_config())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("did not respond within 1s"), "{}", err);
+        assert!(err.contains("llm.request_timeout_secs"));
+
+        match provider.stream_chat_completion(hello(), Vec::new(), &test_config()).await {
+            Err(e) => assert!(e.to_string().contains("did not respond within 1s"), "{}", e),
+            Ok(_) => panic!("expected the stream request to time out"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_stalling_mid_response_times_out() {
+        let preamble: &'static [u8] = b"HTTP/1.1 200 OK\r\n\
+            Content-Type: text/event-stream\r\n\
+            Transfer-Encoding: chunked\r\n\r\n\
+            31\r\ndata: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n\r\n";
+        let provider = OpenAICompatibleProvider::new(stalled_server(preamble).await, None)
+            .with_request_timeout(Duration::from_secs(1));
+
+        let mut events = match provider.stream_chat_completion(hello(), Vec::new(), &test_config()).await {
+            Ok(events) => events,
+            Err(e) => panic!("headers were sent, so the request should succeed: {}", e),
+        };
+
+        let first = events.next().await.unwrap().unwrap();
+        assert!(matches!(first, StreamEvent::TextDelta(ref s) if s == "Hel"));
+
+        let err = events.next().await.unwrap().unwrap_err().to_string();
+        assert!(err.contains("stopped sending for 1s mid-response"), "{}", err);
+        assert!(events.next().await.is_none());
+    }
+
+    /// A server that answers each connection with the next of `responses`
+    async fn scripted_server(responses: Vec<String>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            for response in responses {
+                let Ok((mut socket, _)) = listener.accept().await else { return };
+                let mut request = [0u8; 8192];
+                let _ = socket.read(&mut request).await;
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+        format!("http://{}/v1", addr)
+    }
+
+    fn http_response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    fn rate_limited() -> String {
+        http_response("429 Too Many Requests", "Retry-After: 0\r\n", "{\"error\":\"slow down\"}")
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_request_is_retried() {
+        let ok = http_response(
+            "200 OK",
+            "Content-Type: application/json\r\n",
+            r#"{"choices":[{"message":{"content":"Hi there"},"finish_reason":"stop"}]}"#,
+        );
+        let (ui_tx, mut ui_rx) = tokio::sync::mpsc::channel(10);
+        let provider = OpenAICompatibleProvider::new(scripted_server(vec![rate_limited(), ok]).await, None)
+            .with_ui_sender(ui_tx);
+
+        let response = provider.chat_completion(hello(), Vec::new(), &test_config()).await.unwrap();
+        assert!(matches!(&response.content[0], ContentBlock::Text { text } if text == "Hi there"));
+
+        match ui_rx.try_recv() {
+            Ok(UIUpdate::SystemMessage(notice)) => {
+                assert_eq!(notice, "Rate limited by the LLM server, retrying in 0s (1/3)")
+            }
+            other => panic!("expected a rate limit notice, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_stream_is_retried() {
+        let sse = "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\ndata: [DONE]\n\n";
+        let ok = http_response("200 OK", "Content-Type: text/event-stream\r\n", sse);
+        let provider = OpenAICompatibleProvider::new(scripted_server(vec![rate_limited(), ok]).await, None);
+
+        let mut events = match provider.stream_chat_completion(hello(), Vec::new(), &test_config()).await {
+            Ok(events) => events,
+            Err(e) => panic!("the retry should have succeeded: {}", e),
+        };
+        let first = events.next().await.unwrap().unwrap();
+        assert!(matches!(first, StreamEvent::TextDelta(ref s) if s == "Hi"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_gives_up_after_configured_retries() {
+        let provider = OpenAICompatibleProvider::new(
+            scripted_server(vec![rate_limited(), rate_limited()]).await,
+            None,
+        )
+        .with_rate_limit_retries(1);
+
+        let err = provider
+            .chat_completion(hello(), Vec::new(), &test_config())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("(HTTP 429) after 1 retries"), "{}", err);
+        assert!(err.contains("slow down"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    fn test_config() -> GenerationConfig {
+        GenerationConfig {
+            temperature: 0.7,
+            max_tokens: Some(1000),
+            model: "test-model".to_string(),
+            streaming: true,
+            reasoning_level: "medium".to_string(),
+            context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        }
+    }
+
+    #[test]
+    fn test_request_body_includes_stop_and_seed_when_set() {
+        let config = GenerationConfig {
+            stop: vec!["</answer>".to_string(), "\n\nUser:".to_string()],
+            seed: Some(42),
+            ..test_config()
+        };
+
+        for stream in [false, true] {
+            let body = OpenAICompatibleProvider::build_request_body(Vec::new(), Vec::new(), &config, stream);
+            assert_eq!(body["stop"], json!(["</answer>", "\n\nUser:"]));
+            assert_eq!(body["seed"], 42);
+            assert_eq!(body["max_tokens"], 1000);
+            assert_eq!(body.get("stream").is_some(), stream);
+        }
+    }
+
+    #[test]
+    fn test_request_body_omits_unset_stop_and_seed() {
+        let body = OpenAICompatibleProvider::build_request_body(Vec::new(), Vec::new(), &test_config(), true);
+        assert!(body.get("stop").is_none());
+        assert!(body.get("seed").is_none());
+        assert!(body.get("tools").is_none());
+        assert!(body.get("response_format").is_none());
+        assert_eq!(body["stream"], true);
+        assert_eq!(body["stream_options"]["include_usage"], true);
+    }
+
+    fn answer_format() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            name: "answer".to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": { "answer": { "type": "string" } },
+                "required": ["answer"]
+            }),
+        }
+    }
+
+    #[test]
+    fn test_request_body_includes_response_format() {
+        let config = GenerationConfig {
+            response_format: Some(answer_format()),
+            ..test_config()
+        };
+        let body = OpenAICompatibleProvider::build_request_body(Vec::new(), Vec::new(), &config, false);
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(body["response_format"]["json_schema"]["name"], "answer");
+        assert_eq!(body["response_format"]["json_schema"]["strict"], true);
+        assert_eq!(
+            body["response_format"]["json_schema"]["schema"]["required"],
+            json!(["answer"])
+        );
+
+        let config = GenerationConfig {
+            response_format: Some(ResponseFormat::JsonObject),
+            ..test_config()
+        };
+        let body = OpenAICompatibleProvider::build_request_body(Vec::new(), Vec::new(), &config, false);
+        assert_eq!(body["response_format"], json!({ "type": "json_object" }));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_answer_is_validated_against_schema() {
+        use futures::stream;
+
+        let done = || StreamEvent::Done {
+            stop_reason: StopReason::EndTurn,
+            usage: TokenUsage { input_tokens: 0, output_tokens: 0 },
+        };
+        let events = |deltas: &[&str]| {
+            let mut events: Vec<Result<StreamEvent>> = deltas
+                .iter()
+                .map(|delta| Ok(StreamEvent::TextDelta(delta.to_string())))
+                .collect();
+            events.push(Ok(done()));
+            stream::iter(events)
+        };
+
+        let valid: Vec<_> = validate_stream(events(&["{\"answer\": ", "\"42\"}"]), answer_format())
+            .collect()
+            .await;
+        assert!(matches!(valid.last(), Some(Ok(StreamEvent::Done { .. }))));
+
+        let invalid: Vec<_> = validate_stream(events(&["{\"answer\": 42}"]), answer_format())
+            .collect()
+            .await;
+        assert!(matches!(
+            invalid.last(),
+            Some(Ok(StreamEvent::Error(e))) if e.contains("$.answer: expected string, got integer")
+        ));
+    }
+
     #[tokio::test]
     async fn test_parse_sse_stream_text_delta() {
         use bytes::Bytes;
@@ -592,13 +1124,138 @@ mod tests {
         let event2 = event_stream.next().await.unwrap().unwrap();
         assert!(matches!(event2, StreamEvent::TextDelta(ref s) if s == " world"));
 
-        // Third event: Done with stop reason
+        // Third event: Done with stop reason (sent at the [DONE] marker)
         let event3 = event_stream.next().await.unwrap().unwrap();
         assert!(matches!(event3, StreamEvent::Done { stop_reason: StopReason::EndTurn, .. }));
 
-        // Fourth event: [DONE] marker
+        // Only one Done per response
+        assert!(event_stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_stream_usage_after_finish_reason() {
+        use bytes::Bytes;
+        use futures::stream;
+        use futures::pin_mut;
+
+        // With stream_options.include_usage, usage arrives in its own chunk with no choices
+        let sse_data = vec![
+            Bytes::from("data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n\n"),
+            Bytes::from("data: {\"choices\":[{\"finish_reason\":\"length\"}],\"usage\":null}\n\n"),
+            Bytes::from("data: {\"choices\":[],\"usage\":{\"prompt_tokens\":120,\"completion_tokens\":30}}\n\n"),
+            Bytes::from("data: [DONE]\n\n"),
+        ];
+
+        let byte_stream = stream::iter(sse_data.into_iter().map(Ok::<_, reqwest::Error>));
+        let event_stream = OpenAICompatibleProvider::parse_sse_stream(byte_stream);
+        pin_mut!(event_stream);
+
+        let event1 = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(event1, StreamEvent::TextDelta(ref s) if s == "Hi"));
+
+        match event_stream.next().await.unwrap().unwrap() {
+            StreamEvent::Done { stop_reason, usage } => {
+                assert!(matches!(stop_reason, StopReason::MaxTokens));
+                assert_eq!((usage.input_tokens, usage.output_tokens), (120, 30));
+            }
+            other => panic!("Expected Done, got {:?}", other),
+        }
+        assert!(event_stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parse_sse_stream_reasoning_content() {
+        use bytes::Bytes;
+        use futures::stream;
+        use futures::pin_mut;
+
+        let sse_data = vec![
+            Bytes::from("data: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":null,\"reasoning_content\":\"Let me \"}}]}\n\n"),
+            Bytes::from("data: {\"choices\":[{\"delta\":{\"reasoning_content\":\"think.\"}}]}\n\n"),
+            Bytes::from("data: {\"choices\":[{\"delta\":{\"content\":\"Answer\"}}]}\n\n"),
+            Bytes::from("data: {\"choices\":[{\"finish_reason\":\"stop\"}]}\n\n"),
+        ];
+
+        let byte_stream = stream::iter(sse_data.into_iter().map(Ok::<_, reqwest::Error>));
+        let event_stream = OpenAICompatibleProvider::parse_sse_stream(byte_stream);
+        pin_mut!(event_stream);
+
+        let event1 = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(event1, StreamEvent::ThinkingDelta(ref s) if s == "Let me "));
+
+        let event2 = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(event2, StreamEvent::ThinkingDelta(ref s) if s == "think."));
+
+        // The answer stays on the text channel
+        let event3 = event_stream.next().await.unwrap().unwrap();
+        assert!(matches!(event3, StreamEvent::TextDelta(ref s) if s == "Answer"));
+
         let event4 = event_stream.next().await.unwrap().unwrap();
-        assert!(matches!(event4, StreamEvent::Done { .. }));
+        assert!(matches!(event4, StreamEvent::Done { stop_reason: StopReason::EndTurn, .. }));
+    }
+
+    #[test]
+    fn test_thinking_is_not_sent_back_to_api() {
+        let provider = OpenAICompatibleProvider::new(
+            "http://localhost:1234/v1".to_string(),
+            None,
+        );
+
+        let messages = vec![Message {
+            role: Role::Assistant,
+            content: vec![
+                ContentBlock::Thinking {
+                    text: "Private reasoning".to_string(),
+                },
+                ContentBlock::Text {
+                    text: "Final answer".to_string(),
+                },
+            ],
+        }];
+
+        let converted = provider.convert_messages(messages, "medium", false);
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0]["content"], "Final answer");
+    }
+
+    #[test]
+    fn test_images_become_image_url_parts_only_with_vision() {
+        let provider = OpenAICompatibleProvider::new(
+            "http://localhost:1234/v1".to_string(),
+            None,
+        );
+
+        let messages = vec![Message {
+            role: Role::User,
+            content: vec![
+                ContentBlock::ToolResult {
+                    tool_use_id: "call_1".to_string(),
+                    content: "[image: shot.png, 640x480]".to_string(),
+                    is_error: false,
+                },
+                ContentBlock::Image {
+                    media_type: "image/png".to_string(),
+                    data: "iVBORw0KGgo=".to_string(),
+                },
+            ],
+        }];
+
+        let converted = provider.convert_messages(messages.clone(), "medium", true);
+        assert_eq!(converted.len(), 2);
+        assert_eq!(converted[0]["role"], "tool");
+        assert_eq!(converted[1]["role"], "user");
+        assert_eq!(converted[1]["content"][0]["type"], "image_url");
+        assert_eq!(
+            converted[1]["content"][0]["image_url"]["url"],
+            "data:image/png;base64,iVBORw0KGgo="
+        );
+
+        let converted = provider.convert_messages(messages, "medium", false);
+        assert_eq!(converted.len(), 2);
+        assert_eq!(
+            converted[1]["content"],
+            "[1 image(s) omitted: vision is disabled for this model]"
+        );
     }
 
     #[tokio::test]
@@ -1,3 +1,4 @@
+use super::response_format::ResponseFormat;
 use crate::types::{ContentBlock, Message, StopReason, TokenUsage};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -13,6 +14,10 @@ pub struct GenerationConfig {
     pub streaming: bool,
     pub reasoning_level: String,  // "low", "medium", "high"
     pub context_window: usize,     // Max context window for the model
+    pub stop: Vec<String>,         // Stop sequences (omitted from requests when empty)
+    pub seed: Option<u64>,         // Sampling seed for reproducible output
+    pub vision: bool,              // Model accepts image content parts
+    pub response_format: Option<ResponseFormat>, // Structured output; answers are validated against it
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +30,7 @@ pub struct LLMResponse {
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
     TextDelta(String),
+    ThinkingDelta(String), // Reasoning channel (`reasoning_content`), kept apart from the answer
     ToolCallStart { id: String, name: String },
     ToolCallDelta { id: String, arguments_delta: String },
     Done { stop_reason: StopReason, usage: TokenUsage },
@@ -0,0 +1,250 @@
+use super::provider::StreamEvent;
+use super::thinking::split_think_tags;
+use anyhow::{anyhow, Result};
+use futures::{Stream, StreamExt};
+use serde_json::{json, Value};
+
+/// Structured output requested from the model (OpenAI `response_format`)
+#[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)] // Requested by library callers; the CLI doesn't ask for structured output
+pub enum ResponseFormat {
+    /// Any JSON object
+    JsonObject,
+    /// JSON matching `schema`; the answer is checked against it after generation
+    JsonSchema { name: String, schema: Value },
+}
+
+impl ResponseFormat {
+    /// The `response_format` value sent in the request body
+    pub fn to_request(&self) -> Value {
+        match self {
+            ResponseFormat::JsonObject => json!({ "type": "json_object" }),
+            ResponseFormat::JsonSchema { name, schema } => json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": name,
+                    "schema": schema,
+                    "strict": true,
+                }
+            }),
+        }
+    }
+
+    /// Parse the model's answer and check it has the requested shape
+    pub fn validate(&self, text: &str) -> Result<Value> {
+        let (_, text) = split_think_tags(text);
+        let value: Value = serde_json::from_str(strip_code_fence(&text))
+            .map_err(|e| anyhow!("Structured response is not valid JSON: {}", e))?;
+
+        match self {
+            ResponseFormat::JsonObject => {
+                if !value.is_object() {
+                    anyhow::bail!(
+                        "Structured response should be a JSON object, got {}",
+                        type_name(&value)
+                    );
+                }
+            }
+            ResponseFormat::JsonSchema { name, schema } => {
+                let mut errors = Vec::new();
+                schema_errors(&value, schema, "$", &mut errors);
+                if !errors.is_empty() {
+                    anyhow::bail!(
+                        "Structured response does not match schema '{}':\n  {}",
+                        name,
+                        errors.join("\n  ")
+                    );
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// Check the streamed answer against `format` once it's complete, replacing
+/// `Done` with an error when it doesn't match
+pub fn validate_stream(
+    events: impl Stream<Item = Result<StreamEvent>> + Send,
+    format: ResponseFormat,
+) -> impl Stream<Item = Result<StreamEvent>> + Send {
+    let mut answer = String::new();
+    events.map(move |event| {
+        match &event {
+            Ok(StreamEvent::TextDelta(delta)) => answer.push_str(delta),
+            Ok(StreamEvent::Done { .. }) => {
+                if let Err(e) = format.validate(&answer) {
+                    return Ok(StreamEvent::Error(e.to_string()));
+                }
+            }
+            _ => {}
+        }
+        event
+    })
+}
+
+/// Some servers wrap JSON answers in a markdown code fence even in JSON mode
+fn strip_code_fence(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map_or(text, str::trim)
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        other => type_name(value) == other,
+    }
+}
+
+/// Collect mismatches for the JSON Schema keywords structured outputs use
+/// (`type`, `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`, `anyOf`)
+fn schema_errors(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            errors.push(format!("{}: expected {}, got {}", path, types.join(" or "), type_name(value)));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!("{}: {} is not one of {}", path, value, Value::Array(options.clone())));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(format!("{}: expected {}, got {}", path, expected, value));
+        }
+    }
+
+    if let Some(alternatives) = schema.get("anyOf").and_then(Value::as_array) {
+        let matches_any = alternatives.iter().any(|alternative| {
+            let mut alternative_errors = Vec::new();
+            schema_errors(value, alternative, path, &mut alternative_errors);
+            alternative_errors.is_empty()
+        });
+        if !matches_any {
+            errors.push(format!("{}: does not match any of the anyOf schemas", path));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for required in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            if let Some(key) = required.as_str() {
+                if !object.contains_key(key) {
+                    errors.push(format!("{}: missing required property '{}'", path, key));
+                }
+            }
+        }
+        for (key, item) in object {
+            let item_path = format!("{}.{}", path, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(item_schema) => schema_errors(item, item_schema, &item_path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        errors.push(format!("{}: unexpected property", item_path))
+                    }
+                    Some(extra) if extra.is_object() => {
+                        schema_errors(item, extra, &item_path, errors)
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            schema_errors(item, item_schema, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo_schema() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            name: "todos".to_string(),
+            schema: json!({
+                "type": "object",
+                "properties": {
+                    "todos": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "id": { "type": "integer" },
+                                "status": { "enum": ["pending", "done"] }
+                            },
+                            "required": ["id", "status"],
+                            "additionalProperties": false
+                        }
+                    }
+                },
+                "required": ["todos"]
+            }),
+        }
+    }
+
+    #[test]
+    fn test_valid_response_passes() {
+        let value = todo_schema()
+            .validate("```json\n{\"todos\": [{\"id\": 1, \"status\": \"done\"}]}\n```")
+            .unwrap();
+        assert_eq!(value["todos"][0]["id"], 1);
+
+        let value = todo_schema()
+            .validate("<think>list them</think>{\"todos\": []}")
+            .unwrap();
+        assert_eq!(value["todos"], json!([]));
+    }
+
+    #[test]
+    fn test_schema_mismatches_are_reported_with_paths() {
+        let err = todo_schema()
+            .validate(r#"{"todos": [{"id": "one", "status": "started", "extra": true}]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("does not match schema 'todos'"));
+        assert!(err.contains("$.todos[0].id: expected integer, got string"));
+        assert!(err.contains("$.todos[0].status: \"started\" is not one of [\"pending\",\"done\"]"));
+        assert!(err.contains("$.todos[0].extra: unexpected property"));
+
+        let err = todo_schema().validate("{}").unwrap_err().to_string();
+        assert!(err.contains("$: missing required property 'todos'"));
+    }
+
+    #[test]
+    fn test_json_object_mode() {
+        assert!(ResponseFormat::JsonObject.validate("{\"a\": 1}").is_ok());
+
+        let err = ResponseFormat::JsonObject.validate("[1, 2]").unwrap_err().to_string();
+        assert!(err.contains("should be a JSON object, got array"));
+
+        let err = ResponseFormat::JsonObject.validate("not json").unwrap_err().to_string();
+        assert!(err.contains("not valid JSON"));
+    }
+}
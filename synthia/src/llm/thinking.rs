@@ -0,0 +1,63 @@
+/// Separating model reasoning from the visible answer
+/// Reasoning arrives either on its own channel (`reasoning_content`) or inline as
+/// `<think>...</think>` blocks in the content; this handles the inline form
+const OPEN_TAG: &str = "<think>";
+const CLOSE_TAG: &str = "</think>";
+
+/// Split `<think>` blocks out of `text`, returning (reasoning, remaining text)
+/// An unclosed block runs to the end of the text, since output can be cut off mid-thought
+pub fn split_think_tags(text: &str) -> (Option<String>, String) {
+    if !text.contains(OPEN_TAG) {
+        return (None, text.to_string());
+    }
+
+    let mut thinking = Vec::new();
+    let mut answer = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(OPEN_TAG) {
+        answer.push_str(&rest[..start]);
+        let inner = &rest[start + OPEN_TAG.len()..];
+        match inner.find(CLOSE_TAG) {
+            Some(end) => {
+                thinking.push(inner[..end].trim().to_string());
+                rest = &inner[end + CLOSE_TAG.len()..];
+            }
+            None => {
+                thinking.push(inner.trim().to_string());
+                rest = "";
+            }
+        }
+    }
+    answer.push_str(rest);
+
+    thinking.retain(|t| !t.is_empty());
+    let thinking = (!thinking.is_empty()).then(|| thinking.join("\n\n"));
+    (thinking, answer.trim_start().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_think_tags() {
+        let (thinking, answer) = split_think_tags("<think>\nThe user wants a greeting.\n</think>\n\nHello!");
+        assert_eq!(thinking.as_deref(), Some("The user wants a greeting."));
+        assert_eq!(answer, "Hello!");
+    }
+
+    #[test]
+    fn test_text_without_tags_is_unchanged() {
+        assert_eq!(split_think_tags("  Just text\n"), (None, "  Just text\n".to_string()));
+        // An empty block isn't reasoning worth keeping, but the tags still go
+        assert_eq!(split_think_tags("<think></think>Hi"), (None, "Hi".to_string()));
+    }
+
+    #[test]
+    fn test_unclosed_block_runs_to_end() {
+        let (thinking, answer) = split_think_tags("<think>still going");
+        assert_eq!(thinking.as_deref(), Some("still going"));
+        assert_eq!(answer, "");
+    }
+}
@@ -12,22 +12,29 @@ mod tools;
 mod types;
 mod ui;
 
-use agent::{messages::Command, messages::UIUpdate, AgentActor};
+use agent::{messages::Command, messages::UIUpdate, AgentActor, LlmProfile};
 use project_context::ProjectContext;
-use anyhow::Result;
-use config::Config;
-use llm::{openai::OpenAICompatibleProvider, GenerationConfig};
+use anyhow::{Context, Result};
+use config::{Config, LLMConfig, ProviderKind};
+use llm::{
+    gemini::{self, GeminiProvider},
+    openai::OpenAICompatibleProvider,
+    GenerationConfig, LLMProvider,
+};
 use permission_manager::PermissionManager;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 use tools::{
-    bash::BashTool, edit::EditTool, git::GitTool, glob::GlobTool, grep::GrepTool,
-    powertools::PowertoolsTool, read::ReadTool, registry::ToolRegistry,
-    todo::TodoTool, webfetch::WebFetchTool, workshop::WorkshopTool, write::WriteTool,
+    apply_patch::ApplyPatchTool, bash::BashTool, edit::EditTool, git::GitTool, glob::GlobTool,
+    grep::GrepTool, list_directory::ListDirectoryTool, powertools::PowertoolsTool, read::ReadTool,
+    registry::ToolRegistry, subagent::SpawnSubagentTool,
+    todo::{SharedTodoList, TodoTool}, webfetch::WebFetchTool, workshop::WorkshopTool, write::WriteTool,
 };
 use tokio::sync::mpsc;
-use ui::App;
+use ui::{colors::Theme, App};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -43,8 +50,34 @@ async fn main() -> Result<()> {
         .with_max_level(tracing::Level::DEBUG)
         .init();
 
-    // Load configuration
+    // `--no-color` (like NO_COLOR in the environment) turns off every color escape
+    if std::env::args().skip(1).any(|arg| arg == "--no-color") {
+        ui::colors::set_no_color(true);
+    }
+
+    // `synthia --print-config`: show the effective (merged) config and exit
+    if std::env::args().skip(1).any(|arg| arg == "--print-config") {
+        let config = Config::load()?;
+        println!("# Effective Synthia config (highest priority last):");
+        for path in Config::config_sources() {
+            println!("#   {}", path.display());
+        }
+        print!("{}", config.to_effective_toml()?);
+        return Ok(());
+    }
+
+    // `synthia init [--force]` (or `--init-config`): write the commented config template and exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("init") || args.iter().any(|arg| arg == "--init-config") {
+        let path = Config::init(args.iter().any(|arg| arg == "--force"))?;
+        println!("Wrote config template to {}", path.display());
+        return Ok(());
+    }
+
+    // Load configuration, writing a starter template on first run
+    let created_config = Config::write_template_if_missing()?;
     let config = Config::load()?;
+    let theme = Theme::from_config(&config.ui.theme)?;
     tracing::info!("Configuration loaded successfully");
 
     // Load project context
@@ -62,11 +95,23 @@ async fn main() -> Result<()> {
         PermissionManager::new(project_root)?
     ));
 
-    // Create LLM provider
-    let llm_provider = Arc::new(OpenAICompatibleProvider::new(
-        config.llm.api_base.clone(),
-        config.llm.api_key.clone(),
-    ));
+    // Create channels
+    let (cmd_tx, cmd_rx) = mpsc::channel::<Command>(100);
+    let (ui_tx, ui_rx) = mpsc::channel::<UIUpdate>(100);
+
+    // Create a provider for every LLM profile so they can be switched at runtime
+    let mut llm_profiles = BTreeMap::new();
+    for name in config.llm.profile_names() {
+        let settings = config.llm.resolve_profile(&name)?;
+        let profile = build_llm_profile(&settings, &ui_tx)
+            .with_context(|| format!("LLM profile '{}'", name))?;
+        llm_profiles.insert(name, profile);
+    }
+    let active_profile = config.llm.active_profile_name().to_string();
+    let LlmProfile {
+        provider: llm_provider,
+        config: gen_config,
+    } = llm_profiles[&active_profile].clone();
 
     // Create tool registry with configured timeouts and output limits
     let mut tool_registry = ToolRegistry::new(permission_manager);
@@ -80,31 +125,54 @@ async fn main() -> Result<()> {
     )))?;
     tool_registry.register(Arc::new(WriteTool::new()))?;
     tool_registry.register(Arc::new(EditTool::new()))?;
+    tool_registry.register(Arc::new(ApplyPatchTool::new()))?;
     tool_registry.register(Arc::new(GrepTool::new()))?;
     tool_registry.register(Arc::new(GlobTool::new()))?;
+    tool_registry.register(Arc::new(ListDirectoryTool::new()))?;
     tool_registry.register(Arc::new(WebFetchTool::new()))?;
     tool_registry.register(Arc::new(GitTool::new(config.timeouts.git_timeout)))?;
     tool_registry.register(Arc::new(PowertoolsTool::new(config.tools.powertools_binary_path.clone())?))?;
     tool_registry.register(Arc::new(WorkshopTool::new(config.timeouts.workshop_timeout)))?;
-    tool_registry.register(Arc::new(TodoTool::new()))?;
-
-    // Create channels
-    let (cmd_tx, cmd_rx) = mpsc::channel::<Command>(100);
-    let (ui_tx, ui_rx) = mpsc::channel::<UIUpdate>(100);
+    // The agent keeps the todo list in the session so it survives reloads
+    let todo_list = SharedTodoList::default();
+    tool_registry.register(Arc::new(TodoTool::with_list(todo_list.clone())))?;
+    // Sub-agents use the active profile and run their tools through this same registry
+    let subagent_tool = Arc::new(SpawnSubagentTool::new(llm_provider.clone(), gen_config.clone()));
+    tool_registry.register(subagent_tool.clone())?;
 
     // Wire up UI sender to tool registry for edit approval
     tool_registry.set_ui_sender(ui_tx.clone());
+    if let Some(path) = created_config {
+        // Queued now, shown once the TUI starts
+        ui_tx
+            .send(UIUpdate::SystemMessage(format!(
+                "No config file found; wrote a commented template to {} (edit it to choose your model and API endpoint)",
+                path.display()
+            )))
+            .await?;
+    }
+    let tool_names = tool_registry.tool_names();
+    let unknown_timeouts = config.timeouts.unknown_tools(&tool_names);
+    if !unknown_timeouts.is_empty() {
+        ui_tx
+            .send(UIUpdate::SystemMessage(format!(
+                "Ignoring [timeouts] entries for unknown tools: {} (tools: {})",
+                unknown_timeouts.join(", "),
+                tool_names.join(", ")
+            )))
+            .await?;
+    }
+    tool_registry.set_timeouts(config.timeouts.clone());
+    tool_registry.set_cache_ttl(config.tools.cache_ttl());
+    tool_registry.set_retry_policy(config.tools.retry.clone());
+    tool_registry.set_output_policy(config.tools.output.clone());
+    tool_registry.set_edit_approval(config.ui.edit_approval);
+    tool_registry.set_auto_allow_read_only(config.tools.auto_allow_read_only);
     let tool_registry = Arc::new(tool_registry);
+    subagent_tool.attach_registry(&tool_registry);
 
-    // Create generation config from loaded settings
-    let gen_config = GenerationConfig {
-        model: config.llm.model.clone(),
-        temperature: config.llm.temperature,
-        max_tokens: config.llm.max_tokens,
-        streaming: config.llm.streaming,
-        reasoning_level: "medium".to_string(),  // default
-        context_window: config.llm.context_window.unwrap_or(8192),
-    };
+    let profile_names: Vec<String> = llm_profiles.keys().cloned().collect();
+    let active_model = gen_config.model.clone();
     let mut agent = AgentActor::new(
         llm_provider,
         tool_registry,
@@ -113,6 +181,10 @@ async fn main() -> Result<()> {
         cmd_rx,
         project_context.custom_instructions,
     );
+    agent.set_system_prompt(config.agent.system_prompt.clone(), project_context.prompt_fragment);
+    agent.set_llm_profiles(llm_profiles);
+    agent.set_todo_list(todo_list);
+    agent.set_autosave(config.session.autosave, config.session.autosave_interval());
 
     // Spawn agent actor
     tokio::spawn(async move {
@@ -123,7 +195,49 @@ async fn main() -> Result<()> {
 
     // Run TUI
     let mut app = App::new(cmd_tx, ui_rx);
+    app.set_syntax_highlighting(config.ui.syntax_highlighting);
+    app.set_theme(theme);
+    app.set_llm_profiles(profile_names, active_profile, active_model);
     app.run().await?;
 
     Ok(())
 }
+
+/// Create the provider and generation settings for one resolved LLM profile
+fn build_llm_profile(settings: &LLMConfig, ui_tx: &mpsc::Sender<UIUpdate>) -> Result<LlmProfile> {
+    let request_timeout = Duration::from_secs(settings.request_timeout_secs);
+    let provider: Arc<dyn LLMProvider> = match settings.provider {
+        ProviderKind::OpenAI => Arc::new(
+            OpenAICompatibleProvider::new(settings.api_base.clone(), settings.api_key.clone())
+                .with_request_timeout(request_timeout)
+                .with_rate_limit_retries(settings.rate_limit_retries)
+                .with_ui_sender(ui_tx.clone()),
+        ),
+        ProviderKind::Gemini => {
+            let api_key = GeminiProvider::resolve_api_key(settings.api_key.as_deref())
+                .context("Gemini needs an API key: set llm.api_key or the GEMINI_API_KEY environment variable")?;
+            let api_base = if settings.api_base == config::DEFAULT_API_BASE {
+                gemini::DEFAULT_API_BASE.to_string()
+            } else {
+                settings.api_base.clone()
+            };
+            Arc::new(GeminiProvider::new(api_base, api_key).with_request_timeout(request_timeout))
+        }
+    };
+
+    Ok(LlmProfile {
+        provider,
+        config: GenerationConfig {
+            model: settings.model.clone(),
+            temperature: settings.temperature,
+            max_tokens: settings.max_tokens,
+            streaming: settings.streaming,
+            reasoning_level: "medium".to_string(),  // default
+            context_window: settings.context_window.unwrap_or(8192),
+            stop: settings.stop.clone(),
+            seed: settings.seed,
+            vision: settings.vision,
+            response_format: None,
+        },
+    })
+}
@@ -11,6 +11,38 @@ pub enum PermissionDecision {
     Ask,
 }
 
+/// How much an operation could change, shown on permission prompts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OperationRisk {
+    /// Only looks at files or repository state
+    ReadOnly,
+    /// Talks to another machine
+    Network,
+    /// Changes files, the repository or stored notes
+    FileWrite,
+    /// Runs arbitrary commands; also assumed for tools we know nothing about
+    ShellExec,
+}
+
+impl OperationRisk {
+    pub fn label(self) -> &'static str {
+        match self {
+            OperationRisk::ReadOnly => "read-only",
+            OperationRisk::Network => "network",
+            OperationRisk::FileWrite => "file write",
+            OperationRisk::ShellExec => "shell exec",
+        }
+    }
+}
+
+/// Git subcommands that only inspect the repository
+const GIT_READ_ONLY: &[&str] = &[
+    "status", "diff", "log", "show", "blame", "rev-parse", "rev-list", "ls-files", "describe", "shortlog",
+];
+
+/// Git subcommands that reach a remote
+const GIT_NETWORK: &[&str] = &["push", "pull", "fetch", "clone", "ls-remote"];
+
 pub struct PermissionManager {
     config: PermissionConfig,
     config_path: PathBuf,
@@ -31,6 +63,10 @@ impl PermissionManager {
 
     /// Check if an operation is permitted
     pub fn check_permission(&self, tool: &str, params: &Value) -> PermissionDecision {
+        if tool == "apply_patch" {
+            return self.check_patch_permission(params);
+        }
+
         let pattern = self.build_pattern(tool, params);
 
         // Check deny list first (highest priority)
@@ -47,6 +83,56 @@ impl PermissionManager {
         PermissionDecision::Ask
     }
 
+    /// Check a patch against the Edit/Write rules for every file it touches
+    ///
+    /// Denied if any file is denied, allowed only if every file is allowed. Patches
+    /// that can't be parsed or leave their base directory are left to the prompt;
+    /// the tool rejects them itself.
+    fn check_patch_permission(&self, params: &Value) -> PermissionDecision {
+        if self.matches_any("apply_patch", &self.config.permissions.deny) {
+            return PermissionDecision::Deny;
+        }
+        let Some(targets) = self.patch_patterns(params) else {
+            return PermissionDecision::Ask;
+        };
+
+        // An Edit rule or a Write rule for the same file both cover patching it
+        let rules = |target: &String| [target.clone(), target.replacen("Edit(", "Write(", 1)];
+        let matches = |target: &String, patterns: &[String]| {
+            rules(target).iter().any(|rule| self.matches_any(rule, patterns))
+        };
+
+        if targets.iter().any(|t| matches(t, &self.config.permissions.deny)) {
+            PermissionDecision::Deny
+        } else if !targets.is_empty() && targets.iter().all(|t| matches(t, &self.config.permissions.allow)) {
+            PermissionDecision::Allow
+        } else {
+            PermissionDecision::Ask
+        }
+    }
+
+    /// One `Edit(//abs/path)` pattern per file an apply_patch call touches
+    ///
+    /// `None` if the patch can't be parsed or one of its paths leads outside the
+    /// patch's base directory.
+    pub fn patch_patterns(&self, params: &Value) -> Option<Vec<String>> {
+        let files = crate::tools::apply_patch::parse_patch(params["patch"].as_str()?).ok()?;
+        let base = match params["base_dir"].as_str() {
+            Some(dir) => PathBuf::from(self.normalize_path(dir)),
+            None => self.project_root.clone(),
+        };
+
+        let mut patterns = Vec::new();
+        for path in files.iter().flat_map(|file| file.paths()) {
+            let target = crate::tools::apply_patch::resolve_in(&base, path)?;
+            let pattern = format!("Edit({})", path_with_prefix(&target.to_string_lossy()));
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        Some(patterns)
+    }
+
     /// Add a permission pattern and save config
     pub fn add_permission(&mut self, pattern: String) -> Result<()> {
         self.config.add_permission(pattern)?;
@@ -68,13 +154,7 @@ impl PermissionManager {
             "read" => {
                 if let Some(file_path) = params["file_path"].as_str() {
                     let abs_path = self.normalize_path(file_path);
-                    // Prepend // only if path doesn't already start with /
-                    let path_with_prefix = if abs_path.starts_with('/') {
-                        format!("/{}", abs_path) // Add one / to make //
-                    } else {
-                        format!("//{}", abs_path)
-                    };
-                    format!("Read({})", path_with_prefix)
+                    format!("Read({})", path_with_prefix(&abs_path))
                 } else {
                     "Read(unknown)".to_string()
                 }
@@ -87,13 +167,7 @@ impl PermissionManager {
                         "edit" => "Edit",
                         _ => tool,
                     };
-                    // Prepend // only if path doesn't already start with /
-                    let path_with_prefix = if abs_path.starts_with('/') {
-                        format!("/{}", abs_path) // Add one / to make //
-                    } else {
-                        format!("//{}", abs_path)
-                    };
-                    format!("{}({})", tool_name, path_with_prefix)
+                    format!("{}({})", tool_name, path_with_prefix(&abs_path))
                 } else {
                     format!("{}(unknown)", tool)
                 }
@@ -107,6 +181,14 @@ impl PermissionManager {
                     } else {
                         "Git(unknown:*)".to_string()
                     }
+                } else if let Some(operation) = params["operation"].as_str() {
+                    // The passthrough operation is keyed by the real subcommand
+                    let subcommand = if operation == "run" {
+                        params["args"][0].as_str().unwrap_or("unknown")
+                    } else {
+                        operation
+                    };
+                    format!("Git({}:*)", subcommand)
                 } else {
                     "Git(unknown:*)".to_string()
                 }
@@ -201,6 +283,48 @@ impl PermissionManager {
         }
     }
 
+    /// Classify what a tool call could change, for the permission prompt
+    pub fn classify_risk(&self, tool: &str, params: &Value) -> OperationRisk {
+        match tool {
+            "read" | "grep" | "glob" | "list_directory" | "todo" => OperationRisk::ReadOnly,
+            "write" | "edit" | "apply_patch" => OperationRisk::FileWrite,
+            "bash" => OperationRisk::ShellExec,
+            // A sub-agent's own tool calls are prompted for separately
+            "webfetch" | "spawn_subagent" => OperationRisk::Network,
+            "git" => {
+                let subcommand = match params["operation"].as_str() {
+                    Some("run") => params["args"][0].as_str().unwrap_or("unknown"),
+                    Some(operation) => operation,
+                    None => "unknown",
+                };
+                // `git diff --output=<file>` and friends write the result to a file
+                let writes_output = params["args"].as_array().is_some_and(|args| {
+                    args.iter()
+                        .filter_map(Value::as_str)
+                        .any(|arg| arg == "-o" || arg.starts_with("--output"))
+                });
+                if writes_output {
+                    OperationRisk::FileWrite
+                } else if GIT_READ_ONLY.contains(&subcommand) {
+                    OperationRisk::ReadOnly
+                } else if GIT_NETWORK.contains(&subcommand) {
+                    OperationRisk::Network
+                } else {
+                    OperationRisk::FileWrite
+                }
+            }
+            "powertools" => match params["operation"].as_str() {
+                Some("index") => OperationRisk::FileWrite,
+                _ => OperationRisk::ReadOnly,
+            },
+            "workshop" => match params["operation"].as_str() {
+                Some("context" | "search" | "recent" | "why") => OperationRisk::ReadOnly,
+                _ => OperationRisk::FileWrite,
+            },
+            _ => OperationRisk::ShellExec,
+        }
+    }
+
     /// Generate suggested pattern for "don't ask again" based on tool and params
     pub fn suggest_pattern(&self, tool: &str, params: &Value) -> String {
         match tool {
@@ -236,6 +360,16 @@ impl PermissionManager {
     }
 }
 
+/// An absolute path as written in permission patterns, which start paths with `//`
+fn path_with_prefix(abs_path: &str) -> String {
+    // Prepend // only if path doesn't already start with /
+    if abs_path.starts_with('/') {
+        format!("/{}", abs_path) // Add one / to make //
+    } else {
+        format!("//{}", abs_path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,6 +467,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_patch_checked_against_each_target_file() {
+        let mut manager = create_test_manager();
+        let root = manager.project_root.to_string_lossy().to_string();
+        let patch = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+                     --- a/old.rs\n+++ b/new.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let params = serde_json::json!({ "patch": patch });
+
+        assert_eq!(
+            manager.patch_patterns(&params).unwrap(),
+            vec![
+                format!("Edit(/{}/src/a.rs)", root),
+                format!("Edit(/{}/old.rs)", root),
+                format!("Edit(/{}/new.rs)", root),
+            ]
+        );
+        assert_eq!(manager.check_permission("apply_patch", &params), PermissionDecision::Ask);
+
+        // Every file has to be allowed, by an Edit or a Write rule
+        manager.config.permissions.allow.push(format!("Edit(/{}/src/*)", root));
+        manager.config.permissions.allow.push(format!("Write(/{}/new.rs)", root));
+        assert_eq!(manager.check_permission("apply_patch", &params), PermissionDecision::Ask);
+        manager.config.permissions.allow.push(format!("Edit(/{}/old.rs)", root));
+        assert_eq!(manager.check_permission("apply_patch", &params), PermissionDecision::Allow);
+
+        // One denied file denies the whole patch
+        manager.config.permissions.deny.push(format!("Write(/{}/old.rs)", root));
+        assert_eq!(manager.check_permission("apply_patch", &params), PermissionDecision::Deny);
+    }
+
+    #[test]
+    fn test_patch_leaving_base_dir_has_no_patterns() {
+        let mut manager = create_test_manager();
+        manager.config.permissions.allow.push("Edit(*)".to_string());
+        let params = serde_json::json!({
+            "patch": "--- /dev/null\n+++ b/../outside.rs\n@@ -0,0 +1 @@\n+x\n"
+        });
+
+        assert_eq!(manager.patch_patterns(&params), None);
+        assert_eq!(manager.check_permission("apply_patch", &params), PermissionDecision::Ask);
+    }
+
     #[test]
     fn test_default_is_ask() {
         let manager = create_test_manager();
@@ -360,6 +536,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_git_operation_pattern() {
+        let mut manager = create_test_manager();
+        manager.add_permission("Git(status:*)".to_string()).unwrap();
+
+        assert_eq!(
+            manager.check_permission("git", &serde_json::json!({ "operation": "status" })),
+            PermissionDecision::Allow
+        );
+        assert_eq!(
+            manager.build_pattern("git", &serde_json::json!({ "operation": "run", "args": ["stash", "pop"] })),
+            "Git(stash:*)"
+        );
+    }
+
     #[test]
     fn test_suggest_pattern_bash() {
         let manager = create_test_manager();
@@ -371,6 +562,33 @@ mod tests {
         assert!(suggestion.contains("cargo"));
     }
 
+    #[test]
+    fn test_classify_risk() {
+        use serde_json::json;
+
+        let manager = create_test_manager();
+        let risk = |tool: &str, params: Value| manager.classify_risk(tool, &params);
+
+        assert_eq!(risk("read", json!({"file_path": "src/main.rs"})), OperationRisk::ReadOnly);
+        assert_eq!(risk("webfetch", json!({"url": "https://example.com"})), OperationRisk::Network);
+        assert_eq!(risk("write", json!({"file_path": "src/main.rs"})), OperationRisk::FileWrite);
+        assert_eq!(risk("bash", json!({"command": "rm -rf target"})), OperationRisk::ShellExec);
+
+        assert_eq!(risk("git", json!({"operation": "status"})), OperationRisk::ReadOnly);
+        assert_eq!(risk("git", json!({"operation": "run", "args": ["blame", "a.rs"]})), OperationRisk::ReadOnly);
+        assert_eq!(risk("git", json!({"operation": "push"})), OperationRisk::Network);
+        assert_eq!(risk("git", json!({"operation": "commit", "message": "x"})), OperationRisk::FileWrite);
+        assert_eq!(risk("git", json!({"operation": "run", "args": ["reset", "--hard"]})), OperationRisk::FileWrite);
+        assert_eq!(risk("git", json!({"operation": "run", "args": ["diff", "--output=x"]})), OperationRisk::FileWrite);
+        assert_eq!(risk("git", json!({"operation": "run", "args": ["log", "-o", "x"]})), OperationRisk::FileWrite);
+
+        assert_eq!(risk("workshop", json!({"operation": "search"})), OperationRisk::ReadOnly);
+        assert_eq!(risk("workshop", json!({"operation": "note"})), OperationRisk::FileWrite);
+
+        // Unknown (e.g. MCP) tools get the most caution
+        assert_eq!(risk("mcp__db__query", json!({})), OperationRisk::ShellExec);
+    }
+
     #[test]
     fn test_add_permission_saves_config() {
         let temp_dir = env::temp_dir();
@@ -9,6 +9,8 @@ use tracing::{warn, info};
 pub struct ProjectContext {
     /// Custom instructions from .synthia/.SYNTHIA.md (trimmed, None if empty)
     pub custom_instructions: Option<String>,
+    /// Text appended to the system prompt, from .synthia/system_prompt.md (None if missing or empty)
+    pub prompt_fragment: Option<String>,
     /// Path to the .synthia/ directory
     pub synthia_dir: PathBuf,
 }
@@ -23,6 +25,7 @@ impl ProjectContext {
                 warn!("Failed to load project context: {}. Continuing without project-specific instructions.", e);
                 Self {
                     custom_instructions: None,
+                    prompt_fragment: None,
                     synthia_dir: PathBuf::from(".synthia"),
                 }
             }
@@ -49,8 +52,17 @@ impl ProjectContext {
         // Read custom instructions
         let custom_instructions = Self::load_custom_instructions(&synthia_md)?;
 
+        // The system prompt fragment is optional, so it isn't created
+        let prompt_file = synthia_dir.join("system_prompt.md");
+        let prompt_fragment = if prompt_file.exists() {
+            Self::load_custom_instructions(&prompt_file)?
+        } else {
+            None
+        };
+
         Ok(Self {
             custom_instructions,
+            prompt_fragment,
             synthia_dir,
         })
     }
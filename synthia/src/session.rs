@@ -1,8 +1,15 @@
-use crate::types::Message;
+use crate::tools::todo::Todo;
+use crate::types::{ContentBlock, Message};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Most sessions `search` returns
+pub const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Characters of context kept on each side of a search match
+const SNIPPET_CONTEXT: usize = 40;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
@@ -12,6 +19,12 @@ pub struct Session {
     pub last_modified: i64,
     pub model: String,
     pub messages: Vec<Message>,
+    #[serde(default)]
+    pub todos: Vec<Todo>, // Todo tool list, restored when the session is reloaded
+    #[serde(default)]
+    pub parent_id: Option<String>, // Session this one was forked from
+    #[serde(default)]
+    pub tags: Vec<String>, // Free-form labels for grouping, e.g. by project or task
 }
 
 impl Session {
@@ -24,14 +37,59 @@ impl Session {
             last_modified: now,
             model,
             messages: Vec::new(),
+            todos: Vec::new(),
+            parent_id: None,
+            tags: Vec::new(),
         }
     }
 
+    /// A new session continuing from this one's first `message_count` messages
+    /// (all of them if `None`); this session is left as it is
+    pub fn fork(&self, message_count: Option<usize>) -> Self {
+        let count = message_count.unwrap_or(self.messages.len()).min(self.messages.len());
+        let parent_name = self.name.clone().unwrap_or_else(|| self.id.clone());
+
+        let mut fork = Self::new(self.model.clone());
+        fork.name = Some(format!("{} (fork)", parent_name));
+        fork.messages = self.messages[..count].to_vec();
+        fork.todos = self.todos.clone();
+        fork.parent_id = Some(self.id.clone());
+        fork.tags = self.tags.clone();
+        fork
+    }
+
     pub fn add_message(&mut self, message: Message) {
         self.messages.push(message);
         self.last_modified = chrono::Utc::now().timestamp_millis();
     }
 
+    pub fn set_todos(&mut self, todos: Vec<Todo>) {
+        self.todos = todos;
+        self.last_modified = chrono::Utc::now().timestamp_millis();
+    }
+
+    /// Add a tag (trimmed); returns false if it's empty or already present
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.tags.iter().any(|t| t == tag) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        self.last_modified = chrono::Utc::now().timestamp_millis();
+        true
+    }
+
+    /// Remove a tag; returns false if the session didn't have it
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let before = self.tags.len();
+        self.tags.retain(|t| t != tag.trim());
+        if self.tags.len() == before {
+            return false;
+        }
+        self.last_modified = chrono::Utc::now().timestamp_millis();
+        true
+    }
+
     pub fn set_name(&mut self, name: String) {
         self.name = Some(name);
         self.last_modified = chrono::Utc::now().timestamp_millis();
@@ -86,6 +144,10 @@ pub struct SessionInfo {
     pub last_modified: i64,
     pub model: String,
     pub message_count: usize,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 impl From<&Session> for SessionInfo {
@@ -97,6 +159,8 @@ impl From<&Session> for SessionInfo {
             last_modified: session.last_modified,
             model: session.model.clone(),
             message_count: session.messages.len(),
+            parent_id: session.parent_id.clone(),
+            tags: session.tags.clone(),
         }
     }
 }
@@ -137,6 +201,121 @@ pub fn list_sessions() -> Result<Vec<SessionInfo>> {
     Ok(sessions)
 }
 
+/// Saved sessions with `tag`, most recent first (case-insensitive)
+pub fn list_sessions_tagged(tag: &str) -> Result<Vec<SessionInfo>> {
+    Ok(filter_by_tag(list_sessions()?, tag))
+}
+
+fn filter_by_tag(sessions: Vec<SessionInfo>, tag: &str) -> Vec<SessionInfo> {
+    let tag = tag.trim();
+    sessions
+        .into_iter()
+        .filter(|session| session.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        .collect()
+}
+
+/// A saved session with a message containing the search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMatch {
+    pub id: String,
+    pub name: Option<String>,
+    pub last_modified: i64,
+    /// The first matching message, trimmed to the text around the match
+    pub snippet: String,
+}
+
+/// Find saved sessions with a message containing `query` (case-insensitive)
+///
+/// Session files are read one at a time, most recently modified first, and
+/// reading stops after [`MAX_SEARCH_RESULTS`] matching sessions.
+pub fn search(query: &str) -> Result<Vec<SessionMatch>> {
+    search_in(&get_sessions_dir()?, query, MAX_SEARCH_RESULTS)
+}
+
+fn search_in(sessions_dir: &Path, query: &str, limit: usize) -> Result<Vec<SessionMatch>> {
+    if query.trim().is_empty() || !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Order by file mtime so the newest sessions are searched without parsing the rest
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(sessions_dir)
+        .with_context(|| format!("Failed to read sessions directory: {:?}", sessions_dir))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+        .map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    let mut matches = Vec::new();
+    for (_, path) in files {
+        if matches.len() >= limit {
+            break;
+        }
+        let session: Session = match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| serde_json::from_str(&json).map_err(anyhow::Error::from))
+        {
+            Ok(session) => session,
+            Err(e) => {
+                tracing::warn!("Skipping unreadable session {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        let snippet = session.messages.iter().find_map(|message| {
+            message.content.iter().find_map(|block| match block {
+                ContentBlock::Text { text } => snippet_around(text, query),
+                _ => None,
+            })
+        });
+        if let Some(snippet) = snippet {
+            matches.push(SessionMatch {
+                id: session.id,
+                name: session.name,
+                last_modified: session.last_modified,
+                snippet,
+            });
+        }
+    }
+    Ok(matches)
+}
+
+/// The text around the first case-insensitive occurrence of `query`, on one line
+fn snippet_around(text: &str, query: &str) -> Option<String> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<char> = text.chars().collect();
+
+    // Compare char by char: lowercasing whole strings can shift byte offsets
+    let start = (0..chars.len()).find(|&i| {
+        let mut lowered = chars[i..].iter().flat_map(|c| c.to_lowercase());
+        query.iter().all(|q| lowered.next() == Some(*q))
+    })?;
+    let mut end = start;
+    let mut matched = 0;
+    while matched < query.len() {
+        matched += chars[end].to_lowercase().count();
+        end += 1;
+    }
+
+    let from = start.saturating_sub(SNIPPET_CONTEXT);
+    let to = (end + SNIPPET_CONTEXT).min(chars.len());
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(chars[from..to].iter().map(|&c| if c.is_whitespace() { ' ' } else { c }));
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
 #[allow(dead_code)]
 pub fn get_most_recent_session() -> Result<Option<Session>> {
     let sessions = list_sessions()?;
@@ -179,6 +358,7 @@ fn generate_session_id() -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::todo::TodoStatus;
     use crate::types::{ContentBlock, Role};
     use tempfile::TempDir;
     use std::env;
@@ -265,6 +445,165 @@ mod tests {
         assert_eq!(loaded.messages.len(), 1);
     }
 
+    #[test]
+    fn test_session_save_and_load_keeps_todos() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Override the sessions directory for this test
+        env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+
+        let mut session = Session::new("test-model".to_string());
+        session.set_todos(vec![Todo {
+            content: "Run tests".to_string(),
+            status: TodoStatus::Pending,
+            active_form: "Running tests".to_string(),
+        }]);
+        session.save().unwrap();
+
+        let loaded = Session::load(&session.id).unwrap();
+        assert_eq!(loaded.todos, session.todos);
+    }
+
+    #[test]
+    fn test_session_without_todos_field_loads() {
+        // Sessions saved before todos were persisted have no "todos" key
+        let json = r#"{"id":"old","name":null,"created_at":1,"last_modified":1,"model":"m","messages":[]}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert!(session.todos.is_empty());
+        assert!(session.tags.is_empty());
+        assert!(session.parent_id.is_none());
+
+        let info: SessionInfo = serde_json::from_str(
+            r#"{"id":"old","name":null,"created_at":1,"last_modified":1,"model":"m","message_count":0}"#,
+        )
+        .unwrap();
+        assert!(info.tags.is_empty());
+    }
+
+    #[test]
+    fn test_session_tags_round_trip() {
+        let mut session = Session::new("test-model".to_string());
+        assert!(session.add_tag(" powertools "));
+        assert!(session.add_tag("refactor"));
+        assert!(!session.add_tag("powertools"));
+        assert!(!session.add_tag("  "));
+
+        let json = serde_json::to_string(&session).unwrap();
+        let mut loaded: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.tags, vec!["powertools", "refactor"]);
+
+        assert!(loaded.remove_tag("refactor"));
+        assert!(!loaded.remove_tag("refactor"));
+        assert_eq!(loaded.tags, vec!["powertools"]);
+
+        let info = SessionInfo::from(&loaded);
+        let info: SessionInfo = serde_json::from_str(&serde_json::to_string(&info).unwrap()).unwrap();
+        assert_eq!(info.tags, vec!["powertools"]);
+    }
+
+    #[test]
+    fn test_filter_by_tag() {
+        let tagged = |id: &str, tags: &[&str]| {
+            let mut session = Session::new("m".to_string());
+            session.id = id.to_string();
+            for tag in tags {
+                session.add_tag(tag);
+            }
+            SessionInfo::from(&session)
+        };
+        let sessions = vec![tagged("a", &["Powertools"]), tagged("b", &["synthia"]), tagged("c", &["synthia", "powertools"])];
+
+        let ids: Vec<_> = filter_by_tag(sessions, "powertools").into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_fork_session() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Override the sessions directory for this test
+        env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+
+        let text = |text: &str| Message {
+            role: Role::User,
+            content: vec![ContentBlock::Text { text: text.to_string() }],
+        };
+        let mut parent = Session::new("test-model".to_string());
+        parent.set_name("Refactor".to_string());
+        for message in ["one", "two", "three"] {
+            parent.add_message(text(message));
+        }
+        parent.save().unwrap();
+
+        let mut fork = parent.fork(Some(2));
+        assert_ne!(fork.id, parent.id);
+        assert_eq!(fork.parent_id.as_deref(), Some(parent.id.as_str()));
+        assert_eq!(fork.name.as_deref(), Some("Refactor (fork)"));
+        assert_eq!(
+            serde_json::to_value(&fork.messages).unwrap(),
+            serde_json::to_value(&parent.messages[..2]).unwrap()
+        );
+
+        // The fork goes its own way; the parent on disk is untouched
+        fork.add_message(text("alternative"));
+        fork.save().unwrap();
+        let parent_on_disk = Session::load(&parent.id).unwrap();
+        assert_eq!(parent_on_disk.messages.len(), 3);
+        assert!(parent_on_disk.parent_id.is_none());
+        assert_eq!(Session::load(&fork.id).unwrap().messages.len(), 3);
+
+        // Without a cut-off point the whole conversation is copied
+        assert_eq!(parent.fork(None).messages.len(), 3);
+        assert_eq!(parent.fork(Some(10)).messages.len(), 3);
+    }
+
+    #[test]
+    fn test_search_finds_message_with_snippet() {
+        let dir = TempDir::new().unwrap();
+        let save = |session: &Session| {
+            let json = serde_json::to_string(session).unwrap();
+            fs::write(dir.path().join(format!("{}.json", session.id)), json).unwrap();
+        };
+
+        let mut session = Session::new("test-model".to_string());
+        session.set_name("Caching".to_string());
+        session.add_message(Message {
+            role: Role::User,
+            content: vec![ContentBlock::Text {
+                text: "Can we put an LRU cache in front of the\nSCIP index reader so repeated lookups are fast?".to_string(),
+            }],
+        });
+        save(&session);
+        let mut other = Session::new("test-model".to_string());
+        other.add_message(Message {
+            role: Role::User,
+            content: vec![ContentBlock::Text { text: "Unrelated".to_string() }],
+        });
+        save(&other);
+        fs::write(dir.path().join("corrupt.json"), "{").unwrap();
+
+        let matches = search_in(dir.path(), "scip INDEX", 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, session.id);
+        assert_eq!(matches[0].name.as_deref(), Some("Caching"));
+        assert_eq!(
+            matches[0].snippet,
+            "Can we put an LRU cache in front of the SCIP index reader so repeated lookups are fast?"
+        );
+
+        assert!(search_in(dir.path(), "no such topic", 10).unwrap().is_empty());
+        assert!(search_in(dir.path(), "  ", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_snippet_around() {
+        let text = format!("{}Straße{}", "a".repeat(50), "b".repeat(50));
+        let snippet = snippet_around(&text, "STRAßE").unwrap();
+        assert_eq!(snippet, format!("…{}Straße{}…", "a".repeat(40), "b".repeat(40)));
+        assert_eq!(snippet_around("İstanbul", "i̇stanbul").as_deref(), Some("İstanbul"));
+        assert!(snippet_around("short", "longer query").is_none());
+    }
+
     #[test]
     fn test_session_delete() {
         let temp_dir = TempDir::new().unwrap();
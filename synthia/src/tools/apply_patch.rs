@@ -0,0 +1,620 @@
+use super::{Tool, ToolResult};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+
+/// Applies a unified diff (git-style, possibly spanning several files)
+pub struct ApplyPatchTool;
+
+impl ApplyPatchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ApplyPatchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// 1-indexed start line in the original file (0 for an empty file)
+    pub old_start: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+/// All hunks for one file. `None` paths mean /dev/null (file created or deleted).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// Path to report for this file (new path, or old path for deletions)
+    pub fn display_path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or("unknown")
+    }
+
+    /// Old and new paths, each once
+    pub fn paths(&self) -> Vec<&str> {
+        let mut paths: Vec<&str> = self.old_path.iter().chain(&self.new_path).map(String::as_str).collect();
+        paths.dedup();
+        paths
+    }
+}
+
+/// `path` joined onto `base` with `.` and `..` resolved, or `None` if it leads outside `base`
+///
+/// Purely lexical: symlinks inside `base` aren't followed.
+pub fn resolve_in(base: &Path, path: &str) -> Option<PathBuf> {
+    let mut resolved = base.to_path_buf();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+                if !resolved.starts_with(base) {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Parse a unified diff into per-file patches
+pub fn parse_patch(patch: &str) -> Result<Vec<FilePatch>> {
+    let lines: Vec<&str> = patch.lines().collect();
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+
+        if let Some(old) = line.strip_prefix("--- ") {
+            let new = lines
+                .get(i + 1)
+                .and_then(|l| l.strip_prefix("+++ "))
+                .ok_or_else(|| anyhow!("Expected '+++' line after '{}'", line))?;
+            files.push(FilePatch {
+                old_path: parse_path(old),
+                new_path: parse_path(new),
+                hunks: Vec::new(),
+            });
+            i += 2;
+            continue;
+        }
+
+        if line.starts_with("@@") {
+            let file = files
+                .last_mut()
+                .ok_or_else(|| anyhow!("Hunk header before any file header: '{}'", line))?;
+            let (old_start, old_count, new_count) = parse_hunk_header(line)?;
+
+            // Hunk length comes from the header counts, so lines like "--- x"
+            // inside a hunk aren't mistaken for file headers
+            let mut hunk_lines = Vec::new();
+            let (mut old_left, mut new_left) = (old_count, new_count);
+            i += 1;
+            while (old_left > 0 || new_left > 0) && i < lines.len() {
+                let body = lines[i];
+                match body.chars().next() {
+                    Some('-') => {
+                        hunk_lines.push(HunkLine::Remove(body[1..].to_string()));
+                        old_left = old_left.saturating_sub(1);
+                    }
+                    Some('+') => {
+                        hunk_lines.push(HunkLine::Add(body[1..].to_string()));
+                        new_left = new_left.saturating_sub(1);
+                    }
+                    Some('\\') => {} // "\ No newline at end of file"
+                    // Some editors strip the single space from blank context lines
+                    Some(' ') | None => {
+                        let text = body.get(1..).unwrap_or("").to_string();
+                        hunk_lines.push(HunkLine::Context(text));
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                    }
+                    Some(_) => return Err(anyhow!("Malformed hunk line: '{}'", body)),
+                }
+                i += 1;
+            }
+
+            if old_left > 0 || new_left > 0 {
+                return Err(anyhow!(
+                    "Hunk for {} ended early (header: '{}')",
+                    file.display_path(),
+                    line
+                ));
+            }
+
+            file.hunks.push(Hunk {
+                old_start,
+                lines: hunk_lines,
+            });
+            continue;
+        }
+
+        // diff --git, index, mode lines and free text between files are ignored
+        i += 1;
+    }
+
+    files.retain(|f| !f.hunks.is_empty() || f.new_path.is_none());
+    Ok(files)
+}
+
+/// Parse a '---'/'+++' path, stripping git's a/ b/ prefixes and timestamps
+fn parse_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Parse "@@ -l,s +l,s @@" into (old_start, old_count, new_count)
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize)> {
+    let malformed = || anyhow!("Malformed hunk header: '{}'", line);
+
+    let inner = line
+        .strip_prefix("@@ ")
+        .and_then(|rest| rest.split(" @@").next())
+        .ok_or_else(malformed)?;
+    let mut parts = inner.split_whitespace();
+    let old = parts.next().and_then(|p| p.strip_prefix('-')).ok_or_else(malformed)?;
+    let new = parts.next().and_then(|p| p.strip_prefix('+')).ok_or_else(malformed)?;
+
+    let parse_range = |range: &str| -> Result<(usize, usize)> {
+        let mut split = range.splitn(2, ',');
+        let start = split.next().unwrap_or("").parse().map_err(|_| malformed())?;
+        let count = match split.next() {
+            Some(c) => c.parse().map_err(|_| malformed())?,
+            None => 1,
+        };
+        Ok((start, count))
+    };
+
+    let (old_start, old_count) = parse_range(old)?;
+    let (_, new_count) = parse_range(new)?;
+    Ok((old_start, old_count, new_count))
+}
+
+/// Apply hunks to file content
+///
+/// Each hunk is first tried at its stated position (adjusted for earlier hunks),
+/// then at the nearest position where its context matches, first exactly and
+/// then ignoring trailing whitespace. Returns an error message if any hunk's
+/// context can't be found.
+pub fn apply_hunks(content: &str, hunks: &[Hunk]) -> std::result::Result<String, String> {
+    let had_trailing_newline = content.is_empty() || content.ends_with('\n');
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let mut offset: isize = 0;
+    let mut min_start = 0;
+
+    for (n, hunk) in hunks.iter().enumerate() {
+        let old: Vec<&str> = hunk
+            .lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect();
+
+        let expected = (hunk.old_start.saturating_sub(1) as isize + offset).max(0) as usize;
+        let pos = find_hunk_position(&lines, &old, expected, min_start).ok_or_else(|| {
+            format!(
+                "hunk {} (line {}) context does not match the file",
+                n + 1,
+                hunk.old_start
+            )
+        })?;
+
+        // Keep the file's own context lines in case they only matched fuzzily
+        let mut replacement = Vec::new();
+        let mut file_idx = pos;
+        for line in &hunk.lines {
+            match line {
+                HunkLine::Context(_) => {
+                    replacement.push(lines[file_idx].clone());
+                    file_idx += 1;
+                }
+                HunkLine::Remove(_) => file_idx += 1,
+                HunkLine::Add(s) => replacement.push(s.clone()),
+            }
+        }
+
+        let new_len = replacement.len();
+        lines.splice(pos..pos + old.len(), replacement);
+        min_start = pos + new_len;
+        offset = (pos + new_len) as isize - (hunk.old_start.saturating_sub(1) + old.len()) as isize;
+    }
+
+    let mut result = lines.join("\n");
+    if had_trailing_newline && !result.is_empty() {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// How two lines are compared when locating a hunk
+type LineMatcher<'a> = &'a dyn Fn(&str, &str) -> bool;
+
+fn find_hunk_position(lines: &[String], old: &[&str], expected: usize, min_start: usize) -> Option<usize> {
+    if old.is_empty() {
+        return Some(expected.clamp(min_start, lines.len().max(min_start)));
+    }
+    if old.len() > lines.len() {
+        return None;
+    }
+
+    let max_start = lines.len() - old.len();
+    let exact = |a: &str, b: &str| a == b;
+    let loose = |a: &str, b: &str| a.trim_end() == b.trim_end();
+
+    let strategies: [LineMatcher; 2] = [&exact, &loose];
+
+    for matches in strategies {
+        let fits = |start: usize| {
+            start >= min_start
+                && start <= max_start
+                && old
+                    .iter()
+                    .enumerate()
+                    .all(|(i, o)| matches(lines[start + i].as_str(), o))
+        };
+
+        // Search outward from the expected position, nearest first
+        for distance in 0..=lines.len() {
+            if let Some(before) = expected.checked_sub(distance) {
+                if fits(before) {
+                    return Some(before);
+                }
+            }
+            if distance > 0 && fits(expected + distance) {
+                return Some(expected + distance);
+            }
+        }
+    }
+
+    None
+}
+
+/// What applying one file's patch will do
+enum PlannedChange {
+    Write { path: PathBuf, content: String, remove_old: Option<PathBuf> },
+    Delete { path: PathBuf },
+}
+
+async fn plan_file(base: &Path, file: &FilePatch) -> std::result::Result<PlannedChange, String> {
+    let resolve = |path: &str| {
+        resolve_in(base, path).ok_or_else(|| format!("{} is outside {}", path, base.display()))
+    };
+    match (&file.old_path, &file.new_path) {
+        (None, Some(new)) => {
+            let path = resolve(new)?;
+            if fs::metadata(&path).await.is_ok() {
+                return Err("file already exists".to_string());
+            }
+            let content = apply_hunks("", &file.hunks)?;
+            Ok(PlannedChange::Write { path, content, remove_old: None })
+        }
+        (Some(old), None) => {
+            let path = resolve(old)?;
+            if fs::metadata(&path).await.is_err() {
+                return Err("file to delete does not exist".to_string());
+            }
+            Ok(PlannedChange::Delete { path })
+        }
+        (Some(old), Some(new)) => {
+            let old_path = resolve(old)?;
+            let path = resolve(new)?;
+            let content = fs::read_to_string(&old_path)
+                .await
+                .map_err(|e| format!("failed to read: {}", e))?;
+            let content = apply_hunks(&content, &file.hunks)?;
+            let remove_old = if path != old_path { Some(old_path) } else { None };
+            Ok(PlannedChange::Write { path, content, remove_old })
+        }
+        (None, None) => Err("patch has no file paths".to_string()),
+    }
+}
+
+#[async_trait]
+impl Tool for ApplyPatchTool {
+    fn name(&self) -> &str {
+        "apply_patch"
+    }
+
+    fn description(&self) -> &str {
+        "Apply a unified diff (git-style, may span multiple files). Use for multi-hunk or multi-file edits. \
+        All files are checked first; if any hunk's context doesn't match, nothing is written."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff text with '--- a/path', '+++ b/path' and '@@' hunk headers"
+                },
+                "base_dir": {
+                    "type": "string",
+                    "description": "Directory the patch paths are relative to (defaults to current directory)"
+                }
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult> {
+        let patch = params["patch"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Missing 'patch' parameter"))?;
+        let base = super::expand_path(params["base_dir"].as_str().unwrap_or("."))?;
+
+        let files = match parse_patch(patch) {
+            Ok(files) if files.is_empty() => {
+                return Ok(ToolResult {
+                    content: "Patch contains no file changes".to_string(),
+                    is_error: true,
+                });
+            }
+            Ok(files) => files,
+            Err(e) => {
+                return Ok(ToolResult {
+                    content: format!("Failed to parse patch: {}", e),
+                    is_error: true,
+                });
+            }
+        };
+
+        // Check every file before touching any of them
+        let mut planned = Vec::new();
+        let mut report = Vec::new();
+        let mut failed = false;
+        for file in &files {
+            match plan_file(&base, file).await {
+                Ok(change) => {
+                    report.push(format!("✓ {}: {} hunk(s)", file.display_path(), file.hunks.len()));
+                    planned.push(change);
+                }
+                Err(e) => {
+                    report.push(format!("✗ {}: {}", file.display_path(), e));
+                    failed = true;
+                }
+            }
+        }
+
+        if failed {
+            return Ok(ToolResult {
+                content: format!("Patch rejected, no files were changed:\n{}", report.join("\n")),
+                is_error: true,
+            });
+        }
+
+        for change in planned {
+            match change {
+                PlannedChange::Write { path, content, remove_old } => {
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent).await?;
+                    }
+                    fs::write(&path, content).await?;
+                    if let Some(old) = remove_old {
+                        fs::remove_file(old).await?;
+                    }
+                }
+                PlannedChange::Delete { path } => fs::remove_file(path).await?,
+            }
+        }
+
+        Ok(ToolResult {
+            content: format!("Applied patch to {} file(s):\n{}", files.len(), report.join("\n")),
+            is_error: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_patch_headers() {
+        let patch = "diff --git a/src/a.rs b/src/a.rs\n\
+                     index 111..222 100644\n\
+                     --- a/src/a.rs\n\
+                     +++ b/src/a.rs\n\
+                     @@ -1,2 +1,2 @@\n \
+                     fn a() {}\n\
+                     -fn b() {}\n\
+                     +fn c() {}\n\
+                     --- /dev/null\n\
+                     +++ b/new.txt\n\
+                     @@ -0,0 +1 @@\n\
+                     +hello\n";
+
+        let files = parse_patch(patch).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].old_path.as_deref(), Some("src/a.rs"));
+        assert_eq!(files[0].hunks[0].old_start, 1);
+        assert_eq!(files[0].hunks[0].lines.len(), 3);
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].new_path.as_deref(), Some("new.txt"));
+    }
+
+    #[test]
+    fn test_apply_hunks_with_offset() {
+        // Hunk says line 2, but two lines were inserted above since the diff was made
+        let content = "extra\nextra\none\ntwo\nthree\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Context("one".into()),
+                HunkLine::Remove("two".into()),
+                HunkLine::Add("TWO".into()),
+                HunkLine::Context("three".into()),
+            ],
+        }];
+
+        let result = apply_hunks(content, &hunks).unwrap();
+        assert_eq!(result, "extra\nextra\none\nTWO\nthree\n");
+    }
+
+    #[test]
+    fn test_apply_hunks_ignores_trailing_whitespace() {
+        let content = "keep   \nold\n";
+        let hunks = vec![Hunk {
+            old_start: 1,
+            lines: vec![
+                HunkLine::Context("keep".into()),
+                HunkLine::Remove("old".into()),
+                HunkLine::Add("new".into()),
+            ],
+        }];
+
+        assert_eq!(apply_hunks(content, &hunks).unwrap(), "keep   \nnew\n");
+    }
+
+    #[tokio::test]
+    async fn test_apply_two_file_patch() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.txt"), "alpha\nbeta\ngamma\n").await.unwrap();
+        fs::create_dir_all(temp.path().join("src")).await.unwrap();
+        fs::write(temp.path().join("src/b.rs"), "fn main() {\n    old();\n}\n").await.unwrap();
+
+        let patch = "--- a/a.txt\n\
+                     +++ b/a.txt\n\
+                     @@ -1,3 +1,3 @@\n \
+                     alpha\n\
+                     -beta\n\
+                     +BETA\n \
+                     gamma\n\
+                     --- a/src/b.rs\n\
+                     +++ b/src/b.rs\n\
+                     @@ -1,3 +1,4 @@\n \
+                     fn main() {\n\
+                     -    old();\n\
+                     +    new();\n\
+                     +    more();\n \
+                     }\n";
+
+        let tool = ApplyPatchTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "patch": patch,
+                "base_dir": temp.path().to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error, "{}", result.content);
+        assert!(result.content.contains("✓ a.txt"));
+        assert!(result.content.contains("✓ src/b.rs"));
+        assert_eq!(
+            fs::read_to_string(temp.path().join("a.txt")).await.unwrap(),
+            "alpha\nBETA\ngamma\n"
+        );
+        assert_eq!(
+            fs::read_to_string(temp.path().join("src/b.rs")).await.unwrap(),
+            "fn main() {\n    new();\n    more();\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_context_mismatch_rejects_whole_patch() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.txt"), "one\ntwo\n").await.unwrap();
+        fs::write(temp.path().join("b.txt"), "something else entirely\n").await.unwrap();
+
+        let patch = "--- a/a.txt\n\
+                     +++ b/a.txt\n\
+                     @@ -1,2 +1,2 @@\n \
+                     one\n\
+                     -two\n\
+                     +TWO\n\
+                     --- a/b.txt\n\
+                     +++ b/b.txt\n\
+                     @@ -1,2 +1,2 @@\n \
+                     expected context\n\
+                     -old line\n\
+                     +new line\n";
+
+        let tool = ApplyPatchTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "patch": patch,
+                "base_dir": temp.path().to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.is_error);
+        assert!(result.content.contains("✗ b.txt"));
+        assert!(result.content.contains("context does not match"));
+        // The file whose hunks did match must not be written either
+        assert_eq!(fs::read_to_string(temp.path().join("a.txt")).await.unwrap(), "one\ntwo\n");
+    }
+
+    #[tokio::test]
+    async fn test_paths_outside_base_dir_are_rejected() {
+        let base = Path::new("/project");
+        assert_eq!(resolve_in(base, "src/./a.rs"), Some(PathBuf::from("/project/src/a.rs")));
+        assert_eq!(resolve_in(base, "src/../b.rs"), Some(PathBuf::from("/project/b.rs")));
+        assert_eq!(resolve_in(base, "../b.rs"), None);
+        assert_eq!(resolve_in(base, "src/../../b.rs"), None);
+        assert_eq!(resolve_in(base, "/etc/passwd"), None);
+
+        let temp = TempDir::new().unwrap();
+        let project = temp.path().join("project");
+        fs::create_dir_all(&project).await.unwrap();
+        fs::write(project.join("in.txt"), "one\n").await.unwrap();
+
+        let patch = "--- a/in.txt\n\
+                     +++ b/in.txt\n\
+                     @@ -1 +1 @@\n\
+                     -one\n\
+                     +ONE\n\
+                     --- /dev/null\n\
+                     +++ b/../outside.txt\n\
+                     @@ -0,0 +1 @@\n\
+                     +escaped\n";
+
+        let result = ApplyPatchTool::new()
+            .execute(serde_json::json!({
+                "patch": patch,
+                "base_dir": project.to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.is_error);
+        assert!(result.content.contains("✗ ../outside.txt: ../outside.txt is outside"), "{}", result.content);
+        assert!(fs::metadata(temp.path().join("outside.txt")).await.is_err());
+        assert_eq!(fs::read_to_string(project.join("in.txt")).await.unwrap(), "one\n");
+    }
+}
@@ -1,3 +1,4 @@
+use super::output::strip_ansi;
 use super::{Tool, ToolResult};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -39,6 +40,10 @@ impl Tool for BashTool {
                 "command": {
                     "type": "string",
                     "description": "The bash command to execute"
+                },
+                "keep_color": {
+                    "type": "boolean",
+                    "description": "Keep ANSI color codes in the output (stripped by default)"
                 }
             },
             "required": ["command"]
@@ -58,7 +63,12 @@ impl Tool for BashTool {
 
         let result = timeout(
             Duration::from_secs(self.timeout_seconds),
-            Command::new("bash").arg("-c").arg(command).output(),
+            // kill_on_drop so a canceled or timed-out command doesn't outlive the tool call
+            Command::new("bash")
+                .arg("-c")
+                .arg(command)
+                .kill_on_drop(true)
+                .output(),
         )
         .await;
 
@@ -79,8 +89,12 @@ impl Tool for BashTool {
             }
         };
 
-        let stdout = String::from_utf8_lossy(&result.stdout);
-        let stderr = String::from_utf8_lossy(&result.stderr);
+        let mut stdout = String::from_utf8_lossy(&result.stdout).into_owned();
+        let mut stderr = String::from_utf8_lossy(&result.stderr).into_owned();
+        if !params["keep_color"].as_bool().unwrap_or(false) {
+            stdout = strip_ansi(&stdout);
+            stderr = strip_ansi(&stderr);
+        }
 
         // Prepend command for clarity
         let mut content = if !stderr.is_empty() {
@@ -149,6 +163,26 @@ mod tests {
         assert!(result.is_error);
     }
 
+    #[tokio::test]
+    async fn test_bash_strips_color_codes() {
+        let tool = BashTool::new(5, 50_000);
+        let command = r"printf '\033[1;32mok\033[0m done\n'; printf '\033[31merror\033[0m\n' >&2";
+
+        let result = tool
+            .execute(serde_json::json!({ "command": command }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("stdout:\nok done\n"));
+        assert!(result.content.contains("stderr:\nerror\n"));
+        assert!(!result.content.contains('\x1b'));
+
+        let result = tool
+            .execute(serde_json::json!({ "command": command, "keep_color": true }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("\x1b[1;32mok\x1b[0m done"));
+    }
+
     #[tokio::test]
     async fn test_bash_timeout() {
         let tool = BashTool::new(1, 50_000);
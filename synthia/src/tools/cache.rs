@@ -2,14 +2,16 @@ use lru::LruCache;
 use serde_json::Value;
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use super::ToolResult;
 
 type CacheKey = (String, String); // (tool_name, params_hash)
 
 /// Thread-safe LRU cache for tool results
 pub struct ToolCache {
-    cache: Arc<Mutex<LruCache<CacheKey, ToolResult>>>,
+    cache: Arc<Mutex<LruCache<CacheKey, (ToolResult, Instant)>>>,
     enabled: bool,
+    ttl: Option<Duration>,
     hits: Arc<Mutex<u64>>,
     misses: Arc<Mutex<u64>>,
 }
@@ -22,6 +24,7 @@ impl ToolCache {
                 LruCache::new(NonZeroUsize::new(capacity).unwrap())
             )),
             enabled: true,
+            ttl: None,
             hits: Arc::new(Mutex::new(0)),
             misses: Arc::new(Mutex::new(0)),
         }
@@ -36,8 +39,17 @@ impl ToolCache {
         let key = self.make_key(tool_name, params);
         let mut cache = self.cache.lock().unwrap();
 
+        let expired = match (cache.peek(&key), self.ttl) {
+            (Some((_, stored_at)), Some(ttl)) => stored_at.elapsed() >= ttl,
+            _ => false,
+        };
+        if expired {
+            cache.pop(&key);
+            tracing::debug!("Cache entry expired for tool '{}': params={}", tool_name, params);
+        }
+
         match cache.get(&key) {
-            Some(result) => {
+            Some((result, _)) => {
                 *self.hits.lock().unwrap() += 1;
                 tracing::debug!("Cache hit for tool '{}': params={}", tool_name, params);
                 Some(result.clone())
@@ -58,7 +70,7 @@ impl ToolCache {
 
         let key = self.make_key(tool_name, params);
         let mut cache = self.cache.lock().unwrap();
-        cache.put(key, result);
+        cache.put(key, (result, Instant::now()));
         tracing::debug!("Cached result for tool '{}': params={}", tool_name, params);
     }
 
@@ -126,6 +138,11 @@ impl ToolCache {
         tracing::info!("Cache {}", if enabled { "enabled" } else { "disabled" });
     }
 
+    /// Expire entries after `ttl` (None keeps them until evicted or invalidated)
+    pub fn set_ttl(&mut self, ttl: Option<Duration>) {
+        self.ttl = ttl;
+    }
+
     /// Check if cache is enabled
     #[allow(dead_code)]
     pub fn is_enabled(&self) -> bool {
@@ -298,6 +315,25 @@ mod tests {
         assert!(cache.is_enabled());
     }
 
+    #[test]
+    fn test_cache_ttl_expiry() {
+        let mut cache = ToolCache::new(10);
+        cache.set_ttl(Some(Duration::from_millis(20)));
+
+        let params = serde_json::json!({"file_path": "/tmp/test.txt"});
+        let result = ToolResult {
+            content: "test".to_string(),
+            is_error: false,
+        };
+
+        cache.put("read", &params, result);
+        assert!(cache.get("read", &params).is_some());
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(cache.get("read", &params).is_none());
+        assert_eq!(cache.stats().size, 0);
+    }
+
     #[test]
     fn test_cache_clear() {
         let cache = ToolCache::new(10);
@@ -1,6 +1,7 @@
 use super::{Tool, ToolResult};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use serde::Serialize;
 use serde_json::Value;
 use std::time::Duration;
 use tokio::process::Command;
@@ -9,12 +10,13 @@ use tokio::time::timeout;
 /// Git tool that provides common git operations by shelling out to git commands.
 /// Each operation is specified via the "operation" parameter:
 /// - init: Initialize a new git repository
-/// - status: Check working tree status
-/// - diff: Show changes (optional: staged, unstaged, or both)
+/// - status: Check working tree status (structured JSON)
+/// - diff: Show changes (optional: staged, limited to a path)
 /// - log: Show commit history (configurable limit)
 /// - add: Stage files
-/// - commit: Create commits with messages
+/// - commit: Create a commit (optional author, sign-off, GPG signing) and report its hash
 /// - push: Push to remote
+/// - run: Any other git command, passed through as raw arguments
 pub struct GitTool {
     timeout_seconds: u64,
 }
@@ -52,15 +54,38 @@ impl GitTool {
     }
 
     async fn git_status(&self, cwd: Option<&str>) -> Result<ToolResult> {
-        self.run_git_command(&["status"], cwd).await
+        let mut cmd = Command::new("git");
+        cmd.args(["status", "--porcelain=v1", "--branch", "-z"]);
+        if let Some(dir) = cwd {
+            cmd.current_dir(dir);
+        }
+
+        let result = timeout(Duration::from_secs(self.timeout_seconds), cmd.output()).await??;
+        if !result.status.success() {
+            return Ok(ToolResult {
+                content: format!(
+                    "Command: git status\n\nstderr:\n{}",
+                    String::from_utf8_lossy(&result.stderr)
+                ),
+                is_error: true,
+            });
+        }
+
+        let status = parse_porcelain_status(&String::from_utf8_lossy(&result.stdout));
+        Ok(ToolResult {
+            content: serde_json::to_string_pretty(&status)?,
+            is_error: false,
+        })
     }
 
-    async fn git_diff(&self, cwd: Option<&str>, staged: bool) -> Result<ToolResult> {
-        let args = if staged {
-            vec!["diff", "--cached"]
-        } else {
-            vec!["diff"]
-        };
+    async fn git_diff(&self, cwd: Option<&str>, staged: bool, path: Option<&str>) -> Result<ToolResult> {
+        let mut args = vec!["diff"];
+        if staged {
+            args.push("--cached");
+        }
+        if let Some(path) = path {
+            args.extend(["--", path]);
+        }
         self.run_git_command(&args, cwd).await
     }
 
@@ -82,13 +107,23 @@ impl GitTool {
         self.run_git_command(&args, cwd).await
     }
 
-    async fn git_commit(&self, cwd: Option<&str>, message: String) -> Result<ToolResult> {
-        if message.is_empty() {
+    async fn git_commit(&self, cwd: Option<&str>, options: CommitOptions) -> Result<ToolResult> {
+        if options.message.is_empty() {
             return Err(anyhow!("Commit message cannot be empty"));
         }
 
-        let args = vec!["commit", "-m", &message];
-        self.run_git_command(&args, cwd).await
+        let args = commit_args(&options);
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        let mut result = self.run_git_command(&args, cwd).await?;
+        if result.is_error {
+            return Ok(result);
+        }
+
+        let head = self.run_git_command(&["rev-parse", "HEAD"], cwd).await?;
+        if let Some(hash) = head.content.lines().last().filter(|_| !head.is_error) {
+            result.content.push_str(&format!("\nCommit: {}\n", hash.trim()));
+        }
+        Ok(result)
     }
 
     async fn git_push(
@@ -112,6 +147,173 @@ impl GitTool {
     async fn git_init(&self, cwd: Option<&str>) -> Result<ToolResult> {
         self.run_git_command(&["init"], cwd).await
     }
+
+    async fn git_run(&self, cwd: Option<&str>, args: Vec<String>) -> Result<ToolResult> {
+        if args.is_empty() {
+            return Err(anyhow!("No arguments specified for git run"));
+        }
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        self.run_git_command(&args, cwd).await
+    }
+}
+
+/// Options for the `commit` operation
+#[derive(Debug, Default)]
+pub struct CommitOptions {
+    pub message: String,
+    /// Author in `Name <email>` form, instead of the configured user
+    pub author: Option<String>,
+    /// Add a `Signed-off-by` trailer
+    pub signoff: bool,
+    /// Force GPG signing on or off; None follows the repository's `commit.gpgsign`
+    pub sign: Option<bool>,
+}
+
+/// Arguments for `git commit` with the given options
+pub fn commit_args(options: &CommitOptions) -> Vec<String> {
+    let mut args = vec!["commit".to_string(), "-m".to_string(), options.message.clone()];
+    // One argument each, so a value starting with '-' can't be taken for a flag
+    if let Some(author) = &options.author {
+        args.push(format!("--author={}", author));
+    }
+    if options.signoff {
+        args.push("--signoff".to_string());
+    }
+    match options.sign {
+        Some(true) => args.push("--gpg-sign".to_string()),
+        Some(false) => args.push("--no-gpg-sign".to_string()),
+        None => {}
+    }
+    args
+}
+
+/// Working tree status, parsed from `git status --porcelain=v1 --branch -z`
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct GitStatus {
+    /// Current branch, or None when HEAD is detached
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub ahead: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub behind: u32,
+    pub staged: Vec<FileChange>,
+    pub unstaged: Vec<FileChange>,
+    pub untracked: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicted: Vec<String>,
+    pub clean: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    /// added, modified, deleted, renamed, copied or type_changed
+    pub change: &'static str,
+    /// Original path of a rename or copy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn change_type(code: char) -> &'static str {
+    match code {
+        'A' => "added",
+        'D' => "deleted",
+        'R' => "renamed",
+        'C' => "copied",
+        'T' => "type_changed",
+        _ => "modified",
+    }
+}
+
+/// Parse NUL-separated porcelain v1 output into a [`GitStatus`]
+pub fn parse_porcelain_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut entries = output.split('\0').filter(|e| !e.is_empty());
+
+    while let Some(entry) = entries.next() {
+        if let Some(header) = entry.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+
+        let mut codes = entry.chars();
+        let (Some(x), Some(y)) = (codes.next(), codes.next()) else {
+            continue;
+        };
+        let path = entry.get(3..).unwrap_or_default().to_string();
+        // Renames and copies are followed by their original path
+        let from = if matches!(x, 'R' | 'C') || matches!(y, 'R' | 'C') {
+            entries.next().map(String::from)
+        } else {
+            None
+        };
+
+        match (x, y) {
+            ('?', '?') => status.untracked.push(path),
+            ('!', '!') => {}
+            ('D', 'D') | ('A', 'A') | ('U', _) | (_, 'U') => status.conflicted.push(path),
+            _ => {
+                if x != ' ' {
+                    status.staged.push(FileChange {
+                        path: path.clone(),
+                        change: change_type(x),
+                        from: from.clone(),
+                    });
+                }
+                if y != ' ' {
+                    status.unstaged.push(FileChange {
+                        path,
+                        change: change_type(y),
+                        from,
+                    });
+                }
+            }
+        }
+    }
+
+    status.clean = status.staged.is_empty()
+        && status.unstaged.is_empty()
+        && status.untracked.is_empty()
+        && status.conflicted.is_empty();
+    status
+}
+
+/// Parse a branch header such as `main...origin/main [ahead 1, behind 2]`
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    let (refs, tracking) = match header.split_once(" [") {
+        Some((refs, tracking)) => (refs, tracking.trim_end_matches(']')),
+        None => (header, ""),
+    };
+
+    let refs = refs
+        .strip_prefix("No commits yet on ")
+        .or_else(|| refs.strip_prefix("Initial commit on "))
+        .unwrap_or(refs);
+    if refs.starts_with("HEAD (no branch)") {
+        return;
+    }
+
+    match refs.split_once("...") {
+        Some((branch, upstream)) => {
+            status.branch = Some(branch.to_string());
+            status.upstream = Some(upstream.to_string());
+        }
+        None => status.branch = Some(refs.to_string()),
+    }
+
+    for part in tracking.split(", ") {
+        if let Some(n) = part.strip_prefix("ahead ") {
+            status.ahead = n.parse().unwrap_or(0);
+        } else if let Some(n) = part.strip_prefix("behind ") {
+            status.behind = n.parse().unwrap_or(0);
+        }
+    }
 }
 
 #[async_trait]
@@ -121,7 +323,9 @@ impl Tool for GitTool {
     }
 
     fn description(&self) -> &str {
-        "Execute git operations (init, status, diff, log, add, commit, push)"
+        "Execute git operations (init, status, diff, log, add, commit, push). status returns JSON with \
+        staged/unstaged/untracked files; commit takes optional author/signoff/sign and reports the new \
+        commit hash; use 'run' with raw args for any other git command."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -131,7 +335,7 @@ impl Tool for GitTool {
                 "operation": {
                     "type": "string",
                     "description": "Git operation to perform",
-                    "enum": ["init", "status", "diff", "log", "add", "commit", "push"]
+                    "enum": ["init", "status", "diff", "log", "add", "commit", "push", "run"]
                 },
                 "cwd": {
                     "type": "string",
@@ -141,6 +345,10 @@ impl Tool for GitTool {
                     "type": "boolean",
                     "description": "For diff: show staged changes (default: false)"
                 },
+                "path": {
+                    "type": "string",
+                    "description": "For diff: limit the diff to this file or directory"
+                },
                 "limit": {
                     "type": "integer",
                     "description": "For log: number of commits to show (default: 10)"
@@ -156,6 +364,18 @@ impl Tool for GitTool {
                     "type": "string",
                     "description": "For commit: commit message"
                 },
+                "author": {
+                    "type": "string",
+                    "description": "For commit: author as 'Name <email>' (default: configured user)"
+                },
+                "signoff": {
+                    "type": "boolean",
+                    "description": "For commit: add a Signed-off-by trailer (default: false)"
+                },
+                "sign": {
+                    "type": "boolean",
+                    "description": "For commit: GPG-sign (true) or don't (false); omit to follow git config"
+                },
                 "remote": {
                     "type": "string",
                     "description": "For push: remote name (default: origin)"
@@ -163,6 +383,13 @@ impl Tool for GitTool {
                 "branch": {
                     "type": "string",
                     "description": "For push: branch name (optional)"
+                },
+                "args": {
+                    "type": "array",
+                    "items": {
+                        "type": "string"
+                    },
+                    "description": "For run: git arguments, e.g. [\"stash\", \"list\"]"
                 }
             },
             "required": ["operation"]
@@ -181,7 +408,7 @@ impl Tool for GitTool {
             "status" => self.git_status(cwd).await,
             "diff" => {
                 let staged = params["staged"].as_bool().unwrap_or(false);
-                self.git_diff(cwd, staged).await
+                self.git_diff(cwd, staged, params["path"].as_str()).await
             }
             "log" => {
                 let limit = params["limit"].as_u64().map(|l| l as u32);
@@ -201,13 +428,28 @@ impl Tool for GitTool {
                     .as_str()
                     .ok_or_else(|| anyhow!("Missing 'message' parameter for commit operation"))?
                     .to_string();
-                self.git_commit(cwd, message).await
+                let options = CommitOptions {
+                    message,
+                    author: params["author"].as_str().map(|s| s.to_string()),
+                    signoff: params["signoff"].as_bool().unwrap_or(false),
+                    sign: params["sign"].as_bool(),
+                };
+                self.git_commit(cwd, options).await
             }
             "push" => {
                 let remote = params["remote"].as_str().map(|s| s.to_string());
                 let branch = params["branch"].as_str().map(|s| s.to_string());
                 self.git_push(cwd, remote, branch).await
             }
+            "run" => {
+                let args = params["args"]
+                    .as_array()
+                    .ok_or_else(|| anyhow!("Missing 'args' parameter for run operation"))?
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect();
+                self.git_run(cwd, args).await
+            }
             _ => Err(anyhow!("Unknown git operation: {}", operation)),
         }
     }
@@ -305,9 +547,8 @@ mod tests {
             .unwrap();
 
         assert!(!result.is_error);
-        assert!(
-            result.content.contains("On branch") || result.content.contains("No commits yet")
-        );
+        let status: Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(status["clean"], true);
 
         cleanup_test_repo(&repo_dir).await.unwrap();
     }
@@ -672,8 +913,177 @@ mod tests {
             .unwrap();
 
         assert!(!status_result.is_error);
-        assert!(status_result.content.contains("On branch") || status_result.content.contains("No commits yet"));
+        assert!(status_result.content.contains("\"clean\": true"));
 
         cleanup_test_repo(&temp_dir).await.unwrap();
     }
+
+    #[test]
+    fn test_parse_porcelain_status() {
+        let output = "## main...origin/main [ahead 2, behind 1]\0\
+            M  src/lib.rs\0\
+            \x20M README.md\0\
+            MM src/main.rs\0\
+            A  new.rs\0\
+            R  renamed.rs\0old.rs\0\
+            \x20D gone.rs\0\
+            UU conflict.rs\0\
+            ?? notes with space.txt\0";
+
+        let status = parse_porcelain_status(output);
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/main"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+
+        let staged: Vec<(&str, &str)> =
+            status.staged.iter().map(|c| (c.path.as_str(), c.change)).collect();
+        assert_eq!(
+            staged,
+            vec![
+                ("src/lib.rs", "modified"),
+                ("src/main.rs", "modified"),
+                ("new.rs", "added"),
+                ("renamed.rs", "renamed"),
+            ]
+        );
+        assert_eq!(status.staged[3].from.as_deref(), Some("old.rs"));
+
+        let unstaged: Vec<(&str, &str)> =
+            status.unstaged.iter().map(|c| (c.path.as_str(), c.change)).collect();
+        assert_eq!(
+            unstaged,
+            vec![("README.md", "modified"), ("src/main.rs", "modified"), ("gone.rs", "deleted")]
+        );
+        assert_eq!(status.untracked, vec!["notes with space.txt"]);
+        assert_eq!(status.conflicted, vec!["conflict.rs"]);
+        assert!(!status.clean);
+    }
+
+    #[test]
+    fn test_commit_args() {
+        let options = CommitOptions {
+            message: "Fix parser".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(commit_args(&options), vec!["commit", "-m", "Fix parser"]);
+
+        let options = CommitOptions {
+            message: "-x looks like a flag".to_string(),
+            author: Some("Jo Doe <jo@example.com>".to_string()),
+            signoff: true,
+            sign: Some(true),
+        };
+        assert_eq!(
+            commit_args(&options),
+            vec!["commit", "-m", "-x looks like a flag", "--author=Jo Doe <jo@example.com>", "--signoff", "--gpg-sign"]
+        );
+
+        let options = CommitOptions {
+            message: "Unsigned".to_string(),
+            sign: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(commit_args(&options), vec!["commit", "-m", "Unsigned", "--no-gpg-sign"]);
+    }
+
+    #[tokio::test]
+    async fn test_git_commit_with_author_and_signoff() {
+        let repo_dir = create_test_repo().await.unwrap();
+        let tool = GitTool::new(5);
+        let cwd = repo_dir.to_str().unwrap();
+
+        fs::write(repo_dir.join("a.txt"), "a").unwrap();
+        tool.execute(serde_json::json!({ "operation": "add", "cwd": cwd, "files": ["a.txt"] }))
+            .await
+            .unwrap();
+        let result = tool
+            .execute(serde_json::json!({
+                "operation": "commit",
+                "cwd": cwd,
+                "message": "Add a",
+                "author": "Jo Doe <jo@example.com>",
+                "signoff": true,
+                "sign": false
+            }))
+            .await
+            .unwrap();
+        assert!(!result.is_error, "{}", result.content);
+
+        let hash = result.content.lines().find_map(|l| l.strip_prefix("Commit: ")).unwrap();
+        assert_eq!(hash.len(), 40);
+
+        let log = tool
+            .execute(serde_json::json!({ "operation": "run", "cwd": cwd, "args": ["log", "-1", "--format=%H%n%an%n%b"] }))
+            .await
+            .unwrap();
+        assert!(log.content.contains(hash));
+        assert!(log.content.contains("Jo Doe"));
+        assert!(log.content.contains("Signed-off-by: Test User <test@example.com>"));
+
+        cleanup_test_repo(&repo_dir).await.unwrap();
+    }
+
+    #[test]
+    fn test_parse_porcelain_status_new_repo() {
+        let status = parse_porcelain_status("## No commits yet on master\0");
+        assert_eq!(status.branch.as_deref(), Some("master"));
+        assert_eq!(status.upstream, None);
+        assert!(status.clean);
+
+        let detached = parse_porcelain_status("## HEAD (no branch)\0");
+        assert_eq!(detached.branch, None);
+    }
+
+    #[tokio::test]
+    async fn test_git_status_structured_and_diff_path_filter() {
+        let repo_dir = create_test_repo().await.unwrap();
+        let tool = GitTool::new(5);
+        let cwd = repo_dir.to_str().unwrap();
+
+        fs::write(repo_dir.join("a.txt"), "a").unwrap();
+        fs::write(repo_dir.join("b.txt"), "b").unwrap();
+        tool.execute(serde_json::json!({ "operation": "add", "cwd": cwd, "files": ["a.txt", "b.txt"] }))
+            .await
+            .unwrap();
+        tool.execute(serde_json::json!({ "operation": "commit", "cwd": cwd, "message": "Initial commit" }))
+            .await
+            .unwrap();
+
+        fs::write(repo_dir.join("a.txt"), "a changed").unwrap();
+        fs::write(repo_dir.join("b.txt"), "b changed").unwrap();
+        fs::write(repo_dir.join("c.txt"), "c").unwrap();
+        tool.execute(serde_json::json!({ "operation": "add", "cwd": cwd, "files": ["b.txt"] }))
+            .await
+            .unwrap();
+
+        let result = tool
+            .execute(serde_json::json!({ "operation": "status", "cwd": cwd }))
+            .await
+            .unwrap();
+        let status: Value = serde_json::from_str(&result.content).unwrap();
+        assert_eq!(status["staged"][0]["path"], "b.txt");
+        assert_eq!(status["unstaged"][0]["path"], "a.txt");
+        assert_eq!(status["untracked"][0], "c.txt");
+
+        let result = tool
+            .execute(serde_json::json!({ "operation": "diff", "cwd": cwd, "staged": true, "path": "a.txt" }))
+            .await
+            .unwrap();
+        assert!(!result.content.contains("b changed"));
+
+        let result = tool
+            .execute(serde_json::json!({ "operation": "diff", "cwd": cwd, "path": "a.txt" }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("a changed"));
+
+        let result = tool
+            .execute(serde_json::json!({ "operation": "run", "cwd": cwd, "args": ["rev-list", "--count", "HEAD"] }))
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+        assert!(result.content.trim_end().ends_with('1'));
+
+        cleanup_test_repo(&repo_dir).await.unwrap();
+    }
 }
@@ -1,58 +1,116 @@
 use super::{Tool, ToolResult};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use glob::{MatchOptions, Pattern};
+use ignore::WalkBuilder;
 use serde_json::Value;
-use std::process::Stdio;
-use tokio::process::Command;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 
-pub struct GlobTool;
-
-impl GlobTool {
-    pub fn new() -> Self {
-        Self
-    }
-
-    async fn try_fd(&self, pattern: &str, path: &str) -> Result<std::process::Output> {
-        let mut cmd = Command::new("fd");
-        cmd.arg("--type").arg("f"); // Only files
-        cmd.arg("--color").arg("never"); // No colors
-        cmd.arg(pattern); // Pattern
-        cmd.arg(path); // Search path
+/// Maximum paths returned before output is truncated
+const MAX_RESULTS: usize = 1000;
 
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
+pub struct GlobTool;
 
-        cmd.output().await.map_err(|e| anyhow::anyhow!("fd failed: {}", e))
-    }
+/// A compiled glob plus what it's matched against
+struct FileMatcher {
+    pattern: Pattern,
+    /// Patterns without a '/' match the file name at any depth ("*.rs"),
+    /// others match the path relative to the search root ("src/**/*.rs")
+    match_name: bool,
+}
 
-    async fn try_find(&self, pattern: &str, path: &str) -> Result<std::process::Output> {
-        let mut cmd = Command::new("find");
-        cmd.arg(path);
-        cmd.arg("-type").arg("f");
-
-        // Convert glob pattern to find-compatible pattern
-        // Strip **/ prefix since find is recursive by default
-        let find_pattern = if let Some(stripped) = pattern.strip_prefix("**/") {
-            stripped.to_string()  // "**/*.rs" → "*.rs"
-        } else if pattern == "**/*" || pattern == "**" {
-            "*".to_string()  // "**/*" → "*" (match all files)
+impl FileMatcher {
+    fn new(pattern: &str) -> Result<Self> {
+        let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+        let name_only = pattern.strip_prefix("**/").unwrap_or(pattern);
+        let match_name = !name_only.contains('/');
+        let pattern = if !match_name {
+            pattern.to_string()
+        } else if name_only.contains(['*', '?', '[']) {
+            name_only.to_string()
         } else {
-            pattern.to_string()  // No change for simple patterns
+            // A bare word ("config") finds any file name containing it
+            format!("*{}*", name_only)
         };
+        Ok(Self {
+            pattern: Pattern::new(&pattern)
+                .map_err(|e| anyhow!("Invalid glob pattern '{}': {}", pattern, e))?,
+            match_name,
+        })
+    }
 
-        if find_pattern.contains('*') {
-            cmd.arg("-name").arg(find_pattern);
+    fn matches(&self, relative: &Path) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        if self.match_name {
+            relative
+                .file_name()
+                .is_some_and(|name| self.pattern.matches_with(&name.to_string_lossy(), options))
         } else {
-            cmd.arg("-name").arg(format!("*{}*", find_pattern));
+            self.pattern.matches_path_with(relative, options)
         }
+    }
+}
 
-        cmd.stdout(Stdio::piped());
-        cmd.stderr(Stdio::piped());
+impl GlobTool {
+    pub fn new() -> Self {
+        Self
+    }
 
-        cmd.output().await.map_err(|e| anyhow::anyhow!("find failed: {}", e))
+    /// Walk `root` and collect files matching any of `matchers`, sorted and deduplicated
+    /// Returns the matches and whether the result was truncated
+    fn find(root: &Path, matchers: &[FileMatcher], include_ignored: bool) -> Result<(Vec<String>, bool)> {
+        let walker = WalkBuilder::new(root)
+            .hidden(!include_ignored)
+            .git_ignore(!include_ignored)
+            .git_global(!include_ignored)
+            .git_exclude(!include_ignored)
+            .ignore(!include_ignored)
+            .parents(!include_ignored)
+            .require_git(false)
+            .follow_links(false)
+            .build();
+
+        let mut matches = BTreeSet::new();
+        for entry in walker {
+            let entry = entry?;
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            if matchers.iter().any(|m| m.matches(relative)) {
+                matches.insert(entry.path().display().to_string());
+            }
+        }
+
+        let truncated = matches.len() > MAX_RESULTS;
+        Ok((matches.into_iter().take(MAX_RESULTS).collect(), truncated))
     }
 }
 
+/// Accept `pattern` as either a single string or an array of strings
+fn parse_patterns(value: &Value) -> Result<Vec<String>> {
+    let patterns: Vec<String> = match value {
+        Value::String(pattern) => vec![pattern.clone()],
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(String::from)
+                    .ok_or_else(|| anyhow!("Invalid 'pattern' parameter: array items must be strings"))
+            })
+            .collect::<Result<_>>()?,
+        _ => return Err(anyhow!("Missing 'pattern' parameter")),
+    };
+    if patterns.is_empty() {
+        return Err(anyhow!("Missing 'pattern' parameter"));
+    }
+    Ok(patterns)
+}
+
 #[async_trait]
 impl Tool for GlobTool {
     fn name(&self) -> &str {
@@ -60,7 +118,8 @@ impl Tool for GlobTool {
     }
 
     fn description(&self) -> &str {
-        "Find files matching a glob pattern (e.g., '*.rs', 'src/**/*.ts'). Returns list of matching file paths."
+        "Find files matching one or more glob patterns (e.g., '*.rs', 'src/**/*.ts'). \
+        Skips .gitignore'd and hidden files by default. Returns a sorted list of matching file paths."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -68,12 +127,19 @@ impl Tool for GlobTool {
             "type": "object",
             "properties": {
                 "pattern": {
-                    "type": "string",
-                    "description": "The glob pattern to match files against (e.g., '*.rs', '**/*.json')"
+                    "oneOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ],
+                    "description": "Glob pattern, or array of patterns, to match files against (e.g., '*.rs', ['**/*.json', 'src/**/*.toml']). Patterns without '/' match file names at any depth"
                 },
                 "path": {
                     "type": "string",
                     "description": "Directory to search in (defaults to current directory)"
+                },
+                "include_ignored": {
+                    "type": "boolean",
+                    "description": "Also match .gitignore'd and hidden files, e.g. inside target/ or node_modules/ (default: false)"
                 }
             },
             "required": ["pattern"]
@@ -81,46 +147,47 @@ impl Tool for GlobTool {
     }
 
     async fn execute(&self, params: Value) -> Result<ToolResult> {
-        let pattern = params["pattern"]
-            .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' parameter"))?;
-
+        let patterns = parse_patterns(&params["pattern"])?;
         let path = params["path"].as_str().unwrap_or(".");
+        let include_ignored = params["include_ignored"].as_bool().unwrap_or(false);
+        let pattern_list = patterns.join(", ");
 
-        // Try fd first, fall back to find if not available
-        let output = match self.try_fd(pattern, path).await {
-            Ok(output) => output,
-            Err(_) => {
-                // fd not available, try find
-                self.try_find(pattern, path).await?
-            }
-        };
-
-        if !output.status.success() && output.stdout.is_empty() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if stderr.contains("No such file or directory") {
+        let matchers = match patterns.iter().map(|p| FileMatcher::new(p)).collect::<Result<Vec<_>>>() {
+            Ok(matchers) => matchers,
+            Err(e) => {
                 return Ok(ToolResult {
-                    content: format!("Path not found: {}", path),
+                    content: e.to_string(),
                     is_error: true,
                 });
             }
+        };
+
+        let root: PathBuf = super::expand_path(path)?;
+        if !root.is_dir() {
             return Ok(ToolResult {
-                content: format!("No files found matching pattern: {}", pattern),
-                is_error: false,
+                content: format!("Path not found: {}", path),
+                is_error: true,
             });
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (matches, truncated) =
+            tokio::task::spawn_blocking(move || Self::find(&root, &matchers, include_ignored)).await??;
 
-        if stdout.trim().is_empty() {
+        if matches.is_empty() {
             return Ok(ToolResult {
-                content: format!("No files found matching pattern: {}", pattern),
+                content: format!("No files found matching pattern: {}", pattern_list),
                 is_error: false,
             });
         }
 
         // Prepend search context for clarity
-        let output = format!("Pattern: {}\nPath: {}\n\n{}", pattern, path, stdout);
+        let mut output = format!("Pattern: {}\nPath: {}\n\n{}\n", pattern_list, path, matches.join("\n"));
+        if truncated {
+            output.push_str(&format!(
+                "\n(truncated at {} files; use a more specific pattern)\n",
+                MAX_RESULTS
+            ));
+        }
 
         Ok(ToolResult {
             content: output,
@@ -288,4 +355,88 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(temp_dir).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_glob_respects_gitignore() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join(".gitignore"), "target/\nnode_modules/\n").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("target/debug/build.rs"), "").unwrap();
+        std::fs::write(root.join("node_modules/pkg/index.js"), "").unwrap();
+
+        let tool = GlobTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": ["**/*.rs", "*.js"],
+                "path": root.to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("lib.rs"));
+        assert!(!result.content.contains("build.rs"));
+        assert!(!result.content.contains("index.js"));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": ["**/*.rs", "*.js"],
+                "path": root.to_str().unwrap(),
+                "include_ignored": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("build.rs"));
+        assert!(result.content.contains("index.js"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_multiple_patterns_sorted_and_deduplicated() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/b.rs"), "").unwrap();
+        std::fs::write(root.join("src/nested/a.rs"), "").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "").unwrap();
+        std::fs::write(root.join("notes.md"), "").unwrap();
+
+        let tool = GlobTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                // src/b.rs matches the first two patterns but should be listed once
+                "pattern": ["*.rs", "src/*.rs", "*.toml"],
+                "path": root.to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+
+        let files: Vec<&str> = result.content.lines().skip(3).filter(|l| !l.is_empty()).collect();
+        let expected: Vec<String> = ["Cargo.toml", "src/b.rs", "src/nested/a.rs"]
+            .iter()
+            .map(|f| root.join(f).display().to_string())
+            .collect();
+        assert_eq!(files, expected);
+    }
+
+    #[tokio::test]
+    async fn test_glob_path_pattern_is_anchored() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/top.rs"), "").unwrap();
+        std::fs::write(root.join("src/nested/deep.rs"), "").unwrap();
+
+        let tool = GlobTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "src/*.rs",
+                "path": root.to_str().unwrap()
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("top.rs"));
+        assert!(!result.content.contains("deep.rs"));
+    }
 }
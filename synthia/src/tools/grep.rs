@@ -1,45 +1,63 @@
 use super::{Tool, ToolResult};
 use anyhow::Result;
 use async_trait::async_trait;
+use ignore::WalkBuilder;
+use regex::RegexBuilder;
 use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::Path;
 use std::process::Stdio;
 use tokio::process::Command;
 
 pub struct GrepTool;
 
+/// What to search for and how, shared by every search backend
+struct GrepOptions<'a> {
+    pattern: &'a str,
+    path: &'a str,
+    case_insensitive: bool,
+    files_with_matches: bool,
+    /// Treat the pattern as literal text rather than a regex
+    fixed_string: bool,
+    /// Let the pattern match across line boundaries
+    multiline: bool,
+    glob: Option<&'a str>,
+}
+
 impl GrepTool {
     pub fn new() -> Self {
         Self
     }
 
-    async fn try_ripgrep(
-        &self,
-        pattern: &str,
-        path: &str,
-        case_insensitive: bool,
-        files_with_matches: bool,
-        glob: Option<&str>,
-    ) -> Result<std::process::Output> {
+    async fn try_ripgrep(&self, options: &GrepOptions<'_>) -> Result<std::process::Output> {
         let mut cmd = Command::new("rg");
 
-        // Add pattern and path
-        cmd.arg(pattern).arg(path);
+        // Add pattern and path; --regexp keeps a pattern starting with '-' from being read as a flag
+        cmd.arg(format!("--regexp={}", options.pattern)).arg(options.path);
 
         // Add flags
         cmd.arg("--color").arg("never"); // No ANSI colors
         cmd.arg("--no-heading"); // Don't group by file
 
-        if case_insensitive {
+        if options.case_insensitive {
             cmd.arg("--ignore-case");
         }
 
-        if files_with_matches {
+        if options.fixed_string {
+            cmd.arg("--fixed-strings");
+        }
+
+        if options.multiline {
+            cmd.arg("--multiline");
+        }
+
+        if options.files_with_matches {
             cmd.arg("--files-with-matches");
         } else {
             cmd.arg("--line-number"); // Show line numbers
         }
 
-        if let Some(g) = glob {
+        if let Some(g) = options.glob {
             cmd.arg("--glob").arg(g);
         }
 
@@ -50,38 +68,35 @@ impl GrepTool {
         cmd.output().await.map_err(|e| anyhow::anyhow!("ripgrep failed: {}", e))
     }
 
-    async fn try_grep(
-        &self,
-        pattern: &str,
-        path: &str,
-        case_insensitive: bool,
-        files_with_matches: bool,
-        glob: Option<&str>,
-    ) -> Result<std::process::Output> {
+    async fn try_grep(&self, options: &GrepOptions<'_>) -> Result<std::process::Output> {
         let mut cmd = Command::new("grep");
 
         // Add flags
         cmd.arg("-r"); // Recursive
 
-        if case_insensitive {
+        if options.case_insensitive {
             cmd.arg("-i");
         }
 
-        if files_with_matches {
+        if options.fixed_string {
+            cmd.arg("-F");
+        }
+
+        if options.files_with_matches {
             cmd.arg("-l"); // Only filenames
         } else {
             cmd.arg("-n"); // Line numbers
         }
 
         // Add pattern
-        cmd.arg(pattern);
+        cmd.arg("-e").arg(options.pattern);
 
         // Add path
-        cmd.arg(path);
+        cmd.arg(options.path);
 
         // Note: standard grep doesn't have direct glob support
         // For glob filtering, we'd need to combine with find, which is complex
-        if glob.is_some() {
+        if options.glob.is_some() {
             tracing::warn!("Glob filtering not supported with standard grep, ignoring");
         }
 
@@ -93,6 +108,65 @@ impl GrepTool {
     }
 }
 
+/// Multiline search without ripgrep, since standard grep only matches within a line
+///
+/// Output follows ripgrep's: `path:line:text` for every line a match touches, or
+/// one path per matching file with `files_with_matches`.
+fn search_multiline(options: &GrepOptions) -> Result<String> {
+    let pattern = if options.fixed_string {
+        regex::escape(options.pattern)
+    } else {
+        options.pattern.to_string()
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(options.case_insensitive)
+        .multi_line(true)
+        .build()?;
+    let glob = options.glob.map(glob::Pattern::new).transpose()?;
+
+    let root = Path::new(options.path);
+    let mut output = String::new();
+    for entry in WalkBuilder::new(root).require_git(false).build() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        if let Some(glob) = &glob {
+            let name_matches = entry.file_name().to_str().is_some_and(|name| glob.matches(name));
+            if !name_matches && !glob.matches_path(relative) {
+                continue;
+            }
+        }
+        // Binary and unreadable files are skipped, as ripgrep does
+        let Ok(content) = std::fs::read_to_string(entry.path()) else { continue };
+
+        let path = entry.path().display();
+        if options.files_with_matches {
+            if regex.is_match(&content) {
+                writeln!(output, "{}", path)?;
+            }
+            continue;
+        }
+
+        let mut last_printed = 0;
+        for m in regex.find_iter(&content) {
+            let first_line = content[..m.start()].matches('\n').count() + 1;
+            let start = content[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+            let end = content[m.end()..].find('\n').map_or(content.len(), |i| m.end() + i);
+            for (offset, line) in content[start..end].lines().enumerate() {
+                let line_number = first_line + offset;
+                // Overlapping matches share lines; print each line once
+                if line_number > last_printed {
+                    writeln!(output, "{}:{}:{}", path, line_number, line)?;
+                    last_printed = line_number;
+                }
+            }
+        }
+    }
+    Ok(output)
+}
+
 #[async_trait]
 impl Tool for GrepTool {
     fn name(&self) -> &str {
@@ -100,7 +174,9 @@ impl Tool for GrepTool {
     }
 
     fn description(&self) -> &str {
-        "Search for patterns in files using ripgrep. Returns matching lines with file paths and line numbers."
+        "Search for patterns in files using ripgrep. Returns matching lines with file paths and line numbers, \
+        or just the matching files with files_with_matches. Supports literal (fixed_string) and \
+        cross-line (multiline) patterns."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -123,6 +199,15 @@ impl Tool for GrepTool {
                     "type": "boolean",
                     "description": "Only show file names with matches, not match content (default: false)"
                 },
+                "fixed_string": {
+                    "type": "boolean",
+                    "description": "Treat the pattern as literal text, not a regex (default: false)"
+                },
+                "multiline": {
+                    "type": "boolean",
+                    "description": "Allow matches to span lines: \\n in the pattern matches a line break; \
+                        prefix with (?s) to let . match one too (default: false)"
+                },
                 "glob": {
                     "type": "string",
                     "description": "Only search files matching this glob pattern (e.g., '*.rs', '*.ts')"
@@ -138,20 +223,39 @@ impl Tool for GrepTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' parameter"))?;
 
         let path = params["path"].as_str().unwrap_or(".");
-        let case_insensitive = params["case_insensitive"].as_bool().unwrap_or(false);
-        let files_with_matches = params["files_with_matches"].as_bool().unwrap_or(false);
-        let glob = params["glob"].as_str();
+        let options = GrepOptions {
+            pattern,
+            path,
+            case_insensitive: params["case_insensitive"].as_bool().unwrap_or(false),
+            files_with_matches: params["files_with_matches"].as_bool().unwrap_or(false),
+            fixed_string: params["fixed_string"].as_bool().unwrap_or(false),
+            multiline: params["multiline"].as_bool().unwrap_or(false),
+            glob: params["glob"].as_str(),
+        };
 
         // Try ripgrep first, fall back to grep if not available
-        let output = match self
-            .try_ripgrep(pattern, path, case_insensitive, files_with_matches, glob)
-            .await
-        {
+        let output = match self.try_ripgrep(&options).await {
             Ok(output) => output,
+            Err(_) if options.multiline => {
+                if !Path::new(path).exists() {
+                    return Ok(ToolResult {
+                        content: format!("Path not found: {}", path),
+                        is_error: true,
+                    });
+                }
+                let matches = search_multiline(&options)?;
+                return Ok(ToolResult {
+                    content: if matches.is_empty() {
+                        format!("No matches found for pattern: {}", pattern)
+                    } else {
+                        matches
+                    },
+                    is_error: false,
+                });
+            }
             Err(_) => {
                 // Ripgrep not available, try standard grep
-                self.try_grep(pattern, path, case_insensitive, files_with_matches, glob)
-                    .await?
+                self.try_grep(&options).await?
             }
         };
 
@@ -274,4 +378,103 @@ mod tests {
         // Cleanup
         fs::remove_file(temp_path).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_grep_fixed_string() {
+        let temp_path = "/tmp/synthia_grep_test_fixed.txt";
+        fs::write(temp_path, "let v = vec![1];\nlet v = vec!(1);\n-x flag").await.unwrap();
+
+        let tool = GrepTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "vec![1]",
+                "path": temp_path,
+                "fixed_string": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("vec![1]"));
+        assert!(!result.content.contains("vec!(1)"));
+
+        // A pattern that looks like a flag is still a pattern
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "-x",
+                "path": temp_path,
+                "fixed_string": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("-x flag"));
+
+        fs::remove_file(temp_path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_grep_multiline() {
+        let temp_path = "/tmp/synthia_grep_test_multiline.rs";
+        fs::write(temp_path, "fn parse(\n    input: &str,\n) -> u32 {\n    0\n}\n").await.unwrap();
+
+        let tool = GrepTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "fn parse\\(\\n\\s*input",
+                "path": temp_path,
+                "multiline": true
+            }))
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+        assert!(result.content.contains("1:fn parse("), "{}", result.content);
+        assert!(result.content.contains("2:    input: &str,"), "{}", result.content);
+        assert!(!result.content.contains("-> u32"));
+
+        // Without multiline, a pattern can't cross a line break
+        let result = tool
+            .execute(serde_json::json!({
+                "pattern": "fn parse\\(\\n\\s*input",
+                "path": temp_path
+            }))
+            .await
+            .unwrap();
+        assert!(!result.content.contains("input: &str"));
+
+        fs::remove_file(temp_path).await.unwrap();
+    }
+
+    #[test]
+    fn test_search_multiline_without_ripgrep() {
+        let dir = std::env::temp_dir().join(format!("synthia_grep_multiline_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.rs"), "struct A {\n    x: u32,\n}\n").unwrap();
+        std::fs::write(dir.join("b.rs"), "struct B;\n").unwrap();
+        std::fs::write(dir.join("c.txt"), "struct C {\n    x: u32,\n}\n").unwrap();
+        let path = dir.to_str().unwrap();
+
+        let options = GrepOptions {
+            pattern: "{\n    x",
+            path,
+            case_insensitive: false,
+            files_with_matches: false,
+            fixed_string: true,
+            multiline: true,
+            glob: Some("*.rs"),
+        };
+        let output = search_multiline(&options).unwrap();
+        let a = dir.join("a.rs").display().to_string();
+        assert_eq!(output, format!("{a}:1:struct A {{\n{a}:2:    x: u32,\n"));
+
+        let options = GrepOptions {
+            files_with_matches: true,
+            glob: None,
+            ..options
+        };
+        let output = search_multiline(&options).unwrap();
+        let mut files: Vec<&str> = output.lines().collect();
+        files.sort();
+        let c = dir.join("c.txt").display().to_string();
+        assert_eq!(files, vec![a.as_str(), c.as_str()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,276 @@
+use super::{Tool, ToolResult};
+use anyhow::Result;
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Maximum entries listed before output is truncated
+const MAX_ENTRIES: usize = 1000;
+
+pub struct ListDirectoryTool;
+
+impl ListDirectoryTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Walk `root` and render a sorted, indented listing
+    fn list(
+        root: &Path,
+        max_depth: usize,
+        include_hidden: bool,
+        respect_gitignore: bool,
+    ) -> Result<String> {
+        let walker = WalkBuilder::new(root)
+            .max_depth(Some(max_depth))
+            .hidden(!include_hidden)
+            .git_ignore(respect_gitignore)
+            .git_global(respect_gitignore)
+            .git_exclude(respect_gitignore)
+            .ignore(respect_gitignore)
+            .parents(respect_gitignore)
+            .require_git(false)
+            .follow_links(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        let mut lines = Vec::new();
+        let (mut dirs, mut files, mut links) = (0, 0, 0);
+        let mut truncated = false;
+
+        for entry in walker {
+            let entry = entry?;
+            // Depth 0 is the root itself
+            if entry.depth() == 0 {
+                continue;
+            }
+            if lines.len() >= MAX_ENTRIES {
+                truncated = true;
+                break;
+            }
+
+            let indent = "  ".repeat(entry.depth() - 1);
+            let name = entry.file_name().to_string_lossy();
+            let file_type = entry.file_type();
+
+            if file_type.is_some_and(|t| t.is_symlink()) {
+                let target = std::fs::read_link(entry.path())
+                    .map(|t| t.display().to_string())
+                    .unwrap_or_else(|_| "?".to_string());
+                lines.push(format!("{}{} -> {} (symlink)", indent, name, target));
+                links += 1;
+            } else if file_type.is_some_and(|t| t.is_dir()) {
+                lines.push(format!("{}{}/", indent, name));
+                dirs += 1;
+            } else {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                lines.push(format!("{}{} ({})", indent, name, format_size(size)));
+                files += 1;
+            }
+        }
+
+        if lines.is_empty() {
+            return Ok(format!("{} is empty", root.display()));
+        }
+
+        let mut output = format!("{}/\n{}\n\n", root.display(), lines.join("\n"));
+        output.push_str(&format!("{} directories, {} files", dirs, files));
+        if links > 0 {
+            output.push_str(&format!(", {} symlinks", links));
+        }
+        if truncated {
+            output.push_str(&format!(" (truncated at {} entries)", MAX_ENTRIES));
+        }
+        Ok(output)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+impl Default for ListDirectoryTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for ListDirectoryTool {
+    fn name(&self) -> &str {
+        "list_directory"
+    }
+
+    fn description(&self) -> &str {
+        "List the contents of a directory as a sorted tree with entry types and file sizes. \
+        Respects .gitignore by default. Prefer this over 'bash ls'."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to list (defaults to current directory)"
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "List subdirectories too (default: false)"
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "Maximum depth when recursive (default: 3)"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Include dotfiles and dot-directories (default: false)"
+                },
+                "respect_gitignore": {
+                    "type": "boolean",
+                    "description": "Skip entries ignored by .gitignore (default: true)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult> {
+        let path = super::expand_path(params["path"].as_str().unwrap_or("."))?;
+        let recursive = params["recursive"].as_bool().unwrap_or(false);
+        let max_depth = if recursive {
+            params["max_depth"].as_u64().unwrap_or(3).max(1) as usize
+        } else {
+            1
+        };
+        let include_hidden = params["include_hidden"].as_bool().unwrap_or(false);
+        let respect_gitignore = params["respect_gitignore"].as_bool().unwrap_or(true);
+
+        if !path.is_dir() {
+            return Ok(ToolResult {
+                content: format!("Not a directory: {}", path.display()),
+                is_error: true,
+            });
+        }
+
+        let root: PathBuf = path.clone();
+        let listing = tokio::task::spawn_blocking(move || {
+            Self::list(&root, max_depth, include_hidden, respect_gitignore)
+        })
+        .await?;
+
+        match listing {
+            Ok(content) => Ok(ToolResult {
+                content,
+                is_error: false,
+            }),
+            Err(e) => Ok(ToolResult {
+                content: format!("Failed to list {}: {}", path.display(), e),
+                is_error: true,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(root.join(".gitignore"), "ignored.txt\n").unwrap();
+        fs::write(root.join("ignored.txt"), "x").unwrap();
+        fs::write(root.join(".hidden"), "x").unwrap();
+        fs::write(root.join("b.txt"), "hello").unwrap();
+        fs::write(root.join("a.txt"), "hi").unwrap();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join("src/nested/deep.rs"), "").unwrap();
+        temp
+    }
+
+    #[tokio::test]
+    async fn test_list_top_level_sorted() {
+        let temp = setup();
+        let tool = ListDirectoryTool::new();
+        let result = tool
+            .execute(serde_json::json!({ "path": temp.path().to_str().unwrap() }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        let body: Vec<&str> = result.content.lines().skip(1).take(3).collect();
+        assert_eq!(body, vec!["a.txt (2 B)", "b.txt (5 B)", "src/"]);
+        assert!(!result.content.contains("main.rs"));
+        assert!(!result.content.contains("ignored.txt"));
+        assert!(!result.content.contains(".hidden"));
+        assert!(result.content.ends_with("1 directories, 2 files"));
+    }
+
+    #[tokio::test]
+    async fn test_list_recursive_with_depth() {
+        let temp = setup();
+        let tool = ListDirectoryTool::new();
+
+        let result = tool
+            .execute(serde_json::json!({
+                "path": temp.path().to_str().unwrap(),
+                "recursive": true,
+                "max_depth": 2
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("\n  main.rs (12 B)"));
+        assert!(result.content.contains("\n  nested/"));
+        assert!(!result.content.contains("deep.rs"));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "path": temp.path().to_str().unwrap(),
+                "recursive": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.content.contains("\n    deep.rs (0 B)"));
+    }
+
+    #[tokio::test]
+    async fn test_list_hidden_and_ignored() {
+        let temp = setup();
+        let tool = ListDirectoryTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "path": temp.path().to_str().unwrap(),
+                "include_hidden": true,
+                "respect_gitignore": false
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.content.contains(".hidden"));
+        assert!(result.content.contains(".gitignore"));
+        assert!(result.content.contains("ignored.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_list_not_a_directory() {
+        let temp = setup();
+        let tool = ListDirectoryTool::new();
+        let result = tool
+            .execute(serde_json::json!({ "path": temp.path().join("a.txt").to_str().unwrap() }))
+            .await
+            .unwrap();
+        assert!(result.is_error);
+    }
+}
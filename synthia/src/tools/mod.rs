@@ -1,3 +1,4 @@
+pub mod apply_patch;
 pub mod bash;
 pub mod cache;
 pub mod diff;
@@ -5,9 +6,12 @@ pub mod edit;
 pub mod git;
 pub mod glob;
 pub mod grep;
+pub mod list_directory;
+pub mod output;
 pub mod powertools;
 pub mod read;
 pub mod registry;
+pub mod subagent;
 pub mod todo;
 pub mod webfetch;
 pub mod workshop;
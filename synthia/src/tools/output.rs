@@ -0,0 +1,183 @@
+use crate::config::OutputConfig;
+
+/// Clean up tool output before it's stored in the conversation: strip ANSI codes,
+/// dedent, collapse repeated lines, then cap the size
+pub fn process_output(content: &str, config: &OutputConfig) -> String {
+    let mut output = if config.strip_ansi {
+        strip_ansi(content)
+    } else {
+        content.to_string()
+    };
+    if config.dedent {
+        output = dedent(&output);
+    }
+    if config.collapse_repeated_lines > 0 {
+        output = collapse_repeated_lines(&output, config.collapse_repeated_lines);
+    }
+    if config.max_bytes > 0 {
+        output = truncate_middle(&output, config.max_bytes);
+    }
+    output
+}
+
+/// Remove escape sequences: CSI (colors, cursor movement), OSC (titles, hyperlinks)
+/// and two-character escapes
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            plain.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                // Parameters run until a final byte in '@'..='~'
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                // Terminated by BEL or ESC '\'
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    plain
+}
+
+/// Remove the leading whitespace shared by every non-blank line
+fn dedent(text: &str) -> String {
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .reduce(|common, indent| {
+            let shared = common
+                .bytes()
+                .zip(indent.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            &common[..shared]
+        })
+        .unwrap_or("");
+
+    if indent.is_empty() {
+        return text.to_string();
+    }
+    map_lines(text, |lines| {
+        lines
+            .map(|line| line.strip_prefix(indent).unwrap_or(line.trim_start()).to_string())
+            .collect()
+    })
+}
+
+/// Replace runs of at least `min_run` identical lines with the line and a repeat count
+fn collapse_repeated_lines(text: &str, min_run: usize) -> String {
+    map_lines(text, |lines| {
+        let mut collapsed = Vec::new();
+        let mut lines = lines.peekable();
+        while let Some(line) = lines.next() {
+            let mut run = 1;
+            while lines.peek() == Some(&line) {
+                lines.next();
+                run += 1;
+            }
+            if run >= min_run.max(2) {
+                collapsed.push(line.to_string());
+                collapsed.push(format!("[previous line repeated {} more times]", run - 1));
+            } else {
+                collapsed.extend(std::iter::repeat_n(line.to_string(), run));
+            }
+        }
+        collapsed
+    })
+}
+
+/// Apply `f` to the lines of `text`, keeping a trailing newline if there was one
+fn map_lines<'a>(text: &'a str, f: impl FnOnce(std::str::Lines<'a>) -> Vec<String>) -> String {
+    let mut result = f(text.lines()).join("\n");
+    if text.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Keep the start and end of `text` (errors usually come last) within `max_bytes`,
+/// marking how much of the middle was cut
+fn truncate_middle(text: &str, max_bytes: usize) -> String {
+    if text.len() <= max_bytes {
+        return text.to_string();
+    }
+
+    let mut head_end = max_bytes / 2;
+    while !text.is_char_boundary(head_end) {
+        head_end -= 1;
+    }
+    let mut tail_start = text.len() - (max_bytes - max_bytes / 2);
+    while !text.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+
+    format!(
+        "{}\n... [{} bytes truncated] ...\n{}",
+        &text[..head_end],
+        tail_start - head_end,
+        &text[tail_start..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let colored = "\x1b[1;31merror\x1b[0m: build failed\x1b]0;cargo\x07\n\x1b]8;;https://x.dev\x1b\\link\x1b]8;;\x1b\\";
+        assert_eq!(strip_ansi(colored), "error: build failed\nlink");
+        assert_eq!(strip_ansi("plain ünïcode"), "plain ünïcode");
+    }
+
+    #[test]
+    fn test_truncation_marker() {
+        let text = format!("{}{}", "a".repeat(60), "z".repeat(40));
+        let truncated = truncate_middle(&text, 20);
+        assert_eq!(truncated, format!("{}\n... [80 bytes truncated] ...\n{}", "a".repeat(10), "z".repeat(10)));
+
+        // Cuts land on character boundaries
+        let truncated = truncate_middle(&"é".repeat(20), 9);
+        assert!(truncated.starts_with("éé\n... [32 bytes truncated] ...\n"));
+        assert!(truncated.ends_with("éé"));
+
+        assert_eq!(truncate_middle("short", 20), "short");
+    }
+
+    #[test]
+    fn test_collapse_and_dedent() {
+        let config = OutputConfig {
+            max_bytes: 0,
+            ..OutputConfig::default()
+        };
+        let output = "    Compiling a\n    waiting\n    waiting\n    waiting\n      done\n";
+        assert_eq!(
+            process_output(output, &config),
+            "Compiling a\nwaiting\n[previous line repeated 2 more times]\n  done\n"
+        );
+
+        // Runs shorter than the threshold are kept
+        assert_eq!(process_output("x\nx\ny", &config), "x\nx\ny");
+    }
+}
@@ -13,6 +13,9 @@ use tokio::time::timeout;
 // In a Cargo workspace, target directory is at workspace root
 static POWERTOOLS_BINARY: &[u8] = include_bytes!("../../../target/release/powertools");
 
+/// Results per page for paginated operations unless the model asks for more
+const DEFAULT_LIMIT: usize = 50;
+
 /// Powertools integration tool that provides semantic code navigation capabilities
 /// by shelling out to the powertools binary. Operations include:
 /// - index: Index a project for semantic navigation
@@ -77,8 +80,10 @@ impl PowertoolsTool {
         Ok(binary_path)
     }
 
+    /// Run `powertools <operation> <args> --format json` and turn its JSON into a compact result
     async fn run_powertools_command(
         &self,
+        operation: &str,
         args: &[&str],
         cwd: Option<&str>,
     ) -> Result<ToolResult> {
@@ -94,7 +99,7 @@ impl PowertoolsTool {
         }
 
         let mut cmd = Command::new(&self.binary_path);
-        cmd.args(args);
+        cmd.arg(operation).args(args).args(["--format", "json"]);
 
         if let Some(dir) = cwd {
             cmd.current_dir(dir);
@@ -105,23 +110,25 @@ impl PowertoolsTool {
         let stdout = String::from_utf8_lossy(&result.stdout);
         let stderr = String::from_utf8_lossy(&result.stderr);
 
+        let json = extract_json(&stdout);
+        if let Some(error) = json.as_ref().and_then(|value| value["error"].as_str()) {
+            return Ok(ToolResult {
+                content: format!("powertools {} error: {}", operation, error),
+                is_error: true,
+            });
+        }
         if !result.status.success() {
+            let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
             return Ok(ToolResult {
-                content: if !stderr.is_empty() {
-                    stderr.to_string()
-                } else {
-                    stdout.to_string()
-                },
+                content: format!("powertools {} failed: {}", operation, message),
                 is_error: true,
             });
         }
 
-        // Try to parse and format JSON output
-        let content = if let Ok(json_value) = serde_json::from_str::<Value>(&stdout) {
-            // Pretty print JSON for better readability
-            serde_json::to_string_pretty(&json_value).unwrap_or_else(|_| stdout.to_string())
-        } else {
-            stdout.to_string()
+        // index only reports progress as text
+        let content = match json {
+            Some(value) => normalize_output(operation, &value),
+            None => stdout.trim().to_string(),
         };
 
         Ok(ToolResult {
@@ -136,7 +143,7 @@ impl PowertoolsTool {
         auto_install: bool,
         languages: Option<Vec<String>>,
     ) -> Result<ToolResult> {
-        let mut args = vec!["index", "--format", "json"];
+        let mut args = Vec::new();
 
         if auto_install {
             args.push("--auto-install");
@@ -151,20 +158,18 @@ impl PowertoolsTool {
             }
         }
 
-        self.run_powertools_command(&args, cwd).await
+        self.run_powertools_command("index", &args, cwd).await
     }
 
     async fn definition(&self, location: String, project_root: Option<&str>) -> Result<ToolResult> {
-        let mut args = vec!["definition", &location, "--format", "json"];
+        let mut args = vec![location.as_str()];
 
-        let project_root_str;
         if let Some(root) = project_root {
-            args.push("-p");
-            project_root_str = root.to_string();
-            args.push(&project_root_str);
+            args.push("--project");
+            args.push(root);
         }
 
-        self.run_powertools_command(&args, None).await
+        self.run_powertools_command("definition", &args, None).await
     }
 
     async fn references(
@@ -172,61 +177,171 @@ impl PowertoolsTool {
         symbol: String,
         project_root: Option<&str>,
         include_declarations: bool,
+        page: Pagination,
     ) -> Result<ToolResult> {
-        let mut args = vec!["references", &symbol, "--format", "json"];
+        let mut args = vec![symbol.as_str()];
 
         if include_declarations {
             args.push("--include-declarations");
         }
 
-        let project_root_str;
         if let Some(root) = project_root {
-            args.push("-p");
-            project_root_str = root.to_string();
-            args.push(&project_root_str);
+            args.push("--project");
+            args.push(root);
         }
 
-        self.run_powertools_command(&args, None).await
+        let page_args = page.args();
+        args.extend(page_args.iter().map(String::as_str));
+        self.run_powertools_command("references", &args, None).await
     }
 
-    async fn functions(&self, path: Option<&str>, cwd: Option<&str>) -> Result<ToolResult> {
-        let mut args = vec!["functions", "--format", "json"];
+    /// `functions` and `classes` take the path to analyze as a positional argument
+    async fn list_symbols(
+        &self,
+        operation: &str,
+        path: Option<&str>,
+        cwd: Option<&str>,
+        page: Pagination,
+    ) -> Result<ToolResult> {
+        let mut args: Vec<&str> = path.into_iter().collect();
 
-        let path_str;
-        if let Some(p) = path {
-            args.push("-p");
-            path_str = p.to_string();
-            args.push(&path_str);
-        }
+        let page_args = page.args();
+        args.extend(page_args.iter().map(String::as_str));
+        self.run_powertools_command(operation, &args, cwd).await
+    }
 
-        self.run_powertools_command(&args, cwd).await
+    async fn stats(&self, path: Option<&str>, cwd: Option<&str>) -> Result<ToolResult> {
+        let args: Vec<&str> = path.into_iter().collect();
+        self.run_powertools_command("stats", &args, cwd).await
     }
+}
 
-    async fn classes(&self, path: Option<&str>, cwd: Option<&str>) -> Result<ToolResult> {
-        let mut args = vec!["classes", "--format", "json"];
+/// `limit`/`offset` for operations whose results are paginated
+#[derive(Debug, Clone, Copy)]
+struct Pagination {
+    limit: usize,
+    offset: usize,
+}
 
-        let path_str;
-        if let Some(p) = path {
-            args.push("-p");
-            path_str = p.to_string();
-            args.push(&path_str);
+impl Pagination {
+    fn from_params(params: &Value) -> Self {
+        Self {
+            limit: params["limit"].as_u64().map_or(DEFAULT_LIMIT, |n| n.max(1) as usize),
+            offset: params["offset"].as_u64().unwrap_or(0) as usize,
         }
+    }
 
-        self.run_powertools_command(&args, cwd).await
+    fn args(&self) -> Vec<String> {
+        vec![
+            "--limit".to_string(),
+            self.limit.to_string(),
+            "--offset".to_string(),
+            self.offset.to_string(),
+        ]
     }
+}
 
-    async fn stats(&self, path: Option<&str>, cwd: Option<&str>) -> Result<ToolResult> {
-        let mut args = vec!["stats", "--format", "json"];
+/// The JSON document in powertools output; some commands print a status line before it
+fn extract_json(stdout: &str) -> Option<Value> {
+    let trimmed = stdout.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+    let start = trimmed
+        .match_indices('\n')
+        .map(|(i, _)| i + 1)
+        .find(|&i| trimmed[i..].starts_with(['{', '[']))?;
+    serde_json::from_str(&trimmed[start..]).ok()
+}
 
-        let path_str;
-        if let Some(p) = path {
-            args.push("-p");
-            path_str = p.to_string();
-            args.push(&path_str);
+/// Compact, line-per-result text for the model (pretty JSON costs several times the tokens)
+fn normalize_output(operation: &str, value: &Value) -> String {
+    match operation {
+        "functions" | "classes" | "references" => {
+            let items = value[operation].as_array().map(Vec::as_slice).unwrap_or_default();
+            let mut lines = vec![page_header(value, items.len(), operation)];
+            lines.extend(items.iter().map(|item| {
+                if operation == "references" {
+                    format_reference(item)
+                } else {
+                    format_symbol(item)
+                }
+            }));
+            lines.join("\n")
+        }
+        "definition" => {
+            let symbols = value.as_array().map(Vec::as_slice).unwrap_or_default();
+            if symbols.is_empty() {
+                return "No definition found".to_string();
+            }
+            symbols
+                .iter()
+                .map(|symbol| format!("Definition: {}", format_location(&symbol["location"])))
+                .collect::<Vec<_>>()
+                .join("\n")
         }
+        _ => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
 
-        self.run_powertools_command(&args, cwd).await
+/// "Showing 1-50 of 120 functions (pass offset 50 for more)"
+fn page_header(page: &Value, shown: usize, noun: &str) -> String {
+    let count = page["count"].as_u64().unwrap_or(shown as u64);
+    let offset = page["offset"].as_u64().unwrap_or(0);
+    if shown == 0 {
+        return if offset > 0 {
+            format!("No {} at offset {} (only {})", noun, offset, count)
+        } else {
+            format!("No {} found", noun)
+        };
+    }
+
+    let last = offset + shown as u64;
+    let mut header = format!("Showing {}-{} of {} {}", offset + 1, last, count, noun);
+    if page["has_more"].as_bool().unwrap_or(false) {
+        header.push_str(&format!(" (pass offset {} for more)", last));
+    }
+    header
+}
+
+fn format_location(location: &Value) -> String {
+    format!(
+        "{}:{}:{}",
+        location["file_path"].as_str().unwrap_or("?"),
+        location["line"].as_u64().unwrap_or(0),
+        location["column"].as_u64().unwrap_or(0)
+    )
+}
+
+/// `path:line:col kind Container::name signature`
+fn format_symbol(symbol: &Value) -> String {
+    let name = symbol["name"].as_str().unwrap_or("?");
+    let mut line = format!(
+        "{} {} ",
+        format_location(&symbol["location"]),
+        symbol["kind"].as_str().unwrap_or("symbol")
+    );
+    if let Some(container) = symbol["container"].as_str() {
+        line.push_str(&format!("{}::", container));
+    }
+    line.push_str(name);
+    if let Some(signature) = symbol["signature"].as_str() {
+        line.push_str(&format!(" - {}", signature.trim()));
+    }
+    line
+}
+
+/// `path:line:col kind: context`
+fn format_reference(reference: &Value) -> String {
+    let mut line = format!(
+        "{} {}",
+        format_location(&reference["location"]),
+        reference["kind"].as_str().unwrap_or("reference")
+    );
+    if let Some(context) = reference["context"].as_str() {
+        line.push_str(&format!(": {}", context.trim()));
     }
+    line
 }
 
 #[async_trait]
@@ -236,7 +351,8 @@ impl Tool for PowertoolsTool {
     }
 
     fn description(&self) -> &str {
-        "Execute powertools operations for semantic code navigation (index, definition, references, functions, classes, stats)"
+        "Execute powertools operations for semantic code navigation (index, definition, references, functions, classes, stats). \
+        Results come one per line as path:line:column; long lists are paginated with limit/offset"
     }
 
     fn parameters_schema(&self) -> Value {
@@ -282,6 +398,14 @@ impl Tool for PowertoolsTool {
                 "path": {
                     "type": "string",
                     "description": "For functions/classes/stats: path to analyze (optional)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "For references/functions/classes: maximum results to return (default: 50)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "For references/functions/classes: results to skip, to page through long lists (default: 0)"
                 }
             },
             "required": ["operation"]
@@ -322,16 +446,16 @@ impl Tool for PowertoolsTool {
                     .to_string();
                 let project_root = params["project_root"].as_str();
                 let include_declarations = params["include_declarations"].as_bool().unwrap_or(false);
-                self.references(symbol, project_root, include_declarations)
+                self.references(symbol, project_root, include_declarations, Pagination::from_params(&params))
                     .await
             }
             "functions" => {
                 let path = params["path"].as_str();
-                self.functions(path, cwd).await
+                self.list_symbols("functions", path, cwd, Pagination::from_params(&params)).await
             }
             "classes" => {
                 let path = params["path"].as_str();
-                self.classes(path, cwd).await
+                self.list_symbols("classes", path, cwd, Pagination::from_params(&params)).await
             }
             "stats" => {
                 let path = params["path"].as_str();
@@ -346,6 +470,60 @@ impl Tool for PowertoolsTool {
 mod tests {
     use super::*;
 
+    fn fixture(name: &str) -> String {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/powertools")
+            .join(name);
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_paginated_symbols() {
+        let value = extract_json(&fixture("functions.json")).unwrap();
+        assert_eq!(
+            normalize_output("functions", &value),
+            "Showing 1-2 of 3 functions (pass offset 2 for more)\n\
+             src/tools/bash.rs:48:5 function BashTool::execute - async fn execute(&self, params: Value) -> Result<ToolResult>\n\
+             src/tools/bash.rs:15:5 function BashTool::new - pub fn new(timeout_seconds: u64, max_output_chars: usize) -> Self"
+        );
+
+        let value = extract_json(&fixture("references.json")).unwrap();
+        assert_eq!(
+            normalize_output("references", &value),
+            "Showing 1-2 of 2 references\n\
+             src/main.rs:151:19 call: tool_registry.set_retry_policy(config.tools.retry.clone());\n\
+             src/tools/registry.rs:46:12 definition"
+        );
+
+        let empty = serde_json::json!({"count": 3, "offset": 10, "has_more": false, "classes": []});
+        assert_eq!(normalize_output("classes", &empty), "No classes at offset 10 (only 3)");
+    }
+
+    #[test]
+    fn test_normalize_definition_and_stats() {
+        // definition prints a status line before its JSON
+        let value = extract_json(&fixture("definition.txt")).unwrap();
+        assert_eq!(normalize_output("definition", &value), "Definition: src/tools/registry.rs:46:12");
+
+        let value = extract_json(&fixture("stats.json")).unwrap();
+        let stats = normalize_output("stats", &value);
+        assert!(!stats.contains('\n'));
+        assert!(stats.contains("\"total_files\":42"));
+
+        let value = extract_json(&fixture("error.json")).unwrap();
+        assert_eq!(value["error"], "No definition found at this location");
+        assert!(extract_json("✓ Indexes built in 1.2s").is_none());
+    }
+
+    #[test]
+    fn test_pagination_params() {
+        let page = Pagination::from_params(&serde_json::json!({"limit": 10, "offset": 20}));
+        assert_eq!(page.args(), vec!["--limit", "10", "--offset", "20"]);
+
+        let page = Pagination::from_params(&serde_json::json!({}));
+        assert_eq!(page.args(), vec!["--limit", "50", "--offset", "0"]);
+    }
+
     fn get_test_binary_path() -> PathBuf {
         // Use relative path from synthia directory to powertools binary
         // In a Cargo workspace, target directory is at workspace root
@@ -383,8 +561,8 @@ mod tests {
             .unwrap();
 
         assert!(!result.is_error);
-        // Should return JSON array of functions
-        assert!(result.content.contains("name") || result.content.contains("Found"));
+        // One line per function under a page header
+        assert!(result.content.contains("functions"));
     }
 
     #[tokio::test]
@@ -400,8 +578,8 @@ mod tests {
             .unwrap();
 
         assert!(!result.is_error);
-        // Should return JSON array of classes/structs
-        assert!(result.content.contains("name") || result.content.contains("Found"));
+        // One line per class/struct under a page header
+        assert!(result.content.contains("classes"));
     }
 
     #[tokio::test]
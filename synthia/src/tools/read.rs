@@ -1,8 +1,34 @@
 use super::{Tool, ToolResult};
+use crate::types::ContentBlock;
 use anyhow::Result;
 use async_trait::async_trait;
+use base64::Engine;
 use serde_json::Value;
+use std::path::Path;
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Bytes inspected when deciding whether a file is binary
+const BINARY_PROBE_BYTES: usize = 8192;
+
+/// Leading magic bytes of common binary formats, for the binary file summary
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "PNG image"),
+    (b"\xff\xd8\xff", "JPEG image"),
+    (b"GIF8", "GIF image"),
+    (b"%PDF", "PDF document"),
+    (b"PK\x03\x04", "ZIP archive"),
+    (b"\x1f\x8b", "gzip archive"),
+    (b"\x7fELF", "ELF executable"),
+    (b"\0asm", "WebAssembly module"),
+    (b"SQLite format 3\0", "SQLite database"),
+];
+
+/// Largest image file sent to vision-capable models
+const MAX_IMAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Start of the text the read tool returns for image files
+pub const IMAGE_PLACEHOLDER_PREFIX: &str = "[image: ";
 
 pub struct ReadTool {
     max_output_chars: usize,
@@ -16,6 +42,160 @@ impl ReadTool {
             warn_at_chars,
         }
     }
+
+    /// Read a raw byte window, decoding it lossily so partial or binary data is still readable
+    async fn read_range(&self, path: &Path, offset: u64, limit: Option<u64>) -> Result<ToolResult> {
+        let mut file = fs::File::open(path).await?;
+        let total = file.metadata().await?.len();
+        if offset > total {
+            return Ok(ToolResult {
+                content: format!(
+                    "byte_offset {} is past the end of {} ({} bytes)",
+                    offset,
+                    path.display(),
+                    total
+                ),
+                is_error: true,
+            });
+        }
+
+        let limit = limit
+            .unwrap_or(self.max_output_chars as u64)
+            .min(self.max_output_chars as u64);
+        let mut bytes = Vec::new();
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        file.take(limit).read_to_end(&mut bytes).await?;
+
+        let end = offset + bytes.len() as u64;
+        let text = String::from_utf8_lossy(&bytes);
+        let note = if matches!(text, std::borrow::Cow::Owned(_)) {
+            "Note: invalid UTF-8 bytes (binary data, or a character split by the window) were replaced with \u{FFFD}\n"
+        } else {
+            ""
+        };
+
+        Ok(ToolResult {
+            content: format!(
+                "File: {}\nBytes {}-{} of {} total\n{}\n{}",
+                path.display(),
+                offset,
+                end,
+                total,
+                note,
+                text
+            ),
+            is_error: false,
+        })
+    }
+}
+
+/// Read the first few KB of a file; NUL bytes there mean it's binary
+async fn probe(path: &Path) -> std::io::Result<Vec<u8>> {
+    let file = fs::File::open(path).await?;
+    let mut head = Vec::with_capacity(BINARY_PROBE_BYTES);
+    file.take(BINARY_PROBE_BYTES as u64)
+        .read_to_end(&mut head)
+        .await?;
+    Ok(head)
+}
+
+/// Media type of the image formats vision models accept
+fn image_media_type(head: &[u8]) -> Option<&'static str> {
+    if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if head.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if head.starts_with(b"GIF8") {
+        Some("image/gif")
+    } else if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Width and height read from the image header
+fn image_dimensions(bytes: &[u8], media_type: &str) -> Option<(u32, u32)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32);
+    let le16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u32);
+    let le24 = |at: usize| {
+        let b = bytes.get(at..at + 3)?;
+        Some(b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16)
+    };
+
+    match media_type {
+        "image/png" => {
+            let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+            let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+            Some((width, height))
+        }
+        "image/gif" => Some((le16(6)?, le16(8)?)),
+        "image/webp" => match bytes.get(12..16)? {
+            b"VP8X" => Some((le24(24)? + 1, le24(27)? + 1)),
+            b"VP8 " => Some((le16(26)? & 0x3fff, le16(28)? & 0x3fff)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+            }
+            _ => None,
+        },
+        "image/jpeg" => {
+            // Walk the segments up to the first start-of-frame marker
+            let mut at = 2;
+            while *bytes.get(at)? == 0xff {
+                let marker = *bytes.get(at + 1)?;
+                let is_frame = matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+                if is_frame {
+                    return Some((be16(at + 7)?, be16(at + 5)?));
+                }
+                at += 2 + be16(at + 2)? as usize;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// The image at `path` as a content block for vision-capable models
+/// (None if it isn't a supported image or is too large to send)
+pub async fn load_image_block(path: &Path) -> Result<Option<ContentBlock>> {
+    if fs::metadata(path).await?.len() > MAX_IMAGE_BYTES {
+        return Ok(None);
+    }
+    let bytes = fs::read(path).await?;
+    Ok(image_media_type(&bytes).map(|media_type| ContentBlock::Image {
+        media_type: media_type.to_string(),
+        data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+    }))
+}
+
+/// Placeholder text for an image: `[image: path, WxH]`
+async fn describe_image(path: &Path, head: &[u8], media_type: &str) -> Result<String> {
+    let size = fs::metadata(path).await?.len();
+    // The JPEG frame header can sit past the probed bytes, behind large metadata
+    let bytes = if size <= MAX_IMAGE_BYTES {
+        fs::read(path).await?
+    } else {
+        head.to_vec()
+    };
+    let dimensions = image_dimensions(&bytes, media_type)
+        .map_or("unknown size".to_string(), |(width, height)| format!("{}x{}", width, height));
+
+    let mut description = format!("{}{}, {}]", IMAGE_PLACEHOLDER_PREFIX, path.display(), dimensions);
+    if size > MAX_IMAGE_BYTES {
+        description.push_str(&format!(
+            "\nImage is {} bytes, over the {} byte limit for sending it to the model",
+            size, MAX_IMAGE_BYTES
+        ));
+    }
+    Ok(description)
+}
+
+fn detect_binary_type(head: &[u8]) -> &'static str {
+    MAGIC_NUMBERS
+        .iter()
+        .find(|(magic, _)| head.starts_with(magic))
+        .map_or("unknown binary data", |(_, kind)| kind)
 }
 
 #[async_trait]
@@ -25,7 +205,9 @@ impl Tool for ReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read a file from the filesystem"
+        "Read a file from the filesystem. For large or binary files, pass byte_offset/byte_limit \
+        to read a raw byte window instead of the whole file. Images (PNG, JPEG, GIF, WebP) are \
+        shown to the model when vision is enabled."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -35,6 +217,14 @@ impl Tool for ReadTool {
                 "file_path": {
                     "type": "string",
                     "description": "Path to the file to read"
+                },
+                "byte_offset": {
+                    "type": "integer",
+                    "description": "Start of a raw byte window to read (default: 0 when byte_limit is given)"
+                },
+                "byte_limit": {
+                    "type": "integer",
+                    "description": "Maximum bytes to read from byte_offset (capped at the read output limit)"
                 }
             },
             "required": ["file_path"]
@@ -49,8 +239,8 @@ impl Tool for ReadTool {
         // Expand tilde and environment variables
         let path = super::expand_path(file_path)?;
 
-        let content = match fs::read_to_string(&path).await {
-            Ok(c) => c,
+        let head = match probe(&path).await {
+            Ok(head) => head,
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 return Ok(ToolResult {
                     content: format!("File not found: {}", path.display()),
@@ -60,6 +250,50 @@ impl Tool for ReadTool {
             Err(e) => return Err(e.into()),
         };
 
+        let byte_offset = params["byte_offset"].as_u64();
+        let byte_limit = params["byte_limit"].as_u64();
+        if byte_offset.is_some() || byte_limit.is_some() {
+            return self
+                .read_range(&path, byte_offset.unwrap_or(0), byte_limit)
+                .await;
+        }
+
+        if let Some(media_type) = image_media_type(&head) {
+            return Ok(ToolResult {
+                content: describe_image(&path, &head, media_type).await?,
+                is_error: false,
+            });
+        }
+
+        if head.contains(&0) {
+            let size = fs::metadata(&path).await?.len();
+            return Ok(ToolResult {
+                content: format!(
+                    "File: {}\n\nBinary file ({} bytes, detected type: {})\n\
+                    Use byte_offset/byte_limit to read a raw byte window if you need its contents.",
+                    path.display(),
+                    size,
+                    detect_binary_type(&head)
+                ),
+                is_error: false,
+            });
+        }
+
+        let content = match fs::read_to_string(&path).await {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                return Ok(ToolResult {
+                    content: format!(
+                        "File is not valid UTF-8: {}\n\
+                        Use byte_offset/byte_limit to read a raw byte window with invalid bytes replaced.",
+                        path.display()
+                    ),
+                    is_error: true,
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+
         // Check if file exceeds maximum size
         if content.len() > self.max_output_chars {
             let error_msg = format!(
@@ -71,8 +305,9 @@ impl Tool for ReadTool {
                 1. Use grep/rg to search for specific content in the file\n\
                 2. Use 'head' to read the first N lines: head -n 100 {}\n\
                 3. Use 'tail' to read the last N lines: tail -n 100 {}\n\
-                4. Ask me to summarize specific sections or search for keywords\n\
-                5. Increase max_read_output_chars in synthia.toml if absolutely necessary\n\n\
+                4. Read a byte window with byte_offset/byte_limit\n\
+                5. Ask me to summarize specific sections or search for keywords\n\
+                6. Increase max_read_output_chars in synthia.toml if absolutely necessary\n\n\
                 Tip: For large files, it's better to search for what you need rather than reading everything.",
                 path.display(),
                 content.len(),
@@ -197,4 +432,124 @@ mod tests {
         // Cleanup
         fs::remove_file(temp_path).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_read_byte_range() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("log.txt");
+        fs::write(&path, "0123456789abcdef").await.unwrap();
+
+        let tool = ReadTool::new(200_000, 100_000);
+        let result = tool
+            .execute(serde_json::json!({
+                "file_path": path.to_str().unwrap(),
+                "byte_offset": 10,
+                "byte_limit": 4
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("Bytes 10-14 of 16 total"));
+        assert!(result.content.ends_with("abcd"));
+        assert!(!result.content.contains("Note:"));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "file_path": path.to_str().unwrap(),
+                "byte_offset": 100
+            }))
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("past the end"));
+    }
+
+    #[tokio::test]
+    async fn test_read_byte_range_replaces_invalid_utf8() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("mixed.bin");
+        fs::write(&path, b"ok\xff\x00ok").await.unwrap();
+
+        let tool = ReadTool::new(200_000, 100_000);
+        let result = tool
+            .execute(serde_json::json!({
+                "file_path": path.to_str().unwrap(),
+                "byte_limit": 100
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("Note: invalid UTF-8 bytes"));
+        assert!(result.content.contains("ok\u{FFFD}\0ok"));
+    }
+
+    #[tokio::test]
+    async fn test_read_binary_file_returns_summary() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("data.db");
+        let mut db = b"SQLite format 3\0".to_vec();
+        db.extend_from_slice(&[0u8; 56]);
+        fs::write(&path, &db).await.unwrap();
+
+        let tool = ReadTool::new(200_000, 100_000);
+        let result = tool
+            .execute(serde_json::json!({ "file_path": path.to_str().unwrap() }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("Binary file (72 bytes, detected type: SQLite database)"));
+    }
+
+    /// A PNG header (signature plus IHDR chunk) for a `width` x `height` image
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&width.to_be_bytes());
+        png.extend_from_slice(&height.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0]);
+        png
+    }
+
+    #[tokio::test]
+    async fn test_read_image_returns_placeholder_and_loads_block() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("screenshot.png");
+        fs::write(&path, png_header(1280, 720)).await.unwrap();
+
+        let tool = ReadTool::new(200_000, 100_000);
+        let result = tool
+            .execute(serde_json::json!({ "file_path": path.to_str().unwrap() }))
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert_eq!(result.content, format!("[image: {}, 1280x720]", path.display()));
+
+        match load_image_block(&path).await.unwrap() {
+            Some(ContentBlock::Image { media_type, data }) => {
+                assert_eq!(media_type, "image/png");
+                assert!(data.starts_with("iVBORw0KGgo"));
+            }
+            other => panic!("expected an image block, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        assert_eq!(image_dimensions(gif, "image/gif"), Some((320, 240)));
+
+        // SOI, an APP0 segment, then a baseline frame header for 800x600
+        let jpeg = [
+            0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00, 0xff, 0xc0, 0x00, 0x11, 0x08, 0x02, 0x58,
+            0x03, 0x20,
+        ];
+        assert_eq!(image_media_type(&jpeg), Some("image/jpeg"));
+        assert_eq!(image_dimensions(&jpeg, "image/jpeg"), Some((800, 600)));
+
+        assert_eq!(image_media_type(b"RIFF\0\0\0\0WEBPVP8X"), Some("image/webp"));
+        assert_eq!(image_media_type(b"not an image"), None);
+    }
 }
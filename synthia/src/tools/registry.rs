@@ -1,12 +1,16 @@
 use super::cache::ToolCache;
+use super::output::process_output;
 use super::{Tool, ToolResult};
 use anyhow::{anyhow, Result};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::Sender;
+use tokio_util::sync::CancellationToken;
 use crate::agent::messages::UIUpdate;
-use crate::permission_manager::{PermissionManager, PermissionDecision};
+use crate::config::{EditApproval, OutputConfig, RetryConfig, TimeoutConfig};
+use crate::permission_manager::{OperationRisk, PermissionManager, PermissionDecision};
 use std::sync::Mutex;
 
 pub struct ToolRegistry {
@@ -14,6 +18,14 @@ pub struct ToolRegistry {
     cache: ToolCache,
     ui_tx: Option<Sender<UIUpdate>>,
     permission_manager: Arc<Mutex<PermissionManager>>,
+    timeouts: Option<TimeoutConfig>,
+    retry: RetryConfig,
+    output: OutputConfig,
+    edit_approval: EditApproval,
+    /// Files an edit was approved for, so `EditApproval::FirstTime` stops asking about them
+    approved_files: Mutex<HashSet<String>>,
+    /// Run read-only operations that permissions leave to the user without prompting
+    auto_allow_read_only: bool,
 }
 
 impl ToolRegistry {
@@ -23,6 +35,12 @@ impl ToolRegistry {
             cache: ToolCache::new(100), // Cache last 100 results
             ui_tx: None,
             permission_manager,
+            timeouts: None,
+            retry: RetryConfig::default(),
+            output: OutputConfig::default(),
+            edit_approval: EditApproval::default(),
+            approved_files: Mutex::new(HashSet::new()),
+            auto_allow_read_only: false,
         }
     }
 
@@ -30,6 +48,31 @@ impl ToolRegistry {
         self.ui_tx = Some(ui_tx);
     }
 
+    /// Apply per-tool timeouts around every tool execution
+    pub fn set_timeouts(&mut self, timeouts: TimeoutConfig) {
+        self.timeouts = Some(timeouts);
+    }
+
+    /// Retry transient tool failures according to `retry`
+    pub fn set_retry_policy(&mut self, retry: RetryConfig) {
+        self.retry = retry;
+    }
+
+    /// Clean up output of the tools `output` names before it reaches the conversation
+    pub fn set_output_policy(&mut self, output: OutputConfig) {
+        self.output = output;
+    }
+
+    /// Decide when edits that permissions leave to the user are prompted for
+    pub fn set_edit_approval(&mut self, edit_approval: EditApproval) {
+        self.edit_approval = edit_approval;
+    }
+
+    /// Skip the permission prompt for read-only operations (denied ones stay denied)
+    pub fn set_auto_allow_read_only(&mut self, enabled: bool) {
+        self.auto_allow_read_only = enabled;
+    }
+
     pub fn register(&mut self, tool: Arc<dyn Tool>) -> Result<()> {
         let tool_name = tool.name().to_string();
         if self.tools.contains_key(&tool_name) {
@@ -47,6 +90,35 @@ impl ToolRegistry {
     }
 
     pub async fn execute(&self, name: &str, params: Value) -> Result<ToolResult> {
+        self.execute_cancellable(name, params, &CancellationToken::new())
+            .await
+    }
+
+    /// Execute a tool, aborting it (and any child process it owns) once `cancel` fires
+    pub async fn execute_cancellable(
+        &self,
+        name: &str,
+        params: Value,
+        cancel: &CancellationToken,
+    ) -> Result<ToolResult> {
+        let result = tokio::select! {
+            result = self.execute_inner(name, params) => result,
+            _ = cancel.cancelled() => {
+                tracing::info!("Tool '{}' canceled", name);
+                Err(anyhow!("Tool '{}' was canceled", name))
+            }
+        };
+
+        // Anything that may have touched the filesystem makes cached reads stale.
+        // Failed or canceled runs count too: bash may have written files before stopping.
+        if Self::modifies_files(name) {
+            self.invalidate_file_cache();
+        }
+
+        result
+    }
+
+    async fn execute_inner(&self, name: &str, params: Value) -> Result<ToolResult> {
         // 1. Check permission first
         let decision = self.permission_manager
             .lock()
@@ -66,47 +138,38 @@ impl ToolRegistry {
             PermissionDecision::Allow => {
                 // For Allow: bypass approval flow, go straight to cache/execute
                 // For edit/write: show informational diff
-                if (name == "edit" || name == "write") && self.ui_tx.is_some() {
+                if Self::is_edit_tool(name) && self.ui_tx.is_some() {
                     tracing::debug!("Tool '{}' auto-approved, showing informational diff", name);
-
-                    // Compute diff for informational display
-                    let diff_result = if name == "edit" {
-                        self.compute_edit_diff(&params).await
-                    } else {
-                        self.compute_write_diff(&params).await
-                    };
-
-                    if let Ok(diff) = diff_result {
-                        if let Some(ui_tx) = &self.ui_tx {
-                            let _ = ui_tx
-                                .send(UIUpdate::InformationalDiff {
-                                    tool_name: name.to_string(),
-                                    file_path: params["file_path"]
-                                        .as_str()
-                                        .unwrap_or("unknown")
-                                        .to_string(),
-                                    diff,
-                                })
-                                .await;
-                        }
-                    }
+                    self.send_informational_diff(name, &params).await;
                 }
                 tracing::debug!("Tool '{}' allowed by permission system, bypassing approval", name);
             }
             PermissionDecision::Ask => {
-                // For Ask: if edit/write, use approval flow
-                if name == "edit" || name == "write" {
+                // For Ask: if edit/write/apply_patch, use approval flow unless the
+                // edit_approval policy says this edit needn't be asked about
+                if Self::is_edit_tool(name) {
                     if self.ui_tx.is_some() {
-                        tracing::debug!("Tool '{}' requires approval, routing to approval flow", name);
-                        if name == "edit" {
-                            return self.execute_edit_with_approval(params).await;
-                        } else {
-                            return self.execute_write_with_approval(params).await;
+                        if self.needs_edit_approval(name, &params) {
+                            tracing::debug!("Tool '{}' requires approval, routing to approval flow", name);
+                            return match name {
+                                "edit" => self.execute_edit_with_approval(params).await,
+                                "write" => self.execute_write_with_approval(params).await,
+                                _ => self.execute_patch_with_approval(params).await,
+                            };
                         }
+                        tracing::debug!("Tool '{}' approved by edit_approval policy, showing informational diff", name);
+                        self.send_informational_diff(name, &params).await;
                     }
                 } else {
+                    let risk = self.permission_manager
+                        .lock()
+                        .map_err(|e| anyhow!("Failed to acquire permission manager lock: {}", e))?
+                        .classify_risk(name, &params);
+
                     // New permission prompt for other tools
-                    if let Some(ui_tx) = &self.ui_tx {
+                    if risk == OperationRisk::ReadOnly && self.auto_allow_read_only {
+                        tracing::debug!("Tool '{}' is read-only, allowed without a prompt", name);
+                    } else if let Some(ui_tx) = &self.ui_tx {
                         tracing::debug!("Tool '{}' requires permission prompt", name);
                         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
 
@@ -123,13 +186,29 @@ impl ToolRegistry {
                                     params["file_path"].as_str().unwrap_or("unknown"))
                             }
                             "git" => {
-                                format!("Git command: {}",
-                                    params["command"].as_str().unwrap_or("unknown"))
+                                let command = match params["operation"].as_str() {
+                                    Some("run") => params["args"]
+                                        .as_array()
+                                        .map(|args| {
+                                            args.iter()
+                                                .filter_map(|a| a.as_str())
+                                                .collect::<Vec<_>>()
+                                                .join(" ")
+                                        })
+                                        .unwrap_or_default(),
+                                    Some(operation) => operation.to_string(),
+                                    None => params["command"].as_str().unwrap_or("unknown").to_string(),
+                                };
+                                format!("Git command: {}", command)
                             }
                             "webfetch" => {
                                 format!("Fetch URL: {}",
                                     params["url"].as_str().unwrap_or("unknown"))
                             }
+                            "spawn_subagent" => {
+                                format!("Delegate task: {}",
+                                    params["task"].as_str().unwrap_or("unknown"))
+                            }
                             _ => format!("Operation: {} with params", name),
                         };
 
@@ -142,6 +221,7 @@ impl ToolRegistry {
                             .send(UIUpdate::PermissionPrompt {
                                 tool_name: name.to_string(),
                                 operation_details,
+                                risk,
                                 suggested_pattern,
                                 response_tx,
                             })
@@ -192,7 +272,15 @@ impl ToolRegistry {
         let tool = self
             .get(name)
             .ok_or_else(|| anyhow!("Tool '{}' not found", name))?;
-        let result = tool.execute(params.clone()).await?;
+        let mut result = self.run_tool_with_retry(tool, params.clone()).await?;
+        if self.output.applies_to(name) {
+            let output = OutputConfig {
+                // Honor tools' own opt-out of color stripping
+                strip_ansi: self.output.strip_ansi && !params["keep_color"].as_bool().unwrap_or(false),
+                ..self.output.clone()
+            };
+            result.content = process_output(&result.content, &output);
+        }
 
         // Cache result if tool is deterministic
         if Self::is_deterministic(name) {
@@ -202,6 +290,143 @@ impl ToolRegistry {
         Ok(result)
     }
 
+    /// Execute a tool, retrying transient failures before reporting them to the model
+    /// Tools that may modify files are never retried: a failed run may have partly applied
+    async fn run_tool_with_retry(&self, tool: Arc<dyn Tool>, params: Value) -> Result<ToolResult> {
+        let mut attempt = 0;
+        loop {
+            let result = self.run_tool(tool.clone(), params.clone()).await;
+
+            let error = match &result {
+                Ok(output) if !output.is_error => None,
+                Ok(output) => Some(output.content.clone()),
+                Err(e) => Some(e.to_string()),
+            };
+            let Some(error) = error else {
+                return result;
+            };
+
+            if attempt >= self.retry.max_retries
+                || Self::modifies_files(tool.name())
+                || !self.retry.is_retryable(&error)
+            {
+                return result;
+            }
+
+            attempt += 1;
+            tracing::warn!(
+                "Tool '{}' failed with a transient error, retrying ({}/{}) in {}ms: {}",
+                tool.name(),
+                attempt,
+                self.retry.max_retries,
+                self.retry.delay_ms,
+                error
+            );
+            tokio::time::sleep(Duration::from_millis(self.retry.delay_ms)).await;
+        }
+    }
+
+    /// Execute a tool, cutting it off if it exceeds its configured timeout
+    async fn run_tool(&self, tool: Arc<dyn Tool>, params: Value) -> Result<ToolResult> {
+        let Some(timeouts) = &self.timeouts else {
+            return tool.execute(params).await;
+        };
+
+        let limit = timeouts.timeout_for(tool.name());
+        match tokio::time::timeout(limit, tool.execute(params)).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!("Tool '{}' timed out after {:?}", tool.name(), limit);
+                Ok(ToolResult {
+                    content: format!(
+                        "Tool '{}' timed out after {} seconds. \
+                        The timeout can be raised in synthia.toml under [timeouts] {} = <seconds>.",
+                        tool.name(),
+                        limit.as_secs(),
+                        tool.name()
+                    ),
+                    is_error: true,
+                })
+            }
+        }
+    }
+
+    fn is_edit_tool(name: &str) -> bool {
+        matches!(name, "edit" | "write" | "apply_patch")
+    }
+
+    /// Files an edit, write, or patch would change
+    fn edit_targets(name: &str, params: &Value) -> Vec<String> {
+        if name == "apply_patch" {
+            Self::patch_text(params)
+                .ok()
+                .and_then(|patch| super::apply_patch::parse_patch(patch).ok())
+                .unwrap_or_default()
+                .iter()
+                .map(|f| f.display_path().to_string())
+                .collect()
+        } else {
+            params["file_path"].as_str().map(String::from).into_iter().collect()
+        }
+    }
+
+    /// Whether the edit_approval policy wants the user to approve this edit
+    fn needs_edit_approval(&self, name: &str, params: &Value) -> bool {
+        match self.edit_approval {
+            EditApproval::Always => true,
+            EditApproval::Never => false,
+            EditApproval::FirstTime => {
+                let targets = Self::edit_targets(name, params);
+                let Ok(approved) = self.approved_files.lock() else {
+                    return true;
+                };
+                targets.is_empty() || !targets.iter().all(|file| approved.contains(file))
+            }
+        }
+    }
+
+    /// Note that the user approved editing these files
+    fn remember_approval(&self, name: &str, params: &Value) {
+        if self.edit_approval != EditApproval::FirstTime {
+            return;
+        }
+        if let Ok(mut approved) = self.approved_files.lock() {
+            approved.extend(Self::edit_targets(name, params));
+        }
+    }
+
+    /// Show the diff of an edit that's applied without asking
+    async fn send_informational_diff(&self, name: &str, params: &Value) {
+        let Some(ui_tx) = &self.ui_tx else {
+            return;
+        };
+
+        // Compute diff for informational display
+        let diff_result = match name {
+            "edit" => self.compute_edit_diff(params).await,
+            "write" => self.compute_write_diff(params).await,
+            _ => Self::patch_text(params).map(String::from),
+        };
+
+        if let Ok(diff) = diff_result {
+            let file_path = if name == "apply_patch" {
+                Self::patch_files_label(params)
+            } else {
+                params["file_path"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string()
+            };
+            let _ = ui_tx
+                .send(UIUpdate::InformationalDiff {
+                    tool_name: name.to_string(),
+                    file_path,
+                    diff,
+                })
+                .await;
+        }
+    }
+
     async fn compute_edit_diff(&self, params: &Value) -> Result<String> {
         use crate::tools::diff::compute_diff;
 
@@ -258,8 +483,6 @@ impl ToolRegistry {
     }
 
     async fn execute_edit_with_approval(&self, params: Value) -> Result<ToolResult> {
-        // TODO: Check config.ui.edit_approval before prompting
-        // For MVP, always prompt
         use crate::tools::diff::compute_diff;
 
         let file_path = params["file_path"]
@@ -305,9 +528,11 @@ impl ToolRegistry {
         // Wait for user response
         match response_rx.await {
             Ok(crate::agent::messages::ApprovalResponse::Approve) => {
+                self.remember_approval("edit", &params);
+
                 // Execute the edit
                 let tool = self.get("edit").ok_or_else(|| anyhow!("Edit tool not found"))?;
-                tool.execute(params).await
+                self.run_tool(tool, params).await
             }
             Ok(crate::agent::messages::ApprovalResponse::ApproveDontAsk(pattern)) => {
                 // Add permission and execute
@@ -315,10 +540,11 @@ impl ToolRegistry {
                     .lock()
                     .map_err(|e| anyhow!("Failed to acquire permission manager lock: {}", e))?
                     .add_permission(pattern)?;
+                self.remember_approval("edit", &params);
 
                 // Execute the edit
                 let tool = self.get("edit").ok_or_else(|| anyhow!("Edit tool not found"))?;
-                tool.execute(params).await
+                self.run_tool(tool, params).await
             }
             Ok(crate::agent::messages::ApprovalResponse::Reject) => {
                 // User rejected
@@ -384,9 +610,11 @@ impl ToolRegistry {
         // Wait for user response
         match response_rx.await {
             Ok(crate::agent::messages::ApprovalResponse::Approve) => {
+                self.remember_approval("write", &params);
+
                 // Execute the write
                 let tool = self.get("write").ok_or_else(|| anyhow!("Write tool not found"))?;
-                tool.execute(params).await
+                self.run_tool(tool, params).await
             }
             Ok(crate::agent::messages::ApprovalResponse::ApproveDontAsk(pattern)) => {
                 // Add permission and execute
@@ -394,10 +622,11 @@ impl ToolRegistry {
                     .lock()
                     .map_err(|e| anyhow!("Failed to acquire permission manager lock: {}", e))?
                     .add_permission(pattern)?;
+                self.remember_approval("write", &params);
 
                 // Execute the write
                 let tool = self.get("write").ok_or_else(|| anyhow!("Write tool not found"))?;
-                tool.execute(params).await
+                self.run_tool(tool, params).await
             }
             Ok(crate::agent::messages::ApprovalResponse::Reject) => {
                 // User rejected
@@ -416,6 +645,90 @@ impl ToolRegistry {
         }
     }
 
+    async fn execute_patch_with_approval(&self, params: Value) -> Result<ToolResult> {
+        // The patch itself is the diff to preview
+        let diff = Self::patch_text(&params)?.to_string();
+        let files_label = Self::patch_files_label(&params);
+
+        // Create approval channel
+        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+
+        if let Some(ui_tx) = &self.ui_tx {
+            ui_tx
+                .send(UIUpdate::EditPreview {
+                    file_path: format!("{} [PATCH]", files_label),
+                    old_string: "[PATCH]".to_string(),
+                    new_string: format!("{} lines", diff.lines().count()),
+                    diff,
+                    response_tx,
+                })
+                .await?;
+        }
+
+        // Wait for user response
+        match response_rx.await {
+            Ok(crate::agent::messages::ApprovalResponse::Approve) => {
+                self.remember_approval("apply_patch", &params);
+                let tool = self.get("apply_patch").ok_or_else(|| anyhow!("Apply patch tool not found"))?;
+                self.run_tool(tool, params).await
+            }
+            Ok(crate::agent::messages::ApprovalResponse::ApproveDontAsk(_)) => {
+                // Remember each patched file, as if it had been edited on its own
+                {
+                    let mut manager = self.permission_manager
+                        .lock()
+                        .map_err(|e| anyhow!("Failed to acquire permission manager lock: {}", e))?;
+                    for pattern in manager.patch_patterns(&params).unwrap_or_default() {
+                        manager.add_permission(pattern)?;
+                    }
+                }
+                self.remember_approval("apply_patch", &params);
+
+                let tool = self.get("apply_patch").ok_or_else(|| anyhow!("Apply patch tool not found"))?;
+                self.run_tool(tool, params).await
+            }
+            Ok(crate::agent::messages::ApprovalResponse::Reject) => Ok(ToolResult {
+                content: "Patch cancelled by user".to_string(),
+                is_error: false,
+            }),
+            Err(_) => Ok(ToolResult {
+                content: "Patch approval cancelled".to_string(),
+                is_error: true,
+            }),
+        }
+    }
+
+    fn patch_text(params: &Value) -> Result<&str> {
+        params["patch"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Missing patch"))
+    }
+
+    /// Comma-separated list of files touched by an apply_patch call, for display
+    fn patch_files_label(params: &Value) -> String {
+        let files = Self::patch_text(params)
+            .ok()
+            .and_then(|patch| super::apply_patch::parse_patch(patch).ok())
+            .unwrap_or_default();
+
+        if files.is_empty() {
+            "unknown".to_string()
+        } else {
+            files
+                .iter()
+                .map(|f| f.display_path().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+
+    /// Names of all registered tools, sorted
+    pub fn tool_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.tools.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     pub fn definitions(&self) -> Vec<Value> {
         self.tools
             .values()
@@ -434,15 +747,25 @@ impl ToolRegistry {
         matches!(tool_name, "read" | "grep" | "glob" | "powertools")
     }
 
+    /// Check if a tool may modify files (conservatively includes bash and git)
+    fn modifies_files(tool_name: &str) -> bool {
+        matches!(tool_name, "edit" | "write" | "apply_patch" | "bash" | "git")
+    }
+
     /// Invalidate cache when files change (e.g., after write, edit, git operations)
-    #[allow(dead_code)]
     pub fn invalidate_file_cache(&self) {
         self.cache.invalidate_tool("read");
         self.cache.invalidate_tool("grep");
         self.cache.invalidate_tool("glob");
+        self.cache.invalidate_tool("powertools");
         tracing::debug!("Invalidated file-based tool caches");
     }
 
+    /// Expire cached results after `ttl` (None keeps them until invalidated)
+    pub fn set_cache_ttl(&mut self, ttl: Option<std::time::Duration>) {
+        self.cache.set_ttl(ttl);
+    }
+
     /// Get cache statistics
     #[allow(dead_code)]
     pub fn cache_stats(&self) -> super::cache::CacheStats {
@@ -686,6 +1009,314 @@ mod tests {
         assert_eq!(stats.size, 0);
     }
 
+    #[tokio::test]
+    async fn test_read_after_edit_is_fresh() {
+        use crate::tools::{edit::EditTool, read::ReadTool};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("fresh.txt");
+        std::fs::write(&file, "original text\n").unwrap();
+        let file_path = file.to_str().unwrap();
+
+        let mut registry = create_test_registry();
+        registry.register(Arc::new(ReadTool::new(200_000, 100_000))).unwrap();
+        registry.register(Arc::new(EditTool::new())).unwrap();
+
+        let read_params = serde_json::json!({ "file_path": file_path });
+        let first = registry.execute("read", read_params.clone()).await.unwrap();
+        assert!(first.content.contains("original text"));
+
+        let edit = registry
+            .execute(
+                "edit",
+                serde_json::json!({
+                    "file_path": file_path,
+                    "old_string": "original",
+                    "new_string": "updated"
+                }),
+            )
+            .await
+            .unwrap();
+        assert!(!edit.is_error);
+
+        let second = registry.execute("read", read_params).await.unwrap();
+        assert!(second.content.contains("updated text"));
+        assert!(!second.content.contains("original text"));
+    }
+
+    struct SleepyTool;
+
+    #[async_trait]
+    impl Tool for SleepyTool {
+        fn name(&self) -> &str {
+            "sleepy"
+        }
+        fn description(&self) -> &str {
+            "Sleeps for a long time"
+        }
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({})
+        }
+        async fn execute(&self, _params: Value) -> Result<ToolResult> {
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            Ok(ToolResult {
+                content: "finished".to_string(),
+                is_error: false,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_running_tool() {
+        let mut registry = create_test_registry();
+        registry.register(Arc::new(SleepyTool)).unwrap();
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let start = std::time::Instant::now();
+        let result = registry
+            .execute_cancellable("sleepy", serde_json::json!({}), &cancel)
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("canceled"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    /// Fails with `error` for the first `failures` calls, then succeeds
+    struct FlakyTool {
+        name: &'static str,
+        error: &'static str,
+        failures: u32,
+        calls: Arc<std::sync::atomic::AtomicU32>,
+    }
+
+    #[async_trait]
+    impl Tool for FlakyTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn description(&self) -> &str {
+            "Fails a few times before succeeding"
+        }
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({})
+        }
+        async fn execute(&self, _params: Value) -> Result<ToolResult> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ToolResult {
+                content: if call < self.failures { self.error.to_string() } else { "ok".to_string() },
+                is_error: call < self.failures,
+            })
+        }
+    }
+
+    fn flaky_registry(name: &'static str, error: &'static str, failures: u32) -> (ToolRegistry, Arc<std::sync::atomic::AtomicU32>) {
+        let calls = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let mut registry = create_test_registry();
+        registry.register(Arc::new(FlakyTool { name, error, failures, calls: calls.clone() })).unwrap();
+        registry.set_retry_policy(RetryConfig { delay_ms: 0, ..RetryConfig::default() });
+        (registry, calls)
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_is_retried_once() {
+        use std::sync::atomic::Ordering;
+
+        let (registry, calls) = flaky_registry("flaky", "Connection failed: connection reset", 1);
+        let result = registry.execute("flaky", serde_json::json!({})).await.unwrap();
+        assert!(!result.is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Only one retry by default, so a second failure is reported
+        let (registry, calls) = flaky_registry("flaky", "HTTP error 503: Service Unavailable", 2);
+        let result = registry.execute("flaky", serde_json::json!({})).await.unwrap();
+        assert!(result.is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_non_transient_failures_are_not_retried() {
+        use std::sync::atomic::Ordering;
+
+        // Validation error
+        let (registry, calls) = flaky_registry("flaky", "Missing 'url' parameter", 1);
+        assert!(registry.execute("flaky", serde_json::json!({})).await.unwrap().is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Unknown error signature
+        let (registry, calls) = flaky_registry("flaky", "HTTP error 404: Not Found", 1);
+        assert!(registry.execute("flaky", serde_json::json!({})).await.unwrap().is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Tools with side effects
+        let (registry, calls) = flaky_registry("bash", "Resource busy", 1);
+        assert!(registry.execute("bash", serde_json::json!({})).await.unwrap().is_error);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_edit_approval_policy_branches() {
+        let edit = serde_json::json!({ "file_path": "src/lib.rs", "old_string": "a", "new_string": "b" });
+        let other_file = serde_json::json!({ "file_path": "src/main.rs", "content": "fn main() {}" });
+
+        let mut registry = create_test_registry();
+        assert!(registry.needs_edit_approval("edit", &edit)); // Always is the default
+        registry.remember_approval("edit", &edit);
+        assert!(registry.needs_edit_approval("edit", &edit));
+
+        registry.set_edit_approval(EditApproval::Never);
+        assert!(!registry.needs_edit_approval("edit", &edit));
+        assert!(!registry.needs_edit_approval("write", &other_file));
+
+        let mut registry = create_test_registry();
+        registry.set_edit_approval(EditApproval::FirstTime);
+        assert!(registry.needs_edit_approval("edit", &edit));
+        registry.remember_approval("edit", &edit);
+        assert!(!registry.needs_edit_approval("edit", &edit));
+        assert!(!registry.needs_edit_approval("write", &serde_json::json!({ "file_path": "src/lib.rs" })));
+        assert!(registry.needs_edit_approval("write", &other_file));
+    }
+
+    #[test]
+    fn test_first_time_approval_covers_every_patched_file() {
+        let mut registry = create_test_registry();
+        registry.set_edit_approval(EditApproval::FirstTime);
+        let patch = |files: &[&str]| {
+            let text: String = files
+                .iter()
+                .map(|f| format!("--- a/{f}\n+++ b/{f}\n@@ -1 +1 @@\n-old\n+new\n"))
+                .collect();
+            serde_json::json!({ "patch": text })
+        };
+
+        registry.remember_approval("edit", &serde_json::json!({ "file_path": "a.rs" }));
+        assert!(!registry.needs_edit_approval("apply_patch", &patch(&["a.rs"])));
+        assert!(registry.needs_edit_approval("apply_patch", &patch(&["a.rs", "b.rs"])));
+
+        registry.remember_approval("apply_patch", &patch(&["a.rs", "b.rs"]));
+        assert!(!registry.needs_edit_approval("apply_patch", &patch(&["a.rs", "b.rs"])));
+
+        // A patch that can't be parsed always asks
+        assert!(registry.needs_edit_approval("apply_patch", &serde_json::json!({ "patch": "nonsense" })));
+    }
+
+    #[tokio::test]
+    async fn test_dont_ask_again_for_patch_remembers_each_file() {
+        let project_root = std::env::temp_dir().join(format!("test_registry_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(project_root.join("src")).unwrap();
+        std::fs::write(project_root.join("a.rs"), "old\n").unwrap();
+        std::fs::write(project_root.join("src/b.rs"), "old\n").unwrap();
+        let permission_manager = Arc::new(Mutex::new(PermissionManager::new(project_root.clone()).unwrap()));
+        let mut registry = ToolRegistry::new(permission_manager.clone());
+        registry.register(Arc::new(super::super::apply_patch::ApplyPatchTool::new())).unwrap();
+        let (ui_tx, mut ui_rx) = tokio::sync::mpsc::channel(10);
+        registry.set_ui_sender(ui_tx);
+
+        let patch = |from: &str, to: &str| {
+            let text: String = ["a.rs", "src/b.rs"]
+                .iter()
+                .map(|f| format!("--- a/{f}\n+++ b/{f}\n@@ -1 +1 @@\n-{from}\n+{to}\n"))
+                .collect();
+            serde_json::json!({ "patch": text, "base_dir": project_root.to_str().unwrap() })
+        };
+
+        let prompt = tokio::spawn(async move {
+            match ui_rx.recv().await {
+                Some(UIUpdate::EditPreview { response_tx, .. }) => {
+                    let _ = response_tx.send(crate::agent::messages::ApprovalResponse::ApproveDontAsk(String::new()));
+                    ui_rx
+                }
+                other => panic!("expected an edit preview, got {:?}", other),
+            }
+        });
+        let result = registry.execute("apply_patch", patch("old", "new")).await.unwrap();
+        assert!(!result.is_error, "{}", result.content);
+        let mut ui_rx = prompt.await.unwrap();
+
+        let saved = std::fs::read_to_string(project_root.join(".synthia/settings-local.json")).unwrap();
+        let root = project_root.to_string_lossy();
+        assert!(saved.contains(&format!("Edit(/{}/a.rs)", root)), "{}", saved);
+        assert!(saved.contains(&format!("Edit(/{}/src/b.rs)", root)), "{}", saved);
+        assert!(!saved.contains("\"apply_patch\""), "{}", saved);
+
+        // The same files are patched again without a prompt; only the diff is shown
+        let result = registry.execute("apply_patch", patch("new", "newer")).await.unwrap();
+        assert!(!result.is_error, "{}", result.content);
+        assert!(matches!(ui_rx.try_recv(), Ok(UIUpdate::InformationalDiff { .. })));
+        assert_eq!(std::fs::read_to_string(project_root.join("src/b.rs")).unwrap(), "newer\n");
+    }
+
+    #[tokio::test]
+    async fn test_read_only_prompts_show_risk_unless_auto_allowed() {
+        struct GrepTool;
+
+        #[async_trait]
+        impl Tool for GrepTool {
+            fn name(&self) -> &str {
+                "grep"
+            }
+            fn description(&self) -> &str {
+                "Test tool"
+            }
+            fn parameters_schema(&self) -> Value {
+                serde_json::json!({})
+            }
+            async fn execute(&self, _params: Value) -> Result<ToolResult> {
+                Ok(ToolResult {
+                    content: "match".to_string(),
+                    is_error: false,
+                })
+            }
+        }
+
+        let mut registry = create_test_registry();
+        registry.register(Arc::new(GrepTool)).unwrap();
+        let (ui_tx, mut ui_rx) = tokio::sync::mpsc::channel(10);
+        registry.set_ui_sender(ui_tx);
+
+        // Without auto-allow, the user is asked, and told the operation is read-only
+        let prompt = tokio::spawn(async move {
+            match ui_rx.recv().await {
+                Some(UIUpdate::PermissionPrompt { risk, response_tx, .. }) => {
+                    let _ = response_tx.send(crate::agent::messages::PermissionResponse::No);
+                    (risk, ui_rx)
+                }
+                other => panic!("expected a permission prompt, got {:?}", other),
+            }
+        });
+        let result = registry.execute("grep", serde_json::json!({ "pattern": "a" })).await.unwrap();
+        assert_eq!(result.content, "Operation cancelled by user");
+        let (risk, mut ui_rx) = prompt.await.unwrap();
+        assert_eq!(risk, OperationRisk::ReadOnly);
+
+        registry.set_auto_allow_read_only(true);
+        let result = registry.execute("grep", serde_json::json!({ "pattern": "b" })).await.unwrap();
+        assert_eq!(result.content, "match");
+        assert!(ui_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tool_timeout_cuts_off_slow_tool() {
+        let mut registry = create_test_registry();
+        registry.register(Arc::new(SleepyTool)).unwrap();
+
+        let mut timeouts = TimeoutConfig::default();
+        timeouts.per_tool.insert("sleepy".to_string(), 1);
+        registry.set_timeouts(timeouts);
+
+        let start = std::time::Instant::now();
+        let result = registry.execute("sleepy", serde_json::json!({})).await.unwrap();
+
+        assert!(result.is_error);
+        assert!(result.content.contains("timed out after 1 seconds"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
     #[tokio::test]
     async fn test_non_deterministic_tools_not_cached() {
         let mut registry = create_test_registry();
@@ -0,0 +1,405 @@
+/// Delegating a self-contained subtask to a fresh sub-agent
+/// The sub-agent has its own conversation and (optionally) a restricted tool set; only its
+/// final answer is returned, so the parent's context doesn't fill up with intermediate steps
+use super::registry::ToolRegistry;
+use super::{Tool, ToolResult};
+use crate::llm::{GenerationConfig, LLMProvider};
+use crate::types::{ContentBlock, Message, Role};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock, Weak};
+
+pub const TOOL_NAME: &str = "spawn_subagent";
+
+/// Tool-call budget for one sub-agent run (the main agent allows 50)
+const MAX_SUBAGENT_TOOL_CALLS: usize = 20;
+
+const SUBAGENT_PROMPT: &str = "You are a sub-agent working on one self-contained task for another agent. \
+Use the available tools to complete the task, then reply with a concise summary of what you found or did. \
+Your final message is the only thing the other agent will see, so include any file paths, results, or errors it needs.";
+
+pub struct SpawnSubagentTool {
+    llm_provider: Arc<dyn LLMProvider>,
+    config: GenerationConfig,
+    /// The registry this tool is registered in, attached once it's built
+    /// (Weak, since the registry owns this tool)
+    registry: OnceLock<Weak<ToolRegistry>>,
+}
+
+impl SpawnSubagentTool {
+    pub fn new(llm_provider: Arc<dyn LLMProvider>, config: GenerationConfig) -> Self {
+        Self {
+            llm_provider,
+            // Nobody watches a sub-agent's output stream, so skip streaming
+            config: GenerationConfig {
+                streaming: false,
+                ..config
+            },
+            registry: OnceLock::new(),
+        }
+    }
+
+    /// Give the tool the registry sub-agents run their tools through
+    pub fn attach_registry(&self, registry: &Arc<ToolRegistry>) {
+        let _ = self.registry.set(Arc::downgrade(registry));
+    }
+
+    /// Run the sub-agent until it answers without calling tools or runs out of budget
+    async fn run(
+        &self,
+        registry: &ToolRegistry,
+        task: &str,
+        allowed_tools: Option<&HashSet<String>>,
+    ) -> Result<ToolResult> {
+        let is_available = |name: &str| {
+            name != TOOL_NAME && allowed_tools.is_none_or(|allowed| allowed.contains(name))
+        };
+        let tools: Vec<Value> = registry
+            .definitions()
+            .into_iter()
+            .filter(|def| def["name"].as_str().is_some_and(is_available))
+            .collect();
+
+        let mut conversation = vec![
+            text_message(Role::System, SUBAGENT_PROMPT),
+            text_message(Role::User, task),
+        ];
+        let mut tool_call_count = 0;
+
+        loop {
+            let response = self
+                .llm_provider
+                .chat_completion(conversation.clone(), tools.clone(), &self.config)
+                .await?;
+
+            let calls: Vec<(String, String, Value)> = response
+                .content
+                .iter()
+                .filter_map(|block| match block {
+                    ContentBlock::ToolUse { id, name, input } => {
+                        Some((id.clone(), name.clone(), input.clone()))
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            if calls.is_empty() {
+                let summary = response
+                    .content
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::Text { text } => Some(text.as_str()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                return Ok(ToolResult {
+                    content: if summary.trim().is_empty() {
+                        "Sub-agent finished without a summary".to_string()
+                    } else {
+                        summary
+                    },
+                    is_error: false,
+                });
+            }
+
+            if tool_call_count + calls.len() > MAX_SUBAGENT_TOOL_CALLS {
+                tracing::warn!("Sub-agent hit its tool call limit ({})", MAX_SUBAGENT_TOOL_CALLS);
+                return Ok(ToolResult {
+                    content: format!(
+                        "Sub-agent stopped after {} tool calls without finishing the task. \
+                        Try splitting it into smaller subtasks.",
+                        tool_call_count
+                    ),
+                    is_error: true,
+                });
+            }
+            tool_call_count += calls.len();
+            conversation.push(Message {
+                role: Role::Assistant,
+                content: response.content,
+            });
+
+            let mut results = Vec::new();
+            for (id, name, input) in calls {
+                tracing::debug!("Sub-agent calling tool '{}'", name);
+                let result = if is_available(&name) {
+                    registry
+                        .execute(&name, input)
+                        .await
+                        .unwrap_or_else(|e| ToolResult {
+                            content: format!("Error: {}", e),
+                            is_error: true,
+                        })
+                } else {
+                    ToolResult {
+                        content: format!("Tool '{}' is not available to this sub-agent", name),
+                        is_error: true,
+                    }
+                };
+                results.push(ContentBlock::ToolResult {
+                    tool_use_id: id,
+                    content: result.content,
+                    is_error: result.is_error,
+                });
+            }
+            conversation.push(Message {
+                role: Role::User,
+                content: results,
+            });
+        }
+    }
+}
+
+fn text_message(role: Role, text: &str) -> Message {
+    Message {
+        role,
+        content: vec![ContentBlock::Text {
+            text: text.to_string(),
+        }],
+    }
+}
+
+#[async_trait]
+impl Tool for SpawnSubagentTool {
+    fn name(&self) -> &str {
+        TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "Delegate a self-contained subtask (e.g. 'find every caller of parse_config and summarize how they use it') \
+        to a sub-agent with its own fresh context. Returns only the sub-agent's final summary, keeping this \
+        conversation small. Optionally restrict which tools the sub-agent may use."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "task": {
+                    "type": "string",
+                    "description": "Complete description of the subtask, including what the summary should contain"
+                },
+                "allowed_tools": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Tool names the sub-agent may use (defaults to all tools)"
+                }
+            },
+            "required": ["task"]
+        })
+    }
+
+    async fn execute(&self, params: Value) -> Result<ToolResult> {
+        let task = params["task"]
+            .as_str()
+            .filter(|task| !task.trim().is_empty())
+            .ok_or_else(|| anyhow!("Missing 'task' parameter"))?;
+
+        let registry = self
+            .registry
+            .get()
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| anyhow!("spawn_subagent is not attached to a tool registry"))?;
+
+        let allowed_tools: Option<HashSet<String>> = match params.get("allowed_tools") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(names)) => Some(
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().map(String::from))
+                    .collect(),
+            ),
+            Some(other) => {
+                return Err(anyhow!("Invalid 'allowed_tools' parameter: expected an array of tool names, got {}", other));
+            }
+        };
+
+        if let Some(allowed) = &allowed_tools {
+            let unknown: Vec<&str> = allowed
+                .iter()
+                .map(String::as_str)
+                .filter(|name| registry.get(name).is_none())
+                .collect();
+            if !unknown.is_empty() {
+                return Ok(ToolResult {
+                    content: format!("Unknown tools in allowed_tools: {}", unknown.join(", ")),
+                    is_error: true,
+                });
+            }
+        }
+
+        tracing::info!("Spawning sub-agent for task: {}", task);
+        self.run(&registry, task, allowed_tools.as_ref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::provider::StreamResult;
+    use crate::llm::LLMResponse;
+    use crate::types::{StopReason, TokenUsage};
+    use std::sync::Mutex;
+
+    /// Replays canned responses in order and records the tools offered on each call
+    struct ScriptedProvider {
+        responses: Mutex<Vec<Vec<ContentBlock>>>,
+        offered_tools: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(mut responses: Vec<Vec<ContentBlock>>) -> Self {
+            responses.reverse();
+            Self {
+                responses: Mutex::new(responses),
+                offered_tools: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLMProvider for ScriptedProvider {
+        async fn chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<LLMResponse> {
+            let mut names: Vec<String> = tools
+                .iter()
+                .filter_map(|t| t["name"].as_str().map(String::from))
+                .collect();
+            names.sort();
+            self.offered_tools.lock().unwrap().push(names);
+
+            Ok(LLMResponse {
+                content: self.responses.lock().unwrap().pop().expect("no scripted response left"),
+                stop_reason: StopReason::EndTurn,
+                usage: TokenUsage {
+                    input_tokens: 0,
+                    output_tokens: 0,
+                },
+            })
+        }
+
+        async fn stream_chat_completion(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<Value>,
+            _config: &GenerationConfig,
+        ) -> Result<StreamResult> {
+            unimplemented!("Sub-agents don't stream")
+        }
+    }
+
+    struct EchoTool(&'static str);
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+        fn description(&self) -> &str {
+            "Echoes its input"
+        }
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({})
+        }
+        async fn execute(&self, params: Value) -> Result<ToolResult> {
+            Ok(ToolResult {
+                content: format!("{} says {}", self.0, params["text"].as_str().unwrap_or("")),
+                is_error: false,
+            })
+        }
+    }
+
+    fn tool_call(id: &str, name: &str) -> ContentBlock {
+        ContentBlock::ToolUse {
+            id: id.to_string(),
+            name: name.to_string(),
+            input: serde_json::json!({ "text": "hi" }),
+        }
+    }
+
+    fn text(text: &str) -> ContentBlock {
+        ContentBlock::Text {
+            text: text.to_string(),
+        }
+    }
+
+    fn setup(provider: Arc<ScriptedProvider>) -> Arc<ToolRegistry> {
+        let config = GenerationConfig {
+            temperature: 0.7,
+            max_tokens: None,
+            model: "test-model".to_string(),
+            streaming: true,
+            reasoning_level: "medium".to_string(),
+            context_window: 8192,
+            stop: Vec::new(),
+            seed: None,
+            vision: false,
+            response_format: None,
+        };
+        let subagent = Arc::new(SpawnSubagentTool::new(provider, config));
+
+        let mut registry = ToolRegistry::default();
+        registry.register(Arc::new(EchoTool("echo"))).unwrap();
+        registry.register(Arc::new(EchoTool("shout"))).unwrap();
+        registry.register(subagent.clone()).unwrap();
+        let registry = Arc::new(registry);
+        subagent.attach_registry(&registry);
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_subagent_returns_only_final_summary() {
+        let provider = Arc::new(ScriptedProvider::new(vec![
+            vec![text("Let me check."), tool_call("call_1", "echo")],
+            vec![text("Echo said hi.")],
+        ]));
+        let registry = setup(provider.clone());
+
+        let result = registry
+            .execute(TOOL_NAME, serde_json::json!({ "task": "Ask echo to say hi" }))
+            .await
+            .unwrap();
+        assert!(!result.is_error);
+        assert_eq!(result.content, "Echo said hi.");
+
+        // Sub-agents can't spawn further sub-agents
+        let offered = provider.offered_tools.lock().unwrap();
+        assert_eq!(offered[0], vec!["echo", "shout"]);
+    }
+
+    #[tokio::test]
+    async fn test_subagent_honors_tool_allowlist() {
+        let provider = Arc::new(ScriptedProvider::new(vec![
+            vec![tool_call("call_1", "shout")],
+            vec![text("Shout was not allowed.")],
+        ]));
+        let registry = setup(provider.clone());
+
+        let result = registry
+            .execute(
+                TOOL_NAME,
+                serde_json::json!({ "task": "Shout hi", "allowed_tools": ["echo"] }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.content, "Shout was not allowed.");
+        assert_eq!(provider.offered_tools.lock().unwrap()[0], vec!["echo"]);
+
+        let result = registry
+            .execute(
+                TOOL_NAME,
+                serde_json::json!({ "task": "Anything", "allowed_tools": ["nope"] }),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("nope"));
+    }
+}
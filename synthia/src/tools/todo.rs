@@ -20,15 +20,22 @@ pub enum TodoStatus {
     Completed,
 }
 
+/// Todo list shared between the tool and the agent, which persists it in the session
+pub type SharedTodoList = Arc<Mutex<Vec<Todo>>>;
+
 pub struct TodoTool {
-    todos: Arc<Mutex<Vec<Todo>>>,
+    todos: SharedTodoList,
 }
 
 impl TodoTool {
+    #[cfg(test)]
     pub fn new() -> Self {
-        Self {
-            todos: Arc::new(Mutex::new(Vec::new())),
-        }
+        Self::with_list(SharedTodoList::default())
+    }
+
+    /// Create a tool that reads and writes an existing shared list
+    pub fn with_list(todos: SharedTodoList) -> Self {
+        Self { todos }
     }
 
     fn format_todos(&self) -> String {
@@ -257,4 +264,26 @@ mod tests {
         assert!(result.content.contains("New task"));
         assert!(!result.content.contains("Old task"));
     }
+
+    #[tokio::test]
+    async fn test_todo_writes_shared_list() {
+        let shared = SharedTodoList::default();
+        let tool = TodoTool::with_list(shared.clone());
+
+        tool.execute(serde_json::json!({
+            "todos": [
+                {
+                    "content": "Run tests",
+                    "status": "in_progress",
+                    "active_form": "Running tests"
+                }
+            ]
+        }))
+        .await
+        .unwrap();
+
+        let todos = shared.lock().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].status, TodoStatus::InProgress);
+    }
 }
@@ -1,10 +1,15 @@
 use super::{Tool, ToolResult};
 use anyhow::Result;
 use async_trait::async_trait;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::header::{self, HeaderMap, HeaderName, HeaderValue};
+use reqwest::redirect::Policy;
 use serde_json::Value;
+use std::net::IpAddr;
 use std::time::Duration;
-use url::Url;
+use url::{Host, Url};
+
+/// Redirects followed when the caller doesn't say
+const DEFAULT_MAX_REDIRECTS: u64 = 5;
 
 pub struct WebFetchTool {
     client: reqwest::Client,
@@ -13,8 +18,10 @@ pub struct WebFetchTool {
 impl WebFetchTool {
     pub fn new() -> Self {
         Self {
+            // Redirects are followed by hand, so every hop is checked like the first URL
             client: reqwest::Client::builder()
                 .user_agent("Synthia/0.1.0")
+                .redirect(Policy::none())
                 .build()
                 .expect("Failed to create reqwest client"),
         }
@@ -44,6 +51,75 @@ impl WebFetchTool {
     }
 }
 
+/// Addresses inside this machine or its network, off limits unless `allow_private` is set
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || (a == 100 && (b & 0xc0) == 64) // Carrier-grade NAT, 100.64.0.0/10
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_private_ip(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                v6.is_loopback()
+                    || v6.is_unspecified()
+                    || (first & 0xfe00) == 0xfc00 // Unique local, fc00::/7
+                    || (first & 0xffc0) == 0xfe80 // Link-local, fe80::/10
+            }
+        },
+    }
+}
+
+/// Refuse a URL whose host is, or resolves to, a private address (SSRF protection)
+///
+/// The host is resolved again when connecting, so this doesn't stop a DNS server that
+/// answers differently the second time; it does stop plain links to internal services.
+async fn check_host(url: &Url) -> std::result::Result<(), String> {
+    let addresses: Vec<IpAddr> = match url.host() {
+        Some(Host::Ipv4(ip)) => vec![ip.into()],
+        Some(Host::Ipv6(ip)) => vec![ip.into()],
+        Some(Host::Domain(domain)) => {
+            let port = url.port_or_known_default().unwrap_or(80);
+            match tokio::net::lookup_host((domain, port)).await {
+                Ok(addresses) => addresses.map(|a| a.ip()).collect(),
+                Err(e) => return Err(format!("Connection failed: could not resolve {}: {}", domain, e)),
+            }
+        }
+        None => return Err(format!("Invalid URL '{}': no host", url)),
+    };
+
+    match addresses.into_iter().find(|ip| is_private_ip(*ip)) {
+        Some(ip) => Err(format!(
+            "Refusing to fetch {}: {} is a private or loopback address (set allow_private to allow)",
+            url, ip
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Where a redirect from `current` to `location` leads, if it may be followed
+fn redirect_target(current: &Url, location: &str, allow_downgrade: bool) -> std::result::Result<Url, String> {
+    let next = current
+        .join(location)
+        .map_err(|e| format!("Invalid redirect location '{}': {}", location, e))?;
+    if next.scheme() != "http" && next.scheme() != "https" {
+        return Err(format!("Refusing redirect to {}: scheme must be http or https", next));
+    }
+    if current.scheme() == "https" && next.scheme() == "http" && !allow_downgrade {
+        return Err(format!(
+            "Refusing redirect from https to http ({}); set allow_downgrade to follow it",
+            next
+        ));
+    }
+    Ok(next)
+}
+
 #[async_trait]
 impl Tool for WebFetchTool {
     fn name(&self) -> &str {
@@ -51,7 +127,9 @@ impl Tool for WebFetchTool {
     }
 
     fn description(&self) -> &str {
-        "Fetch content from a URL (HTTP/HTTPS GET request)"
+        "Fetch content from a URL (HTTP/HTTPS GET request). Redirects are followed up to max_redirects; \
+        when one was followed, the result starts with the final URL. Private and loopback addresses \
+        are refused unless allow_private is set."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -73,6 +151,18 @@ impl Tool for WebFetchTool {
                 "max_size_mb": {
                     "type": "integer",
                     "description": "Maximum response size in MB (default: 10)"
+                },
+                "max_redirects": {
+                    "type": "integer",
+                    "description": "Maximum redirects to follow; 0 returns the redirect as an error (default: 5)"
+                },
+                "allow_private": {
+                    "type": "boolean",
+                    "description": "Allow localhost and private network addresses (default: false)"
+                },
+                "allow_downgrade": {
+                    "type": "boolean",
+                    "description": "Follow redirects from https to plain http (default: false)"
                 }
             },
             "required": ["url"]
@@ -106,37 +196,72 @@ impl Tool for WebFetchTool {
         let timeout_seconds = params["timeout_seconds"].as_u64().unwrap_or(30);
         let max_size_mb = params["max_size_mb"].as_u64().unwrap_or(10);
         let max_size_bytes = max_size_mb * 1024 * 1024; // Convert MB to bytes
-        let headers = self.parse_headers(params.get("headers"))?;
-
-        // Build request (use validated URL string)
-        let mut request = self.client.get(url_str);
-
-        // Add custom headers
-        if !headers.is_empty() {
-            request = request.headers(headers);
-        }
-
-        // Set timeout
-        request = request.timeout(Duration::from_secs(timeout_seconds));
+        let mut headers = self.parse_headers(params.get("headers"))?;
+        let max_redirects = params["max_redirects"].as_u64().unwrap_or(DEFAULT_MAX_REDIRECTS);
+        let allow_private = params["allow_private"].as_bool().unwrap_or(false);
+        let allow_downgrade = params["allow_downgrade"].as_bool().unwrap_or(false);
+        let failed = |content: String| Ok(ToolResult { content, is_error: true });
+
+        let mut url = parsed_url;
+        let mut redirects = 0;
+        let response = loop {
+            if !allow_private {
+                if let Err(e) = check_host(&url).await {
+                    return failed(e);
+                }
+            }
 
-        // Execute request
-        let response = match request.send().await {
-            Ok(resp) => resp,
-            Err(e) => {
-                let error_msg = if e.is_timeout() {
-                    format!("Request timed out after {} seconds", timeout_seconds)
-                } else if e.is_connect() {
-                    format!("Connection failed: {}", e)
-                } else if e.is_request() {
-                    format!("Invalid request: {}", e)
-                } else {
-                    format!("Network error: {}", e)
-                };
-                return Ok(ToolResult {
-                    content: error_msg,
-                    is_error: true,
-                });
+            // Build request, with custom headers and timeout
+            let mut request = self.client.get(url.clone());
+            if !headers.is_empty() {
+                request = request.headers(headers.clone());
+            }
+            request = request.timeout(Duration::from_secs(timeout_seconds));
+
+            // Execute request
+            let response = match request.send().await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    let error_msg = if e.is_timeout() {
+                        format!("Request timed out after {} seconds", timeout_seconds)
+                    } else if e.is_connect() {
+                        format!("Connection failed: {}", e)
+                    } else if e.is_request() {
+                        format!("Invalid request: {}", e)
+                    } else {
+                        format!("Network error: {}", e)
+                    };
+                    return failed(error_msg);
+                }
+            };
+
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|value| value.to_str().ok());
+            let Some(location) = location.filter(|_| response.status().is_redirection()) else {
+                break response;
+            };
+
+            if redirects == max_redirects {
+                return failed(format!(
+                    "Too many redirects: stopped after {} (max_redirects); next was {}",
+                    redirects, location
+                ));
+            }
+            let next = match redirect_target(&url, location, allow_downgrade) {
+                Ok(next) => next,
+                Err(e) => return failed(e),
+            };
+
+            // Credentials are meant for the host they were given for
+            if next.host_str() != url.host_str() {
+                for name in [header::AUTHORIZATION, header::COOKIE, header::PROXY_AUTHORIZATION] {
+                    headers.remove(name);
+                }
             }
+            url = next;
+            redirects += 1;
         };
 
         // Check HTTP status
@@ -186,8 +311,13 @@ impl Tool for WebFetchTool {
             }
         };
 
+        let content = if redirects > 0 {
+            format!("Final URL: {}\n\n{}", url, body)
+        } else {
+            body
+        };
         Ok(ToolResult {
-            content: body,
+            content,
             is_error: false,
         })
     }
@@ -235,6 +365,7 @@ mod tests {
         let result = tool
             .execute(serde_json::json!({
                 "url": format!("{}/test", server.url()),
+                "allow_private": true,
                 "headers": {
                     "X-Custom-Header": "test-value"
                 }
@@ -260,7 +391,8 @@ mod tests {
         let tool = WebFetchTool::new();
         let result = tool
             .execute(serde_json::json!({
-                "url": format!("{}/test", server.url())
+                "url": format!("{}/test", server.url()),
+                "allow_private": true
             }))
             .await
             .unwrap();
@@ -282,7 +414,8 @@ mod tests {
         let tool = WebFetchTool::new();
         let result = tool
             .execute(serde_json::json!({
-                "url": format!("{}/notfound", server.url())
+                "url": format!("{}/notfound", server.url()),
+                "allow_private": true
             }))
             .await
             .unwrap();
@@ -352,6 +485,7 @@ mod tests {
         let result = tool
             .execute(serde_json::json!({
                 "url": format!("{}/test", server.url()),
+                "allow_private": true,
                 "timeout_seconds": 60
             }))
             .await
@@ -426,7 +560,8 @@ mod tests {
         // Test with default max_size (10MB) - should succeed
         let result = tool
             .execute(serde_json::json!({
-                "url": format!("{}/large", server.url())
+                "url": format!("{}/large", server.url()),
+                "allow_private": true
             }))
             .await
             .unwrap();
@@ -447,6 +582,7 @@ mod tests {
         let result = tool
             .execute(serde_json::json!({
                 "url": format!("{}/large2", server.url()),
+                "allow_private": true,
                 "max_size_mb": 1
             }))
             .await
@@ -456,4 +592,101 @@ mod tests {
         assert!(result.is_error);
         assert!(result.content.contains("Response too large") || result.content.contains("exceeds maximum"));
     }
+
+    #[tokio::test]
+    async fn test_webfetch_follows_redirects_up_to_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("GET", "/old")
+            .with_status(301)
+            .with_header("Location", "/moved")
+            .expect(2)
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/moved")
+            .with_status(302)
+            .with_header("Location", &format!("{}/final", server.url()))
+            .expect(2)
+            .create_async()
+            .await;
+        let last = server
+            .mock("GET", "/final")
+            .with_status(200)
+            .with_body("Arrived")
+            .create_async()
+            .await;
+
+        let tool = WebFetchTool::new();
+        let result = tool
+            .execute(serde_json::json!({
+                "url": format!("{}/old", server.url()),
+                "allow_private": true
+            }))
+            .await
+            .unwrap();
+        assert!(!result.is_error, "{}", result.content);
+        assert_eq!(result.content, format!("Final URL: {}/final\n\nArrived", server.url()));
+
+        // One redirect allowed, two needed
+        let result = tool
+            .execute(serde_json::json!({
+                "url": format!("{}/old", server.url()),
+                "allow_private": true,
+                "max_redirects": 1
+            }))
+            .await
+            .unwrap();
+        assert!(result.is_error);
+        assert!(result.content.contains("Too many redirects"), "{}", result.content);
+
+        first.assert_async().await;
+        second.assert_async().await;
+        last.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_webfetch_blocks_private_addresses() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server.mock("GET", "/internal").expect(0).create_async().await;
+
+        let tool = WebFetchTool::new();
+        for url in [
+            format!("{}/internal", server.url()),
+            "http://localhost/".to_string(),
+            "http://10.0.0.1/".to_string(),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+            "http://[::1]/".to_string(),
+        ] {
+            let result = tool.execute(serde_json::json!({ "url": url })).await.unwrap();
+            assert!(result.is_error, "{} was fetched", url);
+            assert!(result.content.contains("private or loopback"), "{}", result.content);
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_is_private_ip() {
+        for ip in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.0.1", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(is_private_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "192.0.2.1", "100.128.0.1", "2606:4700::1"] {
+            assert!(!is_private_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_redirect_target() {
+        let current = Url::parse("https://example.com/docs/page").unwrap();
+
+        let next = redirect_target(&current, "other", false).unwrap();
+        assert_eq!(next.as_str(), "https://example.com/docs/other");
+
+        let err = redirect_target(&current, "http://example.com/", false).unwrap_err();
+        assert!(err.contains("https to http"), "{}", err);
+        assert!(redirect_target(&current, "http://example.com/", true).is_ok());
+
+        assert!(redirect_target(&current, "file:///etc/passwd", true).is_err());
+    }
 }
@@ -30,6 +30,12 @@ pub enum ContentBlock {
         content: String,
         is_error: bool,
     },
+    /// Model reasoning kept for display and the session log; never sent back to the API
+    #[serde(rename = "thinking")]
+    Thinking { text: String },
+    /// Base64-encoded image for vision-capable models
+    #[serde(rename = "image")]
+    Image { media_type: String, data: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
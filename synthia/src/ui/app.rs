@@ -1,28 +1,41 @@
 use crate::agent::messages::{Command, UIUpdate};
 use crate::context_manager::TokenStats;
-use crate::ui::colors::PastelColors;
+use crate::llm::thinking::split_think_tags;
+use crate::permission_manager::OperationRisk;
+use crate::tools::todo::{Todo, TodoStatus};
+use crate::ui::clipboard::{last_code_block, ClipboardWriter, CopyDestination};
+use crate::ui::colors::{should_color, ResetColor, SetForegroundColor, Theme};
+use crate::ui::highlight::{fence_language, CodeHighlighter, Segment};
+use crate::ui::scrollback::Scrollback;
+use crate::ui::table;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyModifiers},
     execute, queue,
-    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    style::{Attribute, Color, Print, SetAttribute},
     terminal::{disable_raw_mode, enable_raw_mode, size, Clear, ClearType},
 };
 use std::io::{self, Write};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc::{Receiver, Sender};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 // Event batching safety limits
 const MAX_BATCH_SIZE: usize = 1000;
 const BATCH_TIMEOUT_MS: u64 = 10;
 
 /// Wrap text at word boundaries for a given terminal width
-/// Markdown-aware: preserves newlines, code blocks, and indentation
+/// Markdown-aware: preserves newlines, code blocks, tables, and indentation
 fn wrap_text(text: &str, width: usize) -> String {
     let mut result = String::new();
     let mut in_code_block = false;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
 
-    for line in text.lines() {
         // Check for code block markers
         if line.trim_start().starts_with("```") {
             in_code_block = !in_code_block;
@@ -44,6 +57,16 @@ fn wrap_text(text: &str, width: usize) -> String {
             continue;
         }
 
+        // Tables are laid out at render time, so keep their rows intact
+        if let Some(len) = table::table_len(&lines[i - 1..]) {
+            for row in &lines[i - 1..i - 1 + len] {
+                result.push_str(row);
+                result.push('\n');
+            }
+            i += len - 1;
+            continue;
+        }
+
         // Wrap regular lines
         let wrapped_line = wrap_single_line(line, width);
         result.push_str(&wrapped_line);
@@ -54,15 +77,16 @@ fn wrap_text(text: &str, width: usize) -> String {
 }
 
 /// Wrap a single line of text at word boundaries
+/// Widths are terminal display columns, so CJK and emoji count as 2
 fn wrap_single_line(text: &str, width: usize) -> String {
     let mut wrapped = String::new();
     let mut current_line = String::new();
     let mut current_width = 0;
 
     for word in text.split_whitespace() {
-        let word_len = word.chars().count(); // Unicode-aware
+        let word_width = UnicodeWidthStr::width(word);
 
-        if current_width > 0 && current_width + 1 + word_len > width {
+        if current_width > 0 && current_width + 1 + word_width > width {
             // Wrap to new line
             wrapped.push_str(&current_line);
             wrapped.push('\n');
@@ -76,36 +100,24 @@ fn wrap_single_line(text: &str, width: usize) -> String {
         }
 
         // Handle very long words (URLs, hashes, etc.)
-        if word_len > width {
-            // Break at width boundary
-            let chars: Vec<char> = word.chars().collect();
-            let mut chunk_start = 0;
-
-            while chunk_start < chars.len() {
-                let remaining = width.saturating_sub(current_width);
-                let chunk_end = (chunk_start + remaining).min(chars.len());
-                let chunk: String = chars[chunk_start..chunk_end].iter().collect();
+        if word_width > width {
+            // Break at the width boundary, never splitting a wide character across lines
+            for ch in word.chars() {
+                let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
 
-                if current_width > 0 {
-                    wrapped.push_str(&current_line);
-                    wrapped.push('\n');
-                    current_line.clear();
-                }
-
-                current_line.push_str(&chunk);
-                current_width = chunk_end - chunk_start;
-                chunk_start = chunk_end;
-
-                if chunk_start < chars.len() {
+                if current_width > 0 && current_width + ch_width > width {
                     wrapped.push_str(&current_line);
                     wrapped.push('\n');
                     current_line.clear();
                     current_width = 0;
                 }
+
+                current_line.push(ch);
+                current_width += ch_width;
             }
         } else {
             current_line.push_str(word);
-            current_width += word_len;
+            current_width += word_width;
         }
     }
 
@@ -155,7 +167,7 @@ fn print_bordered_line(stdout: &mut impl Write, text: &str, color: Color) -> io:
 
 /// Render a line of text with markdown formatting using crossterm
 /// Supports: **bold**, *italic*, `code`
-fn render_markdown_line(stdout: &mut impl Write, line: &str) -> io::Result<()> {
+fn render_markdown_line(stdout: &mut impl Write, line: &str, code_color: Color) -> io::Result<()> {
     let mut chars = line.chars().peekable();
     let mut current = String::new();
 
@@ -240,7 +252,7 @@ fn render_markdown_line(stdout: &mut impl Write, line: &str) -> io::Result<()> {
                 if !code_text.is_empty() {
                     queue!(
                         stdout,
-                        SetForegroundColor(PastelColors::TOOL),
+                        SetForegroundColor(code_color),
                         Print(&code_text),
                         ResetColor
                     )?;
@@ -261,6 +273,161 @@ fn render_markdown_line(stdout: &mut impl Write, line: &str) -> io::Result<()> {
     stdout.flush()
 }
 
+/// Render wrapped assistant text: fenced code blocks are syntax highlighted
+/// (when enabled), tables are drawn with aligned columns, and everything else
+/// gets inline markdown formatting
+fn render_markdown_text(
+    stdout: &mut impl Write,
+    text: &str,
+    width: usize,
+    highlight: bool,
+    code_color: Color,
+) -> io::Result<()> {
+    let mut code_block: Option<CodeHighlighter> = None;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if line.trim_start().starts_with("```") {
+            code_block = match code_block {
+                Some(_) => None,
+                None => {
+                    let language = if highlight { fence_language(line) } else { None };
+                    Some(CodeHighlighter::new(language))
+                }
+            };
+            print_colored_line(stdout, line, Color::DarkGrey)?;
+            continue;
+        }
+
+        match code_block.as_mut() {
+            Some(highlighter) => {
+                let segments = highlighter.highlight(&sanitize_text(line));
+                render_code_line(stdout, &segments, width)?;
+            }
+            None => {
+                if let Some(len) = table::table_len(&lines[i - 1..]) {
+                    for row in table::render_table(&lines[i - 1..i - 1 + len], width) {
+                        print_line(stdout, &row)?;
+                    }
+                    i += len - 1;
+                } else {
+                    render_markdown_line(stdout, line, code_color)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print one highlighted code line, hard-wrapping at `width` display columns
+/// Code blocks skip word wrapping, so long lines would otherwise overflow the terminal
+fn render_code_line(stdout: &mut impl Write, segments: &[Segment], width: usize) -> io::Result<()> {
+    let width = width.max(1);
+    let mut column = 0;
+
+    queue!(stdout, Print("\r"))?;
+
+    for (color, text) in segments {
+        if let Some(color) = color {
+            queue!(stdout, SetForegroundColor(*color))?;
+        }
+
+        for ch in text.chars() {
+            let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if column > 0 && column + ch_width > width {
+                queue!(stdout, Print("\r\n"))?;
+                column = 0;
+            }
+            queue!(stdout, Print(ch))?;
+            column += ch_width;
+        }
+
+        if color.is_some() {
+            queue!(stdout, ResetColor)?;
+        }
+    }
+
+    queue!(stdout, Print("\n"))?;
+    stdout.flush()
+}
+
+/// Top-level menu entries, in display order (indexes match handle_menu_selection)
+const MENU_ITEMS: &[&str] = &[
+    "Set Session Name",
+    "Tag Session",
+    "Save Session",
+    "Load Session",
+    "Search Sessions",
+    "Sessions by Tag",
+    "New Session",
+    "Fork Session",
+    "Set Reasoning Level",
+    "Context Management",
+    "Switch LLM Profile",
+    "Toggle Mode (Coming Soon)",
+];
+
+/// Print one row of the header box, padded so the right border lines up
+fn print_header_row(stdout: &mut impl Write, line: &str) -> io::Result<()> {
+    // Pad to 64 chars (including the final ║)
+    let padding = 64_usize.saturating_sub(line.chars().count() + 1);
+    queue!(
+        stdout,
+        Print(line),
+        Print(" ".repeat(padding)),
+        Print(" ║\r\n"),
+        Print("╠════════════════════════════════════════════════════════════════╣\r\n")
+    )
+}
+
+/// Header line summarizing todo progress and the task in progress, if any
+fn todo_summary(todos: &[Todo]) -> Option<String> {
+    if todos.is_empty() {
+        return None;
+    }
+    let done = todos.iter().filter(|t| t.status == TodoStatus::Completed).count();
+    let mut line = format!("║  Todos: {}/{} done", done, todos.len());
+    if let Some(current) = todos.iter().find(|t| t.status == TodoStatus::InProgress) {
+        // Keep the row inside the 64-column header box
+        let room = 62_usize.saturating_sub(line.chars().count() + 5);
+        let mut active: String = current.active_form.chars().take(room).collect();
+        if active.chars().count() < current.active_form.chars().count() {
+            active.pop();
+            active.push('…');
+        }
+        line.push_str(&format!(" · ⏳ {}", active));
+    }
+    Some(line)
+}
+
+/// Updates that add to the conversation transcript (as opposed to prompts and menus)
+fn is_transcript_update(update: &UIUpdate) -> bool {
+    matches!(
+        update,
+        UIUpdate::AssistantText(_)
+            | UIUpdate::AssistantThinking
+            | UIUpdate::AssistantReasoning(_)
+            | UIUpdate::AssistantTextDelta(_)
+            | UIUpdate::ToolExecutionStarted { .. }
+            | UIUpdate::ToolResult { .. }
+            | UIUpdate::Error(_)
+            | UIUpdate::Complete
+            | UIUpdate::SessionSaved { .. }
+            | UIUpdate::SystemMessage(_)
+            | UIUpdate::InformationalDiff { .. }
+    )
+}
+
+/// Normalize pasted text: CRLF/CR line endings become \n and tabs become spaces
+fn normalize_paste(text: &str) -> String {
+    sanitize_text(&text.replace("\r\n", "\n").replace('\r', "\n"))
+}
+
 #[derive(Debug)]
 struct EditApprovalState {
     file_path: String,
@@ -268,19 +435,143 @@ struct EditApprovalState {
     old_string: String,
     #[allow(dead_code)]
     new_string: String,
-    #[allow(dead_code)]
     diff: String,
     response_tx: tokio::sync::oneshot::Sender<crate::agent::messages::ApprovalResponse>,
+    /// First diff row shown when the diff is taller than the prompt
+    scroll: usize,
+    /// Show long unchanged runs instead of collapsing them
+    expanded: bool,
+}
+
+/// Lines of context kept around each change in the edit preview
+const DIFF_CONTEXT_LINES: usize = 2;
+
+/// Unchanged runs longer than this are collapsed in the edit preview
+const DIFF_MAX_UNCHANGED: usize = 3;
+
+/// Rows the edit prompt needs besides the diff itself, header included
+const EDIT_PROMPT_CHROME: usize = 16;
+
+/// One row of the edit preview diff
+#[derive(Debug, PartialEq)]
+enum DiffRow {
+    Line(String, Color),
+    /// A run of unchanged lines far from any change
+    Collapsed(usize),
+}
+
+/// Rows of the edit preview: changes with context, long unchanged runs collapsed
+///
+/// With `expanded`, every line is shown. Nothing is ever dropped: how much of this
+/// fits on screen is up to the prompt, which scrolls.
+fn edit_preview_rows(diff: &str, expanded: bool) -> Vec<DiffRow> {
+    let is_change = |line: &str| line.starts_with('+') || line.starts_with('-');
+    let row = |line: &str| {
+        let color = if line.starts_with('+') {
+            Color::Green
+        } else if line.starts_with('-') {
+            Color::Red
+        } else {
+            Color::DarkGrey // Context lines in grey
+        };
+        DiffRow::Line(sanitize_text(line), color)
+    };
+
+    let all_lines: Vec<&str> = diff.lines().collect();
+    if expanded {
+        return all_lines.into_iter().map(row).collect();
+    }
+
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < all_lines.len() {
+        let start = i.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (i + DIFF_CONTEXT_LINES + 1).min(all_lines.len());
+        if all_lines[start..end].iter().any(|l| is_change(l)) {
+            rows.push(row(all_lines[i]));
+            i += 1;
+            continue;
+        }
+
+        // Unchanged lines up to the next change
+        let skip_count = all_lines[i..].iter().take_while(|l| !is_change(l)).count();
+        if skip_count > DIFF_MAX_UNCHANGED {
+            rows.push(DiffRow::Collapsed(skip_count));
+            i += skip_count;
+        } else {
+            rows.push(row(all_lines[i]));
+            i += 1;
+        }
+    }
+    rows
+}
+
+/// Braille spinner shown on the header line of a running tool
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// How often running tool headers are redrawn
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A tool call that has started but hasn't reported its result yet
+struct RunningTool {
+    id: String,
+    name: String,
+    started: Instant,
+    /// Screen row of the tool's header line, while it's known not to have moved
+    row: Option<u16>,
+}
+
+/// Header line of a running tool: spinner frame and whole seconds elapsed
+fn running_tool_header(name: &str, elapsed: Duration) -> String {
+    let frame = (elapsed.as_millis() / SPINNER_INTERVAL.as_millis()) as usize % SPINNER_FRAMES.len();
+    format!("[Tool: {}] {} Running... {}s", name, SPINNER_FRAMES[frame], elapsed.as_secs())
 }
 
 struct PermissionApprovalState {
     tool_name: String,
     operation_details: String,
+    risk: OperationRisk,
     suggested_pattern: String,
     response_tx: tokio::sync::oneshot::Sender<crate::agent::messages::PermissionResponse>,
     selected_option: usize,  // 0, 1, or 2
 }
 
+/// What the one-line session input modal is collecting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionInput {
+    Name,
+    /// Comma-separated tags; a leading `-` removes the tag
+    Tags,
+    Search,
+    TagFilter,
+}
+
+impl SessionInput {
+    /// Title and field label
+    fn prompt(self) -> (&'static str, &'static str) {
+        match self {
+            SessionInput::Name => ("Enter session name (Enter to confirm, Esc to cancel):", "Name: "),
+            SessionInput::Tags => ("Tags to add, comma-separated; prefix with - to remove (Esc to cancel):", "Tags: "),
+            SessionInput::Search => ("Search saved sessions (Enter to search, Esc to cancel):", "Search: "),
+            SessionInput::TagFilter => ("Show sessions with tag (Enter to list, Esc to cancel):", "Tag: "),
+        }
+    }
+}
+
+/// Split tag input into tags to add and tags to remove
+fn parse_tag_input(input: &str) -> (Vec<String>, Vec<String>) {
+    let mut add = Vec::new();
+    let mut remove = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        match tag.strip_prefix('-') {
+            Some(tag) if !tag.trim().is_empty() => remove.push(tag.trim().to_string()),
+            Some(_) => {}
+            None => add.push(tag.to_string()),
+        }
+    }
+    (add, remove)
+}
+
 pub struct App {
     input: String,
     cursor_position: usize,
@@ -293,6 +584,7 @@ pub struct App {
     session_list: Vec<crate::session::SessionInfo>,
     show_session_list: bool,
     session_list_selected: usize,
+    session_search: Option<(String, Vec<crate::session::SessionMatch>)>, // Search results shown instead of the session list
     input_needs_render: bool, // Track if input line needs re-rendering
     is_rendering_input: bool, // Guard flag to prevent concurrent input renders
     pending_edit_approval: Option<EditApprovalState>,
@@ -302,10 +594,13 @@ pub struct App {
     show_reasoning_submenu: bool, // NEW: Reasoning submenu display flag
     reasoning_submenu_selected: usize, // NEW: Selected reasoning level index
     show_context_submenu: bool,     // NEW: Context management submenu display flag
+    show_profile_submenu: bool,     // LLM profile picker display flag
+    profile_submenu_selected: usize, // Selected LLM profile index
     context_submenu_selected: usize, // NEW: Selected context submenu item index
     show_session_name_input: bool,  // NEW: Session name input modal flag
     session_name_input: String,     // NEW: Separate from main input
     session_name_cursor: usize,     // NEW: Cursor position for session name input
+    session_input: SessionInput,    // What the session name input is collecting
     token_stats: Option<TokenStats>, // NEW: Token usage stats for header display
     show_log_viewer: bool,          // NEW: JSONL log viewer modal flag
     log_entries: Vec<String>,       // NEW: Log entries to display
@@ -313,6 +608,17 @@ pub struct App {
     log_viewer_selected: usize,     // NEW: Selected log entry index
     last_key_time: Option<std::time::Instant>, // Paste detection: track last key time
     is_pasting: bool,               // Paste detection: true when rapid key events detected
+    bracketed_paste_seen: bool,     // Terminal delivers Event::Paste, so the timing heuristic is unneeded
+    syntax_highlighting: bool,      // Highlight fenced code blocks in assistant output
+    scrollback: Scrollback,         // Rendered transcript history for PageUp/PageDown
+    theme: Theme,                   // Colors for each UI role
+    last_assistant_text: Option<String>, // Most recent assistant message, for Ctrl+Y / Alt+Y
+    clipboard: ClipboardWriter,     // Copy target (falls back to a temp file without a clipboard)
+    llm_profiles: Vec<String>,      // Names of the configured LLM profiles
+    active_profile: Option<(String, String)>, // Active LLM profile name and model, for the header
+    todos: Vec<Todo>,               // Todo tool list, summarized in the header
+    running_tools: Vec<RunningTool>, // Tools awaiting a result, animated on their header lines
+    last_spinner_tick: Instant,     // When running tool headers were last redrawn
 }
 
 impl App {
@@ -329,6 +635,7 @@ impl App {
             session_list: Vec::new(),
             show_session_list: false,
             session_list_selected: 0,
+            session_search: None,
             input_needs_render: true, // Render on first loop
             is_rendering_input: false, // Not rendering initially
             pending_edit_approval: None,
@@ -338,19 +645,49 @@ impl App {
             show_reasoning_submenu: false, // NEW
             reasoning_submenu_selected: 1, // NEW: default to "medium" (index 1)
             show_context_submenu: false,   // NEW
+            show_profile_submenu: false,
+            profile_submenu_selected: 0,
             context_submenu_selected: 0,   // NEW
             show_session_name_input: false,   // NEW
             session_name_input: String::new(), // NEW
             session_name_cursor: 0,            // NEW
+            session_input: SessionInput::Name,
             token_stats: None,                 // NEW
             show_log_viewer: false,            // NEW
             log_entries: Vec::new(),           // NEW
             log_viewer_selected: 0,            // NEW
             last_key_time: None,               // Paste detection
             is_pasting: false,                 // Paste detection
+            bracketed_paste_seen: false,
+            syntax_highlighting: true,
+            scrollback: Scrollback::new(),
+            theme: Theme::default(),
+            last_assistant_text: None,
+            clipboard: ClipboardWriter::new(),
+            llm_profiles: Vec::new(),
+            active_profile: None,
+            todos: Vec::new(),
+            running_tools: Vec::new(),
+            last_spinner_tick: Instant::now(),
         }
     }
 
+    /// Use `theme` for all colored output
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    /// Set the LLM profiles offered in the menu and the one active at startup
+    pub fn set_llm_profiles(&mut self, profiles: Vec<String>, active: String, model: String) {
+        self.llm_profiles = profiles;
+        self.active_profile = Some((active, model));
+    }
+
+    /// Enable or disable syntax highlighting of fenced code blocks
+    pub fn set_syntax_highlighting(&mut self, enabled: bool) {
+        self.syntax_highlighting = enabled && should_color();
+    }
+
     fn char_to_byte_pos(&self, char_pos: usize) -> usize {
         self.input
             .char_indices()
@@ -367,19 +704,23 @@ impl App {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
 
+        // Pasted text arrives as a single Event::Paste instead of a burst of key events
+        execute!(stdout, EnableBracketedPaste)?;
+
         // Print welcome header
         self.print_header(&mut stdout)?;
 
         while !self.should_quit {
             // Handle UI updates from agent
             while let Ok(update) = self.ui_rx.try_recv() {
-                self.handle_ui_update(&mut stdout, update)?;
+                self.dispatch_ui_update(&mut stdout, update)?;
             }
 
             // Process ALL pending key events before rendering
             // This prevents rendering after each character during paste operations
             // Safety limits prevent infinite loops from paste bombs
             let mut had_input = false;
+            let mut resized = false;
             let mut events_processed = 0;
             let batch_start = Instant::now();
 
@@ -387,32 +728,44 @@ impl App {
                 && events_processed < MAX_BATCH_SIZE
                 && batch_start.elapsed() < Duration::from_millis(BATCH_TIMEOUT_MS)
             {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_input(&mut stdout, key).await?;
-                    had_input = true;
-                    events_processed += 1;
+                match event::read()? {
+                    Event::Key(key) => {
+                        self.handle_input(&mut stdout, key).await?;
+                        had_input = true;
+                        events_processed += 1;
+                    }
+                    Event::Paste(text) => {
+                        self.handle_paste(&text);
+                        had_input = true;
+                        events_processed += 1;
+                    }
+                    Event::Resize(_, _) => {
+                        // Dragging a window edge sends a burst of these; redraw once per batch
+                        resized = true;
+                        events_processed += 1;
+                    }
+                    _ => {}
                 }
             }
 
+            if resized {
+                self.handle_resize(&mut stdout)?;
+            }
+
             if events_processed >= MAX_BATCH_SIZE {
                 tracing::warn!("Hit max batch size ({}), possible paste bomb detected", MAX_BATCH_SIZE);
             }
 
             // Render input line only after all input processed
-            if !self.show_session_list
-                && !self.show_menu
-                && !self.show_reasoning_submenu
-                && !self.show_context_submenu
-                && !self.show_session_name_input
-                && !self.show_log_viewer
-                && self.pending_edit_approval.is_none()
-                && self.pending_permission_approval.is_none()
-                && self.input_needs_render
-            {
+            if self.showing_transcript() && self.input_needs_render {
                 self.render_input_line(&mut stdout)?;
                 self.input_needs_render = false;
             }
 
+            if self.last_spinner_tick.elapsed() >= SPINNER_INTERVAL {
+                self.tick_running_tools(&mut stdout)?;
+            }
+
             // Wait a bit if no input (don't busy-loop)
             if !had_input {
                 tokio::time::sleep(std::time::Duration::from_millis(16)).await;
@@ -420,6 +773,7 @@ impl App {
         }
 
         // Cleanup
+        execute!(stdout, DisableBracketedPaste)?;
         disable_raw_mode()?;
         writeln!(stdout)?;
         stdout.flush()?;
@@ -430,11 +784,20 @@ impl App {
     fn print_header(&self, stdout: &mut impl Write) -> io::Result<()> {
         queue!(
             stdout,
-            SetForegroundColor(PastelColors::HEADER),
+            SetForegroundColor(self.theme.header),
             Print("╔════════════════════════════════════════════════════════════════╗\r\n"),
             Print("║  Synthia v0.1.0                                                ║\r\n"),
         )?;
 
+        // Only worth a line when there's more than one profile to switch between
+        if let Some((name, model)) = self.active_profile.as_ref().filter(|_| self.llm_profiles.len() > 1) {
+            print_header_row(stdout, &format!("║  Profile: {} ({})", name, model))?;
+        }
+
+        if let Some(line) = todo_summary(&self.todos) {
+            print_header_row(stdout, &line)?;
+        }
+
         // Display token stats if available
         if let Some(stats) = &self.token_stats {
             let warning = if stats.current >= stats.threshold { " ⚠" } else { "" };
@@ -442,15 +805,7 @@ impl App {
                 "║  Context: {} / {} tokens ({:.0}%){}",
                 stats.current, stats.max, stats.usage_percent, warning
             );
-            // Pad to 64 chars (including the final ║)
-            let padding = 64_usize.saturating_sub(stats_line.chars().count() + 1);
-            queue!(
-                stdout,
-                Print(&stats_line),
-                Print(" ".repeat(padding)),
-                Print(" ║\r\n"),
-                Print("╠════════════════════════════════════════════════════════════════╣\r\n")
-            )?;
+            print_header_row(stdout, &stats_line)?;
         }
 
         queue!(
@@ -459,7 +814,233 @@ impl App {
             ResetColor,
             Print("\r\n"),
             SetForegroundColor(Color::DarkGrey),
-            Print("💡 Tip: Enter to send | Shift+Enter for newline | Ctrl+P for menu\r\n\r\n"),
+            Print("💡 Tip: Enter to send | Shift+Enter for newline | Ctrl+P for menu | Ctrl+Y copy | PgUp to scroll back\r\n\r\n"),
+            ResetColor
+        )?;
+        stdout.flush()
+    }
+
+    /// Print model reasoning dimmed and indented so it reads as an aside to the answer
+    fn render_reasoning(&self, stdout: &mut impl Write, text: &str) -> io::Result<()> {
+        let (width, _) = size()?;
+        let usable_width = (width as usize).saturating_sub(4);
+
+        queue!(stdout, SetForegroundColor(Color::DarkGrey), Print("Synthia (thinking):\r\n"))?;
+        for line in wrap_text(text, usable_width).lines() {
+            queue!(stdout, Print("  "), Print(line), Print("\r\n"))?;
+        }
+        queue!(stdout, ResetColor)?;
+        stdout.flush()
+    }
+
+    /// Route a UI update through the scrollback buffer
+    /// Transcript output is recorded for later review; while the user is scrolled up
+    /// it is only recorded (not printed) so the view stays put. Prompts and other
+    /// interactive updates snap back to the bottom first so they're never hidden.
+    fn dispatch_ui_update(&mut self, stdout: &mut impl Write, update: UIUpdate) -> io::Result<()> {
+        // Anything else printed may push running tools' header lines out of place
+        if !matches!(update, UIUpdate::ToolExecutionStarted { .. }) {
+            self.forget_tool_rows();
+        }
+
+        match update {
+            UIUpdate::ConversationCleared => {
+                self.scrollback.clear();
+                self.handle_ui_update(stdout, UIUpdate::ConversationCleared)
+            }
+            UIUpdate::TokenStatsUpdate(stats) if self.scrollback.is_scrolled() => {
+                // Header redraw clears the screen; defer it until the user returns to the bottom
+                self.token_stats = Some(stats);
+                Ok(())
+            }
+            update @ UIUpdate::TodosUpdated(_) => {
+                // Only stored; shown at the next header redraw, so no need to snap to the bottom
+                self.handle_ui_update(stdout, update)
+            }
+            UIUpdate::LlmProfileChanged { name, model } if self.scrollback.is_scrolled() => {
+                self.active_profile = Some((name, model));
+                Ok(())
+            }
+            update if is_transcript_update(&update) => {
+                let mut scrollback = std::mem::take(&mut self.scrollback);
+                let result = if scrollback.is_scrolled() {
+                    let mut hidden = io::sink();
+                    let mut recorder = scrollback.recorder(&mut hidden);
+                    self.handle_ui_update(&mut recorder, update)
+                } else {
+                    let mut recorder = scrollback.recorder(stdout);
+                    self.handle_ui_update(&mut recorder, update)
+                };
+                self.scrollback = scrollback;
+
+                if self.scrollback.is_scrolled() {
+                    // Refresh the status line so the "new output" count stays current
+                    self.render_scrollback(stdout)?;
+                }
+                result
+            }
+            update => {
+                if self.scrollback.is_scrolled() {
+                    self.snap_to_bottom(stdout)?;
+                }
+                self.handle_ui_update(stdout, update)
+            }
+        }
+    }
+
+    /// Redraw after a terminal resize so the header and input line match the new width
+    /// Printed output isn't reflowed, but any open prompt or overlay is drawn again
+    fn handle_resize(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        self.forget_tool_rows();
+        if self.scrollback.is_scrolled() {
+            return self.render_scrollback(stdout);
+        }
+
+        execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        self.print_header(stdout)?;
+
+        if let Some(state) = &self.pending_edit_approval {
+            self.render_edit_approval_prompt(stdout, state)?;
+        } else if self.pending_permission_approval.is_some() {
+            self.render_permission_prompt(stdout)?;
+        } else if self.show_session_list {
+            self.render_session_list(stdout)?;
+        } else if self.show_reasoning_submenu {
+            self.render_reasoning_submenu(stdout)?;
+        } else if self.show_context_submenu {
+            self.render_context_submenu(stdout)?;
+        } else if self.show_profile_submenu {
+            self.render_profile_submenu(stdout)?;
+        } else if self.show_session_name_input {
+            self.render_session_name_input(stdout)?;
+        } else if self.show_log_viewer {
+            self.render_log_viewer(stdout)?;
+        } else if self.show_menu {
+            self.render_menu(stdout)?;
+        }
+
+        self.input_needs_render = true;
+        Ok(())
+    }
+
+    /// Whether the conversation is on screen, with no prompt, overlay or scrollback over it
+    fn showing_transcript(&self) -> bool {
+        !self.show_session_list
+            && !self.show_menu
+            && !self.show_reasoning_submenu
+            && !self.show_context_submenu
+            && !self.show_profile_submenu
+            && !self.show_session_name_input
+            && !self.show_log_viewer
+            && self.pending_edit_approval.is_none()
+            && self.pending_permission_approval.is_none()
+            && !self.scrollback.is_scrolled()
+    }
+
+    /// Stop animating running tools whose header lines may have moved or been cleared
+    fn forget_tool_rows(&mut self) {
+        for tool in &mut self.running_tools {
+            tool.row = None;
+        }
+    }
+
+    /// Advance the spinner and elapsed time on each running tool's header line
+    fn tick_running_tools(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        self.last_spinner_tick = Instant::now();
+        if !self.showing_transcript() {
+            self.forget_tool_rows();
+            return Ok(());
+        }
+        if self.running_tools.iter().all(|tool| tool.row.is_none()) {
+            return Ok(());
+        }
+
+        queue!(stdout, cursor::SavePosition)?;
+        for tool in &self.running_tools {
+            if let Some(row) = tool.row {
+                queue!(
+                    stdout,
+                    cursor::MoveTo(0, row),
+                    Clear(ClearType::CurrentLine),
+                    SetForegroundColor(self.theme.tool),
+                    Print(running_tool_header(&tool.name, tool.started.elapsed())),
+                    ResetColor
+                )?;
+            }
+        }
+        queue!(stdout, cursor::RestorePosition)?;
+        stdout.flush()
+    }
+
+    /// Number of lines one PageUp/PageDown moves
+    fn scroll_page_size() -> io::Result<usize> {
+        let (_, height) = size()?;
+        Ok((height as usize).saturating_sub(2).max(1))
+    }
+
+    fn scroll_up(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        if self.scrollback.is_empty() {
+            return Ok(());
+        }
+        self.scrollback.scroll_up(Self::scroll_page_size()?);
+        self.render_scrollback(stdout)
+    }
+
+    fn scroll_down(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        self.scrollback.scroll_down(Self::scroll_page_size()?);
+        if self.scrollback.is_scrolled() {
+            self.render_scrollback(stdout)
+        } else {
+            self.snap_to_bottom(stdout)
+        }
+    }
+
+    /// Leave scroll mode: redraw the most recent output and re-enable input
+    fn snap_to_bottom(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        self.scrollback.snap_to_bottom();
+        self.forget_tool_rows();
+
+        let (width, height) = size()?;
+        // Leave a row for the input line
+        let (_, lines) = self.scrollback.window((height as usize).saturating_sub(1), width as usize);
+
+        queue!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        for line in lines {
+            queue!(stdout, Print("\r"), Print(line), ResetColor, Print("\r\n"))?;
+        }
+        stdout.flush()?;
+
+        self.input_needs_render = true;
+        Ok(())
+    }
+
+    /// Draw the scrolled-back window with a status line in place of the input prompt
+    fn render_scrollback(&self, stdout: &mut impl Write) -> io::Result<()> {
+        let (width, height) = size()?;
+        let rows = (height as usize).saturating_sub(1);
+        let (start, lines) = self.scrollback.window(rows, width as usize);
+
+        queue!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        for line in lines {
+            queue!(stdout, Print("\r"), Print(line), ResetColor, Print("\r\n"))?;
+        }
+
+        let mut status = format!(
+            "-- Scrollback: lines {}-{} of {} | PgUp/PgDn scroll | Esc return --",
+            start + 1,
+            start + lines.len(),
+            self.scrollback.len()
+        );
+        if self.scrollback.unseen() > 0 {
+            status.push_str(&format!(" ({} new lines below)", self.scrollback.unseen()));
+        }
+
+        queue!(
+            stdout,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            Clear(ClearType::CurrentLine),
+            SetForegroundColor(Color::DarkGrey),
+            Print(status),
             ResetColor
         )?;
         stdout.flush()
@@ -470,6 +1051,7 @@ impl App {
             UIUpdate::AssistantText(text) => {
                 self.clear_input_line(stdout)?;
                 self.is_streaming = false;
+                self.last_assistant_text = Some(text.clone());
 
                 // Get terminal width and wrap text
                 let (width, _) = size()?;
@@ -478,15 +1060,19 @@ impl App {
 
                 execute!(
                     stdout,
-                    SetForegroundColor(PastelColors::ASSISTANT),
+                    SetForegroundColor(self.theme.assistant),
                     Print("Synthia: "),
                     ResetColor
                 )?;
 
                 // Print each line with markdown rendering
-                for line in wrapped.lines() {
-                    render_markdown_line(stdout, line)?;
-                }
+                render_markdown_text(
+                    stdout,
+                    &wrapped,
+                    usable_width,
+                    self.syntax_highlighting,
+                    self.theme.tool,
+                )?;
                 execute!(stdout, Print("\r\n"))?;
                 stdout.flush()?;
                 self.input_needs_render = true;
@@ -503,13 +1089,18 @@ impl App {
                 )?;
                 stdout.flush()?;
             }
+            UIUpdate::AssistantReasoning(text) => {
+                self.clear_input_line(stdout)?;
+                self.render_reasoning(stdout, &text)?;
+                self.input_needs_render = true;
+            }
             UIUpdate::AssistantTextDelta(delta) => {
                 if !self.is_streaming {
                     // First chunk - clear input line and print "Thinking..." indicator
                     self.clear_input_line(stdout)?;
                     queue!(
                         stdout,
-                        SetForegroundColor(PastelColors::ASSISTANT),
+                        SetForegroundColor(self.theme.assistant),
                         Print("Synthia: "),
                         SetForegroundColor(Color::DarkGrey),
                         Print("Thinking..."),
@@ -524,7 +1115,7 @@ impl App {
                 // We'll print the wrapped version at the end
                 self.streaming_buffer.push_str(&delta);
             }
-            UIUpdate::ToolExecutionStarted { name, id: _ } => {
+            UIUpdate::ToolExecutionStarted { name, id } => {
                 self.clear_input_line(stdout)?;
                 self.is_streaming = false;
 
@@ -534,29 +1125,47 @@ impl App {
                     writeln!(stdout)?;
                 }
 
+                // The newline after a header on the bottom row scrolls the screen up a line
+                let (_, height) = size()?;
+                let (_, mut row) = cursor::position()?;
+                if row + 1 >= height {
+                    row = row.saturating_sub(1);
+                    for tool in &mut self.running_tools {
+                        tool.row = tool.row.and_then(|r| r.checked_sub(1));
+                    }
+                }
+
                 queue!(
                     stdout,
-                    SetForegroundColor(PastelColors::TOOL),
-                    Print(format!("[Tool: {}] ⏳ Running...\n", name)),
+                    SetForegroundColor(self.theme.tool),
+                    Print(format!("{}\n", running_tool_header(&name, Duration::ZERO))),
                     ResetColor
                 )?;
                 stdout.flush()?;
+
+                self.running_tools.push(RunningTool {
+                    id,
+                    name,
+                    started: Instant::now(),
+                    row: Some(row),
+                });
             }
             UIUpdate::ToolResult {
                 name,
-                id: _,
+                id,
                 input,
                 output,
                 is_error,
                 duration_ms,
             } => {
                 self.clear_input_line(stdout)?;
+                self.running_tools.retain(|tool| tool.id != id);
 
                 let status_icon = if is_error { "✗" } else { "✓" };
-                let color = if is_error { PastelColors::ERROR } else { PastelColors::SUCCESS };
+                let color = if is_error { self.theme.error } else { self.theme.success };
 
                 // Write tool header line atomically (no queue/flush mixing)
-                write!(stdout, "{}", SetForegroundColor(PastelColors::TOOL))?;
+                write!(stdout, "{}", SetForegroundColor(self.theme.tool))?;
                 write!(stdout, "[Tool: {}] ", name)?;
                 write!(stdout, "{}", SetForegroundColor(color))?;
                 write!(stdout, "{} ", status_icon)?;
@@ -609,7 +1218,7 @@ impl App {
 
                 queue!(
                     stdout,
-                    SetForegroundColor(PastelColors::ERROR),
+                    SetForegroundColor(self.theme.error),
                     Print(format!("Error: {}\n", err)),
                     ResetColor
                 )?;
@@ -624,29 +1233,40 @@ impl App {
                     let (width, _) = size()?;
                     let usable_width = (width as usize).saturating_sub(10); // -10 for "Synthia: " prefix
 
+                    // Inline <think> blocks are shown dimmed, apart from the answer
+                    let (thinking, answer) = split_think_tags(&self.streaming_buffer);
+
                     // Wrap the accumulated text
-                    let wrapped = wrap_text(&self.streaming_buffer, usable_width);
+                    let wrapped = wrap_text(&answer, usable_width);
 
                     // Clear the unwrapped streaming output
                     self.clear_input_line(stdout)?;
+                    if let Some(thinking) = &thinking {
+                        self.render_reasoning(stdout, thinking)?;
+                    }
 
                     // Re-print with proper wrapping
                     // Print line-by-line to ensure proper carriage returns
                     execute!(
                         stdout,
-                        SetForegroundColor(PastelColors::ASSISTANT),
+                        SetForegroundColor(self.theme.assistant),
                         Print("Synthia: "),
                         ResetColor
                     )?;
 
                     // Print each line with markdown rendering
-                    for line in wrapped.lines() {
-                        render_markdown_line(stdout, line)?;
-                    }
+                    render_markdown_text(
+                        stdout,
+                        &wrapped,
+                        usable_width,
+                        self.syntax_highlighting,
+                        self.theme.tool,
+                    )?;
                     execute!(stdout, Print("\r\n"))?;
 
                     self.is_streaming = false;
                     self.streaming_buffer.clear();
+                    self.last_assistant_text = Some(answer);
                     stdout.flush()?;
                     self.input_needs_render = true;
                 }
@@ -654,9 +1274,9 @@ impl App {
                 // Display token usage stats after turn completes
                 if let Some(stats) = &self.token_stats {
                     let color = if stats.current >= stats.threshold {
-                        PastelColors::ERROR
+                        self.theme.error
                     } else if stats.usage_percent > 60.0 {
-                        PastelColors::TOOL
+                        self.theme.tool
                     } else {
                         Color::DarkGrey
                     };
@@ -677,7 +1297,7 @@ impl App {
 
                 queue!(
                     stdout,
-                    SetForegroundColor(PastelColors::TOOL),
+                    SetForegroundColor(self.theme.tool),
                     Print(format!("[Session saved: {}]\n", &session_id[..session_id.len().min(20)])),
                     ResetColor
                 )?;
@@ -687,7 +1307,18 @@ impl App {
                 self.current_session_id = Some(session_id);
                 self.show_session_list = false;
             }
+            UIUpdate::SessionSearchResults { query, matches } => {
+                if matches.is_empty() {
+                    print_colored_line(stdout, &format!("[No saved sessions mention \"{}\"]", query), self.theme.tool)?;
+                } else {
+                    self.session_search = Some((query, matches));
+                    self.show_session_list = true;
+                    self.session_list_selected = 0;
+                    self.render_session_list(stdout)?;
+                }
+            }
             UIUpdate::SessionList { sessions } => {
+                self.session_search = None;
                 self.session_list = sessions;
                 self.show_session_list = true;
                 self.session_list_selected = 0;
@@ -708,17 +1339,18 @@ impl App {
             } => {
                 self.clear_input_line(stdout)?;
 
-                // Store approval state with channel
-                self.pending_edit_approval = Some(EditApprovalState {
-                    file_path: file_path.clone(),
+                // Store approval state with channel, then show the diff preview
+                let state = EditApprovalState {
+                    file_path,
                     old_string,
                     new_string,
-                    diff: diff.clone(),
+                    diff,
                     response_tx,
-                });
-
-                // Show diff preview
-                self.render_edit_approval_prompt(stdout, &file_path, &diff)?;
+                    scroll: 0,
+                    expanded: false,
+                };
+                self.render_edit_approval_prompt(stdout, &state)?;
+                self.pending_edit_approval = Some(state);
             }
             UIUpdate::MenuDisplayRequested => {
                 // Menu display is triggered by Ctrl+M in handle_input, not via UIUpdate
@@ -726,7 +1358,7 @@ impl App {
             }
             UIUpdate::SystemMessage(msg) => {
                 self.clear_input_line(stdout)?;
-                print_colored_line(stdout, &format!("[System] {}", msg), PastelColors::TOOL)?;
+                print_colored_line(stdout, &format!("[System] {}", msg), self.theme.tool)?;
                 stdout.flush()?;
                 self.input_needs_render = true;
             }
@@ -738,9 +1370,21 @@ impl App {
                 stdout.flush()?;
                 self.input_needs_render = true;
             }
+            UIUpdate::TodosUpdated(todos) => {
+                self.todos = todos;
+            }
+            UIUpdate::LlmProfileChanged { name, model } => {
+                let message = format!("Switched to LLM profile: {} ({})", name, model);
+                self.active_profile = Some((name, model));
+                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                self.print_header(stdout)?;
+                print_colored_line(stdout, &message, self.theme.success)?;
+                self.input_needs_render = true;
+            }
             UIUpdate::PermissionPrompt {
                 tool_name,
                 operation_details,
+                risk,
                 suggested_pattern,
                 response_tx,
             } => {
@@ -748,6 +1392,7 @@ impl App {
                 self.pending_permission_approval = Some(PermissionApprovalState {
                     tool_name,
                     operation_details,
+                    risk,
                     suggested_pattern,
                     response_tx,
                     selected_option: 0,  // Default to first option
@@ -765,7 +1410,7 @@ impl App {
                 // Format as informational message
                 queue!(
                     stdout,
-                    SetForegroundColor(PastelColors::SUCCESS),
+                    SetForegroundColor(self.theme.success),
                     Print(format!("✓ Auto-approved: {} for {}\r\n", tool_name, file_path)),
                     ResetColor,
                     SetForegroundColor(Color::DarkGrey),
@@ -775,9 +1420,9 @@ impl App {
                 // Show diff with proper formatting
                 for line in diff.lines() {
                     let color = if line.starts_with('+') {
-                        PastelColors::SUCCESS
+                        self.theme.success
                     } else if line.starts_with('-') {
-                        PastelColors::ERROR
+                        self.theme.error
                     } else {
                         Color::DarkGrey
                     };
@@ -831,7 +1476,7 @@ impl App {
             stdout,
             cursor::MoveTo(0, cursor_y),
             Clear(ClearType::FromCursorDown),
-            SetForegroundColor(PastelColors::SUCCESS),
+            SetForegroundColor(self.theme.success),
             Print("You: "),
             ResetColor
         )?;
@@ -902,9 +1547,39 @@ impl App {
         result
     }
 
+    /// Entries in the session picker: search results when searching, else all sessions
+    fn session_list_len(&self) -> usize {
+        match &self.session_search {
+            Some((_, matches)) => matches.len(),
+            None => self.session_list.len(),
+        }
+    }
+
     fn render_session_list(&self, stdout: &mut impl Write) -> io::Result<()> {
         self.clear_input_line(stdout)?;
 
+        if let Some((query, matches)) = &self.session_search {
+            write!(stdout, "\r\n=== Sessions mentioning \"{}\" (↑/↓ navigate | Enter load | Esc cancel) ===\r\n", query)?;
+            for (idx, found) in matches.iter().enumerate() {
+                let selected = if idx == self.session_list_selected { ">" } else { " " };
+                let timestamp = chrono::DateTime::from_timestamp_millis(found.last_modified)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "Unknown".to_string());
+                let display_name = found.name.clone().unwrap_or_else(|| found.id.clone());
+
+                if idx == self.session_list_selected {
+                    write!(stdout, "{}", SetForegroundColor(self.theme.assistant))?;
+                }
+                write!(stdout, "{} {} - {}\r\n", selected, timestamp, display_name)?;
+                if idx == self.session_list_selected {
+                    write!(stdout, "{}", ResetColor)?;
+                }
+                write!(stdout, "{}    {}{}\r\n", SetForegroundColor(Color::DarkGrey), found.snippet, ResetColor)?;
+            }
+            write!(stdout, "\r\n")?;
+            return stdout.flush();
+        }
+
         write!(stdout, "\r\n=== Load Session (↑/↓ navigate | Enter select | Esc cancel) ===\r\n")?;
 
         for (idx, session) in self.session_list.iter().enumerate() {
@@ -914,13 +1589,16 @@ impl App {
                 .unwrap_or_else(|| "Unknown".to_string());
 
             if idx == self.session_list_selected {
-                write!(stdout, "{}", SetForegroundColor(PastelColors::ASSISTANT))?;
+                write!(stdout, "{}", SetForegroundColor(self.theme.assistant))?;
             }
 
             // Display session name if available, otherwise just show ID
-            let display_name = session.name.as_ref()
+            let mut display_name = session.name.as_ref()
                 .map(|n| format!("{} ({})", n, &session.id[..session.id.len().min(10)]))
                 .unwrap_or_else(|| session.id[..session.id.len().min(30)].to_string());
+            if !session.tags.is_empty() {
+                display_name.push_str(&format!(" [{}]", session.tags.join(", ")));
+            }
 
             write!(
                 stdout,
@@ -943,10 +1621,16 @@ impl App {
     fn render_permission_prompt(&self, stdout: &mut impl Write) -> io::Result<()> {
         if let Some(state) = &self.pending_permission_approval {
             // Top border
-            print_colored_line(stdout, "┌─ Permission Required ─────────────────────────────────┐", PastelColors::TOOL)?;
+            print_colored_line(stdout, "┌─ Permission Required ─────────────────────────────────┐", self.theme.tool)?;
 
-            // Tool name
+            // Tool name and what it could change
             print_line(stdout, &format!("│ Tool: {}", state.tool_name))?;
+            let (icon, color) = match state.risk {
+                OperationRisk::ReadOnly => ("●", self.theme.success),
+                OperationRisk::Network | OperationRisk::FileWrite => ("▲", Color::Yellow),
+                OperationRisk::ShellExec => ("■", self.theme.error),
+            };
+            print_bordered_line(stdout, &format!("Risk: {} {}", icon, state.risk.label()), color)?;
             print_line(stdout, "│")?;
 
             // Operation details (multi-line support)
@@ -973,28 +1657,28 @@ impl App {
             queue!(
                 stdout,
                 Print("│ "),
-                SetForegroundColor(PastelColors::ASSISTANT),
+                SetForegroundColor(self.theme.assistant),
                 Print("(↑/↓ or 1-3 to select, Enter to confirm, Esc to cancel)"),
                 ResetColor,
             )?;
             print_line(stdout, "")?;
 
             // Bottom border
-            print_colored_line(stdout, "└───────────────────────────────────────────────────────┘", PastelColors::TOOL)?;
+            print_colored_line(stdout, "└───────────────────────────────────────────────────────┘", self.theme.tool)?;
 
             stdout.flush()?;
         }
         Ok(())
     }
 
-    fn render_edit_approval_prompt(&self, stdout: &mut impl Write, file_path: &str, diff: &str) -> io::Result<()> {
+    fn render_edit_approval_prompt(&self, stdout: &mut impl Write, state: &EditApprovalState) -> io::Result<()> {
         // Top border
-        print_colored_line(stdout, "┌─ Edit Preview ────────────────────────────────────────┐", PastelColors::TOOL)?;
+        print_colored_line(stdout, "┌─ Edit Preview ────────────────────────────────────────┐", self.theme.tool)?;
 
         // Count changes
         let mut additions = 0;
         let mut deletions = 0;
-        for line in diff.lines() {
+        for line in state.diff.lines() {
             if line.starts_with('+') {
                 additions += 1;
             } else if line.starts_with('-') {
@@ -1003,105 +1687,71 @@ impl App {
         }
 
         // File path and change summary
-        print_line(stdout, &format!("│ File: {}", file_path))?;
+        print_line(stdout, &format!("│ File: {}", state.file_path))?;
         print_line(stdout, &format!("│ Changes: +{} lines, -{} lines", additions, deletions))?;
         print_line(stdout, "│")?;
 
-        // Focused diff: show only changed sections with context
-        let context_lines = 2; // Show 2 lines of context around changes
-        let max_consecutive_unchanged = 3; // Collapse if more than 3 unchanged lines in a row
-
-        let all_lines: Vec<&str> = diff.lines().collect();
-        let mut i = 0;
-        let mut shown_lines = 0;
-        let max_total_lines = 100; // Absolute max to prevent overwhelming output
-
-        while i < all_lines.len() && shown_lines < max_total_lines {
-            let line = all_lines[i];
-
-            // Check if this line or nearby lines have changes
-            let has_nearby_change = {
-                let start = i.saturating_sub(context_lines);
-                let end = (i + context_lines + 1).min(all_lines.len());
-                all_lines[start..end].iter().any(|l| l.starts_with('+') || l.starts_with('-'))
-            };
-
-            if has_nearby_change {
-                // Show this line (it's a change or near a change)
-                let color = if line.starts_with('+') {
-                    Color::Green
-                } else if line.starts_with('-') {
-                    Color::Red
-                } else {
-                    Color::DarkGrey // Context lines in grey
-                };
-
-                let sanitized = sanitize_text(line);
-                print_bordered_line(stdout, &sanitized, color)?;
-                shown_lines += 1;
-                i += 1;
-            } else {
-                // Look ahead to find the next change
-                let mut skip_count = 0;
-                let mut next_change_idx = i;
-                while next_change_idx < all_lines.len() {
-                    if all_lines[next_change_idx].starts_with('+') || all_lines[next_change_idx].starts_with('-') {
-                        break;
-                    }
-                    skip_count += 1;
-                    next_change_idx += 1;
-                }
-
-                if skip_count > max_consecutive_unchanged {
-                    // Collapse this section
-                    print_line(stdout, &format!("│ ... ({} unchanged lines) ...", skip_count))?;
-                    shown_lines += 1;
-                    i = next_change_idx;
-                } else {
-                    // Just show this line
-                    let sanitized = sanitize_text(line);
-                    print_bordered_line(stdout, &sanitized, Color::DarkGrey)?;
-                    shown_lines += 1;
-                    i += 1;
-                }
+        // Focused diff, scrolled to fit the terminal
+        let rows = edit_preview_rows(&state.diff, state.expanded);
+        let viewport = Self::edit_preview_height()?;
+        let start = state.scroll.min(rows.len().saturating_sub(viewport));
+        for row in rows.iter().skip(start).take(viewport) {
+            match row {
+                DiffRow::Line(text, color) => print_bordered_line(stdout, text, *color)?,
+                DiffRow::Collapsed(count) => print_line(stdout, &format!("│ ... ({} unchanged lines) ...", count))?,
             }
         }
 
-        if i < all_lines.len() {
-            print_line(stdout, &format!("│ ... ({} more lines not shown) ...", all_lines.len() - i))?;
+        if rows.len() > viewport {
+            let end = (start + viewport).min(rows.len());
+            let position = format!("Lines {}-{} of {} | ↑/↓ PgUp/PgDn scroll", start + 1, end, rows.len());
+            print_bordered_line(stdout, &position, Color::DarkGrey)?;
         }
 
         print_line(stdout, "│")?;
 
         // Accept/Reject prompt
-        print_bordered_line(stdout, "[A]ccept  [D]on't ask for this file  [R]eject", PastelColors::ASSISTANT)?;
+        let toggle = if state.expanded { "[E] Collapse unchanged" } else { "[E]xpand unchanged" };
+        print_bordered_line(
+            stdout,
+            &format!("[A]ccept  [D]on't ask for this file  [R]eject  {}", toggle),
+            self.theme.assistant,
+        )?;
 
         // Bottom border
-        print_colored_line(stdout, "└───────────────────────────────────────────────────────┘", PastelColors::TOOL)?;
+        print_colored_line(stdout, "└───────────────────────────────────────────────────────┘", self.theme.tool)?;
 
         Ok(())
     }
 
+    /// Number of diff rows the edit prompt shows at once
+    fn edit_preview_height() -> io::Result<usize> {
+        let (_, height) = size()?;
+        Ok((height as usize).saturating_sub(EDIT_PROMPT_CHROME).max(5))
+    }
+
+    /// Move the edit preview by `delta` rows and redraw it
+    fn scroll_edit_preview(&mut self, stdout: &mut impl Write, state: &mut EditApprovalState, delta: isize) -> io::Result<()> {
+        let rows = edit_preview_rows(&state.diff, state.expanded).len();
+        let max_scroll = rows.saturating_sub(Self::edit_preview_height()?);
+        state.scroll = state.scroll.saturating_add_signed(delta).min(max_scroll);
+
+        execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        self.print_header(stdout)?;
+        self.render_edit_approval_prompt(stdout, state)?;
+        stdout.flush()
+    }
+
     fn render_menu(&self, stdout: &mut impl Write) -> io::Result<()> {
         execute!(stdout, Clear(ClearType::FromCursorDown))?;
 
         execute!(stdout, Print("\r\n=== Synthia Menu (↑/↓ navigate | Enter select | Esc cancel) ===\n"))?;
 
-        let menu_items = vec![
-            "Set Session Name",
-            "Save Session",
-            "Load Session",
-            "New Session",
-            "Set Reasoning Level",
-            "Context Management",
-            "Toggle Mode (Coming Soon)",
-        ];
-
-        for (idx, item) in menu_items.iter().enumerate() {
+        for (idx, item) in MENU_ITEMS.iter().enumerate() {
             let selected = if idx == self.menu_selected { ">" } else { " " };
 
             if idx == self.menu_selected {
-                queue!(stdout, SetForegroundColor(PastelColors::ASSISTANT))?;
+                queue!(stdout, SetForegroundColor(self.theme.assistant))?;
             }
 
             // Dim "Coming Soon" items
@@ -1134,6 +1784,18 @@ impl App {
         self.render_context_submenu(stdout)
     }
 
+    fn show_profile_submenu(&mut self, stdout: &mut impl Write) -> io::Result<()> {
+        self.show_menu = false;
+        self.show_profile_submenu = true;
+        // Start on the active profile
+        self.profile_submenu_selected = self
+            .active_profile
+            .as_ref()
+            .and_then(|(name, _)| self.llm_profiles.iter().position(|p| p == name))
+            .unwrap_or(0);
+        self.render_profile_submenu(stdout)
+    }
+
     fn render_reasoning_submenu(&self, stdout: &mut impl Write) -> io::Result<()> {
         execute!(stdout, Clear(ClearType::FromCursorDown))?;
 
@@ -1149,7 +1811,7 @@ impl App {
             let selected = if idx == self.reasoning_submenu_selected { ">" } else { " " };
 
             if idx == self.reasoning_submenu_selected {
-                queue!(stdout, SetForegroundColor(PastelColors::ASSISTANT))?;
+                queue!(stdout, SetForegroundColor(self.theme.assistant))?;
             }
 
             writeln!(stdout, "{} {} - {}", selected, level, desc)?;
@@ -1163,6 +1825,31 @@ impl App {
         stdout.flush()
     }
 
+    fn render_profile_submenu(&self, stdout: &mut impl Write) -> io::Result<()> {
+        execute!(stdout, Clear(ClearType::FromCursorDown))?;
+
+        write!(stdout, "\r\n=== Switch LLM Profile (↑/↓ navigate | Enter select | Esc cancel) ===\r\n")?;
+
+        let active = self.active_profile.as_ref().map(|(name, _)| name.as_str());
+        for (idx, name) in self.llm_profiles.iter().enumerate() {
+            let selected = if idx == self.profile_submenu_selected { ">" } else { " " };
+            let marker = if Some(name.as_str()) == active { " (active)" } else { "" };
+
+            if idx == self.profile_submenu_selected {
+                queue!(stdout, SetForegroundColor(self.theme.assistant))?;
+            }
+
+            write!(stdout, "{} {}{}\r\n", selected, name, marker)?;
+
+            if idx == self.profile_submenu_selected {
+                queue!(stdout, ResetColor)?;
+            }
+        }
+
+        write!(stdout, "\r\n")?;
+        stdout.flush()
+    }
+
     fn render_context_submenu(&self, stdout: &mut impl Write) -> io::Result<()> {
         execute!(stdout, Clear(ClearType::FromCursorDown))?;
 
@@ -1178,7 +1865,7 @@ impl App {
             let selected = if idx == self.context_submenu_selected { ">" } else { " " };
 
             if idx == self.context_submenu_selected {
-                write!(stdout, "{}", SetForegroundColor(PastelColors::ASSISTANT))?;
+                write!(stdout, "{}", SetForegroundColor(self.theme.assistant))?;
             }
 
             write!(stdout, "{} {} - {}\r\n", selected, option, desc)?;
@@ -1237,18 +1924,19 @@ impl App {
     fn render_session_name_input(&self, stdout: &mut impl Write) -> io::Result<()> {
         execute!(stdout, Clear(ClearType::FromCursorDown))?;
 
-        print_colored_line(stdout, "Enter session name (Enter to confirm, Esc to cancel):", Color::Yellow)?;
+        let (title, label) = self.session_input.prompt();
+        print_colored_line(stdout, title, Color::Yellow)?;
 
         queue!(
             stdout,
-            SetForegroundColor(PastelColors::SUCCESS),
-            Print("Name: "),
+            SetForegroundColor(self.theme.success),
+            Print(label),
             ResetColor,
             Print(&self.session_name_input),
         )?;
 
         // Position cursor
-        let cursor_x = 6 + self.session_name_cursor; // "Name: " = 6 chars
+        let cursor_x = label.len() + self.session_name_cursor;
         queue!(stdout, cursor::MoveTo(cursor_x as u16, 3))?;
 
         stdout.flush()
@@ -1264,49 +1952,78 @@ impl App {
         state.suggested_pattern.clone()
     }
 
+    /// Open the one-line session input modal
+    fn open_session_input(&mut self, stdout: &mut impl Write, kind: SessionInput) -> io::Result<()> {
+        self.show_menu = false;
+        self.show_session_name_input = true;
+        self.session_input = kind;
+        self.session_name_input.clear();
+        self.session_name_cursor = 0;
+
+        execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+        self.print_header(stdout)?;
+        self.render_session_name_input(stdout)
+    }
+
     async fn handle_menu_selection(&mut self, stdout: &mut impl Write) -> anyhow::Result<()> {
         match self.menu_selected {
             0 => {
                 // Set Session Name
-                self.show_menu = false;
-                self.show_session_name_input = true;
-                self.session_name_input.clear();
-                self.session_name_cursor = 0;
-
-                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
-                self.print_header(stdout)?;
-                self.render_session_name_input(stdout)?;
+                self.open_session_input(stdout, SessionInput::Name)?;
             }
             1 => {
+                // Tag Session
+                self.open_session_input(stdout, SessionInput::Tags)?;
+            }
+            2 => {
                 // Save Session
                 self.cmd_tx.send(Command::SaveSession).await?;
                 self.show_menu = false;
                 execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
                 self.print_header(stdout)?;
             }
-            2 => {
+            3 => {
                 // Load Session - show session list
                 self.cmd_tx.send(Command::ListSessions).await?;
                 self.show_menu = false;
                 execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
                 self.print_header(stdout)?;
             }
-            3 => {
+            4 => {
+                // Search Sessions - ask for a query, then show matching sessions
+                self.open_session_input(stdout, SessionInput::Search)?;
+            }
+            5 => {
+                // Sessions by Tag - ask for a tag, then show the sessions carrying it
+                self.open_session_input(stdout, SessionInput::TagFilter)?;
+            }
+            6 => {
                 // New Session
                 self.cmd_tx.send(Command::NewSession).await?;
                 self.show_menu = false;
                 execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
                 self.print_header(stdout)?;
             }
-            4 => {
+            7 => {
+                // Fork Session - continue in a copy, keeping the current session as it is
+                self.cmd_tx.send(Command::ForkSession(None)).await?;
+                self.show_menu = false;
+                execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                self.print_header(stdout)?;
+            }
+            8 => {
                 // Set Reasoning Level - show submenu
                 self.show_reasoning_submenu(stdout)?;
             }
-            5 => {
+            9 => {
                 // Context Management - show submenu
                 self.show_context_submenu(stdout)?;
             }
-            6 => {
+            10 => {
+                // Switch LLM Profile - show submenu
+                self.show_profile_submenu(stdout)?;
+            }
+            11 => {
                 // Toggle Mode (Coming Soon) - do nothing
                 self.show_menu = false;
                 execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
@@ -1318,12 +2035,70 @@ impl App {
         Ok(())
     }
 
+    /// Copy the last assistant message (or just its last code block) and report where it went
+    fn copy_last_assistant_output(&mut self, stdout: &mut impl Write, code_only: bool) -> io::Result<()> {
+        let (content, what) = match &self.last_assistant_text {
+            Some(text) if code_only => (last_code_block(text), "code block"),
+            Some(text) => (Some(text.clone()), "assistant message"),
+            None => (None, if code_only { "code block" } else { "assistant message" }),
+        };
+
+        self.clear_input_line(stdout)?;
+        let (message, color) = match content {
+            None => (format!("[No {} to copy yet]", what), self.theme.tool),
+            Some(content) => match self.clipboard.copy(&content) {
+                Ok(CopyDestination::Clipboard) => {
+                    (format!("[Copied {} to clipboard]", what), self.theme.success)
+                }
+                Ok(CopyDestination::File(path)) => (
+                    format!("[No clipboard available; saved {} to {}]", what, path.display()),
+                    self.theme.tool,
+                ),
+                Err(e) => (format!("[Failed to copy {}: {}]", what, e), self.theme.error),
+            },
+        };
+        print_colored_line(stdout, &message, color)?;
+        self.input_needs_render = true;
+        Ok(())
+    }
+
+    /// Insert a bracketed paste at the cursor
+    /// Embedded newlines become part of the input rather than sending the message
+    fn handle_paste(&mut self, text: &str) {
+        self.bracketed_paste_seen = true;
+        self.is_pasting = false;
+
+        // Pastes only go to the main input; menus, prompts, and scroll mode ignore them
+        if self.show_session_list
+            || self.show_menu
+            || self.show_reasoning_submenu
+            || self.show_context_submenu
+            || self.show_profile_submenu
+            || self.show_session_name_input
+            || self.show_log_viewer
+            || self.pending_edit_approval.is_some()
+            || self.pending_permission_approval.is_some()
+            || self.scrollback.is_scrolled()
+        {
+            return;
+        }
+
+        let text = normalize_paste(text);
+        let byte_pos = self.char_to_byte_pos(self.cursor_position);
+        self.input.insert_str(byte_pos, &text);
+        self.cursor_position += text.chars().count();
+        self.input_needs_render = true;
+    }
+
     async fn handle_input(&mut self, stdout: &mut impl Write, key: event::KeyEvent) -> anyhow::Result<()> {
         tracing::debug!("Key event: {:?} with modifiers: {:?}", key.code, key.modifiers);
 
-        // Paste detection: track timing between key events
+        // Paste detection fallback for terminals without bracketed paste:
+        // track timing between key events. Once the terminal has delivered an
+        // Event::Paste the heuristic is skipped so fast typing isn't mistaken for a paste.
         let now = std::time::Instant::now();
-        if let Some(last_time) = self.last_key_time {
+        let last_key_time = self.last_key_time.filter(|_| !self.bracketed_paste_seen);
+        if let Some(last_time) = last_key_time {
             let elapsed = now.duration_since(last_time);
             // If keys arrive within 10ms, likely pasting
             if elapsed.as_millis() < 10 {
@@ -1393,7 +2168,21 @@ impl App {
         }
 
         // Handle edit approval input
-        if let Some(approval_state) = self.pending_edit_approval.take() {
+        if let Some(mut approval_state) = self.pending_edit_approval.take() {
+            let page = Self::edit_preview_height()? as isize;
+            let scroll = match key.code {
+                KeyCode::Up => Some(-1),
+                KeyCode::Down => Some(1),
+                KeyCode::PageUp => Some(-page),
+                KeyCode::PageDown => Some(page),
+                _ => None,
+            };
+            if let Some(delta) = scroll {
+                self.scroll_edit_preview(stdout, &mut approval_state, delta)?;
+                self.pending_edit_approval = Some(approval_state);
+                return Ok(());
+            }
+
             match (key.code, key.modifiers) {
                 (KeyCode::Char('a'), _) | (KeyCode::Char('A'), _) => {
                     let _ = approval_state.response_tx.send(crate::agent::messages::ApprovalResponse::Approve);
@@ -1415,6 +2204,12 @@ impl App {
                     self.print_header(stdout)?;
                     return Ok(());
                 }
+                (KeyCode::Char('e'), _) | (KeyCode::Char('E'), _) => {
+                    approval_state.expanded = !approval_state.expanded;
+                    self.scroll_edit_preview(stdout, &mut approval_state, 0)?;
+                    self.pending_edit_approval = Some(approval_state);
+                    return Ok(());
+                }
                 _ => {
                     // Put it back if user didn't approve/reject
                     self.pending_edit_approval = Some(approval_state);
@@ -1434,8 +2229,7 @@ impl App {
                     return Ok(());
                 }
                 KeyCode::Down => {
-                    let menu_item_count = 6;  // Total menu items
-                    if self.menu_selected < menu_item_count - 1 {
+                    if self.menu_selected < MENU_ITEMS.len() - 1 {
                         self.menu_selected += 1;
                         self.render_menu(stdout)?;
                     }
@@ -1499,6 +2293,44 @@ impl App {
             }
         }
 
+        // Handle LLM profile submenu navigation
+        if self.show_profile_submenu {
+            match key.code {
+                KeyCode::Up => {
+                    if self.profile_submenu_selected > 0 {
+                        self.profile_submenu_selected -= 1;
+                        self.render_profile_submenu(stdout)?;
+                    }
+                    return Ok(());
+                }
+                KeyCode::Down => {
+                    if self.profile_submenu_selected + 1 < self.llm_profiles.len() {
+                        self.profile_submenu_selected += 1;
+                        self.render_profile_submenu(stdout)?;
+                    }
+                    return Ok(());
+                }
+                KeyCode::Enter => {
+                    self.show_profile_submenu = false;
+                    execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                    self.print_header(stdout)?;
+
+                    // The agent confirms with LlmProfileChanged once the switch is done
+                    if let Some(name) = self.llm_profiles.get(self.profile_submenu_selected) {
+                        self.cmd_tx.send(Command::SetLlmProfile(name.clone())).await?;
+                    }
+                    return Ok(());
+                }
+                KeyCode::Esc => {
+                    self.show_profile_submenu = false;
+                    self.show_menu = true;
+                    self.render_menu(stdout)?;
+                    return Ok(());
+                }
+                _ => return Ok(()),
+            }
+        }
+
         // Handle context submenu navigation
         if self.show_context_submenu {
             match key.code {
@@ -1534,12 +2366,38 @@ impl App {
         if self.show_session_name_input {
             match (key.code, key.modifiers) {
                 (KeyCode::Enter, _) => {
-                    if !self.session_name_input.is_empty() {
-                        self.cmd_tx.send(Command::SetSessionName(self.session_name_input.clone())).await?;
-
-                        execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
-                        self.print_header(stdout)?;
-                        print_colored_line(stdout, &format!("Session name set to: {}", self.session_name_input), Color::Green)?;
+                    let value = self.session_name_input.trim().to_string();
+                    match self.session_input {
+                        SessionInput::Name if !self.session_name_input.is_empty() => {
+                            self.cmd_tx.send(Command::SetSessionName(self.session_name_input.clone())).await?;
+
+                            execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                            self.print_header(stdout)?;
+                            print_colored_line(stdout, &format!("Session name set to: {}", self.session_name_input), Color::Green)?;
+                        }
+                        SessionInput::Name => {}
+                        SessionInput::Tags => {
+                            execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                            self.print_header(stdout)?;
+                            let (add, remove) = parse_tag_input(&value);
+                            for tag in add {
+                                self.cmd_tx.send(Command::AddSessionTag(tag)).await?;
+                            }
+                            for tag in remove {
+                                self.cmd_tx.send(Command::RemoveSessionTag(tag)).await?;
+                            }
+                        }
+                        SessionInput::Search | SessionInput::TagFilter => {
+                            execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+                            self.print_header(stdout)?;
+                            if !value.is_empty() {
+                                let command = match self.session_input {
+                                    SessionInput::Search => Command::SearchSessions(value),
+                                    _ => Command::ListSessionsTagged(value),
+                                };
+                                self.cmd_tx.send(command).await?;
+                            }
+                        }
                     }
 
                     self.show_session_name_input = false;
@@ -1629,16 +2487,21 @@ impl App {
                     return Ok(());
                 }
                 KeyCode::Down => {
-                    if self.session_list_selected < self.session_list.len().saturating_sub(1) {
+                    if self.session_list_selected < self.session_list_len().saturating_sub(1) {
                         self.session_list_selected += 1;
                         self.render_session_list(stdout)?;
                     }
                     return Ok(());
                 }
                 KeyCode::Enter => {
-                    if let Some(session) = self.session_list.get(self.session_list_selected) {
-                        self.cmd_tx.send(Command::LoadSession(session.id.clone())).await?;
+                    let id = match &self.session_search {
+                        Some((_, matches)) => matches.get(self.session_list_selected).map(|m| m.id.clone()),
+                        None => self.session_list.get(self.session_list_selected).map(|s| s.id.clone()),
+                    };
+                    if let Some(id) = id {
+                        self.cmd_tx.send(Command::LoadSession(id)).await?;
                         self.show_session_list = false;
+                        self.session_search = None;
                         execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
                         self.print_header(stdout)?;
                     }
@@ -1646,6 +2509,7 @@ impl App {
                 }
                 KeyCode::Esc => {
                     self.show_session_list = false;
+                    self.session_search = None;
                     execute!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
                     self.print_header(stdout)?;
                     return Ok(());
@@ -1654,8 +2518,26 @@ impl App {
             }
         }
 
+        // Scroll mode: input is disabled until the user returns to the bottom
+        if self.scrollback.is_scrolled() {
+            match (key.code, key.modifiers) {
+                (KeyCode::PageUp, _) => self.scroll_up(stdout)?,
+                (KeyCode::PageDown, _) => self.scroll_down(stdout)?,
+                (KeyCode::Esc, _) | (KeyCode::End, _) => self.snap_to_bottom(stdout)?,
+                (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                    self.cmd_tx.send(Command::Cancel).await?;
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
         // Normal input handling
         match (key.code, key.modifiers) {
+            (KeyCode::PageUp, _) => {
+                self.scroll_up(stdout)?;
+                return Ok(());
+            }
             (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
                 self.cmd_tx.send(Command::Cancel).await?;
             }
@@ -1672,6 +2554,14 @@ impl App {
             (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
                 self.cmd_tx.send(Command::ListSessions).await?;
             }
+            (KeyCode::Char('y'), KeyModifiers::CONTROL) => {
+                self.copy_last_assistant_output(stdout, false)?;
+                return Ok(());
+            }
+            (KeyCode::Char('y'), KeyModifiers::ALT) => {
+                self.copy_last_assistant_output(stdout, true)?;
+                return Ok(());
+            }
             (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
                 tracing::info!("Ctrl+P pressed - opening menu");
                 self.show_menu = true;
@@ -1760,6 +2650,55 @@ impl App {
 mod tests {
     use super::*;
 
+    fn display_width(line: &str) -> usize {
+        UnicodeWidthStr::width(line)
+    }
+
+    #[test]
+    fn test_running_tool_header_advances() {
+        assert_eq!(running_tool_header("bash", Duration::ZERO), "[Tool: bash] ⠋ Running... 0s");
+        assert_eq!(running_tool_header("bash", Duration::from_millis(250)), "[Tool: bash] ⠹ Running... 0s");
+
+        // The spinner wraps around while the seconds keep counting
+        assert_eq!(running_tool_header("bash", Duration::from_millis(31_000)), "[Tool: bash] ⠋ Running... 31s");
+    }
+
+    #[test]
+    fn test_edit_preview_rows_keep_every_change() {
+        // 300 changed lines used to be cut off at 100 rows
+        let diff: String = (0..300).map(|i| format!("+line {}\n", i)).collect();
+        let rows = edit_preview_rows(&diff, false);
+        assert_eq!(rows.len(), 300);
+        assert_eq!(rows[299], DiffRow::Line("+line 299".to_string(), Color::Green));
+    }
+
+    #[test]
+    fn test_edit_preview_rows_collapse_unchanged_runs() {
+        let mut diff = String::from("-old\n+new\n");
+        for i in 0..10 {
+            diff.push_str(&format!(" same {}\n", i));
+        }
+        diff.push_str("+tail\n");
+
+        let rows = edit_preview_rows(&diff, false);
+        assert_eq!(
+            rows,
+            vec![
+                DiffRow::Line("-old".to_string(), Color::Red),
+                DiffRow::Line("+new".to_string(), Color::Green),
+                DiffRow::Line(" same 0".to_string(), Color::DarkGrey),
+                DiffRow::Line(" same 1".to_string(), Color::DarkGrey),
+                DiffRow::Collapsed(8),
+                DiffRow::Line("+tail".to_string(), Color::Green),
+            ]
+        );
+
+        // Expanded, the collapsed run is shown too
+        let rows = edit_preview_rows(&diff, true);
+        assert_eq!(rows.len(), 13);
+        assert!(!rows.iter().any(|row| matches!(row, DiffRow::Collapsed(_))));
+    }
+
     #[test]
     fn test_word_wrapping_basic() {
         let text = "This is a very long line that should wrap properly at word boundaries";
@@ -1771,7 +2710,7 @@ mod tests {
 
         // All lines should be <= 20 chars
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 20, "Line too long: {}", line);
+            assert!(display_width(line) <= 20, "Line too long: {}", line);
         }
     }
 
@@ -1793,7 +2732,7 @@ mod tests {
 
         // Long URLs should break at width boundary
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 20, "Line too long: '{}' ({})", line, line.chars().count());
+            assert!(display_width(line) <= 20, "Line too long: '{}' ({})", line, display_width(line));
         }
 
         // Should have multiple lines
@@ -1802,21 +2741,38 @@ mod tests {
 
     #[test]
     fn test_unicode_wrapping() {
-        // Emoji are multi-byte but count as 1 character
+        // Emoji are multi-byte and occupy 2 terminal columns each
         let text = "Hello 🦀 Rust 🌟 is 💯 awesome";
         let wrapped = wrap_text(text, 15);
 
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 15, "Line too long: '{}' ({})", line, line.chars().count());
+            assert!(display_width(line) <= 15, "Line too long: '{}' ({})", line, display_width(line));
         }
 
-        // Text should wrap at word boundaries, preserving full words
-        // "Hello 🦀 Rust 🌟" = 15 chars (fits exactly on one line)
-        // "is 💯 awesome" = 13 chars (fits on second line)
+        // "Hello 🦀 Rust" = 13 columns (adding " 🌟" would make 16)
+        // "🌟 is 💯" = 8 columns (adding " awesome" would make 16)
         let lines: Vec<&str> = wrapped.lines().collect();
-        assert_eq!(lines.len(), 2, "Expected 2 lines, got: {:?}", lines);
-        assert_eq!(lines[0], "Hello 🦀 Rust 🌟");
-        assert_eq!(lines[1], "is 💯 awesome");
+        assert_eq!(lines, vec!["Hello 🦀 Rust", "🌟 is 💯", "awesome"]);
+    }
+
+    #[test]
+    fn test_cjk_wrapping() {
+        // Full-width characters count as 2 columns
+        let text = "日本語 のテキスト です";
+        let wrapped = wrap_text(text, 10);
+
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines, vec!["日本語", "のテキスト", "です"]);
+    }
+
+    #[test]
+    fn test_cjk_long_word_breaks_on_character_boundary() {
+        let text = "日本語のテキストです";
+        let wrapped = wrap_text(text, 9);
+
+        // 9 columns only fit 4 full-width characters; a wide char is never split
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines, vec!["日本語の", "テキスト", "です"]);
     }
 
     #[test]
@@ -1826,7 +2782,7 @@ mod tests {
         let wrapped = wrap_text(text, 20);
 
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 20, "Line too long: '{}' ({})", line, line.chars().count());
+            assert!(display_width(line) <= 20, "Line too long: '{}' ({})", line, display_width(line));
         }
     }
 
@@ -1849,7 +2805,7 @@ mod tests {
 
         // Should break into chunks of 20
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 20);
+            assert!(display_width(line) <= 20);
         }
 
         // Should have 3 lines (50 / 20 = 2.5, rounds to 3)
@@ -1863,7 +2819,7 @@ mod tests {
         let wrapped = wrap_text(text, 20);
 
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 20, "Line too long: '{}' ({})", line, line.chars().count());
+            assert!(display_width(line) <= 20, "Line too long: '{}' ({})", line, display_width(line));
         }
     }
 
@@ -1899,10 +2855,50 @@ mod tests {
         assert!(!wrapped.contains("HT ML"));
 
         for line in wrapped.lines() {
-            assert!(line.chars().count() <= 40);
+            assert!(display_width(line) <= 40);
         }
     }
 
+    #[test]
+    fn test_code_line_wraps_at_width() {
+        let mut out = Vec::new();
+        let segments = vec![(None, "abcdefghij".to_string())];
+        render_code_line(&mut out, &segments, 4).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "\rabcd\r\nefgh\r\nij\n");
+    }
+
+    #[test]
+    fn test_table_rows_are_not_wrapped() {
+        let text = "Intro\n| Tool | Cached | Notes |\n|---|---|---|\n| read | yes | a long description that exceeds the width |\nOutro";
+        let wrapped = wrap_text(text, 20);
+
+        assert!(wrapped.contains("\n| read | yes | a long description that exceeds the width |\n"));
+        assert!(wrapped.ends_with("\nOutro"));
+    }
+
+    #[test]
+    fn test_normalize_paste() {
+        assert_eq!(normalize_paste("a\r\nb\rc\n"), "a\nb\nc\n");
+        assert_eq!(normalize_paste("\tindented"), "    indented");
+    }
+
+    #[test]
+    fn test_paste_inserts_at_cursor_without_sending() {
+        let (cmd_tx, mut cmd_rx) = tokio::sync::mpsc::channel(10);
+        let (_ui_tx, ui_rx) = tokio::sync::mpsc::channel(10);
+        let mut app = App::new(cmd_tx, ui_rx);
+        app.input = "héllo".to_string();
+        app.cursor_position = 2;
+
+        app.handle_paste("line one\r\nline two");
+
+        assert_eq!(app.input, "héline one\nline twollo");
+        assert_eq!(app.cursor_position, 2 + "line one\nline two".chars().count());
+        assert!(app.bracketed_paste_seen);
+        assert!(cmd_rx.try_recv().is_err());
+    }
+
     #[test]
     fn test_batch_size_constant() {
         // Verify MAX_BATCH_SIZE is set to expected value
@@ -1918,4 +2914,29 @@ mod tests {
     // Integration tests for event batching would require mocking crossterm events
     // These tests verify the constants are set correctly
     // End-to-end testing would be done manually or in integration tests
+
+    #[test]
+    fn test_parse_tag_input() {
+        let (add, remove) = parse_tag_input(" powertools, -old ,, refactor, - ");
+        assert_eq!(add, vec!["powertools", "refactor"]);
+        assert_eq!(remove, vec!["old"]);
+    }
+
+    #[test]
+    fn test_todo_summary() {
+        let todo = |content: &str, status| Todo {
+            content: content.to_string(),
+            status,
+            active_form: format!("{}ing", content),
+        };
+        assert_eq!(todo_summary(&[]), None);
+
+        let todos = vec![todo("Test", TodoStatus::Completed), todo("Build", TodoStatus::InProgress), todo("Ship", TodoStatus::Pending)];
+        assert_eq!(todo_summary(&todos).unwrap(), "║  Todos: 1/3 done · ⏳ Building");
+
+        let long = vec![todo(&"x".repeat(100), TodoStatus::InProgress)];
+        let line = todo_summary(&long).unwrap();
+        assert!(line.ends_with('…'));
+        assert!(line.chars().count() <= 62);
+    }
 }
@@ -0,0 +1,105 @@
+/// Copying assistant output out of the TUI
+/// Uses the system clipboard when available; headless/SSH sessions fall back to a temp file
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Where copied text ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CopyDestination {
+    Clipboard,
+    File(PathBuf),
+}
+
+#[derive(Default)]
+pub struct ClipboardWriter {
+    /// Kept alive after the first copy: on X11 the clipboard contents are only
+    /// served while the owning handle exists
+    clipboard: Option<arboard::Clipboard>,
+}
+
+impl ClipboardWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy `text` to the clipboard, or to a temp file if no clipboard is available
+    pub fn copy(&mut self, text: &str) -> Result<CopyDestination> {
+        match self.copy_to_clipboard(text) {
+            Ok(()) => Ok(CopyDestination::Clipboard),
+            Err(e) => {
+                tracing::debug!("Clipboard unavailable, writing to temp file instead: {}", e);
+                write_temp_file(text).map(CopyDestination::File)
+            }
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<(), arboard::Error> {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(arboard::Clipboard::new()?);
+        }
+        match self.clipboard.as_mut() {
+            Some(clipboard) => clipboard.set_text(text.to_string()),
+            None => Err(arboard::Error::ClipboardNotSupported),
+        }
+    }
+}
+
+fn write_temp_file(text: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!(
+        "synthia-copy-{}.txt",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    std::fs::write(&path, text)
+        .with_context(|| format!("Failed to write copied text to {}", path.display()))?;
+    Ok(path)
+}
+
+/// Contents of the last fenced code block in `text` (without the fences)
+/// An unterminated final block counts, since streaming can end mid-block
+pub fn last_code_block(text: &str) -> Option<String> {
+    let mut last = None;
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(lines) => last = Some(lines.join("\n")),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+
+    current.map(|lines| lines.join("\n")).or(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_code_block() {
+        let text = "First:\n```rust\nfn a() {}\n```\nThen:\n```python\nprint('b')\nprint('c')\n```\nDone.";
+        assert_eq!(
+            last_code_block(text),
+            Some("print('b')\nprint('c')".to_string())
+        );
+    }
+
+    #[test]
+    fn test_last_code_block_unterminated_and_missing() {
+        assert_eq!(
+            last_code_block("```\nfirst\n```\n```sh\nls -la"),
+            Some("ls -la".to_string())
+        );
+        assert_eq!(last_code_block("No code here"), None);
+    }
+
+    #[test]
+    fn test_temp_file_fallback() {
+        let path = write_temp_file("copied text").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "copied text");
+        std::fs::remove_file(path).unwrap();
+    }
+}
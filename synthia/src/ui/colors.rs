@@ -1,6 +1,87 @@
 /// Pastel color palette for terminal UI
 /// These colors are designed to work well on both light and dark backgrounds
-use crossterm::style::Color;
+use crate::config::ThemeConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use crossterm::style::{self, Color};
+use crossterm::Command;
+use std::ffi::OsString;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by the `--no-color` flag
+static NO_COLOR_FLAG: AtomicBool = AtomicBool::new(false);
+
+/// Turn colors off (or back on) for the rest of the process, as `--no-color` does
+pub fn set_no_color(disabled: bool) {
+    NO_COLOR_FLAG.store(disabled, Ordering::Relaxed);
+}
+
+/// Whether to emit color escapes: not after `--no-color`, nor when `NO_COLOR` is set (https://no-color.org)
+pub fn should_color() -> bool {
+    color_allowed(NO_COLOR_FLAG.load(Ordering::Relaxed), std::env::var_os("NO_COLOR"))
+}
+
+fn color_allowed(no_color_flag: bool, no_color_env: Option<OsString>) -> bool {
+    !no_color_flag && no_color_env.is_none_or(|value| value.is_empty())
+}
+
+/// crossterm's `SetForegroundColor`, writing nothing when [`should_color`] is false
+///
+/// crossterm's own `NO_COLOR` handling still emits an empty `\x1b[m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetForegroundColor(pub Color);
+
+/// Write `command`'s escape sequence only when `color` is set
+fn write_ansi_if(color: bool, command: impl Command, f: &mut impl fmt::Write) -> fmt::Result {
+    if color {
+        command.write_ansi(f)?;
+    }
+    Ok(())
+}
+
+impl Command for SetForegroundColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write_ansi_if(should_color(), style::SetForegroundColor(self.0), f)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        if should_color() {
+            style::SetForegroundColor(self.0).execute_winapi()?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SetForegroundColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_ansi(f)
+    }
+}
+
+/// crossterm's `ResetColor`, writing nothing when [`should_color`] is false
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetColor;
+
+impl Command for ResetColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write_ansi_if(should_color(), style::ResetColor, f)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        if should_color() {
+            style::ResetColor.execute_winapi()?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ResetColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_ansi(f)
+    }
+}
 
 /// Pastel colors that work on both light and dark themes
 /// Uses RGB values for precise control
@@ -32,3 +113,205 @@ impl PastelColors {
     #[allow(dead_code)]
     pub const PROMPT: Color = Color::Rgb { r: 230, g: 230, b: 250 };
 }
+
+/// Colors for each role in the TUI, chosen from a built-in theme plus per-role overrides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub header: Color,
+    pub assistant: Color,
+    pub tool: Color,
+    pub success: Color,
+    pub error: Color,
+}
+
+impl Theme {
+    /// Soft pastel palette (the original look)
+    pub fn pastel() -> Self {
+        Self {
+            header: PastelColors::HEADER,
+            assistant: PastelColors::ASSISTANT,
+            tool: PastelColors::TOOL,
+            success: PastelColors::SUCCESS,
+            error: PastelColors::ERROR,
+        }
+    }
+
+    /// Saturated ANSI colors that stay readable on light backgrounds and low-contrast displays
+    pub fn high_contrast() -> Self {
+        Self {
+            header: Color::Blue,
+            assistant: Color::DarkCyan,
+            tool: Color::DarkYellow,
+            success: Color::DarkGreen,
+            error: Color::DarkRed,
+        }
+    }
+
+    /// No colors: everything uses the terminal's default foreground
+    pub fn mono() -> Self {
+        Self {
+            header: Color::Reset,
+            assistant: Color::Reset,
+            tool: Color::Reset,
+            success: Color::Reset,
+            error: Color::Reset,
+        }
+    }
+
+    /// Look up a built-in theme ("pastel", "high-contrast", "mono")
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "pastel" => Some(Self::pastel()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "mono" | "monochrome" => Some(Self::mono()),
+            _ => None,
+        }
+    }
+
+    /// Build a theme from config: start from the named theme (pastel if unset),
+    /// then apply any per-role overrides
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let mut theme = match config.name.as_deref() {
+            Some(name) => Self::by_name(name).ok_or_else(|| {
+                anyhow!(
+                    "Unknown theme '{}' (expected \"pastel\", \"high-contrast\", or \"mono\")",
+                    name
+                )
+            })?,
+            None => Self::default(),
+        };
+
+        let overrides = [
+            ("header", &config.header, &mut theme.header),
+            ("assistant", &config.assistant, &mut theme.assistant),
+            ("tool", &config.tool, &mut theme.tool),
+            ("success", &config.success, &mut theme.success),
+            ("error", &config.error, &mut theme.error),
+        ];
+        for (role, value, slot) in overrides {
+            if let Some(value) = value {
+                *slot = parse_color(value)
+                    .with_context(|| format!("Invalid color for theme role '{}'", role))?;
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::pastel()
+    }
+}
+
+/// Parse "#rrggbb" or a color name ("cyan", "dark_grey", "default", ...)
+pub fn parse_color(value: &str) -> Result<Color> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("'{}' is not a #rrggbb hex color", value);
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).expect("validated hex");
+        return Ok(Color::Rgb {
+            r: channel(0),
+            g: channel(2),
+            b: channel(4),
+        });
+    }
+
+    let color = match value.to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+        "default" | "reset" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => bail!("Unknown color '{}' (use #rrggbb or a name like \"cyan\")", value),
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_color_flag_and_env_disable_color() {
+        assert!(color_allowed(false, None));
+        assert!(color_allowed(false, Some(OsString::new())));
+        assert!(!color_allowed(false, Some(OsString::from("1"))));
+        assert!(!color_allowed(true, None));
+    }
+
+    #[test]
+    fn test_color_commands_write_nothing_when_disabled() {
+        let mut plain = String::new();
+        write_ansi_if(false, style::SetForegroundColor(Color::Green), &mut plain).unwrap();
+        write_ansi_if(false, style::ResetColor, &mut plain).unwrap();
+        assert_eq!(plain, "");
+
+        let mut colored = String::new();
+        write_ansi_if(true, style::SetForegroundColor(Color::Green), &mut colored).unwrap();
+        write_ansi_if(true, style::ResetColor, &mut colored).unwrap();
+        assert!(colored.starts_with('\x1b') && colored.ends_with("\x1b[0m"), "{:?}", colored);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#ff8000").unwrap(), Color::Rgb { r: 255, g: 128, b: 0 });
+        assert_eq!(parse_color("Dark-Grey").unwrap(), Color::DarkGrey);
+        assert_eq!(parse_color("default").unwrap(), Color::Reset);
+        assert!(parse_color("#ff80").is_err());
+        assert!(parse_color("#gg0000").is_err());
+        assert!(parse_color("chartreuse").is_err());
+    }
+
+    #[test]
+    fn test_theme_defaults_to_pastel() {
+        let theme = Theme::from_config(&ThemeConfig::default()).unwrap();
+        assert_eq!(theme, Theme::pastel());
+        assert_eq!(theme.assistant, PastelColors::ASSISTANT);
+    }
+
+    #[test]
+    fn test_named_theme_with_overrides() {
+        let config = ThemeConfig {
+            name: Some("high-contrast".to_string()),
+            error: Some("#ff0000".to_string()),
+            ..Default::default()
+        };
+        let theme = Theme::from_config(&config).unwrap();
+
+        assert_eq!(theme.assistant, Color::DarkCyan);
+        assert_eq!(theme.error, Color::Rgb { r: 255, g: 0, b: 0 });
+    }
+
+    #[test]
+    fn test_invalid_theme_config() {
+        let unknown = ThemeConfig {
+            name: Some("solarized".to_string()),
+            ..Default::default()
+        };
+        assert!(Theme::from_config(&unknown).is_err());
+
+        let bad_color = ThemeConfig {
+            tool: Some("not-a-color".to_string()),
+            ..Default::default()
+        };
+        let err = Theme::from_config(&bad_color).unwrap_err();
+        assert!(format!("{:#}", err).contains("'tool'"));
+    }
+}
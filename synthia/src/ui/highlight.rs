@@ -0,0 +1,124 @@
+/// Syntax highlighting for fenced code blocks using syntect
+/// Unknown languages fall back to plain text so rendering never fails
+use crossterm::style::Color;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+const THEME_NAME: &str = "base16-ocean.dark";
+
+struct Assets {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+}
+
+/// Syntax definitions are expensive to load, so do it once on first use
+fn assets() -> &'static Assets {
+    static ASSETS: OnceLock<Assets> = OnceLock::new();
+    ASSETS.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        Assets {
+            syntaxes: SyntaxSet::load_defaults_nonewlines(),
+            theme: themes.themes.remove(THEME_NAME).unwrap_or_default(),
+        }
+    })
+}
+
+/// Extract the language tag from an opening fence (e.g. "```rust" -> "rust")
+/// Extra info after the tag (```rust,ignore or ```python title="x") is ignored
+pub fn fence_language(line: &str) -> Option<&str> {
+    let info = line.trim_start().strip_prefix("```")?.trim();
+    let tag = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or("");
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// A highlighted piece of a line; None means the terminal's default color
+pub type Segment = (Option<Color>, String);
+
+/// Highlights the lines of one code block, keeping parser state between lines
+pub struct CodeHighlighter {
+    highlighter: Option<HighlightLines<'static>>,
+}
+
+impl CodeHighlighter {
+    /// Create a highlighter for `language` (plain text if None or unrecognized)
+    pub fn new(language: Option<&str>) -> Self {
+        let assets = assets();
+        let highlighter = language
+            .and_then(|lang| assets.syntaxes.find_syntax_by_token(lang))
+            .map(|syntax| HighlightLines::new(syntax, &assets.theme));
+        Self { highlighter }
+    }
+
+    /// Split a line into colored segments
+    pub fn highlight(&mut self, line: &str) -> Vec<Segment> {
+        if let Some(highlighter) = self.highlighter.as_mut() {
+            match highlighter.highlight_line(line, &assets().syntaxes) {
+                Ok(ranges) => {
+                    return ranges
+                        .into_iter()
+                        .map(|(style, text)| (Some(to_color(style)), text.to_string()))
+                        .collect();
+                }
+                Err(e) => {
+                    // Parser state is unreliable after an error, so stay plain for the rest of the block
+                    tracing::debug!("Syntax highlighting failed, falling back to plain text: {}", e);
+                    self.highlighter = None;
+                }
+            }
+        }
+        vec![(None, line.to_string())]
+    }
+}
+
+fn to_color(style: Style) -> Color {
+    let fg = style.foreground;
+    Color::Rgb {
+        r: fg.r,
+        g: fg.g,
+        b: fg.b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fence_language() {
+        assert_eq!(fence_language("```rust"), Some("rust"));
+        assert_eq!(fence_language("  ```python title=\"app.py\""), Some("python"));
+        assert_eq!(fence_language("```rust,ignore"), Some("rust"));
+        assert_eq!(fence_language("```"), None);
+        assert_eq!(fence_language("```   "), None);
+        assert_eq!(fence_language("not a fence"), None);
+    }
+
+    #[test]
+    fn test_known_language_is_colored() {
+        let mut highlighter = CodeHighlighter::new(Some("rust"));
+        let line = "fn main() { let x = 42; }";
+        let segments = highlighter.highlight(line);
+
+        assert!(segments.len() > 1);
+        assert!(segments.iter().all(|(color, _)| color.is_some()));
+        let joined: String = segments.iter().map(|(_, text)| text.as_str()).collect();
+        assert_eq!(joined, line);
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_plain() {
+        let mut highlighter = CodeHighlighter::new(Some("definitely-not-a-language"));
+        assert_eq!(
+            highlighter.highlight("some code"),
+            vec![(None, "some code".to_string())]
+        );
+
+        let mut highlighter = CodeHighlighter::new(None);
+        assert_eq!(highlighter.highlight("x = 1"), vec![(None, "x = 1".to_string())]);
+    }
+}
@@ -1,5 +1,9 @@
 pub mod app;
+pub mod clipboard;
 pub mod colors;
+pub mod highlight;
 pub mod markdown;
+pub mod scrollback;
+pub mod table;
 
 pub use app::App;
@@ -0,0 +1,248 @@
+/// In-memory history of rendered transcript lines for scrolling back through output
+/// Output is captured as raw terminal bytes (colors included) and split into lines
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+/// Oldest lines are dropped beyond this to bound memory in long sessions
+const MAX_LINES: usize = 10_000;
+
+/// crossterm's Clear(ClearType::CurrentLine)
+const CLEAR_LINE: &str = "\x1b[2K";
+
+#[derive(Debug, Default)]
+pub struct Scrollback {
+    lines: Vec<String>,
+    /// Bytes after the last newline (an unfinished line)
+    pending: Vec<u8>,
+    /// How many lines the view is scrolled up from the bottom (0 = following live output)
+    offset: usize,
+    /// Lines recorded while scrolled up that the user hasn't seen yet
+    unseen: usize,
+}
+
+impl Scrollback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append raw terminal output, completing lines at each newline
+    pub fn record(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+
+        while let Some(pos) = self.pending.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = clean_line(&String::from_utf8_lossy(&raw[..raw.len() - 1]));
+            self.push_line(line);
+        }
+    }
+
+    fn push_line(&mut self, line: String) {
+        self.lines.push(line);
+        if self.is_scrolled() {
+            // Keep the view anchored on the same content while output arrives below it
+            self.offset += 1;
+            self.unseen += 1;
+        }
+        if self.lines.len() > MAX_LINES {
+            let excess = self.lines.len() - MAX_LINES;
+            self.lines.drain(..excess);
+            self.offset = self.offset.min(self.lines.len().saturating_sub(1));
+        }
+    }
+
+    /// Forget all history (e.g. when the conversation is cleared)
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn is_scrolled(&self) -> bool {
+        self.offset > 0
+    }
+
+    pub fn unseen(&self) -> usize {
+        self.unseen
+    }
+
+    /// Scroll up by `lines`, stopping at the oldest line
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.offset = (self.offset + lines).min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll down by `lines`; reaching the bottom resumes following live output
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.offset = self.offset.saturating_sub(lines);
+        if self.offset == 0 {
+            self.unseen = 0;
+        }
+    }
+
+    pub fn snap_to_bottom(&mut self) {
+        self.offset = 0;
+        self.unseen = 0;
+    }
+
+    /// Lines visible in a `rows` x `width` viewport ending at the current scroll position,
+    /// plus the index of the first one. Lines wider than the terminal take several rows.
+    pub fn window(&self, rows: usize, width: usize) -> (usize, &[String]) {
+        let end = self.lines.len().saturating_sub(self.offset);
+        let mut start = end;
+        let mut used = 0;
+
+        while start > 0 {
+            let needed = screen_rows(&self.lines[start - 1], width);
+            if used + needed > rows && start < end {
+                break;
+            }
+            used += needed;
+            start -= 1;
+        }
+
+        (start, &self.lines[start..end])
+    }
+
+    /// Wrap a writer so everything written to it is also recorded here
+    pub fn recorder<'a, W: Write>(&'a mut self, inner: &'a mut W) -> Recorder<'a, W> {
+        Recorder {
+            inner,
+            scrollback: self,
+        }
+    }
+}
+
+/// Writer that tees output into a `Scrollback`
+pub struct Recorder<'a, W: Write> {
+    inner: &'a mut W,
+    scrollback: &'a mut Scrollback,
+}
+
+impl<W: Write> Write for Recorder<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.scrollback.record(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reduce a raw output line to what stays on screen: the text after the last
+/// clear-line sequence, without carriage returns
+fn clean_line(raw: &str) -> String {
+    let visible = raw.rsplit(CLEAR_LINE).next().unwrap_or(raw);
+    visible.replace('\r', "")
+}
+
+/// Number of terminal rows a line occupies once the terminal wraps it
+fn screen_rows(line: &str, width: usize) -> usize {
+    let columns = UnicodeWidthStr::width(strip_ansi(line).as_str());
+    columns.div_ceil(width.max(1)).max(1)
+}
+
+/// Remove CSI escape sequences (colors, attributes) so only printable text remains
+fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            if chars.next() == Some('[') {
+                // Parameters run until a final byte in '@'..='~'
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        plain.push(ch);
+    }
+
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_splits_lines_and_keeps_partial() {
+        let mut scrollback = Scrollback::new();
+        scrollback.record(b"first\r\nsec");
+        assert_eq!(scrollback.len(), 1);
+
+        scrollback.record(b"ond\n");
+        assert_eq!(scrollback.window(10, 80).1, ["first", "second"]);
+    }
+
+    #[test]
+    fn test_clear_line_drops_overwritten_text() {
+        let mut scrollback = Scrollback::new();
+        scrollback.record(b"Synthia: Thinking...\r\x1b[2KSynthia: Hello\n");
+        assert_eq!(scrollback.window(10, 80).1, ["Synthia: Hello"]);
+    }
+
+    #[test]
+    fn test_window_follows_scroll_offset() {
+        let mut scrollback = Scrollback::new();
+        for i in 0..10 {
+            scrollback.record(format!("line {}\n", i).as_bytes());
+        }
+
+        let (start, lines) = scrollback.window(3, 80);
+        assert_eq!(start, 7);
+        assert_eq!(lines, ["line 7", "line 8", "line 9"]);
+
+        scrollback.scroll_up(5);
+        assert!(scrollback.is_scrolled());
+        assert_eq!(scrollback.window(3, 80).1, ["line 2", "line 3", "line 4"]);
+
+        // Scrolling past the top stops at the first line
+        scrollback.scroll_up(100);
+        assert_eq!(scrollback.window(3, 80).1, ["line 0"]);
+
+        scrollback.scroll_down(100);
+        assert!(!scrollback.is_scrolled());
+    }
+
+    #[test]
+    fn test_new_output_keeps_scrolled_view_anchored() {
+        let mut scrollback = Scrollback::new();
+        for i in 0..5 {
+            scrollback.record(format!("line {}\n", i).as_bytes());
+        }
+        scrollback.scroll_up(2);
+        let before = scrollback.window(2, 80).1.to_vec();
+
+        scrollback.record(b"new 1\nnew 2\n");
+        assert_eq!(scrollback.window(2, 80).1, before.as_slice());
+        assert_eq!(scrollback.unseen(), 2);
+
+        scrollback.snap_to_bottom();
+        assert_eq!(scrollback.window(2, 80).1, ["new 1", "new 2"]);
+        assert_eq!(scrollback.unseen(), 0);
+    }
+
+    #[test]
+    fn test_window_accounts_for_wrapped_and_colored_lines() {
+        let mut scrollback = Scrollback::new();
+        scrollback.record(b"short\n");
+        scrollback.record(format!("\x1b[38;2;1;2;3m{}\x1b[0m\n", "x".repeat(25)).as_bytes());
+
+        // The long line needs 3 rows at width 10, leaving no room for "short"
+        let (start, lines) = scrollback.window(3, 10);
+        assert_eq!(start, 1);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(scrollback.window(4, 10).1.len(), 2);
+    }
+}
@@ -0,0 +1,255 @@
+/// Markdown table detection and rendering for the TUI
+/// A table needs a header row followed by a `---` separator row, so ordinary
+/// text containing pipes (shell commands, "a | b") is never treated as a table
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Narrowest a column is shrunk to before giving up on fitting the terminal
+const MIN_COLUMN_WIDTH: usize = 3;
+
+/// Split a table row into trimmed cells (None if the line has no pipes)
+/// Leading/trailing pipes are optional and `\|` is kept as a literal pipe
+fn split_row(line: &str) -> Option<Vec<String>> {
+    let trimmed = line.trim();
+    if !trimmed.contains('|') {
+        return None;
+    }
+
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let inner = match inner.strip_suffix('|') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => inner,
+    };
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'|') => {
+                current.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(ch),
+        }
+    }
+    cells.push(current.trim().to_string());
+
+    Some(cells)
+}
+
+/// Parse a separator row like `|---|:---:|--:|` into column alignments
+fn parse_separator(line: &str) -> Option<Vec<Align>> {
+    split_row(line)?
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Align::Center,
+                (false, true) => Align::Right,
+                _ => Align::Left,
+            })
+        })
+        .collect()
+}
+
+/// If `lines` starts with a markdown table, return how many lines it spans
+pub fn table_len(lines: &[&str]) -> Option<usize> {
+    let header = split_row(lines.first()?)?;
+    let alignments = parse_separator(lines.get(1)?)?;
+    if header.len() != alignments.len() {
+        return None;
+    }
+
+    let rows = lines[2..]
+        .iter()
+        .take_while(|line| !line.trim().is_empty() && split_row(line).is_some())
+        .count();
+    Some(2 + rows)
+}
+
+/// Render the table in `lines` (as found by `table_len`) with box borders,
+/// shrinking the widest columns and truncating cells so it fits in `width` columns
+pub fn render_table(lines: &[&str], width: usize) -> Vec<String> {
+    let header = split_row(lines[0]).unwrap_or_default();
+    let alignments = parse_separator(lines[1]).unwrap_or_default();
+    let columns = header.len();
+
+    let rows: Vec<Vec<String>> = std::iter::once(header)
+        .chain(lines[2..].iter().filter_map(|line| split_row(line)))
+        .map(|mut cells| {
+            // Missing cells are blank, extra cells are dropped
+            cells.resize(columns, String::new());
+            cells.iter().map(|cell| strip_inline_markup(cell)).collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = (0..columns)
+        .map(|col| {
+            rows.iter()
+                .map(|row| UnicodeWidthStr::width(row[col].as_str()))
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect();
+    fit_widths(&mut widths, width);
+
+    let border = |left: &str, mid: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        format!("{}{}{}", left, segments.join(mid), right)
+    };
+
+    let mut output = vec![border("┌", "┬", "┐")];
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .zip(&alignments)
+            .map(|((cell, &w), &align)| pad(&truncate(cell, w), w, align))
+            .collect();
+        output.push(format!("│ {} │", cells.join(" │ ")));
+        if i == 0 {
+            output.push(border("├", "┼", "┤"));
+        }
+    }
+    output.push(border("└", "┴", "┘"));
+    output
+}
+
+/// Shrink the widest columns one at a time until the table fits in `width`
+fn fit_widths(widths: &mut [usize], width: usize) {
+    // Each column adds "│ " + " " and the row ends with "│"
+    let overhead = widths.len() * 3 + 1;
+    let available = width.saturating_sub(overhead);
+
+    while widths.iter().sum::<usize>() > available {
+        let Some(widest) = widths
+            .iter_mut()
+            .filter(|w| **w > MIN_COLUMN_WIDTH)
+            .max_by_key(|w| **w)
+        else {
+            break; // Can't shrink further; let the terminal wrap
+        };
+        *widest -= 1;
+    }
+}
+
+/// Cut `text` to `width` display columns, marking the cut with an ellipsis
+fn truncate(text: &str, width: usize) -> String {
+    if UnicodeWidthStr::width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut result = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if used + ch_width + 1 > width {
+            break;
+        }
+        result.push(ch);
+        used += ch_width;
+    }
+    result.push('…');
+    result
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let gap = width.saturating_sub(UnicodeWidthStr::width(text));
+    match align {
+        Align::Left => format!("{}{}", text, " ".repeat(gap)),
+        Align::Right => format!("{}{}", " ".repeat(gap), text),
+        Align::Center => format!(
+            "{}{}{}",
+            " ".repeat(gap / 2),
+            text,
+            " ".repeat(gap - gap / 2)
+        ),
+    }
+}
+
+/// Table cells are printed as-is, so drop inline markers that would otherwise show literally
+fn strip_inline_markup(cell: &str) -> String {
+    cell.replace("**", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "\
+| Tool | Cached | Notes |
+|------|:------:|------:|
+| read | yes | `fast` |
+| bash | no | **slow** |";
+
+    #[test]
+    fn test_detects_three_column_table() {
+        let lines: Vec<&str> = TABLE.lines().chain(["", "After"]).collect();
+        assert_eq!(table_len(&lines), Some(4));
+    }
+
+    #[test]
+    fn test_renders_three_column_table() {
+        let lines: Vec<&str> = TABLE.lines().collect();
+        let rendered = render_table(&lines, 80);
+
+        assert_eq!(
+            rendered,
+            vec![
+                "┌──────┬────────┬───────┐",
+                "│ Tool │ Cached │ Notes │",
+                "├──────┼────────┼───────┤",
+                "│ read │  yes   │  fast │",
+                "│ bash │   no   │  slow │",
+                "└──────┴────────┴───────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_narrow_terminal_truncates_cells() {
+        let lines = [
+            "| Name | Description |",
+            "| --- | --- |",
+            "| apply_patch | Apply a unified diff to one or more files |",
+        ];
+        let rendered = render_table(&lines, 30);
+
+        for line in &rendered {
+            assert!(UnicodeWidthStr::width(line.as_str()) <= 30, "too wide: {}", line);
+        }
+        assert!(rendered[3].contains('…'));
+    }
+
+    #[test]
+    fn test_pipes_without_separator_are_not_a_table() {
+        assert_eq!(table_len(&["Run cat file | grep foo", "then check output"]), None);
+        assert_eq!(table_len(&["a | b", "c | d"]), None);
+        // Separator column count must match the header
+        assert_eq!(table_len(&["| a | b |", "|---|", "| 1 | 2 |"]), None);
+    }
+
+    #[test]
+    fn test_ragged_rows_and_escaped_pipes() {
+        let lines = ["a | b", "--|--", "x \\| y", "1 | 2 | 3"];
+        assert_eq!(table_len(&lines), Some(4));
+
+        let rendered = render_table(&lines, 80);
+        assert_eq!(rendered[3], "│ x | y │   │");
+        assert_eq!(rendered[4], "│ 1     │ 2 │");
+    }
+}
@@ -55,6 +55,10 @@ async fn test_actor_with_no_project_context() {
         streaming: false,
         reasoning_level: "medium".to_string(),
         context_window: 100000,
+        stop: Vec::new(),
+        seed: None,
+        vision: false,
+        response_format: None,
     };
     let llm_provider = Arc::new(mock_provider::MockLLMProvider);
     let tool_registry = Arc::new(ToolRegistry::new());
@@ -84,6 +88,10 @@ async fn test_actor_with_project_context() {
         streaming: false,
         reasoning_level: "medium".to_string(),
         context_window: 100000,
+        stop: Vec::new(),
+        seed: None,
+        vision: false,
+        response_format: None,
     };
     let llm_provider = Arc::new(mock_provider::MockLLMProvider);
     let tool_registry = Arc::new(ToolRegistry::new());